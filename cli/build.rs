@@ -18,6 +18,7 @@ use std::str;
 
 const GIT_HEAD_PATH: &str = "../.git/HEAD";
 const JJ_OP_HEADS_PATH: &str = "../.jj/repo/op_heads/heads";
+const CARGO_LOCK_PATH: &str = "../Cargo.lock";
 
 fn main() {
     let version = std::env::var("CARGO_PKG_VERSION").unwrap();
@@ -38,6 +39,19 @@ fn main() {
         println!("cargo:rustc-env=JJ_VERSION={version}");
     }
 
+    // gitoxide doesn't provide its version at runtime.
+    println!("cargo:rerun-if-changed={CARGO_LOCK_PATH}");
+    if let Some(gix_version) = get_locked_version("gix") {
+        println!("cargo:rustc-env=JJ_GIX_VERSION={gix_version}");
+    }
+
+    // `jj version --verbose` reports the features the binary was built with.
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!(
+        "cargo:rustc-env=JJ_FEATURES={}",
+        get_enabled_features().join(",")
+    );
+
     let docs_symlink_path = Path::new("docs");
     println!("cargo:rerun-if-changed={}", docs_symlink_path.display());
     if docs_symlink_path.join("index.md").exists() {
@@ -79,3 +93,34 @@ fn get_git_hash() -> Option<String> {
 
     None
 }
+
+fn get_locked_version(package_name: &str) -> Option<String> {
+    let lock = std::fs::read_to_string(CARGO_LOCK_PATH).ok()?;
+    let name_line = format!("name = \"{package_name}\"");
+    let mut lines = lock.lines();
+    lines.find(|line| *line == name_line)?;
+    let version = lines
+        .next()?
+        .strip_prefix("version = \"")?
+        .strip_suffix('"')?;
+    Some(version.to_owned())
+}
+
+/// Returns the features in the `[features]` table of Cargo.toml that are
+/// enabled, except the ones only used for development.
+fn get_enabled_features() -> Vec<String> {
+    let manifest = std::fs::read_to_string("Cargo.toml").unwrap();
+    manifest
+        .lines()
+        .skip_while(|line| *line != "[features]")
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| Some(line.split_once('=')?.0.trim()))
+        .filter(|name| !matches!(*name, "default" | "bench" | "test-fakes"))
+        .filter(|name| {
+            let var_name = format!("CARGO_FEATURE_{}", name.to_uppercase().replace('-', "_"));
+            std::env::var_os(var_name).is_some()
+        })
+        .map(str::to_owned)
+        .collect()
+}
@@ -169,6 +169,10 @@ impl WorkingCopy for ConflictsWorkingCopy {
         self.inner.sparse_patterns()
     }
 
+    fn unrepresentable_paths(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError> {
+        self.inner.unrepresentable_paths()
+    }
+
     fn start_mutation(&self) -> Result<Box<dyn LockedWorkingCopy>, WorkingCopyStateError> {
         let inner = self.inner.start_mutation()?;
         Ok(Box::new(LockedConflictsWorkingCopy {
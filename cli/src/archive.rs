@@ -0,0 +1,353 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Writes files of a tree to directories and archives.
+
+use std::io;
+use std::io::Read as _;
+use std::io::Write;
+
+use chrono::Datelike as _;
+use chrono::Timelike as _;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use jj_lib::backend::BackendResult;
+use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::materialize_tree_value;
+use jj_lib::conflicts::MaterializedTreeValue;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use pollster::FutureExt as _;
+
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// A file or symlink to export.
+pub enum ExportedEntry {
+    File { executable: bool, contents: Vec<u8> },
+    Symlink { target: String },
+}
+
+/// Materializes the entries and passes the files and symlinks to `export`.
+/// Conflicted files are exported with conflict markers.
+pub fn export_tree_entries<P: AsRef<RepoPath>>(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    entries: impl IntoIterator<Item = (P, BackendResult<MergedTreeValue>)>,
+    mut export: impl FnMut(&RepoPath, ExportedEntry) -> Result<(), CommandError>,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    for (path, result) in entries {
+        let path = path.as_ref();
+        let value = result?;
+        let materialized = materialize_tree_value(repo.store(), path, value).block_on()?;
+        let entry = match materialized {
+            MaterializedTreeValue::Absent => panic!("absent values should be excluded"),
+            MaterializedTreeValue::AccessDenied(err) => {
+                let ui_path = workspace_command.format_file_path(path);
+                writeln!(
+                    ui.warning_default(),
+                    "Path '{ui_path}' exists but access is denied: {err}"
+                )?;
+                continue;
+            }
+            MaterializedTreeValue::File {
+                executable,
+                mut reader,
+                ..
+            } => {
+                let mut contents = vec![];
+                reader.read_to_end(&mut contents)?;
+                ExportedEntry::File {
+                    executable,
+                    contents,
+                }
+            }
+            MaterializedTreeValue::FileConflict {
+                contents,
+                executable,
+                ..
+            } => {
+                let mut materialized = vec![];
+                materialize_merge_result(
+                    &contents,
+                    workspace_command.env().conflict_marker_style(),
+                    &mut materialized,
+                )?;
+                ExportedEntry::File {
+                    executable,
+                    contents: materialized,
+                }
+            }
+            MaterializedTreeValue::Symlink { target, .. } => ExportedEntry::Symlink { target },
+            MaterializedTreeValue::OtherConflict { .. }
+            | MaterializedTreeValue::GitSubmodule(_) => {
+                let ui_path = workspace_command.format_file_path(path);
+                writeln!(
+                    ui.warning_default(),
+                    "Path '{ui_path}' exists but is not a file or symlink"
+                )?;
+                continue;
+            }
+            MaterializedTreeValue::Tree(_) => panic!("entries should not contain trees"),
+        };
+        export(path, entry)?;
+    }
+    Ok(())
+}
+
+const TAR_BLOCK_SIZE: usize = 512;
+
+/// Writes a tar archive in the POSIX ustar format.
+pub struct TarWriter<W> {
+    writer: W,
+    mtime: u64,
+}
+
+impl<W: Write> TarWriter<W> {
+    /// Creates a writer giving all entries the modification time `mtime`, in
+    /// seconds since the epoch.
+    pub fn new(writer: W, mtime: u64) -> Self {
+        TarWriter { writer, mtime }
+    }
+
+    pub fn append(&mut self, path: &str, entry: ExportedEntry) -> Result<(), CommandError> {
+        let mut header = [0; TAR_BLOCK_SIZE];
+        // Paths longer than the name field are split at a slash, with the
+        // leading directories stored in the prefix field.
+        let (prefix, name) = if path.len() <= 100 {
+            ("", path)
+        } else {
+            path.match_indices('/')
+                .map(|(pos, _)| (&path[..pos], &path[pos + 1..]))
+                .find(|(prefix, name)| prefix.len() <= 155 && name.len() <= 100)
+                .ok_or_else(|| user_error(format!("Path is too long for a tar archive: {path}")))?
+        };
+        let (mode, size, typeflag, linkname, contents) = match &entry {
+            ExportedEntry::File {
+                executable,
+                contents,
+            } => {
+                let mode = if *executable { 0o755 } else { 0o644 };
+                (mode, contents.len(), b'0', "", contents.as_slice())
+            }
+            ExportedEntry::Symlink { target } => {
+                if target.len() > 100 {
+                    return Err(user_error(format!(
+                        "Symlink target is too long for a tar archive: {path}"
+                    )));
+                }
+                (0o777, 0, b'2', target.as_str(), [].as_slice())
+            }
+        };
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        write_octal(&mut header[100..108], mode)?;
+        write_octal(&mut header[108..116], 0)?;
+        write_octal(&mut header[116..124], 0)?;
+        write_octal(&mut header[124..136], size as u64).map_err(|_| {
+            user_error(format!("File is too large for a tar archive: {path}"))
+        })?;
+        write_octal(&mut header[136..148], self.mtime)?;
+        header[156] = typeflag;
+        header[157..157 + linkname.len()].copy_from_slice(linkname.as_bytes());
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+        // The checksum is computed with the checksum field filled with spaces.
+        header[148..156].fill(b' ');
+        let checksum: u64 = header.iter().map(|&b| u64::from(b)).sum();
+        write_octal(&mut header[148..155], checksum)?;
+
+        self.writer.write_all(&header)?;
+        self.writer.write_all(contents)?;
+        let padding = contents.len().next_multiple_of(TAR_BLOCK_SIZE) - contents.len();
+        self.writer.write_all(&[0; TAR_BLOCK_SIZE][..padding])?;
+        Ok(())
+    }
+
+    /// Writes the end of the archive, and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        // The end of the archive is marked by two zero blocks.
+        self.writer.write_all(&[0; 2 * TAR_BLOCK_SIZE])?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Writes `value` as zero-padded octal digits followed by a NUL byte. Fails if
+/// the value has too many digits for the field.
+fn write_octal(field: &mut [u8], value: u64) -> io::Result<()> {
+    let digits = format!("{value:0width$o}", width = field.len() - 1);
+    if digits.len() >= field.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{value} doesn't fit in a tar header field"),
+        ));
+    }
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    field[digits.len()] = 0;
+    Ok(())
+}
+
+/// Writes a zip archive. Files are compressed with deflate unless that
+/// doesn't make them smaller.
+pub struct ZipWriter<W> {
+    writer: W,
+    /// DOS time and date of all entries.
+    dos_time: u16,
+    dos_date: u16,
+    offset: u64,
+    central_directory: Vec<u8>,
+    entry_count: u64,
+}
+
+impl<W: Write> ZipWriter<W> {
+    /// Creates a writer giving all entries the modification time `mtime`, in
+    /// seconds since the epoch.
+    pub fn new(writer: W, mtime: u64) -> Self {
+        // DOS timestamps are in local time, which is taken to be UTC here so
+        // that archives don't depend on the time zone they're created in.
+        let datetime = i64::try_from(mtime)
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .filter(|datetime| (1980..2108).contains(&datetime.year()))
+            .unwrap_or_default();
+        let (dos_time, dos_date) = if datetime.year() < 1980 {
+            // The earliest date representable: 1980-01-01 00:00:00.
+            (0, (1 << 5) | 1)
+        } else {
+            (
+                (datetime.hour() << 11 | datetime.minute() << 5 | (datetime.second() / 2)) as u16,
+                ((datetime.year() as u32 - 1980) << 9 | datetime.month() << 5 | datetime.day())
+                    as u16,
+            )
+        };
+        ZipWriter {
+            writer,
+            dos_time,
+            dos_date,
+            offset: 0,
+            central_directory: vec![],
+            entry_count: 0,
+        }
+    }
+
+    pub fn append(&mut self, path: &str, entry: ExportedEntry) -> Result<(), CommandError> {
+        let (mode, data): (u32, _) = match entry {
+            ExportedEntry::File {
+                executable,
+                contents,
+            } => {
+                let mode = if executable { 0o100755 } else { 0o100644 };
+                (mode, contents)
+            }
+            ExportedEntry::Symlink { target } => (0o120777, target.into_bytes()),
+        };
+        let mut crc = flate2::Crc::new();
+        crc.update(&data);
+        let mut encoder = DeflateEncoder::new(vec![], Compression::default());
+        encoder.write_all(&data)?;
+        let compressed = encoder.finish()?;
+        let (method, stored): (u16, &[u8]) = if compressed.len() < data.len() {
+            (8, &compressed)
+        } else {
+            (0, &data)
+        };
+        let too_large = || user_error(format!("File is too large for a zip archive: {path}"));
+        let name_len = u16::try_from(path.len()).map_err(|_| too_large())?;
+        let stored_size = u32::try_from(stored.len()).map_err(|_| too_large())?;
+        let size = u32::try_from(data.len()).map_err(|_| too_large())?;
+        let offset = u32::try_from(self.offset).map_err(|_| too_large())?;
+        if self.entry_count == u64::from(u16::MAX) {
+            return Err(user_error("Too many files for a zip archive"));
+        }
+
+        // Fields shared by the local header and the central directory entry:
+        // version needed (2.0), flags (UTF-8 names), method, time, date,
+        // CRC-32, sizes, name length, and extra field length.
+        let mut common = vec![];
+        for value in [20, 0x0800, method, self.dos_time, self.dos_date] {
+            common.extend(value.to_le_bytes());
+        }
+        for value in [crc.sum(), stored_size, size] {
+            common.extend(value.to_le_bytes());
+        }
+        for value in [name_len, 0] {
+            common.extend(value.to_le_bytes());
+        }
+
+        let mut local_header = 0x04034b50_u32.to_le_bytes().to_vec();
+        local_header.extend(&common);
+        local_header.extend(path.as_bytes());
+        self.writer.write_all(&local_header)?;
+        self.writer.write_all(stored)?;
+
+        let central = &mut self.central_directory;
+        central.extend(0x02014b50_u32.to_le_bytes());
+        // Made by Unix, so that the external attributes hold the file mode.
+        central.extend((3_u16 << 8 | 20).to_le_bytes());
+        central.extend(&common);
+        // Comment length, disk number, and internal attributes.
+        central.extend([0; 6]);
+        central.extend((mode << 16).to_le_bytes());
+        central.extend(offset.to_le_bytes());
+        central.extend(path.as_bytes());
+
+        self.offset += (local_header.len() + stored.len()) as u64;
+        self.entry_count += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<(), CommandError> {
+        let too_large = || user_error("Archive is too large for the zip format");
+        let entry_count = u16::try_from(self.entry_count).map_err(|_| too_large())?;
+        let directory_size =
+            u32::try_from(self.central_directory.len()).map_err(|_| too_large())?;
+        let directory_offset = u32::try_from(self.offset).map_err(|_| too_large())?;
+        self.writer.write_all(&self.central_directory)?;
+        let mut end = 0x06054b50_u32.to_le_bytes().to_vec();
+        // Disk numbers.
+        end.extend([0; 4]);
+        end.extend(entry_count.to_le_bytes());
+        end.extend(entry_count.to_le_bytes());
+        end.extend(directory_size.to_le_bytes());
+        end.extend(directory_offset.to_le_bytes());
+        // Comment length.
+        end.extend([0; 2]);
+        self.writer.write_all(&end)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_octal() {
+        let mut field = [0xff; 4];
+        write_octal(&mut field, 0o17).unwrap();
+        assert_eq!(&field, b"017\0");
+        write_octal(&mut field, 0o777).unwrap();
+        assert_eq!(&field, b"777\0");
+        assert!(write_octal(&mut field, 0o1000).is_err());
+        // 8 GiB doesn't fit in the 12-byte size field
+        assert!(write_octal(&mut [0; 12], 8 << 30).is_err());
+        assert!(write_octal(&mut [0; 12], (8 << 30) - 1).is_ok());
+    }
+}
@@ -24,7 +24,6 @@ use std::fmt::Debug;
 use std::fs;
 use std::io;
 use std::io::Write as _;
-use std::iter;
 use std::mem;
 use std::path::Path;
 use std::path::PathBuf;
@@ -69,12 +68,17 @@ use jj_lib::file_util;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
+#[cfg(feature = "native-fsmonitor")]
+use jj_lib::fsmonitor::FsmonitorSettings;
 use jj_lib::git;
 use jj_lib::git_backend::GitBackend;
 use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::id_prefix::IdPrefixContext;
+#[cfg(feature = "native-fsmonitor")]
+use jj_lib::local_working_copy::LocalWorkingCopy;
 use jj_lib::matchers::Matcher;
+use jj_lib::matchers::NothingMatcher;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId;
@@ -124,6 +128,7 @@ use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
 use jj_lib::working_copy::SnapshotOptions;
 use jj_lib::working_copy::SnapshotStats;
+use jj_lib::working_copy::SymlinkPolicy;
 use jj_lib::working_copy::UntrackedReason;
 use jj_lib::working_copy::WorkingCopy;
 use jj_lib::working_copy::WorkingCopyFactory;
@@ -156,9 +161,12 @@ use crate::commit_templater::CommitTemplateLanguageExtension;
 use crate::complete;
 use crate::config::config_from_environment;
 use crate::config::parse_config_args;
+use crate::config::resolve_conditional_config;
 use crate::config::CommandNameAndArgs;
 use crate::config::ConfigArgKind;
 use crate::config::ConfigEnv;
+use crate::config::ConfigResolutionContext;
+use crate::conflict_resolutions::ConflictResolutions;
 use crate::diff_util;
 use crate::diff_util::DiffFormat;
 use crate::diff_util::DiffFormatArgs;
@@ -169,11 +177,15 @@ use crate::formatter::PlainTextFormatter;
 use crate::git_util::is_colocated_git_workspace;
 use crate::git_util::print_failed_git_export;
 use crate::git_util::print_git_import_stats;
+use crate::git_util::print_locked_file_edits;
+use crate::git_util::set_up_ssl_ca_info;
 use crate::merge_tools::DiffEditor;
 use crate::merge_tools::MergeEditor;
 use crate::merge_tools::MergeToolConfigError;
 use crate::operation_templater::OperationTemplateLanguage;
 use crate::operation_templater::OperationTemplateLanguageExtension;
+use crate::profiling::Profiler;
+use crate::revset_cache::RevsetCache;
 use crate::revset_util;
 use crate::revset_util::RevsetExpressionEvaluator;
 use crate::template_builder;
@@ -185,6 +197,7 @@ use crate::templater::TemplateRenderer;
 use crate::text_util;
 use crate::ui::ColorChoice;
 use crate::ui::Ui;
+use crate::workspace_roots::WorkspaceRoots;
 
 const SHORT_CHANGE_ID_TEMPLATE_TEXT: &str = "format_short_change_id(self.change_id())";
 
@@ -209,6 +222,7 @@ pub struct TracingSubscription {
         tracing_subscriber::Registry,
     >,
     _chrome_tracing_flush_guard: ChromeTracingFlushGuard,
+    profiler: Profiler,
 }
 
 impl TracingSubscription {
@@ -250,6 +264,7 @@ impl TracingSubscription {
             }
             Err(_) => (None, ChromeTracingFlushGuard { _inner: None }),
         };
+        let (profiler, profiling_layer) = Profiler::new();
 
         tracing_subscriber::registry()
             .with(
@@ -258,10 +273,12 @@ impl TracingSubscription {
                     .with_filter(filter),
             )
             .with(chrome_tracing_layer)
+            .with(profiling_layer)
             .init();
         TracingSubscription {
             reload_log_filter,
             _chrome_tracing_flush_guard: chrome_tracing_flush_guard,
+            profiler,
         }
     }
 
@@ -277,6 +294,11 @@ impl TracingSubscription {
         tracing::info!("debug logging enabled");
         Ok(())
     }
+
+    /// Starts recording time spent per phase. See [`Profiler::enable()`].
+    pub fn enable_profiling(&self, trace_path: Option<PathBuf>) {
+        self.profiler.enable(trace_path);
+    }
 }
 
 #[derive(Clone)]
@@ -291,6 +313,7 @@ struct CommandHelperData {
     matches: ArgMatches,
     global_args: GlobalArgs,
     config_env: ConfigEnv,
+    raw_config: StackedConfig,
     settings: UserSettings,
     revset_extensions: Arc<RevsetExtensions>,
     commit_template_extensions: Vec<Arc<dyn CommitTemplateLanguageExtension>>,
@@ -329,6 +352,15 @@ impl CommandHelper {
         &self.data.config_env
     }
 
+    /// Config layers as loaded from the config files and arguments, before
+    /// `[[include-if]]` sections are resolved.
+    ///
+    /// Use this to look up the config files to edit. The effective config is
+    /// available through `settings()`.
+    pub fn raw_config(&self) -> &StackedConfig {
+        &self.data.raw_config
+    }
+
     pub fn settings(&self) -> &UserSettings {
         &self.data.settings
     }
@@ -382,7 +414,7 @@ impl CommandHelper {
     pub fn workspace_helper(&self, ui: &Ui) -> Result<WorkspaceCommandHelper, CommandError> {
         let mut workspace_command = self.workspace_helper_no_snapshot(ui)?;
 
-        let workspace_command = match workspace_command.maybe_snapshot_impl(ui) {
+        let workspace_command = match workspace_command.maybe_snapshot_impl(ui, None) {
             Ok(()) => workspace_command,
             Err(SnapshotWorkingCopyError::Command(err)) => return Err(err),
             Err(SnapshotWorkingCopyError::StaleWorkingCopy(err)) => {
@@ -447,6 +479,21 @@ impl CommandHelper {
     pub fn recover_stale_working_copy(
         &self,
         ui: &Ui,
+    ) -> Result<WorkspaceCommandHelper, CommandError> {
+        self.recover_stale_working_copy_to(ui, None, false)
+    }
+
+    /// Updates a stale working copy like `recover_stale_working_copy()`.
+    ///
+    /// If `target` is given, a new working-copy commit is checked out on top of
+    /// it instead of the recorded one. If `discard_changes` is true, changes in
+    /// the working copy that haven't been snapshotted are overwritten instead
+    /// of being snapshotted first.
+    pub fn recover_stale_working_copy_to(
+        &self,
+        ui: &Ui,
+        target: Option<&RevisionArg>,
+        discard_changes: bool,
     ) -> Result<WorkspaceCommandHelper, CommandError> {
         let workspace = self.load_workspace()?;
         let op_id = workspace.working_copy().operation_id();
@@ -456,22 +503,40 @@ impl CommandHelper {
                 let repo = workspace.repo_loader().load_at(&op)?;
                 let mut workspace_command = self.for_workable_repo(ui, workspace, repo)?;
 
-                // Snapshot the current working copy on top of the last known working-copy
-                // operation, then merge the divergent operations. The wc_commit_id of the
-                // merged repo wouldn't change because the old one wins, but it's probably
-                // fine if we picked the new wc_commit_id.
-                workspace_command.maybe_snapshot(ui)?;
+                if !discard_changes {
+                    // Snapshot the current working copy on top of the last known working-copy
+                    // operation, then merge the divergent operations. The wc_commit_id of the
+                    // merged repo wouldn't change because the old one wins, but it's probably
+                    // fine if we picked the new wc_commit_id.
+                    workspace_command.maybe_snapshot(ui)?;
+                }
 
                 let wc_commit_id = workspace_command.get_wc_commit_id().unwrap();
                 let repo = workspace_command.repo().clone();
                 let stale_wc_commit = repo.store().get_commit(wc_commit_id)?;
 
                 let mut workspace_command = self.workspace_helper_no_snapshot(ui)?;
+                if let Some(target) = target {
+                    let new_parent = workspace_command.resolve_single_rev(ui, target)?;
+                    let workspace_id = workspace_command.workspace_id().clone();
+                    let mut tx = workspace_command.start_transaction().into_inner();
+                    tx.repo_mut()
+                        .check_out(workspace_id, self.settings(), &new_parent)?;
+                    tx.repo_mut().rebase_descendants(self.settings())?;
+                    tx.commit(format!(
+                        "update stale working copy to commit {}",
+                        new_parent.id().hex()
+                    ))?;
+                    workspace_command = self.workspace_helper_no_snapshot(ui)?;
+                }
                 let checkout_options = workspace_command.checkout_options();
 
                 let repo = workspace_command.repo().clone();
-                let (mut locked_ws, desired_wc_commit) =
-                    workspace_command.unchecked_start_working_copy_mutation()?;
+                let (mut locked_ws, desired_wc_commit) = if discard_changes {
+                    workspace_command.start_working_copy_mutation_discarding_changes(ui)?
+                } else {
+                    workspace_command.unchecked_start_working_copy_mutation()?
+                };
                 match WorkingCopyFreshness::check_stale(
                     locked_ws.locked_wc(),
                     &desired_wc_commit,
@@ -720,6 +785,7 @@ pub struct WorkspaceCommandEnvironment {
     immutable_heads_expression: Rc<UserRevsetExpression>,
     short_prefixes_expression: Option<Rc<UserRevsetExpression>>,
     conflict_marker_style: ConflictMarkerStyle,
+    symlink_policy: SymlinkPolicy,
 }
 
 impl WorkspaceCommandEnvironment {
@@ -740,6 +806,7 @@ impl WorkspaceCommandEnvironment {
             immutable_heads_expression: RevsetExpression::root(),
             short_prefixes_expression: None,
             conflict_marker_style: command.settings().conflict_marker_style()?,
+            symlink_policy: command.settings().symlink_policy()?,
         };
         env.immutable_heads_expression = env.load_immutable_heads_expression(ui)?;
         env.short_prefixes_expression = env.load_short_prefixes_expression(ui)?;
@@ -806,6 +873,11 @@ impl WorkspaceCommandEnvironment {
         self.conflict_marker_style
     }
 
+    /// User-configured policy for materializing symlinks in the working copy
+    pub fn symlink_policy(&self) -> SymlinkPolicy {
+        self.symlink_policy
+    }
+
     fn load_immutable_heads_expression(
         &self,
         ui: &Ui,
@@ -874,6 +946,41 @@ impl WorkspaceCommandEnvironment {
         Ok(commit_id_iter.next().transpose()?)
     }
 
+    /// Like `find_immutable_commit()`, but the evaluated immutable set is
+    /// cached on disk for the operation of the `repo`.
+    fn find_immutable_commit_cached<'a>(
+        &self,
+        repo: &ReadonlyRepo,
+        revset_cache: &RevsetCache,
+        commits: impl IntoIterator<Item = &'a CommitId>,
+    ) -> Result<Option<CommitId>, CommandError> {
+        if self.command.global_args().ignore_immutable {
+            return self.find_immutable_commit(repo, commits);
+        }
+
+        let id_prefix_context = IdPrefixContext::new(self.command.revset_extensions().clone());
+        let expression = self.immutable_expression();
+        let is_immutable = revset_cache
+            .containing_fn(repo, &self.workspace_id, &expression, || {
+                RevsetExpressionEvaluator::new(
+                    repo,
+                    self.command.revset_extensions().clone(),
+                    &id_prefix_context,
+                    expression.clone(),
+                )
+                .evaluate()
+            })
+            .map_err(|e| {
+                config_error_with_message("Invalid `revset-aliases.immutable_heads()`", e)
+            })?;
+        for commit_id in commits {
+            if is_immutable(commit_id)? {
+                return Ok(Some(commit_id.clone()));
+            }
+        }
+        Ok(None)
+    }
+
     /// Parses template of the given language into evaluation tree.
     ///
     /// `wrap_self` specifies the type of the top-level property, which should
@@ -932,6 +1039,7 @@ pub struct WorkspaceCommandHelper {
     op_summary_template_text: String,
     may_update_working_copy: bool,
     working_copy_shared_with_git: bool,
+    snapshot_stats: SnapshotStats,
 }
 
 enum SnapshotWorkingCopyError {
@@ -970,6 +1078,8 @@ impl WorkspaceCommandHelper {
         let may_update_working_copy =
             loaded_at_head && !env.command.global_args().ignore_working_copy;
         let working_copy_shared_with_git = is_colocated_git_workspace(&workspace, &repo);
+        WorkspaceRoots::new(workspace.repo_path())
+            .record(workspace.workspace_id(), workspace.workspace_root());
         let helper = Self {
             workspace,
             user_repo: ReadonlyUserRepo::new(repo),
@@ -978,6 +1088,7 @@ impl WorkspaceCommandHelper {
             op_summary_template_text,
             may_update_working_copy,
             working_copy_shared_with_git,
+            snapshot_stats: SnapshotStats::default(),
         };
         // Parse commit_summary template early to report error before starting
         // mutable operation.
@@ -991,6 +1102,12 @@ impl WorkspaceCommandHelper {
         self.env.settings()
     }
 
+    /// Stats of the working-copy snapshot taken by this command. Empty if no
+    /// snapshot was taken.
+    pub fn snapshot_stats(&self) -> &SnapshotStats {
+        &self.snapshot_stats
+    }
+
     pub fn git_backend(&self) -> Option<&GitBackend> {
         self.user_repo.git_backend()
     }
@@ -1012,7 +1129,11 @@ impl WorkspaceCommandHelper {
     }
 
     #[instrument(skip_all)]
-    fn maybe_snapshot_impl(&mut self, ui: &Ui) -> Result<(), SnapshotWorkingCopyError> {
+    fn maybe_snapshot_impl(
+        &mut self,
+        ui: &Ui,
+        snapshot_matcher: Option<&dyn Matcher>,
+    ) -> Result<(), SnapshotWorkingCopyError> {
         if self.may_update_working_copy {
             if self.working_copy_shared_with_git {
                 self.import_git_head(ui).map_err(snapshot_command_error)?;
@@ -1021,7 +1142,7 @@ impl WorkspaceCommandHelper {
             // pointing to the new working-copy commit might not be exported.
             // In that situation, the ref would be conflicted anyway, so export
             // failure is okay.
-            self.snapshot_working_copy(ui)?;
+            self.snapshot_working_copy(ui, snapshot_matcher)?;
 
             // import_git_refs() can rebase the working-copy commit.
             if self.working_copy_shared_with_git {
@@ -1035,7 +1156,20 @@ impl WorkspaceCommandHelper {
     /// copy is collocated with Git.
     #[instrument(skip_all)]
     pub fn maybe_snapshot(&mut self, ui: &Ui) -> Result<(), CommandError> {
-        self.maybe_snapshot_impl(ui)
+        self.maybe_snapshot_impl(ui, None)
+            .map_err(|err| err.into_command_error())
+    }
+
+    /// Like `maybe_snapshot()`, but only looks for changes to the paths
+    /// matching `matcher`. Changes to other paths are left in the working copy
+    /// to be picked up by a later snapshot.
+    #[instrument(skip_all)]
+    pub fn maybe_snapshot_matching(
+        &mut self,
+        ui: &Ui,
+        matcher: &dyn Matcher,
+    ) -> Result<(), CommandError> {
+        self.maybe_snapshot_impl(ui, Some(matcher))
             .map_err(|err| err.into_command_error())
     }
 
@@ -1105,7 +1239,7 @@ impl WorkspaceCommandHelper {
     /// This function does not import the Git HEAD, but the HEAD may be reset to
     /// the working copy parent if the repository is colocated.
     #[instrument(skip_all)]
-    fn import_git_refs(&mut self, ui: &Ui) -> Result<(), CommandError> {
+    pub fn import_git_refs(&mut self, ui: &Ui) -> Result<(), CommandError> {
         let git_settings = self.settings().git_settings();
         let mut tx = self.start_transaction();
         // Automated import shouldn't fail because of reserved remote name.
@@ -1157,6 +1291,7 @@ impl WorkspaceCommandHelper {
     pub fn checkout_options(&self) -> CheckoutOptions {
         CheckoutOptions {
             conflict_marker_style: self.env.conflict_marker_style(),
+            symlink_policy: self.env.symlink_policy(),
         }
     }
 
@@ -1629,14 +1764,22 @@ to the current parents may contain changes from multiple commits.
 
     /// Creates commit template language environment for this workspace.
     pub fn commit_template_language(&self) -> CommitTemplateLanguage<'_> {
-        self.env
-            .commit_template_language(self.repo().as_ref(), self.id_prefix_context())
+        let mut language = self
+            .env
+            .commit_template_language(self.repo().as_ref(), self.id_prefix_context());
+        language.set_revset_cache(self.repo(), self.revset_cache());
+        language
+    }
+
+    /// Cache of revsets evaluated at the operations of this repo.
+    pub fn revset_cache(&self) -> RevsetCache {
+        RevsetCache::new(self.workspace.repo_path())
     }
 
     /// Creates operation template language environment for this workspace.
     pub fn operation_template_language(&self) -> OperationTemplateLanguage {
         OperationTemplateLanguage::new(
-            self.repo().op_store().root_operation_id(),
+            self.repo().loader(),
             Some(self.repo().op_id()),
             self.env.operation_template_extensions(),
         )
@@ -1700,9 +1843,9 @@ to the current parents may contain changes from multiple commits.
         &self,
         commits: impl IntoIterator<Item = &'a CommitId>,
     ) -> Result<(), CommandError> {
-        let Some(commit_id) = self
-            .env
-            .find_immutable_commit(self.repo().as_ref(), commits)?
+        let Some(commit_id) =
+            self.env
+                .find_immutable_commit_cached(self.repo(), &self.revset_cache(), commits)?
         else {
             return Ok(());
         };
@@ -1725,8 +1868,46 @@ to the current parents may contain changes from multiple commits.
         Err(error)
     }
 
+    /// Locks the working copy like `unchecked_start_working_copy_mutation()`,
+    /// and snapshots it without recording the result in the repo, so that
+    /// checking out a commit afterwards overwrites any changes made to the
+    /// tracked files. Untracked files are left alone.
+    fn start_working_copy_mutation_discarding_changes(
+        &mut self,
+        ui: &Ui,
+    ) -> Result<(LockedWorkspace<'_>, Commit), CommandError> {
+        let base_ignores = self.base_ignores()?;
+        let fsmonitor_settings = self.settings().fsmonitor_settings()?;
+        let file_state_trust = self.settings().file_state_trust()?;
+        let rehash_throttle_size = self.settings().rehash_throttle_size()?;
+        let conflict_marker_style = self.env.conflict_marker_style();
+        let symlink_policy = self.env.symlink_policy();
+        let (mut locked_ws, wc_commit) = self.unchecked_start_working_copy_mutation()?;
+        let progress = crate::progress::snapshot_progress(ui);
+        locked_ws.locked_wc().snapshot(&SnapshotOptions {
+            base_ignores,
+            fsmonitor_settings,
+            progress: progress.as_ref().map(|x| x as _),
+            // Tracking new files here would make the checkout delete them.
+            start_tracking_matcher: &NothingMatcher,
+            snapshot_matcher: None,
+            // Only applies to new files, which aren't tracked anyway.
+            max_new_file_size: u64::MAX,
+            file_state_trust,
+            rehash_throttle_size,
+            conflict_marker_style,
+            symlink_policy,
+        })?;
+        drop(progress);
+        Ok((locked_ws, wc_commit))
+    }
+
     #[instrument(skip_all)]
-    fn snapshot_working_copy(&mut self, ui: &Ui) -> Result<(), SnapshotWorkingCopyError> {
+    fn snapshot_working_copy(
+        &mut self,
+        ui: &Ui,
+        snapshot_matcher: Option<&dyn Matcher>,
+    ) -> Result<(), SnapshotWorkingCopyError> {
         let workspace_id = self.workspace_id().to_owned();
         let get_wc_commit = |repo: &ReadonlyRepo| -> Result<Option<_>, _> {
             repo.view()
@@ -1751,12 +1932,24 @@ to the current parents may contain changes from multiple commits.
             .settings()
             .fsmonitor_settings()
             .map_err(snapshot_command_error)?;
+        #[cfg(feature = "native-fsmonitor")]
+        let use_native_fsmonitor = fsmonitor_settings == FsmonitorSettings::Native;
         let max_new_file_size = self
             .settings()
             .max_new_file_size()
             .map_err(snapshot_command_error)?;
+        let file_state_trust = self
+            .settings()
+            .file_state_trust()
+            .map_err(snapshot_command_error)?;
+        let rehash_throttle_size = self
+            .settings()
+            .rehash_throttle_size()
+            .map_err(snapshot_command_error)?;
         let conflict_marker_style = self.env.conflict_marker_style();
+        let symlink_policy = self.env.symlink_policy();
         let command = self.env.command.clone();
+        let conflict_resolutions = ConflictResolutions::new(self.workspace.repo_path());
         let mut locked_ws = self
             .workspace
             .start_working_copy_mutation()
@@ -1822,12 +2015,33 @@ See https://martinvonz.github.io/jj/latest/working-copy/#stale-working-copy \
                 fsmonitor_settings,
                 progress: progress.as_ref().map(|x| x as _),
                 start_tracking_matcher: &auto_tracking_matcher,
+                snapshot_matcher,
                 max_new_file_size,
+                file_state_trust,
+                rehash_throttle_size,
                 conflict_marker_style,
+                symlink_policy,
             })
             .map_err(snapshot_command_error)?;
         drop(progress);
         if new_tree_id != *wc_commit.tree_id() {
+            if let Some(git_backend) = self.user_repo.git_backend() {
+                let new_tree = self
+                    .user_repo
+                    .repo
+                    .store()
+                    .get_root_tree(&new_tree_id)
+                    .map_err(snapshot_command_error)?;
+                print_locked_file_edits(
+                    ui,
+                    git_backend,
+                    &wc_commit.tree().map_err(snapshot_command_error)?,
+                    &new_tree,
+                    &command.settings().user_email(),
+                    &self.env.path_converter,
+                )
+                .map_err(snapshot_command_error)?;
+            }
             let mut tx = start_repo_transaction(
                 &self.user_repo.repo,
                 command.settings(),
@@ -1856,6 +2070,16 @@ See https://martinvonz.github.io/jj/latest/working-copy/#stale-working-copy \
                 .map_err(snapshot_command_error)?;
             }
 
+            if command
+                .settings()
+                .get_bool("rerere.enabled")
+                .map_err(snapshot_command_error)?
+            {
+                conflict_resolutions
+                    .record_commits(mut_repo, &self.user_repo.repo)
+                    .map_err(snapshot_command_error)?;
+            }
+
             if self.working_copy_shared_with_git {
                 let refs = git::export_refs(mut_repo).map_err(snapshot_command_error)?;
                 print_failed_git_export(ui, &refs).map_err(snapshot_command_error)?;
@@ -1871,6 +2095,56 @@ See https://martinvonz.github.io/jj/latest/working-copy/#stale-working-copy \
             .map_err(snapshot_command_error)?;
         print_snapshot_stats(ui, &stats, &self.env.path_converter)
             .map_err(snapshot_command_error)?;
+        self.snapshot_stats = stats;
+        #[cfg(feature = "native-fsmonitor")]
+        if use_native_fsmonitor {
+            if let Err(err) = self.start_native_fsmonitor_daemon() {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to start the filesystem monitor daemon: {err}"
+                )
+                .map_err(snapshot_command_error)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts the native filesystem monitor daemon in the background unless
+    /// it's already running. The next snapshot will make use of it.
+    #[cfg(feature = "native-fsmonitor")]
+    fn start_native_fsmonitor_daemon(&self) -> io::Result<()> {
+        let Some(wc) = self
+            .working_copy()
+            .as_any()
+            .downcast_ref::<LocalWorkingCopy>()
+        else {
+            return Ok(());
+        };
+        if wc.native_fsmonitor().is_daemon_running() {
+            return Ok(());
+        }
+        let mut cmd = std::process::Command::new(std::env::current_exe()?);
+        cmd.args(["debug", "fsmonitor", "daemon", "--repository"])
+            .arg(self.workspace_root())
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null());
+        // Detach the daemon so that it isn't interrupted along with this
+        // process.
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt as _;
+            cmd.process_group(0);
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt as _;
+            const DETACHED_PROCESS: u32 = 0x00000008;
+            const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+            cmd.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+        }
+        tracing::info!(?cmd, "Starting the filesystem monitor daemon:");
+        cmd.spawn()?;
         Ok(())
     }
 
@@ -1920,13 +2194,17 @@ See https://martinvonz.github.io/jj/latest/working-copy/#stale-working-copy \
     }
 
     pub fn start_transaction(&mut self) -> WorkspaceCommandTransaction {
-        let tx =
+        let mut tx =
             start_repo_transaction(self.repo(), self.settings(), self.env.command.string_args());
+        for (key, value) in &self.env.command.global_args().op_metadata {
+            tx.set_tag(key.clone(), value.clone());
+        }
         let id_prefix_context = mem::take(&mut self.user_repo.id_prefix_context);
         WorkspaceCommandTransaction {
             helper: self,
             tx,
             id_prefix_context,
+            record_resolutions: None,
         }
     }
 
@@ -2248,6 +2526,8 @@ pub struct WorkspaceCommandTransaction<'a> {
     tx: Transaction,
     /// Cache of index built against the current MutableRepo state.
     id_prefix_context: OnceCell<IdPrefixContext>,
+    /// Whether to record conflict resolutions. Defaults to `rerere.enabled`.
+    record_resolutions: Option<bool>,
 }
 
 impl WorkspaceCommandTransaction<'_> {
@@ -2338,7 +2618,50 @@ impl WorkspaceCommandTransaction<'_> {
         )
     }
 
+    /// Overrides whether the conflicts resolved in this transaction are
+    /// recorded for reuse.
+    pub fn set_record_resolutions(&mut self, record: bool) {
+        self.record_resolutions = Some(record);
+    }
+
+    /// Applies the recorded conflict resolutions to the conflicted commits
+    /// written in this transaction.
+    pub fn reuse_conflict_resolutions(&mut self, ui: &Ui) -> Result<(), CommandError> {
+        let resolutions = ConflictResolutions::new(self.helper.repo_path());
+        let base_repo = self.tx.base_repo().clone();
+        self.id_prefix_context.take(); // invalidate
+        let (num_paths, num_commits) =
+            resolutions.apply_to_commits(self.helper.settings(), self.tx.repo_mut(), &base_repo)?;
+        print_reused_resolutions(ui, num_paths, num_commits)
+    }
+
+    /// Applies the recorded conflict resolutions to the conflicted commits
+    /// made visible in this transaction, including the ones that were hidden
+    /// before.
+    pub fn reuse_conflict_resolutions_of_revived_commits(
+        &mut self,
+        ui: &Ui,
+    ) -> Result<(), CommandError> {
+        let resolutions = ConflictResolutions::new(self.helper.repo_path());
+        let base_repo = self.tx.base_repo().clone();
+        self.id_prefix_context.take(); // invalidate
+        let (num_paths, num_commits) = resolutions.apply_to_revived_commits(
+            self.helper.settings(),
+            self.tx.repo_mut(),
+            &base_repo,
+        )?;
+        print_reused_resolutions(ui, num_paths, num_commits)
+    }
+
     pub fn finish(self, ui: &Ui, description: impl Into<String>) -> Result<(), CommandError> {
+        let record_resolutions = match self.record_resolutions {
+            Some(record) => record,
+            None => self.helper.settings().get_bool("rerere.enabled")?,
+        };
+        if record_resolutions {
+            ConflictResolutions::new(self.helper.repo_path())
+                .record_commits(self.tx.repo(), self.tx.base_repo())?;
+        }
         self.helper.finish_transaction(ui, self.tx, description)
     }
 
@@ -2368,6 +2691,29 @@ impl WorkspaceCommandTransaction<'_> {
     }
 }
 
+fn print_reused_resolutions(
+    ui: &Ui,
+    num_paths: usize,
+    num_commits: usize,
+) -> Result<(), CommandError> {
+    if num_paths > 0 {
+        writeln!(
+            ui.status(),
+            "Reused recorded resolutions of {num_paths} conflicts in {num_commits} commits"
+        )?;
+    }
+    Ok(())
+}
+
+/// Returns the context to resolve conditional config for the workspace that
+/// `loader` would load.
+fn config_resolution_context(loader: Option<&dyn WorkspaceLoader>) -> ConfigResolutionContext {
+    ConfigResolutionContext::new(
+        loader.map(|loader| loader.workspace_root()),
+        loader.map(|loader| loader.repo_path()),
+    )
+}
+
 pub fn find_workspace_dir(cwd: &Path) -> &Path {
     cwd.ancestors()
         .find(|path| path.join(".jj").is_dir())
@@ -2445,6 +2791,20 @@ pub fn start_repo_transaction(
     tx
 }
 
+fn parse_op_metadata_arg(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| "expected KEY=VALUE".to_string())?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("metadata key must not be empty".to_string());
+    }
+    if key == "args" {
+        return Err("metadata key 'args' is reserved".to_string());
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
 fn update_stale_working_copy(
     mut locked_ws: LockedWorkspace,
     op_id: OperationId,
@@ -2607,6 +2967,8 @@ pub fn print_snapshot_stats(
             r"
             This is to prevent large files from being added by accident. You can fix this by:
               - Adding the file to `.gitignore`
+              - Run `jj file track --force <path>`
+                This will track the file regardless of its size.
               - Run `jj config set --repo snapshot.max-new-file-size {size}`
                 This will increase the maximum file size allowed for new files, in this repository only.
               - Run `jj --config snapshot.max-new-file-size={size} st`
@@ -2646,6 +3008,18 @@ Discard the conflicting changes with `jj restore --from {}`.",
             short_commit_hash(new_commit.id())
         )?;
     }
+    if stats.unrepresentable_files != 0 {
+        writeln!(
+            ui.warning_default(),
+            "{} of those updates were skipped because the file system can't represent their \
+             paths, e.g. because they differ only in case from other paths.",
+            stats.unrepresentable_files
+        )?;
+        writeln!(
+            ui.hint_default(),
+            "Run `jj status` to list the paths that weren't checked out."
+        )?;
+    }
     Ok(())
 }
 
@@ -3074,9 +3448,37 @@ pub struct GlobalArgs {
         add = ArgValueCandidates::new(complete::operations),
     )]
     pub at_operation: Option<String>,
+    /// Attach metadata to the operation created by the command
+    ///
+    /// The metadata is stored as a tag on the operation and can be shown with
+    /// the `tag(name)` method in `jj op log` templates. For example, `jj
+    /// --op-metadata ci-job=1234 new` records the CI job that created the
+    /// operation.
+    ///
+    /// This option can be repeated to attach several entries.
+    #[arg(
+        long,
+        global = true,
+        value_name = "KEY=VALUE",
+        value_parser = parse_op_metadata_arg,
+    )]
+    pub op_metadata: Vec<(String, String)>,
     /// Enable debug logging
     #[arg(long, global = true)]
     pub debug: bool,
+    /// Report the time spent in each phase of the command
+    ///
+    /// When the command finishes, the time spent snapshotting the working
+    /// copy, evaluating revsets, diffing trees, reading from and writing to
+    /// the backend, and committing the transaction is printed to stderr.
+    #[arg(long, global = true)]
+    pub profile: bool,
+    /// Also write the profile in Chrome trace format to the given file
+    ///
+    /// The file can be loaded in https://ui.perfetto.dev/. Implies
+    /// `--profile`.
+    #[arg(long, global = true, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    pub profile_trace: Option<PathBuf>,
 
     #[command(flatten)]
     pub early_args: EarlyArgs,
@@ -3252,11 +3654,108 @@ fn resolve_default_command(
     Ok(string_args)
 }
 
+/// Substitutes the `$N` and `$@` placeholders in an alias definition with the
+/// arguments the alias was invoked with.
+///
+/// Arguments not consumed by a `$N` placeholder are appended to the expanded
+/// definition unless `$@` is used. `$$` is a literal `$`. Definition items
+/// following a `--` item are passed through verbatim since they are usually
+/// arguments to an external command, which may have its own `$1` syntax.
+///
+/// If `allow_missing` is true, placeholders referring to arguments that weren't
+/// given expand to empty strings, and items consisting only of such
+/// placeholders are omitted. This is used for shell completion, where the
+/// command line is incomplete.
+fn expand_alias_placeholders(
+    alias_name: &str,
+    definition: &[String],
+    alias_args: &[String],
+    allow_missing: bool,
+) -> Result<Vec<String>, CommandError> {
+    let mut expanded = Vec::with_capacity(definition.len() + alias_args.len());
+    let mut max_index = 0;
+    let mut uses_all_args = false;
+    let mut missing_index = None;
+    let mut verbatim = false;
+    for item in definition {
+        if verbatim {
+            expanded.push(item.clone());
+            continue;
+        } else if item == "--" {
+            verbatim = true;
+            expanded.push(item.clone());
+            continue;
+        } else if item == "$@" {
+            uses_all_args = true;
+            expanded.extend_from_slice(alias_args);
+            continue;
+        }
+        let mut result = String::with_capacity(item.len());
+        let mut item_missing_arg = false;
+        let mut chars = item.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                result.push(c);
+                continue;
+            }
+            match chars.peek() {
+                Some('$') => {
+                    chars.next();
+                    result.push('$');
+                }
+                Some('@') => {
+                    chars.next();
+                    uses_all_args = true;
+                    result.push_str(&alias_args.join(" "));
+                }
+                Some('1'..='9') => {
+                    let mut digits = String::new();
+                    while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                        digits.push(d);
+                        chars.next();
+                    }
+                    // Absurdly large indices can't be given anyway
+                    let index: usize = digits.parse().unwrap_or(usize::MAX);
+                    max_index = max_index.max(index);
+                    if let Some(arg) = alias_args.get(index - 1) {
+                        result.push_str(arg);
+                    } else {
+                        item_missing_arg = true;
+                        if missing_index.map_or(true, |i| index < i) {
+                            missing_index = Some(index);
+                        }
+                    }
+                }
+                _ => result.push('$'),
+            }
+        }
+        if !(allow_missing && item_missing_arg && result.is_empty()) {
+            expanded.push(result);
+        }
+    }
+    if let Some(index) = missing_index.filter(|_| !allow_missing) {
+        let given = match alias_args.len() {
+            0 => "none were given".to_owned(),
+            1 => "only 1 was given".to_owned(),
+            n => format!("only {n} were given"),
+        };
+        return Err(user_error_with_hint(
+            format!(r#"Alias "{alias_name}" expects argument ${index}, but {given}"#),
+            format!("The alias is defined as {definition:?}"),
+        ));
+    }
+    if !uses_all_args {
+        expanded.extend(alias_args.iter().skip(max_index).cloned());
+    }
+    Ok(expanded)
+}
+
 fn resolve_aliases(
     ui: &Ui,
     config: &StackedConfig,
     app: &Command,
     mut string_args: Vec<String>,
+    allow_missing_placeholders: bool,
 ) -> Result<Vec<String>, CommandError> {
     let defined_aliases: HashSet<_> = config.table_keys("aliases").collect();
     let mut resolved_aliases = HashSet::new();
@@ -3293,9 +3792,14 @@ fn resolve_aliases(
                 if let Some(&alias_name) = defined_aliases.get(&*alias_name) {
                     let alias_definition: Vec<String> = config.get(["aliases", alias_name])?;
                     assert!(string_args.ends_with(&alias_args));
+                    let expanded = expand_alias_placeholders(
+                        alias_name,
+                        &alias_definition,
+                        &alias_args,
+                        allow_missing_placeholders,
+                    )?;
                     string_args.truncate(string_args.len() - 1 - alias_args.len());
-                    string_args.extend(alias_definition);
-                    string_args.extend_from_slice(&alias_args);
+                    string_args.extend(expanded);
                     resolved_aliases.insert(alias_name);
                     continue;
                 } else {
@@ -3365,6 +3869,9 @@ fn handle_early_args(
     Ok(())
 }
 
+/// Stands in for the arg being completed while expanding aliases.
+const COMPLETION_CURSOR: &str = "\0jj-completion-cursor\0";
+
 fn handle_shell_completion(
     ui: &Ui,
     app: &Command,
@@ -3379,26 +3886,45 @@ fn handle_shell_completion(
     // Make sure aliases are expanded before passing them to clap_complete. We
     // skip the first two args ("jj" and "--") for alias resolution, then we
     // stitch the args back together, like clap_complete expects them.
-    let orig_args = env::args_os().skip(2);
-    if orig_args.len() > 0 {
+    let mut orig_args = env::args_os().skip(2).collect_vec();
+    if !orig_args.is_empty() {
         let arg_index: Option<usize> = env::var("_CLAP_COMPLETE_INDEX")
             .ok()
             .and_then(|s| s.parse().ok());
-        let resolved_aliases = if let Some(index) = arg_index {
-            // As of clap_complete 4.5.38, zsh completion script doesn't pad an
-            // empty arg at the complete position. If the args doesn't include a
-            // command name, the default command would be expanded at that
-            // position. Therefore, no other command names would be suggested.
-            // TODO: Maybe we should instead expand args[..index] + [""], adjust
-            // the index accordingly, strip the last "", and append remainder?
-            let pad_len = usize::saturating_sub(index + 1, orig_args.len());
-            let padded_args = orig_args.chain(iter::repeat(OsString::new()).take(pad_len));
-            expand_args(ui, app, padded_args, config)?
-        } else {
-            expand_args(ui, app, orig_args, config)?
-        };
-        args.extend(resolved_aliases.into_iter().map(OsString::from));
+        // The arg being completed is the one at the index, or the last one if
+        // the shell doesn't pass an index. It's substituted by a placeholder
+        // so we can tell where it ends up after alias expansion, which may
+        // insert args before it or move it into a `$N` placeholder.
+        // As of clap_complete 4.5.38, zsh completion script doesn't pad an
+        // empty arg at the complete position, so we do.
+        let index = arg_index.unwrap_or(orig_args.len() - 1);
+        if orig_args.len() <= index {
+            orig_args.resize(index + 1, OsString::new());
+        }
+        let current_arg = mem::replace(&mut orig_args[index], COMPLETION_CURSOR.into());
+        let current_arg = current_arg
+            .into_string()
+            .map_err(|_| cli_error("Non-utf8 argument"))?;
+        let mut expanded = expand_args_for_completion(ui, app, orig_args, config)?;
+        if let Some(new_index) = expanded
+            .iter()
+            .position(|arg| arg.contains(COMPLETION_CURSOR))
+        {
+            expanded[new_index] = expanded[new_index].replace(COMPLETION_CURSOR, &current_arg);
+            if arg_index.is_some() {
+                env::set_var("_CLAP_COMPLETE_INDEX", new_index.to_string());
+            } else {
+                // Without an index, the last arg is the one to complete.
+                expanded.truncate(new_index + 1);
+            }
+        }
+        args.extend(expanded.into_iter().map(OsString::from));
     }
+    complete::set_expanded_args(
+        args.iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect(),
+    );
     let ran_completion = clap_complete::CompleteEnv::with_factory(|| {
         app.clone()
             // for completing aliases
@@ -3417,6 +3943,27 @@ pub fn expand_args(
     app: &Command,
     args_os: impl IntoIterator<Item = OsString>,
     config: &StackedConfig,
+) -> Result<Vec<String>, CommandError> {
+    expand_args_impl(ui, app, args_os, config, false)
+}
+
+/// Like [`expand_args()`], but tolerates incomplete command lines, such as
+/// aliases with missing placeholder arguments.
+pub fn expand_args_for_completion(
+    ui: &Ui,
+    app: &Command,
+    args_os: impl IntoIterator<Item = OsString>,
+    config: &StackedConfig,
+) -> Result<Vec<String>, CommandError> {
+    expand_args_impl(ui, app, args_os, config, true)
+}
+
+fn expand_args_impl(
+    ui: &Ui,
+    app: &Command,
+    args_os: impl IntoIterator<Item = OsString>,
+    config: &StackedConfig,
+    allow_missing_placeholders: bool,
 ) -> Result<Vec<String>, CommandError> {
     let mut string_args: Vec<String> = vec![];
     for arg_os in args_os {
@@ -3428,7 +3975,7 @@ pub fn expand_args(
     }
 
     let string_args = resolve_default_command(ui, config, app, string_args)?;
-    resolve_aliases(ui, config, app, string_args)
+    resolve_aliases(ui, config, app, string_args, allow_missing_placeholders)
 }
 
 pub fn parse_args(
@@ -3450,6 +3997,9 @@ pub fn parse_args(
         // TODO: set up debug logging as early as possible
         tracing_subscription.enable_debug_logging()?;
     }
+    if args.global_args.profile || args.global_args.profile_trace.is_some() {
+        tracing_subscription.enable_profiling(args.global_args.profile_trace.clone());
+    }
 
     Ok((matches, args))
 }
@@ -3657,13 +4207,17 @@ impl CliRunner {
             config_env.reset_repo_path(loader.repo_path());
             config_env.reload_repo_config(&mut config)?;
         }
-        ui.reset(&config)?;
+        let resolved_config = resolve_conditional_config(
+            &config,
+            &config_resolution_context(maybe_cwd_workspace_loader.as_deref().ok()),
+        )?;
+        ui.reset(&resolved_config)?;
 
         if env::var_os("COMPLETE").is_some() {
-            return handle_shell_completion(ui, &self.app, &config, &cwd);
+            return handle_shell_completion(ui, &self.app, &resolved_config, &cwd);
         }
 
-        let string_args = expand_args(ui, &self.app, env::args_os(), &config)?;
+        let string_args = expand_args(ui, &self.app, env::args_os(), &resolved_config)?;
         let (matches, args) = parse_args(
             ui,
             &self.app,
@@ -3671,7 +4225,7 @@ impl CliRunner {
             &string_args,
             &mut config,
         )
-        .map_err(|err| map_clap_cli_error(err, ui, &config))?;
+        .map_err(|err| map_clap_cli_error(err, ui, &resolved_config))?;
         for process_global_args_fn in self.process_global_args_fns {
             process_global_args_fn(ui, &matches)?;
         }
@@ -3690,12 +4244,16 @@ impl CliRunner {
         };
 
         // Apply workspace configs and --config arguments.
-        ui.reset(&config)?;
+        let resolved_config = resolve_conditional_config(
+            &config,
+            &config_resolution_context(maybe_workspace_loader.as_deref().ok()),
+        )?;
+        ui.reset(&resolved_config)?;
 
         // If -R is specified, check if the expanded arguments differ. Aliases
         // can also be injected by --config, but that's obviously wrong.
         if args.global_args.repository.is_some() {
-            let new_string_args = expand_args(ui, &self.app, env::args_os(), &config).ok();
+            let new_string_args = expand_args(ui, &self.app, env::args_os(), &resolved_config).ok();
             if new_string_args.as_ref() != Some(&string_args) {
                 writeln!(
                     ui.warning_default(),
@@ -3704,7 +4262,8 @@ impl CliRunner {
             }
         }
 
-        let settings = UserSettings::from_config(config);
+        let settings = UserSettings::from_config(resolved_config);
+        set_up_ssl_ca_info(ui, &settings.git_settings())?;
         let command_helper_data = CommandHelperData {
             app: self.app,
             cwd,
@@ -3712,6 +4271,7 @@ impl CliRunner {
             matches,
             global_args: args.global_args,
             config_env,
+            raw_config: config,
             settings,
             revset_extensions: self.revset_extensions.into(),
             commit_template_extensions: self.commit_template_extensions,
@@ -3737,8 +4297,12 @@ impl CliRunner {
         let config = config_from_environment(self.config_layers.drain(..));
         let mut ui = Ui::with_config(&config)
             .expect("default config should be valid, env vars are stringly typed");
+        let profiler = self.tracing_subscription.profiler.clone();
         let result = self.run_internal(&mut ui, config);
         let exit_code = handle_command_result(&mut ui, result);
+        if let Err(err) = profiler.finish(&mut ui.stderr()) {
+            writeln!(ui.warning_default(), "Failed to write profile: {err}").ok();
+        }
         ui.finalize_pager();
         exit_code
     }
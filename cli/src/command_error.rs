@@ -37,12 +37,14 @@ use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::op_heads_store::OpHeadResolutionError;
 use jj_lib::op_heads_store::OpHeadsStoreError;
 use jj_lib::op_store::OpStoreError;
+use jj_lib::op_sync::OpSyncError;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
 use jj_lib::repo::CheckOutCommitError;
 use jj_lib::repo::EditCommitError;
 use jj_lib::repo::RepoLoaderError;
 use jj_lib::repo::RewriteRootCommit;
+use jj_lib::repo_archive::RepoArchiveError;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::UiPathParseError;
 use jj_lib::revset::RevsetEvaluationError;
@@ -56,10 +58,12 @@ use jj_lib::working_copy::ResetError;
 use jj_lib::working_copy::SnapshotError;
 use jj_lib::working_copy::WorkingCopyStateError;
 use jj_lib::workspace::WorkspaceInitError;
+use jj_lib::workspace::WorkspaceMoveError;
 use thiserror::Error;
 
 use crate::cli_util::short_operation_hash;
 use crate::config::ConfigEnvError;
+use crate::config_schema::ConfigSchemaError;
 use crate::description_util::ParseBulkEditMessageError;
 use crate::diff_util::DiffRenderError;
 use crate::formatter::FormatRecorder;
@@ -287,6 +291,13 @@ impl From<RewriteRootCommit> for CommandError {
     }
 }
 
+impl From<ConfigSchemaError> for CommandError {
+    fn from(err: ConfigSchemaError) -> Self {
+        let hint = format!("Expected {}.", err.expected);
+        user_error_with_hint(err, hint)
+    }
+}
+
 impl From<EditCommitError> for CommandError {
     fn from(err: EditCommitError) -> Self {
         internal_error_with_message("Failed to edit a commit", err)
@@ -349,6 +360,19 @@ impl From<WorkspaceInitError> for CommandError {
     }
 }
 
+impl From<WorkspaceMoveError> for CommandError {
+    fn from(err: WorkspaceMoveError) -> Self {
+        match err {
+            WorkspaceMoveError::DestinationExists(_)
+            | WorkspaceMoveError::DestinationInsideWorkspace
+            | WorkspaceMoveError::NonUnicodePath => user_error(err),
+            WorkspaceMoveError::Path(err) => {
+                user_error_with_message("Failed to move workspace", err)
+            }
+        }
+    }
+}
+
 impl From<OpHeadResolutionError> for CommandError {
     fn from(err: OpHeadResolutionError) -> Self {
         match err {
@@ -387,12 +411,35 @@ impl From<OpStoreError> for CommandError {
     }
 }
 
+impl From<OpSyncError> for CommandError {
+    fn from(err: OpSyncError) -> Self {
+        match err {
+            OpSyncError::UnexpectedBackend | OpSyncError::Io { .. } => user_error(err),
+            OpSyncError::OpStore(err) => err.into(),
+            OpSyncError::OpHeadsStore(err) => err.into(),
+            OpSyncError::OperationIdMismatch(_) | OpSyncError::Git(_) => {
+                internal_error_with_message("Failed to copy operations", err)
+            }
+        }
+    }
+}
+
 impl From<RepoLoaderError> for CommandError {
     fn from(err: RepoLoaderError) -> Self {
         internal_error_with_message("Failed to load the repo", err)
     }
 }
 
+impl From<RepoArchiveError> for CommandError {
+    fn from(err: RepoArchiveError) -> Self {
+        match err {
+            RepoArchiveError::Io(_) | RepoArchiveError::InvalidArchive(_) => user_error(err),
+            RepoArchiveError::Backend(err) => err.into(),
+            RepoArchiveError::OpStore(err) => err.into(),
+        }
+    }
+}
+
 impl From<ResetError> for CommandError {
     fn from(err: ResetError) -> Self {
         internal_error_with_message("Failed to reset the working copy", err)
@@ -523,6 +570,17 @@ impl From<RevsetParseError> for CommandError {
     }
 }
 
+/// Converts operation set parse error, which shares the revset syntax.
+pub fn opset_parse_error(err: RevsetParseError) -> CommandError {
+    let hint = revset_parse_error_hint(&err);
+    let mut cmd_err = user_error_with_message(
+        format!("Failed to parse operation set: {}", err.kind()),
+        err,
+    );
+    cmd_err.extend_hints(hint);
+    cmd_err
+}
+
 impl From<RevsetResolutionError> for CommandError {
     fn from(err: RevsetResolutionError) -> Self {
         let hint = revset_resolution_error_hint(&err);
@@ -692,8 +750,12 @@ fn revset_resolution_error_hint(err: &RevsetResolutionError) -> Option<String> {
             name: _,
             candidates,
         } => format_similarity_hint(candidates),
+        RevsetResolutionError::WorkspaceMissingWorkingCopy { .. } => Some(
+            "Specify a revision explicitly. In a bare repo, run `jj workspace add --first` to \
+             check out a working copy."
+                .to_owned(),
+        ),
         RevsetResolutionError::EmptyString
-        | RevsetResolutionError::WorkspaceMissingWorkingCopy { .. }
         | RevsetResolutionError::AmbiguousCommitIdPrefix(_)
         | RevsetResolutionError::AmbiguousChangeIdPrefix(_)
         | RevsetResolutionError::StoreError(_)
@@ -785,6 +847,12 @@ fn print_error(
     Ok(())
 }
 
+/// Prints the `err` and its hints without terminating the command, e.g. before
+/// asking the user to retry.
+pub fn print_recoverable_error(ui: &Ui, err: &CommandError) -> io::Result<()> {
+    print_error(ui, "Error: ", &*err.error, &err.hints)
+}
+
 fn print_error_sources(ui: &Ui, source: Option<&dyn error::Error>) -> io::Result<()> {
     let Some(err) = source else {
         return Ok(());
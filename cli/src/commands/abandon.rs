@@ -46,6 +46,11 @@ pub(crate) struct AbandonArgs {
     #[arg(short = 'r', hide = true, action = clap::ArgAction::Count)]
     unused_revision: u8,
     /// Do not modify the content of the children of the abandoned commits
+    ///
+    /// The children are reparented onto the parents of the abandoned commits
+    /// while keeping their trees as they were, so the changes of the abandoned
+    /// commits become part of the children. This is useful when the abandoned
+    /// commit has already been merged upstream in a different form.
     #[arg(long)]
     restore_descendants: bool,
 }
@@ -0,0 +1,59 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Delete existing change aliases
+///
+/// The aliased changes are left untouched.
+#[derive(clap::Args, Clone, Debug)]
+pub struct AliasDeleteArgs {
+    /// The aliases to delete
+    #[arg(
+        required = true,
+        add = ArgValueCandidates::new(complete::change_aliases),
+    )]
+    names: Vec<String>,
+}
+
+pub fn cmd_alias_delete(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &AliasDeleteArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let view = workspace_command.repo().view();
+    let names = args.names.iter().unique().collect_vec();
+    if let Some(name) = names
+        .iter()
+        .find(|name| view.get_change_alias(name).is_none())
+    {
+        return Err(user_error(format!("No such alias: {name}")));
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    for name in &names {
+        tx.repo_mut().set_change_alias(name, None);
+    }
+    writeln!(ui.status(), "Deleted {} aliases.", names.len())?;
+    tx.finish(ui, format!("delete alias {}", names.iter().join(", ")))?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::repo::Repo;
+
+use crate::cli_util::short_change_hash;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// List change aliases and the commits of the changes they point to
+///
+/// An alias pointing to a change without visible commits (e.g. because the
+/// change was abandoned) is listed with its change ID only. If the change is
+/// divergent, all of its visible commits are listed.
+#[derive(clap::Args, Clone, Debug)]
+pub struct AliasListArgs {}
+
+pub fn cmd_alias_list(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &AliasListArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let template = workspace_command.commit_summary_template();
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for (name, change_id) in repo.view().change_aliases() {
+        let commit_ids = repo.resolve_change_id(change_id).unwrap_or_default();
+        if commit_ids.is_empty() {
+            write!(formatter.labeled("change_alias"), "{name}")?;
+            write!(formatter, ": ")?;
+            write!(
+                formatter.labeled("hint"),
+                "{} (no visible commits)",
+                short_change_hash(change_id)
+            )?;
+            writeln!(formatter)?;
+        }
+        for commit_id in &commit_ids {
+            write!(formatter.labeled("change_alias"), "{name}")?;
+            write!(formatter, ": ")?;
+            let commit = repo.store().get_commit(commit_id)?;
+            template.format(&commit, formatter.as_mut())?;
+            writeln!(formatter)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod delete;
+mod list;
+mod set;
+
+use self::delete::cmd_alias_delete;
+use self::delete::AliasDeleteArgs;
+use self::list::cmd_alias_list;
+use self::list::AliasListArgs;
+use self::set::cmd_alias_set;
+use self::set::AliasSetArgs;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Manage change aliases
+///
+/// A change alias is a short, local name for a change. Unlike a bookmark, it
+/// follows the change ID rather than a commit, so it keeps pointing to the
+/// change when the change is rewritten. Aliases are never pushed.
+///
+/// Use `alias:NAME` to refer to the aliased change in a revset.
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum AliasCommand {
+    #[command(visible_alias("d"))]
+    Delete(AliasDeleteArgs),
+    #[command(visible_alias("l"))]
+    List(AliasListArgs),
+    #[command(visible_alias("s"))]
+    Set(AliasSetArgs),
+}
+
+pub fn cmd_alias(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &AliasCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        AliasCommand::Delete(args) => cmd_alias_delete(ui, command, args),
+        AliasCommand::List(args) => cmd_alias_list(ui, command, args),
+        AliasCommand::Set(args) => cmd_alias_set(ui, command, args),
+    }
+}
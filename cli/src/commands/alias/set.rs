@@ -0,0 +1,75 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::builder::NonEmptyStringValueParser;
+use clap_complete::ArgValueCandidates;
+use jj_lib::object_id::ObjectId as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Create or update a change alias to point to a certain change
+#[derive(clap::Args, Clone, Debug)]
+pub struct AliasSetArgs {
+    /// The alias to create or update
+    #[arg(
+        value_parser = NonEmptyStringValueParser::new(),
+        add = ArgValueCandidates::new(complete::change_aliases),
+    )]
+    name: String,
+
+    /// The revision whose change the alias should point to
+    #[arg(
+        long, short,
+        visible_alias = "to",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revision: Option<RevisionArg>,
+}
+
+pub fn cmd_alias_set(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &AliasSetArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let target_commit = workspace_command
+        .resolve_single_rev(ui, args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
+    let change_id = target_commit.change_id();
+    if workspace_command.repo().view().get_change_alias(&args.name) == Some(change_id) {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    tx.repo_mut()
+        .set_change_alias(&args.name, Some(change_id.clone()));
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Alias {} now points to ", args.name)?;
+        tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(
+        ui,
+        format!(
+            "point alias {name} to change {id}",
+            name = args.name,
+            id = change_id.hex()
+        ),
+    )?;
+    Ok(())
+}
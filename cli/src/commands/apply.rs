@@ -0,0 +1,296 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Read as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::backend::FileId;
+use jj_lib::backend::Signature;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
+use jj_lib::git_backend::GitBackend;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::store::Store;
+use pollster::FutureExt as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::patch_util::apply_hunks;
+use crate::patch_util::parse_patches;
+use crate::patch_util::FilePatch;
+use crate::ui::Ui;
+
+/// Apply patches as new commits
+///
+/// Reads patches in unified diff format, or series of patch emails in mbox
+/// format such as those produced by `jj format-patch` or `git format-patch`.
+/// Each patch is committed on top of the previous one, starting from the
+/// destination revision. The author, date, and description are taken from
+/// the email headers and message.
+///
+/// If a patch doesn't apply cleanly and it records the Git blob hashes of the
+/// files it was made from, those files are looked up in the repo and the
+/// patch is applied with a 3-way merge. Changes that can't be merged are
+/// recorded as conflicts.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct ApplyArgs {
+    /// Patch files to apply (reads from stdin if none are given)
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    patches: Vec<PathBuf>,
+    /// The revision to apply the patches onto
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    destination: RevisionArg,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_apply(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ApplyArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let destination = workspace_command.resolve_single_rev(ui, &args.destination)?;
+    let mut messages = vec![];
+    if args.patches.is_empty() {
+        let mut input = vec![];
+        std::io::stdin()
+            .read_to_end(&mut input)
+            .map_err(|err| user_error_with_message("Failed to read patch from stdin", err))?;
+        messages.extend(parse_patches(&input).map_err(user_error)?);
+    } else {
+        for path in &args.patches {
+            let input = std::fs::read(path).map_err(|err| {
+                user_error_with_message(format!("Failed to read {}", path.display()), err)
+            })?;
+            let parsed = parse_patches(&input).map_err(|err| {
+                user_error_with_message(format!("Failed to parse {}", path.display()), err)
+            })?;
+            messages.extend(parsed);
+        }
+    }
+    let git_repo = workspace_command
+        .git_backend()
+        .map(GitBackend::open_git_repo)
+        .transpose()?;
+
+    let mut tx = workspace_command.start_transaction();
+    let store = tx.repo().store().clone();
+    let mut parent = destination;
+    let mut new_commits: Vec<Commit> = vec![];
+    for message in &messages {
+        let parent_tree = parent.tree()?;
+        let mut tree_builder = MergedTreeBuilder::new(parent.tree_id().clone());
+        for file in &message.files {
+            let merged = apply_file_patch(
+                &store,
+                &parent_tree,
+                &mut tree_builder,
+                file,
+                git_repo.as_ref(),
+            )?;
+            if merged {
+                let path = file.new_path.as_ref().or(file.old_path.as_ref()).unwrap();
+                let ui_path = tx
+                    .base_workspace_helper()
+                    .format_file_path(&parse_repo_path(path)?);
+                writeln!(
+                    ui.status(),
+                    "Patch for {ui_path} did not apply cleanly, falling back to 3-way merge"
+                )?;
+            }
+        }
+        let tree_id = tree_builder.write_tree(&store)?;
+        let mut commit_builder = tx
+            .repo_mut()
+            .new_commit(command.settings(), vec![parent.id().clone()], tree_id)
+            .set_description(&message.description);
+        if message.author.is_some() || message.date.is_some() {
+            let default_author = commit_builder.author();
+            let (name, email) = message
+                .author
+                .clone()
+                .unwrap_or_else(|| (default_author.name.clone(), default_author.email.clone()));
+            let timestamp = message.date.unwrap_or(default_author.timestamp);
+            commit_builder = commit_builder.set_author(Signature {
+                name,
+                email,
+                timestamp,
+            });
+        }
+        parent = commit_builder.write()?;
+        new_commits.push(parent.clone());
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(formatter, "Applied {} patches:", new_commits.len())?;
+        for commit in &new_commits {
+            write!(formatter, "  ")?;
+            tx.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    tx.finish(ui, format!("apply {} patches", new_commits.len()))?;
+    Ok(())
+}
+
+/// Applies the changes to a single file. Returns true if the patch had to be
+/// applied with a 3-way merge.
+fn apply_file_patch(
+    store: &Store,
+    tree: &MergedTree,
+    tree_builder: &mut MergedTreeBuilder,
+    file: &FilePatch,
+    git_repo: Option<&git2::Repository>,
+) -> Result<bool, CommandError> {
+    let display_path = file.new_path.as_ref().or(file.old_path.as_ref()).unwrap();
+    let does_not_apply = || user_error(format!("Patch does not apply to {display_path}"));
+    let old_repo_path = file.old_path.as_deref().map(parse_repo_path).transpose()?;
+    let new_repo_path = file.new_path.as_deref().map(parse_repo_path).transpose()?;
+
+    let (old_id, old_executable) = match &old_repo_path {
+        Some(path) => {
+            let value = tree.path_value(path)?;
+            match value.as_resolved() {
+                Some(Some(TreeValue::File { id, executable })) => (Some(id.clone()), *executable),
+                Some(None) => return Err(user_error(format!("{display_path} does not exist"))),
+                _ => {
+                    return Err(user_error(format!(
+                        "{display_path} is not a regular file without conflicts"
+                    )))
+                }
+            }
+        }
+        None => {
+            let path = new_repo_path.as_ref().unwrap();
+            if tree.path_value(path)?.is_present() {
+                return Err(user_error(format!("{display_path} already exists")));
+            }
+            (None, false)
+        }
+    };
+    let parse_mode = |mode: Option<&str>| match mode {
+        None => Ok(None),
+        Some("100644") => Ok(Some(false)),
+        Some("100755") => Ok(Some(true)),
+        Some(mode) => Err(user_error(format!(
+            "Unsupported file mode {mode} for {display_path}"
+        ))),
+    };
+    // The modes recorded in the patch may be out of date, so only a mode
+    // change made by the patch is applied to the current file.
+    let base_executable = parse_mode(file.old_mode.as_deref())?.unwrap_or(old_executable);
+    let patched_executable = parse_mode(file.new_mode.as_deref())?.unwrap_or(base_executable);
+    let executable = if patched_executable != base_executable {
+        patched_executable
+    } else {
+        old_executable
+    };
+    let old_content = match (&old_repo_path, &old_id) {
+        (Some(path), Some(id)) => read_file(store, path, id)?,
+        _ => vec![],
+    };
+
+    let Some(new_repo_path) = new_repo_path else {
+        // Deleted files must match the old contents exactly.
+        if !matches!(apply_hunks(&old_content, &file.hunks), Some(content) if content.is_empty()) {
+            return Err(does_not_apply());
+        }
+        tree_builder.set_or_remove(old_repo_path.unwrap(), Merge::absent());
+        return Ok(false);
+    };
+    let mut merged = false;
+    let new_value = match apply_hunks(&old_content, &file.hunks) {
+        Some(new_content) => {
+            let id = store
+                .write_file(&new_repo_path, &mut new_content.as_slice())
+                .block_on()?;
+            Merge::normal(TreeValue::File { id, executable })
+        }
+        None => {
+            // Apply the patch to the original file, and merge the result into
+            // the current file.
+            let (Some(old_path), Some(old_id), Some(base_id)) = (
+                &old_repo_path,
+                &old_id,
+                resolve_blob(git_repo, file.old_blob.as_deref()),
+            ) else {
+                return Err(does_not_apply());
+            };
+            let base_content = read_file(store, old_path, &base_id)?;
+            let new_content = apply_hunks(&base_content, &file.hunks).ok_or_else(does_not_apply)?;
+            let new_id = store
+                .write_file(&new_repo_path, &mut new_content.as_slice())
+                .block_on()?;
+            merged = true;
+            Merge::from_vec(vec![
+                Some(TreeValue::File {
+                    id: old_id.clone(),
+                    executable: old_executable,
+                }),
+                Some(TreeValue::File {
+                    id: base_id,
+                    executable: base_executable,
+                }),
+                Some(TreeValue::File {
+                    id: new_id,
+                    executable: patched_executable,
+                }),
+            ])
+        }
+    };
+
+    if let Some(old_path) = old_repo_path.filter(|path| *path != new_repo_path) {
+        if !file.is_copy {
+            tree_builder.set_or_remove(old_path, Merge::absent());
+        }
+    }
+    tree_builder.set_or_remove(new_repo_path, new_value);
+    Ok(merged)
+}
+
+fn parse_repo_path(path: &str) -> Result<RepoPathBuf, CommandError> {
+    RepoPathBuf::from_relative_path(Path::new(path))
+        .map_err(|err| user_error_with_message(format!("Invalid path in patch: {path}"), err))
+}
+
+fn read_file(store: &Store, path: &RepoPathBuf, id: &FileId) -> Result<Vec<u8>, CommandError> {
+    let mut content = vec![];
+    store.read_file(path, id)?.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// Looks up the blob for the abbreviated hash recorded in the patch.
+fn resolve_blob(git_repo: Option<&git2::Repository>, hash: Option<&str>) -> Option<FileId> {
+    let object = git_repo?
+        .find_object_by_prefix(hash?, Some(git2::ObjectType::Blob))
+        .ok()?;
+    Some(FileId::from_bytes(object.id().as_bytes()))
+}
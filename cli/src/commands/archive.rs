@@ -0,0 +1,153 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::io::Write;
+
+use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tracing::instrument;
+
+use crate::archive::export_tree_entries;
+use crate::archive::TarWriter;
+use crate::archive::ZipWriter;
+use crate::cli_util::print_unmatched_explicit_paths;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Create an archive of the files in a revision
+///
+/// All entries get the committer timestamp of the revision as their
+/// modification time, so archiving the same revision twice produces identical
+/// archives.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct ArchiveArgs {
+    /// The revision to archive
+    #[arg(
+        long, short,
+        default_value = "@",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revision: RevisionArg,
+    /// File to write the archive to, or `-` for stdout
+    #[arg(long, short, value_hint = clap::ValueHint::FilePath)]
+    output: String,
+    /// Format of the archive
+    ///
+    /// Defaults to the format matching the extension of the output file.
+    #[arg(long, value_enum)]
+    format: Option<ArchiveFormat>,
+    /// Prefix to prepend to the paths in the archive, such as `proj-1.0/`
+    #[arg(long, default_value = "")]
+    prefix: String,
+    /// Only archive these paths
+    #[arg(
+        value_hint = clap::ValueHint::AnyPath,
+        add = ArgValueCompleter::new(complete::all_revision_files),
+    )]
+    paths: Vec<String>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum ArchiveFormat {
+    Tar,
+    #[value(name = "tar.gz", alias = "tgz")]
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    fn from_file_name(name: &str) -> Option<Self> {
+        if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_archive(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ArchiveArgs,
+) -> Result<(), CommandError> {
+    let format = match args.format {
+        Some(format) => format,
+        None => ArchiveFormat::from_file_name(&args.output).ok_or_else(|| {
+            user_error(format!(
+                "Cannot infer the archive format of {}; use --format",
+                args.output
+            ))
+        })?,
+    };
+    let workspace_command = command.workspace_helper(ui)?;
+    let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let tree = commit.tree()?;
+    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let matcher = fileset_expression.to_matcher();
+    let timestamp = &commit.committer().timestamp.timestamp;
+    let mtime = u64::try_from(timestamp.0.div_euclid(1000)).unwrap_or(0);
+
+    let writer: Box<dyn Write + '_> = if args.output == "-" {
+        Box::new(ui.stdout())
+    } else {
+        let file = File::create(&args.output).map_err(|err| {
+            user_error_with_message(format!("Failed to create {}", args.output), err)
+        })?;
+        Box::new(file)
+    };
+    let entries = tree.entries_matching(matcher.as_ref());
+    let archive_path = |path: &jj_lib::repo_path::RepoPath| {
+        format!("{}{}", args.prefix, path.as_internal_file_string())
+    };
+    match format {
+        ArchiveFormat::Tar => {
+            let mut tar = TarWriter::new(writer, mtime);
+            export_tree_entries(ui, &workspace_command, entries, |path, entry| {
+                tar.append(&archive_path(path), entry)
+            })?;
+            tar.finish()?;
+        }
+        ArchiveFormat::TarGz => {
+            // The gzip header has no timestamp, so the output is reproducible.
+            let gz = GzEncoder::new(writer, Compression::default());
+            let mut tar = TarWriter::new(gz, mtime);
+            export_tree_entries(ui, &workspace_command, entries, |path, entry| {
+                tar.append(&archive_path(path), entry)
+            })?;
+            tar.finish()?.finish()?;
+        }
+        ArchiveFormat::Zip => {
+            let mut zip = ZipWriter::new(writer, mtime);
+            export_tree_entries(ui, &workspace_command, entries, |path, entry| {
+                zip.append(&archive_path(path), entry)
+            })?;
+            zip.finish()?;
+        }
+    }
+    print_unmatched_explicit_paths(ui, &workspace_command, &fileset_expression, [&tree])?;
+    Ok(())
+}
@@ -18,6 +18,7 @@ use jj_lib::op_store::RefTarget;
 use jj_lib::str_util::StringPattern;
 
 use super::find_local_bookmarks;
+use super::ProtectedBookmarks;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -38,6 +39,10 @@ pub struct BookmarkDeleteArgs {
         add = ArgValueCandidates::new(complete::local_bookmarks),
     )]
     names: Vec<StringPattern>,
+
+    /// Allow changing bookmarks protected by `bookmarks.protected`
+    #[arg(long)]
+    allow_protected: bool,
 }
 
 pub fn cmd_bookmark_delete(
@@ -48,6 +53,12 @@ pub fn cmd_bookmark_delete(
     let mut workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo().clone();
     let matched_bookmarks = find_local_bookmarks(repo.view(), &args.names)?;
+    if !args.allow_protected {
+        ProtectedBookmarks::from_settings(command.settings())?
+            .check(matched_bookmarks.iter().map(|(name, _)| *name), |name| {
+                format!("Refusing to delete protected bookmark: {name}")
+            })?;
+    }
     let mut tx = workspace_command.start_transaction();
     for (name, _) in &matched_bookmarks {
         tx.repo_mut()
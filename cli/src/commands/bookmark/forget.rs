@@ -21,6 +21,7 @@ use jj_lib::str_util::StringPattern;
 use jj_lib::view::View;
 
 use super::find_bookmarks_with;
+use super::ProtectedBookmarks;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -44,6 +45,10 @@ pub struct BookmarkForgetArgs {
         add = ArgValueCandidates::new(complete::bookmarks),
     )]
     names: Vec<StringPattern>,
+
+    /// Allow changing bookmarks protected by `bookmarks.protected`
+    #[arg(long)]
+    allow_protected: bool,
 }
 
 pub fn cmd_bookmark_forget(
@@ -54,6 +59,12 @@ pub fn cmd_bookmark_forget(
     let mut workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo().clone();
     let matched_bookmarks = find_forgettable_bookmarks(repo.view(), &args.names)?;
+    if !args.allow_protected {
+        ProtectedBookmarks::from_settings(command.settings())?
+            .check(matched_bookmarks.iter().map(|(name, _)| *name), |name| {
+                format!("Refusing to forget protected bookmark: {name}")
+            })?;
+    }
     let mut tx = workspace_command.start_transaction();
     for (name, bookmark_target) in &matched_bookmarks {
         tx.repo_mut()
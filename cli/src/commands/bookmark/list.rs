@@ -92,7 +92,7 @@ pub struct BookmarkListArgs {
     /// All 0-argument methods of the `RefName` type are available as keywords.
     ///
     /// For the syntax, see https://martinvonz.github.io/jj/latest/templates/
-    #[arg(long, short = 'T')]
+    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::ref_name_templates))]
     template: Option<String>,
 }
 
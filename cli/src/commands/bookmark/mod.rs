@@ -24,10 +24,12 @@ mod untrack;
 
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::repo::Repo;
+use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
 use jj_lib::view::View;
 
@@ -52,7 +54,9 @@ use self::untrack::BookmarkUntrackArgs;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RemoteBookmarkName;
 use crate::cli_util::RemoteBookmarkNamePattern;
+use crate::command_error::config_error_with_message;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -202,3 +206,50 @@ fn is_fast_forward(repo: &dyn Repo, old_target: &RefTarget, new_target_id: &Comm
         true
     }
 }
+
+/// Bookmarks matching the `bookmarks.protected` patterns.
+///
+/// Protected bookmarks can't be moved backwards or sideways, deleted, or
+/// force-pushed unless `--allow-protected` is given.
+pub(crate) struct ProtectedBookmarks {
+    patterns: Vec<StringPattern>,
+}
+
+impl ProtectedBookmarks {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, CommandError> {
+        let patterns = settings
+            .get::<Vec<String>>("bookmarks.protected")
+            .optional()?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| {
+                StringPattern::parse(&s).map_err(|err| {
+                    config_error_with_message(
+                        format!("Error parsing '{s}' for bookmarks.protected"),
+                        err,
+                    )
+                })
+            })
+            .try_collect()?;
+        Ok(ProtectedBookmarks { patterns })
+    }
+
+    pub fn is_protected(&self, name: &str) -> bool {
+        self.patterns.iter().any(|pattern| pattern.matches(name))
+    }
+
+    /// Fails with `message` for the first protected bookmark in `names`.
+    pub fn check<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a str>,
+        message: impl Fn(&str) -> String,
+    ) -> Result<(), CommandError> {
+        match names.into_iter().find(|name| self.is_protected(name)) {
+            Some(name) => Err(user_error_with_hint(
+                message(name),
+                "Use --allow-protected to allow it.",
+            )),
+            None => Ok(()),
+        }
+    }
+}
@@ -20,6 +20,8 @@ use jj_lib::str_util::StringPattern;
 
 use super::find_bookmarks_with;
 use super::is_fast_forward;
+use super::ProtectedBookmarks;
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::user_error_with_hint;
@@ -36,9 +38,16 @@ use crate::ui::Ui;
 /// specified revisions will be updated. The bookmarks can also be filtered by
 /// names.
 ///
+/// All bookmarks are moved in a single operation. If more than one bookmark is
+/// moved, their old and new targets are listed.
+///
 /// Example: pull up the nearest bookmarks to the working-copy parent
 ///
 /// $ jj bookmark move --from 'heads(::@- & bookmarks())' --to @-
+///
+/// Example: move all release bookmarks to the working-copy parent
+///
+/// $ jj bookmark move 'glob:release/*' --to @-
 #[derive(clap::Args, Clone, Debug)]
 #[command(group(clap::ArgGroup::new("source").multiple(true).required(true)))]
 pub struct BookmarkMoveArgs {
@@ -69,6 +78,10 @@ pub struct BookmarkMoveArgs {
     #[arg(long, short = 'B')]
     allow_backwards: bool,
 
+    /// Allow changing bookmarks protected by `bookmarks.protected`
+    #[arg(long)]
+    allow_protected: bool,
+
     /// Move bookmarks matching the given name patterns
     ///
     /// By default, the specified name matches exactly. Use `glob:` prefix to
@@ -150,6 +163,18 @@ pub fn cmd_bookmark_move(
         }
     }
 
+    if !args.allow_protected {
+        ProtectedBookmarks::from_settings(command.settings())?.check(
+            matched_bookmarks
+                .iter()
+                .filter(|(_, old_target)| {
+                    !is_fast_forward(repo.as_ref(), old_target, target_commit.id())
+                })
+                .map(|(name, _)| *name),
+            |name| format!("Refusing to move protected bookmark backwards or sideways: {name}"),
+        )?;
+    }
+
     let mut tx = workspace_command.start_transaction();
     for (name, _) in &matched_bookmarks {
         tx.repo_mut()
@@ -160,6 +185,16 @@ pub fn cmd_bookmark_move(
         write!(formatter, "Moved {} bookmarks to ", matched_bookmarks.len())?;
         tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
         writeln!(formatter)?;
+        if matched_bookmarks.len() > 1 {
+            for (name, old_target) in &matched_bookmarks {
+                let old_ids = old_target.added_ids().map(short_commit_hash).join(", ");
+                writeln!(
+                    formatter,
+                    "  {name}: {old_ids} -> {new_id}",
+                    new_id = short_commit_hash(target_commit.id())
+                )?;
+            }
+        }
     }
     if matched_bookmarks.len() > 1 && args.names.is_empty() {
         writeln!(
@@ -12,19 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
+
 use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::git;
+use jj_lib::git::GitBranchPushTargets;
 use jj_lib::op_store::RefTarget;
+use jj_lib::refs::LocalAndRemoteRef;
+use jj_lib::repo::Repo as _;
 
 use super::has_tracked_remote_bookmarks;
+use super::ProtectedBookmarks;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
+use crate::commands::git::push::classify_bookmark_update;
+use crate::commands::git::push::get_default_push_remote;
+use crate::commands::git::push::map_push_error;
+use crate::commands::git::push::print_commits_ready_to_push;
+use crate::commands::git::push::validate_commits_ready_to_push;
 use crate::complete;
+use crate::git_util::get_git_repo;
+use crate::git_util::with_remote_git_callbacks;
+use crate::git_util::GitSidebandProgressMessageWriter;
 use crate::ui::Ui;
 
 /// Rename `old` bookmark name to `new` bookmark name
 ///
 /// The new bookmark name points at the same commit as the old bookmark name.
+///
+/// With `--push`, the bookmark is also renamed on a Git remote by pushing the
+/// new bookmark and deleting the old one there, in the same operation as the
+/// local rename. The same [safety checks] as in `jj git push` apply.
+///
+/// [safety checks]:
+///     https://martinvonz.github.io/jj/latest/bookmarks/#pushing-bookmarks-safety-checks
 #[derive(clap::Args, Clone, Debug)]
 pub struct BookmarkRenameArgs {
     /// The old name of the bookmark
@@ -33,6 +58,25 @@ pub struct BookmarkRenameArgs {
 
     /// The new name of the bookmark
     new: String,
+
+    /// Also rename the bookmark on the remote
+    #[arg(long)]
+    push: bool,
+
+    /// The remote to push to
+    ///
+    /// This defaults to the `git.push` setting, like in `jj git push`.
+    #[arg(long, requires = "push", add = ArgValueCandidates::new(complete::git_remotes))]
+    remote: Option<String>,
+
+    /// Only display what will change on the remote, without renaming the
+    /// bookmark
+    #[arg(long, requires = "push")]
+    dry_run: bool,
+
+    /// Allow changing bookmarks protected by `bookmarks.protected`
+    #[arg(long)]
+    allow_protected: bool,
 }
 
 pub fn cmd_bookmark_rename(
@@ -48,6 +92,13 @@ pub fn cmd_bookmark_rename(
         return Err(user_error(format!("No such bookmark: {old_bookmark}")));
     }
 
+    if !args.allow_protected {
+        ProtectedBookmarks::from_settings(command.settings())?
+            .check([old_bookmark.as_str()], |name| {
+                format!("Refusing to rename protected bookmark: {name}")
+            })?;
+    }
+
     let new_bookmark = &args.new;
     if view.get_local_bookmark(new_bookmark).is_present() {
         return Err(user_error(format!(
@@ -60,6 +111,18 @@ pub fn cmd_bookmark_rename(
         .set_local_bookmark_target(new_bookmark, ref_target);
     tx.repo_mut()
         .set_local_bookmark_target(old_bookmark, RefTarget::absent());
+    if args.push {
+        let Some(remote) = push_renamed_bookmark(ui, command, &mut tx, args)? else {
+            return Ok(());
+        };
+        tx.finish(
+            ui,
+            format!(
+                "rename bookmark {old_bookmark} to {new_bookmark} and push to git remote {remote}"
+            ),
+        )?;
+        return Ok(());
+    }
     tx.finish(
         ui,
         format!("rename bookmark {old_bookmark} to {new_bookmark}"),
@@ -94,3 +157,87 @@ pub fn cmd_bookmark_rename(
 
     Ok(())
 }
+
+/// Pushes the renamed bookmark and deletes the old bookmark on the remote.
+/// Returns the remote name, or `None` if nothing was pushed because of
+/// `--dry-run`.
+fn push_renamed_bookmark(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    tx: &mut WorkspaceCommandTransaction,
+    args: &BookmarkRenameArgs,
+) -> Result<Option<String>, CommandError> {
+    let git_repo = get_git_repo(tx.repo().store())?;
+    let remote = match &args.remote {
+        Some(remote) => remote.clone(),
+        None => get_default_push_remote(ui, command.settings(), &git_repo)?,
+    };
+    let view = tx.repo().view();
+    if view.get_remote_bookmark(&args.new, &remote).is_present() {
+        return Err(user_error_with_hint(
+            format!("Remote bookmark {}@{remote} already exists", args.new),
+            "Rename the bookmark without --push, or choose a different name.",
+        ));
+    }
+    let mut bookmark_updates = vec![];
+    for name in [&args.old, &args.new] {
+        let targets = LocalAndRemoteRef {
+            local_target: view.get_local_bookmark(name),
+            remote_ref: view.get_remote_bookmark(name, &remote),
+        };
+        let allow_new = true; // the renamed bookmark is new on the remote
+        if let Some(update) = classify_bookmark_update(name, &remote, targets, allow_new)? {
+            bookmark_updates.push((name.clone(), update));
+        }
+    }
+    let new_heads = bookmark_updates
+        .iter()
+        .filter_map(|(_, update)| update.new_target.clone())
+        .collect_vec();
+    validate_commits_ready_to_push(
+        ui,
+        new_heads,
+        &remote,
+        tx.base_workspace_helper(),
+        command,
+        false,
+        false,
+    )?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(formatter, "Changes to push to {remote}:")?;
+        print_commits_ready_to_push(formatter.as_mut(), tx.repo(), &bookmark_updates)?;
+    }
+    if args.dry_run {
+        writeln!(ui.status(), "Dry-run requested, not renaming or pushing.")?;
+        return Ok(None);
+    }
+
+    let targets = GitBranchPushTargets {
+        branch_updates: bookmark_updates,
+        tags: vec![],
+    };
+    let mut writer = GitSidebandProgressMessageWriter::new(ui);
+    let mut sideband_progress_callback = |progress_message: &[u8]| {
+        _ = writer.write(ui, progress_message);
+    };
+    let git_settings = tx.settings().git_settings();
+    with_remote_git_callbacks(
+        ui,
+        Some(&mut sideband_progress_callback),
+        &git_settings,
+        Some(&remote),
+        |cb| {
+            git::push_branches(
+                tx.repo_mut(),
+                &git_repo,
+                &remote,
+                &targets,
+                cb,
+                &git_settings,
+            )
+        },
+    )
+    .map_err(map_push_error)?;
+    writer.flush(ui)?;
+    Ok(Some(remote))
+}
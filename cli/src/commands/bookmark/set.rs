@@ -19,6 +19,7 @@ use jj_lib::op_store::RefTarget;
 
 use super::has_tracked_remote_bookmarks;
 use super::is_fast_forward;
+use super::ProtectedBookmarks;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::user_error_with_hint;
@@ -41,6 +42,10 @@ pub struct BookmarkSetArgs {
     #[arg(long, short = 'B')]
     allow_backwards: bool,
 
+    /// Allow changing bookmarks protected by `bookmarks.protected`
+    #[arg(long)]
+    allow_protected: bool,
+
     /// The bookmarks to update
     #[arg(
         required = true,
@@ -60,6 +65,7 @@ pub fn cmd_bookmark_set(
         .resolve_single_rev(ui, args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
     let repo = workspace_command.repo().as_ref();
     let bookmark_names = &args.names;
+    let protected_bookmarks = ProtectedBookmarks::from_settings(command.settings())?;
     let mut new_bookmark_count = 0;
     let mut moved_bookmark_count = 0;
     for name in bookmark_names {
@@ -77,6 +83,11 @@ pub fn cmd_bookmark_set(
                 "Use --allow-backwards to allow it.",
             ));
         }
+        if !args.allow_protected && !is_fast_forward(repo, old_target, target_commit.id()) {
+            protected_bookmarks.check([name.as_str()], |name| {
+                format!("Refusing to move protected bookmark backwards or sideways: {name}")
+            })?;
+        }
     }
 
     let mut tx = workspace_command.start_transaction();
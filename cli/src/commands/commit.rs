@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use jj_lib::backend::Signature;
 use jj_lib::object_id::ObjectId;
@@ -22,9 +23,13 @@ use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::add_gerrit_change_id_trailer;
+use crate::description_util::add_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::join_message_paragraphs;
+use crate::description_util::set_default_description;
+use crate::description_util::verify_description;
 use crate::text_util::parse_author;
 use crate::ui::Ui;
 
@@ -35,7 +40,11 @@ pub(crate) struct CommitArgs {
     #[arg(short, long)]
     interactive: bool,
     /// Specify diff editor to be used (implies --interactive)
-    #[arg(long, value_name = "NAME")]
+    #[arg(
+        long,
+        value_name = "NAME",
+        add = ArgValueCandidates::new(complete::diff_editors)
+    )]
     tool: Option<String>,
     /// The change description to use (don't open editor)
     #[arg(long = "message", short, value_name = "MESSAGE")]
@@ -46,6 +55,13 @@ pub(crate) struct CommitArgs {
         add = ArgValueCompleter::new(complete::modified_files),
     )]
     paths: Vec<String>,
+    /// Only snapshot the given paths
+    ///
+    /// Changes to other files in the working copy are not looked at, which
+    /// can be much faster in a large working copy. They will be snapshotted
+    /// into the new working-copy commit by the next command.
+    #[arg(long, requires = "paths")]
+    only: bool,
     /// Reset the author to the configured user
     ///
     /// This resets the author name, email, and timestamp.
@@ -66,6 +82,9 @@ pub(crate) struct CommitArgs {
         value_parser = parse_author
     )]
     author: Option<(String, String)>,
+    /// Don't check the description against the `commit.verify` settings
+    #[arg(long)]
+    no_verify: bool,
 }
 
 #[instrument(skip_all)]
@@ -74,15 +93,22 @@ pub(crate) fn cmd_commit(
     command: &CommandHelper,
     args: &CommitArgs,
 ) -> Result<(), CommandError> {
-    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut workspace_command = if args.only {
+        command.workspace_helper_no_snapshot(ui)?
+    } else {
+        command.workspace_helper(ui)?
+    };
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
+    if args.only {
+        workspace_command.maybe_snapshot_matching(ui, matcher.as_ref())?;
+    }
 
     let commit_id = workspace_command
         .get_wc_commit_id()
         .ok_or_else(|| user_error("This command requires a working copy"))?;
     let commit = workspace_command.repo().store().get_commit(commit_id)?;
-    let matcher = workspace_command
-        .parse_file_patterns(ui, &args.paths)?
-        .to_matcher();
     let advanceable_bookmarks = workspace_command.get_advanceable_bookmarks(commit.parent_ids())?;
     let diff_selector =
         workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?;
@@ -134,9 +160,7 @@ new working-copy commit.
     let description = if !args.message_paragraphs.is_empty() {
         join_message_paragraphs(&args.message_paragraphs)
     } else {
-        if commit_builder.description().is_empty() {
-            commit_builder.set_description(command.settings().default_description());
-        }
+        set_default_description(ui, &tx, &mut commit_builder)?;
         let temp_commit = commit_builder.write_hidden()?;
         let template = description_template(ui, &tx, "", &temp_commit)?;
         edit_description(
@@ -145,6 +169,12 @@ new working-copy commit.
             command.settings(),
         )?
     };
+    let description =
+        add_gerrit_change_id_trailer(command.settings(), description, commit.change_id())?;
+    let description = add_trailers(ui, &tx, &commit, description)?;
+    if !args.no_verify {
+        verify_description(command.settings(), &description)?;
+    }
     commit_builder.set_description(description);
     let new_commit = commit_builder.write(tx.repo_mut())?;
 
@@ -12,27 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::file_util::IoResultExt as _;
 use tracing::instrument;
 
 use super::ConfigLevelArgs;
 use crate::cli_util::run_ui_editor;
 use crate::cli_util::CommandHelper;
+use crate::command_error::print_recoverable_error;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
+use crate::config_schema::check_config_table;
 use crate::ui::Ui;
 
 /// Start an editor on a jj config file.
 ///
 /// Creates the file if it doesn't already exist regardless of what the editor
-/// does.
+/// does. If the edited file can't be parsed, or has values of the wrong type,
+/// the previous contents are restored, and the edited contents are saved next
+/// to the config file with a `.rejected` suffix.
 #[derive(clap::Args, Clone, Debug)]
 pub struct ConfigEditArgs {
     #[command(flatten)]
     pub level: ConfigLevelArgs,
+    /// If the edited file is invalid, show the error and offer to re-open the
+    /// editor instead of restoring the previous contents right away
+    #[arg(long)]
+    pub validate_on_save: bool,
 }
 
 #[instrument(skip_all)]
 pub fn cmd_config_edit(
-    _ui: &mut Ui,
+    ui: &mut Ui,
     command: &CommandHelper,
     args: &ConfigEditArgs,
 ) -> Result<(), CommandError> {
@@ -40,5 +57,52 @@ pub fn cmd_config_edit(
     if !file.path().exists() {
         file.save()?;
     }
-    run_ui_editor(command.settings(), file.path())
+    loop {
+        run_ui_editor(command.settings(), file.path())?;
+        let Err(mut err) = check_config_file(file.path()) else {
+            return Ok(());
+        };
+        if args.validate_on_save && Ui::can_prompt() {
+            print_recoverable_error(ui, &err)?;
+            if ui.prompt_yes_no("Re-open the editor to fix the config file?", Some(true))? {
+                continue;
+            }
+            // The details have already been printed.
+            err = user_error("The edited config file is invalid");
+        }
+        // Keep the edits around so the user can fix them up later.
+        let rejected_path = rejected_file_path(file.path());
+        match fs::copy(file.path(), &rejected_path) {
+            Ok(_) => {
+                file.save()?;
+                err.add_hint("The config file was restored to its previous contents.");
+                err.add_hint(format!(
+                    "The edited contents were saved to {}",
+                    rejected_path.display()
+                ));
+            }
+            Err(copy_err) => {
+                err.add_hint(format!(
+                    "The edited config file was left as is, since the edited contents couldn't \
+                     be saved to {}: {copy_err}",
+                    rejected_path.display()
+                ));
+            }
+        }
+        return Err(err);
+    }
+}
+
+fn rejected_file_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_owned();
+    file_name.push(".rejected");
+    path.with_file_name(file_name)
+}
+
+fn check_config_file(path: &Path) -> Result<(), CommandError> {
+    let text = fs::read_to_string(path).context(path)?;
+    let layer = ConfigLayer::parse(ConfigSource::User, &text)
+        .map_err(|err| user_error_with_message("Failed to parse the edited config file", err))?;
+    check_config_table(&layer.data)?;
+    Ok(())
 }
@@ -12,17 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
+
 use clap_complete::ArgValueCandidates;
+use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use tracing::instrument;
 
 use super::ConfigLevelArgs;
 use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::config::resolved_config_values;
 use crate::config::AnnotatedValue;
+use crate::config_schema::value_to_json;
 use crate::generic_templater::GenericTemplateLanguage;
 use crate::template_builder::TemplateLanguage as _;
 use crate::templater::TemplatePropertyExt as _;
@@ -43,7 +50,15 @@ pub struct ConfigListArgs {
     pub include_overridden: bool,
     #[command(flatten)]
     pub level: ConfigLevelArgs,
-    // TODO(#1047): Support --show-origin using StackedConfig.
+    /// How to print the variables
+    #[arg(long, value_enum, default_value_t = ConfigListFormat::Text)]
+    format: ConfigListFormat,
+    /// Annotate each variable with the source and file it came from
+    ///
+    /// In the `text` and `toml` formats, the origin is added as a trailing
+    /// comment. The `json` format always includes it.
+    #[arg(long, conflicts_with = "template")]
+    show_origin: bool,
     /// Render each variable using the given template
     ///
     /// The following keywords are defined:
@@ -51,55 +66,111 @@ pub struct ConfigListArgs {
     /// * `name: String`: Config name.
     /// * `value: String`: Serialized value in TOML syntax.
     /// * `overridden: Boolean`: True if the value is shadowed by other.
+    /// * `source: String`: Source of the value: "default", "env", "user",
+    ///   "repo", or "cli".
+    /// * `path: String`: Path to the config file, or empty if the value didn't
+    ///   come from a file.
     ///
     /// For the syntax, see https://martinvonz.github.io/jj/latest/templates/
     #[arg(long, short = 'T', verbatim_doc_comment)]
     template: Option<String>,
 }
 
+/// Output format of `jj config list`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum ConfigListFormat {
+    /// Render each variable using the template
+    Text,
+    /// A TOML document containing the variables
+    Toml,
+    /// A JSON array with the name, value, and origin of each variable
+    Json,
+}
+
 #[instrument(skip_all)]
 pub fn cmd_config_list(
     ui: &mut Ui,
     command: &CommandHelper,
     args: &ConfigListArgs,
 ) -> Result<(), CommandError> {
-    let template = {
-        let language = config_template_language();
-        let text = match &args.template {
-            Some(value) => value.to_owned(),
-            None => command.settings().get_string("templates.config_list")?,
-        };
-        command
-            .parse_template(ui, &language, &text, GenericTemplateLanguage::wrap_self)?
-            .labeled("config_list")
-    };
+    if args.format != ConfigListFormat::Text && args.template.is_some() {
+        return Err(user_error("--template can only be used with --format text"));
+    }
+    if args.format == ConfigListFormat::Toml && args.include_overridden {
+        return Err(user_error(
+            "--include-overridden cannot be used with --format toml",
+        ));
+    }
 
-    ui.request_pager();
-    let mut formatter = ui.stdout_formatter();
     let name_path = args.name.clone().unwrap_or_else(ConfigNamePathBuf::root);
-    let mut wrote_values = false;
-    for annotated in resolved_config_values(command.settings().config(), &name_path) {
+    let values = resolved_config_values(command.settings().config(), &name_path)
+        .into_iter()
         // Remove overridden values.
-        if annotated.is_overridden && !args.include_overridden {
-            continue;
-        }
+        .filter(|annotated| !annotated.is_overridden || args.include_overridden)
+        .filter(|annotated| {
+            args.level
+                .get_source_kind()
+                .map_or(true, |target_source| target_source == annotated.source)
+        })
+        // Skip built-ins if not included.
+        .filter(|annotated| args.include_defaults || annotated.source != ConfigSource::Default)
+        .collect::<Vec<_>>();
 
-        if let Some(target_source) = args.level.get_source_kind() {
-            if target_source != annotated.source {
-                continue;
+    ui.request_pager();
+    match args.format {
+        ConfigListFormat::Text => {
+            let template = {
+                let language = config_template_language();
+                let text = match &args.template {
+                    Some(value) => value.to_owned(),
+                    None if args.show_origin => "builtin_config_list_origin".to_owned(),
+                    None => command.settings().get_string("templates.config_list")?,
+                };
+                command
+                    .parse_template(ui, &language, &text, GenericTemplateLanguage::wrap_self)?
+                    .labeled("config_list")
+            };
+            let mut formatter = ui.stdout_formatter();
+            for annotated in &values {
+                template.format(annotated, formatter.as_mut())?;
             }
         }
-
-        // Skip built-ins if not included.
-        if !args.include_defaults && annotated.source == ConfigSource::Default {
-            continue;
+        ConfigListFormat::Toml => {
+            let mut layer = ConfigLayer::empty(ConfigSource::Default);
+            for annotated in &values {
+                let mut value = annotated.value.clone();
+                value.decor_mut().clear();
+                if args.show_origin {
+                    value
+                        .decor_mut()
+                        .set_suffix(format!(" # {}", format_origin(annotated)));
+                }
+                layer.set_value(&annotated.name, value).map_err(|err| {
+                    user_error_with_message(format!("Failed to list {}", annotated.name), err)
+                })?;
+            }
+            write!(ui.stdout(), "{}", layer.data)?;
+        }
+        ConfigListFormat::Json => {
+            let entries: Vec<_> = values
+                .iter()
+                .map(|annotated| {
+                    serde_json::json!({
+                        "name": annotated.name.to_string(),
+                        "value": value_to_json(&annotated.value),
+                        "source": annotated.source.to_string(),
+                        "path": annotated.path,
+                        "overridden": annotated.is_overridden,
+                    })
+                })
+                .collect();
+            let mut stdout = ui.stdout();
+            serde_json::to_writer_pretty(&mut stdout, &entries).map_err(internal_error)?;
+            writeln!(stdout)?;
         }
-
-        template.format(&annotated, formatter.as_mut())?;
-        wrote_values = true;
     }
-    drop(formatter);
-    if !wrote_values {
+
+    if values.is_empty() {
         // Note to stderr explaining why output is empty.
         if let Some(name) = &args.name {
             writeln!(ui.warning_default(), "No matching config key for {name}")?;
@@ -110,6 +181,14 @@ pub fn cmd_config_list(
     Ok(())
 }
 
+/// Describes where the value came from, e.g. "user: /path/to/config.toml".
+fn format_origin(annotated: &AnnotatedValue) -> String {
+    match &annotated.path {
+        Some(path) => format!("{}: {}", annotated.source, path.display()),
+        None => annotated.source.to_string(),
+    }
+}
+
 // AnnotatedValue will be cloned internally in the templater. If the cloning
 // cost matters, wrap it with Rc.
 fn config_template_language() -> GenericTemplateLanguage<'static, AnnotatedValue> {
@@ -130,5 +209,18 @@ fn config_template_language() -> GenericTemplateLanguage<'static, AnnotatedValue
         let out_property = self_property.map(|annotated| annotated.is_overridden);
         Ok(L::wrap_boolean(out_property))
     });
+    language.add_keyword("source", |self_property| {
+        let out_property = self_property.map(|annotated| annotated.source.to_string());
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("path", |self_property| {
+        let out_property = self_property.map(|annotated| {
+            annotated
+                .path
+                .as_ref()
+                .map_or_else(String::new, |path| path.to_string_lossy().into_owned())
+        });
+        Ok(L::wrap_string(out_property))
+    });
     language
 }
@@ -28,6 +28,7 @@ use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::config::parse_value_or_bare_string;
+use crate::config_schema::check_config_value;
 use crate::ui::Ui;
 
 /// Update config file to set the given option to a given value.
@@ -39,8 +40,15 @@ pub struct ConfigSetArgs {
     ///
     /// The value should be specified as a TOML expression. If string value
     /// doesn't contain any TOML constructs (such as array notation), quotes can
-    /// be omitted.
-    #[arg(required = true, value_parser = parse_value_or_bare_string)]
+    /// be omitted. Arrays and tables can be specified in TOML inline notation,
+    /// e.g. `['a', 'b']` or `{ fg = 'red' }`.
+    ///
+    /// The value is checked against the type declared for the config variable.
+    #[arg(
+        required = true,
+        value_parser = parse_value_or_bare_string,
+        add = ArgValueCandidates::new(complete::config_values),
+    )]
     value: ConfigValue,
     #[command(flatten)]
     level: ConfigLevelArgs,
@@ -58,6 +66,7 @@ pub fn cmd_config_set(
     command: &CommandHelper,
     args: &ConfigSetArgs,
 ) -> Result<(), CommandError> {
+    check_config_value(&args.name, &args.value)?;
     let mut file = args.level.edit_config_file(command)?;
 
     // If the user is trying to change the author config, we should warn them that
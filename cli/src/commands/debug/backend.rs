@@ -0,0 +1,192 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io;
+use std::io::Write as _;
+
+use jj_lib::default_index::DefaultIndexStore;
+use jj_lib::default_index::INDEX_SEGMENT_FILE_FORMAT_VERSION;
+use jj_lib::file_util::check_symlink_support;
+use jj_lib::git_backend::GitBackend;
+use jj_lib::local_backend::LocalBackend;
+use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::store::Store;
+use jj_lib::working_copy::WorkingCopy;
+use serde::Serialize;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Show the backends used by the repo and their capabilities
+///
+/// This only reads the repo, so it also works if one of the backends is
+/// misbehaving.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugBackendArgs {
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct BackendReport {
+    commit_store: CommitStoreInfo,
+    op_store: BackendInfo,
+    op_heads_store: BackendInfo,
+    index_store: BackendInfo,
+    working_copy: BackendInfo,
+    submodule_store: BackendInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct CommitStoreInfo {
+    #[serde(flatten)]
+    info: BackendInfo,
+    commit_id_length: usize,
+    change_id_length: usize,
+    concurrency: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct BackendInfo {
+    name: String,
+    /// Version of the on-disk format, if the backend has one.
+    format_version: Option<u32>,
+    /// Known capabilities. Empty for backends this command doesn't know about.
+    capabilities: Vec<&'static str>,
+}
+
+impl BackendInfo {
+    fn new(name: &str) -> Self {
+        BackendInfo {
+            name: name.to_owned(),
+            format_version: None,
+            capabilities: vec![],
+        }
+    }
+}
+
+pub fn cmd_debug_backend(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugBackendArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo, so this command won't update the index or snapshot
+    // the working copy.
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let store = repo_loader.store();
+    let index_store = repo_loader.index_store();
+    let report = BackendReport {
+        commit_store: CommitStoreInfo {
+            info: BackendInfo {
+                capabilities: commit_store_capabilities(store),
+                ..BackendInfo::new(store.backend_name())
+            },
+            commit_id_length: store.commit_id_length(),
+            change_id_length: store.change_id_length(),
+            concurrency: store.concurrency(),
+        },
+        op_store: BackendInfo::new(repo_loader.op_store().name()),
+        op_heads_store: BackendInfo::new(repo_loader.op_heads_store().name()),
+        index_store: BackendInfo {
+            format_version: index_store
+                .as_any()
+                .is::<DefaultIndexStore>()
+                .then_some(INDEX_SEGMENT_FILE_FORMAT_VERSION),
+            ..BackendInfo::new(index_store.name())
+        },
+        working_copy: BackendInfo {
+            capabilities: working_copy_capabilities(workspace.working_copy()),
+            ..BackendInfo::new(workspace.working_copy().name())
+        },
+        submodule_store: BackendInfo::new(repo_loader.submodule_store().name()),
+    };
+
+    let mut stdout = ui.stdout();
+    if args.json {
+        serde_json::to_writer_pretty(&mut stdout, &report).map_err(internal_error)?;
+        writeln!(stdout)?;
+        return Ok(());
+    }
+
+    let BackendReport {
+        commit_store,
+        op_store,
+        op_heads_store,
+        index_store,
+        working_copy,
+        submodule_store,
+    } = &report;
+    write_backend_info(&mut stdout, "Commit store", &commit_store.info)?;
+    writeln!(
+        stdout,
+        "  Commit ID length: {}",
+        commit_store.commit_id_length
+    )?;
+    writeln!(
+        stdout,
+        "  Change ID length: {}",
+        commit_store.change_id_length
+    )?;
+    writeln!(stdout, "  Concurrency: {}", commit_store.concurrency)?;
+    write_backend_info(&mut stdout, "Operation store", op_store)?;
+    write_backend_info(&mut stdout, "Operation heads store", op_heads_store)?;
+    write_backend_info(&mut stdout, "Index store", index_store)?;
+    write_backend_info(&mut stdout, "Working copy", working_copy)?;
+    write_backend_info(&mut stdout, "Submodule store", submodule_store)?;
+    Ok(())
+}
+
+fn write_backend_info(out: &mut dyn io::Write, label: &str, info: &BackendInfo) -> io::Result<()> {
+    writeln!(out, "{label}: {}", info.name)?;
+    if let Some(version) = info.format_version {
+        writeln!(out, "  Format version: {version}")?;
+    }
+    if !info.capabilities.is_empty() {
+        writeln!(out, "  Capabilities: {}", info.capabilities.join(", "))?;
+    }
+    Ok(())
+}
+
+fn commit_store_capabilities(store: &Store) -> Vec<&'static str> {
+    let backend = store.backend_impl();
+    if backend.is::<GitBackend>() {
+        vec!["copy-tracking", "git-interop", "signing"]
+    } else if backend.is::<LocalBackend>() {
+        vec!["signing"]
+    } else {
+        vec![]
+    }
+}
+
+fn working_copy_capabilities(wc: &dyn WorkingCopy) -> Vec<&'static str> {
+    if !wc.as_any().is::<LocalWorkingCopy>() {
+        return vec![];
+    }
+    let mut capabilities = vec!["sparse"];
+    if cfg!(unix) {
+        capabilities.push("executable-bit");
+    }
+    if cfg!(feature = "watchman") {
+        capabilities.push("watchman");
+    }
+    if check_symlink_support().unwrap_or(false) {
+        capabilities.push("symlinks");
+    }
+    capabilities
+}
@@ -0,0 +1,139 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::graph::GraphEdgeType;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Print a shell script that recreates the commit graph of the repo
+///
+/// Commit contents and descriptions are replaced with placeholders, so the
+/// script can be attached to bug reports without sharing private code.
+/// Bookmarks and the working-copy commit are recreated. The operation log
+/// isn't: the script runs one operation per command, so the new repo doesn't
+/// reproduce bugs that depend on the operation history.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugCreateGraphFixtureArgs {
+    /// Commits to include
+    ///
+    /// Edges to commits outside of the set are reconnected to their nearest
+    /// ancestors in the set.
+    #[arg(long, short, default_value = "all()", value_name = "REVSETS")]
+    revisions: Vec<RevisionArg>,
+}
+
+pub fn cmd_debug_create_graph_fixture(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugCreateGraphFixtureArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().as_ref();
+    let root_commit_id = repo.store().root_commit_id();
+    let revset = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate()?;
+    // Parents have to be created before their children.
+    let nodes: Vec<_> = revset.iter_graph().try_collect()?;
+    let nodes = nodes
+        .into_iter()
+        .rev()
+        .filter(|(commit_id, _)| commit_id != root_commit_id)
+        .collect_vec();
+
+    let mut names: HashMap<CommitId, String> = HashMap::new();
+    let mut script = vec![];
+    for (commit_id, edges) in &nodes {
+        let parents = edges
+            .iter()
+            .filter(|edge| edge.edge_type != GraphEdgeType::Missing)
+            .map(|edge| match names.get(&edge.target) {
+                Some(name) => revset_for_name(name),
+                None => "root()".to_owned(),
+            })
+            .collect_vec();
+        let parents = if parents.is_empty() {
+            vec!["root()".to_owned()]
+        } else {
+            parents
+        };
+        let index = names.len() + 1;
+        let name = format!("commit {index}");
+        script.push(format!(
+            "jj new {} -m {}",
+            parents.iter().map(|parent| shell_quote(parent)).join(" "),
+            shell_quote(&name)
+        ));
+        let commit = repo.store().get_commit(commit_id)?;
+        if !commit.is_empty(repo)? {
+            script.push(format!("echo {index} > file{index}"));
+        }
+        names.insert(commit_id.clone(), name);
+    }
+
+    for (bookmark_name, target) in repo.view().local_bookmarks() {
+        let Some(name) = target.as_normal().and_then(|id| names.get(id)) else {
+            continue;
+        };
+        script.push(format!(
+            "jj bookmark create {} -r {}",
+            shell_quote(bookmark_name),
+            shell_quote(&revset_for_name(name))
+        ));
+    }
+    if let Some(name) = workspace_command
+        .get_wc_commit_id()
+        .and_then(|id| names.get(id))
+    {
+        script.push(format!("jj edit {}", shell_quote(&revset_for_name(name))));
+    }
+
+    let mut formatter = ui.stdout_formatter();
+    writeln!(formatter, "#!/bin/sh")?;
+    writeln!(
+        formatter,
+        "# Recreates the commit graph of a jj repo. Run in an empty directory."
+    )?;
+    writeln!(formatter, "set -e")?;
+    writeln!(formatter, "jj git init")?;
+    for line in script {
+        writeln!(formatter, "{line}")?;
+    }
+    Ok(())
+}
+
+fn revset_for_name(name: &str) -> String {
+    format!(r#"description(exact:"{name}\n")"#)
+}
+
+fn shell_quote(text: &str) -> String {
+    if text
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_./".contains(c))
+    {
+        text.to_owned()
+    } else {
+        format!("'{}'", text.replace('\'', r"'\''"))
+    }
+}
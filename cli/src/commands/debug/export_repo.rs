@@ -0,0 +1,59 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write as _;
+
+use jj_lib::repo_archive;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Export the repo to a backend-independent archive
+///
+/// The archive contains the operation log up to the current operation, and
+/// all commits, trees, and files it refers to. It can be imported with `jj
+/// debug import-repo` into a new repo, which may use a different backend.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugExportRepoArgs {
+    /// The file to write the archive to
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    output: String,
+}
+
+pub fn cmd_debug_export_repo(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugExportRepoArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let path = command.cwd().join(&args.output);
+    let file = File::create(&path).map_err(|err| {
+        user_error_with_message(format!("Failed to create {}", path.display()), err)
+    })?;
+    let mut writer = BufWriter::new(file);
+    let stats = repo_archive::export_repo(workspace_command.repo(), &mut writer)?;
+    writer.flush()?;
+    writeln!(
+        ui.status(),
+        "Exported {} operations and {} commits.",
+        stats.operation_count,
+        stats.commit_count
+    )?;
+    Ok(())
+}
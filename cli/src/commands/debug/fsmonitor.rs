@@ -0,0 +1,100 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+#[cfg(feature = "native-fsmonitor")]
+use std::io::Write as _;
+
+use clap::Subcommand;
+
+use crate::cli_util::CommandHelper;
+#[cfg(feature = "native-fsmonitor")]
+use crate::command_error::internal_error;
+#[cfg(not(feature = "native-fsmonitor"))]
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum DebugFsmonitorCommand {
+    /// Check whether the native filesystem monitor daemon is running
+    Status,
+    /// Run the native filesystem monitor daemon in the foreground
+    ///
+    /// The daemon is started in the background automatically if
+    /// `fsmonitor.backend` is set to `"native"`. It exits when the workspace is
+    /// deleted, or when no command has queried it for a few hours.
+    Daemon,
+    /// Stop the native filesystem monitor daemon
+    ///
+    /// The daemon will be started again by the next command that snapshots the
+    /// working copy if `fsmonitor.backend` is set to `"native"`.
+    Stop,
+}
+
+#[cfg(feature = "native-fsmonitor")]
+pub fn cmd_debug_fsmonitor(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &DebugFsmonitorCommand,
+) -> Result<(), CommandError> {
+    // Load the workspace without snapshotting, which would try to start the
+    // daemon.
+    let workspace = command.load_workspace()?;
+    let wc = super::check_local_disk_wc(workspace.working_copy().as_any())?;
+    let fsmonitor = wc.native_fsmonitor();
+    match subcommand {
+        DebugFsmonitorCommand::Status => {
+            if fsmonitor.is_daemon_running() {
+                writeln!(
+                    ui.stdout(),
+                    "The native filesystem monitor daemon is running."
+                )?;
+            } else {
+                writeln!(
+                    ui.stdout(),
+                    "The native filesystem monitor daemon is not running."
+                )?;
+            }
+        }
+        DebugFsmonitorCommand::Stop => {
+            if fsmonitor.stop_daemon().map_err(internal_error)? {
+                writeln!(ui.status(), "Stopped the native filesystem monitor daemon.")?;
+            } else {
+                writeln!(
+                    ui.status(),
+                    "The native filesystem monitor daemon is not running."
+                )?;
+            }
+        }
+        DebugFsmonitorCommand::Daemon => {
+            fsmonitor
+                .run_daemon(workspace.workspace_root())
+                .map_err(internal_error)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "native-fsmonitor"))]
+pub fn cmd_debug_fsmonitor(
+    _ui: &mut Ui,
+    _command: &CommandHelper,
+    _subcommand: &DebugFsmonitorCommand,
+) -> Result<(), CommandError> {
+    Err(user_error(
+        "Cannot use the native filesystem monitor because jj was not compiled with the \
+         `native-fsmonitor` feature",
+    ))
+}
@@ -0,0 +1,120 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::Write as _;
+
+use jj_lib::backend::BackendResult;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_archive;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::cli_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Import an archive written by `jj debug export-repo`
+///
+/// The operation log and commits in the archive replace the ones of the
+/// current repo, which must be empty. Since the repo may use a different
+/// backend than the exported one, the commits get new ids.
+///
+/// The Git refs of the exported repo aren't imported. Run `jj git export` to
+/// create them in a repo backed by Git.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugImportRepoArgs {
+    /// The archive file to import
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    input: String,
+}
+
+pub fn cmd_debug_import_repo(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugImportRepoArgs,
+) -> Result<(), CommandError> {
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    if !is_empty_repo(repo)? {
+        return Err(user_error_with_hint(
+            "The repo is not empty",
+            "Import the archive into a repo created with `jj git init` or `jj init`.",
+        ));
+    }
+    let path = command.cwd().join(&args.input);
+    let file = File::open(&path).map_err(|err| {
+        user_error_with_message(format!("Failed to open {}", path.display()), err)
+    })?;
+    let repo_loader = repo.loader();
+    let (mut operation, stats) = repo_archive::import_repo(repo_loader, &mut BufReader::new(file))?;
+    let workspace_id = workspace_command.workspace_id();
+    let imported_repo = repo_loader.load_at(&operation)?;
+    if imported_repo
+        .view()
+        .get_wc_commit_id(workspace_id)
+        .is_none()
+    {
+        // The workspace didn't exist in the exported repo.
+        let mut tx = imported_repo.start_transaction(command.settings());
+        tx.repo_mut().check_out(
+            workspace_id.clone(),
+            command.settings(),
+            &imported_repo.store().root_commit(),
+        )?;
+        let description = format!("add workspace '{}'", workspace_id.as_str());
+        operation = tx
+            .write(description)
+            .leave_unpublished()
+            .operation()
+            .clone();
+    }
+    repo.op_heads_store()
+        .update_op_heads(&[repo.op_id().clone()], operation.id())?;
+    writeln!(
+        ui.status(),
+        "Imported {} operations and {} commits.",
+        stats.operation_count,
+        stats.commit_count
+    )?;
+    // The working copy still refers to the replaced operation.
+    command.recover_stale_working_copy(ui)?;
+    Ok(())
+}
+
+/// Whether the repo has no commits other than empty working-copy commits.
+fn is_empty_repo(repo: &ReadonlyRepo) -> BackendResult<bool> {
+    let view = repo.view();
+    if view.bookmarks().next().is_some() || !view.tags().is_empty() {
+        return Ok(false);
+    }
+    let root_commit_id = repo.store().root_commit_id();
+    for id in view.heads() {
+        if view.wc_commit_ids().values().all(|wc_id| wc_id != id) {
+            return Ok(false);
+        }
+        let commit = repo.store().get_commit(id)?;
+        if commit.parent_ids() != [root_commit_id.clone()] || !commit.is_discardable(repo)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
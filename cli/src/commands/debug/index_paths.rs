@@ -0,0 +1,63 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use jj_lib::default_index::DefaultIndexStore;
+use jj_lib::default_index::DefaultReadonlyIndex;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Build index of the commits that changed each path
+///
+/// Once built, the index is kept up to date as new commits are added to the
+/// repo, and revsets such as `files(path)` only diff the commits that the
+/// index tells might change the path.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugIndexPathsArgs {}
+
+pub fn cmd_debug_index_paths(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &DebugIndexPathsArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let index_store = repo.index_store();
+    let (Some(default_index_store), Some(default_index)) = (
+        index_store.as_any().downcast_ref::<DefaultIndexStore>(),
+        repo.readonly_index()
+            .as_any()
+            .downcast_ref::<DefaultReadonlyIndex>(),
+    ) else {
+        return Err(user_error(format!(
+            "Cannot index paths of indexes of type '{}'",
+            index_store.name()
+        )));
+    };
+    let num_diffed = default_index_store
+        .build_changed_path_index(default_index, repo.store())
+        .map_err(internal_error)?;
+    writeln!(
+        ui.status(),
+        "Indexed changed paths of {num_diffed} commits."
+    )?;
+    Ok(())
+}
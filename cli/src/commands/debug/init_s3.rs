@@ -0,0 +1,64 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use jj_lib::file_util;
+use jj_lib::workspace::Workspace;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::cli_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Create a new repo storing commits in an S3-compatible object store
+///
+/// The objects are stored in the bucket at the given URL, such as
+/// `http://localhost:9000/bucket/prefix`, and cached in the repo once they
+/// have been read or written. The operation log and the working copy are
+/// stored locally.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugInitS3Args {
+    /// URL of the bucket, optionally followed by a key prefix
+    url: String,
+    /// The destination directory
+    #[arg(default_value = ".", value_hint = clap::ValueHint::DirPath)]
+    destination: String,
+}
+
+pub fn cmd_debug_init_s3(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugInitS3Args,
+) -> Result<(), CommandError> {
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    let cwd = command.cwd();
+    let wc_path = cwd.join(&args.destination);
+    let wc_path = file_util::create_or_reuse_dir(&wc_path)
+        .and_then(|_| wc_path.canonicalize())
+        .map_err(|e| user_error_with_message("Failed to create workspace", e))?;
+    Workspace::init_s3(command.settings(), &wc_path, &args.url)?;
+    let relative_wc_path = file_util::relative_path(cwd, &wc_path);
+    writeln!(
+        ui.status(),
+        "Initialized repo in \"{}\" storing objects at {}",
+        relative_wc_path.display(),
+        args.url
+    )?;
+    Ok(())
+}
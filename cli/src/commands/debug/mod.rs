@@ -12,9 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod backend;
 pub mod copy_detection;
+pub mod create_graph_fixture;
+pub mod export_repo;
 pub mod fileset;
+pub mod fsmonitor;
+pub mod import_repo;
 pub mod index;
+pub mod index_paths;
+#[cfg(feature = "s3")]
+pub mod init_s3;
 pub mod local_working_copy;
 pub mod operation;
 pub mod reindex;
@@ -31,12 +39,28 @@ use std::fmt::Debug;
 use clap::Subcommand;
 use jj_lib::local_working_copy::LocalWorkingCopy;
 
+use self::backend::cmd_debug_backend;
+use self::backend::DebugBackendArgs;
 use self::copy_detection::cmd_debug_copy_detection;
 use self::copy_detection::CopyDetectionArgs;
+use self::create_graph_fixture::cmd_debug_create_graph_fixture;
+use self::create_graph_fixture::DebugCreateGraphFixtureArgs;
+use self::export_repo::cmd_debug_export_repo;
+use self::export_repo::DebugExportRepoArgs;
 use self::fileset::cmd_debug_fileset;
 use self::fileset::DebugFilesetArgs;
+use self::fsmonitor::cmd_debug_fsmonitor;
+use self::fsmonitor::DebugFsmonitorCommand;
+use self::import_repo::cmd_debug_import_repo;
+use self::import_repo::DebugImportRepoArgs;
 use self::index::cmd_debug_index;
 use self::index::DebugIndexArgs;
+use self::index_paths::cmd_debug_index_paths;
+use self::index_paths::DebugIndexPathsArgs;
+#[cfg(feature = "s3")]
+use self::init_s3::cmd_debug_init_s3;
+#[cfg(feature = "s3")]
+use self::init_s3::DebugInitS3Args;
 use self::local_working_copy::cmd_debug_local_working_copy;
 use self::local_working_copy::DebugLocalWorkingCopyArgs;
 use self::operation::cmd_debug_operation;
@@ -64,9 +88,18 @@ use crate::ui::Ui;
 #[derive(Subcommand, Clone, Debug)]
 #[command(hide = true)]
 pub enum DebugCommand {
+    Backend(DebugBackendArgs),
     CopyDetection(CopyDetectionArgs),
+    CreateGraphFixture(DebugCreateGraphFixtureArgs),
+    ExportRepo(DebugExportRepoArgs),
     Fileset(DebugFilesetArgs),
+    #[command(subcommand)]
+    Fsmonitor(DebugFsmonitorCommand),
+    ImportRepo(DebugImportRepoArgs),
     Index(DebugIndexArgs),
+    IndexPaths(DebugIndexPathsArgs),
+    #[cfg(feature = "s3")]
+    InitS3(DebugInitS3Args),
     LocalWorkingCopy(DebugLocalWorkingCopyArgs),
     #[command(visible_alias = "view")]
     Operation(DebugOperationArgs),
@@ -86,12 +119,20 @@ pub fn cmd_debug(
     subcommand: &DebugCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        DebugCommand::Backend(args) => cmd_debug_backend(ui, command, args),
         DebugCommand::Fileset(args) => cmd_debug_fileset(ui, command, args),
+        DebugCommand::Fsmonitor(args) => cmd_debug_fsmonitor(ui, command, args),
         DebugCommand::Index(args) => cmd_debug_index(ui, command, args),
+        DebugCommand::IndexPaths(args) => cmd_debug_index_paths(ui, command, args),
+        #[cfg(feature = "s3")]
+        DebugCommand::InitS3(args) => cmd_debug_init_s3(ui, command, args),
         DebugCommand::LocalWorkingCopy(args) => cmd_debug_local_working_copy(ui, command, args),
         DebugCommand::Operation(args) => cmd_debug_operation(ui, command, args),
         DebugCommand::Reindex(args) => cmd_debug_reindex(ui, command, args),
         DebugCommand::CopyDetection(args) => cmd_debug_copy_detection(ui, command, args),
+        DebugCommand::CreateGraphFixture(args) => cmd_debug_create_graph_fixture(ui, command, args),
+        DebugCommand::ExportRepo(args) => cmd_debug_export_repo(ui, command, args),
+        DebugCommand::ImportRepo(args) => cmd_debug_import_repo(ui, command, args),
         DebugCommand::Revset(args) => cmd_debug_revset(ui, command, args),
         DebugCommand::Snapshot(args) => cmd_debug_snapshot(ui, command, args),
         DebugCommand::Template(args) => cmd_debug_template(ui, command, args),
@@ -22,16 +22,24 @@ use crate::cli_util::CommandHelper;
 use crate::command_error::internal_error;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::revset_cache::RevsetCache;
 use crate::ui::Ui;
 
 /// Rebuild commit index
 #[derive(clap::Args, Clone, Debug)]
-pub struct DebugReindexArgs {}
+pub struct DebugReindexArgs {
+    /// Also build index of the commits that changed each path
+    ///
+    /// This is the same index as built by `jj debug index-paths`. Once built,
+    /// it is rebuilt along with the commit index.
+    #[arg(long)]
+    changed_paths: bool,
+}
 
 pub fn cmd_debug_reindex(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &DebugReindexArgs,
+    args: &DebugReindexArgs,
 ) -> Result<(), CommandError> {
     // Resolve the operation without loading the repo. The index might have to
     // be rebuilt while loading the repo.
@@ -41,6 +49,10 @@ pub fn cmd_debug_reindex(
     let index_store = repo_loader.index_store();
     if let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() {
         default_index_store.reinit().map_err(internal_error)?;
+        // The cached revsets refer to the positions in the old index.
+        RevsetCache::new(workspace.repo_path())
+            .clear()
+            .map_err(internal_error)?;
         let default_index = default_index_store
             .build_index_at_operation(&op, repo_loader.store())
             .map_err(internal_error)?;
@@ -49,6 +61,15 @@ pub fn cmd_debug_reindex(
             "Finished indexing {:?} commits.",
             default_index.as_composite().stats().num_commits
         )?;
+        if args.changed_paths {
+            let num_diffed = default_index_store
+                .build_changed_path_index(&default_index, repo_loader.store())
+                .map_err(internal_error)?;
+            writeln!(
+                ui.status(),
+                "Indexed changed paths of {num_diffed} commits."
+            )?;
+        }
     } else {
         return Err(user_error(format!(
             "Cannot reindex indexes of type '{}'",
@@ -15,6 +15,7 @@
 use std::fmt::Debug;
 use std::io::Write as _;
 
+use jj_lib::default_index::revset_engine;
 use jj_lib::object_id::ObjectId;
 use jj_lib::revset;
 use jj_lib::revset::RevsetDiagnostics;
@@ -29,6 +30,12 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 pub struct DebugRevsetArgs {
     revision: String,
+    /// Show how the revset would be evaluated instead of evaluating it
+    ///
+    /// The evaluation plan lists each node of the backend expression along
+    /// with the strategy the default index uses to evaluate it.
+    #[arg(long)]
+    explain: bool,
 }
 
 pub fn cmd_debug_revset(
@@ -63,6 +70,15 @@ pub fn cmd_debug_revset(
     writeln!(ui.stdout())?;
 
     let backend_expression = expression.to_backend_expression(repo);
+    if args.explain {
+        writeln!(ui.stdout(), "-- Plan:")?;
+        write!(
+            ui.stdout(),
+            "{}",
+            revset_engine::explain(&backend_expression)
+        )?;
+        return Ok(());
+    }
     writeln!(ui.stdout(), "-- Backend:")?;
     writeln!(ui.stdout(), "{backend_expression:#?}")?;
     writeln!(ui.stdout())?;
@@ -28,10 +28,14 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::add_gerrit_change_id_trailer;
+use crate::description_util::add_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::edit_multiple_descriptions;
 use crate::description_util::join_message_paragraphs;
+use crate::description_util::set_default_description;
+use crate::description_util::verify_description;
 use crate::description_util::ParsedBulkEditMessage;
 use crate::text_util::parse_author;
 use crate::ui::Ui;
@@ -40,6 +44,11 @@ use crate::ui::Ui;
 ///
 /// Starts an editor to let you edit the description of changes. The editor
 /// will be $EDITOR, or `pico` if that's not defined (`Notepad` on Windows).
+///
+/// If multiple revisions are specified, their descriptions are edited together
+/// in a single editor session, each following a `JJ: describe` line. All
+/// revisions are then rewritten in one operation, and their descendants are
+/// rebased once.
 #[derive(clap::Args, Clone, Debug)]
 #[command(visible_aliases = &["desc"])]
 pub(crate) struct DescribeArgs {
@@ -86,6 +95,9 @@ pub(crate) struct DescribeArgs {
         value_parser = parse_author
     )]
     author: Option<(String, String)>,
+    /// Don't check the description against the `commit.verify` settings
+    #[arg(long)]
+    no_verify: bool,
 }
 
 #[instrument(skip_all)]
@@ -146,9 +158,7 @@ pub(crate) fn cmd_describe(
                     .repo_mut()
                     .rewrite_commit(command.settings(), commit)
                     .detach();
-                if commit_builder.description().is_empty() {
-                    commit_builder.set_description(command.settings().default_description());
-                }
+                set_default_description(ui, &tx, &mut commit_builder)?;
                 if args.reset_author {
                     let new_author = commit_builder.committer().clone();
                     commit_builder.set_author(new_author);
@@ -215,6 +225,19 @@ pub(crate) fn cmd_describe(
         }
     };
 
+    let commit_descriptions: Vec<(_, _)> = commit_descriptions
+        .into_iter()
+        .map(|(commit, description)| -> Result<_, CommandError> {
+            let description =
+                add_gerrit_change_id_trailer(command.settings(), description, commit.change_id())?;
+            let description = add_trailers(ui, &tx, commit, description)?;
+            if !args.no_verify {
+                verify_description(command.settings(), &description)?;
+            }
+            Ok((commit, description))
+        })
+        .try_collect()?;
+
     // Filter out unchanged commits to avoid rebasing descendants in
     // `transform_descendants` below unnecessarily.
     let commit_descriptions: HashMap<_, _> = commit_descriptions
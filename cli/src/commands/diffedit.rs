@@ -15,10 +15,12 @@
 use std::io::Write;
 
 use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
 use itertools::Itertools;
-use jj_lib::matchers::EverythingMatcher;
 use jj_lib::object_id::ObjectId;
+use jj_lib::repo::Repo as _;
 use jj_lib::rewrite::merge_commit_trees;
+use jj_lib::rewrite::restore_tree;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -35,6 +37,9 @@ use crate::ui::Ui;
 /// With the `--from` and/or `--to` options, starts a [diff editor] comparing
 /// the "from" revision to the "to" revision.
 ///
+/// If paths are given, only the changes in those paths are shown in the diff
+/// editor. The other paths are left unchanged.
+///
 /// [diff editor]:
 ///     https://martinvonz.github.io/jj/latest/config/#editing-diffs
 ///
@@ -71,8 +76,18 @@ pub(crate) struct DiffeditArgs {
     )]
     to: Option<RevisionArg>,
     /// Specify diff editor to be used
-    #[arg(long, value_name = "NAME")]
+    #[arg(
+        long,
+        value_name = "NAME",
+        add = ArgValueCandidates::new(complete::diff_editors)
+    )]
     tool: Option<String>,
+    /// Edit only the changes in these paths (instead of all paths)
+    #[arg(
+        value_hint = clap::ValueHint::AnyPath,
+        add = ArgValueCompleter::new(complete::modified_revision_or_range_files),
+    )]
+    paths: Vec<String>,
     /// Preserve the content (not the diff) when rebasing descendants
     ///
     /// When rebasing a descendant on top of the rewritten revision, its diff
@@ -108,6 +123,9 @@ pub(crate) fn cmd_diffedit(
         diff_description = "The diff initially shows the commit's changes.".to_string();
     };
     workspace_command.check_rewritable([target_commit.id()])?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
 
     let diff_editor = workspace_command.diff_editor(ui, args.tool.as_deref())?;
     let mut tx = workspace_command.start_transaction();
@@ -125,7 +143,12 @@ don't make any changes, then the operation will be aborted.",
     };
     let base_tree = merge_commit_trees(tx.repo(), base_commits.as_slice())?;
     let tree = target_commit.tree()?;
-    let tree_id = diff_editor.edit(&base_tree, &tree, &EverythingMatcher, format_instructions)?;
+    let edited_tree_id =
+        diff_editor.edit(&base_tree, &tree, matcher.as_ref(), format_instructions)?;
+    // Paths outside the matcher may not be preserved by the diff editor, so
+    // only take the edited paths from its result.
+    let edited_tree = tx.repo().store().get_root_tree(&edited_tree_id)?;
+    let tree_id = restore_tree(&edited_tree, &tree, matcher.as_ref())?;
     if tree_id == *target_commit.tree_id() {
         writeln!(ui.status(), "Nothing changed.")?;
     } else {
@@ -19,6 +19,7 @@ use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::CommitIteratorExt;
+use jj_lib::op_store::RefTarget;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::revset::ResolvedRevsetExpression;
@@ -49,6 +50,9 @@ use crate::ui::Ui;
 /// `--insert-after` or `--insert-before` arguments are provided, the new
 /// children indicated by the arguments will be rebased onto the heads of the
 /// specified commits.
+///
+/// For example, `jj duplicate -r 'main..@' -d release --move-bookmarks` copies
+/// the stack onto `release` and moves the bookmarks of the stack to the copies.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct DuplicateArgs {
     /// The revision(s) to duplicate (default: @)
@@ -80,6 +84,9 @@ pub(crate) struct DuplicateArgs {
         add = ArgValueCandidates::new(complete::mutable_revisions)
     )]
     insert_before: Vec<RevisionArg>,
+    /// Move local bookmarks from the original commits to the duplicates
+    #[arg(long)]
+    move_bookmarks: bool,
 }
 
 #[instrument(skip_all)]
@@ -230,6 +237,33 @@ pub(crate) fn cmd_duplicate(
         )?
     };
 
+    let mut num_moved_bookmarks = 0;
+    if args.move_bookmarks {
+        let bookmarks = tx
+            .base_repo()
+            .view()
+            .local_bookmarks()
+            .map(|(name, target)| (name.to_owned(), target.clone()))
+            .collect_vec();
+        for (name, target) in bookmarks {
+            let mut moved = false;
+            for old_id in target.added_ids() {
+                if let Some(new_commit) = duplicated_commits.get(old_id) {
+                    // Replaces only the duplicated side of a conflicted bookmark.
+                    tx.repo_mut().merge_local_bookmark(
+                        &name,
+                        &RefTarget::normal(old_id.clone()),
+                        &RefTarget::normal(new_commit.id().clone()),
+                    );
+                    moved = true;
+                }
+            }
+            if moved {
+                num_moved_bookmarks += 1;
+            }
+        }
+    }
+
     if let Some(mut formatter) = ui.status_formatter() {
         for (old_id, new_commit) in &duplicated_commits {
             write!(formatter, "Duplicated {} as ", short_commit_hash(old_id))?;
@@ -242,6 +276,12 @@ pub(crate) fn cmd_duplicate(
                 "Rebased {num_rebased} commits onto duplicated commits"
             )?;
         }
+        if num_moved_bookmarks > 0 {
+            writeln!(
+                formatter,
+                "Moved {num_moved_bookmarks} bookmarks to the duplicated commits"
+            )?;
+        }
     }
     tx.finish(ui, format!("duplicate {num_to_duplicate} commit(s)"))?;
     Ok(())
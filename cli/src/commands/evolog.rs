@@ -17,6 +17,7 @@ use itertools::Itertools;
 use jj_lib::commit::Commit;
 use jj_lib::dag_walk::topo_order_reverse_ok;
 use jj_lib::matchers::EverythingMatcher;
+use jj_lib::repo::Repo as _;
 use tracing::instrument;
 
 use super::log::get_node_template;
@@ -37,6 +38,10 @@ use crate::ui::Ui;
 ///
 /// Lists the previous commits which a change has pointed to. The current commit
 /// of a change evolves when the change is updated, rebased, etc.
+///
+/// If the change is divergent, the evolution of all visible commits of the
+/// change is shown in the same graph. The visible commits are marked as
+/// divergent by the default templates.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct EvologArgs {
     #[arg(
@@ -62,7 +67,7 @@ pub(crate) struct EvologArgs {
     /// Render each revision using the given template
     ///
     /// For the syntax, see https://martinvonz.github.io/jj/latest/templates/
-    #[arg(long, short = 'T')]
+    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::commit_templates))]
     template: Option<String>,
     /// Show patch compared to the previous version of this change
     ///
@@ -84,6 +89,15 @@ pub(crate) fn cmd_evolog(
     let workspace_command = command.workspace_helper(ui)?;
 
     let start_commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    // Include the other visible commits if the change is divergent
+    let repo = workspace_command.repo();
+    let divergent_commits: Vec<_> = repo
+        .resolve_change_id(start_commit.change_id())
+        .unwrap_or_default()
+        .iter()
+        .filter(|id| *id != start_commit.id())
+        .map(|id| repo.store().get_commit(id))
+        .try_collect()?;
 
     let diff_renderer = workspace_command.diff_renderer_for_log(&args.diff_format, args.patch)?;
     let graph_style = GraphStyle::from_settings(command.settings())?;
@@ -120,7 +134,7 @@ pub(crate) fn cmd_evolog(
     let formatter = formatter.as_mut();
 
     let mut commits = topo_order_reverse_ok(
-        vec![Ok(start_commit)],
+        itertools::chain([start_commit], divergent_commits).map(Ok),
         |commit: &Commit| commit.id().clone(),
         |commit: &Commit| {
             let mut predecessors = commit.predecessors().collect_vec();
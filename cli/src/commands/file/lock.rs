@@ -0,0 +1,108 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
+use jj_lib::git;
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::git_util::get_git_repo;
+use crate::git_util::map_git_file_lock_error;
+use crate::git_util::with_remote_git_callbacks;
+use crate::ui::Ui;
+
+/// Mark files as locked by you
+///
+/// Locks are advisory: they don't prevent anyone from editing the files, but
+/// jj warns when the working copy modifies a file that is locked by someone
+/// else. This is mostly useful for binary files that can't be merged.
+///
+/// Locks are stored as Git refs. Use `--remote` to publish the locks so that
+/// others see them after running `jj git fetch`.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileLockArgs {
+    /// Publish the locks to this remote
+    ///
+    /// Locking fails if any of the files has been locked on the remote in the
+    /// meantime.
+    #[arg(long, add = ArgValueCandidates::new(complete::git_remotes))]
+    remote: Option<String>,
+    /// Paths to lock
+    #[arg(
+        required = true,
+        value_hint = clap::ValueHint::AnyPath,
+        add = ArgValueCompleter::new(complete::all_revision_files),
+    )]
+    paths: Vec<String>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_lock(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileLockArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let git_repo = get_git_repo(repo.store())?;
+    let paths: Vec<_> = args
+        .paths
+        .iter()
+        .map(|path| workspace_command.parse_file_path(path))
+        .try_collect()?;
+    let existing_locks = git::get_file_locks(&git_repo)?;
+    let user_email = command.settings().user_email();
+    for path in &paths {
+        let ui_path = workspace_command.format_file_path(path);
+        if let Some(lock) = existing_locks
+            .iter()
+            .find(|lock| lock.path == *path && lock.owner_email != user_email)
+        {
+            return Err(user_error(format!(
+                "File {ui_path} is already locked by {name} <{email}>",
+                name = lock.owner_name,
+                email = lock.owner_email,
+            )));
+        }
+    }
+    let git_settings = command.settings().git_settings();
+    for path in &paths {
+        let remote = args.remote.as_deref();
+        let lock = with_remote_git_callbacks(ui, None, &git_settings, remote, |cb| {
+            git::lock_file(
+                repo.as_ref(),
+                &git_repo,
+                path,
+                &command.settings().signature(),
+                args.remote.as_deref(),
+                cb,
+            )
+        })
+        .map_err(map_git_file_lock_error)?;
+        writeln!(
+            ui.status(),
+            "Locked {}",
+            workspace_command.format_file_path(&lock.path)
+        )?;
+    }
+    Ok(())
+}
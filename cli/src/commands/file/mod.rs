@@ -15,8 +15,10 @@
 pub mod annotate;
 pub mod chmod;
 pub mod list;
+pub mod lock;
 pub mod show;
 pub mod track;
+pub mod unlock;
 pub mod untrack;
 
 use crate::cli_util::CommandHelper;
@@ -29,8 +31,10 @@ pub enum FileCommand {
     Annotate(annotate::FileAnnotateArgs),
     Chmod(chmod::FileChmodArgs),
     List(list::FileListArgs),
+    Lock(lock::FileLockArgs),
     Show(show::FileShowArgs),
     Track(track::FileTrackArgs),
+    Unlock(unlock::FileUnlockArgs),
     Untrack(untrack::FileUntrackArgs),
 }
 
@@ -43,8 +47,10 @@ pub fn cmd_file(
         FileCommand::Annotate(args) => annotate::cmd_file_annotate(ui, command, args),
         FileCommand::Chmod(args) => chmod::cmd_file_chmod(ui, command, args),
         FileCommand::List(args) => list::cmd_file_list(ui, command, args),
+        FileCommand::Lock(args) => lock::cmd_file_lock(ui, command, args),
         FileCommand::Show(args) => show::cmd_file_show(ui, command, args),
         FileCommand::Track(args) => track::cmd_file_track(ui, command, args),
+        FileCommand::Unlock(args) => unlock::cmd_file_unlock(ui, command, args),
         FileCommand::Untrack(args) => untrack::cmd_file_untrack(ui, command, args),
     }
 }
@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs;
 use std::io;
+use std::io::Read as _;
 use std::io::Write;
+use std::path::Path;
 
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
@@ -29,6 +32,9 @@ use jj_lib::repo_path::RepoPath;
 use pollster::FutureExt;
 use tracing::instrument;
 
+use crate::archive::export_tree_entries;
+use crate::archive::ExportedEntry;
+use crate::archive::TarWriter;
 use crate::cli_util::print_unmatched_explicit_paths;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
@@ -36,12 +42,21 @@ use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::syntax_highlight::write_highlighted;
+use crate::syntax_highlight::HighlightedLines;
+use crate::syntax_highlight::MAX_HIGHLIGHT_SIZE;
 use crate::ui::Ui;
 
 /// Print contents of files in a revision
 ///
 /// If the given path is a directory, files in the directory will be visited
 /// recursively.
+///
+/// With `--output-dir` or `--tar`, the selected files are exported with their
+/// paths instead of being printed one after another.
+///
+/// If `ui.syntax-highlight` is enabled, file contents are highlighted when the
+/// output is colored.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct FileShowArgs {
     /// The revision to get the file contents from
@@ -58,6 +73,13 @@ pub(crate) struct FileShowArgs {
         add = ArgValueCompleter::new(complete::all_revision_files),
     )]
     paths: Vec<String>,
+    /// Write the files to this directory, at their paths relative to the
+    /// workspace root
+    #[arg(long, value_hint = clap::ValueHint::DirPath, conflicts_with = "tar")]
+    output_dir: Option<String>,
+    /// Write the files to stdout as a tar archive
+    #[arg(long)]
+    tar: bool,
 }
 
 #[instrument(skip_all)]
@@ -73,6 +95,39 @@ pub(crate) fn cmd_file_show(
     // parse_union_filesets(). paths = [] should be "none()" if supported.
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
 
+    if args.output_dir.is_some() || args.tar {
+        let matcher = fileset_expression.to_matcher();
+        let entries = tree.entries_matching(matcher.as_ref());
+        if let Some(output_dir) = &args.output_dir {
+            let output_dir = Path::new(output_dir);
+            let mut count = 0;
+            export_tree_entries(ui, &workspace_command, entries, |path, entry| {
+                write_entry_to_dir(output_dir, path, entry)?;
+                count += 1;
+                Ok(())
+            })?;
+            writeln!(
+                ui.status(),
+                "Exported {count} files to {}",
+                output_dir.display()
+            )?;
+        } else {
+            let timestamp = &commit.committer().timestamp.timestamp;
+            let mtime = u64::try_from(timestamp.0.div_euclid(1000)).unwrap_or(0);
+            let mut tar = TarWriter::new(ui.stdout(), mtime);
+            export_tree_entries(ui, &workspace_command, entries, |path, entry| {
+                tar.append(path.as_internal_file_string(), entry)
+            })?;
+            tar.finish()?;
+        }
+        print_unmatched_explicit_paths(ui, &workspace_command, &fileset_expression, [&tree])?;
+        return Ok(());
+    }
+
+    let highlight = ui.color()
+        && workspace_command
+            .settings()
+            .get_bool("ui.syntax-highlight")?;
     // Try fast path for single file entry
     if let Some(path) = get_single_path(&fileset_expression) {
         let value = tree.path_value(path)?;
@@ -82,7 +137,7 @@ pub(crate) fn cmd_file_show(
         }
         if !value.is_tree() {
             ui.request_pager();
-            write_tree_entries(ui, &workspace_command, [(path, Ok(value))])?;
+            write_tree_entries(ui, &workspace_command, [(path, Ok(value))], highlight)?;
             return Ok(());
         }
     }
@@ -93,6 +148,7 @@ pub(crate) fn cmd_file_show(
         ui,
         &workspace_command,
         tree.entries_matching(matcher.as_ref()),
+        highlight,
     )?;
     print_unmatched_explicit_paths(ui, &workspace_command, &fileset_expression, [&tree])?;
     Ok(())
@@ -114,6 +170,7 @@ fn write_tree_entries<P: AsRef<RepoPath>>(
     ui: &Ui,
     workspace_command: &WorkspaceCommandHelper,
     entries: impl IntoIterator<Item = (P, BackendResult<MergedTreeValue>)>,
+    highlight: bool,
 ) -> Result<(), CommandError> {
     let repo = workspace_command.repo();
     for (path, result) in entries {
@@ -128,6 +185,19 @@ fn write_tree_entries<P: AsRef<RepoPath>>(
                     "Path '{ui_path}' exists but access is denied: {err}"
                 )?;
             }
+            MaterializedTreeValue::File { mut reader, .. } if highlight => {
+                // Don't buffer large files which wouldn't be highlighted anyway.
+                let limit = MAX_HIGHLIGHT_SIZE as u64 + 1;
+                let mut contents = vec![];
+                reader.by_ref().take(limit).read_to_end(&mut contents)?;
+                if contents.len() <= MAX_HIGHLIGHT_SIZE {
+                    write_highlighted_file(ui, path.as_ref(), &contents)?;
+                } else {
+                    let mut formatter = ui.stdout_formatter();
+                    formatter.write_all(&contents)?;
+                    io::copy(&mut reader, &mut formatter.as_mut())?;
+                }
+            }
             MaterializedTreeValue::File { mut reader, .. } => {
                 io::copy(&mut reader, &mut ui.stdout_formatter().as_mut())?;
             }
@@ -153,3 +223,58 @@ fn write_tree_entries<P: AsRef<RepoPath>>(
     }
     Ok(())
 }
+
+fn write_highlighted_file(ui: &Ui, path: &RepoPath, contents: &[u8]) -> io::Result<()> {
+    let highlights = HighlightedLines::new(path, contents);
+    let mut formatter = ui.stdout_formatter();
+    for (index, line) in contents.split_inclusive(|b| *b == b'\n').enumerate() {
+        write_highlighted(
+            formatter.as_mut(),
+            highlights.line(index),
+            0,
+            line,
+            |formatter, text| formatter.write_all(text),
+        )?;
+    }
+    Ok(())
+}
+
+fn write_entry_to_dir(
+    output_dir: &Path,
+    path: &RepoPath,
+    entry: ExportedEntry,
+) -> Result<(), CommandError> {
+    let disk_path = path.to_fs_path(output_dir).map_err(user_error)?;
+    if let Some(parent) = disk_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::symlink_metadata(&disk_path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(&disk_path)?,
+        Ok(_) => fs::remove_file(&disk_path)?,
+        Err(_) => {}
+    }
+    match entry {
+        ExportedEntry::File {
+            executable,
+            contents,
+        } => {
+            fs::write(&disk_path, contents)?;
+            #[cfg(unix)]
+            if executable {
+                use std::os::unix::fs::PermissionsExt as _;
+                fs::set_permissions(&disk_path, fs::Permissions::from_mode(0o755))?;
+            }
+            #[cfg(not(unix))]
+            let _ = executable;
+        }
+        #[cfg(unix)]
+        ExportedEntry::Symlink { target } => {
+            std::os::unix::fs::symlink(target, &disk_path)?;
+        }
+        #[cfg(not(unix))]
+        ExportedEntry::Symlink { target } => {
+            fs::write(&disk_path, target)?;
+        }
+    }
+    Ok(())
+}
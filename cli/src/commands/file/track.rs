@@ -15,6 +15,7 @@
 use std::io::Write;
 
 use jj_lib::working_copy::SnapshotOptions;
+use jj_lib::working_copy::SnapshotStats;
 use tracing::instrument;
 
 use crate::cli_util::print_snapshot_stats;
@@ -31,11 +32,19 @@ use crate::ui::Ui;
 /// `snapshot.auto-track` (e.g. to `"none()"` or `"glob:**/*.rs"`). Files that
 /// don't match the pattern can be manually tracked using this command. The
 /// default pattern is `all()` and this command has no effect.
+///
+/// New files larger than `snapshot.max-new-file-size` are refused. If the
+/// terminal is interactive, you will be asked whether to track them anyway.
+/// Use `--force` to track them without asking.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct FileTrackArgs {
     /// Paths to track
     #[arg(required = true, value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    /// Track the paths even if they're larger than
+    /// `snapshot.max-new-file-size`
+    #[arg(long)]
+    force: bool,
 }
 
 #[instrument(skip_all)]
@@ -46,6 +55,8 @@ pub(crate) fn cmd_file_track(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let conflict_marker_style = workspace_command.env().conflict_marker_style();
+    let symlink_policy = workspace_command.env().symlink_policy();
+    let path_converter = workspace_command.env().path_converter().clone();
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
@@ -53,20 +64,41 @@ pub(crate) fn cmd_file_track(
     let mut tx = workspace_command.start_transaction().into_inner();
     let base_ignores = workspace_command.base_ignores()?;
     let (mut locked_ws, _wc_commit) = workspace_command.start_working_copy_mutation()?;
-    let (_tree_id, stats) = locked_ws.locked_wc().snapshot(&SnapshotOptions {
+    let max_new_file_size = if args.force {
+        u64::MAX
+    } else {
+        command.settings().max_new_file_size()?
+    };
+    let options = SnapshotOptions {
         base_ignores,
         fsmonitor_settings: command.settings().fsmonitor_settings()?,
         progress: None,
         start_tracking_matcher: &matcher,
-        max_new_file_size: command.settings().max_new_file_size()?,
+        snapshot_matcher: None,
+        max_new_file_size,
+        file_state_trust: command.settings().file_state_trust()?,
+        rehash_throttle_size: command.settings().rehash_throttle_size()?,
         conflict_marker_style,
-    })?;
+        symlink_policy,
+    };
+    let (_tree_id, mut stats) = locked_ws.locked_wc().snapshot(&options)?;
+    if !stats.untracked_paths.is_empty() && Ui::can_prompt() {
+        print_snapshot_stats(ui, &stats, &path_converter)?;
+        if ui.prompt_yes_no("Track them anyway?", Some(false))? {
+            locked_ws.locked_wc().snapshot(&SnapshotOptions {
+                max_new_file_size: u64::MAX,
+                ..options
+            })?;
+        }
+        // The refused paths have already been reported
+        stats = SnapshotStats::default();
+    }
     let num_rebased = tx.repo_mut().rebase_descendants(command.settings())?;
     if num_rebased > 0 {
         writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
     }
     let repo = tx.commit("track paths")?;
     locked_ws.finish(repo.op_id().clone())?;
-    print_snapshot_stats(ui, &stats, workspace_command.env().path_converter())?;
+    print_snapshot_stats(ui, &stats, &path_converter)?;
     Ok(())
 }
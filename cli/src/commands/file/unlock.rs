@@ -0,0 +1,114 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
+use jj_lib::git;
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::git_util::get_git_repo;
+use crate::git_util::map_git_file_lock_error;
+use crate::git_util::with_remote_git_callbacks;
+use crate::ui::Ui;
+
+/// Release advisory locks on files
+///
+/// Locks fetched from a remote are also deleted from that remote.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileUnlockArgs {
+    /// Also delete the locks from this remote
+    #[arg(long, add = ArgValueCandidates::new(complete::git_remotes))]
+    remote: Option<String>,
+    /// Release locks held by other users
+    #[arg(long)]
+    force: bool,
+    /// Paths to unlock
+    #[arg(
+        required = true,
+        value_hint = clap::ValueHint::AnyPath,
+        add = ArgValueCompleter::new(complete::all_revision_files),
+    )]
+    paths: Vec<String>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_unlock(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileUnlockArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let git_repo = get_git_repo(repo.store())?;
+    let paths: Vec<_> = args
+        .paths
+        .iter()
+        .map(|path| workspace_command.parse_file_path(path))
+        .try_collect()?;
+    let existing_locks = git::get_file_locks(&git_repo)?;
+    let user_email = command.settings().user_email();
+    let mut locks_to_release = vec![];
+    for path in &paths {
+        let ui_path = workspace_command.format_file_path(path);
+        let locks = existing_locks
+            .iter()
+            .filter(|lock| lock.path == *path)
+            .collect_vec();
+        if locks.is_empty() {
+            return Err(user_error(format!("File {ui_path} is not locked")));
+        }
+        if let Some(lock) = locks.iter().find(|lock| lock.owner_email != user_email) {
+            if !args.force {
+                return Err(user_error_with_hint(
+                    format!(
+                        "File {ui_path} is locked by {name} <{email}>",
+                        name = lock.owner_name,
+                        email = lock.owner_email,
+                    ),
+                    "Use --force to release it anyway.",
+                ));
+            }
+        }
+        // A lock published to a remote is also mirrored locally. Releasing the
+        // remote one deletes both refs.
+        if let Some(lock) = locks.iter().find(|lock| lock.remote.is_some()) {
+            locks_to_release.push(*lock);
+        } else {
+            locks_to_release.push(locks[0]);
+        }
+    }
+    let git_settings = command.settings().git_settings();
+    for lock in locks_to_release {
+        let remote = args.remote.as_deref();
+        with_remote_git_callbacks(ui, None, &git_settings, remote, |cb| {
+            git::unlock_file(repo.as_ref(), &git_repo, lock, args.remote.as_deref(), cb)
+        })
+        .map_err(map_git_file_lock_error)?;
+        writeln!(
+            ui.status(),
+            "Unlocked {}",
+            workspace_command.format_file_path(&lock.path)
+        )?;
+    }
+    Ok(())
+}
@@ -52,6 +52,7 @@ pub(crate) fn cmd_file_untrack(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let conflict_marker_style = workspace_command.env().conflict_marker_style();
+    let symlink_policy = workspace_command.env().symlink_policy();
     let store = workspace_command.repo().store().clone();
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
@@ -82,8 +83,12 @@ pub(crate) fn cmd_file_untrack(
         fsmonitor_settings: command.settings().fsmonitor_settings()?,
         progress: None,
         start_tracking_matcher: &auto_tracking_matcher,
+        snapshot_matcher: None,
         max_new_file_size: command.settings().max_new_file_size()?,
+        file_state_trust: command.settings().file_state_trust()?,
+        rehash_throttle_size: command.settings().rehash_throttle_size()?,
         conflict_marker_style,
+        symlink_policy,
     })?;
     if wc_tree_id != *new_commit.tree_id() {
         let wc_tree = store.get_root_tree(&wc_tree_id)?;
@@ -0,0 +1,114 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::repo::Repo as _;
+
+use super::load_forge;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::forge::NewPullRequest;
+use crate::ui::Ui;
+
+/// Open a pull request from a bookmark
+///
+/// The bookmark must have been pushed to the remote already. The title and
+/// description default to the description of the commit the bookmark points
+/// to.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ForgeCreateArgs {
+    /// The bookmark to merge
+    #[arg(long, short, add = ArgValueCandidates::new(complete::local_bookmarks))]
+    bookmark: String,
+    /// The branch to merge into [default: the repository's default branch]
+    #[arg(long)]
+    base: Option<String>,
+    /// The title of the pull request
+    #[arg(long)]
+    title: Option<String>,
+    /// The description of the pull request
+    #[arg(long)]
+    body: Option<String>,
+    /// Mark the pull request as a draft
+    #[arg(long)]
+    draft: bool,
+    /// The remote hosted on the forge
+    ///
+    /// This defaults to the `git.push` setting. If that is not configured, and
+    /// if there are multiple remotes, the remote named "origin" will be used.
+    #[arg(long, add = ArgValueCandidates::new(complete::git_remotes))]
+    remote: Option<String>,
+}
+
+pub fn cmd_forge_create(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ForgeCreateArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let (remote, forge) = load_forge(ui, command, &workspace_command, args.remote.as_deref())?;
+    let repo = workspace_command.repo();
+    let bookmark_name = &args.bookmark;
+    let local_target = repo.view().get_local_bookmark(bookmark_name);
+    let Some(commit_id) = local_target.as_normal() else {
+        return Err(if local_target.is_absent() {
+            user_error(format!("No such bookmark: {bookmark_name}"))
+        } else {
+            user_error(format!("Bookmark {bookmark_name} is conflicted"))
+        });
+    };
+    let remote_ref = repo.view().get_remote_bookmark(bookmark_name, &remote);
+    if remote_ref.target.as_normal() != Some(commit_id) {
+        return Err(user_error_with_hint(
+            format!("Bookmark {bookmark_name} has not been pushed to {remote}"),
+            format!("Run `jj git push --bookmark {bookmark_name} --remote {remote}` first."),
+        ));
+    }
+
+    let commit = repo.store().get_commit(commit_id)?;
+    let (description_title, description_body) = commit
+        .description()
+        .trim()
+        .split_once('\n')
+        .unwrap_or((commit.description().trim(), ""));
+    let title = args.title.as_deref().unwrap_or(description_title);
+    if title.is_empty() {
+        return Err(user_error_with_hint(
+            "The pull request has no title",
+            "Describe the commit or use `--title`.",
+        ));
+    }
+    let body = args.body.as_deref().unwrap_or(description_body.trim());
+    let new = NewPullRequest {
+        head: bookmark_name,
+        base: args.base.as_deref(),
+        title,
+        body,
+        draft: args.draft,
+    };
+    let pr = forge.create_pull_request(&new).map_err(user_error)?;
+    writeln!(
+        ui.status(),
+        "Created pull request #{} on {} for bookmark {bookmark_name}",
+        pr.number,
+        forge.name()
+    )?;
+    writeln!(ui.stdout(), "{}", pr.url)?;
+    Ok(())
+}
@@ -0,0 +1,104 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::git;
+use jj_lib::git::GitFetchError;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+
+use super::load_forge;
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::git_util::get_git_repo;
+use crate::git_util::map_git_error;
+use crate::git_util::with_remote_git_callbacks;
+use crate::ui::Ui;
+
+/// Fetch pull requests as bookmarks
+///
+/// The head of each pull request is fetched into a local bookmark, which is
+/// named `pr-<number>` by default. Use the `forge.pull-request-bookmark-prefix`
+/// setting to change the prefix. This works for pull requests from forks too.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ForgeFetchArgs {
+    /// The numbers of the pull requests to fetch
+    #[arg(required = true)]
+    numbers: Vec<u64>,
+    /// The remote hosted on the forge
+    ///
+    /// This defaults to the `git.push` setting. If that is not configured, and
+    /// if there are multiple remotes, the remote named "origin" will be used.
+    #[arg(long, add = ArgValueCandidates::new(complete::git_remotes))]
+    remote: Option<String>,
+}
+
+pub fn cmd_forge_fetch(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ForgeFetchArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let (remote, forge) = load_forge(ui, command, &workspace_command, args.remote.as_deref())?;
+    let git_repo = get_git_repo(workspace_command.repo().store())?;
+    let bookmark_prefix = command
+        .settings()
+        .get_string("forge.pull-request-bookmark-prefix")
+        .optional()?
+        .unwrap_or_else(|| "pr-".to_owned());
+
+    let git_settings = command.settings().git_settings();
+    let mut tx = workspace_command.start_transaction();
+    for &number in args.numbers.iter().unique() {
+        let ref_name = forge.pull_request_ref(number);
+        let commit_id = with_remote_git_callbacks(ui, None, &git_settings, Some(&remote), |cb| {
+            git::fetch_ref(tx.repo_mut(), &git_repo, &remote, &ref_name, cb)
+        })
+        .map_err(|err| match err {
+            GitFetchError::NoSuchRemoteRef(_) => {
+                user_error(format!("No pull request #{number} on {remote}"))
+            }
+            GitFetchError::GitImportError(err) => err.into(),
+            GitFetchError::InternalGitError(err) => map_git_error(err),
+            _ => user_error(err),
+        })?;
+        let bookmark_name = format!("{bookmark_prefix}{number}");
+        writeln!(
+            ui.status(),
+            "Fetched pull request #{number} into bookmark {bookmark_name} at {}",
+            short_commit_hash(&commit_id),
+        )?;
+        tx.repo_mut()
+            .set_local_bookmark_target(&bookmark_name, RefTarget::normal(commit_id));
+    }
+    tx.finish(
+        ui,
+        format!(
+            "fetch pull requests {} from git remote {remote}",
+            args.numbers
+                .iter()
+                .unique()
+                .map(|n| format!("#{n}"))
+                .join(", ")
+        ),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::str_util::StringPattern;
+
+use super::load_forge;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// List open pull requests for local bookmarks
+#[derive(clap::Args, Clone, Debug)]
+pub struct ForgeListArgs {
+    /// The remote hosted on the forge
+    ///
+    /// This defaults to the `git.push` setting. If that is not configured, and
+    /// if there are multiple remotes, the remote named "origin" will be used.
+    #[arg(long, add = ArgValueCandidates::new(complete::git_remotes))]
+    remote: Option<String>,
+    /// Show pull requests only for these bookmarks
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` prefix to
+    /// select bookmarks by wildcard pattern. For details, see
+    /// https://martinvonz.github.io/jj/latest/revsets#string-patterns.
+    #[arg(
+        long, short,
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::local_bookmarks),
+    )]
+    bookmark: Vec<StringPattern>,
+}
+
+pub fn cmd_forge_list(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ForgeListArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let (_remote, forge) = load_forge(ui, command, &workspace_command, args.remote.as_deref())?;
+    let view = workspace_command.repo().view();
+    let is_listed = |name: &str| {
+        view.get_local_bookmark(name).is_present()
+            && (args.bookmark.is_empty()
+                || args.bookmark.iter().any(|pattern| pattern.matches(name)))
+    };
+    let pull_requests = forge.list_pull_requests().map_err(user_error)?;
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for pr in pull_requests.iter().filter(|pr| is_listed(&pr.head)) {
+        write!(formatter.labeled("bookmark"), "{}", pr.head)?;
+        write!(formatter, " #{} -> ", pr.number)?;
+        write!(formatter.labeled("bookmark"), "{}", pr.base)?;
+        writeln!(formatter, ": {}", pr.title)?;
+        writeln!(formatter, "  {}", pr.url)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod create;
+mod fetch;
+mod list;
+
+use clap::Subcommand;
+use jj_lib::repo::Repo as _;
+
+use self::create::cmd_forge_create;
+use self::create::ForgeCreateArgs;
+use self::fetch::cmd_forge_fetch;
+use self::fetch::ForgeFetchArgs;
+use self::list::cmd_forge_list;
+use self::list::ForgeListArgs;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::commands::git::push::get_default_push_remote;
+use crate::forge;
+use crate::forge::Forge;
+use crate::git_util::get_git_repo;
+use crate::ui::Ui;
+
+/// Work with pull requests on GitHub or GitLab
+///
+/// The forge is detected from the URL of the remote, or can be set by the
+/// `forge.kind` setting (`"github"` or `"gitlab"`). The commands run the
+/// forge's command-line tool, `gh` or `glab`, which must be installed. The
+/// access token can be set by the `forge.github.token` or `forge.gitlab.token`
+/// setting. Otherwise, the tool's own login is used.
+#[derive(Subcommand, Clone, Debug)]
+pub enum ForgeCommand {
+    Create(ForgeCreateArgs),
+    Fetch(ForgeFetchArgs),
+    List(ForgeListArgs),
+}
+
+pub fn cmd_forge(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &ForgeCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        ForgeCommand::Create(args) => cmd_forge_create(ui, command, args),
+        ForgeCommand::Fetch(args) => cmd_forge_fetch(ui, command, args),
+        ForgeCommand::List(args) => cmd_forge_list(ui, command, args),
+    }
+}
+
+/// Returns the name of the remote and its forge.
+fn load_forge(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+    remote: Option<&str>,
+) -> Result<(String, Box<dyn Forge>), CommandError> {
+    let git_repo = get_git_repo(workspace_command.repo().store())?;
+    let remote = match remote {
+        Some(remote) => remote.to_owned(),
+        None => get_default_push_remote(ui, command.settings(), &git_repo)?,
+    };
+    let git_remote = git_repo
+        .find_remote(&remote)
+        .map_err(|_| user_error(format!("No git remote named '{remote}'")))?;
+    let url = git_remote.url().unwrap_or_default();
+    let forge = forge::forge_from_settings(command.settings(), url)?.ok_or_else(|| {
+        user_error_with_hint(
+            format!("Cannot determine the forge hosting remote '{remote}'"),
+            r#"Set `forge.kind` to "github" or "gitlab"."#,
+        )
+    })?;
+    Ok((remote, forge))
+}
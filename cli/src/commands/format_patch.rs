@@ -0,0 +1,330 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPathUiConverter;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::diff_util::DiffFormat;
+use crate::diff_util::DiffRenderer;
+use crate::diff_util::DiffStatOptions;
+use crate::diff_util::LineCompareMode;
+use crate::diff_util::LineDiffOptions;
+use crate::diff_util::UnifiedDiffOptions;
+use crate::formatter::PlainTextFormatter;
+use crate::patch_util::encode_header_value;
+use crate::patch_util::escape_mbox_from_lines;
+use crate::time_util::format_rfc2822_timestamp;
+use crate::ui::Ui;
+
+/// Width of the diffstat in patch emails, same as `git format-patch`.
+const PATCH_STAT_WIDTH: usize = 72;
+
+/// Export revisions as patches suitable for sending by email
+///
+/// Each revision is rendered as a message in mbox format, with the author,
+/// date, and subject taken from the commit, followed by a diffstat and a
+/// Git-format diff. The patches are numbered from the oldest revision to the
+/// newest. Merge commits are skipped.
+///
+/// By default, the patches are printed to stdout as a single mbox. Use
+/// `--output-directory` to write one file per patch instead.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FormatPatchArgs {
+    #[command(flatten)]
+    series: PatchSeriesArgs,
+    /// Write each patch to a numbered file in this directory
+    #[arg(long, short, value_hint = clap::ValueHint::DirPath)]
+    output_directory: Option<PathBuf>,
+}
+
+/// Options for selecting and rendering a series of patches.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct PatchSeriesArgs {
+    /// The revisions to export
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revisions: Vec<RevisionArg>,
+    /// Prefix in the subject line instead of "PATCH"
+    #[arg(long, default_value = "PATCH")]
+    subject_prefix: String,
+    /// Mark the series as the given version of the patches, e.g. "v2"
+    #[arg(long, short = 'v', value_name = "N")]
+    reroll_count: Option<u32>,
+    /// Make the patches replies to the given Message-ID
+    #[arg(long, value_name = "MESSAGE_ID")]
+    in_reply_to: Option<String>,
+}
+
+/// Patch email rendered from a single revision.
+pub(crate) struct FormattedPatch {
+    /// File name like `0001-fix-the-thing.patch`.
+    pub file_name: String,
+    pub content: Vec<u8>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_format_patch(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FormatPatchArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let patches = format_patches(ui, command, &workspace_command, &args.series)?;
+    if let Some(dir) = &args.output_directory {
+        fs::create_dir_all(dir).map_err(|err| {
+            user_error_with_message(format!("Failed to create directory {}", dir.display()), err)
+        })?;
+        for patch in &patches {
+            let path = dir.join(&patch.file_name);
+            fs::write(&path, &patch.content).map_err(|err| {
+                user_error_with_message(format!("Failed to write {}", path.display()), err)
+            })?;
+            writeln!(ui.stdout(), "{}", path.display())?;
+        }
+    } else {
+        ui.request_pager();
+        let mut stdout = ui.stdout();
+        for patch in &patches {
+            stdout.write_all(&patch.content)?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders the revisions selected by `args` as patch emails, oldest first.
+pub(crate) fn format_patches(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+    args: &PatchSeriesArgs,
+) -> Result<Vec<FormattedPatch>, CommandError> {
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let mut patch_commits = vec![];
+    // Revsets are ordered newest first, but patches are applied oldest first.
+    for commit in commits.into_iter().rev() {
+        if commit.parent_ids().len() > 1 {
+            writeln!(
+                ui.warning_default(),
+                "Skipping merge commit {}",
+                workspace_command.format_commit_summary(&commit)
+            )?;
+        } else if commit.id() == workspace_command.repo().store().root_commit_id() {
+            return Err(user_error("Cannot format the root commit as a patch"));
+        } else {
+            patch_commits.push(commit);
+        }
+    }
+    if patch_commits.is_empty() {
+        return Err(user_error("No revisions to format"));
+    }
+
+    // Paths in patches are relative to the repo root regardless of the cwd.
+    let workspace_root = workspace_command.workspace_root().to_owned();
+    let path_converter = RepoPathUiConverter::Fs {
+        cwd: workspace_root.clone(),
+        base: workspace_root,
+    };
+    let line_diff = LineDiffOptions {
+        compare_mode: LineCompareMode::Exact,
+    };
+    let stat_renderer = DiffRenderer::new(
+        workspace_command.repo().as_ref(),
+        &path_converter,
+        workspace_command.env().conflict_marker_style(),
+        vec![DiffFormat::Stat(Box::new(DiffStatOptions {
+            line_diff: line_diff.clone(),
+        }))],
+    );
+    let diff_renderer = DiffRenderer::new(
+        workspace_command.repo().as_ref(),
+        &path_converter,
+        workspace_command.env().conflict_marker_style(),
+        vec![DiffFormat::Git(Box::new(UnifiedDiffOptions {
+            context: command.settings().get("diff.git.context")?,
+            line_diff,
+            // Patches are meant to be applied, not viewed in a terminal.
+            syntax_highlight: false,
+        }))],
+    );
+
+    let total = patch_commits.len();
+    let mut patches = vec![];
+    for (i, commit) in patch_commits.iter().enumerate() {
+        let number = i + 1;
+        let subject_prefix = format_subject_prefix(args, number, total);
+        let mut output = vec![];
+        write_patch(
+            ui,
+            &mut output,
+            [&stat_renderer, &diff_renderer],
+            commit,
+            &subject_prefix,
+            args.in_reply_to.as_deref(),
+        )?;
+        patches.push(FormattedPatch {
+            file_name: patch_file_name(args.reroll_count, number, commit),
+            content: output,
+        });
+    }
+    Ok(patches)
+}
+
+fn format_subject_prefix(args: &PatchSeriesArgs, number: usize, total: usize) -> String {
+    let mut words = vec![args.subject_prefix.clone()];
+    if let Some(count) = args.reroll_count {
+        words.push(format!("v{count}"));
+    }
+    if total > 1 {
+        words.push(format!("{number}/{total}"));
+    }
+    format!(
+        "[{}]",
+        words.iter().filter(|word| !word.is_empty()).join(" ")
+    )
+}
+
+fn write_patch(
+    ui: &Ui,
+    output: &mut Vec<u8>,
+    [stat_renderer, diff_renderer]: [&DiffRenderer; 2],
+    commit: &Commit,
+    subject_prefix: &str,
+    in_reply_to: Option<&str>,
+) -> Result<(), CommandError> {
+    let author = commit.author();
+    let date = format_rfc2822_timestamp(&author.timestamp)
+        .map_err(|err| user_error_with_message("Invalid author timestamp", err))?;
+    let description = commit.description();
+    let (subject, body) = description.split_once('\n').unwrap_or((description, ""));
+
+    let mut content = vec![];
+    let body = body.trim_matches('\n');
+    if !body.is_empty() {
+        writeln!(content, "{}", escape_mbox_from_lines(body))?;
+        writeln!(content)?;
+    }
+    writeln!(content, "---")?;
+    let mut formatter = PlainTextFormatter::new(&mut content);
+    stat_renderer.show_patch(
+        ui,
+        &mut formatter,
+        commit,
+        &EverythingMatcher,
+        PATCH_STAT_WIDTH,
+    )?;
+    writeln!(formatter)?;
+    diff_renderer.show_patch(
+        ui,
+        &mut formatter,
+        commit,
+        &EverythingMatcher,
+        PATCH_STAT_WIDTH,
+    )?;
+
+    // mbox "From " line, with the fixed date used by `git format-patch`
+    writeln!(
+        output,
+        "From {} Mon Sep 17 00:00:00 2001",
+        commit.id().hex()
+    )?;
+    writeln!(
+        output,
+        "From: {} <{}>",
+        encode_header_value(&author.name),
+        author.email
+    )?;
+    writeln!(output, "Date: {date}")?;
+    writeln!(
+        output,
+        "Subject: {subject_prefix} {}",
+        encode_header_value(subject)
+    )?;
+    if let Some(message_id) = in_reply_to {
+        let message_id = format_message_id(message_id);
+        writeln!(output, "In-Reply-To: {message_id}")?;
+        writeln!(output, "References: {message_id}")?;
+    }
+    // Like `git format-patch`, declare the charset only if needed
+    if !author.name.is_ascii() || !description.is_ascii() || !content.is_ascii() {
+        writeln!(output, "MIME-Version: 1.0")?;
+        writeln!(output, "Content-Type: text/plain; charset=UTF-8")?;
+        writeln!(output, "Content-Transfer-Encoding: 8bit")?;
+    }
+    writeln!(output)?;
+    output.extend_from_slice(&content);
+    writeln!(output, "-- ")?;
+    writeln!(output, "jj {}", env!("CARGO_PKG_VERSION"))?;
+    writeln!(output)?;
+    Ok(())
+}
+
+fn format_message_id(message_id: &str) -> String {
+    if message_id.starts_with('<') {
+        message_id.to_owned()
+    } else {
+        format!("<{message_id}>")
+    }
+}
+
+/// Builds a file name like `v2-0001-fix-the-thing.patch` from the subject.
+fn patch_file_name(reroll_count: Option<u32>, number: usize, commit: &Commit) -> String {
+    let subject = commit.description().lines().next().unwrap_or_default();
+    let slug = subject
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '.')
+        .filter(|word| !word.is_empty())
+        .join("-");
+    // Truncate long subjects at a word boundary like `git format-patch`
+    let mut name = String::new();
+    for word in slug.split('-') {
+        if !name.is_empty() && name.len() + word.len() >= 52 {
+            break;
+        }
+        if !name.is_empty() {
+            name.push('-');
+        }
+        name.push_str(word);
+    }
+    let name = name.trim_matches('.');
+    let version = reroll_count.map_or(String::new(), |count| format!("v{count}-"));
+    if name.is_empty() {
+        format!("{version}{number:04}.patch")
+    } else {
+        format!("{version}{number:04}-{name}.patch")
+    }
+}
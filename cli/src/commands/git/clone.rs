@@ -63,6 +63,13 @@ pub struct GitCloneArgs {
     /// Create a shallow clone of the given depth
     #[arg(long)]
     depth: Option<NonZeroU32>,
+    /// Don't check out a working copy
+    ///
+    /// The repo can still be inspected and modified by commands that don't
+    /// need a working copy. Use `jj workspace add --first` to check out a
+    /// working copy later.
+    #[arg(long, conflicts_with = "colocate")]
+    bare: bool,
 }
 
 fn absolute_git_source(cwd: &Path, source: &str) -> String {
@@ -181,12 +188,22 @@ pub fn cmd_git_clone(
             checkout_tx
                 .repo_mut()
                 .track_remote_bookmark(default_branch, remote_name);
-            if let Ok(commit) = checkout_tx.repo().store().get_commit(&commit_id) {
-                checkout_tx.check_out(&commit)?;
+            if args.bare {
+                checkout_tx.finish(ui, "track git remote's default branch")?;
+            } else {
+                if let Ok(commit) = checkout_tx.repo().store().get_commit(&commit_id) {
+                    checkout_tx.check_out(&commit)?;
+                }
+                checkout_tx.finish(ui, "check out git remote's default branch")?;
             }
-            checkout_tx.finish(ui, "check out git remote's default branch")?;
         }
     }
+    if args.bare {
+        let mut tx = workspace_command.start_transaction();
+        let workspace_id = tx.base_workspace_helper().workspace_id().clone();
+        tx.repo_mut().remove_wc_commit(&workspace_id)?;
+        tx.finish(ui, "remove working copy of bare repo")?;
+    }
     Ok(())
 }
 
@@ -215,14 +232,15 @@ fn do_git_clone(
     git_repo.remote(remote_name, source).unwrap();
     let mut fetch_tx = workspace_command.start_transaction();
 
-    let stats = with_remote_git_callbacks(ui, None, |cb| {
+    let git_settings = command.settings().git_settings();
+    let stats = with_remote_git_callbacks(ui, None, &git_settings, Some(remote_name), |cb| {
         git::fetch(
             fetch_tx.repo_mut(),
             &git_repo,
             remote_name,
             &[StringPattern::everything()],
             cb,
-            &command.settings().git_settings(),
+            &git_settings,
             depth,
         )
     })
@@ -235,6 +253,12 @@ fn do_git_clone(
         GitFetchError::InvalidBranchPattern => {
             unreachable!("we didn't provide any globs")
         }
+        GitFetchError::NoSuchRemoteRef(_) => {
+            unreachable!("we didn't fetch a specific ref")
+        }
+        GitFetchError::InvalidRefSpec(_) | GitFetchError::UnsupportedFetchRefSpec { .. } => {
+            user_error(err)
+        }
     })?;
     print_git_import_stats(ui, fetch_tx.repo(), &stats.import_stats, true)?;
     fetch_tx.finish(ui, "fetch from git remote into empty repo")?;
@@ -15,6 +15,7 @@
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
 use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::git::RefSpec;
 use jj_lib::repo::Repo;
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
@@ -60,6 +61,30 @@ pub struct GitFetchArgs {
     /// Fetch from all remotes
     #[arg(long, conflicts_with = "remotes")]
     all_remotes: bool,
+    /// Delete remote bookmarks that no longer exist on the remote
+    ///
+    /// This defaults to the `git.fetch-prune` setting, which can be overridden
+    /// for a remote by `remotes.<name>.fetch-prune`. Pruning is enabled if
+    /// neither is configured.
+    #[arg(long, conflicts_with = "no_prune")]
+    prune: bool,
+    /// Keep remote bookmarks that no longer exist on the remote
+    #[arg(long)]
+    no_prune: bool,
+    /// Fetch the refs matched by the refspec instead of the branches (can be
+    /// repeated)
+    ///
+    /// For example, `--refspec '+refs/heads/team/*:refs/remotes/origin/team/*'`
+    /// fetches only the branches under `team/`. The destinations must be under
+    /// `refs/remotes/<remote>/`. This overrides the
+    /// `remotes.<name>.fetch-refspecs` setting.
+    #[arg(
+        long = "refspec",
+        value_name = "REFSPEC",
+        value_parser = RefSpec::parse,
+        conflicts_with = "branch",
+    )]
+    refspecs: Vec<RefSpec>,
 }
 
 #[tracing::instrument(skip(ui, command))]
@@ -78,7 +103,20 @@ pub fn cmd_git_fetch(
         args.remotes.clone()
     };
     let mut tx = workspace_command.start_transaction();
-    git_fetch(ui, &mut tx, &git_repo, &remotes, &args.branch)?;
+    let prune = match (args.prune, args.no_prune) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    };
+    git_fetch(
+        ui,
+        &mut tx,
+        &git_repo,
+        &remotes,
+        &args.branch,
+        prune,
+        &args.refspecs,
+    )?;
     tx.finish(
         ui,
         format!("fetch from git remote(s) {}", remotes.iter().join(",")),
@@ -17,6 +17,7 @@ use jj_lib::git;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::git_util::print_git_import_stats;
+use crate::git_util::print_imported_stashes;
 use crate::ui::Ui;
 
 /// Update repo with changes made in the underlying Git repo
@@ -24,12 +25,19 @@ use crate::ui::Ui;
 /// If a working-copy commit gets abandoned, it will be given a new, empty
 /// commit. This is true in general; it is not specific to this command.
 #[derive(clap::Args, Clone, Debug)]
-pub struct GitImportArgs {}
+pub struct GitImportArgs {
+    /// Also import the Git stashes as new changes
+    ///
+    /// Each stash becomes a change on top of the commit it was created from.
+    /// Stashes which were already imported are skipped.
+    #[arg(long)]
+    stashes: bool,
+}
 
 pub fn cmd_git_import(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &GitImportArgs,
+    args: &GitImportArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let mut tx = workspace_command.start_transaction();
@@ -38,6 +46,10 @@ pub fn cmd_git_import(
     git::import_head(tx.repo_mut())?;
     let stats = git::import_refs(tx.repo_mut(), &command.settings().git_settings())?;
     print_git_import_stats(ui, tx.repo(), &stats, true)?;
+    if args.stashes {
+        let commits = git::import_stashes(tx.repo_mut(), command.settings())?;
+        print_imported_stashes(ui, &tx, &commits)?;
+    }
     tx.finish(ui, "import git refs")?;
     Ok(())
 }
@@ -39,6 +39,7 @@ use crate::git_util::get_git_repo;
 use crate::git_util::is_colocated_git_workspace;
 use crate::git_util::print_failed_git_export;
 use crate::git_util::print_git_import_stats;
+use crate::git_util::print_imported_stashes;
 use crate::ui::Ui;
 
 /// Create a new Git backed repo.
@@ -75,6 +76,13 @@ pub struct GitInitArgs {
     /// This option is mutually exclusive with `--colocate`.
     #[arg(long, conflicts_with = "colocate", value_hint = clap::ValueHint::DirPath)]
     git_repo: Option<String>,
+
+    /// Import the stashes of the existing Git repo as new changes
+    ///
+    /// Each stash becomes a change on top of the commit it was created from,
+    /// so that stashed work isn't lost when switching to `jj`.
+    #[arg(long)]
+    import_stashes: bool,
 }
 
 pub fn cmd_git_init(
@@ -100,6 +108,7 @@ pub fn cmd_git_init(
         &wc_path,
         args.colocate,
         args.git_repo.as_deref(),
+        args.import_stashes,
     )?;
 
     let relative_wc_path = file_util::relative_path(cwd, &wc_path);
@@ -118,6 +127,7 @@ pub fn do_init(
     workspace_root: &Path,
     colocate: bool,
     git_repo: Option<&str>,
+    import_stashes: bool,
 ) -> Result<(), CommandError> {
     #[derive(Clone, Debug)]
     enum GitInitMode {
@@ -183,6 +193,14 @@ pub fn do_init(
                     tx.finish(ui, "import git head")?;
                 }
             }
+            if import_stashes {
+                let mut tx = workspace_command.start_transaction();
+                let commits = git::import_stashes(tx.repo_mut(), command.settings())?;
+                print_imported_stashes(ui, &tx, &commits)?;
+                if !commits.is_empty() {
+                    tx.finish(ui, "import git stashes")?;
+                }
+            }
             print_trackable_remote_bookmarks(ui, workspace_command.repo().view())?;
         }
         GitInitMode::Internal => {
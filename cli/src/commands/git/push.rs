@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
 use std::io;
@@ -21,10 +22,14 @@ use clap::ArgGroup;
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
 use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::git::GitBranchPushTargets;
 use jj_lib::git::GitPushError;
+use jj_lib::git::GitRefUpdate;
+use jj_lib::git::GitTagSignError;
+use jj_lib::git::SignedTag;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::RefTarget;
 use jj_lib::refs::classify_bookmark_push_action;
@@ -46,6 +51,7 @@ use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
+use crate::commands::bookmark::ProtectedBookmarks;
 use crate::commands::git::get_single_remote;
 use crate::complete;
 use crate::formatter::Formatter;
@@ -60,7 +66,8 @@ use crate::ui::Ui;
 /// By default, pushes tracking bookmarks pointing to
 /// `remote_bookmarks(remote=<remote>)..@`. Use `--bookmark` to push specific
 /// bookmarks. Use `--all` to push all bookmarks. Use `--change` to generate
-/// bookmark names based on the change IDs of specific commits.
+/// bookmark names based on the change IDs of specific commits. Use `--stack`
+/// to do the same for every commit in a stack of changes.
 ///
 /// Before the command actually moves, creates, or deletes a remote bookmark, it
 /// makes several [safety checks]. If there is a problem, you may need to run
@@ -74,7 +81,7 @@ use crate::ui::Ui;
 ///     https://martinvonz.github.io/jj/latest/bookmarks/#conflicts
 
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("specific").args(&["bookmark", "change", "revisions"]).multiple(true)))]
+#[command(group(ArgGroup::new("specific").args(&["bookmark", "change", "stack", "revisions"]).multiple(true)))]
 #[command(group(ArgGroup::new("what").args(&["all", "deleted", "tracked"]).conflicts_with("specific")))]
 pub struct GitPushArgs {
     /// The remote to push to (only named remotes are supported)
@@ -95,7 +102,7 @@ pub struct GitPushArgs {
         long, short,
         alias = "branch",
         value_parser = StringPattern::parse,
-        add = ArgValueCandidates::new(complete::local_bookmarks),
+        add = ArgValueCandidates::new(complete::pushable_bookmarks),
     )]
     bookmark: Vec<StringPattern>,
     /// Push all bookmarks (including new and deleted bookmarks)
@@ -137,6 +144,54 @@ pub struct GitPushArgs {
     /// names.
     #[arg(long, short)]
     change: Vec<RevisionArg>,
+    /// Push every commit in these revisions by creating bookmarks based on
+    /// their change IDs (can be repeated)
+    ///
+    /// The bookmarks are pushed in dependency order, parents first. For each
+    /// commit, a line with the bookmark name, the commit ID, and the base it
+    /// should be reviewed against is printed to stdout, separated by tabs. The
+    /// base is the bookmark of the parent commit if the parent is part of the
+    /// stack or has a local bookmark, and the parent's commit ID otherwise.
+    /// External tools can use this to create chained pull requests.
+    #[arg(long, value_name = "REVSETS")]
+    stack: Vec<RevisionArg>,
+    /// Push the commit specified by `--revisions` (or `@` by default) to
+    /// Gerrit for review on this branch
+    ///
+    /// The commit and its ancestors are pushed to the `refs/for/<BRANCH>` ref
+    /// of the remote. No bookmarks are created or updated.
+    #[arg(
+        long = "for",
+        value_name = "BRANCH",
+        conflicts_with_all = ["what", "bookmark", "change", "stack"],
+    )]
+    for_branch: Option<String>,
+    /// Add a reviewer to the changes pushed with `--for` (can be repeated)
+    #[arg(long, value_name = "REVIEWER", requires = "for_branch")]
+    reviewer: Vec<String>,
+    /// Set the topic of the changes pushed with `--for`
+    #[arg(long, requires = "for_branch")]
+    topic: Option<String>,
+    /// Push tags matching the pattern along with the bookmarks (can be
+    /// repeated)
+    ///
+    /// Tags are pushed as they are in the Git repository, so annotated and
+    /// signed tags stay as they are. Tags which already exist on the remote at
+    /// a different location are never overwritten.
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` prefix to
+    /// select tags by wildcard pattern. For details, see
+    /// https://martinvonz.github.io/jj/latest/revsets#string-patterns.
+    #[arg(long, value_parser = StringPattern::parse)]
+    tag: Vec<StringPattern>,
+    /// Replace the lightweight tags pushed with `--tag` with annotated tags
+    /// signed by the configured `signing.backend`
+    #[arg(long, requires = "tag")]
+    sign_tags: bool,
+    /// Allow deleting or force-pushing bookmarks protected by
+    /// `bookmarks.protected`
+    #[arg(long)]
+    allow_protected: bool,
     /// Only display what will change on the remote
     #[arg(long)]
     dry_run: bool,
@@ -171,11 +226,24 @@ pub fn cmd_git_push(
     } else {
         get_default_push_remote(ui, command.settings(), &git_repo)?
     };
+    if let Some(branch) = &args.for_branch {
+        return push_for_review(
+            ui,
+            command,
+            &workspace_command,
+            &git_repo,
+            &remote,
+            branch,
+            args,
+        );
+    }
 
     let mut tx = workspace_command.start_transaction();
     let view = tx.repo().view();
     let tx_description;
     let mut bookmark_updates = vec![];
+    let mut stack_commits = vec![];
+    let mut stack_bookmark_names = vec![];
     if args.all {
         for (bookmark_name, targets) in view.local_remote_bookmarks(&remote) {
             let allow_new = true; // implied by --all
@@ -227,15 +295,31 @@ pub fn cmd_git_push(
         } else {
             command.settings().push_bookmark_prefix()
         };
+        let change_commits = if args.change.is_empty() {
+            // NOTE: we don't want resolve_some_revsets_default_single to fail if the
+            // changes argument wasn't provided, so handle that
+            vec![]
+        } else {
+            tx.base_workspace_helper()
+                .resolve_some_revsets_default_single(ui, &args.change)?
+                .into_iter()
+                .collect_vec()
+        };
         let change_bookmark_names =
-            update_change_bookmarks(ui, &mut tx, &args.change, &bookmark_prefix)?;
-        let change_bookmarks = change_bookmark_names.iter().map(|bookmark_name| {
-            let targets = LocalAndRemoteRef {
-                local_target: tx.repo().view().get_local_bookmark(bookmark_name),
-                remote_ref: tx.repo().view().get_remote_bookmark(bookmark_name, &remote),
-            };
-            (bookmark_name.as_ref(), targets)
-        });
+            update_change_bookmarks(ui, &mut tx, &change_commits, &bookmark_prefix)?;
+        stack_commits = resolve_stack_commits(ui, tx.base_workspace_helper(), &args.stack)?;
+        stack_bookmark_names =
+            update_change_bookmarks(ui, &mut tx, &stack_commits, &bookmark_prefix)?;
+        let change_bookmarks = change_bookmark_names
+            .iter()
+            .chain(&stack_bookmark_names)
+            .map(|bookmark_name| {
+                let targets = LocalAndRemoteRef {
+                    local_target: tx.repo().view().get_local_bookmark(bookmark_name),
+                    remote_ref: tx.repo().view().get_remote_bookmark(bookmark_name, &remote),
+                };
+                (bookmark_name.as_ref(), targets)
+            });
         let view = tx.repo().view();
         for (bookmark_name, targets) in change_bookmarks {
             if !seen_bookmarks.insert(bookmark_name) {
@@ -267,8 +351,11 @@ pub fn cmd_git_push(
             }
         }
 
-        let use_default_revset =
-            args.bookmark.is_empty() && args.change.is_empty() && args.revisions.is_empty();
+        let use_default_revset = args.bookmark.is_empty()
+            && args.change.is_empty()
+            && args.stack.is_empty()
+            && args.revisions.is_empty()
+            && args.tag.is_empty();
         let bookmarks_targeted = find_bookmarks_targeted_by_revisions(
             ui,
             tx.base_workspace_helper(),
@@ -298,34 +385,263 @@ pub fn cmd_git_push(
             &remote
         );
     }
-    if bookmark_updates.is_empty() {
+    let tags = find_tags_to_push(tx.repo().view(), &args.tag)?;
+    if bookmark_updates.is_empty() && tags.is_empty() {
         writeln!(ui.status(), "Nothing changed.")?;
+        print_stack_mapping(ui, tx.repo().view(), &stack_commits, &stack_bookmark_names)?;
         return Ok(());
     }
+    if !args.allow_protected {
+        let repo = tx.repo();
+        let protected_bookmarks = ProtectedBookmarks::from_settings(command.settings())?;
+        protected_bookmarks.check(
+            bookmark_updates
+                .iter()
+                .filter(|(_, update)| update.new_target.is_none())
+                .map(|(name, _)| name.as_str()),
+            |name| format!("Refusing to delete protected bookmark from the remote: {name}"),
+        )?;
+        protected_bookmarks.check(
+            bookmark_updates
+                .iter()
+                .filter(
+                    |(_, update)| match (&update.old_target, &update.new_target) {
+                        (Some(old), Some(new)) => !repo.index().is_ancestor(old, new),
+                        _ => false,
+                    },
+                )
+                .map(|(name, _)| name.as_str()),
+            |name| format!("Refusing to force-push protected bookmark: {name}"),
+        )?;
+    }
+    if args.sign_tags && !tx.repo().store().signer().can_sign() {
+        return Err(user_error_with_hint(
+            "Cannot sign tags because no signing backend is configured",
+            "Set `signing.backend` to sign tags.",
+        ));
+    }
 
-    validate_commits_ready_to_push(ui, &bookmark_updates, &remote, &tx, command, args)?;
+    let new_heads = bookmark_updates
+        .iter()
+        .filter_map(|(_, update)| update.new_target.clone())
+        .chain(tags.iter().map(|(_, id)| id.clone()))
+        .collect_vec();
+    validate_commits_ready_to_push(
+        ui,
+        new_heads,
+        &remote,
+        tx.base_workspace_helper(),
+        command,
+        args.allow_empty_description,
+        args.allow_private,
+    )?;
     if let Some(mut formatter) = ui.status_formatter() {
         writeln!(formatter, "Changes to push to {remote}:")?;
         print_commits_ready_to_push(formatter.as_mut(), tx.repo(), &bookmark_updates)?;
+        for (tag_name, commit_id) in &tags {
+            writeln!(
+                formatter,
+                "  Push tag {tag_name} at {}",
+                short_commit_hash(commit_id)
+            )?;
+        }
     }
 
     if args.dry_run {
         writeln!(ui.status(), "Dry-run requested, not pushing.")?;
+        print_stack_mapping(ui, tx.repo().view(), &stack_commits, &stack_bookmark_names)?;
         return Ok(());
     }
 
+    let mut signed_tags = vec![];
+    if args.sign_tags {
+        let settings = command.settings();
+        let signer = tx.repo().store().signer();
+        let key = settings.sign_settings().key;
+        for (tag_name, _) in &tags {
+            let result = git::sign_tag(
+                &git_repo,
+                tag_name,
+                &settings.signature(),
+                signer,
+                key.as_deref(),
+            );
+            let signed_tag = match result {
+                Ok(signed_tag) => signed_tag,
+                Err(err) => {
+                    unsign_tags(&git_repo, &signed_tags)?;
+                    return Err(match err {
+                        GitTagSignError::InternalGitError(err) => map_git_error(err),
+                        _ => user_error(err),
+                    });
+                }
+            };
+            if let Some(signed_tag) = signed_tag {
+                writeln!(ui.status(), "Signed tag {tag_name}")?;
+                signed_tags.push(signed_tag);
+            }
+        }
+    }
+
     let targets = GitBranchPushTargets {
         branch_updates: bookmark_updates,
+        tags: tags.into_iter().map(|(name, _)| name).collect(),
     };
     let mut writer = GitSidebandProgressMessageWriter::new(ui);
     let mut sideband_progress_callback = |progress_message: &[u8]| {
         _ = writer.write(ui, progress_message);
     };
-    with_remote_git_callbacks(ui, Some(&mut sideband_progress_callback), |cb| {
-        git::push_branches(tx.repo_mut(), &git_repo, &remote, &targets, cb)
-    })
-    .map_err(|err| match err {
+    let git_settings = tx.settings().git_settings();
+    let push_result = with_remote_git_callbacks(
+        ui,
+        Some(&mut sideband_progress_callback),
+        &git_settings,
+        Some(&remote),
+        |cb| {
+            git::push_branches(
+                tx.repo_mut(),
+                &git_repo,
+                &remote,
+                &targets,
+                cb,
+                &git_settings,
+            )
+        },
+    );
+    if push_result.is_err() {
+        // Don't leave behind signed tags that weren't pushed.
+        unsign_tags(&git_repo, &signed_tags)?;
+    }
+    push_result.map_err(map_push_error)?;
+    writer.flush(ui)?;
+    // Bookmarks pushed to refs mapped by push refspecs don't update the view.
+    if tx.repo().has_changes() {
+        tx.finish(ui, tx_description)?;
+    }
+    print_stack_mapping(
+        ui,
+        workspace_command.repo().view(),
+        &stack_commits,
+        &stack_bookmark_names,
+    )?;
+    Ok(())
+}
+
+fn unsign_tags(git_repo: &git2::Repository, signed_tags: &[SignedTag]) -> Result<(), CommandError> {
+    for signed_tag in signed_tags {
+        git::unsign_tag(git_repo, signed_tag).map_err(map_git_error)?;
+    }
+    Ok(())
+}
+
+/// Pushes a single commit to Gerrit's `refs/for/<branch>` ref for review.
+fn push_for_review(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+    git_repo: &git2::Repository,
+    remote: &str,
+    branch: &str,
+    args: &GitPushArgs,
+) -> Result<(), CommandError> {
+    let revisions = if args.revisions.is_empty() {
+        vec![RevisionArg::AT]
+    } else {
+        args.revisions.clone()
+    };
+    let expression = workspace_command
+        .parse_union_revsets(ui, &revisions)?
+        .expression()
+        .heads();
+    let heads: Vec<CommitId> = workspace_command
+        .attach_revset_evaluator(expression)
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    let head = match heads.as_slice() {
+        [head] => head.clone(),
+        [] => {
+            writeln!(ui.status(), "Nothing changed.")?;
+            return Ok(());
+        }
+        _ => {
+            return Err(user_error_with_hint(
+                "Revisions to push for review must have a single head",
+                "Push each stack of changes separately.",
+            ));
+        }
+    };
+    validate_commits_ready_to_push(
+        ui,
+        vec![head.clone()],
+        remote,
+        workspace_command,
+        command,
+        args.allow_empty_description,
+        args.allow_private,
+    )?;
+
+    let mut options = args.reviewer.iter().map(|r| format!("r={r}")).collect_vec();
+    if let Some(topic) = &args.topic {
+        options.push(format!("topic={topic}"));
+    }
+    let mut qualified_name = format!("refs/for/{branch}");
+    if !options.is_empty() {
+        qualified_name = format!("{qualified_name}%{}", options.join(","));
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(formatter, "Changes to push to {remote}:")?;
+        writeln!(
+            formatter,
+            "  Push commit {} to {qualified_name}",
+            short_commit_hash(&head)
+        )?;
+    }
+    if args.dry_run {
+        writeln!(ui.status(), "Dry-run requested, not pushing.")?;
+        return Ok(());
+    }
+
+    let update = GitRefUpdate {
+        qualified_name,
+        // Gerrit's magic refs never exist on the remote.
+        expected_current_target: None,
+        new_target: Some(head),
+    };
+    let mut writer = GitSidebandProgressMessageWriter::new(ui);
+    let mut sideband_progress_callback = |progress_message: &[u8]| {
+        _ = writer.write(ui, progress_message);
+    };
+    let git_settings = command.settings().git_settings();
+    with_remote_git_callbacks(
+        ui,
+        Some(&mut sideband_progress_callback),
+        &git_settings,
+        Some(remote),
+        |cb| {
+            git::push_updates(
+                workspace_command.repo().as_ref(),
+                git_repo,
+                remote,
+                &[update],
+                cb,
+            )
+        },
+    )
+    .map_err(map_push_error)?;
+    writer.flush(ui)?;
+    Ok(())
+}
+
+pub(crate) fn map_push_error(err: GitPushError) -> CommandError {
+    match err {
         GitPushError::InternalGitError(err) => map_git_error(err),
+        GitPushError::TagAlreadyExists(tags) => user_error_with_hint(
+            format!(
+                "Refusing to move tags that already exist on the remote: {}",
+                tags.join(", ")
+            ),
+            "Published tags shouldn't be moved. Create a new tag instead.",
+        ),
         GitPushError::RefInUnexpectedLocation(refs) => user_error_with_hint(
             format!(
                 "Refusing to push a bookmark that unexpectedly moved on the remote. Affected \
@@ -336,29 +652,22 @@ pub fn cmd_git_push(
              be, and push again.",
         ),
         _ => user_error(err),
-    })?;
-    writer.flush(ui)?;
-    tx.finish(ui, tx_description)?;
-    Ok(())
+    }
 }
 
 /// Validates that the commits that will be pushed are ready (have authorship
 /// information, are not conflicted, etc.)
-fn validate_commits_ready_to_push(
+pub(crate) fn validate_commits_ready_to_push(
     ui: &Ui,
-    bookmark_updates: &[(String, BookmarkPushUpdate)],
+    new_heads: Vec<CommitId>,
     remote: &str,
-    tx: &WorkspaceCommandTransaction,
+    workspace_helper: &WorkspaceCommandHelper,
     command: &CommandHelper,
-    args: &GitPushArgs,
+    allow_empty_description: bool,
+    allow_private: bool,
 ) -> Result<(), CommandError> {
-    let workspace_helper = tx.base_workspace_helper();
     let repo = workspace_helper.repo();
 
-    let new_heads = bookmark_updates
-        .iter()
-        .filter_map(|(_, update)| update.new_target.clone())
-        .collect_vec();
     let old_heads = repo
         .view()
         .remote_bookmarks(remote)
@@ -385,7 +694,7 @@ fn validate_commits_ready_to_push(
     {
         let commit = commit?;
         let mut reasons = vec![];
-        if commit.description().is_empty() && !args.allow_empty_description {
+        if commit.description().is_empty() && !allow_empty_description {
             reasons.push("it has no description");
         }
         if commit.author().name.is_empty()
@@ -403,7 +712,7 @@ fn validate_commits_ready_to_push(
             reasons.push("it has conflicts");
         }
         let is_private = is_private(commit.id())?;
-        if !args.allow_private && is_private {
+        if !allow_private && is_private {
             reasons.push("it is private");
         }
         if !reasons.is_empty() {
@@ -417,7 +726,7 @@ fn validate_commits_ready_to_push(
                 workspace_helper.write_commit_summary(formatter, &commit)?;
                 Ok(())
             });
-            if !args.allow_private && is_private {
+            if !allow_private && is_private {
                 error.add_hint(format!(
                     "Configured git.private-commits: '{}'",
                     settings
@@ -431,7 +740,7 @@ fn validate_commits_ready_to_push(
     Ok(())
 }
 
-fn print_commits_ready_to_push(
+pub(crate) fn print_commits_ready_to_push(
     formatter: &mut dyn Formatter,
     repo: &dyn Repo,
     bookmark_updates: &[(String, BookmarkPushUpdate)],
@@ -493,7 +802,7 @@ fn print_commits_ready_to_push(
     Ok(())
 }
 
-fn get_default_push_remote(
+pub(crate) fn get_default_push_remote(
     ui: &Ui,
     settings: &UserSettings,
     git_repo: &git2::Repository,
@@ -515,7 +824,7 @@ fn get_default_push_remote(
 }
 
 #[derive(Clone, Debug)]
-struct RejectedBookmarkUpdateReason {
+pub(crate) struct RejectedBookmarkUpdateReason {
     message: String,
     hint: Option<String>,
 }
@@ -539,7 +848,7 @@ impl From<RejectedBookmarkUpdateReason> for CommandError {
     }
 }
 
-fn classify_bookmark_update(
+pub(crate) fn classify_bookmark_update(
     bookmark_name: &str,
     remote_name: &str,
     targets: LocalAndRemoteRef,
@@ -586,20 +895,11 @@ fn classify_bookmark_update(
 fn update_change_bookmarks(
     ui: &Ui,
     tx: &mut WorkspaceCommandTransaction,
-    changes: &[RevisionArg],
+    commits: &[Commit],
     bookmark_prefix: &str,
 ) -> Result<Vec<String>, CommandError> {
-    if changes.is_empty() {
-        // NOTE: we don't want resolve_some_revsets_default_single to fail if the
-        // changes argument wasn't provided, so handle that
-        return Ok(vec![]);
-    }
-
     let mut bookmark_names = Vec::new();
-    let workspace_command = tx.base_workspace_helper();
-    let all_commits = workspace_command.resolve_some_revsets_default_single(ui, changes)?;
-
-    for commit in all_commits {
+    for commit in commits {
         let workspace_command = tx.base_workspace_helper();
         let short_change_id = short_change_hash(commit.change_id());
         let mut bookmark_name = format!("{bookmark_prefix}{}", commit.change_id().hex());
@@ -628,6 +928,64 @@ fn update_change_bookmarks(
     Ok(bookmark_names)
 }
 
+/// Resolves the commits of the stacks to push, parents first.
+fn resolve_stack_commits(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    stack: &[RevisionArg],
+) -> Result<Vec<Commit>, CommandError> {
+    if stack.is_empty() {
+        return Ok(vec![]);
+    }
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, stack)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    if commits.is_empty() {
+        return Err(user_error("No revisions in the stack to push"));
+    }
+    if let Some(commit) = commits
+        .iter()
+        .find(|commit| commit.id() == workspace_command.repo().store().root_commit_id())
+    {
+        return Err(user_error(format!(
+            "Cannot push the root commit {}",
+            short_commit_hash(commit.id())
+        )));
+    }
+    // Revsets are ordered children first.
+    Ok(commits.into_iter().rev().collect())
+}
+
+/// Prints the bookmark, commit ID, and base of each commit in the stack.
+fn print_stack_mapping(
+    ui: &Ui,
+    view: &View,
+    commits: &[Commit],
+    bookmark_names: &[String],
+) -> io::Result<()> {
+    let bookmarks_by_commit: HashMap<&CommitId, &str> = commits
+        .iter()
+        .map(|commit| commit.id())
+        .zip(bookmark_names.iter().map(String::as_str))
+        .collect();
+    let mut stdout = ui.stdout();
+    for (commit, bookmark_name) in commits.iter().zip(bookmark_names) {
+        let parent_id = &commit.parent_ids()[0];
+        let base = bookmarks_by_commit
+            .get(parent_id)
+            .copied()
+            .or_else(|| {
+                view.local_bookmarks_for_commit(parent_id)
+                    .map(|(name, _)| name)
+                    .next()
+            })
+            .map_or_else(|| parent_id.hex(), str::to_owned);
+        writeln!(stdout, "{bookmark_name}\t{}\t{base}", commit.id().hex())?;
+    }
+    Ok(())
+}
+
 fn find_bookmarks_to_push<'a>(
     view: &'a View,
     bookmark_patterns: &[StringPattern],
@@ -659,6 +1017,40 @@ fn find_bookmarks_to_push<'a>(
     }
 }
 
+fn find_tags_to_push(
+    view: &View,
+    tag_patterns: &[StringPattern],
+) -> Result<Vec<(String, CommitId)>, CommandError> {
+    let mut tags = vec![];
+    let mut unmatched_patterns = vec![];
+    for pattern in tag_patterns {
+        let mut matches = view.tags_matching(pattern).peekable();
+        if matches.peek().is_none() {
+            unmatched_patterns.push(pattern);
+        }
+        for (tag_name, target) in matches {
+            if tags.iter().any(|(name, _)| name == tag_name) {
+                continue;
+            }
+            let Some(commit_id) = target.as_normal() else {
+                return Err(user_error_with_hint(
+                    format!("Tag {tag_name} is conflicted"),
+                    "Run `jj tag list` to inspect, and use `git tag` to resolve conflicts.",
+                ));
+            };
+            tags.push((tag_name.to_owned(), commit_id.clone()));
+        }
+    }
+    match &unmatched_patterns[..] {
+        [] => Ok(tags),
+        [pattern] if pattern.is_exact() => Err(user_error(format!("No such tag: {pattern}"))),
+        patterns => Err(user_error(format!(
+            "No matching tags for patterns: {}",
+            patterns.iter().join(", ")
+        ))),
+    }
+}
+
 fn find_bookmarks_targeted_by_revisions<'a>(
     ui: &Ui,
     workspace_command: &'a WorkspaceCommandHelper,
@@ -12,15 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
 use std::slice;
 
-use clap::ArgGroup;
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
@@ -31,19 +36,33 @@ use crate::ui::Ui;
 /// This excludes changes from other commits by temporarily rebasing `--from`
 /// onto `--to`'s parents. If you wish to compare the same change across
 /// versions, consider `jj evolog -p` instead.
+///
+/// If neither `--from` nor `--to` is given, the revisions are taken from the
+/// positional arguments, as in `jj interdiff FROM [TO] [PATHS]...`. `TO`
+/// defaults to `@`. If `FROM` and `TO`
+/// resolve to the same number of revisions, each revision in `FROM` is
+/// compared to the corresponding revision in `TO`, oldest first.
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("to_diff").args(&["from", "to"]).multiple(true).required(true)))]
 #[command(mut_arg("ignore_all_space", |a| a.short('w')))]
 #[command(mut_arg("ignore_space_change", |a| a.short('b')))]
 pub(crate) struct InterdiffArgs {
-    /// Show changes from this revision
+    /// Show changes from this revision [default: @]
     #[arg(long, short, add = ArgValueCandidates::new(complete::all_revisions))]
     from: Option<RevisionArg>,
-    /// Show changes to this revision
+    /// Show changes to this revision [default: @]
     #[arg(long, short, add = ArgValueCandidates::new(complete::all_revisions))]
     to: Option<RevisionArg>,
+    /// The revisions to compare unless `--from` or `--to` is given, followed
+    /// by paths to restrict the diff to
+    #[arg(
+        value_name = "ARGS",
+        value_hint = clap::ValueHint::AnyPath,
+        add = ArgValueCompleter::new(complete::interdiff_files),
+    )]
+    args: Vec<String>,
     /// Restrict the diff to these paths
     #[arg(
+        last = true,
         value_hint = clap::ValueHint::AnyPath,
         add = ArgValueCompleter::new(complete::interdiff_files),
     )]
@@ -59,22 +78,86 @@ pub(crate) fn cmd_interdiff(
     args: &InterdiffArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
-    let from =
-        workspace_command.resolve_single_rev(ui, args.from.as_ref().unwrap_or(&RevisionArg::AT))?;
-    let to =
-        workspace_command.resolve_single_rev(ui, args.to.as_ref().unwrap_or(&RevisionArg::AT))?;
+    // Without `--from` and `--to`, the leading positional arguments are the
+    // revisions to compare. Otherwise, all of them are paths.
+    let mut positional = args.args.iter().cloned();
+    let (from_arg, to_arg) = if args.from.is_none() && args.to.is_none() {
+        let from = positional.next().ok_or_else(|| {
+            user_error_with_hint(
+                "No revision to compare from",
+                "Use `jj interdiff FROM [TO]` or `jj interdiff --from FROM`",
+            )
+        })?;
+        let to = positional.next().map_or(RevisionArg::AT, RevisionArg::from);
+        (RevisionArg::from(from), to)
+    } else {
+        (
+            args.from.clone().unwrap_or(RevisionArg::AT),
+            args.to.clone().unwrap_or(RevisionArg::AT),
+        )
+    };
+    let paths = positional.chain(args.paths.iter().cloned()).collect_vec();
+    let pairs = resolve_pairs(ui, &workspace_command, &from_arg, &to_arg)?;
+
     let matcher = workspace_command
-        .parse_file_patterns(ui, &args.paths)?
+        .parse_file_patterns(ui, &paths)?
         .to_matcher();
     let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
     ui.request_pager();
-    diff_renderer.show_inter_diff(
-        ui,
-        ui.stdout_formatter().as_mut(),
-        slice::from_ref(&from),
-        &to,
-        matcher.as_ref(),
-        ui.term_width(),
-    )?;
+    let mut formatter = ui.stdout_formatter();
+    for (i, (from, to)) in pairs.iter().enumerate() {
+        if pairs.len() > 1 {
+            if i > 0 {
+                writeln!(formatter)?;
+            }
+            write!(formatter, "From: ")?;
+            workspace_command.write_commit_summary(formatter.as_mut(), from)?;
+            writeln!(formatter)?;
+            write!(formatter, "To:   ")?;
+            workspace_command.write_commit_summary(formatter.as_mut(), to)?;
+            writeln!(formatter)?;
+        }
+        diff_renderer.show_inter_diff(
+            ui,
+            formatter.as_mut(),
+            slice::from_ref(from),
+            to,
+            matcher.as_ref(),
+            ui.term_width(),
+        )?;
+    }
     Ok(())
 }
+
+/// Resolves the revisions to compare. Multiple revisions are paired up in
+/// order, oldest first.
+fn resolve_pairs(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    from_arg: &RevisionArg,
+    to_arg: &RevisionArg,
+) -> Result<Vec<(Commit, Commit)>, CommandError> {
+    let resolve = |arg: &RevisionArg| -> Result<Vec<Commit>, CommandError> {
+        let commits: Vec<Commit> = workspace_command
+            .parse_revset(ui, arg)?
+            .evaluate_to_commits()?
+            .try_collect()?;
+        Ok(commits.into_iter().rev().collect())
+    };
+    let from_commits = resolve(from_arg)?;
+    let to_commits = resolve(to_arg)?;
+    if from_commits.len() <= 1 && to_commits.len() <= 1 {
+        // Resolve single revisions in the usual way to report empty revsets.
+        let from = workspace_command.resolve_single_rev(ui, from_arg)?;
+        let to = workspace_command.resolve_single_rev(ui, to_arg)?;
+        return Ok(vec![(from, to)]);
+    }
+    if from_commits.len() != to_commits.len() {
+        return Err(user_error(format!(
+            "Cannot pair up revisions: {} to compare from, but {} to compare to",
+            from_commits.len(),
+            to_commits.len()
+        )));
+    }
+    Ok(from_commits.into_iter().zip(to_commits).collect())
+}
@@ -12,12 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use bstr::ByteVec as _;
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::graph::GraphEdge;
 use jj_lib::graph::GraphEdgeType;
+use jj_lib::graph::GraphNode;
 use jj_lib::graph::ReverseGraphIterator;
 use jj_lib::graph::TopoGroupedGraphIterator;
 use jj_lib::repo::Repo;
@@ -25,17 +33,21 @@ use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::revset::RevsetIteratorExt;
+use jj_lib::revset::UserRevsetExpression;
 use jj_lib::settings::UserSettings;
+use jj_lib::str_util::StringPattern;
 use tracing::instrument;
 
 use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::RevisionArg;
+use crate::command_error::cli_error;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
+use crate::formatter::PlainTextFormatter;
 use crate::graphlog::get_graphlog;
 use crate::graphlog::Edge;
 use crate::graphlog::GraphStyle;
@@ -51,7 +63,10 @@ use crate::ui::Ui;
 /// syntax.
 ///
 /// Spans of revisions that are not included in the graph per `--revisions` are
-/// rendered as a synthetic node labeled "(elided revisions)".
+/// rendered as a synthetic node labeled "(elided revisions)". With
+/// `--collapse-linear`, runs of revisions without forks, merges, bookmarks, or
+/// working copies are also collapsed into a node showing the number of
+/// revisions in the run.
 ///
 /// The working-copy commit is indicated by a `@` symbol in the graph. Immutable
 /// revisions (https://martinvonz.github.io/jj/latest/config/#set-of-immutable-commits)
@@ -72,11 +87,37 @@ pub(crate) struct LogArgs {
     )]
     paths: Vec<String>,
     /// Show revisions in the opposite order (older revisions first)
-    #[arg(long)]
+    ///
+    /// The graph is drawn bottom-up, so the working-copy commit is usually
+    /// printed last.
+    #[arg(long, visible_alias = "reverse")]
     reversed: bool,
+    /// Only show revisions pointed to by bookmarks, tags, or working copies
+    ///
+    /// The other revisions are omitted, but the graph still shows how the
+    /// remaining revisions are connected, similar to
+    /// `git log --simplify-by-decoration`. This is a quick way to get an
+    /// overview of the important points in the repository.
+    #[arg(long)]
+    simplify_by_decoration: bool,
+    /// Collapse linear runs of revisions in the graph
+    ///
+    /// Revisions with a single parent and a single child in the graph are
+    /// replaced with a node like "(3 revisions collapsed)", unless they have a
+    /// bookmark, tag, or working copy. Runs of a single revision are not
+    /// collapsed.
+    ///
+    /// If not specified, this defaults to the `ui.log-collapse-linear`
+    /// setting.
+    #[arg(long, conflicts_with = "no_graph")]
+    collapse_linear: bool,
     /// Limit number of revisions to show
     ///
-    /// Applied after revisions are filtered and reordered.
+    /// Applied after revisions are filtered and reordered. Use `--limit 0` to
+    /// show all revisions.
+    ///
+    /// If not specified and the output is a terminal, this defaults to the
+    /// `ui.log-default-limit` setting.
     #[arg(long, short = 'n')]
     limit: Option<usize>,
     // TODO: Delete `-l` alias in jj 0.25+
@@ -98,8 +139,30 @@ pub(crate) struct LogArgs {
     /// see https://martinvonz.github.io/jj/latest/templates/.
     ///
     /// If not specified, this defaults to the `templates.log` setting.
-    #[arg(long, short = 'T')]
+    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::commit_templates))]
     template: Option<String>,
+    /// Print the selected fields as tab-separated columns, one revision per
+    /// line
+    ///
+    /// This implies `--no-graph`. Tabs, newlines, and backslashes within a
+    /// field are escaped as `\t`, `\n`, and `\\` respectively, so each
+    /// revision is printed on exactly one line.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_enum,
+        conflicts_with_all = ["template", "patch"],
+    )]
+    columns: Vec<LogColumn>,
+    /// Write this delimiter after each revision
+    ///
+    /// Requires `--no-graph` or `--columns`. With `nul`, the output can be
+    /// split safely even if the template output contains newlines, similar to
+    /// `git log -z`. With `newline`, nothing is written after revisions whose
+    /// output already ends with a newline. With `--columns`, the delimiter
+    /// replaces the newline at the end of each row.
+    #[arg(long, value_enum, ignore_case = true)]
+    delimiter: Option<LogDelimiter>,
     /// Show patch
     #[arg(long, short = 'p')]
     patch: bool,
@@ -107,12 +170,165 @@ pub(crate) struct LogArgs {
     diff_format: DiffFormatArgs,
 }
 
+/// Field that can be selected by `jj log --columns`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+#[value(rename_all = "snake_case")]
+enum LogColumn {
+    /// Full change ID
+    ChangeId,
+    /// Full commit ID
+    CommitId,
+    /// Author name and email
+    Author,
+    /// First line of the description
+    Description,
+    /// Local bookmarks pointing to the revision, separated by commas
+    Bookmarks,
+}
+
+impl LogColumn {
+    fn template_text(self) -> &'static str {
+        match self {
+            LogColumn::ChangeId => "change_id",
+            LogColumn::CommitId => "commit_id",
+            LogColumn::Author => r#"author.name() ++ " <" ++ author.email() ++ ">""#,
+            LogColumn::Description => "description.first_line()",
+            LogColumn::Bookmarks => r#"local_bookmarks.map(|b| b.name()).join(",")"#,
+        }
+    }
+}
+
+/// Separator written after each revision by `jj log --delimiter`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum LogDelimiter {
+    /// Newline character
+    Newline,
+    /// NUL character
+    Nul,
+}
+
+impl LogDelimiter {
+    fn as_str(self) -> &'static str {
+        match self {
+            LogDelimiter::Newline => "\n",
+            LogDelimiter::Nul => "\0",
+        }
+    }
+}
+
+/// Revisions which have some kind of label attached to them.
+fn decorated_revisions() -> Rc<UserRevsetExpression> {
+    RevsetExpression::union_all(&[
+        RevsetExpression::bookmarks(StringPattern::everything()),
+        RevsetExpression::remote_bookmarks(
+            StringPattern::everything(),
+            StringPattern::everything(),
+            None,
+        ),
+        RevsetExpression::tags(StringPattern::everything()),
+        RevsetExpression::working_copies(),
+        RevsetExpression::git_head(),
+    ])
+}
+
+/// Removes the runs of at least two nodes that each have a single direct parent
+/// and a single direct child, and aren't `is_kept`. The child of each run gets
+/// an indirect edge to the parent of the run instead.
+///
+/// Returns the remaining nodes and the number of nodes removed between each
+/// `(child, parent)` pair.
+#[allow(clippy::type_complexity)]
+fn collapse_linear_runs(
+    nodes: Vec<GraphNode<CommitId>>,
+    is_kept: &dyn Fn(&CommitId) -> Result<bool, RevsetEvaluationError>,
+) -> Result<
+    (
+        Vec<GraphNode<CommitId>>,
+        HashMap<(CommitId, CommitId), usize>,
+    ),
+    RevsetEvaluationError,
+> {
+    let mut num_children: HashMap<&CommitId, usize> = HashMap::new();
+    let mut num_direct_children: HashMap<&CommitId, usize> = HashMap::new();
+    for edge in nodes.iter().flat_map(|(_, edges)| edges) {
+        match edge.edge_type {
+            GraphEdgeType::Missing => {}
+            GraphEdgeType::Direct => {
+                *num_children.entry(&edge.target).or_default() += 1;
+                *num_direct_children.entry(&edge.target).or_default() += 1;
+            }
+            GraphEdgeType::Indirect => {
+                *num_children.entry(&edge.target).or_default() += 1;
+            }
+        }
+    }
+    let mut linear_parents: HashMap<&CommitId, &CommitId> = HashMap::new();
+    for (id, edges) in &nodes {
+        let [edge] = edges.as_slice() else {
+            continue;
+        };
+        if edge.edge_type == GraphEdgeType::Direct
+            && num_children.get(id) == Some(&1)
+            && num_direct_children.get(id) == Some(&1)
+            && !is_kept(id)?
+        {
+            linear_parents.insert(id, &edge.target);
+        }
+    }
+
+    let mut collapsed: HashSet<&CommitId> = HashSet::new();
+    let mut runs = HashMap::new();
+    for (id, edges) in &nodes {
+        if linear_parents.contains_key(id) {
+            continue;
+        }
+        for edge in edges {
+            let mut run = vec![];
+            let mut target = &edge.target;
+            while let Some(&parent) = linear_parents.get(target) {
+                run.push(target);
+                target = parent;
+            }
+            if run.len() >= 2 {
+                runs.insert((id.clone(), target.clone()), run.len());
+                collapsed.extend(run);
+            }
+        }
+    }
+
+    let remaining_nodes = nodes
+        .iter()
+        .filter(|(id, _)| !collapsed.contains(id))
+        .map(|(id, edges)| {
+            let edges = edges
+                .iter()
+                .map(|edge| {
+                    let mut target = &edge.target;
+                    while collapsed.contains(target) {
+                        target = linear_parents[target];
+                    }
+                    if target == &edge.target {
+                        edge.clone()
+                    } else {
+                        GraphEdge::indirect(target.clone())
+                    }
+                })
+                .collect();
+            (id.clone(), edges)
+        })
+        .collect();
+    Ok((remaining_nodes, runs))
+}
+
 #[instrument(skip_all)]
 pub(crate) fn cmd_log(
     ui: &mut Ui,
     command: &CommandHelper,
     args: &LogArgs,
 ) -> Result<(), CommandError> {
+    if args.delimiter.is_some() && !args.no_graph && args.columns.is_empty() {
+        return Err(cli_error("--delimiter requires --no-graph or --columns"));
+    }
     let workspace_command = command.workspace_helper(ui)?;
 
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
@@ -133,6 +349,9 @@ pub(crate) fn cmd_log(
             let predicate = RevsetFilterPredicate::File(fileset_expression.clone());
             expression.intersect_with(&RevsetExpression::filter(predicate));
         }
+        if args.simplify_by_decoration {
+            expression.intersect_with(&decorated_revisions());
+        }
         expression
     };
 
@@ -147,10 +366,13 @@ pub(crate) fn cmd_log(
     let use_elided_nodes = command
         .settings()
         .get_bool("ui.log-synthetic-elided-nodes")?;
+    let collapse_linear =
+        args.collapse_linear || command.settings().get_bool("ui.log-collapse-linear")?;
     let with_content_format = LogContentFormat::new(ui, command.settings())?;
 
     let template;
     let node_template;
+    let column_templates;
     {
         let language = workspace_command.commit_template_language();
         let template_string = match &args.template {
@@ -165,6 +387,18 @@ pub(crate) fn cmd_log(
                 CommitTemplateLanguage::wrap_commit,
             )?
             .labeled("log");
+        column_templates = args
+            .columns
+            .iter()
+            .map(|column| {
+                workspace_command.parse_template(
+                    ui,
+                    &language,
+                    column.template_text(),
+                    CommitTemplateLanguage::wrap_commit,
+                )
+            })
+            .try_collect::<_, Vec<_>, _>()?;
         node_template = workspace_command
             .parse_template(
                 ui,
@@ -175,6 +409,17 @@ pub(crate) fn cmd_log(
             .labeled("node");
     }
 
+    // Giant repos shouldn't dump the whole history on the terminal, but
+    // scripts expect all revisions.
+    let default_limit = if ui.is_terminal() {
+        command
+            .settings()
+            .get::<usize>("ui.log-default-limit")
+            .optional()?
+            .filter(|&limit| limit > 0)
+    } else {
+        None
+    };
     {
         ui.request_pager();
         let mut formatter = ui.stdout_formatter();
@@ -186,11 +431,35 @@ pub(crate) fn cmd_log(
                 "The -l shorthand is deprecated, use -n instead."
             )?;
         }
-        let limit = args.limit.or(args.deprecated_limit).unwrap_or(usize::MAX);
+        let limit = match args.limit.or(args.deprecated_limit) {
+            Some(0) => usize::MAX,
+            Some(limit) => limit,
+            None => default_limit.unwrap_or(usize::MAX),
+        };
 
-        if !args.no_graph {
+        if !column_templates.is_empty() {
+            let iter: Box<dyn Iterator<Item = Result<CommitId, RevsetEvaluationError>>> =
+                if args.reversed {
+                    Box::new(revset.iter().reversed()?)
+                } else {
+                    Box::new(revset.iter())
+                };
+            for commit_or_error in iter.commits(store).take(limit) {
+                let commit = commit_or_error?;
+                let fields = column_templates.iter().map(|template| {
+                    let mut output = vec![];
+                    template
+                        .format(&commit, &mut PlainTextFormatter::new(&mut output))
+                        .expect("write() to vec backed formatter should never fail");
+                    escape_column_field(&output.into_string_lossy())
+                });
+                let delimiter = args.delimiter.map_or("\n", LogDelimiter::as_str);
+                write!(formatter, "{}{delimiter}", fields.format("\t"))?;
+            }
+        } else if !args.no_graph {
             let mut raw_output = formatter.raw()?;
             let mut graph = get_graphlog(graph_style, raw_output.as_mut());
+            let mut collapsed_runs = HashMap::new();
             let iter: Box<dyn Iterator<Item = _>> = {
                 let mut forward_iter = TopoGroupedGraphIterator::new(revset.iter_graph());
                 // Emit the working-copy branch first, which is usually most
@@ -201,6 +470,18 @@ pub(crate) fn cmd_log(
                         forward_iter.prioritize_branch(id.clone());
                     }
                 }
+                let forward_iter: Box<dyn Iterator<Item = _>> = if collapse_linear {
+                    let decorated = workspace_command
+                        .attach_revset_evaluator(decorated_revisions())
+                        .evaluate()?;
+                    let is_decorated = decorated.containing_fn();
+                    let (nodes, runs) =
+                        collapse_linear_runs(forward_iter.try_collect()?, &is_decorated)?;
+                    collapsed_runs = runs;
+                    Box::new(nodes.into_iter().map(Ok))
+                } else {
+                    Box::new(forward_iter)
+                };
                 if args.reversed {
                     Box::new(ReverseGraphIterator::new(forward_iter)?)
                 } else {
@@ -226,8 +507,17 @@ pub(crate) fn cmd_log(
                             graphlog_edges.push(Edge::Direct((edge.target, false)));
                         }
                         GraphEdgeType::Indirect => {
-                            if use_elided_nodes {
-                                elided_targets.push(edge.target.clone());
+                            let num_collapsed = collapsed_runs
+                                .get(&(commit_id.clone(), edge.target.clone()))
+                                .or_else(|| {
+                                    collapsed_runs.get(&(edge.target.clone(), commit_id.clone()))
+                                })
+                                .copied();
+                            if let Some(num_collapsed) = num_collapsed {
+                                elided_targets.push((edge.target.clone(), Some(num_collapsed)));
+                                graphlog_edges.push(Edge::Direct((edge.target, true)));
+                            } else if use_elided_nodes {
+                                elided_targets.push((edge.target.clone(), None));
                                 graphlog_edges.push(Edge::Direct((edge.target, true)));
                             } else {
                                 graphlog_edges.push(Edge::Indirect((edge.target, false)));
@@ -267,7 +557,7 @@ pub(crate) fn cmd_log(
                     &node_symbol,
                     &String::from_utf8_lossy(&buffer),
                 )?;
-                for elided_target in elided_targets {
+                for (elided_target, num_collapsed) in elided_targets {
                     let elided_key = (elided_target, true);
                     let real_key = (elided_key.0.clone(), false);
                     let edges = [Edge::Direct(real_key)];
@@ -275,7 +565,11 @@ pub(crate) fn cmd_log(
                     let within_graph =
                         with_content_format.sub_width(graph.width(&elided_key, &edges));
                     within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
-                        writeln!(formatter.labeled("elided"), "(elided revisions)")
+                        if let Some(n) = num_collapsed {
+                            writeln!(formatter.labeled("elided"), "({n} revisions collapsed)")
+                        } else {
+                            writeln!(formatter.labeled("elided"), "(elided revisions)")
+                        }
                     })?;
                     let node_symbol = format_template(ui, &None, &node_template);
                     graph.add_node(
@@ -295,16 +589,47 @@ pub(crate) fn cmd_log(
                 };
             for commit_or_error in iter.commits(store).take(limit) {
                 let commit = commit_or_error?;
-                with_content_format
-                    .write(formatter, |formatter| template.format(&commit, formatter))?;
-                if let Some(renderer) = &diff_renderer {
-                    let width = ui.term_width();
-                    renderer.show_patch(ui, formatter, &commit, matcher.as_ref(), width)?;
+                let mut buffer = vec![];
+                {
+                    let mut formatter = ui.new_formatter(&mut buffer);
+                    with_content_format.write(formatter.as_mut(), |formatter| {
+                        template.format(&commit, formatter)
+                    })?;
+                    if let Some(renderer) = &diff_renderer {
+                        let width = ui.term_width();
+                        renderer.show_patch(
+                            ui,
+                            formatter.as_mut(),
+                            &commit,
+                            matcher.as_ref(),
+                            width,
+                        )?;
+                    }
+                }
+                formatter.raw()?.write_all(&buffer)?;
+                match args.delimiter {
+                    // Most templates already end each entry with a newline.
+                    Some(LogDelimiter::Newline) if buffer.ends_with(b"\n") => {}
+                    Some(delimiter) => write!(formatter, "{}", delimiter.as_str())?,
+                    None => {}
                 }
             }
         }
     }
 
+    if let Some(limit) =
+        default_limit.filter(|_| args.limit.is_none() && args.deprecated_limit.is_none())
+    {
+        // Counting all revisions would walk the whole revset, so only check
+        // if there's one more.
+        if revset.iter().nth(limit).transpose()?.is_some() {
+            writeln!(
+                ui.hint_default(),
+                "More revisions not shown, use --limit 0 to see all."
+            )?;
+        }
+    }
+
     // Check to see if the user might have specified a path when they intended
     // to specify a revset.
     if let ([], [only_path]) = (args.revisions.as_slice(), args.paths.as_slice()) {
@@ -332,6 +657,21 @@ pub(crate) fn cmd_log(
     Ok(())
 }
 
+/// Escapes characters that would break the tab-separated `--columns` output.
+fn escape_column_field(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 pub fn get_node_template(
     style: GraphStyle,
     settings: &UserSettings,
@@ -14,6 +14,9 @@
 
 mod abandon;
 mod absorb;
+mod alias;
+mod apply;
+mod archive;
 mod backout;
 #[cfg(feature = "bench")]
 mod bench;
@@ -29,6 +32,9 @@ mod edit;
 mod evolog;
 mod file;
 mod fix;
+#[cfg(feature = "forge")]
+mod forge;
+mod format_patch;
 mod git;
 mod help;
 mod init;
@@ -41,14 +47,18 @@ mod parallelize;
 mod prev;
 mod rebase;
 mod resolve;
+mod resolve_divergence;
 mod restore;
+mod revset;
 mod root;
 mod run;
+pub(crate) mod serve;
 mod show;
 mod simplify_parents;
 mod sparse;
 mod split;
 mod squash;
+mod stash;
 mod status;
 mod tag;
 mod unsquash;
@@ -78,6 +88,11 @@ use crate::ui::Ui;
 enum Command {
     Abandon(abandon::AbandonArgs),
     Absorb(absorb::AbsorbArgs),
+    #[command(subcommand)]
+    Alias(alias::AliasCommand),
+    #[command(visible_alias = "am")]
+    Apply(apply::ApplyArgs),
+    Archive(archive::ArchiveArgs),
     Backout(backout::BackoutArgs),
     #[cfg(feature = "bench")]
     #[command(subcommand)]
@@ -111,6 +126,10 @@ enum Command {
     #[command(hide = true)]
     Files(file::list::FileListArgs),
     Fix(fix::FixArgs),
+    #[cfg(feature = "forge")]
+    #[command(subcommand)]
+    Forge(forge::ForgeCommand),
+    FormatPatch(format_patch::FormatPatchArgs),
     #[command(subcommand)]
     Git(git::GitCommand),
     Help(help::HelpArgs),
@@ -126,22 +145,27 @@ enum Command {
     Prev(prev::PrevArgs),
     Rebase(rebase::RebaseArgs),
     Resolve(resolve::ResolveArgs),
+    ResolveDivergence(resolve_divergence::ResolveDivergenceArgs),
     Restore(restore::RestoreArgs),
     #[command(
         hide = true,
         help_template = "Not a real subcommand; consider `jj backout` or `jj restore`"
     )]
     Revert(DummyCommandArgs),
+    #[command(subcommand)]
+    Revset(revset::RevsetCommand),
     Root(root::RootArgs),
     #[command(hide = true)]
     // TODO: Flesh out.
     Run(run::RunArgs),
+    Serve(serve::ServeArgs),
     Show(show::ShowArgs),
     SimplifyParents(simplify_parents::SimplifyParentsArgs),
     #[command(subcommand)]
     Sparse(sparse::SparseCommand),
     Split(split::SplitArgs),
     Squash(squash::SquashArgs),
+    Stash(stash::StashArgs),
     Status(status::StatusArgs),
     #[command(subcommand)]
     Tag(tag::TagCommand),
@@ -177,6 +201,9 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
     match &subcommand {
         Command::Abandon(args) => abandon::cmd_abandon(ui, command_helper, args),
         Command::Absorb(args) => absorb::cmd_absorb(ui, command_helper, args),
+        Command::Alias(args) => alias::cmd_alias(ui, command_helper, args),
+        Command::Apply(args) => apply::cmd_apply(ui, command_helper, args),
+        Command::Archive(args) => archive::cmd_archive(ui, command_helper, args),
         Command::Backout(args) => backout::cmd_backout(ui, command_helper, args),
         #[cfg(feature = "bench")]
         Command::Bench(args) => bench::cmd_bench(ui, command_helper, args),
@@ -207,6 +234,9 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
             cmd(ui, command_helper, args)
         }
         Command::Fix(args) => fix::cmd_fix(ui, command_helper, args),
+        #[cfg(feature = "forge")]
+        Command::Forge(args) => forge::cmd_forge(ui, command_helper, args),
+        Command::FormatPatch(args) => format_patch::cmd_format_patch(ui, command_helper, args),
         Command::Git(args) => git::cmd_git(ui, command_helper, args),
         Command::Help(args) => help::cmd_help(ui, command_helper, args),
         Command::Init(args) => init::cmd_init(ui, command_helper, args),
@@ -220,10 +250,15 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
         Command::Prev(args) => prev::cmd_prev(ui, command_helper, args),
         Command::Rebase(args) => rebase::cmd_rebase(ui, command_helper, args),
         Command::Resolve(args) => resolve::cmd_resolve(ui, command_helper, args),
+        Command::ResolveDivergence(args) => {
+            resolve_divergence::cmd_resolve_divergence(ui, command_helper, args)
+        }
         Command::Restore(args) => restore::cmd_restore(ui, command_helper, args),
         Command::Revert(_args) => revert(),
+        Command::Revset(args) => revset::cmd_revset(ui, command_helper, args),
         Command::Root(args) => root::cmd_root(ui, command_helper, args),
         Command::Run(args) => run::cmd_run(ui, command_helper, args),
+        Command::Serve(args) => serve::cmd_serve(ui, command_helper, args),
         Command::SimplifyParents(args) => {
             simplify_parents::cmd_simplify_parents(ui, command_helper, args)
         }
@@ -231,6 +266,7 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
         Command::Sparse(args) => sparse::cmd_sparse(ui, command_helper, args),
         Command::Split(args) => split::cmd_split(ui, command_helper, args),
         Command::Squash(args) => squash::cmd_squash(ui, command_helper, args),
+        Command::Stash(args) => stash::cmd_stash(ui, command_helper, args),
         Command::Status(args) => status::cmd_status(ui, command_helper, args),
         Command::Tag(args) => tag::cmd_tag(ui, command_helper, args),
         Command::Undo(args) => operation::undo::cmd_op_undo(ui, command_helper, args),
@@ -18,6 +18,7 @@ use std::rc::Rc;
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
+use jj_lib::backend::BackendError;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::CommitIteratorExt;
 use jj_lib::repo::ReadonlyRepo;
@@ -27,8 +28,13 @@ use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetIteratorExt;
 use jj_lib::rewrite::merge_commit_trees;
 use jj_lib::rewrite::rebase_commit;
+use jj_lib::rewrite::restore_tree;
+use jj_lib::rewrite::squash_commits;
+use jj_lib::rewrite::CommitToSquash;
+use jj_lib::rewrite::SquashResult;
 use tracing::instrument;
 
+use super::squash::current_commit;
 use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
@@ -47,6 +53,11 @@ use crate::ui::Ui;
 /// argument. For example, `jj new @ main` will create a new commit with the
 /// working copy and the `main` bookmark as parents.
 ///
+/// With `--from` and `--path`, the changes to the given paths are moved out of
+/// the source revision into the new change, as if by `jj squash`. For example,
+/// `jj new -B @ --from @ --path src/foo.rs` extracts the changes to
+/// `src/foo.rs` into a new commit before the working-copy commit.
+///
 /// For more information, see
 /// https://martinvonz.github.io/jj/latest/working-copy/.
 #[derive(clap::Args, Clone, Debug)]
@@ -85,6 +96,17 @@ pub(crate) struct NewArgs {
         add = ArgValueCandidates::new(complete::mutable_revisions),
     )]
     insert_before: Vec<RevisionArg>,
+    /// Move changes from this revision into the new change
+    #[arg(
+        long,
+        value_name = "REVSET",
+        requires = "paths",
+        add = ArgValueCandidates::new(complete::mutable_revisions),
+    )]
+    from: Option<RevisionArg>,
+    /// Move only changes to these paths (used with `--from`)
+    #[arg(long = "path", value_name = "FILESETS", requires = "from")]
+    paths: Vec<String>,
 }
 
 #[instrument(skip_all)]
@@ -183,11 +205,22 @@ pub(crate) fn cmd_new(
     };
     workspace_command.check_rewritable(children_commits.iter().ids())?;
 
+    let source = if let Some(from) = &args.from {
+        let source_commit = workspace_command.resolve_single_rev(ui, from)?;
+        workspace_command.check_rewritable([source_commit.id()])?;
+        let matcher = workspace_command
+            .parse_file_patterns(ui, &args.paths)?
+            .to_matcher();
+        Some((source_commit, matcher))
+    } else {
+        None
+    };
+
     let parent_commit_ids_set: HashSet<CommitId> = parent_commit_ids.iter().cloned().collect();
 
     let mut tx = workspace_command.start_transaction();
     let merged_tree = merge_commit_trees(tx.repo(), &parent_commits)?;
-    let new_commit = tx
+    let mut new_commit = tx
         .repo_mut()
         .new_commit(command.settings(), parent_commit_ids, merged_tree.id())
         .set_description(join_message_paragraphs(&args.message_paragraphs))
@@ -212,6 +245,47 @@ pub(crate) fn cmd_new(
     }
     num_rebased += tx.repo_mut().rebase_descendants(command.settings())?;
 
+    if let Some((source_commit, matcher)) = &source {
+        // The source may have been rebased onto the new commit above.
+        let source_commit = current_commit(tx.repo(), source_commit)?;
+        let parent_tree = source_commit.parent_tree(tx.repo())?;
+        let selected_tree_id =
+            restore_tree(&source_commit.tree()?, &parent_tree, matcher.as_ref())?;
+        let selected_tree = tx.repo().store().get_root_tree(&selected_tree_id)?;
+        let source_to_squash = CommitToSquash {
+            commit: source_commit,
+            selected_tree,
+            parent_tree,
+        };
+        let description = new_commit.description().to_owned();
+        match squash_commits(
+            command.settings(),
+            tx.repo_mut(),
+            &[source_to_squash],
+            &new_commit,
+            true,
+            |_| Ok::<_, BackendError>(description),
+        )? {
+            SquashResult::NoChanges => {
+                writeln!(
+                    ui.warning_default(),
+                    "No changes to the given paths in the source revision"
+                )?;
+            }
+            SquashResult::NewCommit(commit) => {
+                new_commit = commit;
+                // Descendants rebased above are rebased again here, so don't
+                // count them twice.
+                tx.repo_mut().rebase_descendants(command.settings())?;
+            }
+        }
+    }
+
+    if command.settings().get_bool("rerere.enabled")? {
+        tx.reuse_conflict_resolutions(ui)?;
+        new_commit = current_commit(tx.repo(), &new_commit)?;
+    }
+
     if args.no_edit {
         if let Some(mut formatter) = ui.status_formatter() {
             write!(formatter, "Created new commit ")?;
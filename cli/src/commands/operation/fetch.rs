@@ -0,0 +1,75 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::op_sync;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::cli_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Copy operations from a directory into the operation log (experimental)
+///
+/// Copies the operations which were pushed to the given directory with `jj op
+/// push`, along with the commits they refer to. The fetched operations are
+/// then merged with the local ones, just like operations which were run
+/// concurrently.
+///
+/// Only repos backed by Git are supported.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationFetchArgs {
+    /// The directory to copy the operations from
+    #[arg(value_hint = clap::ValueHint::DirPath)]
+    source: String,
+}
+
+pub fn cmd_op_fetch(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationFetchArgs,
+) -> Result<(), CommandError> {
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    // Snapshot the working copy first so local changes aren't lost.
+    let workspace_command = command.workspace_helper(ui)?;
+    let stats = op_sync::fetch_operations(
+        workspace_command.workspace().repo_loader(),
+        workspace_command.repo_path(),
+        &command.cwd().join(&args.source),
+    )?;
+    if stats.operation_count == 0 {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+    writeln!(
+        ui.status(),
+        "Fetched {} operations and {} commits.",
+        stats.operation_count,
+        stats.commit_count
+    )?;
+    // Reload the repo to merge the fetched operations with the local ones,
+    // and update the working copy if it was changed by them.
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    if let Some(wc_commit_id) = workspace_command.get_wc_commit_id() {
+        let wc_commit = workspace_command.repo().store().get_commit(wc_commit_id)?;
+        if wc_commit.tree_id() != workspace_command.working_copy().tree_id()? {
+            command.recover_stale_working_copy(ui)?;
+        }
+    }
+    Ok(())
+}
@@ -14,21 +14,31 @@
 
 use std::slice;
 
+use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::dag_walk;
+use jj_lib::graph::GraphEdgeType;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
+use jj_lib::opset;
 use jj_lib::repo::RepoLoader;
+use jj_lib::revset::RevsetDiagnostics;
 use jj_lib::settings::UserSettings;
 
 use super::diff::show_op_diff;
 use crate::cli_util::format_template;
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::WorkspaceCommandEnvironment;
+use crate::command_error::opset_parse_error;
+use crate::command_error::print_parse_diagnostics;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
+use crate::complete;
 use crate::diff_util::diff_formats_for_log;
 use crate::diff_util::DiffFormatArgs;
 use crate::diff_util::DiffRenderer;
@@ -43,9 +53,18 @@ use crate::ui::Ui;
 ///
 /// Like other commands, `jj op log` snapshots the current working-copy changes
 /// and reconciles divergent operations. Use `--at-op=@ --ignore-working-copy`
-/// to inspect the current state without mutation.
+/// to inspect the current state without mutation. In that case, divergent
+/// operations are shown as separate heads of the graph.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationLogArgs {
+    /// Which operations to show
+    ///
+    /// The operation set language is similar to revsets. For example,
+    /// `user(alice) & date(after:yesterday)` selects the operations made by
+    /// `alice` since yesterday. See the operation log documentation for the
+    /// list of supported functions.
+    #[arg(long, short = 'r', value_name = "OPSET")]
+    operations: Vec<String>,
     /// Limit number of operations to show
     #[arg(long, short = 'n')]
     limit: Option<usize>,
@@ -63,7 +82,7 @@ pub struct OperationLogArgs {
     /// Render each operation using the given template
     ///
     /// For the syntax, see https://martinvonz.github.io/jj/latest/templates/
-    #[arg(long, short = 'T')]
+    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::operation_templates))]
     template: Option<String>,
     /// Show changes to the repository at each operation
     #[arg(long)]
@@ -88,7 +107,15 @@ pub fn cmd_op_log(
         let workspace_command = command.workspace_helper(ui)?;
         let current_op = workspace_command.repo().operation();
         let repo_loader = workspace_command.workspace().repo_loader();
-        do_op_log(ui, workspace_command.env(), repo_loader, current_op, args)
+        let head_ops = slice::from_ref(current_op);
+        do_op_log(
+            ui,
+            workspace_command.env(),
+            repo_loader,
+            head_ops,
+            Some(current_op),
+            args,
+        )
     } else {
         // Don't load the repo so that the operation history can be inspected
         // even with a corrupted repo state. For example, you can find the first
@@ -96,19 +123,81 @@ pub fn cmd_op_log(
         let workspace = command.load_workspace()?;
         let workspace_env = command.workspace_environment(ui, &workspace)?;
         let repo_loader = workspace.repo_loader();
-        let current_op = command.resolve_operation(ui, workspace.repo_loader())?;
-        do_op_log(ui, &workspace_env, repo_loader, &current_op, args)
+        let head_ops = if command.is_at_head_operation() {
+            load_divergent_head_ops(repo_loader)?
+        } else {
+            vec![]
+        };
+        if head_ops.len() > 1 {
+            writeln!(
+                ui.warning_default(),
+                "Operation heads are divergent: {}",
+                head_ops
+                    .iter()
+                    .map(|op| short_operation_hash(op.id()))
+                    .join(", ")
+            )?;
+            writeln!(
+                ui.hint_default(),
+                "The next command that modifies the repo will reconcile them."
+            )?;
+            do_op_log(ui, &workspace_env, repo_loader, &head_ops, None, args)
+        } else {
+            let current_op = command.resolve_operation(ui, workspace.repo_loader())?;
+            let head_ops = slice::from_ref(&current_op);
+            do_op_log(
+                ui,
+                &workspace_env,
+                repo_loader,
+                head_ops,
+                Some(&current_op),
+                args,
+            )
+        }
     }
 }
 
+/// Loads the current head operations without merging them, excluding
+/// redundant heads that are ancestors of the others.
+fn load_divergent_head_ops(repo_loader: &RepoLoader) -> Result<Vec<Operation>, CommandError> {
+    let head_ops = op_walk::get_current_head_ops(
+        repo_loader.op_store(),
+        repo_loader.op_heads_store().as_ref(),
+    )?;
+    let head_ops = dag_walk::heads_ok(
+        head_ops.into_iter().map(Ok),
+        |op: &Operation| op.id().clone(),
+        |op: &Operation| op.parents().collect_vec(),
+    )?;
+    Ok(head_ops
+        .into_iter()
+        .sorted_by_key(|op| op.metadata().end_time.timestamp)
+        .collect())
+}
+
 fn do_op_log(
     ui: &mut Ui,
     workspace_env: &WorkspaceCommandEnvironment,
     repo_loader: &RepoLoader,
-    current_op: &Operation,
+    head_ops: &[Operation],
+    current_op: Option<&Operation>,
     args: &OperationLogArgs,
 ) -> Result<(), CommandError> {
     let settings = workspace_env.settings();
+    let opset_expression = if args.operations.is_empty() {
+        None
+    } else {
+        let date_pattern_context = *workspace_env.revset_parse_context().date_pattern_context();
+        let mut diagnostics = RevsetDiagnostics::new();
+        let expressions: Vec<_> = args
+            .operations
+            .iter()
+            .map(|text| opset::parse(&mut diagnostics, text, &date_pattern_context))
+            .try_collect()
+            .map_err(opset_parse_error)?;
+        print_parse_diagnostics(ui, "In operation set expression", &diagnostics)?;
+        Some(opset::OpsetExpression::union_all(&expressions))
+    };
     let graph_style = GraphStyle::from_settings(settings)?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
 
@@ -116,8 +205,8 @@ fn do_op_log(
     let op_node_template;
     {
         let language = OperationTemplateLanguage::new(
-            repo_loader.op_store().root_operation_id(),
-            Some(current_op.id()),
+            repo_loader,
+            current_op.map(|op| op.id()),
             workspace_env.operation_template_extensions(),
         );
         let text = match &args.template {
@@ -204,16 +293,33 @@ fn do_op_log(
         )?;
     }
     let limit = args.limit.or(args.deprecated_limit).unwrap_or(usize::MAX);
-    let iter = op_walk::walk_ancestors(slice::from_ref(current_op)).take(limit);
+    let iter: Box<dyn Iterator<Item = Result<OpGraphNode, CommandError>>> =
+        if let Some(expression) = &opset_expression {
+            let nodes = opset::evaluate(expression, repo_loader.op_store(), head_ops)?;
+            Box::new(nodes.into_iter().map(|(op, edges)| {
+                let edges = edges
+                    .into_iter()
+                    .map(|edge| match edge.edge_type {
+                        GraphEdgeType::Missing => Edge::Missing,
+                        GraphEdgeType::Direct => Edge::Direct(edge.target),
+                        GraphEdgeType::Indirect => Edge::Indirect(edge.target),
+                    })
+                    .collect();
+                Ok((op, edges))
+            }))
+        } else {
+            Box::new(op_walk::walk_ancestors(head_ops).map(|op| {
+                let op = op?;
+                let edges = op.parent_ids().iter().cloned().map(Edge::Direct).collect();
+                Ok((op, edges))
+            }))
+        };
+    let iter = iter.take(limit);
     if !args.no_graph {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
-        for op in iter {
-            let op = op?;
-            let mut edges = vec![];
-            for id in op.parent_ids() {
-                edges.push(Edge::Direct(id.clone()));
-            }
+        for node in iter {
+            let (op, edges) = node?;
             let mut buffer = vec![];
             let within_graph = with_content_format.sub_width(graph.width(op.id(), &edges));
             within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
@@ -235,8 +341,8 @@ fn do_op_log(
             )?;
         }
     } else {
-        for op in iter {
-            let op = op?;
+        for node in iter {
+            let (op, _) = node?;
             with_content_format.write(formatter, |formatter| template.format(&op, formatter))?;
             if let Some(show) = &maybe_show_op_diff {
                 show(ui, formatter, &op, &with_content_format)?;
@@ -247,6 +353,8 @@ fn do_op_log(
     Ok(())
 }
 
+type OpGraphNode = (Operation, Vec<Edge<OperationId>>);
+
 fn get_node_template(style: GraphStyle, settings: &UserSettings) -> Result<String, ConfigGetError> {
     let symbol = settings.get_string("templates.op_log_node").optional()?;
     let default = if style.is_ascii() {
@@ -14,7 +14,9 @@
 
 mod abandon;
 mod diff;
+mod fetch;
 mod log;
+mod push;
 mod restore;
 mod show;
 pub mod undo;
@@ -24,8 +26,12 @@ use abandon::OperationAbandonArgs;
 use clap::Subcommand;
 use diff::cmd_op_diff;
 use diff::OperationDiffArgs;
+use fetch::cmd_op_fetch;
+use fetch::OperationFetchArgs;
 use log::cmd_op_log;
 use log::OperationLogArgs;
+use push::cmd_op_push;
+use push::OperationPushArgs;
 use restore::cmd_op_restore;
 use restore::OperationRestoreArgs;
 use show::cmd_op_show;
@@ -45,7 +51,9 @@ use crate::ui::Ui;
 pub enum OperationCommand {
     Abandon(OperationAbandonArgs),
     Diff(OperationDiffArgs),
+    Fetch(OperationFetchArgs),
     Log(OperationLogArgs),
+    Push(OperationPushArgs),
     Restore(OperationRestoreArgs),
     Show(OperationShowArgs),
     Undo(OperationUndoArgs),
@@ -59,7 +67,9 @@ pub fn cmd_operation(
     match subcommand {
         OperationCommand::Abandon(args) => cmd_op_abandon(ui, command, args),
         OperationCommand::Diff(args) => cmd_op_diff(ui, command, args),
+        OperationCommand::Fetch(args) => cmd_op_fetch(ui, command, args),
         OperationCommand::Log(args) => cmd_op_log(ui, command, args),
+        OperationCommand::Push(args) => cmd_op_push(ui, command, args),
         OperationCommand::Restore(args) => cmd_op_restore(ui, command, args),
         OperationCommand::Show(args) => cmd_op_show(ui, command, args),
         OperationCommand::Undo(args) => cmd_op_undo(ui, command, args),
@@ -68,7 +78,7 @@ pub fn cmd_operation(
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
 enum UndoWhatToRestore {
-    /// The jj repo state and local bookmarks
+    /// The jj repo state, local bookmarks, and change aliases
     Repo,
     /// The remote-tracking bookmarks. Do not restore these if you'd like to
     /// push after the undo
@@ -102,5 +112,7 @@ fn view_with_desired_portions_restored(
         git_refs: current_view.git_refs.clone(),
         git_head: current_view.git_head.clone(),
         wc_commit_ids: repo_source.wc_commit_ids.clone(),
+        change_aliases: repo_source.change_aliases.clone(),
+        stash_ids: repo_source.stash_ids.clone(),
     }
 }
@@ -0,0 +1,66 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::slice;
+
+use jj_lib::op_sync;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Copy the operation log to a directory (experimental)
+///
+/// Copies the current operation and its ancestors, along with the commits
+/// they refer to, to the given directory. The directory can be shared with
+/// other machines, e.g. with `rsync` or a network file system, and the
+/// operations can then be fetched into another clone of the repo with `jj op
+/// fetch`. This keeps the undo history and bookmarks in sync between them.
+///
+/// Only repos backed by Git are supported.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationPushArgs {
+    /// The directory to copy the operations to
+    ///
+    /// The directory is created if it doesn't exist.
+    #[arg(value_hint = clap::ValueHint::DirPath)]
+    destination: String,
+}
+
+pub fn cmd_op_push(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationPushArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let stats = op_sync::push_operations(
+        workspace_command.workspace().repo_loader(),
+        workspace_command.repo_path(),
+        slice::from_ref(repo.op_id()),
+        &command.cwd().join(&args.destination),
+    )?;
+    if stats.operation_count == 0 {
+        writeln!(ui.status(), "Nothing changed.")?;
+    } else {
+        writeln!(
+            ui.status(),
+            "Pushed {} operations and {} commits.",
+            stats.operation_count,
+            stats.commit_count
+        )?;
+    }
+    Ok(())
+}
@@ -12,23 +12,38 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use clap_complete::ArgValueCandidates;
 use jj_lib::object_id::ObjectId;
+use jj_lib::operation::Operation;
+use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 
+use super::diff::show_op_diff;
 use super::view_with_desired_portions_restored;
 use super::UndoWhatToRestore;
 use super::DEFAULT_UNDO_WHAT;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::LogContentFormat;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
+use crate::commit_templater::CommitTemplateLanguage;
 use crate::complete;
+use crate::graphlog::GraphStyle;
 use crate::ui::Ui;
 
 /// Create a new operation that undoes an earlier operation
 ///
 /// This undoes an individual operation by applying the inverse of the
 /// operation.
+///
+/// With `--interactive`, the commits and refs changed by the operation are
+/// shown first, and you are asked to confirm the undo. You may want to make it
+/// the default with an alias like `undo = ["undo", "--interactive"]`, and pass
+/// `--yes` in scripts.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationUndoArgs {
     /// The operation to undo
@@ -42,6 +57,14 @@ pub struct OperationUndoArgs {
     /// This option is EXPERIMENTAL.
     #[arg(long, value_enum, default_values_t = DEFAULT_UNDO_WHAT)]
     what: Vec<UndoWhatToRestore>,
+
+    /// Show the changes to be undone and ask for confirmation
+    #[arg(long, short)]
+    interactive: bool,
+
+    /// Don't ask for confirmation
+    #[arg(long)]
+    yes: bool,
 }
 
 pub fn cmd_op_undo(
@@ -59,10 +82,23 @@ pub fn cmd_op_undo(
         return Err(user_error("Cannot undo a merge operation"));
     }
 
-    let mut tx = workspace_command.start_transaction();
-    let repo_loader = tx.base_repo().loader();
+    let repo_loader = workspace_command.repo().loader();
     let bad_repo = repo_loader.load_at(&bad_op)?;
     let parent_repo = repo_loader.load_at(&parent_op)?;
+    if args.interactive {
+        if !args.yes && !Ui::can_prompt() {
+            return Err(user_error_with_hint(
+                "Refusing to undo without confirmation",
+                "Use --yes to undo without confirmation.",
+            ));
+        }
+        show_undo_preview(ui, &workspace_command, &bad_op, &bad_repo, &parent_repo)?;
+        if !args.yes && !ui.prompt_yes_no("Undo this operation?", Some(false))? {
+            return Err(user_error("Aborted"));
+        }
+    }
+
+    let mut tx = workspace_command.start_transaction();
     tx.repo_mut().merge(&bad_repo, &parent_repo);
     let new_view = view_with_desired_portions_restored(
         tx.repo().view().store_view(),
@@ -76,7 +112,55 @@ pub fn cmd_op_undo(
         template.format(&bad_op, formatter.as_mut())?;
         writeln!(formatter)?;
     }
+    // Undoing may bring back conflicted commits that have been resolved since.
+    if command.settings().get_bool("rerere.enabled")? {
+        tx.reuse_conflict_resolutions_of_revived_commits(ui)?;
+    }
     tx.finish(ui, format!("undo operation {}", bad_op.id().hex()))?;
 
     Ok(())
 }
+
+/// Shows the changes made by `bad_op`, which undoing it will revert.
+fn show_undo_preview(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    bad_op: &Operation,
+    bad_repo: &Arc<ReadonlyRepo>,
+    parent_repo: &Arc<ReadonlyRepo>,
+) -> Result<(), CommandError> {
+    let workspace_env = workspace_command.env();
+    // Show the commits as they will be after the undo, but merge the index of
+    // the bad repo so the commits it added are accessible.
+    let mut tx = parent_repo.start_transaction(workspace_command.settings());
+    tx.repo_mut().merge_index(bad_repo);
+    let merged_repo = tx.repo();
+    let id_prefix_context = workspace_env.new_id_prefix_context();
+    let commit_summary_template = {
+        let language = workspace_env.commit_template_language(merged_repo, &id_prefix_context);
+        let text = workspace_command
+            .settings()
+            .get_string("templates.commit_summary")?;
+        workspace_env.parse_template(ui, &language, &text, CommitTemplateLanguage::wrap_commit)?
+    };
+    let graph_style = GraphStyle::from_settings(workspace_command.settings())?;
+    let with_content_format = LogContentFormat::new(ui, workspace_command.settings())?;
+
+    let mut formatter = ui.stdout_formatter();
+    write!(formatter, "Operation to undo: ")?;
+    workspace_command
+        .operation_summary_template()
+        .format(bad_op, formatter.as_mut())?;
+    writeln!(formatter)?;
+    show_op_diff(
+        ui,
+        formatter.as_mut(),
+        merged_repo,
+        bad_repo,
+        parent_repo,
+        &commit_summary_template,
+        Some(graph_style),
+        &with_content_format,
+        None,
+    )
+}
@@ -40,8 +40,10 @@ use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::cli_error;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::ui::Ui;
@@ -178,6 +180,41 @@ pub(crate) struct RebaseArgs {
     /// parents.
     #[arg(long)]
     skip_emptied: bool,
+
+    /// Resolve conflicts in the rebased commits with recorded resolutions
+    ///
+    /// If a file conflict in a rebased commit is the same as one previously
+    /// resolved by `jj resolve`, the recorded resolution is applied instead of
+    /// leaving the conflict in the commit. This is the default if
+    /// `rerere.enabled` is set.
+    #[arg(long)]
+    reuse_resolutions: bool,
+
+    /// What to do with commits that have new conflicts after rebasing
+    ///
+    /// A commit is only considered to have new conflicts if it wasn't
+    /// conflicted before the rebase, and the conflicts couldn't be resolved
+    /// with recorded resolutions.
+    #[arg(long, value_enum, default_value_t = OnConflict::Keep)]
+    on_conflict: OnConflict,
+}
+
+/// Policy for commits that have new conflicts after rebasing.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum OnConflict {
+    /// Keep the conflicts in the rebased commits
+    Keep,
+    /// Abandon the commits that would have conflicts, rebasing their
+    /// descendants onto their parents
+    Skip,
+    /// Don't rebase anything if any commit would have conflicts
+    Stop,
+}
+
+/// How to handle conflicts in the rebased commits.
+struct ConflictOptions {
+    reuse_resolutions: bool,
+    on_conflict: OnConflict,
 }
 
 #[derive(clap::Args, Clone, Debug)]
@@ -228,6 +265,10 @@ pub(crate) fn cmd_rebase(
         },
         simplify_ancestor_merge: false,
     };
+    let conflict_options = ConflictOptions {
+        reuse_resolutions: args.reuse_resolutions,
+        on_conflict: args.on_conflict,
+    };
     let mut workspace_command = command.workspace_helper(ui)?;
     if !args.revisions.is_empty() {
         rebase_revisions(
@@ -237,6 +278,7 @@ pub(crate) fn cmd_rebase(
             &args.revisions,
             &args.destination,
             &rebase_options,
+            &conflict_options,
         )?;
     } else if !args.source.is_empty() {
         rebase_source(
@@ -246,6 +288,7 @@ pub(crate) fn cmd_rebase(
             &args.source,
             &args.destination,
             &rebase_options,
+            &conflict_options,
         )?;
     } else {
         rebase_branch(
@@ -255,6 +298,7 @@ pub(crate) fn cmd_rebase(
             &args.branch,
             &args.destination,
             rebase_options,
+            &conflict_options,
         )?;
     }
     Ok(())
@@ -267,6 +311,7 @@ fn rebase_revisions(
     revisions: &[RevisionArg],
     rebase_destination: &RebaseDestinationArgs,
     rebase_options: &RebaseOptions,
+    conflict_options: &ConflictOptions,
 ) -> Result<(), CommandError> {
     let target_commits: Vec<_> = workspace_command
         .parse_union_revsets(ui, revisions)?
@@ -294,6 +339,7 @@ fn rebase_revisions(
         &new_children,
         target_commits,
         rebase_options,
+        conflict_options,
     )
 }
 
@@ -304,6 +350,7 @@ fn rebase_source(
     source: &[RevisionArg],
     rebase_destination: &RebaseDestinationArgs,
     rebase_options: &RebaseOptions,
+    conflict_options: &ConflictOptions,
 ) -> Result<(), CommandError> {
     let source_commits = workspace_command
         .resolve_some_revsets_default_single(ui, source)?
@@ -327,6 +374,7 @@ fn rebase_source(
         &new_children,
         source_commits,
         rebase_options,
+        conflict_options,
     )
 }
 
@@ -337,6 +385,7 @@ fn rebase_branch(
     branch: &[RevisionArg],
     rebase_destination: &RebaseDestinationArgs,
     rebase_options: RebaseOptions,
+    conflict_options: &ConflictOptions,
 ) -> Result<(), CommandError> {
     let branch_commits: Vec<_> = if branch.is_empty() {
         vec![workspace_command.resolve_single_rev(ui, &RevisionArg::AT)?]
@@ -376,9 +425,11 @@ fn rebase_branch(
         &new_children,
         root_commits,
         &rebase_options,
+        conflict_options,
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn rebase_descendants_transaction(
     ui: &mut Ui,
     settings: &UserSettings,
@@ -387,6 +438,7 @@ fn rebase_descendants_transaction(
     new_children: &[Commit],
     target_roots: Vec<Commit>,
     rebase_options: &RebaseOptions,
+    conflict_options: &ConflictOptions,
 ) -> Result<(), CommandError> {
     if target_roots.is_empty() {
         writeln!(ui.status(), "Nothing changed.")?;
@@ -414,10 +466,97 @@ fn rebase_descendants_transaction(
         &MoveCommitsTarget::Roots(target_roots),
         rebase_options,
     )?;
+    handle_conflicts(ui, settings, &mut tx, conflict_options)?;
     print_move_commits_stats(ui, &stats)?;
     tx.finish(ui, tx_description)
 }
 
+/// Reuses recorded conflict resolutions and then applies the `--on-conflict`
+/// policy to the commits with new conflicts.
+fn handle_conflicts(
+    ui: &Ui,
+    settings: &UserSettings,
+    tx: &mut WorkspaceCommandTransaction,
+    conflict_options: &ConflictOptions,
+) -> Result<(), CommandError> {
+    if conflict_options.reuse_resolutions || settings.get_bool("rerere.enabled")? {
+        tx.reuse_conflict_resolutions(ui)?;
+    }
+    if conflict_options.on_conflict == OnConflict::Keep {
+        return Ok(());
+    }
+
+    let base_heads = tx.base_repo().view().heads().iter().cloned().collect_vec();
+    let heads = tx.repo().view().heads().iter().cloned().collect_vec();
+    let new_commits: Vec<Commit> = RevsetExpression::commits(heads)
+        .ancestors()
+        .minus(&RevsetExpression::commits(base_heads).ancestors())
+        .evaluate(tx.repo())?
+        .iter()
+        .commits(tx.repo().store())
+        .try_collect()?;
+    let mut skipped_commits = vec![];
+    // Visit parents first since skipping a commit rebases its descendants.
+    for commit in new_commits.iter().rev() {
+        let commit_ids = tx
+            .repo()
+            .resolve_change_id(commit.change_id())
+            .unwrap_or_default();
+        let [commit_id] = commit_ids.as_slice() else {
+            continue;
+        };
+        let commit = tx.repo().store().get_commit(commit_id)?;
+        if !commit.has_conflict()? {
+            continue;
+        }
+        // Commits that were already conflicted don't count.
+        let old_commit_ids = tx
+            .base_repo()
+            .resolve_change_id(commit.change_id())
+            .unwrap_or_default();
+        let mut was_conflicted = false;
+        for old_commit_id in &old_commit_ids {
+            was_conflicted |= tx
+                .base_repo()
+                .store()
+                .get_commit(old_commit_id)?
+                .has_conflict()?;
+        }
+        if was_conflicted {
+            continue;
+        }
+        match conflict_options.on_conflict {
+            OnConflict::Keep => unreachable!(),
+            OnConflict::Stop => {
+                return Err(user_error_with_hint(
+                    format!(
+                        "Rebasing would create conflicts in commit {}",
+                        tx.format_commit_summary(&commit)
+                    ),
+                    "Use --on-conflict=keep to rebase anyway, or --on-conflict=skip to leave out \
+                     the conflicted commits.",
+                ));
+            }
+            OnConflict::Skip => {
+                skipped_commits.push(tx.format_commit_summary(&commit));
+                tx.repo_mut().record_abandoned_commit(commit.id().clone());
+                tx.repo_mut().rebase_descendants(settings)?;
+            }
+        }
+    }
+    if !skipped_commits.is_empty() {
+        writeln!(
+            ui.status(),
+            "Skipped {} commits that would have conflicts:",
+            skipped_commits.len()
+        )?;
+        for summary in &skipped_commits {
+            writeln!(ui.status(), "  {summary}")?;
+        }
+    }
+    Ok(())
+}
+
 /// Computes the new parents and children for the given
 /// [`RebaseDestinationArgs`].
 fn compute_rebase_destination(
@@ -487,6 +626,7 @@ fn compute_rebase_destination(
 }
 
 /// Creates a transaction for rebasing revisions.
+#[allow(clippy::too_many_arguments)]
 fn rebase_revisions_transaction(
     ui: &mut Ui,
     settings: &UserSettings,
@@ -495,6 +635,7 @@ fn rebase_revisions_transaction(
     new_children: &[Commit],
     target_commits: Vec<Commit>,
     rebase_options: &RebaseOptions,
+    conflict_options: &ConflictOptions,
 ) -> Result<(), CommandError> {
     if target_commits.is_empty() {
         writeln!(ui.status(), "Nothing changed.")?;
@@ -520,6 +661,7 @@ fn rebase_revisions_transaction(
         &MoveCommitsTarget::Commits(target_commits),
         rebase_options,
     )?;
+    handle_conflicts(ui, settings, &mut tx, conflict_options)?;
     print_move_commits_stats(ui, &stats)?;
     tx.finish(ui, tx_description)
 }
@@ -36,6 +36,11 @@ use crate::ui::Ui;
 /// Note that conflicts can also be resolved without using this command. You may
 /// edit the conflict markers in the conflicted file directly with a text
 /// editor.
+///
+/// Resolutions made by this command are recorded so that `jj rebase
+/// --reuse-resolutions` can apply them if the same conflict shows up again.
+/// Use `--no-rerere` to not record the resolution. Recorded resolutions can be
+/// listed and forgotten with `jj util resolutions`.
 //  TODOs:
 //   - `jj resolve --editor` to resolve a conflict in the default text editor. Should work for
 //     conflicts with 3+ adds. Useful to resolve conflicts in a commit other than the current one.
@@ -57,8 +62,16 @@ pub(crate) struct ResolveArgs {
     #[arg(long, short)]
     list: bool,
     /// Specify 3-way merge tool to be used
-    #[arg(long, conflicts_with = "list", value_name = "NAME")]
+    #[arg(
+        long,
+        conflicts_with = "list",
+        value_name = "NAME",
+        add = ArgValueCandidates::new(complete::merge_editors)
+    )]
     tool: Option<String>,
+    /// Don't record the resolution for reuse
+    #[arg(long, conflicts_with = "list")]
+    no_rerere: bool,
     /// Restrict to these paths when searching for a conflict to resolve. We
     /// will attempt to resolve the first conflict we can find. You can use
     /// the `--list` argument to find paths to use here.
@@ -111,6 +124,7 @@ pub(crate) fn cmd_resolve(
     )?;
     let mut tx = workspace_command.start_transaction();
     let new_tree_id = merge_editor.edit_file(&tree, repo_path)?;
+    tx.set_record_resolutions(!args.no_rerere);
     let new_commit = tx
         .repo_mut()
         .rewrite_commit(command.settings(), &commit)
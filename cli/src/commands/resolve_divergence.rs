@@ -0,0 +1,273 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::rewrite::rebase_to_dest_parent;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::description_util::combine_messages;
+use crate::diff_util::DiffFormatArgs;
+use crate::ui::Ui;
+
+/// Make a divergent change visible as a single commit again
+///
+/// A change becomes divergent when more than one visible commit has its change
+/// ID, for example when it was rewritten by two concurrent operations. This
+/// command shows the divergent versions and their diffs, then keeps one of
+/// them or merges their contents into one commit. The other versions are
+/// abandoned, and their descendants and bookmarks are moved to the resulting
+/// commit.
+///
+/// Without `--keep` or `--merge`, you are asked which version to keep if the
+/// terminal is interactive. Otherwise the versions are only listed.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct ResolveDivergenceArgs {
+    /// A revision of the divergent change
+    #[arg(
+        long, short,
+        default_value = "@",
+        add = ArgValueCandidates::new(complete::mutable_revisions),
+    )]
+    revision: RevisionArg,
+    /// The version of the change to keep
+    #[arg(
+        long,
+        conflicts_with = "merge",
+        add = ArgValueCandidates::new(complete::mutable_revisions),
+    )]
+    keep: Option<RevisionArg>,
+    /// Merge the contents of all versions into one commit
+    ///
+    /// Changes made by each version since their common predecessor are
+    /// combined. If the versions made conflicting changes, the resulting
+    /// commit will have conflicts.
+    #[arg(long)]
+    merge: bool,
+    #[command(flatten)]
+    format: DiffFormatArgs,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_resolve_divergence(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ResolveDivergenceArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let commits: Vec<_> = workspace_command
+        .parse_revset(ui, &args.revision)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let change_ids = commits
+        .iter()
+        .map(|commit| commit.change_id())
+        .unique()
+        .collect_vec();
+    let change_id = match change_ids.as_slice() {
+        [] => return Err(user_error("Revset didn't resolve to any revisions")),
+        [change_id] => (*change_id).clone(),
+        _ => {
+            return Err(user_error(
+                "Revset resolved to revisions of more than one change",
+            ))
+        }
+    };
+    let repo = workspace_command.repo().clone();
+    let versions: Vec<Commit> = repo
+        .resolve_change_id(&change_id)
+        .unwrap_or_default()
+        .iter()
+        .map(|id| repo.store().get_commit(id))
+        .try_collect()?;
+    if versions.len() < 2 {
+        return Err(user_error(format!(
+            "Change {} is not divergent",
+            change_id.reverse_hex()
+        )));
+    }
+    workspace_command.check_rewritable(versions.iter().ids())?;
+
+    let keep = if let Some(keep_arg) = &args.keep {
+        let keep = workspace_command.resolve_single_rev(ui, keep_arg)?;
+        if !versions.contains(&keep) {
+            return Err(user_error(format!(
+                "Commit {} is not a version of the divergent change",
+                keep.id().hex()
+            )));
+        }
+        Some(keep)
+    } else if args.merge {
+        None
+    } else {
+        print_versions(ui, &workspace_command, &versions, &args.format)?;
+        if !Ui::can_prompt() {
+            writeln!(
+                ui.hint_default(),
+                "Use `--keep` to keep one of the versions or `--merge` to merge them."
+            )?;
+            return Ok(());
+        }
+        let mut choices = (1..=versions.len()).map(|i| i.to_string()).collect_vec();
+        choices.push("m".to_owned());
+        choices.push("q".to_owned());
+        let choice = ui.prompt_choice(
+            "Enter the index of the version to keep, m to merge them, or q to quit",
+            &choices,
+            None,
+        )?;
+        match choice.as_str() {
+            "q" => return Err(user_error("Divergence not resolved")),
+            "m" => None,
+            index => Some(versions[index.parse::<usize>().unwrap() - 1].clone()),
+        }
+    };
+
+    let mut tx = workspace_command.start_transaction();
+    let (result, others) = if let Some(keep) = keep {
+        let others = versions
+            .iter()
+            .filter(|commit| commit.id() != keep.id())
+            .cloned()
+            .collect_vec();
+        (keep, others)
+    } else {
+        let (destination, sources) = versions.split_first().unwrap();
+        let mut tree = destination.tree()?;
+        for source in sources {
+            let base = match common_predecessor(destination, source)? {
+                Some(predecessor) => rebase_to_dest_parent(tx.repo(), &[predecessor], destination)?,
+                None => destination.parent_tree(tx.repo())?,
+            };
+            let source_tree =
+                rebase_to_dest_parent(tx.repo(), std::slice::from_ref(source), destination)?;
+            tree = tree.merge(&base, &source_tree)?;
+        }
+        let description = if sources
+            .iter()
+            .all(|source| source.description() == destination.description())
+        {
+            destination.description().to_owned()
+        } else {
+            combine_messages(
+                tx.base_workspace_helper().repo_path(),
+                &sources.iter().collect_vec(),
+                destination,
+                command.settings(),
+            )?
+        };
+        let merged = tx
+            .repo_mut()
+            .rewrite_commit(command.settings(), destination)
+            .set_tree_id(tree.id())
+            .set_predecessors(versions.iter().ids().cloned().collect())
+            .set_description(description)
+            .write()?;
+        (merged, sources.to_vec())
+    };
+    for commit in &others {
+        tx.repo_mut()
+            .set_rewritten_commit(commit.id().clone(), result.id().clone());
+    }
+    let num_rebased = tx.repo_mut().rebase_descendants(command.settings())?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Resolved divergence with commit ")?;
+        tx.write_commit_summary(formatter.as_mut(), &result)?;
+        writeln!(formatter)?;
+        writeln!(
+            formatter,
+            "Abandoned {} other versions of the change",
+            others.len()
+        )?;
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+        }
+    }
+    tx.finish(
+        ui,
+        format!("resolve divergence of change {}", change_id.reverse_hex()),
+    )?;
+    Ok(())
+}
+
+fn print_versions(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    versions: &[Commit],
+    format: &DiffFormatArgs,
+) -> Result<(), CommandError> {
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let template = workspace_command.commit_summary_template();
+    let diff_renderer = workspace_command.diff_renderer_for(format)?;
+    writeln!(formatter, "The change has {} versions:", versions.len())?;
+    for (i, commit) in versions.iter().enumerate() {
+        write!(formatter, "{}: ", i + 1)?;
+        template.format(commit, formatter.as_mut())?;
+        writeln!(formatter)?;
+        diff_renderer.show_patch(
+            ui,
+            formatter.as_mut(),
+            commit,
+            &EverythingMatcher,
+            ui.term_width(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Finds the closest commit both versions evolved from.
+fn common_predecessor(a: &Commit, b: &Commit) -> Result<Option<Commit>, CommandError> {
+    let mut a_predecessors: HashSet<CommitId> = HashSet::new();
+    let mut queue = VecDeque::from([a.clone()]);
+    while let Some(commit) = queue.pop_front() {
+        for predecessor in commit.predecessors() {
+            let predecessor = predecessor?;
+            if a_predecessors.insert(predecessor.id().clone()) {
+                queue.push_back(predecessor);
+            }
+        }
+    }
+    let mut visited: HashSet<CommitId> = HashSet::new();
+    let mut queue = VecDeque::from([b.clone()]);
+    while let Some(commit) = queue.pop_front() {
+        for predecessor in commit.predecessors() {
+            let predecessor = predecessor?;
+            if a_predecessors.contains(predecessor.id()) {
+                return Ok(Some(predecessor));
+            }
+            if visited.insert(predecessor.id().clone()) {
+                queue.push_back(predecessor);
+            }
+        }
+    }
+    Ok(None)
+}
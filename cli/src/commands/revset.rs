@@ -0,0 +1,199 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use bstr::ByteVec as _;
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetEvaluationError;
+use jj_lib::revset::RevsetIteratorExt as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::commit_templater::CommitTemplateLanguage;
+use crate::complete;
+use crate::formatter::PlainTextFormatter;
+use crate::ui::Ui;
+
+/// Commands for working with revsets
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum RevsetCommand {
+    Evaluate(RevsetEvaluateArgs),
+}
+
+/// Evaluate a revset and print the matching revisions
+///
+/// This is meant for scripts. Unlike `jj log`, the output doesn't depend on
+/// the configured templates, and it's never colored or paged. Revisions are
+/// printed in the same order as `jj log --no-graph`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct RevsetEvaluateArgs {
+    /// The revset to evaluate
+    #[arg(add = ArgValueCandidates::new(complete::all_revisions))]
+    revset: RevisionArg,
+    /// How to print the matching revisions
+    #[arg(long, value_enum, default_value_t = EvaluateFormat::Lines)]
+    format: EvaluateFormat,
+    /// Which ID to print in the `lines` and `nul` formats
+    #[arg(long, value_enum, default_value_t = EvaluateId::CommitId)]
+    id: EvaluateId,
+    /// Additional field to include in the `json` format, as `NAME=TEMPLATE`
+    ///
+    /// The template is rendered as a string for each revision. For the syntax,
+    /// see https://martinvonz.github.io/jj/latest/templates/.
+    #[arg(long, value_name = "NAME=TEMPLATE", value_parser = parse_field)]
+    field: Vec<(String, String)>,
+    /// Print the revisions in the opposite order (older revisions first)
+    #[arg(long)]
+    reversed: bool,
+    /// Limit number of revisions to print
+    #[arg(long, short = 'n')]
+    limit: Option<usize>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum EvaluateFormat {
+    /// One ID per line
+    Lines,
+    /// One ID per entry, each terminated by a NUL byte
+    Nul,
+    /// A JSON array with one object per revision, containing `commit_id`,
+    /// `change_id`, and the fields selected by `--field`
+    Json,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+#[value(rename_all = "snake_case")]
+enum EvaluateId {
+    /// Full commit ID
+    CommitId,
+    /// Full change ID
+    ChangeId,
+}
+
+fn parse_field(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, template)) if !name.is_empty() => Ok((name.to_owned(), template.to_owned())),
+        _ => Err("field must be of the form NAME=TEMPLATE".to_owned()),
+    }
+}
+
+pub fn cmd_revset(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &RevsetCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        RevsetCommand::Evaluate(args) => cmd_revset_evaluate(ui, command, args),
+    }
+}
+
+#[instrument(skip_all)]
+fn cmd_revset_evaluate(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &RevsetEvaluateArgs,
+) -> Result<(), CommandError> {
+    if !args.field.is_empty() && args.format != EvaluateFormat::Json {
+        return Err(user_error("--field can only be used with --format json"));
+    }
+    if let Some(name) = args
+        .field
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .chain(["commit_id", "change_id"])
+        .duplicates()
+        .next()
+    {
+        return Err(user_error(format!(
+            "Field \"{name}\" is specified more than once"
+        )));
+    }
+    let workspace_command = command.workspace_helper(ui)?;
+    let revset = workspace_command
+        .parse_revset(ui, &args.revset)?
+        .evaluate()?;
+    let field_templates = {
+        let language = workspace_command.commit_template_language();
+        args.field
+            .iter()
+            .map(|(name, text)| {
+                let template = workspace_command.parse_template(
+                    ui,
+                    &language,
+                    text,
+                    CommitTemplateLanguage::wrap_commit,
+                )?;
+                Ok::<_, CommandError>((name, template))
+            })
+            .try_collect::<_, Vec<_>, _>()?
+    };
+
+    let store = workspace_command.repo().store();
+    let iter: Box<dyn Iterator<Item = Result<CommitId, RevsetEvaluationError>>> = if args.reversed {
+        Box::new(revset.iter().reversed()?)
+    } else {
+        Box::new(revset.iter())
+    };
+    let commits = iter.commits(store).take(args.limit.unwrap_or(usize::MAX));
+    let mut stdout = ui.stdout();
+    match args.format {
+        EvaluateFormat::Lines | EvaluateFormat::Nul => {
+            let terminator = if args.format == EvaluateFormat::Nul {
+                "\0"
+            } else {
+                "\n"
+            };
+            for commit in commits {
+                let commit = commit?;
+                let id = match args.id {
+                    EvaluateId::CommitId => commit.id().hex(),
+                    EvaluateId::ChangeId => commit.change_id().reverse_hex(),
+                };
+                write!(stdout, "{id}{terminator}")?;
+            }
+        }
+        EvaluateFormat::Json => {
+            let entries: Vec<_> = commits
+                .map_ok(|commit| {
+                    let mut entry = serde_json::Map::new();
+                    entry.insert("commit_id".to_owned(), commit.id().hex().into());
+                    entry.insert(
+                        "change_id".to_owned(),
+                        commit.change_id().reverse_hex().into(),
+                    );
+                    for (name, template) in &field_templates {
+                        let mut output = vec![];
+                        template
+                            .format(&commit, &mut PlainTextFormatter::new(&mut output))
+                            .expect("write() to vec backed formatter should never fail");
+                        entry.insert((*name).clone(), output.into_string_lossy().into());
+                    }
+                    entry
+                })
+                .try_collect()?;
+            serde_json::to_writer_pretty(&mut stdout, &entries).map_err(internal_error)?;
+            writeln!(stdout)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,536 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::io::BufRead as _;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use itertools::Itertools as _;
+use jj_lib::backend::Signature;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_walk;
+use jj_lib::operation::Operation;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPathBuf;
+use serde::Deserialize;
+use serde_json::json;
+use serde_json::Value;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Answer queries about the repo from other processes (experimental)
+///
+/// The repo is loaded once and kept in memory, so that editor integrations
+/// and other tools can query it without running a new `jj` process for each
+/// query. The repo is reloaded when another command has changed it, or when
+/// the refs of the backing Git repo have changed. The working copy is not
+/// snapshotted, but changes made by Git to the refs of a colocated repo are
+/// imported.
+///
+/// Each request is a line of JSON such as `{"id": 1, "method": "revset",
+/// "params": {"revset": "@"}}`. It is answered by a line of JSON with the same
+/// `id` and either a `result` or an `error` object with a `message`.
+///
+/// Available methods:
+///
+/// * `revset`: Commits in the `revset` param, newest first, at most `limit`
+///   (default 100)
+///
+/// * `files`: Paths of the files in the `revision` param (default `@`)
+///
+/// * `file`: Contents of the file at `path` in `revision` (default `@`)
+///
+/// * `bookmarks`: Local bookmarks and the commits they point to
+///
+/// * `operations`: Operations in the op log, newest first, at most `limit`
+///   (default 100)
+///
+/// * `status`: The process id of the server and the operation it has loaded
+///
+/// * `shutdown`: Stop the server. When listening on a socket, the `token`
+///   param must be the contents of the `<socket>.token` file, which only the
+///   user running the server can read.
+#[derive(clap::Args, Clone, Debug)]
+#[command(group(clap::ArgGroup::new("transport").args(&["socket", "stdio"]).required(true)))]
+pub(crate) struct ServeArgs {
+    /// Listen for connections on a Unix domain socket at this path
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    socket: Option<String>,
+    /// Serve a single client on stdin and stdout
+    #[arg(long)]
+    stdio: bool,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_serve(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ServeArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let mut server = Server {
+        git_refs_state: git_refs_state(&workspace_command),
+        workspace_command,
+        shutdown_token: None,
+    };
+    if let Some(path) = &args.socket {
+        serve_socket(ui, command, &mut server, Path::new(path))
+    } else {
+        let mut stdout = io::stdout().lock();
+        for line in io::stdin().lock().lines() {
+            let (response, shutdown) = server.handle_line(ui, command, &line?);
+            writeln!(stdout, "{response}")?;
+            stdout.flush()?;
+            if shutdown {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the path of a file kept next to the socket at `path`.
+#[cfg(unix)]
+fn socket_sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut sibling = path.as_os_str().to_owned();
+    sibling.push(suffix);
+    PathBuf::from(sibling)
+}
+
+#[cfg(unix)]
+fn serve_socket(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    server: &mut Server,
+    path: &Path,
+) -> Result<(), CommandError> {
+    use std::io::BufReader;
+    use std::os::unix::fs::OpenOptionsExt as _;
+    use std::os::unix::net::UnixListener;
+    use std::os::unix::net::UnixStream;
+    use std::sync::mpsc;
+    use std::thread;
+
+    use jj_lib::lock::FileLock;
+
+    // Don't create the lock file next to something that isn't ours.
+    check_socket_path(path)?;
+    // The lock is held for as long as the server runs, so a socket left behind
+    // by a server that was killed can be replaced without racing against
+    // another server starting up.
+    let lock_path = socket_sibling_path(path, ".lock");
+    let _lock = FileLock::try_lock(lock_path)
+        .map_err(|err| user_error_with_message("Failed to lock the socket", err))?
+        .ok_or_else(|| {
+            user_error(format!(
+                "Another server is already listening on {}",
+                path.display()
+            ))
+        })?;
+    let token_path = socket_sibling_path(path, ".token");
+    remove_stale_socket(path, &token_path)?;
+
+    // Only clients that can read the token may stop the server.
+    let token = format!("{:032x}", rand::random::<u128>());
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&token_path)?
+        .write_all(token.as_bytes())?;
+    server.shutdown_token = Some(token);
+
+    let listener = UnixListener::bind(path).map_err(|err| {
+        user_error_with_message(format!("Failed to listen on {}", path.display()), err)
+    })?;
+    writeln!(ui.status(), "Listening on {}", path.display())?;
+
+    // Connections are read on their own threads, but the requests are all
+    // answered here since the loaded repo can't be shared between threads.
+    let (request_tx, request_rx) = mpsc::channel::<(String, UnixStream)>();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let request_tx = request_tx.clone();
+            thread::spawn(move || -> io::Result<()> {
+                for line in BufReader::new(stream.try_clone()?).lines() {
+                    if request_tx.send((line?, stream.try_clone()?)).is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            });
+        }
+    });
+    for (line, mut stream) in request_rx {
+        let (response, shutdown) = server.handle_line(ui, command, &line);
+        // The client may have disconnected without waiting for the response.
+        writeln!(stream, "{response}").ok();
+        if shutdown {
+            break;
+        }
+    }
+    fs::remove_file(path)?;
+    fs::remove_file(token_path)?;
+    Ok(())
+}
+
+/// Fails if something other than a socket exists at `path`.
+#[cfg(unix)]
+fn check_socket_path(path: &Path) -> Result<(), CommandError> {
+    use std::os::unix::fs::FileTypeExt as _;
+
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if !metadata.file_type().is_socket() => Err(user_error(format!(
+            "{} already exists and is not a socket",
+            path.display()
+        ))),
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Removes the socket and token left behind by a server that is no longer
+/// running. Fails instead if anything else is found at the paths.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path, token_path: &Path) -> Result<(), CommandError> {
+    use std::os::unix::net::UnixStream;
+
+    check_socket_path(path)?;
+    let socket_exists = fs::symlink_metadata(path).is_ok();
+    if socket_exists {
+        // A server that isn't holding the lock may still be running, e.g. if
+        // the lock file was removed by hand.
+        if UnixStream::connect(path).is_ok() {
+            return Err(user_error(format!(
+                "Another server is already listening on {}",
+                path.display()
+            )));
+        }
+        fs::remove_file(path)?;
+    }
+    match fs::symlink_metadata(token_path) {
+        // The token of the server that left the socket behind.
+        Ok(metadata) if socket_exists && metadata.is_file() => {
+            fs::remove_file(token_path)?;
+            Ok(())
+        }
+        Ok(_) => Err(user_error(format!(
+            "{} already exists",
+            token_path.display()
+        ))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(not(unix))]
+fn serve_socket(
+    _ui: &mut Ui,
+    _command: &CommandHelper,
+    _server: &mut Server,
+    _path: &Path,
+) -> Result<(), CommandError> {
+    Err(user_error(
+        "Unix domain sockets are not supported on this platform; use --stdio instead",
+    ))
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RevsetParams {
+    revset: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FilesParams {
+    revision: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileParams {
+    revision: Option<String>,
+    path: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ShutdownParams {
+    token: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct OperationsParams {
+    limit: Option<usize>,
+}
+
+const DEFAULT_LIMIT: usize = 100;
+
+struct Server {
+    workspace_command: WorkspaceCommandHelper,
+    git_refs_state: Option<Vec<GitRefFileState>>,
+    /// Token that a `shutdown` request must carry, if any.
+    shutdown_token: Option<String>,
+}
+
+impl Server {
+    /// Answers a request line. Returns the response line and whether the
+    /// server should stop.
+    fn handle_line(&mut self, ui: &Ui, command: &CommandHelper, line: &str) -> (String, bool) {
+        let request: Request = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(err) => {
+                let response = json!({
+                    "id": Value::Null,
+                    "error": {"message": format!("Invalid request: {err}")},
+                });
+                return (response.to_string(), false);
+            }
+        };
+        let result = self.handle_request(ui, command, &request);
+        let shutdown = request.method == "shutdown" && result.is_ok();
+        let response = match result {
+            Ok(result) => json!({"id": request.id, "result": result}),
+            Err(err) => json!({"id": request.id, "error": {"message": err.error.to_string()}}),
+        };
+        (response.to_string(), shutdown)
+    }
+
+    fn handle_request(
+        &mut self,
+        ui: &Ui,
+        command: &CommandHelper,
+        request: &Request,
+    ) -> Result<Value, CommandError> {
+        if request.method == "shutdown" {
+            let params: ShutdownParams = parse_params(&request.params)?;
+            if self.shutdown_token.is_some() && params.token != self.shutdown_token {
+                return Err(user_error("Invalid or missing param: token"));
+            }
+            return Ok(Value::Null);
+        }
+        self.reload_if_changed(ui, command)?;
+        let workspace_command = &self.workspace_command;
+        match request.method.as_str() {
+            "status" => Ok(json!({
+                "pid": std::process::id(),
+                "operation_id": workspace_command.repo().op_id().hex(),
+            })),
+            "revset" => {
+                let params: RevsetParams = parse_params(&request.params)?;
+                let revset = params
+                    .revset
+                    .ok_or_else(|| user_error("Missing param: revset"))?;
+                let commits: Vec<_> = workspace_command
+                    .parse_revset(ui, &RevisionArg::from(revset))?
+                    .evaluate_to_commits()?
+                    .take(params.limit.unwrap_or(DEFAULT_LIMIT))
+                    .try_collect()?;
+                Ok(Value::Array(commits.iter().map(commit_to_json).collect()))
+            }
+            "files" => {
+                let params: FilesParams = parse_params(&request.params)?;
+                let commit = resolve_revision(ui, workspace_command, params.revision)?;
+                let paths = commit
+                    .tree()?
+                    .entries()
+                    .map(|(path, _)| Value::from(path.as_internal_file_string()))
+                    .collect();
+                Ok(Value::Array(paths))
+            }
+            "file" => {
+                let params: FileParams = parse_params(&request.params)?;
+                let commit = resolve_revision(ui, workspace_command, params.revision)?;
+                let path = params
+                    .path
+                    .ok_or_else(|| user_error("Missing param: path"))?;
+                let repo_path = RepoPathBuf::from_relative_path(&path)
+                    .map_err(|err| user_error_with_message(format!("Invalid path {path}"), err))?;
+                let value = commit.tree()?.path_value(&repo_path)?;
+                let id = match value.into_resolved() {
+                    Ok(Some(TreeValue::File { id, .. })) => id,
+                    Ok(_) => return Err(user_error(format!("No such file: {path}"))),
+                    Err(_) => return Err(user_error(format!("File is conflicted: {path}"))),
+                };
+                let mut content = Vec::new();
+                workspace_command
+                    .repo()
+                    .store()
+                    .read_file(&repo_path, &id)?
+                    .read_to_end(&mut content)?;
+                let content = String::from_utf8(content)
+                    .map_err(|_| user_error(format!("File is not valid UTF-8: {path}")))?;
+                Ok(json!({"content": content}))
+            }
+            "bookmarks" => {
+                let bookmarks = workspace_command
+                    .repo()
+                    .view()
+                    .local_bookmarks()
+                    .map(|(name, target)| {
+                        let ids = target.added_ids().map(|id| id.hex()).collect_vec();
+                        json!({"name": name, "targets": ids})
+                    })
+                    .collect();
+                Ok(Value::Array(bookmarks))
+            }
+            "operations" => {
+                let params: OperationsParams = parse_params(&request.params)?;
+                let head_op = workspace_command.repo().operation().clone();
+                let operations: Vec<_> = op_walk::walk_ancestors(&[head_op])
+                    .take(params.limit.unwrap_or(DEFAULT_LIMIT))
+                    .try_collect()?;
+                Ok(Value::Array(
+                    operations.iter().map(operation_to_json).collect(),
+                ))
+            }
+            method => Err(user_error(format!("Unknown method: {method}"))),
+        }
+    }
+
+    /// Reloads the repo if another process has changed the op heads or the
+    /// Git refs since it was loaded.
+    fn reload_if_changed(&mut self, ui: &Ui, command: &CommandHelper) -> Result<(), CommandError> {
+        if !command.is_at_head_operation() {
+            return Ok(());
+        }
+        let repo = self.workspace_command.repo();
+        let op_heads = repo.op_heads_store().get_op_heads()?;
+        let git_refs_changed = self.workspace_command.working_copy_shared_with_git()
+            && git_refs_state(&self.workspace_command) != self.git_refs_state;
+        if !git_refs_changed && op_heads == [repo.op_id().clone()] {
+            return Ok(());
+        }
+        self.workspace_command = command.workspace_helper_no_snapshot(ui)?;
+        if git_refs_changed {
+            // Import what Git changed in the colocated repo like other commands
+            // do, but leave the working copy alone.
+            self.workspace_command.import_git_refs(ui)?;
+        }
+        // Importing from or exporting to Git may have updated the refs again.
+        self.git_refs_state = git_refs_state(&self.workspace_command);
+        Ok(())
+    }
+}
+
+/// Path, size, and modification time of a file or directory storing Git refs.
+type GitRefFileState = (PathBuf, u64, Option<SystemTime>);
+
+/// Returns the state of the files storing the refs of the backing Git repo, so
+/// that changes made by Git can be noticed without reading the refs.
+///
+/// Git writes a loose ref by renaming a new file into place, which updates the
+/// modification time of the directory containing it, so only the directories
+/// are checked instead of every loose ref. Refs nested in further directories,
+/// such as `refs/heads/feature/foo`, are noticed when a ref in one of the
+/// checked directories or `packed-refs` changes.
+fn git_refs_state(workspace_command: &WorkspaceCommandHelper) -> Option<Vec<GitRefFileState>> {
+    let git_repo_path = workspace_command.git_backend()?.git_repo_path();
+    let mut paths = vec![
+        git_repo_path.join("HEAD"),
+        git_repo_path.join("packed-refs"),
+        git_repo_path.join("refs").join("heads"),
+        git_repo_path.join("refs").join("tags"),
+    ];
+    if let Ok(entries) = fs::read_dir(git_repo_path.join("refs").join("remotes")) {
+        paths.extend(entries.filter_map(|entry| Some(entry.ok()?.path())));
+    }
+    let mut state = paths
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            Some((path, metadata.len(), metadata.modified().ok()))
+        })
+        .collect_vec();
+    state.sort();
+    Some(state)
+}
+
+fn parse_params<T: Default + for<'de> Deserialize<'de>>(params: &Value) -> Result<T, CommandError> {
+    if params.is_null() {
+        return Ok(T::default());
+    }
+    T::deserialize(params).map_err(|err| user_error(format!("Invalid params: {err}")))
+}
+
+fn resolve_revision(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    revision: Option<String>,
+) -> Result<Commit, CommandError> {
+    let revision = RevisionArg::from(revision.unwrap_or_else(|| "@".to_owned()));
+    workspace_command.resolve_single_rev(ui, &revision)
+}
+
+fn commit_to_json(commit: &Commit) -> Value {
+    json!({
+        "commit_id": commit.id().hex(),
+        "change_id": commit.change_id().reverse_hex(),
+        "parent_ids": commit.parent_ids().iter().map(|id| id.hex()).collect_vec(),
+        "description": commit.description(),
+        "author": signature_to_json(commit.author()),
+        "committer": signature_to_json(commit.committer()),
+    })
+}
+
+fn signature_to_json(signature: &Signature) -> Value {
+    json!({
+        "name": signature.name,
+        "email": signature.email,
+        "timestamp": signature.timestamp.timestamp.0,
+        "tz_offset": signature.timestamp.tz_offset,
+    })
+}
+
+fn operation_to_json(operation: &Operation) -> Value {
+    let metadata = operation.metadata();
+    json!({
+        "id": operation.id().hex(),
+        "parent_ids": operation.parent_ids().iter().map(|id| id.hex()).collect_vec(),
+        "description": metadata.description,
+        "user": format!("{}@{}", metadata.username, metadata.hostname),
+        "start_time": metadata.start_time.timestamp.0,
+        "end_time": metadata.end_time.timestamp.0,
+    })
+}
@@ -13,29 +13,38 @@
 // limitations under the License.
 
 use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
 use jj_lib::matchers::EverythingMatcher;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
 use crate::ui::Ui;
 
-/// Show commit description and changes in a revision
+/// Show commit description and changes in revisions
+///
+/// If the revisions resolve to more than one commit, the commits are shown one
+/// after another, newest first. The `templates.show_separator` template is
+/// rendered between them.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct ShowArgs {
-    /// Show changes in this revision, compared to its parent(s)
+    /// Show changes in these revisions, compared to their parent(s)
     #[arg(default_value = "@", add = ArgValueCandidates::new(complete::all_revisions))]
-    revision: RevisionArg,
+    revisions: Vec<RevisionArg>,
     /// Ignored (but lets you pass `-r` for consistency with other commands)
     #[arg(short = 'r', hide = true)]
     unused_revision: bool,
+    /// Show revisions in the opposite order (older revisions first)
+    #[arg(long)]
+    reversed: bool,
     /// Render a revision using the given template
     ///
     /// For the syntax, see https://martinvonz.github.io/jj/latest/templates/
-    #[arg(long, short = 'T')]
+    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::commit_templates))]
     template: Option<String>,
     #[command(flatten)]
     format: DiffFormatArgs,
@@ -48,17 +57,35 @@ pub(crate) fn cmd_show(
     args: &ShowArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
-    let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let mut commits: Vec<_> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    if commits.is_empty() {
+        return Err(user_error("Empty revision set"));
+    }
+    if args.reversed {
+        commits.reverse();
+    }
     let template_string = match &args.template {
         Some(value) => value.to_string(),
         None => command.settings().get_string("templates.show")?,
     };
     let template = workspace_command.parse_commit_template(ui, &template_string)?;
+    let separator_template = workspace_command.parse_commit_template(
+        ui,
+        &command.settings().get_string("templates.show_separator")?,
+    )?;
     let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
-    template.format(&commit, formatter)?;
-    diff_renderer.show_patch(ui, formatter, &commit, &EverythingMatcher, ui.term_width())?;
+    for (i, commit) in commits.iter().enumerate() {
+        if i > 0 {
+            separator_template.format(commit, formatter)?;
+        }
+        template.format(commit, formatter)?;
+        diff_renderer.show_patch(ui, formatter, commit, &EverythingMatcher, ui.term_width())?;
+    }
     Ok(())
 }
@@ -15,6 +15,8 @@ use std::io::Write;
 
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use jj_lib::backend::Signature;
+use jj_lib::commit_builder::DetachedCommitBuilder;
 use jj_lib::object_id::ObjectId;
 use jj_lib::repo::Repo;
 use tracing::instrument;
@@ -24,8 +26,13 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::add_gerrit_change_id_trailer;
+use crate::description_util::add_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
+use crate::description_util::set_default_description;
+use crate::description_util::verify_description;
+use crate::text_util::parse_author;
 use crate::ui::Ui;
 
 /// Split a revision in two
@@ -53,7 +60,11 @@ pub(crate) struct SplitArgs {
     #[arg(long, short)]
     interactive: bool,
     /// Specify diff editor to be used (implies --interactive)
-    #[arg(long, value_name = "NAME")]
+    #[arg(
+        long,
+        value_name = "NAME",
+        add = ArgValueCandidates::new(complete::diff_editors)
+    )]
     tool: Option<String>,
     /// The revision to split
     #[arg(
@@ -73,6 +84,29 @@ pub(crate) struct SplitArgs {
         add = ArgValueCompleter::new(complete::modified_revision_files),
     )]
     filesets: Vec<String>,
+    /// Reset the author of both commits to the configured user
+    ///
+    /// This resets the author name, email, and timestamp.
+    ///
+    /// You can use it in combination with the JJ_USER and JJ_EMAIL
+    /// environment variables to set a different author:
+    ///
+    /// $ JJ_USER='Foo Bar' JJ_EMAIL=foo@bar.com jj split --reset-author
+    #[arg(long)]
+    reset_author: bool,
+    /// Set author of both commits to the provided string
+    ///
+    /// This changes author name and email while retaining author
+    /// timestamp for non-discardable commits.
+    #[arg(
+        long,
+        conflicts_with = "reset_author",
+        value_parser = parse_author
+    )]
+    author: Option<(String, String)>,
+    /// Don't check the description against the `commit.verify` settings
+    #[arg(long)]
+    no_verify: bool,
 }
 
 #[instrument(skip_all)]
@@ -133,6 +167,20 @@ The remainder will be in the second commit.
         )?;
     }
 
+    let set_author = |commit_builder: &mut DetachedCommitBuilder| {
+        if args.reset_author {
+            commit_builder.set_author(commit_builder.committer().clone());
+        }
+        if let Some((name, email)) = args.author.clone() {
+            let new_author = Signature {
+                name,
+                email,
+                timestamp: commit_builder.author().timestamp,
+            };
+            commit_builder.set_author(new_author);
+        }
+    };
+
     // Create the first commit, which includes the changes selected by the user.
     let selected_tree = tx.repo().store().get_root_tree(&selected_tree_id)?;
     let first_commit = {
@@ -141,9 +189,8 @@ The remainder will be in the second commit.
             .rewrite_commit(command.settings(), &commit)
             .detach();
         commit_builder.set_tree_id(selected_tree_id);
-        if commit_builder.description().is_empty() {
-            commit_builder.set_description(command.settings().default_description());
-        }
+        set_author(&mut commit_builder);
+        set_default_description(ui, &tx, &mut commit_builder)?;
         let temp_commit = commit_builder.write_hidden()?;
         let template = description_template(
             ui,
@@ -156,6 +203,12 @@ The remainder will be in the second commit.
             &template,
             command.settings(),
         )?;
+        let description =
+            add_gerrit_change_id_trailer(command.settings(), description, commit.change_id())?;
+        let description = add_trailers(ui, &tx, &commit, description)?;
+        if !args.no_verify {
+            verify_description(command.settings(), &description)?;
+        }
         commit_builder.set_description(description);
         commit_builder.write(tx.repo_mut())?
     };
@@ -186,6 +239,7 @@ The remainder will be in the second commit.
             // Generate a new change id so that the commit being split doesn't
             // become divergent.
             .generate_new_change_id();
+        set_author(&mut commit_builder);
         let description = if commit.description().is_empty() {
             // If there was no description before, don't ask for one for the
             // second commit.
@@ -204,6 +258,15 @@ The remainder will be in the second commit.
                 command.settings(),
             )?
         };
+        let description = add_gerrit_change_id_trailer(
+            command.settings(),
+            description,
+            commit_builder.change_id(),
+        )?;
+        let description = add_trailers(ui, &tx, &commit, description)?;
+        if !args.no_verify {
+            verify_description(command.settings(), &description)?;
+        }
         commit_builder.set_description(description);
         commit_builder.write(tx.repo_mut())?
     };
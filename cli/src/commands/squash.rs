@@ -12,24 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+use std::io::Write as _;
+
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt;
 use jj_lib::matchers::Matcher;
 use jj_lib::object_id::ObjectId;
 use jj_lib::repo::Repo;
+use jj_lib::revset::RevsetExpression;
 use jj_lib::rewrite;
 use jj_lib::settings::UserSettings;
+use regex::Regex;
 use tracing::instrument;
 
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::DiffSelector;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::description_util::combine_messages;
@@ -88,7 +97,11 @@ pub(crate) struct SquashArgs {
     #[arg(long, short)]
     interactive: bool,
     /// Specify diff editor to be used (implies --interactive)
-    #[arg(long, value_name = "NAME")]
+    #[arg(
+        long,
+        value_name = "NAME",
+        add = ArgValueCandidates::new(complete::diff_editors)
+    )]
     tool: Option<String>,
     /// Move only changes to these paths (instead of all paths)
     #[arg(
@@ -100,6 +113,23 @@ pub(crate) struct SquashArgs {
     /// The source revision will not be abandoned
     #[arg(long, short)]
     keep_emptied: bool,
+    /// Squash each of the `--from` revisions whose description matches this
+    /// regular expression into the nearest ancestor it refers to
+    ///
+    /// The referenced ancestor is the closest one whose first description
+    /// line equals the text captured by the first group of the expression,
+    /// or the rest of the line following the match if the expression has no
+    /// groups. For example, `jj squash --from 'mutable()'
+    /// --filter-description '^fixup! '` squashes every "fixup! <subject>"
+    /// revision into the revision described as "<subject>". The descriptions
+    /// of the destination revisions are kept.
+    #[arg(
+        long,
+        value_name = "REGEX",
+        requires = "from",
+        conflicts_with_all = ["into", "message_paragraphs", "interactive", "tool"],
+    )]
+    filter_description: Option<String>,
 }
 
 #[instrument(skip_all)]
@@ -110,6 +140,13 @@ pub(crate) fn cmd_squash(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
 
+    if let Some(pattern) = &args.filter_description {
+        let regex = Regex::new(pattern).map_err(|err| {
+            user_error_with_message(format!("Invalid regular expression: {pattern}"), err)
+        })?;
+        return squash_by_description(ui, command, &mut workspace_command, args, &regex);
+    }
+
     let mut sources: Vec<Commit>;
     let destination;
     if !args.from.is_empty() || args.into.is_some() {
@@ -167,6 +204,156 @@ pub(crate) fn cmd_squash(
     Ok(())
 }
 
+/// Squashes the `--from` revisions matching `regex` into the ancestors their
+/// descriptions refer to.
+fn squash_by_description(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    workspace_command: &mut WorkspaceCommandHelper,
+    args: &SquashArgs,
+    regex: &Regex,
+) -> Result<(), CommandError> {
+    let sources: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.from)?
+        .evaluate_to_commits()?
+        .filter_ok(|commit| regex.is_match(commit.description()))
+        .try_collect()?;
+    if sources.is_empty() {
+        writeln!(ui.status(), "No revisions match the description pattern.")?;
+        return Ok(());
+    }
+    let source_ids: HashSet<&CommitId> = sources.iter().ids().collect();
+
+    // Pair each destination with its sources, oldest sources first.
+    let mut groups: Vec<(Commit, Vec<Commit>)> = vec![];
+    for source in sources.iter().rev() {
+        let Some(subject) = referenced_subject(regex, source.description()) else {
+            return Err(user_error(format!(
+                "Description of revision {} doesn't refer to another revision",
+                short_commit_hash(source.id())
+            )));
+        };
+        let expression = RevsetExpression::commit(source.id().clone())
+            .parents()
+            .ancestors();
+        let destination = workspace_command
+            .attach_revset_evaluator(expression)
+            .evaluate_to_commits()?
+            .filter_ok(|commit| {
+                !source_ids.contains(commit.id())
+                    && commit
+                        .description()
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .trim()
+                        == subject
+            })
+            .next()
+            .transpose()?;
+        let Some(destination) = destination else {
+            return Err(user_error(format!(
+                "No ancestor of revision {} is described as {subject:?}",
+                short_commit_hash(source.id())
+            )));
+        };
+        match groups
+            .iter_mut()
+            .find(|(commit, _)| commit.id() == destination.id())
+        {
+            Some((_, group_sources)) => group_sources.push(source.clone()),
+            None => groups.push((destination, vec![source.clone()])),
+        }
+    }
+    workspace_command.check_rewritable(
+        sources
+            .iter()
+            .chain(groups.iter().map(|(destination, _)| destination))
+            .ids(),
+    )?;
+
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
+    let diff_selector = workspace_command.diff_selector(ui, None, false)?;
+    let mut tx = workspace_command.start_transaction();
+    for (destination, group_sources) in &groups {
+        // Earlier squashes may have rewritten the commits of this group.
+        tx.repo_mut().rebase_descendants(command.settings())?;
+        let destination = current_commit(tx.repo(), destination)?;
+        let mut source_commits = vec![];
+        for source in group_sources {
+            let source = current_commit(tx.repo(), source)?;
+            let parent_tree = source.parent_tree(tx.repo())?;
+            let selected_tree_id =
+                diff_selector.select(&parent_tree, &source.tree()?, matcher.as_ref(), || {
+                    String::new()
+                })?;
+            let selected_tree = tx.repo().store().get_root_tree(&selected_tree_id)?;
+            source_commits.push(rewrite::CommitToSquash {
+                commit: source,
+                selected_tree,
+                parent_tree,
+            });
+        }
+        rewrite::squash_commits(
+            command.settings(),
+            tx.repo_mut(),
+            &source_commits,
+            &destination,
+            args.keep_emptied,
+            |_| Ok::<_, CommandError>(destination.description().to_owned()),
+        )?;
+    }
+    tx.repo_mut().rebase_descendants(command.settings())?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        for (destination, group_sources) in &groups {
+            match group_sources.len() {
+                1 => write!(formatter, "Squashed 1 revision into ")?,
+                n => write!(formatter, "Squashed {n} revisions into ")?,
+            }
+            let destination = current_commit(tx.repo(), destination)?;
+            tx.write_commit_summary(formatter.as_mut(), &destination)?;
+            writeln!(formatter)?;
+        }
+    }
+    let tx_description = format!(
+        "squash {} commits matching {:?}",
+        sources.len(),
+        regex.as_str()
+    );
+    tx.finish(ui, tx_description)?;
+    Ok(())
+}
+
+/// Returns the description subject `description` refers to after matching
+/// `regex`.
+fn referenced_subject<'a>(regex: &Regex, description: &'a str) -> Option<&'a str> {
+    let captures = regex.captures(description)?;
+    let subject = match captures.get(1) {
+        Some(group) => group.as_str(),
+        None => {
+            let rest = &description[captures.get(0).unwrap().end()..];
+            rest.lines().next().unwrap_or_default()
+        }
+    };
+    Some(subject.trim()).filter(|subject| !subject.is_empty())
+}
+
+/// Looks up the visible commit that `commit` has been rewritten to.
+pub(crate) fn current_commit(repo: &dyn Repo, commit: &Commit) -> Result<Commit, CommandError> {
+    let commit_ids = repo
+        .resolve_change_id(commit.change_id())
+        .unwrap_or_default();
+    match commit_ids.as_slice() {
+        [commit_id] => Ok(repo.store().get_commit(commit_id)?),
+        _ => Err(user_error(format!(
+            "Revision {} was abandoned or became divergent",
+            short_commit_hash(commit.id())
+        ))),
+    }
+}
+
 enum SquashedDescription {
     // Use this exact description.
     Exact(String),
@@ -0,0 +1,56 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use super::get_stash;
+use super::parse_stash_index;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Drop a stash without applying it
+///
+/// The stashed commit can still be found in the operation log.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct StashDropArgs {
+    /// The stash to drop
+    #[arg(default_value = "0", value_parser = parse_stash_index)]
+    stash: usize,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_stash_drop(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &StashDropArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let stash = get_stash(&workspace_command, args.stash)?;
+    let mut tx = workspace_command.start_transaction();
+    let mut stash_ids = tx.repo().view().stash_ids().to_vec();
+    stash_ids.remove(args.stash);
+    tx.repo_mut().set_stash_ids(stash_ids);
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Dropped stash@{{{}}}: ", args.stash)?;
+        tx.write_commit_summary(formatter.as_mut(), &stash)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(ui, format!("drop stash {}", stash.id().hex()))?;
+    Ok(())
+}
@@ -0,0 +1,113 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod drop;
+mod list;
+mod pop;
+mod push;
+
+use jj_lib::commit::Commit;
+use jj_lib::repo::Repo as _;
+
+use self::drop::cmd_stash_drop;
+use self::drop::StashDropArgs;
+use self::list::cmd_stash_list;
+use self::list::StashListArgs;
+use self::pop::cmd_stash_apply;
+use self::pop::cmd_stash_pop;
+use self::pop::StashApplyArgs;
+use self::pop::StashPopArgs;
+use self::push::cmd_stash_push;
+use self::push::StashPushArgs;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Set working-copy changes aside and restore them later
+///
+/// jj doesn't need stashes, since the working copy is a commit that can be
+/// left behind with `jj new`. These commands are for users used to `git
+/// stash`. `jj stash` with no subcommand is the same as `jj stash push`.
+///
+/// Stashes are hidden commits, like abandoned commits, that are kept in a list
+/// until they're dropped. They're referred to by their position in the list,
+/// either as `N` or `stash@{N}`, where 0 is the most recent stash.
+#[derive(clap::Args, Clone, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct StashArgs {
+    #[command(subcommand)]
+    command: Option<StashCommand>,
+    #[command(flatten)]
+    push: StashPushArgs,
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+enum StashCommand {
+    Apply(StashApplyArgs),
+    Drop(StashDropArgs),
+    List(StashListArgs),
+    Pop(StashPopArgs),
+    Push(StashPushArgs),
+}
+
+pub(crate) fn cmd_stash(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &StashArgs,
+) -> Result<(), CommandError> {
+    match &args.command {
+        Some(StashCommand::Apply(args)) => cmd_stash_apply(ui, command, args),
+        Some(StashCommand::Drop(args)) => cmd_stash_drop(ui, command, args),
+        Some(StashCommand::List(args)) => cmd_stash_list(ui, command, args),
+        Some(StashCommand::Pop(args)) => cmd_stash_pop(ui, command, args),
+        Some(StashCommand::Push(args)) => cmd_stash_push(ui, command, args),
+        None => cmd_stash_push(ui, command, &args.push),
+    }
+}
+
+/// Parses a stash position given as `N` or `stash@{N}`.
+fn parse_stash_index(value: &str) -> Result<usize, String> {
+    let index = value
+        .strip_prefix("stash@{")
+        .and_then(|value| value.strip_suffix('}'))
+        .unwrap_or(value);
+    index
+        .parse()
+        .map_err(|_| format!("expected `N` or `stash@{{N}}`, got `{value}`"))
+}
+
+/// Looks up the stash at `index`.
+fn get_stash(
+    workspace_command: &WorkspaceCommandHelper,
+    index: usize,
+) -> Result<Commit, CommandError> {
+    let repo = workspace_command.repo();
+    let stash_id = repo
+        .view()
+        .stash_ids()
+        .get(index)
+        .ok_or_else(|| user_error(format!("No stash entry at stash@{{{index}}}")))?;
+    Ok(repo.store().get_commit(stash_id)?)
+}
+
+/// Returns the working-copy commit, checking that it can be rewritten.
+fn get_wc_commit(workspace_command: &WorkspaceCommandHelper) -> Result<Commit, CommandError> {
+    let commit_id = workspace_command
+        .get_wc_commit_id()
+        .ok_or_else(|| user_error("This command requires a working copy"))?;
+    workspace_command.check_rewritable([commit_id])?;
+    Ok(workspace_command.repo().store().get_commit(commit_id)?)
+}
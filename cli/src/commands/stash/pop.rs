@@ -0,0 +1,103 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use super::get_stash;
+use super::get_wc_commit;
+use super::parse_stash_index;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Apply the changes in a stash to the working copy, keeping the stash
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct StashApplyArgs {
+    /// The stash to apply
+    #[arg(default_value = "0", value_parser = parse_stash_index)]
+    stash: usize,
+}
+
+/// Apply the changes in a stash to the working copy, and drop the stash
+///
+/// If applying the stash results in conflicts, the stash is kept.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct StashPopArgs {
+    /// The stash to apply
+    #[arg(default_value = "0", value_parser = parse_stash_index)]
+    stash: usize,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_stash_apply(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &StashApplyArgs,
+) -> Result<(), CommandError> {
+    apply_stash(ui, command, args.stash, false)
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_stash_pop(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &StashPopArgs,
+) -> Result<(), CommandError> {
+    apply_stash(ui, command, args.stash, true)
+}
+
+fn apply_stash(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    index: usize,
+    drop: bool,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let stash = get_stash(&workspace_command, index)?;
+    let wc_commit = get_wc_commit(&workspace_command)?;
+    let stash_parent_tree = stash.parent_tree(workspace_command.repo().as_ref())?;
+    let new_tree = wc_commit
+        .tree()?
+        .merge(&stash_parent_tree, &stash.tree()?)?;
+
+    let mut tx = workspace_command.start_transaction();
+    tx.repo_mut()
+        .rewrite_commit(command.settings(), &wc_commit)
+        .set_tree_id(new_tree.id())
+        .write()?;
+    tx.repo_mut().rebase_descendants(command.settings())?;
+    let keep_stash = !drop || new_tree.has_conflict();
+    if !keep_stash {
+        let mut stash_ids = tx.repo().view().stash_ids().to_vec();
+        stash_ids.remove(index);
+        tx.repo_mut().set_stash_ids(stash_ids);
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Applied stash@{{{index}}}: ")?;
+        tx.write_commit_summary(formatter.as_mut(), &stash)?;
+        writeln!(formatter)?;
+        if drop && keep_stash {
+            writeln!(
+                formatter,
+                "The stash was kept because applying it resulted in conflicts"
+            )?;
+        }
+    }
+    tx.finish(ui, format!("apply stash {}", stash.id().hex()))?;
+    Ok(())
+}
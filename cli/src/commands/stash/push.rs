@@ -0,0 +1,101 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::rewrite::restore_tree;
+use tracing::instrument;
+
+use super::get_wc_commit;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Move working-copy changes into a new stash
+///
+/// The changes are moved into a new commit on top of the parents of the
+/// working-copy commit, which becomes the most recent stash.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct StashPushArgs {
+    /// Only stash the changes in these paths
+    #[arg(
+        value_hint = clap::ValueHint::AnyPath,
+        add = ArgValueCompleter::new(complete::modified_files),
+    )]
+    paths: Vec<String>,
+    /// Description of the stash (default: the description of the
+    /// working-copy commit)
+    #[arg(long, short)]
+    message: Option<String>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_stash_push(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &StashPushArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let wc_commit = get_wc_commit(&workspace_command)?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
+    let parent_tree = wc_commit.parent_tree(workspace_command.repo().as_ref())?;
+    let wc_tree = wc_commit.tree()?;
+    let stash_tree_id = restore_tree(&wc_tree, &parent_tree, matcher.as_ref())?;
+    if stash_tree_id == parent_tree.id() {
+        return Err(user_error("No changes to stash"));
+    }
+    let new_wc_tree_id = restore_tree(&parent_tree, &wc_tree, matcher.as_ref())?;
+
+    let mut tx = workspace_command.start_transaction();
+    let description = match &args.message {
+        Some(message) => format!("{message}\n"),
+        None => wc_commit.description().to_owned(),
+    };
+    let stash = tx
+        .repo_mut()
+        .new_commit(
+            command.settings(),
+            wc_commit.parent_ids().to_vec(),
+            stash_tree_id,
+        )
+        .set_description(description)
+        .write()?;
+    // Hide the stash like an abandoned commit. The view still references it.
+    tx.repo_mut().record_abandoned_commit(stash.id().clone());
+    tx.repo_mut()
+        .rewrite_commit(command.settings(), &wc_commit)
+        .set_tree_id(new_wc_tree_id)
+        .write()?;
+    tx.repo_mut().rebase_descendants(command.settings())?;
+    let mut stash_ids = tx.repo().view().stash_ids().to_vec();
+    stash_ids.insert(0, stash.id().clone());
+    tx.repo_mut().set_stash_ids(stash_ids);
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Saved stash@{{0}}: ")?;
+        tx.write_commit_summary(formatter.as_mut(), &stash)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(
+        ui,
+        format!("stash changes into commit {}", stash.id().hex()),
+    )?;
+    Ok(())
+}
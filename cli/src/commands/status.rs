@@ -12,15 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use futures::executor::block_on_stream;
 use itertools::Itertools;
+use jj_lib::commit::Commit;
 use jj_lib::copies::CopyRecords;
+use jj_lib::git;
+use jj_lib::matchers::Matcher;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::settings::UserSettings;
+use jj_lib::working_copy::UntrackedReason;
 use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::internal_error;
 use crate::command_error::CommandError;
 use crate::diff_util::get_copy_records;
 use crate::diff_util::DiffFormat;
@@ -33,12 +43,67 @@ use crate::ui::Ui;
 ///  * The working copy commit and its (first) parent, and a summary of the
 ///    changes between them
 ///  * Conflicted bookmarks (see https://martinvonz.github.io/jj/latest/bookmarks/)
+///
+/// More sections can be turned on with the `status.sections` config:
+///
+///  * Other commits of the working-copy change if it's divergent
+///  * Paths that weren't checked out because the file system can't represent
+///    them, e.g. paths differing only in case on a case-insensitive file system
+///  * Bookmarks on ancestors of the working copy that differ from their tracked
+///    remote bookmarks
 #[derive(clap::Args, Clone, Debug)]
 #[command(visible_alias = "st")]
 pub(crate) struct StatusArgs {
     /// Restrict the status display to these paths
     #[arg(value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    /// How to print the status
+    #[arg(long, value_enum, default_value_t = StatusFormat::Text)]
+    format: StatusFormat,
+}
+
+/// Output format of `jj status`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum StatusFormat {
+    /// Human-readable text
+    Text,
+    /// A JSON object
+    Json,
+}
+
+/// Sections of `jj status` enabled by the `status.sections` config.
+struct StatusSections {
+    changes: bool,
+    conflicts: bool,
+    divergence: bool,
+    untracked: bool,
+    skipped: bool,
+    bookmark_conflicts: bool,
+    bookmark_drift: bool,
+}
+
+impl StatusSections {
+    fn from_settings(settings: &UserSettings) -> Result<Self, CommandError> {
+        let enabled = |name: &'static str| settings.get_bool(["status", "sections", name]);
+        Ok(StatusSections {
+            changes: enabled("changes")?,
+            conflicts: enabled("conflicts")?,
+            divergence: enabled("divergence")?,
+            untracked: enabled("untracked")?,
+            skipped: enabled("skipped")?,
+            bookmark_conflicts: enabled("bookmark-conflicts")?,
+            bookmark_drift: enabled("bookmark-drift")?,
+        })
+    }
+}
+
+/// Local bookmark that points to a different commit than its tracked remote
+/// bookmark.
+struct BookmarkDrift {
+    name: String,
+    remote: String,
+    ahead: usize,
+    behind: usize,
 }
 
 #[instrument(skip_all)]
@@ -56,6 +121,16 @@ pub(crate) fn cmd_status(
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
+    let sections = StatusSections::from_settings(command.settings())?;
+    if args.format == StatusFormat::Json {
+        return write_json_status(
+            ui,
+            &workspace_command,
+            maybe_wc_commit.as_ref(),
+            matcher.as_ref(),
+            &sections,
+        );
+    }
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
@@ -63,7 +138,9 @@ pub(crate) fn cmd_status(
     if let Some(wc_commit) = &maybe_wc_commit {
         let parent_tree = wc_commit.parent_tree(repo.as_ref())?;
         let tree = wc_commit.tree()?;
-        if tree.id() == parent_tree.id() {
+        if !sections.changes {
+            // The changes section is disabled
+        } else if tree.id() == parent_tree.id() {
             writeln!(formatter, "The working copy is clean")?;
         } else {
             writeln!(formatter, "Working copy changes:")?;
@@ -85,15 +162,32 @@ pub(crate) fn cmd_status(
             )?;
         }
 
+        // Untracked paths aren't listed here because the snapshot has already
+        // warned about them.
+        if sections.skipped {
+            let skipped = unrepresentable_paths(&workspace_command, matcher.as_ref())?;
+            if !skipped.is_empty() {
+                writeln!(
+                    formatter,
+                    "Paths not checked out because the file system can't represent them:"
+                )?;
+                for path in skipped {
+                    writeln!(formatter, "  {}", workspace_command.format_file_path(path))?;
+                }
+            }
+        }
+
         // TODO: Conflicts should also be filtered by the `matcher`. See the related
         // TODO on `MergedTree::conflicts()`.
-        let conflicts = wc_commit.tree()?.conflicts().collect_vec();
-        if !conflicts.is_empty() {
-            writeln!(
-                formatter.labeled("conflict"),
-                "There are unresolved conflicts at these paths:"
-            )?;
-            print_conflicted_paths(conflicts, formatter, &workspace_command)?;
+        if sections.conflicts {
+            let conflicts = wc_commit.tree()?.conflicts().collect_vec();
+            if !conflicts.is_empty() {
+                writeln!(
+                    formatter.labeled("conflict"),
+                    "There are unresolved conflicts at these paths:"
+                )?;
+                print_conflicted_paths(conflicts, formatter, &workspace_command)?;
+            }
         }
 
         let template = workspace_command.commit_summary_template();
@@ -107,7 +201,24 @@ pub(crate) fn cmd_status(
             writeln!(formatter)?;
         }
 
-        if wc_commit.has_conflict()? {
+        if sections.divergence {
+            let divergent_commits = divergent_commits(repo.as_ref(), wc_commit)?;
+            if !divergent_commits.is_empty() {
+                writeln!(
+                    formatter.labeled("divergent"),
+                    "The working-copy change is divergent. Other commits of the change:"
+                )?;
+                for commit in &divergent_commits {
+                    write!(formatter, "  ")?;
+                    template.format(commit, formatter)?;
+                    writeln!(formatter)?;
+                }
+            }
+        }
+
+        if !sections.conflicts {
+            // The conflicts section is disabled
+        } else if wc_commit.has_conflict()? {
             let wc_revset = RevsetExpression::commit(wc_commit.id().clone());
 
             // Ancestors with conflicts, excluding the current working copy commit.
@@ -151,7 +262,7 @@ pub(crate) fn cmd_status(
         .filter(|(_, remote_ref)| remote_ref.target.has_conflict())
         .map(|(full_name, _)| full_name)
         .collect_vec();
-    if !conflicted_local_bookmarks.is_empty() {
+    if sections.bookmark_conflicts && !conflicted_local_bookmarks.is_empty() {
         writeln!(
             formatter.labeled("conflict"),
             "These bookmarks have conflicts:"
@@ -167,7 +278,7 @@ pub(crate) fn cmd_status(
              resolve."
         )?;
     }
-    if !conflicted_remote_bookmarks.is_empty() {
+    if sections.bookmark_conflicts && !conflicted_remote_bookmarks.is_empty() {
         writeln!(
             formatter.labeled("conflict"),
             "These remote bookmarks have conflicts:"
@@ -186,5 +297,224 @@ pub(crate) fn cmd_status(
         )?;
     }
 
+    if let Some(wc_commit) = maybe_wc_commit.as_ref().filter(|_| sections.bookmark_drift) {
+        let drifts = bookmark_drift(&workspace_command, wc_commit)?;
+        if !drifts.is_empty() {
+            writeln!(
+                formatter,
+                "These bookmarks differ from their tracked remotes:"
+            )?;
+            for drift in drifts {
+                write!(formatter, "  ")?;
+                write!(formatter.labeled("bookmark"), "{}", drift.name)?;
+                writeln!(
+                    formatter,
+                    ": {} ahead, {} behind {}@{}",
+                    drift.ahead, drift.behind, drift.name, drift.remote
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_json_status(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    maybe_wc_commit: Option<&Commit>,
+    matcher: &dyn Matcher,
+    sections: &StatusSections,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    let commit_json = |commit: &Commit| -> Result<serde_json::Value, CommandError> {
+        Ok(serde_json::json!({
+            "commit_id": commit.id().hex(),
+            "change_id": commit.change_id().reverse_hex(),
+            "description": commit.description(),
+            "conflict": commit.has_conflict()?,
+        }))
+    };
+    let mut status = serde_json::Map::new();
+    if let Some(wc_commit) = maybe_wc_commit {
+        status.insert("working_copy".to_owned(), commit_json(wc_commit)?);
+        let parents: Vec<_> = wc_commit
+            .parents()
+            .map(|parent| commit_json(&parent?))
+            .try_collect()?;
+        status.insert("parents".to_owned(), parents.into());
+        if sections.changes {
+            let parent_tree = wc_commit.parent_tree(repo.as_ref())?;
+            let tree = wc_commit.tree()?;
+            let mut changes = vec![];
+            for entry in block_on_stream(parent_tree.diff_stream(&tree, matcher)) {
+                let (before, after) = entry.values?;
+                let status = match (before.is_absent(), after.is_absent()) {
+                    (true, _) => "added",
+                    (_, true) => "removed",
+                    _ => "modified",
+                };
+                changes.push(serde_json::json!({
+                    "path": entry.path.as_internal_file_string(),
+                    "status": status,
+                }));
+            }
+            status.insert("changes".to_owned(), changes.into());
+        }
+        if sections.untracked {
+            let untracked = untracked_paths(workspace_command, matcher)
+                .into_iter()
+                .map(|(path, reason)| {
+                    let UntrackedReason::FileTooLarge { size, max_size } = reason;
+                    serde_json::json!({
+                        "path": path.as_internal_file_string(),
+                        "reason": "too-large",
+                        "size": size,
+                        "max_size": max_size,
+                    })
+                })
+                .collect_vec();
+            status.insert("untracked".to_owned(), untracked.into());
+        }
+        if sections.skipped {
+            let skipped = unrepresentable_paths(workspace_command, matcher)?
+                .into_iter()
+                .map(|path| path.as_internal_file_string().to_owned())
+                .collect_vec();
+            status.insert("skipped".to_owned(), skipped.into());
+        }
+        if sections.conflicts {
+            let conflicts = wc_commit
+                .tree()?
+                .conflicts()
+                .map(|(path, _)| path.as_internal_file_string().to_owned())
+                .collect_vec();
+            status.insert("conflicts".to_owned(), conflicts.into());
+        }
+        if sections.divergence {
+            let divergent: Vec<_> = divergent_commits(repo.as_ref(), wc_commit)?
+                .iter()
+                .map(commit_json)
+                .try_collect()?;
+            status.insert("divergent_commits".to_owned(), divergent.into());
+        }
+        if sections.bookmark_drift {
+            let drifts = bookmark_drift(workspace_command, wc_commit)?
+                .into_iter()
+                .map(|drift| {
+                    serde_json::json!({
+                        "name": drift.name,
+                        "remote": drift.remote,
+                        "ahead": drift.ahead,
+                        "behind": drift.behind,
+                    })
+                })
+                .collect_vec();
+            status.insert("bookmark_drift".to_owned(), drifts.into());
+        }
+    } else {
+        status.insert("working_copy".to_owned(), serde_json::Value::Null);
+    }
+    if sections.bookmark_conflicts {
+        let local = repo
+            .view()
+            .local_bookmarks()
+            .filter(|(_, target)| target.has_conflict())
+            .map(|(name, _)| name.to_owned())
+            .collect_vec();
+        let remote = repo
+            .view()
+            .all_remote_bookmarks()
+            .filter(|(_, remote_ref)| remote_ref.target.has_conflict())
+            .map(|((name, remote), _)| format!("{name}@{remote}"))
+            .collect_vec();
+        status.insert("conflicted_bookmarks".to_owned(), local.into());
+        status.insert("conflicted_remote_bookmarks".to_owned(), remote.into());
+    }
+    let mut formatter = ui.stdout_formatter();
+    serde_json::to_writer_pretty(&mut *formatter, &status).map_err(internal_error)?;
+    writeln!(formatter)?;
     Ok(())
 }
+
+/// Returns the other visible commits of the working-copy change.
+fn divergent_commits(repo: &dyn Repo, wc_commit: &Commit) -> Result<Vec<Commit>, CommandError> {
+    let commit_ids = repo
+        .resolve_change_id(wc_commit.change_id())
+        .unwrap_or_default();
+    let commits = commit_ids
+        .iter()
+        .filter(|id| *id != wc_commit.id())
+        .map(|id| repo.store().get_commit(id))
+        .try_collect()?;
+    Ok(commits)
+}
+
+/// Returns the new files the snapshot refused to track.
+fn untracked_paths<'a>(
+    workspace_command: &'a WorkspaceCommandHelper,
+    matcher: &dyn Matcher,
+) -> Vec<(&'a RepoPathBuf, &'a UntrackedReason)> {
+    workspace_command
+        .snapshot_stats()
+        .untracked_paths
+        .iter()
+        .filter(|(path, _)| matcher.matches(path))
+        .collect()
+}
+
+/// Returns the paths in the working-copy commit that couldn't be checked out.
+fn unrepresentable_paths<'a>(
+    workspace_command: &'a WorkspaceCommandHelper,
+    matcher: &dyn Matcher,
+) -> Result<Vec<&'a RepoPathBuf>, CommandError> {
+    let paths = workspace_command
+        .working_copy()
+        .unrepresentable_paths()?
+        .iter()
+        .filter(|path| matcher.matches(path))
+        .collect();
+    Ok(paths)
+}
+
+/// Returns the bookmarks on ancestors of the working-copy commit that differ
+/// from their tracked remote bookmarks.
+fn bookmark_drift(
+    workspace_command: &WorkspaceCommandHelper,
+    wc_commit: &Commit,
+) -> Result<Vec<BookmarkDrift>, CommandError> {
+    let repo = workspace_command.repo();
+    let count = |from: &jj_lib::backend::CommitId, to: &jj_lib::backend::CommitId| {
+        RevsetExpression::commit(from.clone())
+            .range(&RevsetExpression::commit(to.clone()))
+            .evaluate(repo.as_ref())
+            .map(|revset| revset.iter().count())
+    };
+    let mut drifts = vec![];
+    for (name, targets) in repo.view().bookmarks() {
+        let Some(local_id) = targets.local_target.as_normal() else {
+            continue;
+        };
+        if !repo.index().is_ancestor(local_id, wc_commit.id()) {
+            continue;
+        }
+        for (remote, remote_ref) in targets.remote_refs {
+            if remote == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO || !remote_ref.is_tracking() {
+                continue;
+            }
+            let Some(remote_id) = remote_ref.target.as_normal() else {
+                continue;
+            };
+            if remote_id == local_id {
+                continue;
+            }
+            drifts.push(BookmarkDrift {
+                name: name.to_owned(),
+                remote: remote.to_owned(),
+                ahead: count(remote_id, local_id)?,
+                behind: count(local_id, remote_id)?,
+            });
+        }
+    }
+    Ok(drifts)
+}
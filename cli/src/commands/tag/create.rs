@@ -0,0 +1,146 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::builder::NonEmptyStringValueParser;
+use clap_complete::ArgValueCandidates;
+use jj_lib::git;
+use jj_lib::git::GitTagSignError;
+use jj_lib::git::TagAnnotation;
+use jj_lib::git_backend::GitBackend;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::git_util::map_git_error;
+use crate::ui::Ui;
+
+/// Create a new tag
+///
+/// Without `--message` or `--sign`, a lightweight tag is created. Unlike
+/// bookmarks, tags never move when the tagged commit is rewritten.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TagCreateArgs {
+    /// The tag's target revision
+    #[arg(
+        long, short,
+        default_value = "@",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revision: RevisionArg,
+    /// Create an annotated tag with the given message
+    #[arg(long, short)]
+    message: Option<String>,
+    /// Create an annotated tag signed by the configured `signing.backend`
+    ///
+    /// The tag name is used as the message if `--message` isn't given.
+    #[arg(long)]
+    sign: bool,
+    /// The tags to create
+    #[arg(required = true, value_parser = NonEmptyStringValueParser::new())]
+    names: Vec<String>,
+}
+
+pub fn cmd_tag_create(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TagCreateArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let target_commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let repo = workspace_command.repo().clone();
+    for name in &args.names {
+        if repo.view().get_tag(name).is_present() {
+            return Err(user_error_with_hint(
+                format!("Tag already exists: {name}"),
+                "Use `jj tag delete` to delete it first.",
+            ));
+        }
+    }
+    let git_backend = repo.store().backend_impl().downcast_ref::<GitBackend>();
+    if git_backend.is_none() && (args.message.is_some() || args.sign) {
+        return Err(user_error(
+            "Annotated tags are only supported in Git-backed repos",
+        ));
+    }
+    if args.sign && !repo.store().signer().can_sign() {
+        return Err(user_error_with_hint(
+            "Cannot sign tags because no signing backend is configured",
+            "Set `signing.backend` to sign tags.",
+        ));
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    if let Some(git_backend) = git_backend {
+        let git_repo = git_backend.open_git_repo()?;
+        let settings = command.settings();
+        let tagger = settings.signature();
+        let key = settings.sign_settings().key;
+        for name in &args.names {
+            if git_repo
+                .find_reference(&format!("refs/tags/{name}"))
+                .is_ok()
+            {
+                return Err(user_error_with_hint(
+                    format!("Tag already exists in the Git repo: {name}"),
+                    "Run `jj git import` to import it.",
+                ));
+            }
+        }
+        for name in &args.names {
+            let annotation = TagAnnotation {
+                message: args.message.as_deref().unwrap_or(name),
+                tagger: &tagger,
+                signer: args.sign.then(|| (repo.store().signer(), key.as_deref())),
+            };
+            let annotation = (args.message.is_some() || args.sign).then_some(&annotation);
+            git::create_tag(&git_repo, name, target_commit.id(), annotation).map_err(|err| {
+                match err {
+                    GitTagSignError::InternalGitError(err) => map_git_error(err),
+                    _ => user_error(err),
+                }
+            })?;
+            // Record the ref so the next import doesn't see it as a change made
+            // in the Git repo.
+            tx.repo_mut().set_git_ref_target(
+                &format!("refs/tags/{name}"),
+                RefTarget::normal(target_commit.id().clone()),
+            );
+        }
+    }
+    for name in &args.names {
+        tx.repo_mut()
+            .set_tag_target(name, RefTarget::normal(target_commit.id().clone()));
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Created {} tags pointing to ", args.names.len())?;
+        tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(
+        ui,
+        format!(
+            "create tag {names} pointing to commit {id}",
+            names = args.names.join(", "),
+            id = target_commit.id().hex()
+        ),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use jj_lib::git;
+use jj_lib::git_backend::GitBackend;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+use jj_lib::str_util::StringPattern;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::git_util::map_git_error;
+use crate::ui::Ui;
+
+/// Delete existing tags
+///
+/// Deleting a conflicted tag resolves the conflict. Tags which were pushed to
+/// a remote are not deleted there.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TagDeleteArgs {
+    /// The tags to delete
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` prefix to
+    /// select tags by wildcard pattern. For details, see
+    /// https://martinvonz.github.io/jj/latest/revsets/#string-patterns.
+    #[arg(required = true, value_parser = StringPattern::parse)]
+    names: Vec<StringPattern>,
+}
+
+pub fn cmd_tag_delete(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TagDeleteArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().clone();
+    let mut names = vec![];
+    let mut unmatched_patterns = vec![];
+    for pattern in &args.names {
+        let mut matches = repo.view().tags_matching(pattern).peekable();
+        if matches.peek().is_none() {
+            unmatched_patterns.push(pattern);
+        }
+        names.extend(matches.map(|(name, _)| name.to_owned()));
+    }
+    match &unmatched_patterns[..] {
+        [] => {}
+        [pattern] if pattern.is_exact() => {
+            return Err(user_error(format!("No such tag: {pattern}")));
+        }
+        patterns => {
+            return Err(user_error(format!(
+                "No matching tags for patterns: {}",
+                patterns.iter().join(", ")
+            )));
+        }
+    }
+    let names = names.into_iter().unique().collect_vec();
+
+    let mut tx = workspace_command.start_transaction();
+    if let Some(git_backend) = repo.store().backend_impl().downcast_ref::<GitBackend>() {
+        let git_repo = git_backend.open_git_repo()?;
+        for name in &names {
+            git::delete_tag(&git_repo, name).map_err(map_git_error)?;
+            tx.repo_mut()
+                .set_git_ref_target(&format!("refs/tags/{name}"), RefTarget::absent());
+        }
+    }
+    for name in &names {
+        tx.repo_mut().set_tag_target(name, RefTarget::absent());
+    }
+    writeln!(ui.status(), "Deleted {} tags.", names.len())?;
+    tx.finish(ui, format!("delete tag {}", names.iter().join(", ")))?;
+    Ok(())
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ffi::OsStr;
+
 use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_message;
@@ -73,12 +75,20 @@ pub fn cmd_util_exec(
     _command: &CommandHelper,
     args: &UtilExecArgs,
 ) -> Result<(), CommandError> {
-    let status = std::process::Command::new(&args.command)
-        .args(&args.args)
+    run_external_command(&args.command, &args.args)
+}
+
+/// Runs the command, and fails if it didn't exit successfully.
+pub(super) fn run_external_command(
+    command: &str,
+    args: impl IntoIterator<Item = impl AsRef<OsStr>>,
+) -> Result<(), CommandError> {
+    let status = std::process::Command::new(command)
+        .args(args)
         .status()
         .map_err(|err| {
             user_error_with_message(
-                format!("Failed to execute external command '{}'", &args.command),
+                format!("Failed to execute external command '{command}'"),
                 err,
             )
         })?;
@@ -0,0 +1,77 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+
+use super::exec::run_external_command;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::commands::format_patch::format_patches;
+use crate::commands::format_patch::PatchSeriesArgs;
+use crate::ui::Ui;
+
+/// Execute an external command with revisions exported as patch files
+///
+/// The selected revisions are written as numbered patch emails, in the same
+/// format as `jj format-patch`, to a temporary directory. The paths of the
+/// patch files are appended to the arguments of the command, oldest patch
+/// first. The directory is deleted after the command exits.
+///
+/// This can be used to drive tools that send or review patch series, for
+/// example:
+///
+/// ```shell
+/// jj util exec-with-patches -r 'trunk()..@-' -- git send-email --to list@example.org
+/// ```
+///
+/// Or as an alias:
+///
+/// ```toml
+/// [aliases]
+/// send-series = ["util", "exec-with-patches", "-r", "trunk()..@-", "--", "git", "send-email"]
+/// ```
+#[derive(clap::Args, Clone, Debug)]
+#[command(verbatim_doc_comment)]
+pub(crate) struct UtilExecWithPatchesArgs {
+    #[command(flatten)]
+    series: PatchSeriesArgs,
+    /// External command to execute
+    command: String,
+    /// Arguments to pass to the external command before the patch files
+    args: Vec<String>,
+}
+
+pub fn cmd_util_exec_with_patches(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &UtilExecWithPatchesArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let patches = format_patches(ui, command, &workspace_command, &args.series)?;
+    let patch_dir = tempfile::Builder::new()
+        .prefix("jj-patches-")
+        .tempdir()
+        .map_err(|err| user_error_with_message("Failed to create temporary directory", err))?;
+    let mut patch_paths = vec![];
+    for patch in &patches {
+        let path = patch_dir.path().join(&patch.file_name);
+        fs::write(&path, &patch.content).map_err(|err| {
+            user_error_with_message(format!("Failed to write {}", path.display()), err)
+        })?;
+        patch_paths.push(path.into_os_string());
+    }
+    let command_args = args.args.iter().map(Into::into).chain(patch_paths);
+    run_external_command(&args.command, command_args)
+}
@@ -15,9 +15,12 @@
 mod completion;
 mod config_schema;
 mod exec;
+mod exec_with_patches;
 mod gc;
 mod mangen;
 mod markdown_help;
+mod prompt;
+mod resolutions;
 
 use clap::Subcommand;
 use tracing::instrument;
@@ -28,12 +31,18 @@ use self::config_schema::cmd_util_config_schema;
 use self::config_schema::UtilConfigSchemaArgs;
 use self::exec::cmd_util_exec;
 use self::exec::UtilExecArgs;
+use self::exec_with_patches::cmd_util_exec_with_patches;
+use self::exec_with_patches::UtilExecWithPatchesArgs;
 use self::gc::cmd_util_gc;
 use self::gc::UtilGcArgs;
 use self::mangen::cmd_util_mangen;
 use self::mangen::UtilMangenArgs;
 use self::markdown_help::cmd_util_markdown_help;
 use self::markdown_help::UtilMarkdownHelp;
+use self::prompt::cmd_util_prompt;
+use self::prompt::UtilPromptArgs;
+use self::resolutions::cmd_util_resolutions;
+use self::resolutions::UtilResolutionsArgs;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
@@ -44,9 +53,12 @@ pub(crate) enum UtilCommand {
     Completion(UtilCompletionArgs),
     ConfigSchema(UtilConfigSchemaArgs),
     Exec(UtilExecArgs),
+    ExecWithPatches(UtilExecWithPatchesArgs),
     Gc(UtilGcArgs),
     Mangen(UtilMangenArgs),
     MarkdownHelp(UtilMarkdownHelp),
+    Prompt(UtilPromptArgs),
+    Resolutions(UtilResolutionsArgs),
 }
 
 #[instrument(skip_all)]
@@ -59,8 +71,11 @@ pub(crate) fn cmd_util(
         UtilCommand::Completion(args) => cmd_util_completion(ui, command, args),
         UtilCommand::ConfigSchema(args) => cmd_util_config_schema(ui, command, args),
         UtilCommand::Exec(args) => cmd_util_exec(ui, command, args),
+        UtilCommand::ExecWithPatches(args) => cmd_util_exec_with_patches(ui, command, args),
         UtilCommand::Gc(args) => cmd_util_gc(ui, command, args),
         UtilCommand::Mangen(args) => cmd_util_mangen(ui, command, args),
         UtilCommand::MarkdownHelp(args) => cmd_util_markdown_help(ui, command, args),
+        UtilCommand::Prompt(args) => cmd_util_prompt(ui, command, args),
+        UtilCommand::Resolutions(args) => cmd_util_resolutions(ui, command, args),
     }
 }
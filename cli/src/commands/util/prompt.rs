@@ -0,0 +1,105 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use crate::cli_util::short_operation_hash;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Print a short summary of the working copy for use in a shell prompt
+///
+/// Prints a single line with the change id of the working-copy commit, the
+/// local bookmarks pointing to it, `??` if the change is divergent,
+/// `(conflict)` if it has conflicts, and the id of the current operation.
+/// For example:
+///
+/// ```text
+/// kkmpptxz main (conflict) 7f3ce8a4b2d1
+/// ```
+///
+/// To stay fast enough to run on every prompt, this command never snapshots
+/// the working copy, so the summary may not include changes made since the
+/// last `jj` command. It only reads the operation head and the working-copy
+/// commit.
+#[derive(clap::Args, Clone, Debug)]
+pub struct UtilPromptArgs {}
+
+#[instrument(skip_all)]
+pub fn cmd_util_prompt(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &UtilPromptArgs,
+) -> Result<(), CommandError> {
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let op_heads = repo_loader.op_heads_store().get_op_heads()?;
+    let op = match op_heads.as_slice() {
+        [op_id] if command.global_args().at_operation.is_none() => {
+            repo_loader.load_operation(op_id)?
+        }
+        // Concurrent operations have to be merged first, which is slow but rare.
+        _ => command.resolve_operation(ui, repo_loader)?,
+    };
+    let repo = repo_loader.load_at(&op)?;
+    let Some(wc_commit_id) = repo.view().get_wc_commit_id(workspace.workspace_id()) else {
+        return Err(user_error("This workspace has no working-copy commit"));
+    };
+    let wc_commit = repo.store().get_commit(wc_commit_id)?;
+    let change_id = wc_commit.change_id();
+    let is_divergent = repo
+        .resolve_change_id(change_id)
+        .is_some_and(|commit_ids| commit_ids.len() > 1);
+
+    let mut formatter = ui.stdout_formatter();
+    let hex = change_id.reverse_hex();
+    let prefix_len = repo.shortest_unique_change_id_prefix_len(change_id);
+    let (prefix, rest) = hex.split_at(prefix_len);
+    formatter.with_label("change_id", |formatter| {
+        write!(formatter.labeled("prefix"), "{prefix}")?;
+        write!(
+            formatter.labeled("rest"),
+            "{}",
+            &rest[..8_usize.saturating_sub(prefix_len)]
+        )?;
+        if is_divergent {
+            write!(formatter.labeled("divergent"), "??")?;
+        }
+        Ok::<_, CommandError>(())
+    })?;
+    for (name, target) in repo.view().local_bookmarks_for_commit(wc_commit.id()) {
+        write!(formatter, " ")?;
+        write!(formatter.labeled("bookmark"), "{name}")?;
+        if target.has_conflict() {
+            write!(formatter.labeled("bookmark"), "??")?;
+        }
+    }
+    if wc_commit.has_conflict()? {
+        write!(formatter, " ")?;
+        write!(formatter.labeled("conflict"), "(conflict)")?;
+    }
+    write!(formatter, " ")?;
+    write!(
+        formatter.labeled("operation_id"),
+        "{}",
+        short_operation_hash(op.id())
+    )?;
+    writeln!(formatter)?;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::conflict_resolutions::ConflictResolutions;
+use crate::ui::Ui;
+
+/// List or forget recorded conflict resolutions
+///
+/// Resolutions are recorded by `jj resolve`, and by any command that resolves
+/// a conflict if `rerere.enabled` is set. They are reused by `jj rebase
+/// --reuse-resolutions`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct UtilResolutionsArgs {
+    /// Forget the matching resolutions instead of listing them
+    #[arg(long)]
+    forget: bool,
+    /// Only include resolutions of conflicts in these paths
+    #[arg(value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
+}
+
+pub fn cmd_util_resolutions(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &UtilResolutionsArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
+    let resolutions = ConflictResolutions::new(workspace_command.repo_path());
+    let matching = resolutions
+        .list()
+        .into_iter()
+        .filter(|resolution| matcher.matches(&resolution.path));
+    if args.forget {
+        let mut num_forgotten = 0;
+        for resolution in matching {
+            resolutions.forget(&resolution.key)?;
+            num_forgotten += 1;
+        }
+        writeln!(ui.status(), "Forgot {num_forgotten} recorded resolutions")?;
+    } else {
+        let mut formatter = ui.stdout_formatter();
+        for resolution in matching {
+            writeln!(
+                formatter,
+                "{}  {}",
+                &resolution.key[..12],
+                workspace_command.format_file_path(&resolution.path)
+            )?;
+        }
+    }
+    Ok(())
+}
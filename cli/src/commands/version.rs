@@ -14,22 +14,113 @@
 
 use std::io::Write;
 
+use itertools::Itertools as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
 /// Display version information
 #[derive(clap::Args, Clone, Debug)]
-pub(crate) struct VersionArgs {}
+pub(crate) struct VersionArgs {
+    /// Also print the enabled features, library versions, config files, and
+    /// platform
+    ///
+    /// This is useful to include in bug reports.
+    #[arg(long, short)]
+    verbose: bool,
+    /// How to print the version information
+    ///
+    /// The `json` format always includes the verbose information.
+    #[arg(long, value_enum, default_value_t = VersionFormat::Text)]
+    format: VersionFormat,
+}
+
+/// Output format of `jj version`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum VersionFormat {
+    /// Human-readable text
+    Text,
+    /// A JSON object
+    Json,
+}
 
 #[instrument(skip_all)]
 pub(crate) fn cmd_version(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &VersionArgs,
+    args: &VersionArgs,
 ) -> Result<(), CommandError> {
-    write!(ui.stdout(), "{}", command.app().render_version())?;
+    if args.format == VersionFormat::Text && !args.verbose {
+        write!(ui.stdout(), "{}", command.app().render_version())?;
+        return Ok(());
+    }
+
+    let version = command.app().get_version().unwrap_or_default();
+    let features = enabled_features();
+    let libgit2_version = {
+        let (major, minor, patch) = git2::Version::get().libgit2_version();
+        format!("{major}.{minor}.{patch}")
+    };
+    let gitoxide_version = option_env!("JJ_GIX_VERSION").unwrap_or("unknown");
+    // Each file may be loaded as more than one layer.
+    let config_files = command
+        .settings()
+        .config()
+        .layers()
+        .iter()
+        .filter_map(|layer| Some((layer.source, layer.path.as_ref()?)))
+        .dedup()
+        .collect_vec();
+
+    match args.format {
+        VersionFormat::Text => {
+            let mut stdout = ui.stdout();
+            writeln!(stdout, "jj {version}")?;
+            writeln!(stdout, "features: {}", features.join(", "))?;
+            writeln!(stdout, "libgit2: {libgit2_version}")?;
+            writeln!(stdout, "gitoxide: {gitoxide_version}")?;
+            writeln!(
+                stdout,
+                "platform: {}-{}",
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            )?;
+            writeln!(stdout, "config files:")?;
+            for (source, path) in &config_files {
+                writeln!(stdout, "  {source}: {}", path.display())?;
+            }
+        }
+        VersionFormat::Json => {
+            let report = serde_json::json!({
+                "version": version,
+                "features": features,
+                "libgit2": libgit2_version,
+                "gitoxide": gitoxide_version,
+                "os": std::env::consts::OS,
+                "arch": std::env::consts::ARCH,
+                "config_files": config_files
+                    .iter()
+                    .map(|(source, path)| serde_json::json!({
+                        "source": source.to_string(),
+                        "path": path,
+                    }))
+                    .collect_vec(),
+            });
+            let mut stdout = ui.stdout();
+            serde_json::to_writer_pretty(&mut stdout, &report).map_err(internal_error)?;
+            writeln!(stdout)?;
+        }
+    }
     Ok(())
 }
+
+/// Cargo features of jj-cli the binary was built with.
+fn enabled_features() -> Vec<&'static str> {
+    env!("JJ_FEATURES")
+        .split(',')
+        .filter(|name| !name.is_empty())
+        .collect()
+}
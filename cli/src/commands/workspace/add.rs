@@ -28,8 +28,10 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
+use crate::workspace_roots::WorkspaceRoots;
 
 /// How to handle sparse patterns when creating a new workspace.
 #[derive(clap::ValueEnum, Clone, Debug, Eq, PartialEq)]
@@ -46,10 +48,21 @@ enum SparseInheritance {
 ///
 /// By default, the new workspace inherits the sparse patterns of the current
 /// workspace. You can override this with the `--sparse-patterns` option.
+///
+/// In a repo without any working copy, e.g. one created by `jj git clone
+/// --bare`, use `--first` to check out a working copy in the repo's own
+/// directory.
 #[derive(clap::Args, Clone, Debug)]
 pub struct WorkspaceAddArgs {
     /// Where to create the new workspace
-    destination: String,
+    #[arg(required_unless_present = "first")]
+    destination: Option<String>,
+    /// Check out the first working copy of the repo in the current workspace
+    ///
+    /// Unless any revisions are specified, the working-copy commit is created
+    /// on top of `trunk()`.
+    #[arg(long, conflicts_with_all = ["destination", "name", "sparse_patterns"])]
+    first: bool,
     /// A name for the workspace
     ///
     /// To override the default, which is the basename of the destination
@@ -81,8 +94,11 @@ pub fn cmd_workspace_add(
     command: &CommandHelper,
     args: &WorkspaceAddArgs,
 ) -> Result<(), CommandError> {
+    let Some(destination) = &args.destination else {
+        return add_first_workspace(ui, command, args);
+    };
     let old_workspace_command = command.workspace_helper(ui)?;
-    let destination_path = command.cwd().join(&args.destination);
+    let destination_path = command.cwd().join(destination);
     if destination_path.exists() {
         return Err(user_error("Workspace already exists"));
     } else {
@@ -116,6 +132,8 @@ pub fn cmd_workspace_add(
         working_copy_factory,
         workspace_id,
     )?;
+    WorkspaceRoots::new(repo_path)
+        .record(new_workspace.workspace_id(), new_workspace.workspace_root());
     writeln!(
         ui.status(),
         "Created workspace in \"{}\"",
@@ -123,11 +141,10 @@ pub fn cmd_workspace_add(
     )?;
     // Show a warning if the user passed a path without a separator, since they
     // may have intended the argument to only be the name for the workspace.
-    if !args.destination.contains(std::path::is_separator) {
+    if !destination.contains(std::path::is_separator) {
         writeln!(
             ui.warning_default(),
-            r#"Workspace created inside current directory. If this was unintentional, delete the "{}" directory and run `jj workspace forget {name}` to remove it."#,
-            args.destination
+            r#"Workspace created inside current directory. If this was unintentional, delete the "{destination}" directory and run `jj workspace forget {name}` to remove it."#,
         )?;
     }
 
@@ -197,3 +214,46 @@ pub fn cmd_workspace_add(
     )?;
     Ok(())
 }
+
+/// Checks out a working copy in the current workspace of a repo that has none.
+fn add_first_workspace(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &WorkspaceAddArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if !workspace_command.repo().view().wc_commit_ids().is_empty() {
+        return Err(user_error_with_hint(
+            "The repo already has a working copy",
+            "Use `jj workspace add <destination>` to add another workspace",
+        ));
+    }
+    let revisions = if args.revision.is_empty() {
+        vec![RevisionArg::from("trunk()".to_owned())]
+    } else {
+        args.revision.clone()
+    };
+    let parents = workspace_command
+        .resolve_some_revsets_default_single(ui, &revisions)?
+        .into_iter()
+        .collect_vec();
+
+    let mut tx = workspace_command.start_transaction();
+    let tree = merge_commit_trees(tx.repo(), &parents)?;
+    let parent_ids = parents.iter().ids().cloned().collect_vec();
+    let new_wc_commit = tx
+        .repo_mut()
+        .new_commit(command.settings(), parent_ids, tree.id())
+        .write()?;
+    tx.edit(&new_wc_commit)?;
+    let name = tx
+        .base_workspace_helper()
+        .workspace_id()
+        .as_str()
+        .to_owned();
+    tx.finish(
+        ui,
+        format!("create initial working-copy commit in workspace {name}"),
+    )?;
+    Ok(())
+}
@@ -15,8 +15,10 @@
 mod add;
 mod forget;
 mod list;
+mod move_root;
 mod rename;
 mod root;
+mod run;
 mod update_stale;
 
 use clap::Subcommand;
@@ -28,10 +30,14 @@ use self::forget::cmd_workspace_forget;
 use self::forget::WorkspaceForgetArgs;
 use self::list::cmd_workspace_list;
 use self::list::WorkspaceListArgs;
+use self::move_root::cmd_workspace_move_root;
+use self::move_root::WorkspaceMoveRootArgs;
 use self::rename::cmd_workspace_rename;
 use self::rename::WorkspaceRenameArgs;
 use self::root::cmd_workspace_root;
 use self::root::WorkspaceRootArgs;
+use self::run::cmd_workspace_run;
+use self::run::WorkspaceRunArgs;
 use self::update_stale::cmd_workspace_update_stale;
 use self::update_stale::WorkspaceUpdateStaleArgs;
 use crate::cli_util::CommandHelper;
@@ -54,8 +60,10 @@ pub(crate) enum WorkspaceCommand {
     Add(WorkspaceAddArgs),
     Forget(WorkspaceForgetArgs),
     List(WorkspaceListArgs),
+    MoveRoot(WorkspaceMoveRootArgs),
     Rename(WorkspaceRenameArgs),
     Root(WorkspaceRootArgs),
+    Run(WorkspaceRunArgs),
     UpdateStale(WorkspaceUpdateStaleArgs),
 }
 
@@ -69,8 +77,10 @@ pub(crate) fn cmd_workspace(
         WorkspaceCommand::Add(args) => cmd_workspace_add(ui, command, args),
         WorkspaceCommand::Forget(args) => cmd_workspace_forget(ui, command, args),
         WorkspaceCommand::List(args) => cmd_workspace_list(ui, command, args),
+        WorkspaceCommand::MoveRoot(args) => cmd_workspace_move_root(ui, command, args),
         WorkspaceCommand::Rename(args) => cmd_workspace_rename(ui, command, args),
         WorkspaceCommand::Root(args) => cmd_workspace_root(ui, command, args),
+        WorkspaceCommand::Run(args) => cmd_workspace_run(ui, command, args),
         WorkspaceCommand::UpdateStale(args) => cmd_workspace_update_stale(ui, command, args),
     }
 }
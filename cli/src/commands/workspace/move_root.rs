@@ -0,0 +1,99 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use jj_lib::file_util;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::workspace::move_workspace_root;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+use crate::workspace_roots::WorkspaceRoots;
+
+/// Move the current workspace to another directory
+///
+/// The working-copy files and the `.jj/` directory are moved together, so the
+/// workspace keeps its working-copy commit and state. The destination must not
+/// exist, except when only the case of the name changes. If the destination is
+/// on another file system, the workspace is copied there first and the
+/// original directory is removed once the copy is complete.
+///
+/// If the repo is stored in this workspace, other workspaces of the repo still
+/// refer to its old location and need to be updated manually.
+#[derive(clap::Args, Clone, Debug)]
+pub struct WorkspaceMoveRootArgs {
+    /// The new root directory of the workspace
+    #[arg(value_hint = clap::ValueHint::DirPath)]
+    destination: String,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_workspace_move_root(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &WorkspaceMoveRootArgs,
+) -> Result<(), CommandError> {
+    // Snapshot so no changes in the working copy are lost to a stale state.
+    let workspace_command = command.workspace_helper(ui)?;
+    let workspace_id = workspace_command.workspace_id().clone();
+    let old_root = workspace_command
+        .workspace_root()
+        .canonicalize()
+        .context(workspace_command.workspace_root())?;
+    let destination = file_util::normalize_path(&command.cwd().join(&args.destination));
+    let new_root = move_workspace_root(&old_root, &destination)?;
+    if new_root == old_root {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+    writeln!(
+        ui.status(),
+        "Moved workspace '{}' to {}",
+        workspace_id.as_str(),
+        new_root.display()
+    )?;
+    let repo_dir = new_root.join(".jj").join("repo");
+    let repo_moved = repo_dir.is_dir();
+    let repo_path = if repo_moved {
+        repo_dir
+    } else {
+        workspace_command.repo_path().to_owned()
+    };
+    WorkspaceRoots::new(&repo_path).record(&workspace_id, &new_root);
+
+    let other_workspaces = workspace_command
+        .repo()
+        .view()
+        .wc_commit_ids()
+        .keys()
+        .filter(|id| **id != workspace_id)
+        .map(|id| id.as_str())
+        .collect_vec();
+    if repo_moved && !other_workspaces.is_empty() {
+        writeln!(
+            ui.warning_default(),
+            "The repo was moved along with this workspace. These workspaces still refer to its \
+             old location: {}",
+            other_workspaces.join(", ")
+        )?;
+        writeln!(
+            ui.hint_default(),
+            "Update the path in their `.jj/repo` file to {}",
+            repo_path.display()
+        )?;
+    }
+    Ok(())
+}
@@ -19,6 +19,7 @@ use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
+use crate::workspace_roots::WorkspaceRoots;
 
 /// Renames the current workspace
 #[derive(clap::Args, Clone, Debug)]
@@ -66,13 +67,14 @@ pub fn cmd_workspace_rename(
         .rename_workspace(new_workspace_id.clone());
 
     tx.repo_mut()
-        .rename_workspace(&old_workspace_id, new_workspace_id)?;
+        .rename_workspace(&old_workspace_id, new_workspace_id.clone())?;
     let repo = tx.commit(format!(
         "Renamed workspace '{}' to '{}'",
         old_workspace_id.as_str(),
         args.new_workspace_name
     ))?;
     locked_ws.finish(repo.op_id().clone())?;
+    WorkspaceRoots::new(workspace_command.repo_path()).rename(&old_workspace_id, &new_workspace_id);
 
     Ok(())
 }
@@ -0,0 +1,219 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::process::Stdio;
+
+use clap::ArgGroup;
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::file_util;
+use jj_lib::op_store::WorkspaceId;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::formatter::Formatter;
+use crate::ui::Ui;
+use crate::workspace_roots::WorkspaceRoots;
+
+/// Run a command in multiple workspaces
+///
+/// The command is run with the root directory of each workspace as its working
+/// directory, one workspace after another. With `--parallel`, the command is
+/// run in all workspaces at the same time, and the output of each command is
+/// printed once it has finished. The exit status of the command in each
+/// workspace is reported at the end.
+///
+/// The location of a workspace is recorded when it's created and whenever a
+/// `jj` command is run in it. Workspaces with an unknown location are skipped.
+///
+/// Use `--` to separate the command from the options of this command:
+///
+/// ```shell
+/// jj workspace run --all -- cargo test --quiet
+/// ```
+#[derive(clap::Args, Clone, Debug)]
+#[command(group(ArgGroup::new("target").args(&["all", "workspaces"]).required(true)))]
+pub struct WorkspaceRunArgs {
+    /// Run the command in all workspaces of the repo
+    #[arg(long)]
+    all: bool,
+    /// Run the command in the given workspaces
+    #[arg(
+        long = "workspace",
+        short,
+        value_name = "NAME",
+        add = ArgValueCandidates::new(complete::workspaces),
+    )]
+    workspaces: Vec<String>,
+    /// Run the command in all workspaces at the same time
+    #[arg(long)]
+    parallel: bool,
+    /// External command to execute
+    command: String,
+    /// Arguments to pass to the external command
+    args: Vec<String>,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_workspace_run(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &WorkspaceRunArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let wc_commit_ids = workspace_command.repo().view().wc_commit_ids();
+    let workspace_ids = if args.all {
+        wc_commit_ids.keys().cloned().sorted().collect_vec()
+    } else {
+        let workspace_ids = args
+            .workspaces
+            .iter()
+            .map(|name| WorkspaceId::new(name.clone()))
+            .unique()
+            .collect_vec();
+        for workspace_id in &workspace_ids {
+            if !wc_commit_ids.contains_key(workspace_id) {
+                return Err(user_error(format!(
+                    "No such workspace: {}",
+                    workspace_id.as_str()
+                )));
+            }
+        }
+        workspace_ids
+    };
+
+    let workspace_roots = WorkspaceRoots::new(workspace_command.repo_path());
+    let mut targets: Vec<(WorkspaceId, PathBuf)> = vec![];
+    for workspace_id in workspace_ids {
+        match workspace_roots.get(&workspace_id) {
+            Some(root) if root.is_dir() => targets.push((workspace_id, root)),
+            Some(root) => writeln!(
+                ui.warning_default(),
+                "Skipping workspace '{}' since {} doesn't exist",
+                workspace_id.as_str(),
+                root.display()
+            )?,
+            None => {
+                writeln!(
+                    ui.warning_default(),
+                    "Skipping workspace '{}' since its location is unknown",
+                    workspace_id.as_str()
+                )?;
+                writeln!(
+                    ui.hint_default(),
+                    "Run any jj command in the workspace to record its location."
+                )?;
+            }
+        }
+    }
+
+    let mut results = Vec::with_capacity(targets.len());
+    if args.parallel {
+        let outputs = std::thread::scope(|scope| {
+            let handles = targets
+                .iter()
+                .map(|(_, root)| {
+                    scope.spawn(move || {
+                        std::process::Command::new(&args.command)
+                            .args(&args.args)
+                            .current_dir(root)
+                            .stdin(Stdio::null())
+                            .output()
+                    })
+                })
+                .collect_vec();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect_vec()
+        });
+        for ((workspace_id, root), output) in targets.iter().zip(outputs) {
+            write_header(ui, command, workspace_id, root)?;
+            let status = output.map(|output| {
+                _ = ui.stdout().write_all(&output.stdout);
+                _ = ui.stderr().write_all(&output.stderr);
+                output.status
+            });
+            results.push(status);
+        }
+    } else {
+        for (workspace_id, root) in &targets {
+            write_header(ui, command, workspace_id, root)?;
+            let status = std::process::Command::new(&args.command)
+                .args(&args.args)
+                .current_dir(root)
+                .status();
+            results.push(status);
+        }
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(formatter, "Results:")?;
+        for ((workspace_id, _), result) in targets.iter().zip(&results) {
+            write!(formatter, "  {}: ", workspace_id.as_str())?;
+            write_result(formatter.as_mut(), &args.command, result)?;
+        }
+    }
+    let num_failed = results
+        .iter()
+        .filter(|result| !result.as_ref().is_ok_and(|status| status.success()))
+        .count();
+    if num_failed > 0 {
+        return Err(user_error(format!(
+            "The command failed in {num_failed} of {} workspaces",
+            results.len()
+        )));
+    }
+    Ok(())
+}
+
+fn write_header(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_id: &WorkspaceId,
+    root: &Path,
+) -> io::Result<()> {
+    writeln!(
+        ui.status(),
+        "Running in workspace '{}' at {}:",
+        workspace_id.as_str(),
+        file_util::relative_path(command.cwd(), root).display()
+    )
+}
+
+fn write_result(
+    formatter: &mut dyn Formatter,
+    command: &str,
+    result: &io::Result<ExitStatus>,
+) -> io::Result<()> {
+    match result {
+        Ok(status) => {
+            if let Some(exit_code) = status.code() {
+                writeln!(formatter, "exited with {exit_code}")
+            } else {
+                // signal
+                writeln!(formatter, "was terminated by: {status}")
+            }
+        }
+        Err(err) => writeln!(formatter, "failed to execute '{command}': {err}"),
+    }
+}
@@ -12,26 +12,65 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use clap_complete::ArgValueCandidates;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
+use crate::complete;
 use crate::ui::Ui;
 
 /// Update a workspace that has become stale
 ///
 /// For information about stale working copies, see
 /// https://martinvonz.github.io/jj/latest/working-copy/.
+///
+/// By default, changes in the working copy are snapshotted before it's updated
+/// to the working-copy commit recorded in the repo.
 #[derive(clap::Args, Clone, Debug)]
-pub struct WorkspaceUpdateStaleArgs {}
+pub struct WorkspaceUpdateStaleArgs {
+    /// Check out a new commit on top of the given revision instead of the
+    /// recorded working-copy commit
+    #[arg(long, value_name = "REVSET", add = ArgValueCandidates::new(complete::all_revisions))]
+    to: Option<RevisionArg>,
+    /// Discard the changes in the working copy instead of snapshotting them
+    ///
+    /// The files in the working copy are overwritten by the updated
+    /// working-copy commit. Changes to tracked files that haven't been
+    /// snapshotted are lost and can't be restored with `jj undo`. Untracked
+    /// files are left in place.
+    #[arg(long)]
+    discard_changes: bool,
+    /// Don't ask for confirmation before discarding changes
+    #[arg(long, requires = "discard_changes")]
+    yes: bool,
+}
 
 #[instrument(skip_all)]
 pub fn cmd_workspace_update_stale(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &WorkspaceUpdateStaleArgs,
+    args: &WorkspaceUpdateStaleArgs,
 ) -> Result<(), CommandError> {
-    command.recover_stale_working_copy(ui)?;
+    if args.discard_changes && !args.yes {
+        if !Ui::can_prompt() {
+            return Err(user_error_with_hint(
+                "Refusing to discard changes without confirmation",
+                "Use --yes to discard them without confirmation.",
+            ));
+        }
+        writeln!(
+            ui.warning_default(),
+            "Changes in the working copy that haven't been snapshotted will be lost."
+        )?;
+        if !ui.prompt_yes_no("Discard them?", Some(false))? {
+            return Err(user_error("Aborted"));
+        }
+    }
+    command.recover_stale_working_copy_to(ui, args.to.as_ref(), args.discard_changes)?;
 
     Ok(())
 }
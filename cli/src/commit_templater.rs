@@ -20,26 +20,35 @@ use std::io;
 use std::rc::Rc;
 
 use futures::stream::BoxStream;
+use futures::StreamExt as _;
 use itertools::Itertools as _;
 use jj_lib::backend::BackendResult;
 use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
+use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::copies::CopiesTreeDiffEntry;
+use jj_lib::copies::CopiesTreeDiffEntryPath;
+use jj_lib::copies::CopyOperation;
 use jj_lib::copies::CopyRecords;
+use jj_lib::default_index::DefaultIndexStore;
+use jj_lib::default_index::DiffStatCounts;
 use jj_lib::extensions_map::ExtensionsMap;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::id_prefix::IdPrefixIndex;
+use jj_lib::matchers::EverythingMatcher;
 use jj_lib::matchers::Matcher;
+use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::op_store::WorkspaceId;
+use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::revset;
@@ -51,9 +60,11 @@ use jj_lib::revset::RevsetParseContext;
 use jj_lib::revset::UserRevsetExpression;
 use jj_lib::store::Store;
 use once_cell::unsync::OnceCell;
+use pollster::FutureExt as _;
 
 use crate::diff_util;
 use crate::formatter::Formatter;
+use crate::revset_cache::RevsetCache;
 use crate::revset_util;
 use crate::template_builder;
 use crate::template_builder::merge_fn_map;
@@ -97,6 +108,7 @@ pub struct CommitTemplateLanguage<'repo> {
     revset_parse_context: RevsetParseContext<'repo>,
     id_prefix_context: &'repo IdPrefixContext,
     immutable_expression: Rc<UserRevsetExpression>,
+    revset_cache: Option<(&'repo ReadonlyRepo, RevsetCache)>,
     conflict_marker_style: ConflictMarkerStyle,
     build_fn_table: CommitTemplateBuildFnTable<'repo>,
     keyword_cache: CommitKeywordCache<'repo>,
@@ -134,12 +146,19 @@ impl<'repo> CommitTemplateLanguage<'repo> {
             revset_parse_context,
             id_prefix_context,
             immutable_expression,
+            revset_cache: None,
             conflict_marker_style,
             build_fn_table,
             keyword_cache: CommitKeywordCache::default(),
             cache_extensions,
         }
     }
+
+    /// Caches the evaluated immutable set on disk. The `repo` must be the one
+    /// this language environment was created for.
+    pub fn set_revset_cache(&mut self, repo: &'repo ReadonlyRepo, cache: RevsetCache) {
+        self.revset_cache = Some((repo, cache));
+    }
 }
 
 impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
@@ -197,6 +216,7 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                     property,
                     function,
                     Self::wrap_commit,
+                    Self::wrap_commit_list,
                 )
             }
             CommitTemplatePropertyKind::RefName(property) => {
@@ -226,6 +246,7 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                     property,
                     function,
                     Self::wrap_ref_name,
+                    Self::wrap_ref_name_list,
                 )
             }
             CommitTemplatePropertyKind::CommitOrChangeId(property) => {
@@ -243,6 +264,27 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
+            CommitTemplatePropertyKind::TreeDiffEntry(property) => {
+                let table = &self.build_fn_table.tree_diff_entry_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(self, diagnostics, build_ctx, property, function)
+            }
+            CommitTemplatePropertyKind::TreeDiffEntryList(property) => {
+                template_builder::build_unformattable_list_method(
+                    self,
+                    diagnostics,
+                    build_ctx,
+                    property,
+                    function,
+                    Self::wrap_tree_diff_entry,
+                    Self::wrap_tree_diff_entry_list,
+                )
+            }
+            CommitTemplatePropertyKind::DiffStats(property) => {
+                let table = &self.build_fn_table.diff_stats_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(self, diagnostics, build_ctx, property, function)
+            }
         }
     }
 }
@@ -319,6 +361,24 @@ impl<'repo> CommitTemplateLanguage<'repo> {
     ) -> CommitTemplatePropertyKind<'repo> {
         CommitTemplatePropertyKind::TreeDiff(Box::new(property))
     }
+
+    pub fn wrap_tree_diff_entry(
+        property: impl TemplateProperty<Output = TreeDiffEntry> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo> {
+        CommitTemplatePropertyKind::TreeDiffEntry(Box::new(property))
+    }
+
+    pub fn wrap_tree_diff_entry_list(
+        property: impl TemplateProperty<Output = Vec<TreeDiffEntry>> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo> {
+        CommitTemplatePropertyKind::TreeDiffEntryList(Box::new(property))
+    }
+
+    pub fn wrap_diff_stats(
+        property: impl TemplateProperty<Output = DiffStatCounts> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo> {
+        CommitTemplatePropertyKind::DiffStats(Box::new(property))
+    }
 }
 
 pub enum CommitTemplatePropertyKind<'repo> {
@@ -332,6 +392,9 @@ pub enum CommitTemplatePropertyKind<'repo> {
     CommitOrChangeId(Box<dyn TemplateProperty<Output = CommitOrChangeId> + 'repo>),
     ShortestIdPrefix(Box<dyn TemplateProperty<Output = ShortestIdPrefix> + 'repo>),
     TreeDiff(Box<dyn TemplateProperty<Output = TreeDiff> + 'repo>),
+    TreeDiffEntry(Box<dyn TemplateProperty<Output = TreeDiffEntry> + 'repo>),
+    TreeDiffEntryList(Box<dyn TemplateProperty<Output = Vec<TreeDiffEntry>> + 'repo>),
+    DiffStats(Box<dyn TemplateProperty<Output = DiffStatCounts> + 'repo>),
 }
 
 impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
@@ -347,6 +410,9 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             CommitTemplatePropertyKind::CommitOrChangeId(_) => "CommitOrChangeId",
             CommitTemplatePropertyKind::ShortestIdPrefix(_) => "ShortestIdPrefix",
             CommitTemplatePropertyKind::TreeDiff(_) => "TreeDiff",
+            CommitTemplatePropertyKind::TreeDiffEntry(_) => "TreeDiffEntry",
+            CommitTemplatePropertyKind::TreeDiffEntryList(_) => "List<TreeDiffEntry>",
+            CommitTemplatePropertyKind::DiffStats(_) => "DiffStats",
         }
     }
 
@@ -372,6 +438,11 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             // TODO: boolean cast could be implemented, but explicit
             // diff.empty() method might be better.
             CommitTemplatePropertyKind::TreeDiff(_) => None,
+            CommitTemplatePropertyKind::TreeDiffEntry(_) => None,
+            CommitTemplatePropertyKind::TreeDiffEntryList(property) => {
+                Some(Box::new(property.map(|l| !l.is_empty())))
+            }
+            CommitTemplatePropertyKind::DiffStats(_) => None,
         }
     }
 
@@ -408,6 +479,9 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
                 Some(property.into_template())
             }
             CommitTemplatePropertyKind::TreeDiff(_) => None,
+            CommitTemplatePropertyKind::TreeDiffEntry(_) => None,
+            CommitTemplatePropertyKind::TreeDiffEntryList(_) => None,
+            CommitTemplatePropertyKind::DiffStats(property) => Some(property.into_template()),
         }
     }
 
@@ -426,6 +500,9 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             (CommitTemplatePropertyKind::CommitOrChangeId(_), _) => None,
             (CommitTemplatePropertyKind::ShortestIdPrefix(_), _) => None,
             (CommitTemplatePropertyKind::TreeDiff(_), _) => None,
+            (CommitTemplatePropertyKind::TreeDiffEntry(_), _) => None,
+            (CommitTemplatePropertyKind::TreeDiffEntryList(_), _) => None,
+            (CommitTemplatePropertyKind::DiffStats(_), _) => None,
         }
     }
 
@@ -447,6 +524,9 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             (CommitTemplatePropertyKind::CommitOrChangeId(_), _) => None,
             (CommitTemplatePropertyKind::ShortestIdPrefix(_), _) => None,
             (CommitTemplatePropertyKind::TreeDiff(_), _) => None,
+            (CommitTemplatePropertyKind::TreeDiffEntry(_), _) => None,
+            (CommitTemplatePropertyKind::TreeDiffEntryList(_), _) => None,
+            (CommitTemplatePropertyKind::DiffStats(_), _) => None,
         }
     }
 }
@@ -463,6 +543,8 @@ pub struct CommitTemplateBuildFnTable<'repo> {
     pub commit_or_change_id_methods: CommitTemplateBuildMethodFnMap<'repo, CommitOrChangeId>,
     pub shortest_id_prefix_methods: CommitTemplateBuildMethodFnMap<'repo, ShortestIdPrefix>,
     pub tree_diff_methods: CommitTemplateBuildMethodFnMap<'repo, TreeDiff>,
+    pub tree_diff_entry_methods: CommitTemplateBuildMethodFnMap<'repo, TreeDiffEntry>,
+    pub diff_stats_methods: CommitTemplateBuildMethodFnMap<'repo, DiffStatCounts>,
 }
 
 impl<'repo> CommitTemplateBuildFnTable<'repo> {
@@ -475,6 +557,8 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_or_change_id_methods: builtin_commit_or_change_id_methods(),
             shortest_id_prefix_methods: builtin_shortest_id_prefix_methods(),
             tree_diff_methods: builtin_tree_diff_methods(),
+            tree_diff_entry_methods: builtin_tree_diff_entry_methods(),
+            diff_stats_methods: builtin_diff_stats_methods(),
         }
     }
 
@@ -486,6 +570,8 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_or_change_id_methods: HashMap::new(),
             shortest_id_prefix_methods: HashMap::new(),
             tree_diff_methods: HashMap::new(),
+            tree_diff_entry_methods: HashMap::new(),
+            diff_stats_methods: HashMap::new(),
         }
     }
 
@@ -497,6 +583,8 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_or_change_id_methods,
             shortest_id_prefix_methods,
             tree_diff_methods,
+            tree_diff_entry_methods,
+            diff_stats_methods,
         } = extension;
 
         self.core.merge(core);
@@ -511,6 +599,8 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             shortest_id_prefix_methods,
         );
         merge_fn_map(&mut self.tree_diff_methods, tree_diff_methods);
+        merge_fn_map(&mut self.tree_diff_entry_methods, tree_diff_entry_methods);
+        merge_fn_map(&mut self.diff_stats_methods, diff_stats_methods);
     }
 }
 
@@ -549,12 +639,28 @@ impl<'repo> CommitKeywordCache<'repo> {
         // optimize "::<recent_heads>" query to use bitset-based implementation.
         self.is_immutable_fn.get_or_try_init(|| {
             let expression = &language.immutable_expression;
-            let revset = evaluate_revset_expression(language, span, expression)?;
-            Ok(revset.containing_fn().into())
+            let evaluate = || evaluate_revset_expression(language, span, expression);
+            let containing_fn = match &language.revset_cache {
+                Some((repo, cache)) => {
+                    cache.containing_fn(repo, &language.workspace_id, expression, evaluate)?
+                }
+                None => evaluate()?.containing_fn(),
+            };
+            Ok(containing_fn.into())
         })
     }
 }
 
+/// Names of the builtin keywords available in commit templates, sorted.
+pub fn builtin_commit_keywords() -> Vec<&'static str> {
+    builtin_commit_methods().into_keys().sorted().collect()
+}
+
+/// Names of the builtin keywords available in ref name templates, sorted.
+pub fn builtin_ref_name_keywords() -> Vec<&'static str> {
+    builtin_ref_name_methods().into_keys().sorted().collect()
+}
+
 fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Commit> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -627,7 +733,7 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             function.expect_no_arguments()?;
             let repo = language.repo;
             let out_property = self_property.map(|commit| extract_working_copies(repo, &commit));
-            Ok(L::wrap_string(out_property))
+            Ok(L::wrap_string_list(out_property))
         },
     );
     map.insert(
@@ -734,6 +840,15 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(L::wrap_ref_name_list(out_property))
         },
     );
+    map.insert(
+        "change_aliases",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let out_property = self_property.map(|commit| extract_change_aliases(repo, &commit));
+            Ok(L::wrap_string_list(out_property))
+        },
+    );
     map.insert(
         "git_refs",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -835,12 +950,49 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
                 FilesetExpression::all()
             };
             let repo = language.repo;
+            let all_files = files_node.is_none();
             let matcher: Rc<dyn Matcher> = files.to_matcher().into();
-            let out_property = self_property
-                .and_then(move |commit| Ok(TreeDiff::from_commit(repo, &commit, matcher.clone())?));
+            let out_property = self_property.and_then(move |commit| {
+                let mut diff = TreeDiff::from_commit(repo, &commit, matcher.clone())?;
+                if all_files {
+                    diff.stats_commit_id = Some(commit.id().clone());
+                }
+                Ok(diff)
+            });
             Ok(L::wrap_tree_diff(out_property))
         },
     );
+    map.insert(
+        "files",
+        |language, diagnostics, _build_ctx, self_property, function| {
+            let ([], [files_node]) = function.expect_arguments()?;
+            let files = if let Some(node) = files_node {
+                expect_fileset_literal(diagnostics, node, language.path_converter)?
+            } else {
+                FilesetExpression::all()
+            };
+            let repo = language.repo;
+            let matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            let out_property = self_property.and_then(move |commit| {
+                let diff = TreeDiff::from_commit(repo, &commit, matcher.clone())?;
+                Ok(diff.entries()?)
+            });
+            Ok(L::wrap_tree_diff_entry_list(out_property))
+        },
+    );
+    map.insert(
+        "diff_stat",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let path_converter = language.path_converter;
+            let conflict_marker_style = language.conflict_marker_style;
+            let out_property = self_property.and_then(move |commit| {
+                get_diff_stats(repo, &commit, path_converter, conflict_marker_style)
+            });
+            Ok(L::wrap_diff_stats(out_property))
+        },
+    );
     map.insert(
         "root",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -854,19 +1006,68 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
     map
 }
 
-// TODO: return Vec<String>
-fn extract_working_copies(repo: &dyn Repo, commit: &Commit) -> String {
+/// Computes the diff stats of the commit, or loads them from the index store.
+fn get_diff_stats(
+    repo: &dyn Repo,
+    commit: &Commit,
+    path_converter: &RepoPathUiConverter,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<DiffStatCounts, TemplatePropertyError> {
+    // Look up the cache before the copy records are loaded.
+    if let Some(counts) = read_cached_diff_stats(repo, commit.id(), conflict_marker_style) {
+        return Ok(counts);
+    }
+    let mut diff = TreeDiff::from_commit(repo, commit, Rc::new(EverythingMatcher))?;
+    diff.stats_commit_id = Some(commit.id().clone());
+    diff.stat_counts(repo, path_converter, conflict_marker_style)
+}
+
+fn read_cached_diff_stats(
+    repo: &dyn Repo,
+    commit_id: &CommitId,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Option<DiffStatCounts> {
+    let index_store = repo.base_repo().index_store();
+    let default_index_store: &DefaultIndexStore = index_store.as_any().downcast_ref()?;
+    default_index_store.read_diff_stats(commit_id, conflict_marker_style)
+}
+
+fn write_cached_diff_stats(
+    repo: &dyn Repo,
+    commit_id: &CommitId,
+    conflict_marker_style: ConflictMarkerStyle,
+    counts: &DiffStatCounts,
+) {
+    let index_store = repo.base_repo().index_store();
+    let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() else {
+        return;
+    };
+    // The cache is optional. The stats can be computed again next time.
+    if let Err(err) = default_index_store.write_diff_stats(commit_id, conflict_marker_style, counts)
+    {
+        tracing::warn!(?err, "failed to cache diff stats");
+    }
+}
+
+fn extract_working_copies(repo: &dyn Repo, commit: &Commit) -> Vec<String> {
     let wc_commit_ids = repo.view().wc_commit_ids();
     if wc_commit_ids.len() <= 1 {
-        return "".to_string();
+        return vec![];
     }
-    let mut names = vec![];
-    for (workspace_id, wc_commit_id) in wc_commit_ids.iter().sorted() {
-        if wc_commit_id == commit.id() {
-            names.push(format!("{}@", workspace_id.as_str()));
-        }
-    }
-    names.join(" ")
+    wc_commit_ids
+        .iter()
+        .sorted()
+        .filter(|(_, wc_commit_id)| *wc_commit_id == commit.id())
+        .map(|(workspace_id, _)| format!("{}@", workspace_id.as_str()))
+        .collect()
+}
+
+fn extract_change_aliases(repo: &dyn Repo, commit: &Commit) -> Vec<String> {
+    repo.view()
+        .change_aliases()
+        .filter(|(_, change_id)| *change_id == commit.change_id())
+        .map(|(name, _)| name.to_owned())
+        .collect()
 }
 
 fn expect_fileset_literal(
@@ -1478,6 +1679,9 @@ pub struct TreeDiff {
     to_tree: MergedTree,
     matcher: Rc<dyn Matcher>,
     copy_records: CopyRecords,
+    /// Commit whose full diff this is, if any. The diff stats of such commits
+    /// are cached in the index store.
+    stats_commit_id: Option<CommitId>,
 }
 
 impl TreeDiff {
@@ -1497,6 +1701,7 @@ impl TreeDiff {
             to_tree: commit.tree()?,
             matcher,
             copy_records,
+            stats_commit_id: None,
         })
     }
 
@@ -1505,6 +1710,55 @@ impl TreeDiff {
             .diff_stream_with_copies(&self.to_tree, &*self.matcher, &self.copy_records)
     }
 
+    fn entries(&self) -> BackendResult<Vec<TreeDiffEntry>> {
+        self.diff_stream()
+            .map(|CopiesTreeDiffEntry { path, values }| {
+                let (source_value, target_value) = values?;
+                Ok(TreeDiffEntry {
+                    path,
+                    source_value,
+                    target_value,
+                })
+            })
+            .collect::<Vec<_>>()
+            .block_on()
+            .into_iter()
+            .collect()
+    }
+
+    /// Counts the changed files and lines, or loads the counts from the index
+    /// store if they're cached.
+    fn stat_counts(
+        &self,
+        repo: &dyn Repo,
+        path_converter: &RepoPathUiConverter,
+        conflict_marker_style: ConflictMarkerStyle,
+    ) -> Result<DiffStatCounts, TemplatePropertyError> {
+        if let Some(counts) = self
+            .stats_commit_id
+            .as_ref()
+            .and_then(|id| read_cached_diff_stats(repo, id, conflict_marker_style))
+        {
+            return Ok(counts);
+        }
+        let options = diff_util::DiffStatOptions {
+            line_diff: diff_util::LineDiffOptions {
+                compare_mode: diff_util::LineCompareMode::Exact,
+            },
+        };
+        let counts = diff_util::get_diff_stat_counts(
+            repo.store(),
+            self.diff_stream(),
+            path_converter,
+            &options,
+            conflict_marker_style,
+        )?;
+        if let Some(id) = &self.stats_commit_id {
+            write_cached_diff_stats(repo, id, conflict_marker_style, &counts);
+        }
+        Ok(counts)
+    }
+
     fn into_formatted<F, E>(self, show: F) -> TreeDiffFormatted<F>
     where
         F: Fn(&mut dyn Formatter, &Store, BoxStream<CopiesTreeDiffEntry>) -> Result<(), E>,
@@ -1563,6 +1817,7 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                             compare_mode: diff_util::LineCompareMode::Exact,
                         },
                         max_inline_alternation: Some(3),
+                        syntax_highlight: false,
                     };
                     diff.into_formatted(move |formatter, store, tree_diff| {
                         diff_util::show_color_words_diff(
@@ -1601,6 +1856,7 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                         line_diff: diff_util::LineDiffOptions {
                             compare_mode: diff_util::LineCompareMode::Exact,
                         },
+                        syntax_highlight: false,
                     };
                     diff.into_formatted(move |formatter, store, tree_diff| {
                         diff_util::show_git_diff(
@@ -1619,7 +1875,16 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
     map.insert(
         "stat",
         |language, diagnostics, build_ctx, self_property, function| {
-            let [width_node] = function.expect_exact_arguments()?;
+            let ([], [width_node]) = function.expect_arguments()?;
+            let Some(width_node) = width_node else {
+                let repo = language.repo;
+                let path_converter = language.path_converter;
+                let conflict_marker_style = language.conflict_marker_style;
+                let out_property = self_property.and_then(move |diff| {
+                    diff.stat_counts(repo, path_converter, conflict_marker_style)
+                });
+                return Ok(L::wrap_diff_stats(out_property));
+            };
             let width_property = template_builder::expect_usize_expression(
                 language,
                 diagnostics,
@@ -1651,6 +1916,14 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
             Ok(L::wrap_template(template))
         },
     );
+    map.insert(
+        "files",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|diff| Ok(diff.entries()?));
+            Ok(L::wrap_tree_diff_entry_list(out_property))
+        },
+    );
     map.insert(
         "summary",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -1668,6 +1941,154 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
     );
     // TODO: add types() and name_only()? or let users write their own template?
     // TODO: add support for external tools
-    // TODO: add files() or map() to support custom summary-like formatting?
+    map
+}
+
+/// Changed path in a `TreeDiff`.
+#[derive(Clone, Debug)]
+pub struct TreeDiffEntry {
+    path: CopiesTreeDiffEntryPath,
+    source_value: MergedTreeValue,
+    target_value: MergedTreeValue,
+}
+
+impl TreeDiffEntry {
+    fn status_label(&self) -> &'static str {
+        match self.path.copy_operation() {
+            Some(CopyOperation::Copy) => "copied",
+            Some(CopyOperation::Rename) => "renamed",
+            None => match (
+                self.source_value.is_present(),
+                self.target_value.is_present(),
+            ) {
+                (true, true) => "modified",
+                (false, true) => "added",
+                (true, false) => "removed",
+                (false, false) => unreachable!(),
+            },
+        }
+    }
+}
+
+/// Describes the type of the tree value, or returns an empty string if the
+/// path is absent.
+fn tree_value_mode(value: &MergedTreeValue) -> &'static str {
+    match value.as_resolved() {
+        Some(None) => "",
+        Some(Some(TreeValue::File {
+            executable: false, ..
+        })) => "file",
+        Some(Some(TreeValue::File {
+            executable: true, ..
+        })) => "executable",
+        Some(Some(TreeValue::Symlink(_))) => "symlink",
+        Some(Some(TreeValue::Tree(_))) => "tree",
+        Some(Some(TreeValue::GitSubmodule(_))) => "git-submodule",
+        Some(Some(TreeValue::Conflict(_))) | None => "conflict",
+    }
+}
+
+fn builtin_tree_diff_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, TreeDiffEntry>
+{
+    type L<'repo> = CommitTemplateLanguage<'repo>;
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = CommitTemplateBuildMethodFnMap::<TreeDiffEntry>::new();
+    map.insert(
+        "path",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let path_converter = language.path_converter;
+            let out_property =
+                self_property.map(|entry| path_converter.format_file_path(entry.path.target()));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "source_path",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let path_converter = language.path_converter;
+            let out_property =
+                self_property.map(|entry| path_converter.format_file_path(entry.path.source()));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "status",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|entry| entry.status_label().to_owned());
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "old_mode",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.map(|entry| tree_value_mode(&entry.source_value).to_owned());
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "new_mode",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.map(|entry| tree_value_mode(&entry.target_value).to_owned());
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map
+}
+
+impl Template for DiffStatCounts {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        write!(formatter.labeled("added"), "+{}", self.added)?;
+        write!(formatter, " ")?;
+        write!(formatter.labeled("removed"), "-{}", self.removed)?;
+        write!(
+            formatter,
+            " in {} file{}",
+            self.files,
+            if self.files == 1 { "" } else { "s" }
+        )?;
+        Ok(())
+    }
+}
+
+fn builtin_diff_stats_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, DiffStatCounts> {
+    type L<'repo> = CommitTemplateLanguage<'repo>;
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = CommitTemplateBuildMethodFnMap::<DiffStatCounts>::new();
+    map.insert(
+        "files",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|counts| Ok(i64::try_from(counts.files)?));
+            Ok(L::wrap_integer(out_property))
+        },
+    );
+    map.insert(
+        "added",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|counts| Ok(i64::try_from(counts.added)?));
+            Ok(L::wrap_integer(out_property))
+        },
+    );
+    map.insert(
+        "removed",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|counts| Ok(i64::try_from(counts.removed)?));
+            Ok(L::wrap_integer(out_property))
+        },
+    );
+    map.insert("files_changed", map["files"]);
+    map.insert("insertions", map["added"]);
+    map.insert("deletions", map["removed"]);
     map
 }
@@ -12,27 +12,49 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs;
 use std::io::BufRead;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::process::Output;
+use std::sync::OnceLock;
 
 use clap::builder::StyledStr;
 use clap::FromArgMatches as _;
 use clap_complete::CompletionCandidate;
 use itertools::Itertools;
 use jj_lib::config::ConfigNamePathBuf;
+use jj_lib::config::ConfigSource;
+use jj_lib::config::StackedConfig;
+use jj_lib::content_hash::blake2b_hash;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::StoreFactories;
 use jj_lib::settings::UserSettings;
 use jj_lib::workspace::DefaultWorkspaceLoaderFactory;
+use jj_lib::workspace::WorkspaceLoader;
 use jj_lib::workspace::WorkspaceLoaderFactory as _;
+use tempfile::NamedTempFile;
 
-use crate::cli_util::expand_args;
+use crate::cli_util::expand_args_for_completion;
 use crate::cli_util::find_workspace_dir;
 use crate::cli_util::GlobalArgs;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::commit_templater::builtin_commit_keywords;
+use crate::commit_templater::builtin_ref_name_keywords;
 use crate::config::config_from_environment;
 use crate::config::default_config_layers;
+use crate::config::resolve_conditional_config;
 use crate::config::ConfigArgKind;
 use crate::config::ConfigEnv;
+use crate::config::ConfigResolutionContext;
 use crate::config::CONFIG_SCHEMA;
+use crate::config_schema::known_config_values;
+use crate::merge_tools::get_external_tool_config;
+use crate::merge_tools::ExternalMergeTool;
+use crate::merge_tools::BUILTIN_EDITOR_NAME;
+use crate::operation_templater::builtin_operation_keywords;
 use crate::ui::Ui;
 
 const BOOKMARK_HELP_TEMPLATE: &str = r#"template-aliases.'bookmark_help()'='''
@@ -57,16 +79,15 @@ fn split_help_text(line: &str) -> (&str, Option<StyledStr>) {
 
 pub fn local_bookmarks() -> Vec<CompletionCandidate> {
     with_jj(|jj, _| {
-        let output = jj
-            .build()
-            .arg("bookmark")
-            .arg("list")
-            .arg("--config")
-            .arg(BOOKMARK_HELP_TEMPLATE)
-            .arg("--template")
-            .arg(r#"if(!remote, name ++ bookmark_help()) ++ "\n""#)
-            .output()
-            .map_err(user_error)?;
+        let output = jj.output(
+            jj.build()
+                .arg("bookmark")
+                .arg("list")
+                .arg("--config")
+                .arg(BOOKMARK_HELP_TEMPLATE)
+                .arg("--template")
+                .arg(r#"if(!remote, name ++ bookmark_help()) ++ "\n""#),
+        )?;
 
         Ok(String::from_utf8_lossy(&output.stdout)
             .lines()
@@ -76,19 +97,83 @@ pub fn local_bookmarks() -> Vec<CompletionCandidate> {
     })
 }
 
+/// Local bookmarks which aren't in sync with their tracked remote bookmarks,
+/// or which haven't been pushed yet.
+pub fn pushable_bookmarks() -> Vec<CompletionCandidate> {
+    with_jj(|jj, _| {
+        let output = jj.output(
+            jj.build()
+                .arg("bookmark")
+                .arg("list")
+                .arg("--all-remotes")
+                .arg("--template")
+                .arg(
+                    // the remote is ahead of the local bookmark by its
+                    // "behind" count, and vice versa
+                    r#"if(!remote,
+                        if(present, name ++ "\n"),
+                        if(tracked && tracking_present && remote != "git",
+                            name ++ "@" ++ remote
+                            ++ " " ++ tracking_behind_count.lower()
+                            ++ " " ++ tracking_ahead_count.lower() ++ "\n"
+                        ),
+                    )"#,
+                ),
+        )?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // Remote bookmarks are listed right after their local counterpart.
+        let mut candidates = vec![];
+        let mut current: Option<(&str, bool, Vec<String>)> = None;
+        let mut flush = |current: Option<(&str, bool, Vec<String>)>| {
+            let Some((name, tracked, diffs)) = current else {
+                return;
+            };
+            if !tracked {
+                candidates.push(CompletionCandidate::new(name).help(Some("new".into())));
+            } else if !diffs.is_empty() {
+                candidates.push(CompletionCandidate::new(name).help(Some(diffs.join("; ").into())));
+            }
+        };
+        for line in stdout.lines() {
+            let Some((remote_ref, counts)) = line.split_once(' ') else {
+                flush(current.take());
+                current = Some((line, false, vec![]));
+                continue;
+            };
+            let Some((name, remote)) = remote_ref.rsplit_once('@') else {
+                continue;
+            };
+            let Some((_, tracked, diffs)) = current.as_mut().filter(|(n, ..)| *n == name) else {
+                continue;
+            };
+            *tracked = true;
+            let (ahead, behind) = counts.split_once(' ').unwrap_or((counts, "0"));
+            let diff = match (ahead, behind) {
+                ("0", "0") => continue,
+                (ahead, "0") => format!("{ahead} ahead"),
+                ("0", behind) => format!("{behind} behind"),
+                (ahead, behind) => format!("{ahead} ahead, {behind} behind"),
+            };
+            diffs.push(format!("{remote}: {diff}"));
+        }
+        flush(current);
+        Ok(candidates)
+    })
+}
+
 pub fn tracked_bookmarks() -> Vec<CompletionCandidate> {
     with_jj(|jj, _| {
-        let output = jj
-            .build()
-            .arg("bookmark")
-            .arg("list")
-            .arg("--tracked")
-            .arg("--config")
-            .arg(BOOKMARK_HELP_TEMPLATE)
-            .arg("--template")
-            .arg(r#"if(remote, name ++ '@' ++ remote ++ bookmark_help() ++ "\n")"#)
-            .output()
-            .map_err(user_error)?;
+        let output = jj.output(
+            jj.build()
+                .arg("bookmark")
+                .arg("list")
+                .arg("--tracked")
+                .arg("--config")
+                .arg(BOOKMARK_HELP_TEMPLATE)
+                .arg("--template")
+                .arg(r#"if(remote, name ++ '@' ++ remote ++ bookmark_help() ++ "\n")"#),
+        )?;
 
         Ok(String::from_utf8_lossy(&output.stdout)
             .lines()
@@ -100,21 +185,20 @@ pub fn tracked_bookmarks() -> Vec<CompletionCandidate> {
 
 pub fn untracked_bookmarks() -> Vec<CompletionCandidate> {
     with_jj(|jj, settings| {
-        let output = jj
-            .build()
-            .arg("bookmark")
-            .arg("list")
-            .arg("--all-remotes")
-            .arg("--config")
-            .arg(BOOKMARK_HELP_TEMPLATE)
-            .arg("--template")
-            .arg(
-                r#"if(remote && !tracked && remote != "git",
+        let output = jj.output(
+            jj.build()
+                .arg("bookmark")
+                .arg("list")
+                .arg("--all-remotes")
+                .arg("--config")
+                .arg(BOOKMARK_HELP_TEMPLATE)
+                .arg("--template")
+                .arg(
+                    r#"if(remote && !tracked && remote != "git",
                     name ++ '@' ++ remote ++ bookmark_help() ++ "\n"
                 )"#,
-            )
-            .output()
-            .map_err(user_error)?;
+                ),
+        )?;
 
         let prefix = settings.get_string("git.push-bookmark-prefix").ok();
 
@@ -138,20 +222,19 @@ pub fn untracked_bookmarks() -> Vec<CompletionCandidate> {
 
 pub fn bookmarks() -> Vec<CompletionCandidate> {
     with_jj(|jj, settings| {
-        let output = jj
-            .build()
-            .arg("bookmark")
-            .arg("list")
-            .arg("--all-remotes")
-            .arg("--config")
-            .arg(BOOKMARK_HELP_TEMPLATE)
-            .arg("--template")
-            .arg(
-                // only provide help for local refs, remote could be ambiguous
-                r#"name ++ if(remote, "@" ++ remote, bookmark_help()) ++ "\n""#,
-            )
-            .output()
-            .map_err(user_error)?;
+        let output = jj.output(
+            jj.build()
+                .arg("bookmark")
+                .arg("list")
+                .arg("--all-remotes")
+                .arg("--config")
+                .arg(BOOKMARK_HELP_TEMPLATE)
+                .arg("--template")
+                .arg(
+                    // only provide help for local refs, remote could be ambiguous
+                    r#"name ++ if(remote, "@" ++ remote, bookmark_help()) ++ "\n""#,
+                ),
+        )?;
         let stdout = String::from_utf8_lossy(&output.stdout);
 
         let prefix = settings.get_string("git.push-bookmark-prefix").ok();
@@ -181,15 +264,29 @@ pub fn bookmarks() -> Vec<CompletionCandidate> {
     })
 }
 
+pub fn change_aliases() -> Vec<CompletionCandidate> {
+    with_jj(|jj, _| {
+        let output = jj.output(
+            jj.build()
+                .arg("--config")
+                .arg(r#"templates.commit_summary='if(description, description.first_line(), "(no description set)")'"#)
+                .arg("alias")
+                .arg("list"),
+        )?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        Ok(stdout
+            .lines()
+            .map(|line| line.split_once(": ").unwrap_or((line, "")))
+            .dedup_by(|(name1, _), (name2, _)| name1 == name2)
+            .map(|(name, desc)| CompletionCandidate::new(name).help(Some(desc.to_string().into())))
+            .collect())
+    })
+}
+
 pub fn git_remotes() -> Vec<CompletionCandidate> {
     with_jj(|jj, _| {
-        let output = jj
-            .build()
-            .arg("git")
-            .arg("remote")
-            .arg("list")
-            .output()
-            .map_err(user_error)?;
+        let output = jj.output(jj.build().arg("git").arg("remote").arg("list"))?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
 
@@ -215,6 +312,70 @@ pub fn aliases() -> Vec<CompletionCandidate> {
     })
 }
 
+fn merge_tools_impl(
+    get_args: impl Fn(&ExternalMergeTool) -> &[String],
+) -> Vec<CompletionCandidate> {
+    with_jj(|_, settings| {
+        let mut candidates = vec![CompletionCandidate::new(BUILTIN_EDITOR_NAME)
+            .help(Some("Use the built-in editor".into()))];
+        for name in settings.table_keys("merge-tools") {
+            let Ok(Some(tool)) = get_external_tool_config(settings, name) else {
+                continue;
+            };
+            let args = get_args(&tool);
+            if args.is_empty() {
+                continue;
+            }
+            let command = [tool.program.as_str()]
+                .into_iter()
+                .chain(args.iter().map(String::as_str))
+                .join(" ");
+            candidates.push(CompletionCandidate::new(name).help(Some(command.into())));
+        }
+        Ok(candidates)
+    })
+}
+
+/// Completes the diff editors usable with `--tool`
+pub fn diff_editors() -> Vec<CompletionCandidate> {
+    merge_tools_impl(|tool| &tool.edit_args)
+}
+
+/// Completes the merge tools usable with `jj resolve --tool`
+pub fn merge_editors() -> Vec<CompletionCandidate> {
+    merge_tools_impl(|tool| &tool.merge_args)
+}
+
+fn templates_impl(keywords: Vec<&'static str>) -> Vec<CompletionCandidate> {
+    with_jj(|_, settings| {
+        let aliases = settings
+            .table_keys("template-aliases")
+            // Function aliases can't be used as a template by themselves.
+            .filter(|alias| !alias.contains('('))
+            .sorted()
+            .map(|alias| CompletionCandidate::new(alias).help(Some("Template alias".into())));
+        let keywords = keywords
+            .into_iter()
+            .map(|keyword| CompletionCandidate::new(keyword).help(Some("Keyword".into())));
+        Ok(aliases.chain(keywords).collect())
+    })
+}
+
+/// Completes the template aliases and keywords usable in commit templates
+pub fn commit_templates() -> Vec<CompletionCandidate> {
+    templates_impl(builtin_commit_keywords())
+}
+
+/// Completes the template aliases and keywords usable in operation templates
+pub fn operation_templates() -> Vec<CompletionCandidate> {
+    templates_impl(builtin_operation_keywords())
+}
+
+/// Completes the template aliases and keywords usable in ref name templates
+pub fn ref_name_templates() -> Vec<CompletionCandidate> {
+    templates_impl(builtin_ref_name_keywords())
+}
+
 fn revisions(revisions: Option<&str>) -> Vec<CompletionCandidate> {
     with_jj(|jj, settings| {
         // display order
@@ -244,7 +405,7 @@ fn revisions(revisions: Option<&str>) -> Vec<CompletionCandidate> {
         if let Some(revs) = revisions {
             cmd.arg("--revisions").arg(revs);
         }
-        let output = cmd.output().map_err(user_error)?;
+        let output = jj.output(&mut cmd)?;
         let stdout = String::from_utf8_lossy(&output.stdout);
 
         candidates.extend(stdout.lines().map(|line| {
@@ -271,16 +432,15 @@ fn revisions(revisions: Option<&str>) -> Vec<CompletionCandidate> {
         // revisions is set. This is not a big loss, since tags usually point
         // to immutable revisions anyway.
         if revisions.is_none() {
-            let output = jj
-                .build()
-                .arg("tag")
-                .arg("list")
-                .arg("--config")
-                .arg(BOOKMARK_HELP_TEMPLATE)
-                .arg("--template")
-                .arg(r#"name ++ bookmark_help() ++ "\n""#)
-                .output()
-                .map_err(user_error)?;
+            let output = jj.output(
+                jj.build()
+                    .arg("tag")
+                    .arg("list")
+                    .arg("--config")
+                    .arg(BOOKMARK_HELP_TEMPLATE)
+                    .arg("--template")
+                    .arg(r#"name ++ bookmark_help() ++ "\n""#),
+            )?;
             let stdout = String::from_utf8_lossy(&output.stdout);
 
             candidates.extend(stdout.lines().map(|line| {
@@ -299,18 +459,17 @@ fn revisions(revisions: Option<&str>) -> Vec<CompletionCandidate> {
             .or_else(|| settings.get_string("revsets.log").ok())
             .unwrap_or_default();
 
-        let output = jj
-            .build()
-            .arg("log")
-            .arg("--no-graph")
-            .arg("--limit")
-            .arg("100")
-            .arg("--revisions")
-            .arg(revisions)
-            .arg("--template")
-            .arg(r#"change_id.shortest() ++ " " ++ if(description, description.first_line(), "(no description set)") ++ "\n""#)
-            .output()
-            .map_err(user_error)?;
+        let output = jj.output(
+            jj.build()
+                .arg("log")
+                .arg("--no-graph")
+                .arg("--limit")
+                .arg("100")
+                .arg("--revisions")
+                .arg(revisions)
+                .arg("--template")
+                .arg(r#"change_id.shortest() ++ " " ++ if(description, description.first_line(), "(no description set)") ++ "\n""#),
+        )?;
         let stdout = String::from_utf8_lossy(&output.stdout);
 
         candidates.extend(stdout.lines().map(|line| {
@@ -334,45 +493,52 @@ pub fn all_revisions() -> Vec<CompletionCandidate> {
 
 pub fn operations() -> Vec<CompletionCandidate> {
     with_jj(|jj, _| {
-        let output = jj
-            .build()
-            .arg("operation")
-            .arg("log")
-            .arg("--no-graph")
-            .arg("--limit")
-            .arg("100")
-            .arg("--template")
-            .arg(
-                r#"
+        let output = jj.output(
+            jj.build()
+                .arg("operation")
+                .arg("log")
+                .arg("--no-graph")
+                .arg("--limit")
+                .arg("100")
+                .arg("--template")
+                .arg(
+                    r#"
                 separate(" ",
                     id.short(),
                     "(" ++ format_timestamp(time.end()) ++ ")",
                     description.first_line(),
                 ) ++ "\n""#,
-            )
-            .output()
-            .map_err(user_error)?;
-
-        Ok(String::from_utf8_lossy(&output.stdout)
+                ),
+        )?;
+
+        let special_candidates = [
+            ("@", "The current operation"),
+            ("@-", "The parent of the current operation"),
+        ]
+        .map(|(id, help)| CompletionCandidate::new(id).help(Some(help.into())));
+        let id_candidates = String::from_utf8_lossy(&output.stdout)
             .lines()
             .map(|line| {
                 let (id, help) = split_help_text(line);
                 CompletionCandidate::new(id).help(help)
             })
+            .collect_vec();
+        Ok(special_candidates
+            .into_iter()
+            .chain(id_candidates)
             .collect())
     })
 }
 
 pub fn workspaces() -> Vec<CompletionCandidate> {
     with_jj(|jj, _| {
-        let output = jj
-            .build()
-            .arg("--config")
-            .arg(r#"templates.commit_summary='if(description, description.first_line(), "(no description set)")'"#)
-            .arg("workspace")
-            .arg("list")
-            .output()
-            .map_err(user_error)?;
+        let output = jj.output(
+            jj.build()
+                .arg("--config")
+                .arg(r#"templates.commit_summary='if(description, description.first_line(), "(no description set)")'"#)
+                .arg("workspace")
+                .arg("list"),
+        )?;
         let stdout = String::from_utf8_lossy(&output.stdout);
 
         Ok(stdout
@@ -445,6 +611,21 @@ pub fn leaf_config_keys() -> Vec<CompletionCandidate> {
     config_keys_impl(true)
 }
 
+/// Completes the values declared in the schema for the config variable given
+/// to `jj config set`
+pub fn config_values() -> Vec<CompletionCandidate> {
+    let Some(name) = parse::config_set_name().and_then(|name| name.parse().ok()) else {
+        return Vec::new();
+    };
+    known_config_values(&name)
+        .into_iter()
+        .map(|value| match value {
+            serde_json::Value::String(text) => CompletionCandidate::new(text),
+            value => CompletionCandidate::new(value.to_string()),
+        })
+        .collect()
+}
+
 fn dir_prefix_from<'a>(path: &'a str, current: &str) -> Option<&'a str> {
     path[current.len()..]
         .split_once(std::path::MAIN_SEPARATOR)
@@ -493,12 +674,13 @@ fn all_files_from_rev(rev: String, current: &std::ffi::OsStr) -> Vec<CompletionC
 
 fn modified_files_from_rev_with_jj_cmd(
     rev: (String, Option<String>),
-    mut cmd: std::process::Command,
+    jj: &JjBuilder,
     current: &std::ffi::OsStr,
 ) -> Result<Vec<CompletionCandidate>, CommandError> {
     let Some(current) = current.to_str() else {
         return Ok(Vec::new());
     };
+    let mut cmd = jj.build();
     cmd.arg("diff")
         .arg("--summary")
         .arg("--config=ui.allow-filesets=true")
@@ -507,7 +689,7 @@ fn modified_files_from_rev_with_jj_cmd(
         (rev, None) => cmd.arg("--revision").arg(rev),
         (from, Some(to)) => cmd.arg("--from").arg(from).arg("--to").arg(to),
     };
-    let output = cmd.output().map_err(user_error)?;
+    let output = jj.output(&mut cmd)?;
     let stdout = String::from_utf8_lossy(&output.stdout);
 
     Ok(stdout
@@ -539,7 +721,7 @@ fn modified_files_from_rev(
     rev: (String, Option<String>),
     current: &std::ffi::OsStr,
 ) -> Vec<CompletionCandidate> {
-    with_jj(|jj, _| modified_files_from_rev_with_jj_cmd(rev, jj.build(), current))
+    with_jj(|jj, _| modified_files_from_rev_with_jj_cmd(rev, &jj, current))
 }
 
 fn conflicted_files_from_rev(rev: &str, current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
@@ -547,16 +729,15 @@ fn conflicted_files_from_rev(rev: &str, current: &std::ffi::OsStr) -> Vec<Comple
         return Vec::new();
     };
     with_jj(|jj, _| {
-        let output = jj
-            .build()
-            .arg("resolve")
-            .arg("--list")
-            .arg("--revision")
-            .arg(rev)
-            .arg("--config=ui.allow-filesets=true")
-            .arg(current_prefix_to_fileset(current))
-            .output()
-            .map_err(user_error)?;
+        let output = jj.output(
+            jj.build()
+                .arg("resolve")
+                .arg("--list")
+                .arg("--revision")
+                .arg(rev)
+                .arg("--config=ui.allow-filesets=true")
+                .arg(current_prefix_to_fileset(current)),
+        )?;
         let stdout = String::from_utf8_lossy(&output.stdout);
 
         Ok(stdout
@@ -610,7 +791,10 @@ pub fn revision_conflicted_files(current: &std::ffi::OsStr) -> Vec<CompletionCan
 /// Specific function for completing file paths for `jj squash`
 pub fn squash_revision_files(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
     let rev = parse::squash_revision().unwrap_or_else(|| "@".into());
-    modified_files_from_rev((rev, None), current)
+    // If the destination is specified, only the files that differ between the
+    // source and the destination are worth moving.
+    let into = parse::squash_into();
+    modified_files_from_rev((rev, into), current)
 }
 
 /// Specific function for completing file paths for `jj interdiff`
@@ -622,10 +806,10 @@ pub fn interdiff_files(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
     // files that are the same in both, which is a false positive. This approach
     // is more lightweight than actually doing a temporary rebase here.
     with_jj(|jj, _| {
-        let mut res = modified_files_from_rev_with_jj_cmd((from, None), jj.build(), current)?;
+        let mut res = modified_files_from_rev_with_jj_cmd((from, None), &jj, current)?;
         res.extend(modified_files_from_rev_with_jj_cmd(
             (to, None),
-            jj.build(),
+            &jj,
             current,
         )?);
         Ok(res)
@@ -693,9 +877,22 @@ fn get_jj_command() -> Result<(JjBuilder, UserSettings), CommandError> {
         config_env.reset_repo_path(loader.repo_path());
         let _ = config_env.reload_repo_config(&mut config);
     }
+    let mut maybe_workspace_loader = maybe_cwd_workspace_loader.ok();
+    let resolve_config = |config: &StackedConfig, loader: Option<&dyn WorkspaceLoader>| {
+        let context = ConfigResolutionContext::new(
+            loader.map(|loader| loader.workspace_root()),
+            loader.map(|loader| loader.repo_path()),
+        );
+        resolve_conditional_config(config, &context).unwrap_or_else(|_| config.clone())
+    };
     // skip 2 because of the clap_complete prelude: jj -- jj <actual args...>
     let args = std::env::args_os().skip(2);
-    let args = expand_args(&ui, &app, args, &config)?;
+    let args = expand_args_for_completion(
+        &ui,
+        &app,
+        args,
+        &resolve_config(&config, maybe_workspace_loader.as_deref()),
+    )?;
     let arg_matches = app
         .clone()
         .disable_version_flag(true)
@@ -709,6 +906,7 @@ fn get_jj_command() -> Result<(JjBuilder, UserSettings), CommandError> {
         if let Ok(loader) = DefaultWorkspaceLoaderFactory.create(&cwd.join(&repository)) {
             config_env.reset_repo_path(loader.repo_path());
             let _ = config_env.reload_repo_config(&mut config);
+            maybe_workspace_loader = Some(loader);
         }
         cmd_args.push("--repository".into());
         cmd_args.push(repository);
@@ -749,11 +947,16 @@ fn get_jj_command() -> Result<(JjBuilder, UserSettings), CommandError> {
         cmd_args.push(arg);
     }
 
+    let settings =
+        UserSettings::from_config(resolve_config(&config, maybe_workspace_loader.as_deref()));
+    let cache = maybe_workspace_loader
+        .as_deref()
+        .and_then(|loader| CompletionCache::load(loader, &settings));
     let builder = JjBuilder {
         cmd: current_exe,
         args: cmd_args,
+        cache,
     };
-    let settings = UserSettings::from_config(config);
 
     Ok((builder, settings))
 }
@@ -763,6 +966,7 @@ fn get_jj_command() -> Result<(JjBuilder, UserSettings), CommandError> {
 struct JjBuilder {
     cmd: std::path::PathBuf,
     args: Vec<String>,
+    cache: Option<CompletionCache>,
 }
 
 impl JjBuilder {
@@ -771,6 +975,104 @@ impl JjBuilder {
         cmd.args(&self.args);
         cmd
     }
+
+    /// Runs the command built by `build()`, reusing the output of the previous
+    /// run if the repo hasn't changed since.
+    fn output(&self, cmd: &mut std::process::Command) -> Result<Output, CommandError> {
+        let Some(cache) = &self.cache else {
+            return cmd.output().map_err(user_error);
+        };
+        let args = cmd
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect_vec();
+        if let Some(stdout) = cache.get(&args) {
+            return Ok(Output {
+                status: ExitStatus::default(),
+                stdout,
+                stderr: vec![],
+            });
+        }
+        let output = cmd.output().map_err(user_error)?;
+        if output.status.success() {
+            cache.insert(&args, &output.stdout);
+        }
+        Ok(output)
+    }
+}
+
+/// On-disk cache of the output of the commands run to generate completions.
+///
+/// Entries are stored in a directory keyed by the operation heads and the
+/// config, so they are invalidated as soon as the repo changes.
+struct CompletionCache {
+    dir: PathBuf,
+    state_key: String,
+}
+
+impl CompletionCache {
+    fn load(loader: &dyn WorkspaceLoader, settings: &UserSettings) -> Option<Self> {
+        let op_heads_store = StoreFactories::default()
+            .load_op_heads_store(settings, &loader.repo_path().join("op_heads"))
+            .ok()?;
+        let op_heads = op_heads_store.get_op_heads().ok()?;
+        let mut state = op_heads.iter().map(|id| id.hex()).sorted().collect_vec();
+        // Outputs can depend on the working-copy commit and the config. The
+        // environment overrides (e.g. JJ_TIMESTAMP) are excluded as they
+        // don't affect the output of read-only commands.
+        state.push(loader.workspace_root().to_string_lossy().into_owned());
+        state.extend(
+            settings
+                .config()
+                .layers()
+                .iter()
+                .filter(|layer| layer.source != ConfigSource::EnvOverrides)
+                .map(|layer| layer.data.to_string()),
+        );
+        Some(CompletionCache {
+            dir: loader.repo_path().join("completion_cache"),
+            state_key: hash_key(&state),
+        })
+    }
+
+    fn get(&self, args: &[String]) -> Option<Vec<u8>> {
+        fs::read(self.dir.join(&self.state_key).join(hash_key(args))).ok()
+    }
+
+    fn insert(&self, args: &[String], stdout: &[u8]) {
+        let state_dir = self.dir.join(&self.state_key);
+        if !state_dir.exists() {
+            // Entries for the previous states will never be used again.
+            _ = fs::remove_dir_all(&self.dir);
+            if fs::create_dir_all(&state_dir).is_err() {
+                return;
+            }
+        }
+        // Write to a temporary file first so concurrent completions never see
+        // a partially written entry.
+        let Ok(mut temp_file) = NamedTempFile::new_in(&state_dir) else {
+            return;
+        };
+        if temp_file.write_all(stdout).is_ok() {
+            _ = temp_file.persist(state_dir.join(hash_key(args)));
+        }
+    }
+}
+
+fn hash_key(parts: &[String]) -> String {
+    blake2b_hash(parts)[..16]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// The command line with aliases expanded, as it's passed to clap_complete.
+static EXPANDED_ARGS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Records the command line with aliases expanded, so that the completers see
+/// the args of the command an alias expands to.
+pub(crate) fn set_expanded_args(args: Vec<String>) {
+    EXPANDED_ARGS.set(args).ok();
 }
 
 /// Functions for parsing revisions and revision ranges from the command line.
@@ -782,6 +1084,14 @@ impl JjBuilder {
 /// multiple times, the parsing will pick any of the available ones, while the
 /// actual execution of the command would fail.
 mod parse {
+    fn args() -> impl Iterator<Item = String> {
+        let args = match super::EXPANDED_ARGS.get() {
+            Some(args) => args.clone(),
+            None => std::env::args().collect(),
+        };
+        args.into_iter()
+    }
+
     pub(super) fn parse_flag<'a>(
         candidates: &'a [&str],
         mut args: impl Iterator<Item = String> + 'a,
@@ -820,7 +1130,7 @@ mod parse {
     }
 
     pub fn revision() -> Option<String> {
-        parse_revision_impl(std::env::args())
+        parse_revision_impl(args())
     }
 
     pub fn revision_or_wc() -> String {
@@ -840,25 +1150,46 @@ mod parse {
     }
 
     pub fn range() -> Option<(String, String)> {
-        parse_range_impl(std::env::args)
+        parse_range_impl(args)
     }
 
-    // Special parse function only for `jj squash`. While squash has --from and
+    // Special parse functions only for `jj squash`. While squash has --from and
     // --to arguments, only files within --from should be completed, because
     // the files changed only in some other revision in the range between
     // --from and --to cannot be squashed into --to like that.
     pub fn squash_revision() -> Option<String> {
-        if let Some(rev) = parse_flag(&["-r", "--revision"], std::env::args()).next() {
+        if let Some(rev) = parse_flag(&["-r", "--revision"], args()).next() {
             return Some(rev);
         }
-        parse_flag(&["-f", "--from"], std::env::args()).next()
+        parse_flag(&["-f", "--from"], args()).next()
+    }
+
+    pub fn parse_squash_into_impl(args: impl Iterator<Item = String>) -> Option<String> {
+        parse_flag(&["-t", "--into", "--to"], args).next()
+    }
+
+    pub fn squash_into() -> Option<String> {
+        parse_squash_into_impl(args())
     }
 
     // Special parse function only for `jj log`. It has a --revisions flag,
     // instead of the usual --revision, and it can be supplied multiple times.
     pub fn log_revisions() -> Vec<String> {
         let candidates = &["-r", "--revisions"];
-        parse_flag(candidates, std::env::args()).collect()
+        parse_flag(candidates, args()).collect()
+    }
+
+    pub fn parse_config_set_name_impl(args: impl Iterator<Item = String>) -> Option<String> {
+        args.skip_while(|arg| arg != "config")
+            .skip_while(|arg| arg != "set")
+            .skip(1)
+            .find(|arg| !arg.starts_with('-'))
+    }
+
+    // Special parse function only for `jj config set`. The config name is the
+    // first positional argument after the subcommand.
+    pub fn config_set_name() -> Option<String> {
+        parse_config_set_name_impl(args())
     }
 }
 
@@ -897,6 +1228,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_squash_into_impl() {
+        let good_cases: &[&[&str]] = &[
+            &["-t", "foo"],
+            &["--into", "foo"],
+            &["--to", "foo"],
+            &["-tfoo"],
+            &["--into=foo"],
+            &["--from", "bar", "--to=foo"],
+        ];
+        for case in good_cases {
+            let args = case.iter().map(|s| s.to_string());
+            assert_eq!(
+                parse::parse_squash_into_impl(args),
+                Some("foo".into()),
+                "case: {case:?}",
+            );
+        }
+        let bad_cases: &[&[&str]] = &[&[], &["--from", "foo"], &["--tool", "foo"], &["--tool=foo"]];
+        for case in bad_cases {
+            let args = case.iter().map(|s| s.to_string());
+            assert_eq!(parse::parse_squash_into_impl(args), None, "case: {case:?}");
+        }
+    }
+
     #[test]
     fn test_parse_range_impl() {
         let wc_cases: &[&[&str]] = &[
@@ -959,4 +1315,24 @@ mod tests {
         let expected = ["1", "2", "3", "4", "5"];
         assert_eq!(flags, expected);
     }
+
+    #[test]
+    fn test_parse_config_set_name_impl() {
+        let parse =
+            |args: &[&str]| parse::parse_config_set_name_impl(args.iter().map(|a| a.to_string()));
+        assert_eq!(
+            parse(&["jj", "--", "jj", "config", "set", "ui.color", ""]),
+            Some("ui.color".into())
+        );
+        assert_eq!(
+            parse(&["jj", "--", "jj", "config", "set", "--user", "ui.color", ""]),
+            Some("ui.color".into())
+        );
+        assert_eq!(
+            parse(&["jj", "--", "jj", "-R", "repo", "config", "set", "--repo", "ui.color"]),
+            Some("ui.color".into())
+        );
+        assert_eq!(parse(&["jj", "--", "jj", "config", "set"]), None);
+        assert_eq!(parse(&["jj", "--", "jj", "log", "-r", "set"]), None);
+    }
 }
@@ -13,10 +13,12 @@
 // limitations under the License.
 
 use std::borrow::Cow;
+use std::cell::OnceCell;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
 use std::fmt;
+use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
@@ -29,9 +31,11 @@ use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::ConfigValue;
 use jj_lib::config::StackedConfig;
+use jj_lib::file_util;
 use regex::Captures;
 use regex::Regex;
 use thiserror::Error;
+use toml_edit::DocumentMut;
 use tracing::instrument;
 
 use crate::command_error::config_error;
@@ -90,7 +94,8 @@ pub struct AnnotatedValue {
     pub value: ConfigValue,
     /// Source of the configuration value.
     pub source: ConfigSource,
-    // TODO: add source file path
+    /// Path to the source file, if the value came from a file.
+    pub path: Option<PathBuf>,
     /// True if this value is overridden in higher precedence layers.
     pub is_overridden: bool,
 }
@@ -131,6 +136,7 @@ pub fn resolved_config_values(
                     name,
                     value,
                     source: layer.source,
+                    path: layer.path.clone(),
                     // Note: Value updated below.
                     is_overridden: false,
                 });
@@ -370,6 +376,177 @@ fn config_files_for(
     Ok(files)
 }
 
+/// Context used to evaluate the conditions of `[[include-if]]` config sections.
+#[derive(Debug, Default)]
+pub struct ConfigResolutionContext {
+    workspace_root: Option<PathBuf>,
+    repo_path: Option<PathBuf>,
+    remote_urls: OnceCell<Vec<String>>,
+}
+
+impl ConfigResolutionContext {
+    /// Creates context for the workspace at `workspace_root` backed by the
+    /// repo at `repo_path` (usually `.jj/repo`).
+    pub fn new(workspace_root: Option<&Path>, repo_path: Option<&Path>) -> Self {
+        ConfigResolutionContext {
+            workspace_root: workspace_root.map(ToOwned::to_owned),
+            repo_path: repo_path.map(ToOwned::to_owned),
+            remote_urls: OnceCell::new(),
+        }
+    }
+
+    /// URLs of the Git remotes of the repo. These are only read if a section
+    /// is conditioned on remotes.
+    fn remote_urls(&self) -> &[String] {
+        self.remote_urls.get_or_init(|| {
+            self.repo_path
+                .as_deref()
+                .map(git_remote_urls)
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// Returns the fetch URLs of the remotes of the Git repo backing the repo at
+/// `repo_path`, or an empty list if it isn't backed by Git.
+fn git_remote_urls(repo_path: &Path) -> Vec<String> {
+    let store_path = repo_path.join("store");
+    let Ok(git_target) = fs::read_to_string(store_path.join("git_target")) else {
+        return vec![];
+    };
+    let Ok(git_repo) = gix::open_opts(store_path.join(git_target), gix::open::Options::isolated())
+    else {
+        return vec![];
+    };
+    git_repo
+        .remote_names()
+        .iter()
+        .filter_map(|name| git_repo.find_remote(name.as_ref()).ok())
+        .filter_map(|remote| {
+            let url = remote.url(gix::remote::Direction::Fetch)?;
+            Some(url.to_bstring().to_string())
+        })
+        .collect()
+}
+
+/// Returns config in which the `[[include-if]]` sections of each layer whose
+/// conditions match the `context` are added as layers.
+///
+/// The added layers are placed right after the layer that declares them, so
+/// they override the other values of the same file. An included config file
+/// can't declare `[[include-if]]` sections of its own.
+pub fn resolve_conditional_config(
+    config: &StackedConfig,
+    context: &ConfigResolutionContext,
+) -> Result<StackedConfig, CommandError> {
+    let mut resolved = StackedConfig::empty();
+    for layer in config.layers() {
+        resolved.add_layer(layer.clone());
+        let Ok(Some(item)) = layer.look_up_item("include-if") else {
+            continue;
+        };
+        let invalid = |message: String| {
+            let location = match &layer.path {
+                Some(path) => format!(" in {}", path.display()),
+                None => String::new(),
+            };
+            config_error(format!("Invalid include-if section{location}: {message}"))
+        };
+        let sections = match item {
+            toml_edit::Item::ArrayOfTables(tables) => tables.iter().cloned().collect_vec(),
+            toml_edit::Item::Value(toml_edit::Value::Array(array)) => array
+                .iter()
+                .map(|value| match value {
+                    toml_edit::Value::InlineTable(table) => Ok(table.clone().into_table()),
+                    _ => Err(invalid("expected an array of tables".to_owned())),
+                })
+                .try_collect()?,
+            _ => return Err(invalid("expected an array of tables".to_owned())),
+        };
+        for section in sections {
+            let mut include_path = None;
+            let mut include_data = None;
+            let mut matches = true;
+            for (key, item) in &section {
+                match key {
+                    "repositories" => {
+                        let patterns = parse_glob_patterns(item).map_err(&invalid)?;
+                        matches &= context.workspace_root.as_deref().is_some_and(|root| {
+                            root.ancestors()
+                                .any(|dir| patterns.iter().any(|p| p.matches_path(dir)))
+                        });
+                    }
+                    "remotes" => {
+                        let patterns = parse_glob_patterns(item).map_err(&invalid)?;
+                        matches &= context
+                            .remote_urls()
+                            .iter()
+                            .any(|url| patterns.iter().any(|p| p.matches(url)));
+                    }
+                    "path" => {
+                        let path = item
+                            .as_str()
+                            .ok_or_else(|| invalid("path must be a string".to_owned()))?;
+                        include_path = Some(file_util::expand_home_path(path));
+                    }
+                    "config" => {
+                        let table = match item.clone() {
+                            toml_edit::Item::Table(table) => table,
+                            toml_edit::Item::Value(toml_edit::Value::InlineTable(table)) => {
+                                table.into_table()
+                            }
+                            _ => return Err(invalid("config must be a table".to_owned())),
+                        };
+                        include_data = Some(DocumentMut::from(table));
+                    }
+                    _ => return Err(invalid(format!("unknown key {key:?}"))),
+                }
+            }
+            if !matches {
+                continue;
+            }
+            if let Some(data) = include_data {
+                resolved.add_layer(ConfigLayer {
+                    source: layer.source,
+                    path: layer.path.clone(),
+                    data,
+                });
+            }
+            if let Some(path) = include_path {
+                // Relative paths are resolved from the directory of the
+                // including file.
+                let path = match layer.path.as_deref().and_then(Path::parent) {
+                    Some(dir) => dir.join(path),
+                    None => path,
+                };
+                resolved.add_layer(ConfigLayer::load_from_file(layer.source, path)?);
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// Parses an array of strings as glob patterns. `~/` is expanded to the home
+/// directory.
+fn parse_glob_patterns(item: &toml_edit::Item) -> Result<Vec<glob::Pattern>, String> {
+    let texts: Vec<&str> = item
+        .as_array()
+        .and_then(|array| array.iter().map(|value| value.as_str()).collect())
+        .ok_or_else(|| "patterns must be an array of strings".to_owned())?;
+    texts
+        .into_iter()
+        .map(|text| {
+            let path = file_util::expand_home_path(text);
+            let text = path.to_string_lossy();
+            let text = match text.trim_end_matches(['/', '\\']) {
+                "" => &text,
+                trimmed => trimmed,
+            };
+            glob::Pattern::new(text).map_err(|err| format!("invalid pattern {text:?}: {err}"))
+        })
+        .collect()
+}
+
 /// Initializes stacked config with the given `default_layers` and infallible
 /// sources.
 ///
@@ -866,6 +1043,7 @@ mod tests {
                     },
                 ),
                 source: EnvBase,
+                path: None,
                 is_overridden: false,
             },
             AnnotatedValue {
@@ -893,6 +1071,7 @@ mod tests {
                     },
                 ),
                 source: EnvBase,
+                path: None,
                 is_overridden: true,
             },
             AnnotatedValue {
@@ -920,6 +1099,7 @@ mod tests {
                     },
                 ),
                 source: Repo,
+                path: None,
                 is_overridden: false,
             },
         ]
@@ -968,6 +1148,7 @@ mod tests {
                     },
                 ),
                 source: User,
+                path: None,
                 is_overridden: false,
             },
             AnnotatedValue {
@@ -995,6 +1176,7 @@ mod tests {
                     },
                 ),
                 source: Repo,
+                path: None,
                 is_overridden: false,
             },
         ]
@@ -1002,6 +1184,106 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_resolve_conditional_config() {
+        let temp_dir = testutils::new_temp_dir();
+        let work_config_path = temp_dir.path().join("work.toml");
+        std::fs::write(&work_config_path, "user.name = 'work'").unwrap();
+        let user_config_path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &user_config_path,
+            indoc! {"
+                user.email = 'me@example.com'
+
+                [[include-if]]
+                repositories = ['/home/me/work/*']
+                config.user.email = 'me@work.example.com'
+
+                [[include-if]]
+                remotes = ['https://github.com/acme/*']
+                path = 'work.toml'
+
+                [[include-if]]
+                repositories = ['/home/me/work']
+                remotes = ['https://github.com/acme/*']
+                config.user.email = 'both@work.example.com'
+            "},
+        )
+        .unwrap();
+        let mut config = StackedConfig::empty();
+        config
+            .load_file(ConfigSource::User, &user_config_path)
+            .unwrap();
+        let resolve = |workspace_root: &str, remote_urls: &[&str]| {
+            let context = ConfigResolutionContext {
+                workspace_root: Some(PathBuf::from(workspace_root)),
+                repo_path: None,
+                remote_urls: OnceCell::from(
+                    remote_urls.iter().map(|s| s.to_string()).collect_vec(),
+                ),
+            };
+            let config = resolve_conditional_config(&config, &context).unwrap();
+            let name = config.get::<String>("user.name").ok();
+            let email = config.get::<String>("user.email").unwrap();
+            (name, email)
+        };
+
+        assert_eq!(
+            resolve("/home/me/personal/repo", &[]),
+            (None, "me@example.com".to_owned())
+        );
+        assert_eq!(
+            resolve("/home/me/work/repo/", &[]),
+            (None, "me@work.example.com".to_owned())
+        );
+        assert_eq!(
+            resolve("/home/me/work/repo/nested", &[]),
+            (None, "me@work.example.com".to_owned())
+        );
+        assert_eq!(
+            resolve("/tmp/repo", &["https://github.com/acme/widget"]),
+            (Some("work".to_owned()), "me@example.com".to_owned())
+        );
+        assert_eq!(
+            resolve("/home/me/work/repo", &["https://github.com/acme/widget"]),
+            (Some("work".to_owned()), "both@work.example.com".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_resolve_conditional_config_invalid() {
+        let resolve = |text: &str| {
+            let mut config = StackedConfig::empty();
+            config.add_layer(ConfigLayer::parse(ConfigSource::User, text).unwrap());
+            let context = ConfigResolutionContext::new(Some(Path::new("/repo")), None);
+            resolve_conditional_config(&config, &context)
+                .map(|_| ())
+                .map_err(|err| err.error.to_string())
+        };
+        assert_eq!(resolve("include-if = [{ config.foo = 1 }]"), Ok(()));
+        assert_eq!(
+            resolve("include-if = 'foo'"),
+            Err("Invalid include-if section: expected an array of tables".to_owned())
+        );
+        assert_eq!(
+            resolve("include-if = [{ repositories = '/repo' }]"),
+            Err("Invalid include-if section: patterns must be an array of strings".to_owned())
+        );
+        assert_eq!(
+            resolve("include-if = [{ repository = ['/repo'] }]"),
+            Err(r#"Invalid include-if section: unknown key "repository""#.to_owned())
+        );
+        assert_eq!(
+            resolve("include-if = [{ repositories = ['/a**'] }]"),
+            Err(concat!(
+                r#"Invalid include-if section: invalid pattern "/a**": "#,
+                "Pattern syntax error near position 1: ",
+                "recursive wildcards must form a single path component",
+            )
+            .to_owned())
+        );
+    }
+
     #[test]
     fn test_config_path_home_dir_existing() -> anyhow::Result<()> {
         TestCase {
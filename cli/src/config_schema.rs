@@ -0,0 +1,455 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of config values against the [`CONFIG_SCHEMA`].
+//!
+//! Only the subset of JSON Schema used by the config schema is supported.
+//! Unknown keywords are ignored, and so are config keys which aren't declared
+//! in the schema, since it doesn't cover all config variables.
+
+use itertools::Itertools as _;
+use jj_lib::config::ConfigNamePathBuf;
+use jj_lib::config::ConfigValue;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Map;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::config::CONFIG_SCHEMA;
+
+static SCHEMA: Lazy<Value> = Lazy::new(|| serde_json::from_str(CONFIG_SCHEMA).unwrap());
+
+/// Config value doesn't match the type declared in the schema.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[error("Invalid type or value for {name}")]
+pub struct ConfigSchemaError {
+    /// Dotted config name path.
+    pub name: String,
+    /// Description of the expected type or value, such as "a boolean".
+    pub expected: String,
+}
+
+/// Checks that `value` can be set to the config variable `name`.
+pub fn check_config_value(
+    name: &ConfigNamePathBuf,
+    value: &ConfigValue,
+) -> Result<(), ConfigSchemaError> {
+    let value = name
+        .components()
+        .rev()
+        .fold(value_to_json(value), |value, key| {
+            Value::Object(Map::from_iter([(key.get().to_owned(), value)]))
+        });
+    check_root(&value)
+}
+
+/// Returns the values declared in the schema for the config variable `name`,
+/// such as the choices of an enum or `true` and `false` for a boolean.
+///
+/// Returns an empty list if the variable isn't declared or its values can't
+/// be enumerated.
+pub fn known_config_values(name: &ConfigNamePathBuf) -> Vec<Value> {
+    let schema = name.components().try_fold(&*SCHEMA, |schema, key| {
+        let schema = resolve_refs(schema);
+        schema
+            .get("properties")
+            .and_then(|properties| properties.get(key.get()))
+            .or_else(|| schema.get("additionalProperties"))
+    });
+    let mut values = vec![];
+    if let Some(schema) = schema {
+        collect_known_values(schema, &mut values);
+    }
+    values
+}
+
+fn collect_known_values(schema: &Value, values: &mut Vec<Value>) {
+    let schema = resolve_refs(schema);
+    let push = |value: &Value| {
+        if !values.contains(value) {
+            values.push(value.clone());
+        }
+    };
+    if let Some(alternatives) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+    {
+        for alternative in alternatives {
+            collect_known_values(alternative, values);
+        }
+    } else if let Some(choices) = schema.get("enum").and_then(Value::as_array) {
+        choices.iter().for_each(push);
+    } else if schema.get("type").is_some() && accepts_type(schema, &Value::Bool(true)) {
+        [Value::Bool(true), Value::Bool(false)]
+            .iter()
+            .for_each(push);
+    }
+}
+
+fn resolve_refs(mut schema: &Value) -> &Value {
+    while let Some(target) = schema.get("$ref").and_then(Value::as_str) {
+        schema = resolve_ref(target);
+    }
+    schema
+}
+
+/// Checks all config variables in the `table`.
+pub fn check_config_table(table: &toml_edit::Table) -> Result<(), ConfigSchemaError> {
+    check_root(&table_to_json(table))
+}
+
+fn check_root(value: &Value) -> Result<(), ConfigSchemaError> {
+    let mut path = vec![];
+    check_value(&SCHEMA, value, &mut path).map_err(|expected| ConfigSchemaError {
+        name: ConfigNamePathBuf::from_iter(path).to_string(),
+        expected,
+    })
+}
+
+/// Checks `value` against the `schema`. On error, `path` is left pointing to
+/// the invalid value, and the description of the expected value is returned.
+fn check_value(schema: &Value, value: &Value, path: &mut Vec<String>) -> Result<(), String> {
+    let fields = match schema {
+        Value::Bool(true) => return Ok(()),
+        Value::Object(fields) => fields,
+        _ => return Err(describe(schema)),
+    };
+    if let Some(target) = fields.get("$ref").and_then(Value::as_str) {
+        return check_value(resolve_ref(target), value, path);
+    }
+    if let Some(alternatives) = schema
+        .get("oneOf")
+        .or_else(|| fields.get("anyOf"))
+        .and_then(Value::as_array)
+    {
+        if alternatives
+            .iter()
+            .any(|alternative| check_value(alternative, value, &mut path.clone()).is_ok())
+        {
+            return Ok(());
+        }
+        // If only one alternative accepts the type of the value, the error in
+        // it is more precise.
+        if let Ok(alternative) = alternatives
+            .iter()
+            .filter(|alternative| accepts_type(alternative, value))
+            .exactly_one()
+        {
+            return check_value(alternative, value, path);
+        }
+        return Err(describe(schema));
+    }
+    if let Some(choices) = fields.get("enum").and_then(Value::as_array) {
+        if !choices.contains(value) {
+            return Err(describe(schema));
+        }
+    }
+    if !accepts_type(schema, value) {
+        return Err(describe(schema));
+    }
+    if let (Some(pattern), Some(text)) = (
+        fields.get("pattern").and_then(Value::as_str),
+        value.as_str(),
+    ) {
+        if Regex::new(pattern).is_ok_and(|regex| !regex.is_match(text)) {
+            return Err(describe(schema));
+        }
+    }
+    match value {
+        Value::Array(items) => {
+            if let Some(item_schema) = fields.get("items") {
+                for item in items {
+                    // The config name can't point to an array item, so the
+                    // whole array is reported.
+                    if check_value(item_schema, item, &mut path.clone()).is_err() {
+                        return Err(describe(schema));
+                    }
+                }
+            }
+        }
+        Value::Object(entries) => {
+            let properties = fields.get("properties").and_then(Value::as_object);
+            for (key, entry) in entries {
+                let Some(entry_schema) = properties
+                    .and_then(|properties| properties.get(key))
+                    .or_else(|| fields.get("additionalProperties"))
+                else {
+                    continue;
+                };
+                path.push(key.clone());
+                check_value(entry_schema, entry, path)?;
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Returns true if the `schema` declares no type or the type of the `value`.
+fn accepts_type(schema: &Value, value: &Value) -> bool {
+    let Some(schema) = schema.as_object() else {
+        return true;
+    };
+    if let Some(target) = schema.get("$ref").and_then(Value::as_str) {
+        return accepts_type(resolve_ref(target), value);
+    }
+    if let Some(alternatives) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+    {
+        return alternatives
+            .iter()
+            .any(|alternative| accepts_type(alternative, value));
+    }
+    if let Some(choices) = schema.get("enum").and_then(Value::as_array) {
+        return choices
+            .iter()
+            .any(|choice| json_type(choice) == json_type(value));
+    }
+    match schema.get("type") {
+        Some(Value::String(name)) => type_matches(name, value),
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| type_matches(name, value)),
+        _ => true,
+    }
+}
+
+fn type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        _ => json_type(value) == name,
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn resolve_ref(target: &str) -> &'static Value {
+    target
+        .strip_prefix('#')
+        .and_then(|pointer| SCHEMA.pointer(pointer))
+        .unwrap_or(&Value::Bool(true))
+}
+
+/// Describes the values accepted by the `schema`, e.g. "a string".
+fn describe(schema: &Value) -> String {
+    let Some(schema) = schema.as_object() else {
+        return "any value".to_owned();
+    };
+    if let Some(target) = schema.get("$ref").and_then(Value::as_str) {
+        return describe(resolve_ref(target));
+    }
+    if let Some(alternatives) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+    {
+        return alternatives.iter().map(describe).unique().join(" or ");
+    }
+    if let Some(choices) = schema.get("enum").and_then(Value::as_array) {
+        return format!("one of {}", choices.iter().join(", "));
+    }
+    let type_names = match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => return "any value".to_owned(),
+    };
+    type_names
+        .iter()
+        .map(|&name| match name {
+            "string" => match schema.get("pattern").and_then(Value::as_str) {
+                Some(pattern) => format!("a string matching `{pattern}`"),
+                None => "a string".to_owned(),
+            },
+            "boolean" => "a boolean".to_owned(),
+            "integer" => "an integer".to_owned(),
+            "number" => "a number".to_owned(),
+            "array" => match schema.get("items") {
+                Some(items) => format!("an array whose items are {}", describe(items)),
+                None => "an array".to_owned(),
+            },
+            "object" => "a table".to_owned(),
+            name => format!("a value of type {name}"),
+        })
+        .join(" or ")
+}
+
+/// Converts TOML value to JSON value.
+pub(crate) fn value_to_json(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(v) => Value::from(v.value().as_str()),
+        toml_edit::Value::Integer(v) => Value::from(*v.value()),
+        toml_edit::Value::Float(v) => Value::from(*v.value()),
+        toml_edit::Value::Boolean(v) => Value::from(*v.value()),
+        toml_edit::Value::Datetime(v) => Value::from(v.value().to_string()),
+        toml_edit::Value::Array(array) => array.iter().map(value_to_json).collect(),
+        toml_edit::Value::InlineTable(table) => table
+            .iter()
+            .map(|(key, value)| (key.to_owned(), value_to_json(value)))
+            .collect(),
+    }
+}
+
+fn item_to_json(item: &toml_edit::Item) -> Value {
+    match item {
+        toml_edit::Item::None => Value::Null,
+        toml_edit::Item::Value(value) => value_to_json(value),
+        toml_edit::Item::Table(table) => table_to_json(table),
+        toml_edit::Item::ArrayOfTables(tables) => tables.iter().map(table_to_json).collect(),
+    }
+}
+
+fn table_to_json(table: &toml_edit::Table) -> Value {
+    table
+        .iter()
+        .map(|(key, item)| (key.to_owned(), item_to_json(item)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_value_or_bare_string;
+
+    fn check(name: &str, value: &str) -> Result<(), ConfigSchemaError> {
+        check_config_value(
+            &name.parse().unwrap(),
+            &parse_value_or_bare_string(value).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_check_config_value() {
+        assert_eq!(check("ui.paginate", "never"), Ok(()));
+        assert_eq!(check("ui.diff.format", "git"), Ok(()));
+        assert_eq!(check("snapshot.max-new-file-size", "1024"), Ok(()));
+        assert_eq!(check("snapshot.max-new-file-size", "1MiB"), Ok(()));
+        assert_eq!(check("colors.error", "red"), Ok(()));
+        assert_eq!(check("colors.error", "#ff0000"), Ok(()));
+        assert_eq!(check("colors.error", "{ fg = 'red', bold = true }"), Ok(()));
+        assert_eq!(check("aliases.l", "['log', '-r', 'all()']"), Ok(()));
+        assert_eq!(check("git.fetch", "['origin', 'upstream']"), Ok(()));
+        assert_eq!(check("git.fetch", "origin"), Ok(()));
+        // Unknown keys are accepted
+        assert_eq!(check("unknown.key", "[1, 'two']"), Ok(()));
+        assert_eq!(check("ui.unknown", "true"), Ok(()));
+
+        insta::assert_debug_snapshot!(check("ui.paginate", ":builtin"), @r#"
+        Err(
+            ConfigSchemaError {
+                name: "ui.paginate",
+                expected: "one of \"never\", \"auto\"",
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(check("ui.log-word-wrap", "yes"), @r#"
+        Err(
+            ConfigSchemaError {
+                name: "ui.log-word-wrap",
+                expected: "a boolean",
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(check("ui.paginate.foo", "never"), @r#"
+        Err(
+            ConfigSchemaError {
+                name: "ui.paginate",
+                expected: "one of \"never\", \"auto\"",
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(check("ui", "never"), @r#"
+        Err(
+            ConfigSchemaError {
+                name: "ui",
+                expected: "a table",
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(check("colors.error", "{ fg = 'red', bold = 1 }"), @r#"
+        Err(
+            ConfigSchemaError {
+                name: "colors.error.bold",
+                expected: "a boolean",
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(check("aliases.l", "[1]"), @r#"
+        Err(
+            ConfigSchemaError {
+                name: "aliases.l",
+                expected: "an array whose items are a string",
+            },
+        )
+        "#);
+    }
+
+    #[test]
+    fn test_known_config_values() {
+        let values = |name: &str| {
+            known_config_values(&name.parse().unwrap())
+                .iter()
+                .map(|value| value.to_string())
+                .join(" ")
+        };
+        insta::assert_snapshot!(values("ui.paginate"), @r#""never" "auto""#);
+        insta::assert_snapshot!(values("ui.log-word-wrap"), @"true false");
+        insta::assert_snapshot!(values("ui.color"), @r#""always" "never" "debug" "auto""#);
+        insta::assert_snapshot!(values("colors.error"), @r#""default" "black" "red" "green" "yellow" "blue" "magenta" "cyan" "white" "bright black" "bright red" "bright green" "bright yellow" "bright blue" "bright magenta" "bright cyan" "bright white""#);
+        insta::assert_snapshot!(values("ui.diff.tool"), @"");
+        insta::assert_snapshot!(values("ui"), @"");
+        insta::assert_snapshot!(values("unknown.key"), @"");
+    }
+
+    #[test]
+    fn test_check_config_table() {
+        let check = |text: &str| {
+            let doc: toml_edit::DocumentMut = text.parse().unwrap();
+            check_config_table(doc.as_table())
+        };
+        assert_eq!(check(""), Ok(()));
+        assert_eq!(
+            check(indoc::indoc! {"
+                [ui]
+                paginate = 'never'
+                [[unknown]]
+                key = 1
+            "}),
+            Ok(())
+        );
+        insta::assert_debug_snapshot!(check("ui.color = 1"), @r#"
+        Err(
+            ConfigSchemaError {
+                name: "ui.color",
+                expected: "one of \"always\", \"never\", \"debug\", \"auto\"",
+            },
+        )
+        "#);
+    }
+}
@@ -0,0 +1,359 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recorded resolutions of file conflicts.
+//!
+//! When a conflicted commit is rewritten to resolve a file conflict without
+//! changing its parents, the resolved content is stored keyed by a hash of the
+//! conflicting file contents. If the same conflict shows up again, for example
+//! when rebasing a branch onto an updated upstream again, the recorded
+//! resolution can be reused instead of resolving it by hand.
+//!
+//! Each resolution is stored in a file named by the key. The file starts with
+//! the path of the conflicted file followed by a newline, and the rest is the
+//! resolved content.
+
+use std::fs;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str;
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+use jj_lib::backend::BackendError;
+use jj_lib::backend::BackendResult;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
+use jj_lib::content_hash::blake2b_hash;
+use jj_lib::dag_walk;
+use jj_lib::merge::Merge;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
+use jj_lib::repo::MutableRepo;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetIteratorExt as _;
+use jj_lib::settings::UserSettings;
+use jj_lib::store::Store;
+use pollster::FutureExt as _;
+use tempfile::NamedTempFile;
+
+use crate::command_error::internal_error_with_message;
+use crate::command_error::CommandError;
+
+/// Resolution recorded for a conflict.
+#[derive(Clone, Debug)]
+pub struct RecordedResolution {
+    /// Hash of the conflicting file contents.
+    pub key: String,
+    /// Path of the file the conflict was resolved in.
+    pub path: RepoPathBuf,
+    /// Resolved content.
+    pub content: Vec<u8>,
+}
+
+/// Store of conflict resolutions recorded in a repo.
+#[derive(Clone, Debug)]
+pub struct ConflictResolutions {
+    dir: PathBuf,
+}
+
+impl ConflictResolutions {
+    /// Creates store in the given repo directory.
+    pub fn new(repo_path: &Path) -> Self {
+        ConflictResolutions {
+            dir: repo_path.join("conflict_resolutions"),
+        }
+    }
+
+    /// Records that the `conflict` at `path` was resolved to `resolved`.
+    ///
+    /// Only resolutions to a regular file are recorded.
+    pub fn record(
+        &self,
+        store: &Store,
+        path: &RepoPath,
+        conflict: &MergedTreeValue,
+        resolved: &MergedTreeValue,
+    ) -> Result<(), CommandError> {
+        let Some(key) = conflict_key(conflict) else {
+            return Ok(());
+        };
+        let Some(Some(TreeValue::File { id, .. })) = resolved.as_resolved() else {
+            return Ok(());
+        };
+        let mut content = path.as_internal_file_string().as_bytes().to_vec();
+        content.push(b'\n');
+        store
+            .read_file(path, id)?
+            .read_to_end(&mut content)
+            .map_err(|err| BackendError::ReadFile {
+                path: path.to_owned(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        let write_error = |err| {
+            internal_error_with_message(
+                format!(
+                    "Failed to record resolution of conflict in {}",
+                    path.as_internal_file_string()
+                ),
+                err,
+            )
+        };
+        fs::create_dir_all(&self.dir).map_err(write_error)?;
+        // Write to a temporary file first so concurrent commands never see a
+        // partially written resolution.
+        let mut temp_file = NamedTempFile::new_in(&self.dir).map_err(write_error)?;
+        temp_file.write_all(&content).map_err(write_error)?;
+        temp_file
+            .persist(self.dir.join(key))
+            .map_err(|err| write_error(err.error))?;
+        Ok(())
+    }
+
+    /// Resolves the conflicts in `tree` which have a recorded resolution.
+    ///
+    /// Returns the new tree and the number of resolved paths, or `None` if no
+    /// recorded resolution applied.
+    pub fn resolve_tree(
+        &self,
+        store: &Arc<Store>,
+        tree: &MergedTree,
+    ) -> BackendResult<Option<(MergedTree, usize)>> {
+        let mut tree_builder = MergedTreeBuilder::new(tree.id());
+        let mut num_resolved = 0;
+        for (path, value) in tree.conflicts() {
+            let value = value?;
+            let Some(key) = conflict_key(&value) else {
+                continue;
+            };
+            let Some(resolution) = self.read(&key) else {
+                continue;
+            };
+            let executable = value
+                .to_executable_merge()
+                .and_then(|merge| merge.resolve_trivial().copied())
+                .unwrap_or(false);
+            let id = store
+                .write_file(&path, &mut resolution.content.as_slice())
+                .block_on()?;
+            tree_builder.set_or_remove(path, Merge::normal(TreeValue::File { id, executable }));
+            num_resolved += 1;
+        }
+        if num_resolved == 0 {
+            return Ok(None);
+        }
+        let tree_id = tree_builder.write_tree(store)?;
+        Ok(Some((store.get_root_tree(&tree_id)?, num_resolved)))
+    }
+
+    /// Records the conflicts resolved by the commits written in `repo` since
+    /// `base_repo`.
+    ///
+    /// A commit is considered to resolve a conflict if it has the same parents
+    /// as its conflicted predecessor. If the parents changed, the conflict may
+    /// have gone away because of the new parents.
+    pub fn record_commits(
+        &self,
+        repo: &MutableRepo,
+        base_repo: &ReadonlyRepo,
+    ) -> Result<(), CommandError> {
+        let store = repo.store();
+        for commit in new_visible_commits(repo, base_repo)? {
+            for predecessor in commit.predecessors() {
+                let predecessor = predecessor?;
+                if predecessor.parent_ids() != commit.parent_ids() || !predecessor.has_conflict()? {
+                    continue;
+                }
+                let old_tree = predecessor.tree()?;
+                let new_tree = commit.tree()?;
+                for (path, conflict) in old_tree.conflicts() {
+                    self.record(store, &path, &conflict?, &new_tree.path_value(&path)?)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the recorded resolutions to the conflicted commits written in
+    /// `repo` since `base_repo`.
+    ///
+    /// Returns the number of resolved paths and commits.
+    pub fn apply_to_commits(
+        &self,
+        settings: &UserSettings,
+        repo: &mut MutableRepo,
+        base_repo: &ReadonlyRepo,
+    ) -> BackendResult<(usize, usize)> {
+        let commits = new_visible_commits(repo, base_repo)?;
+        self.apply_to(settings, repo, &commits)
+    }
+
+    /// Applies the recorded resolutions to the conflicted commits that are
+    /// visible in `repo` but weren't in `base_repo`. Unlike
+    /// [`Self::apply_to_commits()`], this includes commits that existed before,
+    /// such as the ones brought back by undoing an operation.
+    ///
+    /// Returns the number of resolved paths and commits.
+    pub fn apply_to_revived_commits(
+        &self,
+        settings: &UserSettings,
+        repo: &mut MutableRepo,
+        base_repo: &ReadonlyRepo,
+    ) -> BackendResult<(usize, usize)> {
+        let commits = revived_commits(repo, base_repo)?;
+        self.apply_to(settings, repo, &commits)
+    }
+
+    /// Applies the recorded resolutions to the `commits`, which should be in
+    /// reverse topological order.
+    fn apply_to(
+        &self,
+        settings: &UserSettings,
+        repo: &mut MutableRepo,
+        commits: &[Commit],
+    ) -> BackendResult<(usize, usize)> {
+        let store = repo.store().clone();
+        let mut num_resolved_paths = 0;
+        let mut num_resolved_commits = 0;
+        // Resolve parents first so the resolutions propagate to their
+        // descendants.
+        for commit in commits.iter().rev() {
+            // The commit may have been rebased onto a resolved parent.
+            let commit_ids = repo
+                .resolve_change_id(commit.change_id())
+                .unwrap_or_default();
+            let [commit_id] = commit_ids.as_slice() else {
+                continue;
+            };
+            let commit = store.get_commit(commit_id)?;
+            if !commit.has_conflict()? {
+                continue;
+            }
+            let Some((new_tree, num_resolved)) = self.resolve_tree(&store, &commit.tree()?)? else {
+                continue;
+            };
+            repo.rewrite_commit(settings, &commit)
+                .set_tree_id(new_tree.id())
+                .write()?;
+            repo.rebase_descendants(settings)?;
+            num_resolved_paths += num_resolved;
+            num_resolved_commits += 1;
+        }
+        Ok((num_resolved_paths, num_resolved_commits))
+    }
+
+    /// Returns all recorded resolutions sorted by path.
+    pub fn list(&self) -> Vec<RecordedResolution> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return vec![];
+        };
+        let mut resolutions = entries
+            .filter_map(|entry| {
+                let key = entry.ok()?.file_name().into_string().ok()?;
+                self.read(&key)
+            })
+            .collect::<Vec<_>>();
+        resolutions.sort_by(|a, b| (&a.path, &a.key).cmp(&(&b.path, &b.key)));
+        resolutions
+    }
+
+    /// Removes the resolution recorded with the given key.
+    pub fn forget(&self, key: &str) -> std::io::Result<()> {
+        fs::remove_file(self.dir.join(key))
+    }
+
+    fn read(&self, key: &str) -> Option<RecordedResolution> {
+        let data = fs::read(self.dir.join(key)).ok()?;
+        let newline = data.iter().position(|&b| b == b'\n')?;
+        let path = str::from_utf8(&data[..newline]).ok()?;
+        let path = RepoPathBuf::from_relative_path(path).ok()?;
+        Some(RecordedResolution {
+            key: key.to_owned(),
+            path,
+            content: data[newline + 1..].to_vec(),
+        })
+    }
+}
+
+/// Returns the visible commits written in `repo` since `base_repo`, in reverse
+/// topological order.
+///
+/// Only the commits missing from the base index are walked, so this is cheap
+/// if the transaction didn't rewrite many commits.
+fn new_visible_commits(repo: &MutableRepo, base_repo: &ReadonlyRepo) -> BackendResult<Vec<Commit>> {
+    let store = repo.store();
+    let base_index = base_repo.index();
+    let new_heads = repo
+        .view()
+        .heads()
+        .iter()
+        .filter(|id| !base_index.has_id(id))
+        .map(|id| store.get_commit(id))
+        .collect_vec();
+    dag_walk::topo_order_reverse_ok(
+        new_heads,
+        |commit: &Commit| commit.id().clone(),
+        |commit: &Commit| {
+            commit
+                .parent_ids()
+                .iter()
+                .filter(|id| !base_index.has_id(id))
+                .map(|id| store.get_commit(id))
+                .collect_vec()
+        },
+    )
+}
+
+/// Returns the commits visible in `repo` but not in `base_repo`, in reverse
+/// topological order.
+///
+/// This has to walk the history of both repos, so it's only used by commands
+/// that make old commits visible again, which are rare.
+fn revived_commits(repo: &MutableRepo, base_repo: &ReadonlyRepo) -> BackendResult<Vec<Commit>> {
+    let base_heads = base_repo.view().heads().iter().cloned().collect();
+    let heads = repo.view().heads().iter().cloned().collect();
+    RevsetExpression::commits(heads)
+        .ancestors()
+        .minus(&RevsetExpression::commits(base_heads).ancestors())
+        .evaluate(repo)
+        .map_err(|err| err.expect_backend_error())?
+        .iter()
+        .commits(repo.store())
+        .try_collect()
+        .map_err(|err| err.expect_backend_error())
+}
+
+/// Hashes the contents of the sides of a file conflict. Since file ids are
+/// content hashes, the same conflict produces the same key wherever it
+/// appears.
+fn conflict_key(conflict: &MergedTreeValue) -> Option<String> {
+    let file_merge = conflict.to_file_merge()?.simplify();
+    if file_merge.is_resolved() {
+        return None;
+    }
+    Some(
+        blake2b_hash(&file_merge)[..16]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect(),
+    )
+}
@@ -1,20 +1,30 @@
 use std::collections::HashMap;
 use std::io::Write as _;
 use std::path::Path;
+use std::process::Stdio;
 
 use bstr::ByteVec as _;
 use indexmap::IndexMap;
 use indoc::indoc;
 use itertools::Itertools;
+use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
+use jj_lib::commit_builder::DetachedCommitBuilder;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::settings::UserSettings;
+use regex::Regex;
 use thiserror::Error;
 
 use crate::cli_util::edit_temp_file;
 use crate::cli_util::short_commit_hash;
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::config_error_with_message;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
+use crate::config::CommandNameAndArgs;
 use crate::formatter::PlainTextFormatter;
 use crate::text_util;
 use crate::ui::Ui;
@@ -224,6 +234,226 @@ pub fn join_message_paragraphs(paragraphs: &[String]) -> String {
         .join("\n")
 }
 
+/// Prefix of Gerrit Change-Ids derived from change IDs. It's the hex encoding
+/// of "jjid", which pads the 32 hex digits of a change ID to the 40 digits
+/// Gerrit expects.
+const GERRIT_CHANGE_ID_PREFIX: &str = "I6a6a6964";
+
+/// Returns the Gerrit Change-Id derived from the given change ID.
+pub fn gerrit_change_id(change_id: &ChangeId) -> String {
+    format!("{GERRIT_CHANGE_ID_PREFIX}{}", change_id.hex())
+}
+
+/// Adds a Gerrit `Change-Id` trailer derived from the change ID to the
+/// description if `gerrit.enabled` is set.
+///
+/// Empty descriptions and descriptions which already have a Change-Id trailer
+/// are left unchanged, except that a trailer derived from another change ID
+/// (e.g. copied from the commit being split) is replaced.
+pub fn add_gerrit_change_id_trailer(
+    settings: &UserSettings,
+    description: String,
+    change_id: &ChangeId,
+) -> Result<String, CommandError> {
+    if settings.get_bool("gerrit.enabled")? {
+        Ok(with_change_id_trailer(
+            &description,
+            &gerrit_change_id(change_id),
+        ))
+    } else {
+        Ok(description)
+    }
+}
+
+/// Appends the trailers rendered by the `commit.trailers` template against the
+/// `commit` to the description.
+///
+/// Each line of the template output is a trailer. Empty descriptions and
+/// trailers which are already in the description are left unchanged.
+pub fn add_trailers(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    commit: &Commit,
+    description: String,
+) -> Result<String, CommandError> {
+    let Some(template_text) = tx.settings().get_string("commit.trailers").optional()? else {
+        return Ok(description);
+    };
+    let trailers = render_commit_template(ui, tx, &template_text, commit)?;
+    let trailers = trailers
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect_vec();
+    Ok(with_trailers(&description, &trailers))
+}
+
+fn with_trailers(description: &str, trailers: &[&str]) -> String {
+    if description.trim().is_empty() || trailers.is_empty() {
+        return description.to_owned();
+    }
+    let mut lines = description.trim_end_matches('\n').lines().collect_vec();
+    match find_trailers_start(&lines) {
+        Some(start) => {
+            let existing = lines[start..].to_vec();
+            lines.extend(
+                trailers
+                    .iter()
+                    .filter(|trailer| !existing.contains(trailer)),
+            );
+        }
+        None => {
+            lines.push("");
+            lines.extend(trailers);
+        }
+    }
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Returns the index of the first line of the trailers paragraph.
+fn find_trailers_start(lines: &[&str]) -> Option<usize> {
+    // Like Git, the trailers are the last paragraph, which can't be the subject.
+    lines
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map(|pos| pos + 1)
+        .filter(|&start| lines[start..].iter().all(|line| is_trailer_line(line)))
+}
+
+fn with_change_id_trailer(description: &str, change_id: &str) -> String {
+    if description.trim().is_empty() {
+        return description.to_owned();
+    }
+    let mut lines = description.trim_end_matches('\n').lines().collect_vec();
+    let trailers_start = find_trailers_start(&lines);
+    let expected_line = format!("Change-Id: {change_id}");
+    if let Some(start) = trailers_start {
+        let mut has_change_id = false;
+        let mut index = start;
+        while index < lines.len() {
+            match lines[index].strip_prefix("Change-Id:").map(str::trim) {
+                Some(value) if value == change_id => has_change_id = true,
+                Some(value) if value.starts_with(GERRIT_CHANGE_ID_PREFIX) => {
+                    lines.remove(index);
+                    continue;
+                }
+                Some(_) => has_change_id = true,
+                None => {}
+            }
+            index += 1;
+        }
+        if !has_change_id {
+            lines.push(&expected_line);
+        }
+    } else {
+        lines.push("");
+        lines.push(&expected_line);
+    }
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+fn is_trailer_line(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(key, _)| {
+        !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// Checks the description against the `commit.verify.pattern` regex and the
+/// `commit.verify.command`, which receives the description on stdin.
+///
+/// Empty descriptions are always accepted so descriptions can be cleared.
+pub fn verify_description(settings: &UserSettings, description: &str) -> Result<(), CommandError> {
+    if description.trim().is_empty() {
+        return Ok(());
+    }
+    let hint = "Use --no-verify to skip the check.";
+    if let Some(pattern) = settings.get_string("commit.verify.pattern").optional()? {
+        let regex = Regex::new(&pattern)
+            .map_err(|err| config_error_with_message("Invalid commit.verify.pattern", err))?;
+        if !regex.is_match(description) {
+            return Err(user_error_with_hint(
+                format!("The description doesn't match the pattern '{pattern}'"),
+                hint,
+            ));
+        }
+    }
+    if let Some(verifier) = settings
+        .get::<CommandNameAndArgs>("commit.verify.command")
+        .optional()?
+    {
+        let mut cmd = verifier.to_command();
+        // Messages from the verifier are diagnostics, not command output.
+        cmd.stdin(Stdio::piped()).stdout(std::io::stderr());
+        tracing::info!(?cmd, "running description verifier");
+        let run_error = |err| {
+            user_error_with_message(
+                format!(
+                    "Failed to run description verifier '{name}'",
+                    name = verifier.split_name(),
+                ),
+                err,
+            )
+        };
+        let mut child = cmd.spawn().map_err(run_error)?;
+        // The verifier may exit without reading the whole description.
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(description.as_bytes())
+            .ok();
+        let exit_status = child.wait().map_err(run_error)?;
+        if !exit_status.success() {
+            return Err(user_error_with_hint(
+                format!(
+                    "The description was rejected by '{name}'",
+                    name = verifier.split_name(),
+                ),
+                hint,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Sets the description of a commit without description to the one the editor
+/// will be populated with.
+///
+/// If `commit.description-template` is set, it's rendered against the commit.
+/// Otherwise, `ui.default-description` is used.
+pub fn set_default_description(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    commit_builder: &mut DetachedCommitBuilder,
+) -> Result<(), CommandError> {
+    if !commit_builder.description().is_empty() {
+        return Ok(());
+    }
+    let template_key = "commit.description-template";
+    let Some(template_text) = tx.settings().get_string(template_key).optional()? else {
+        commit_builder.set_description(tx.settings().default_description());
+        return Ok(());
+    };
+    let temp_commit = commit_builder.write_hidden()?;
+    let description = render_commit_template(ui, tx, &template_text, &temp_commit)?;
+    commit_builder.set_description(description);
+    Ok(())
+}
+
+fn render_commit_template(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    template_text: &str,
+    commit: &Commit,
+) -> Result<String, CommandError> {
+    let template = tx.parse_commit_template(ui, template_text)?;
+    let mut output = Vec::new();
+    template
+        .format(commit, &mut PlainTextFormatter::new(&mut output))
+        .expect("write() to vec backed formatter should never fail");
+    Ok(output.into_string_lossy())
+}
+
 /// Renders commit description template, which will be edited by user.
 pub fn description_template(
     ui: &Ui,
@@ -260,6 +490,8 @@ mod tests {
     use maplit::hashmap;
 
     use super::parse_bulk_edit_message;
+    use super::with_change_id_trailer;
+    use super::with_trailers;
     use crate::description_util::ParseBulkEditMessageError;
 
     #[test]
@@ -412,4 +644,134 @@ mod tests {
         assert!(result.duplicates.is_empty());
         assert!(result.unexpected.is_empty());
     }
+
+    #[test]
+    fn test_with_change_id_trailer() {
+        let change_id = "I6a6a6964aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+        assert_eq!(with_change_id_trailer("", change_id), "");
+        assert_eq!(
+            with_change_id_trailer("subject\n", change_id),
+            indoc! {"
+                subject
+
+                Change-Id: I6a6a6964aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+            "}
+        );
+        // A subject line is never a trailer
+        assert_eq!(
+            with_change_id_trailer("fix: subject", change_id),
+            indoc! {"
+                fix: subject
+
+                Change-Id: I6a6a6964aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+            "}
+        );
+        // Appended to existing trailers
+        assert_eq!(
+            with_change_id_trailer(
+                indoc! {"
+                    subject
+
+                    body text
+
+                    Signed-off-by: Some One <someone@example.com>
+                "},
+                change_id
+            ),
+            indoc! {"
+                subject
+
+                body text
+
+                Signed-off-by: Some One <someone@example.com>
+                Change-Id: I6a6a6964aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+            "}
+        );
+        // Unchanged if there's already the same or a foreign Change-Id
+        let description = indoc! {"
+            subject
+
+            Change-Id: I6a6a6964aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+        "};
+        assert_eq!(with_change_id_trailer(description, change_id), description);
+        let description = indoc! {"
+            subject
+
+            Change-Id: I0123456789abcdef0123456789abcdef01234567
+        "};
+        assert_eq!(with_change_id_trailer(description, change_id), description);
+        // A Change-Id derived from another change is replaced
+        assert_eq!(
+            with_change_id_trailer(
+                indoc! {"
+                    subject
+
+                    Change-Id: I6a6a6964bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb
+                    Reviewed-by: Some One <someone@example.com>
+                "},
+                change_id
+            ),
+            indoc! {"
+                subject
+
+                Reviewed-by: Some One <someone@example.com>
+                Change-Id: I6a6a6964aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+            "}
+        );
+    }
+
+    #[test]
+    fn test_with_trailers() {
+        let signed_off = "Signed-off-by: Some One <someone@example.com>";
+        assert_eq!(with_trailers("", &[signed_off]), "");
+        assert_eq!(with_trailers("subject\n", &[]), "subject\n");
+        assert_eq!(
+            with_trailers("subject\n", &[signed_off, "Co-authored-by: Other"]),
+            indoc! {"
+                subject
+
+                Signed-off-by: Some One <someone@example.com>
+                Co-authored-by: Other
+            "}
+        );
+        // Existing trailers aren't duplicated
+        assert_eq!(
+            with_trailers(
+                indoc! {"
+                    subject
+
+                    Signed-off-by: Some One <someone@example.com>
+                "},
+                &[signed_off, "Co-authored-by: Other"]
+            ),
+            indoc! {"
+                subject
+
+                Signed-off-by: Some One <someone@example.com>
+                Co-authored-by: Other
+            "}
+        );
+        // A trailer-like line in the body isn't an existing trailer
+        assert_eq!(
+            with_trailers(
+                indoc! {"
+                    subject
+
+                    Signed-off-by: Some One <someone@example.com>
+
+                    body text
+                "},
+                &[signed_off]
+            ),
+            indoc! {"
+                subject
+
+                Signed-off-by: Some One <someone@example.com>
+
+                body text
+
+                Signed-off-by: Some One <someone@example.com>
+            "}
+        );
+    }
 }
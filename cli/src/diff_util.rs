@@ -42,6 +42,7 @@ use jj_lib::conflicts::MaterializedTreeValue;
 use jj_lib::copies::CopiesTreeDiffEntry;
 use jj_lib::copies::CopyOperation;
 use jj_lib::copies::CopyRecords;
+use jj_lib::default_index::DiffStatCounts;
 use jj_lib::diff::find_line_ranges;
 use jj_lib::diff::CompareBytesExactly;
 use jj_lib::diff::CompareBytesIgnoreAllWhitespace;
@@ -78,6 +79,9 @@ use crate::merge_tools::new_utf8_temp_dir;
 use crate::merge_tools::DiffGenerateError;
 use crate::merge_tools::DiffToolMode;
 use crate::merge_tools::ExternalMergeTool;
+use crate::syntax_highlight::write_highlighted;
+use crate::syntax_highlight::HighlightedLines;
+use crate::syntax_highlight::SyntaxSpan;
 use crate::text_util;
 use crate::ui::Ui;
 
@@ -293,6 +297,7 @@ impl<'a> DiffRenderer<'a> {
 
     /// Generates diff between `from_tree` and `to_tree`.
     #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
     pub fn show_diff(
         &self,
         ui: &Ui, // TODO: remove Ui dependency if possible
@@ -542,6 +547,8 @@ pub struct ColorWordsDiffOptions {
     pub line_diff: LineDiffOptions,
     /// Maximum number of removed/added word alternation to inline.
     pub max_inline_alternation: Option<usize>,
+    /// Whether to highlight syntax of file contents.
+    pub syntax_highlight: bool,
 }
 
 impl ColorWordsDiffOptions {
@@ -567,6 +574,7 @@ impl ColorWordsDiffOptions {
             context,
             line_diff: LineDiffOptions::from_args(args),
             max_inline_alternation,
+            syntax_highlight: settings.get_bool("ui.syntax-highlight")?,
         })
     }
 }
@@ -575,6 +583,7 @@ fn show_color_words_diff_hunks(
     formatter: &mut dyn Formatter,
     left: &[u8],
     right: &[u8],
+    highlights: &[HighlightedLines; 2],
     options: &ColorWordsDiffOptions,
 ) -> io::Result<()> {
     let line_diff = diff_by_line([left, right], &options.line_diff);
@@ -594,14 +603,20 @@ fn show_color_words_diff_hunks(
                     formatter,
                     &contexts,
                     line_number,
+                    highlights,
                     options,
                     num_after,
                     options.context,
                 )?;
                 contexts.clear();
                 emitted = true;
-                line_number =
-                    show_color_words_diff_lines(formatter, &hunk.contents, line_number, options)?;
+                line_number = show_color_words_diff_lines(
+                    formatter,
+                    &hunk.contents,
+                    line_number,
+                    highlights,
+                    options,
+                )?;
             }
         }
     }
@@ -611,6 +626,7 @@ fn show_color_words_diff_hunks(
             formatter,
             &contexts,
             line_number,
+            highlights,
             options,
             options.context,
             0,
@@ -624,6 +640,7 @@ fn show_color_words_context_lines(
     formatter: &mut dyn Formatter,
     contexts: &[DiffHunkContentVec],
     mut line_number: DiffLineNumber,
+    highlights: &[HighlightedLines; 2],
     options: &ColorWordsDiffOptions,
     num_after: usize,
     num_before: usize,
@@ -653,6 +670,7 @@ fn show_color_words_context_lines(
                 show_color_words_inline_hunks(
                     formatter,
                     &[(DiffLineHunkSide::Both, line.as_ref())],
+                    line_spans(highlights, line_number),
                 )?;
                 line_number.left += 1;
                 line_number.right += 1;
@@ -665,6 +683,7 @@ fn show_color_words_context_lines(
                 formatter,
                 &[BStr::new(&left), BStr::new(&right)],
                 line_number,
+                highlights,
                 options,
             )
         }
@@ -696,6 +715,7 @@ fn show_color_words_diff_lines(
     formatter: &mut dyn Formatter,
     contents: &[&BStr],
     mut line_number: DiffLineNumber,
+    highlights: &[HighlightedLines; 2],
     options: &ColorWordsDiffOptions,
 ) -> io::Result<DiffLineNumber> {
     let word_diff_hunks = Diff::by_word(contents).hunks().collect_vec();
@@ -720,19 +740,25 @@ fn show_color_words_diff_lines(
                     .has_right_content()
                     .then_some(diff_line.line_number.right),
             )?;
-            show_color_words_inline_hunks(formatter, &diff_line.hunks)?;
+            show_color_words_inline_hunks(
+                formatter,
+                &diff_line.hunks,
+                line_spans(highlights, diff_line.line_number),
+            )?;
         }
         line_number = diff_line_iter.next_line_number();
     } else {
         let (left_lines, right_lines) = unzip_diff_hunks_to_lines(&word_diff_hunks);
         for tokens in &left_lines {
             show_color_words_line_number(formatter, Some(line_number.left), None)?;
-            show_color_words_single_sided_line(formatter, tokens, "removed")?;
+            let [spans, _] = line_spans(highlights, line_number);
+            show_color_words_single_sided_line(formatter, tokens, "removed", spans)?;
             line_number.left += 1;
         }
         for tokens in &right_lines {
             show_color_words_line_number(formatter, None, Some(line_number.right))?;
-            show_color_words_single_sided_line(formatter, tokens, "added")?;
+            let [_, spans] = line_spans(highlights, line_number);
+            show_color_words_single_sided_line(formatter, tokens, "added", spans)?;
             line_number.right += 1;
         }
     }
@@ -764,22 +790,36 @@ fn show_color_words_line_number(
 }
 
 /// Prints line hunks which may contain tokens originating from both sides.
+///
+/// The left and right syntax highlighting `spans` are applied to the hunks
+/// depending on their side.
 fn show_color_words_inline_hunks(
     formatter: &mut dyn Formatter,
     line_hunks: &[(DiffLineHunkSide, &BStr)],
+    [left_spans, right_spans]: [&[SyntaxSpan]; 2],
 ) -> io::Result<()> {
+    let mut left_offset = 0;
+    let mut right_offset = 0;
     for (side, data) in line_hunks {
-        let label = match side {
-            DiffLineHunkSide::Both => None,
-            DiffLineHunkSide::Left => Some("removed"),
-            DiffLineHunkSide::Right => Some("added"),
+        let (label, spans, offset) = match side {
+            DiffLineHunkSide::Both => (None, right_spans, right_offset),
+            DiffLineHunkSide::Left => (Some("removed"), left_spans, left_offset),
+            DiffLineHunkSide::Right => (Some("added"), right_spans, right_offset),
         };
-        if let Some(label) = label {
-            formatter.with_label(label, |formatter| {
-                formatter.with_label("token", |formatter| formatter.write_all(data))
-            })?;
-        } else {
-            formatter.write_all(data)?;
+        write_highlighted(formatter, spans, offset, data, |formatter, text| {
+            if let Some(label) = label {
+                formatter.with_label(label, |formatter| {
+                    formatter.with_label("token", |formatter| formatter.write_all(text))
+                })
+            } else {
+                formatter.write_all(text)
+            }
+        })?;
+        if *side != DiffLineHunkSide::Right {
+            left_offset += data.len();
+        }
+        if *side != DiffLineHunkSide::Left {
+            right_offset += data.len();
         }
     }
     let (_, data) = line_hunks.last().expect("diff line must not be empty");
@@ -794,8 +834,9 @@ fn show_color_words_single_sided_line(
     formatter: &mut dyn Formatter,
     tokens: &[(DiffTokenType, &[u8])],
     label: &str,
+    spans: &[SyntaxSpan],
 ) -> io::Result<()> {
-    formatter.with_label(label, |formatter| show_diff_line_tokens(formatter, tokens))?;
+    show_diff_line_tokens(formatter, tokens, label, spans)?;
     let (_, data) = tokens.last().expect("diff line must not be empty");
     if !data.ends_with(b"\n") {
         writeln!(formatter)?;
@@ -803,6 +844,19 @@ fn show_color_words_single_sided_line(
     Ok(())
 }
 
+/// Returns the left and right syntax highlighting spans of the lines at
+/// 1-based `line_number`.
+fn line_spans(
+    highlights: &[HighlightedLines; 2],
+    line_number: DiffLineNumber,
+) -> [&[SyntaxSpan]; 2] {
+    let index = |number: u32| usize::try_from(number).unwrap() - 1;
+    [
+        highlights[0].line(index(line_number.left)),
+        highlights[1].line(index(line_number.right)),
+    ]
+}
+
 /// Counts number of diff-side alternation, ignoring matching hunks.
 ///
 /// This function is meant to measure visual complexity of diff hunks. It's easy
@@ -987,7 +1041,18 @@ pub fn show_color_words_diff(
                 } else if right_content.is_binary {
                     writeln!(formatter.labeled("binary"), "    (binary)")?;
                 } else {
-                    show_color_words_diff_hunks(formatter, &[], &right_content.contents, options)?;
+                    let highlights = highlight_file_contents(
+                        options.syntax_highlight,
+                        [left_path, right_path],
+                        [&[], &right_content.contents],
+                    );
+                    show_color_words_diff_hunks(
+                        formatter,
+                        &[],
+                        &right_content.contents,
+                        &highlights,
+                        options,
+                    )?;
                 }
             } else if right_value.is_present() {
                 let description = match (&left_value, &right_value) {
@@ -1059,10 +1124,16 @@ pub fn show_color_words_diff(
                 if left_content.is_binary || right_content.is_binary {
                     writeln!(formatter.labeled("binary"), "    (binary)")?;
                 } else {
+                    let highlights = highlight_file_contents(
+                        options.syntax_highlight,
+                        [left_path, right_path],
+                        [&left_content.contents, &right_content.contents],
+                    );
                     show_color_words_diff_hunks(
                         formatter,
                         &left_content.contents,
                         &right_content.contents,
+                        &highlights,
                         options,
                     )?;
                 }
@@ -1078,7 +1149,18 @@ pub fn show_color_words_diff(
                 } else if left_content.is_binary {
                     writeln!(formatter.labeled("binary"), "    (binary)")?;
                 } else {
-                    show_color_words_diff_hunks(formatter, &left_content.contents, &[], options)?;
+                    let highlights = highlight_file_contents(
+                        options.syntax_highlight,
+                        [left_path, right_path],
+                        [&left_content.contents, &[]],
+                    );
+                    show_color_words_diff_hunks(
+                        formatter,
+                        &left_content.contents,
+                        &[],
+                        &highlights,
+                        options,
+                    )?;
                 }
             }
         }
@@ -1251,6 +1333,8 @@ pub struct UnifiedDiffOptions {
     pub context: usize,
     /// How lines are tokenized and compared.
     pub line_diff: LineDiffOptions,
+    /// Whether to highlight syntax of file contents.
+    pub syntax_highlight: bool,
 }
 
 impl UnifiedDiffOptions {
@@ -1264,6 +1348,7 @@ impl UnifiedDiffOptions {
         Ok(UnifiedDiffOptions {
             context,
             line_diff: LineDiffOptions::from_args(args),
+            syntax_highlight: settings.get_bool("ui.syntax-highlight")?,
         })
     }
 }
@@ -1436,6 +1521,7 @@ fn show_unified_diff_hunks(
     formatter: &mut dyn Formatter,
     left_content: &[u8],
     right_content: &[u8],
+    highlights: &[HighlightedLines; 2],
     options: &UnifiedDiffOptions,
 ) -> io::Result<()> {
     // "If the chunk size is 0, the first number is one lower than one would
@@ -1462,16 +1548,23 @@ fn show_unified_diff_hunks(
             to_line_number(hunk.right_line_range.clone()),
             hunk.right_line_range.len()
         )?;
+        let mut left_index = hunk.left_line_range.start;
+        let mut right_index = hunk.right_line_range.start;
         for (line_type, tokens) in &hunk.lines {
-            let (label, sigil) = match line_type {
-                DiffLineType::Context => ("context", " "),
-                DiffLineType::Removed => ("removed", "-"),
-                DiffLineType::Added => ("added", "+"),
+            // Context lines are taken from the right side.
+            let (label, sigil, spans) = match line_type {
+                DiffLineType::Context => ("context", " ", highlights[1].line(right_index)),
+                DiffLineType::Removed => ("removed", "-", highlights[0].line(left_index)),
+                DiffLineType::Added => ("added", "+", highlights[1].line(right_index)),
             };
-            formatter.with_label(label, |formatter| {
-                write!(formatter, "{sigil}")?;
-                show_diff_line_tokens(formatter, tokens)
-            })?;
+            if *line_type != DiffLineType::Added {
+                left_index += 1;
+            }
+            if *line_type != DiffLineType::Removed {
+                right_index += 1;
+            }
+            write!(formatter.labeled(label), "{sigil}")?;
+            show_diff_line_tokens(formatter, tokens, label, spans)?;
             let (_, content) = tokens.last().expect("hunk line must not be empty");
             if !content.ends_with(b"\n") {
                 write!(formatter, "\n\\ No newline at end of file\n")?;
@@ -1481,21 +1574,45 @@ fn show_unified_diff_hunks(
     Ok(())
 }
 
+/// Prints line tokens with the given label. Syntax highlighting `spans` are
+/// applied outside of the label so the diff style takes precedence.
 fn show_diff_line_tokens(
     formatter: &mut dyn Formatter,
     tokens: &[(DiffTokenType, &[u8])],
+    label: &str,
+    spans: &[SyntaxSpan],
 ) -> io::Result<()> {
+    let mut offset = 0;
     for (token_type, content) in tokens {
-        match token_type {
-            DiffTokenType::Matching => formatter.write_all(content)?,
-            DiffTokenType::Different => {
-                formatter.with_label("token", |formatter| formatter.write_all(content))?;
-            }
-        }
+        write_highlighted(formatter, spans, offset, content, |formatter, text| {
+            formatter.with_label(label, |formatter| match token_type {
+                DiffTokenType::Matching => formatter.write_all(text),
+                DiffTokenType::Different => {
+                    formatter.with_label("token", |formatter| formatter.write_all(text))
+                }
+            })
+        })?;
+        offset += content.len();
     }
     Ok(())
 }
 
+/// Highlights the left and right file contents if enabled.
+fn highlight_file_contents(
+    enabled: bool,
+    [left_path, right_path]: [&RepoPath; 2],
+    [left_content, right_content]: [&[u8]; 2],
+) -> [HighlightedLines; 2] {
+    if enabled {
+        [
+            HighlightedLines::new(left_path, left_content),
+            HighlightedLines::new(right_path, right_content),
+        ]
+    } else {
+        Default::default()
+    }
+}
+
 pub fn show_git_diff(
     formatter: &mut dyn Formatter,
     store: &Store,
@@ -1580,10 +1697,16 @@ pub fn show_git_diff(
                     writeln!(formatter, "+++ {right_path}")?;
                     io::Result::Ok(())
                 })?;
+                let highlights = highlight_file_contents(
+                    options.syntax_highlight,
+                    [path.source(), path.target()],
+                    [&left_part.content.contents, &right_part.content.contents],
+                );
                 show_unified_diff_hunks(
                     formatter,
                     &left_part.content.contents,
                     &right_part.content.contents,
+                    &highlights,
                     options,
                 )?;
             }
@@ -1680,20 +1803,43 @@ fn get_diff_stat(
     }
 }
 
-pub fn show_diff_stat(
-    formatter: &mut dyn Formatter,
+/// Computes the total number of changed files and lines.
+pub fn get_diff_stat_counts(
     store: &Store,
     tree_diff: BoxStream<CopiesTreeDiffEntry>,
     path_converter: &RepoPathUiConverter,
     options: &DiffStatOptions,
-    display_width: usize,
     conflict_marker_style: ConflictMarkerStyle,
-) -> Result<(), DiffRenderError> {
+) -> Result<DiffStatCounts, DiffRenderError> {
+    let (stats, unresolved_renames) = collect_diff_stats(
+        store,
+        tree_diff,
+        path_converter,
+        options,
+        conflict_marker_style,
+    )?;
+    let mut counts = DiffStatCounts::default();
+    for stat in &stats {
+        if stat.is_deletion && unresolved_renames.contains(&stat.path) {
+            continue;
+        }
+        counts.files += 1;
+        counts.added += stat.added as u64;
+        counts.removed += stat.removed as u64;
+    }
+    Ok(counts)
+}
+
+/// Returns stats per file, and the source paths of the renames.
+fn collect_diff_stats(
+    store: &Store,
+    tree_diff: BoxStream<CopiesTreeDiffEntry>,
+    path_converter: &RepoPathUiConverter,
+    options: &DiffStatOptions,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<(Vec<DiffStat>, HashSet<String>), DiffRenderError> {
     let mut stats: Vec<DiffStat> = vec![];
     let mut unresolved_renames = HashSet::new();
-    let mut max_path_width = 0;
-    let mut max_diffs = 0;
-
     let mut diff_stream = materialized_diff_stream(store, tree_diff);
     async {
         while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
@@ -1710,14 +1856,40 @@ pub fn show_diff_stat(
                 unresolved_renames.insert(left_ui_path);
                 path_converter.format_copied_path(left_path, right_path)
             };
-            max_path_width = max(max_path_width, path.width());
-            let stat = get_diff_stat(path, &left_content, &right_content, options);
-            max_diffs = max(max_diffs, stat.added + stat.removed);
-            stats.push(stat);
+            stats.push(get_diff_stat(path, &left_content, &right_content, options));
         }
         Ok::<(), DiffRenderError>(())
     }
     .block_on()?;
+    Ok((stats, unresolved_renames))
+}
+
+pub fn show_diff_stat(
+    formatter: &mut dyn Formatter,
+    store: &Store,
+    tree_diff: BoxStream<CopiesTreeDiffEntry>,
+    path_converter: &RepoPathUiConverter,
+    options: &DiffStatOptions,
+    display_width: usize,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<(), DiffRenderError> {
+    let (stats, unresolved_renames) = collect_diff_stats(
+        store,
+        tree_diff,
+        path_converter,
+        options,
+        conflict_marker_style,
+    )?;
+    let max_path_width = stats
+        .iter()
+        .map(|stat| stat.path.width())
+        .max()
+        .unwrap_or(0);
+    let max_diffs = stats
+        .iter()
+        .map(|stat| stat.added + stat.removed)
+        .max()
+        .unwrap_or(0);
 
     let number_padding = max_diffs.to_string().len();
     // 4 characters padding for the graph
@@ -0,0 +1,393 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration with code forges such as GitHub and GitLab.
+//!
+//! Forges are accessed through their command-line tools, which take care of
+//! the API details and of the authentication if no token is configured.
+
+use std::io;
+use std::process::Command;
+use std::process::ExitStatus;
+use std::process::Stdio;
+
+use jj_lib::config::ConfigGetError;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::settings::UserSettings;
+use thiserror::Error;
+
+/// Pull request (or merge request) on a forge.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    /// Name of the branch to be merged.
+    pub head: String,
+    /// Name of the branch to merge into.
+    pub base: String,
+}
+
+/// Parameters of a pull request to create.
+#[derive(Clone, Debug)]
+pub struct NewPullRequest<'a> {
+    pub head: &'a str,
+    /// Defaults to the repository's default branch.
+    pub base: Option<&'a str>,
+    pub title: &'a str,
+    pub body: &'a str,
+    pub draft: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum ForgeError {
+    #[error("{program} failed with {exit_status}:\n{stderr}")]
+    Command {
+        program: String,
+        exit_status: ExitStatus,
+        stderr: String,
+    },
+    #[error("Failed to run {program}")]
+    Io {
+        program: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Unexpected output from {program}: {message}")]
+    Output { program: String, message: String },
+}
+
+/// Interface to the pull requests of a repository on a forge.
+pub trait Forge {
+    /// Name of the forge, e.g. "GitHub".
+    fn name(&self) -> &str;
+
+    /// Lists the open pull requests.
+    fn list_pull_requests(&self) -> Result<Vec<PullRequest>, ForgeError>;
+
+    /// Creates a pull request from a branch that has been pushed already.
+    fn create_pull_request(&self, new: &NewPullRequest) -> Result<PullRequest, ForgeError>;
+
+    /// Git ref on the remote which points to the head of the pull request.
+    fn pull_request_ref(&self, number: u64) -> String;
+}
+
+/// Creates the forge for the remote with the given URL.
+///
+/// The kind of forge is taken from the `forge.kind` setting, or guessed from
+/// the host name of the URL. Returns `None` if the kind is unknown.
+pub fn forge_from_settings(
+    settings: &UserSettings,
+    remote_url: &str,
+) -> Result<Option<Box<dyn Forge>>, ConfigGetError> {
+    let repo = parse_remote_url(remote_url);
+    let kind = match settings.get_string("forge.kind").optional()? {
+        Some(kind) => kind,
+        None => match &repo {
+            Some((host, _)) if host.contains("github") => "github".to_owned(),
+            Some((host, _)) if host.contains("gitlab") => "gitlab".to_owned(),
+            _ => return Ok(None),
+        },
+    };
+    let repo = repo.map(|(host, path)| format!("{host}/{path}"));
+    let forge: Box<dyn Forge> = match kind.as_str() {
+        "github" => Box::new(CliForge::from_settings(settings, &GITHUB, repo)?),
+        "gitlab" => Box::new(CliForge::from_settings(settings, &GITLAB, repo)?),
+        _ => return Ok(None),
+    };
+    Ok(Some(forge))
+}
+
+/// Splits the URL of a Git remote into the host name and the repository path.
+///
+/// Returns `None` for local paths.
+fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    let (host, path) = if let Some((_scheme, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        let host = host.split_once(':').map_or(host, |(host, _port)| host);
+        (host, path)
+    } else {
+        // scp-like syntax: [user@]host:path
+        let (authority, path) = url.split_once(':')?;
+        if authority.contains('/') || authority.len() <= 1 {
+            // Local path, or a Windows drive letter
+            return None;
+        }
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        (host, path)
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some((host.to_owned(), path.to_owned()))
+}
+
+/// Command-line interface of a forge tool.
+struct ForgeCli {
+    name: &'static str,
+    config_name: &'static str,
+    default_program: &'static str,
+    token_env: &'static str,
+    repo_flag: &'static str,
+    list_args: &'static [&'static str],
+    /// Flags selecting the page and the page size, if the tool returns one
+    /// page of pull requests at a time.
+    page_flags: Option<(&'static str, &'static str)>,
+    head_flag: &'static str,
+    base_flag: &'static str,
+    title_flag: &'static str,
+    body_flag: &'static str,
+    draft_flag: &'static str,
+    create_args: &'static [&'static str],
+    /// JSON keys of the pull request number, title, URL, head, and base.
+    json_keys: [&'static str; 5],
+    ref_template: (&'static str, &'static str),
+}
+
+const GITHUB: ForgeCli = ForgeCli {
+    name: "GitHub",
+    config_name: "github",
+    default_program: "gh",
+    token_env: "GH_TOKEN",
+    repo_flag: "--repo",
+    list_args: &[
+        "pr",
+        "list",
+        "--state=open",
+        "--limit=1000",
+        "--json=number,title,url,headRefName,baseRefName",
+    ],
+    page_flags: None,
+    head_flag: "--head",
+    base_flag: "--base",
+    title_flag: "--title",
+    body_flag: "--body",
+    draft_flag: "--draft",
+    create_args: &["pr", "create"],
+    json_keys: ["number", "title", "url", "headRefName", "baseRefName"],
+    ref_template: ("refs/pull/", "/head"),
+};
+
+const GITLAB: ForgeCli = ForgeCli {
+    name: "GitLab",
+    config_name: "gitlab",
+    default_program: "glab",
+    token_env: "GITLAB_TOKEN",
+    repo_flag: "--repo",
+    list_args: &["mr", "list", "--output=json"],
+    page_flags: Some(("--page", "--per-page")),
+    head_flag: "--source-branch",
+    base_flag: "--target-branch",
+    title_flag: "--title",
+    body_flag: "--description",
+    draft_flag: "--draft",
+    create_args: &["mr", "create", "--yes"],
+    json_keys: ["iid", "title", "web_url", "source_branch", "target_branch"],
+    ref_template: ("refs/merge-requests/", "/head"),
+};
+
+/// Number of pull requests requested per page from paginating tools.
+const PAGE_SIZE: usize = 100;
+
+/// Forge accessed by running its command-line tool.
+struct CliForge {
+    cli: &'static ForgeCli,
+    program: String,
+    token: Option<String>,
+    repo: Option<String>,
+}
+
+impl CliForge {
+    fn from_settings(
+        settings: &UserSettings,
+        cli: &'static ForgeCli,
+        repo: Option<String>,
+    ) -> Result<Self, ConfigGetError> {
+        let program = settings
+            .get_string(["forge", cli.config_name, "program"])
+            .optional()?
+            .unwrap_or_else(|| cli.default_program.to_owned());
+        let token = settings
+            .get_string(["forge", cli.config_name, "token"])
+            .optional()?;
+        Ok(CliForge {
+            cli,
+            program,
+            token,
+            repo,
+        })
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String, ForgeError> {
+        let mut command = Command::new(&self.program);
+        command.args(args);
+        if let Some(repo) = &self.repo {
+            command.args([self.cli.repo_flag, repo]);
+        }
+        if let Some(token) = &self.token {
+            command.env(self.cli.token_env, token);
+        }
+        command.stdin(Stdio::null()).stderr(Stdio::piped());
+        tracing::info!(?command, "running forge command");
+        let output = command.output().map_err(|source| ForgeError::Io {
+            program: self.program.clone(),
+            source,
+        })?;
+        tracing::info!(?command, ?output.status, "forge command exited");
+        if !output.status.success() {
+            return Err(ForgeError::Command {
+                program: self.program.clone(),
+                exit_status: output.status,
+                stderr: String::from_utf8_lossy(&output.stderr).trim_end().into(),
+            });
+        }
+        String::from_utf8(output.stdout).map_err(|_| self.output_error("invalid UTF-8"))
+    }
+
+    fn output_error(&self, message: impl Into<String>) -> ForgeError {
+        ForgeError::Output {
+            program: self.program.clone(),
+            message: message.into(),
+        }
+    }
+
+    fn parse_pull_requests(&self, output: &str) -> Result<Vec<PullRequest>, ForgeError> {
+        let value: serde_json::Value =
+            serde_json::from_str(output).map_err(|err| self.output_error(err.to_string()))?;
+        let items = value
+            .as_array()
+            .ok_or_else(|| self.output_error("expected a list"))?;
+        items
+            .iter()
+            .map(|item| self.parse_pull_request(item))
+            .collect()
+    }
+
+    fn parse_pull_request(&self, value: &serde_json::Value) -> Result<PullRequest, ForgeError> {
+        let [number_key, title_key, url_key, head_key, base_key] = self.cli.json_keys;
+        let get_str = |key: &str| {
+            value[key]
+                .as_str()
+                .map(ToOwned::to_owned)
+                .ok_or_else(|| self.output_error(format!("missing field {key}")))
+        };
+        Ok(PullRequest {
+            number: value[number_key]
+                .as_u64()
+                .ok_or_else(|| self.output_error(format!("missing field {number_key}")))?,
+            title: get_str(title_key)?,
+            url: get_str(url_key)?,
+            head: get_str(head_key)?,
+            base: get_str(base_key)?,
+        })
+    }
+}
+
+impl Forge for CliForge {
+    fn name(&self) -> &str {
+        self.cli.name
+    }
+
+    fn list_pull_requests(&self) -> Result<Vec<PullRequest>, ForgeError> {
+        let Some((page_flag, page_size_flag)) = self.cli.page_flags else {
+            let output = self.run(self.cli.list_args)?;
+            return self.parse_pull_requests(&output);
+        };
+        let mut pull_requests = vec![];
+        for page in 1.. {
+            let page_arg = format!("{page_flag}={page}");
+            let page_size_arg = format!("{page_size_flag}={PAGE_SIZE}");
+            let mut args = self.cli.list_args.to_vec();
+            args.extend([page_arg.as_str(), page_size_arg.as_str()]);
+            let output = self.run(&args)?;
+            let page_pull_requests = self.parse_pull_requests(&output)?;
+            let is_last_page = page_pull_requests.len() < PAGE_SIZE;
+            pull_requests.extend(page_pull_requests);
+            if is_last_page {
+                break;
+            }
+        }
+        Ok(pull_requests)
+    }
+
+    fn create_pull_request(&self, new: &NewPullRequest) -> Result<PullRequest, ForgeError> {
+        let cli = self.cli;
+        let mut args = cli.create_args.to_vec();
+        args.extend([cli.head_flag, new.head]);
+        if let Some(base) = new.base {
+            args.extend([cli.base_flag, base]);
+        }
+        args.extend([cli.title_flag, new.title, cli.body_flag, new.body]);
+        if new.draft {
+            args.push(cli.draft_flag);
+        }
+        let output = self.run(&args)?;
+        // The tools print the URL of the new pull request last.
+        let url = output
+            .split_whitespace()
+            .rfind(|word| word.starts_with("http"))
+            .ok_or_else(|| self.output_error("no pull request URL"))?;
+        let number = url
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(|| self.output_error(format!("unexpected URL {url}")))?;
+        Ok(PullRequest {
+            number,
+            title: new.title.to_owned(),
+            url: url.to_owned(),
+            head: new.head.to_owned(),
+            base: new.base.unwrap_or_default().to_owned(),
+        })
+    }
+
+    fn pull_request_ref(&self, number: u64) -> String {
+        let (prefix, suffix) = self.cli.ref_template;
+        format!("{prefix}{number}{suffix}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_url() {
+        let parse = |url| parse_remote_url(url);
+        let expected = Some(("github.com".to_owned(), "owner/repo".to_owned()));
+        assert_eq!(parse("https://github.com/owner/repo.git"), expected);
+        assert_eq!(parse("https://user@github.com/owner/repo/"), expected);
+        assert_eq!(parse("ssh://git@github.com:22/owner/repo"), expected);
+        assert_eq!(parse("git@github.com:owner/repo.git"), expected);
+        assert_eq!(
+            parse("https://gitlab.example.com/group/subgroup/repo"),
+            Some((
+                "gitlab.example.com".to_owned(),
+                "group/subgroup/repo".to_owned()
+            ))
+        );
+        assert_eq!(parse("/path/to/repo"), None);
+        assert_eq!(parse("../repo"), None);
+        assert_eq!(parse(r"C:\path\to\repo"), None);
+        assert_eq!(parse("file:///path/to/repo"), None);
+    }
+}
@@ -15,32 +15,44 @@
 //! Git utilities shared by various commands.
 
 use std::error;
+use std::io;
 use std::io::Read;
 use std::io::Write;
 use std::iter;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::thread;
 use std::time::Instant;
 
 use itertools::Itertools;
+use jj_lib::commit::Commit;
 use jj_lib::git;
 use jj_lib::git::FailedRefExport;
 use jj_lib::git::FailedRefExportReason;
 use jj_lib::git::GitFetchError;
+use jj_lib::git::GitFileLockError;
 use jj_lib::git::GitImportStats;
+use jj_lib::git::GitPushError;
 use jj_lib::git::RefName;
+use jj_lib::git::RefSpec;
 use jj_lib::git_backend::GitBackend;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPathUiConverter;
+use jj_lib::settings::GitSettings;
 use jj_lib::store::Store;
 use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 use unicode_width::UnicodeWidthStr;
 
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::internal_error;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
@@ -66,6 +78,50 @@ pub fn map_git_error(err: git2::Error) -> CommandError {
     }
 }
 
+pub fn map_git_file_lock_error(err: GitFileLockError) -> CommandError {
+    match err {
+        GitFileLockError::Push(GitPushError::RefInUnexpectedLocation(_)) => user_error_with_hint(
+            "The lock was changed on the remote by someone else",
+            "Run `jj git fetch` to update the locks, then try again.",
+        ),
+        GitFileLockError::Push(GitPushError::InternalGitError(err))
+        | GitFileLockError::InternalGitError(err) => map_git_error(err),
+        GitFileLockError::Push(err) => user_error(err),
+    }
+}
+
+/// Warns about files changed between `old_tree` and `new_tree` that are
+/// locked by users other than `user_email`.
+pub fn print_locked_file_edits(
+    ui: &Ui,
+    git_backend: &GitBackend,
+    old_tree: &MergedTree,
+    new_tree: &MergedTree,
+    user_email: &str,
+    path_converter: &RepoPathUiConverter,
+) -> Result<(), CommandError> {
+    // Most repos have no locks, so avoid opening the repo with libgit2.
+    if !git::has_file_locks(&git_backend.git_repo()).map_err(internal_error)? {
+        return Ok(());
+    }
+    let git_repo = git_backend.open_git_repo()?;
+    for lock in git::get_file_locks(&git_repo)? {
+        if lock.owner_email == user_email
+            || old_tree.path_value(&lock.path)? == new_tree.path_value(&lock.path)?
+        {
+            continue;
+        }
+        writeln!(
+            ui.warning_default(),
+            "{path} is locked by {name} <{email}>",
+            path = path_converter.format_file_path(&lock.path),
+            name = lock.owner_name,
+            email = lock.owner_email,
+        )?;
+    }
+    Ok(())
+}
+
 pub fn get_git_repo(store: &Store) -> Result<git2::Repository, CommandError> {
     match store.backend_impl().downcast_ref::<GitBackend>() {
         None => Err(user_error("The repo is not backed by a git repo")),
@@ -91,6 +147,25 @@ pub fn is_colocated_git_workspace(workspace: &Workspace, repo: &ReadonlyRepo) ->
     git_workdir.canonicalize().ok().as_deref() == dot_git_path.parent()
 }
 
+/// Asks for a secret with the `git.askpass` program, which is called with the
+/// prompt as its argument and prints the answer to stdout like Git's
+/// `core.askpass`.
+fn askpass_get(program: &str, prompt: &str) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .arg(prompt)
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .inspect_err(|err| tracing::info!(?err, program, "failed to run askpass"))
+        .ok()?;
+    if !output.status.success() {
+        tracing::info!(status = ?output.status, program, "askpass failed");
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Some(stdout.lines().next().unwrap_or_default().to_owned())
+}
+
 fn terminal_get_username(ui: &Ui, url: &str) -> Option<String> {
     ui.prompt(&format!("Username for {url}")).ok()
 }
@@ -151,7 +226,7 @@ fn pinentry_get_pw(url: &str) -> Option<String> {
 }
 
 #[tracing::instrument]
-fn get_ssh_keys(_username: &str) -> Vec<PathBuf> {
+fn get_default_ssh_keys() -> Vec<PathBuf> {
     let mut paths = vec![];
     if let Some(home_dir) = dirs::home_dir() {
         let ssh_dir = Path::new(&home_dir).join(".ssh");
@@ -252,14 +327,22 @@ impl GitSidebandProgressMessageWriter {
     }
 }
 
+/// Serializes credential prompts of transfers running concurrently.
+static PROMPT_LOCK: Mutex<()> = Mutex::new(());
+
+type ProgressCallback<'a> = &'a mut dyn FnMut(&git::Progress);
 type SidebandProgressCallback<'a> = &'a mut dyn FnMut(&[u8]);
 
+/// Calls `f` with callbacks reporting progress and asking for credentials to
+/// access the given remote (or the default remote if `None`) as configured by
+/// the `git_settings`.
 pub fn with_remote_git_callbacks<T>(
     ui: &Ui,
     sideband_progress_callback: Option<SidebandProgressCallback<'_>>,
+    git_settings: &GitSettings,
+    remote_name: Option<&str>,
     f: impl FnOnce(git::RemoteCallbacks<'_>) -> T,
 ) -> T {
-    let mut callbacks = git::RemoteCallbacks::default();
     let mut progress_callback = None;
     if let Some(mut output) = ui.progress_output() {
         let mut progress = Progress::new(Instant::now());
@@ -267,21 +350,100 @@ pub fn with_remote_git_callbacks<T>(
             _ = progress.update(Instant::now(), x, &mut output);
         });
     }
-    callbacks.progress = progress_callback
-        .as_mut()
-        .map(|x| x as &mut dyn FnMut(&git::Progress));
+    with_git_callbacks(
+        ui,
+        progress_callback
+            .as_mut()
+            .map(|x| x as &mut dyn FnMut(&git::Progress)),
+        sideband_progress_callback,
+        git_settings,
+        remote_name,
+        f,
+    )
+}
+
+/// Like [`with_remote_git_callbacks()`], but reports the transfer progress to
+/// `progress_callback` instead of drawing it.
+fn with_git_callbacks<T>(
+    ui: &Ui,
+    progress_callback: Option<ProgressCallback<'_>>,
+    sideband_progress_callback: Option<SidebandProgressCallback<'_>>,
+    git_settings: &GitSettings,
+    remote_name: Option<&str>,
+    f: impl FnOnce(git::RemoteCallbacks<'_>) -> T,
+) -> T {
+    let mut callbacks = git::RemoteCallbacks::default();
+    callbacks.progress = progress_callback.map(|x| x as &mut dyn FnMut(&git::Progress));
     callbacks.sideband_progress = sideband_progress_callback.map(|x| x as &mut dyn FnMut(&[u8]));
-    let mut get_ssh_keys = get_ssh_keys; // Coerce to unit fn type
+    let ssh_keys = git_settings.ssh_keys_for(remote_name);
+    let mut get_ssh_keys = |_username: &str| {
+        if ssh_keys.is_empty() {
+            get_default_ssh_keys()
+        } else {
+            ssh_keys.to_vec()
+        }
+    };
     callbacks.get_ssh_keys = Some(&mut get_ssh_keys);
-    let mut get_pw =
-        |url: &str, _username: &str| pinentry_get_pw(url).or_else(|| terminal_get_pw(ui, url));
-    callbacks.get_password = Some(&mut get_pw);
-    let mut get_user_pw =
-        |url: &str| Some((terminal_get_username(ui, url)?, terminal_get_pw(ui, url)?));
+    let askpass = git_settings.askpass.as_deref();
+    let get_username = |url: &str| {
+        let _lock = PROMPT_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        match askpass {
+            Some(program) => askpass_get(program, &format!("Username for {url}: ")),
+            None => terminal_get_username(ui, url),
+        }
+    };
+    let get_pw = |url: &str| {
+        let _lock = PROMPT_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        match askpass {
+            Some(program) => askpass_get(program, &format!("Passphrase for {url}: ")),
+            None => pinentry_get_pw(url).or_else(|| terminal_get_pw(ui, url)),
+        }
+    };
+    let mut get_pw_for_user = |url: &str, _username: &str| get_pw(url);
+    callbacks.get_password = Some(&mut get_pw_for_user);
+    let mut get_user_pw = |url: &str| Some((get_username(url)?, get_pw(url)?));
     callbacks.get_username_password = Some(&mut get_user_pw);
+    callbacks.skip_credential_helper = !git_settings.credential_helper;
+    callbacks.skip_ssh_agent = !git_settings.ssh_agent;
+    callbacks.proxy = git_settings.proxy.as_deref();
+    callbacks.no_proxy = &git_settings.no_proxy;
     f(callbacks)
 }
 
+/// Configures libgit2 to load the CA certificates from `git.ssl-ca-info`.
+///
+/// This changes process-global libgit2 state, so it must be called at startup
+/// before libgit2 is used.
+pub fn set_up_ssl_ca_info(ui: &Ui, git_settings: &GitSettings) -> io::Result<()> {
+    if let Some(path) = &git_settings.ssl_ca_info {
+        // SAFETY: This is called once at startup, before any other thread is
+        // spawned or any libgit2 operation is started.
+        if let Err(err) = unsafe { git2::opts::set_ssl_cert_file(path) } {
+            writeln!(
+                ui.warning_default(),
+                "Failed to load CA certificates from {}: {err}",
+                path.display()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+pub fn print_imported_stashes(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    commits: &[Commit],
+) -> Result<(), CommandError> {
+    if let Some(mut formatter) = ui.status_formatter() {
+        for commit in commits {
+            write!(formatter, "Imported Git stash as ")?;
+            tx.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    Ok(())
+}
+
 pub fn print_git_import_stats(
     ui: &Ui,
     repo: &dyn Repo,
@@ -462,41 +624,58 @@ pub fn git_fetch(
     git_repo: &git2::Repository,
     remotes: &[String],
     branch: &[StringPattern],
+    prune: Option<bool>,
+    refspecs: &[RefSpec],
 ) -> Result<(), CommandError> {
-    let git_settings = tx.settings().git_settings();
-
-    for remote in remotes {
-        let stats = with_remote_git_callbacks(ui, None, |cb| {
-            git::fetch(
-                tx.repo_mut(),
-                git_repo,
-                remote,
-                branch,
-                cb,
-                &git_settings,
-                None,
-            )
-        })
-        .map_err(|err| match err {
-            GitFetchError::InvalidBranchPattern => {
-                if branch
-                    .iter()
-                    .any(|pattern| pattern.as_exact().is_some_and(|s| s.contains('*')))
+    let mut git_settings = tx.settings().git_settings();
+    if let Some(prune) = prune {
+        git_settings.fetch_prune = prune;
+        git_settings.remote_fetch_prune.clear();
+    }
+    if !refspecs.is_empty() {
+        let refspecs = refspecs.iter().map(RefSpec::to_string).collect_vec();
+        git_settings.remote_fetch_refspecs = remotes
+            .iter()
+            .map(|remote| (remote.clone(), refspecs.clone()))
+            .collect();
+    }
+
+    let downloads = download_remotes(ui, git_repo, remotes, branch, &git_settings);
+    let map_fetch_error = |err: GitFetchError| match err {
+        GitFetchError::InvalidBranchPattern => {
+            if branch
+                .iter()
+                .any(|pattern| pattern.as_exact().is_some_and(|s| s.contains('*')))
+            {
+                user_error_with_hint(
+                    "Branch names may not include `*`.",
+                    "Prefix the pattern with `glob:` to expand `*` as a glob",
+                )
+            } else {
+                user_error(err)
+            }
+        }
+        GitFetchError::GitImportError(err) => err.into(),
+        GitFetchError::InternalGitError(err) => map_git_error(err),
+        _ => user_error(err),
+    };
+    let mut pruned_bookmarks = vec![];
+    for download in downloads {
+        let download = download.map_err(map_fetch_error)?;
+        let import_stats = git::import_download(tx.repo_mut(), &git_settings, &download)?;
+        print_git_import_stats(ui, tx.repo(), &import_stats, true)?;
+        pruned_bookmarks.extend(import_stats.changed_remote_refs.iter().filter_map(
+            |(ref_name, (old_remote_ref, new_target))| match ref_name {
+                RefName::RemoteBranch { branch, .. }
+                    if old_remote_ref.is_tracking() && new_target.is_absent() =>
                 {
-                    user_error_with_hint(
-                        "Branch names may not include `*`.",
-                        "Prefix the pattern with `glob:` to expand `*` as a glob",
-                    )
-                } else {
-                    user_error(err)
+                    Some(branch.clone())
                 }
-            }
-            GitFetchError::GitImportError(err) => err.into(),
-            GitFetchError::InternalGitError(err) => map_git_error(err),
-            _ => user_error(err),
-        })?;
-        print_git_import_stats(ui, tx.repo(), &stats.import_stats, true)?;
+                _ => None,
+            },
+        ));
     }
+    print_untracking_bookmarks(ui, tx.repo(), &pruned_bookmarks)?;
     warn_if_branches_not_found(
         ui,
         tx,
@@ -505,6 +684,95 @@ pub fn git_fetch(
     )
 }
 
+/// Downloads the branches from the `remotes` without importing them.
+///
+/// Multiple remotes are fetched from concurrently, with a progress bar for
+/// each. The results are in the same order as the `remotes`.
+fn download_remotes(
+    ui: &Ui,
+    git_repo: &git2::Repository,
+    remotes: &[String],
+    branch: &[StringPattern],
+    git_settings: &GitSettings,
+) -> Vec<Result<git::GitDownload, GitFetchError>> {
+    if let [remote] = remotes {
+        let download = with_remote_git_callbacks(ui, None, git_settings, Some(remote), |cb| {
+            git::download(git_repo, remote, branch, cb, git_settings, None)
+        });
+        return vec![download];
+    }
+    // git2::Repository can't be shared between threads, so each thread opens
+    // its own.
+    let git_repo_path = git_repo.path();
+    let region = ui.progress_region();
+    thread::scope(|scope| {
+        let handles = remotes
+            .iter()
+            .map(|remote| {
+                let bar = region.add_bar(remote);
+                scope.spawn(move || -> Result<_, GitFetchError> {
+                    let git_repo = git2::Repository::open(git_repo_path)?;
+                    let mut progress_callback =
+                        |progress: &git::Progress| bar.update(Instant::now(), progress);
+                    let download = with_git_callbacks(
+                        ui,
+                        Some(&mut progress_callback),
+                        None,
+                        git_settings,
+                        Some(remote),
+                        |cb| git::download(&git_repo, remote, branch, cb, git_settings, None),
+                    )?;
+                    bar.finish(format!("Fetched from {remote}"));
+                    Ok(download)
+                })
+            })
+            .collect_vec();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("fetch thread panicked"))
+            .collect()
+    })
+}
+
+/// Prints the local bookmarks which no longer track any remote bookmark
+/// because the remote bookmarks they tracked were deleted.
+fn print_untracking_bookmarks(
+    ui: &Ui,
+    repo: &dyn Repo,
+    pruned_bookmarks: &[String],
+) -> Result<(), CommandError> {
+    let view = repo.view();
+    let untracking_bookmarks = pruned_bookmarks
+        .iter()
+        .sorted()
+        .dedup()
+        .filter(|name| view.get_local_bookmark(name).is_present())
+        .filter(|name| {
+            !view
+                .all_remote_bookmarks()
+                .any(|((other_name, remote), remote_ref)| {
+                    other_name == name.as_str()
+                        && remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                        && remote_ref.is_tracking()
+                })
+        })
+        .collect_vec();
+    if untracking_bookmarks.is_empty() {
+        return Ok(());
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "These local bookmarks no longer track any remote bookmark:"
+        )?;
+        for name in untracking_bookmarks {
+            write!(formatter, "  ")?;
+            writeln!(formatter.labeled("bookmark"), "{name}")?;
+        }
+    }
+    Ok(())
+}
+
 fn warn_if_branches_not_found(
     ui: &mut Ui,
     tx: &WorkspaceCommandTransaction,
@@ -535,3 +803,16 @@ fn warn_if_branches_not_found(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_askpass_get() {
+        assert_eq!(askpass_get("echo", "secret").as_deref(), Some("secret"));
+        assert_eq!(askpass_get("false", "Passphrase: "), None);
+        assert_eq!(askpass_get("/nonexistent/askpass", "Passphrase: "), None);
+    }
+}
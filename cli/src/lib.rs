@@ -14,6 +14,7 @@
 
 #![deny(unused_must_use)]
 
+pub mod archive;
 pub mod cleanup_guard;
 pub mod cli_util;
 pub mod command_error;
@@ -21,8 +22,12 @@ pub mod commands;
 pub mod commit_templater;
 pub mod complete;
 pub mod config;
+pub mod config_schema;
+pub mod conflict_resolutions;
 pub mod description_util;
 pub mod diff_util;
+#[cfg(feature = "forge")]
+pub mod forge;
 pub mod formatter;
 pub mod generic_templater;
 pub mod git_util;
@@ -30,11 +35,16 @@ pub mod graphlog;
 pub mod merge_tools;
 pub mod movement_util;
 pub mod operation_templater;
+pub mod patch_util;
+pub mod profiling;
 mod progress;
+pub mod revset_cache;
 pub mod revset_util;
+pub mod syntax_highlight;
 pub mod template_builder;
 pub mod template_parser;
 pub mod templater;
 pub mod text_util;
 pub mod time_util;
 pub mod ui;
+pub mod workspace_roots;
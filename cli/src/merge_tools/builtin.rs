@@ -22,6 +22,7 @@ use jj_lib::files;
 use jj_lib::files::MergeResult;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Merge;
+use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::merged_tree::TreeDiffEntry;
@@ -395,6 +396,19 @@ pub fn make_diff_files(
                 sections.extend(make_diff_sections(&old_contents, &new_contents)?);
             }
 
+            // Only the executable bit of the binary file changed, which is
+            // represented by the mode section above.
+            (
+                FileContents::Binary {
+                    hash: Some(old_hash),
+                    num_bytes: _,
+                },
+                FileContents::Binary {
+                    hash: Some(new_hash),
+                    num_bytes: _,
+                },
+            ) if old_hash == new_hash => {}
+
             (
                 FileContents::Text {
                     contents: _,
@@ -462,6 +476,13 @@ pub fn apply_diff_builtin(
     // TODO: Write files concurrently
     for (path, file) in changed_files.into_iter().zip(files) {
         let (selected, _unselected) = file.get_selected_contents();
+        // The executable bit may be selected independently of the contents.
+        let mode_changed = file.get_file_mode() != file.file_mode;
+        let selected_executable = match file.get_file_mode() {
+            Some(scm_record::FileMode(mode::NORMAL)) => Some(false),
+            Some(scm_record::FileMode(mode::EXECUTABLE)) => Some(true),
+            _ => None,
+        };
         match selected {
             scm_record::SelectedContents::Absent => {
                 // TODO(https://github.com/arxanas/scm-record/issues/26): This
@@ -489,17 +510,29 @@ pub fn apply_diff_builtin(
                     tree_builder.set_or_remove(path, value);
                 } else if file_deleted {
                     tree_builder.set_or_remove(path, Merge::absent());
+                } else if let Some(executable) = selected_executable.filter(|_| mode_changed) {
+                    // The file is empty, or its contents weren't selected,
+                    // but the mode change was.
+                    let value = left_tree.path_value(&path)?;
+                    tree_builder.set_or_remove(path, with_executable(value, executable));
                 }
                 // Else: the file is empty and nothing changed.
             }
             scm_record::SelectedContents::Unchanged => {
-                // Do nothing.
+                if let Some(executable) = selected_executable.filter(|_| mode_changed) {
+                    let value = left_tree.path_value(&path)?;
+                    tree_builder.set_or_remove(path, with_executable(value, executable));
+                }
             }
             scm_record::SelectedContents::Binary {
                 old_description: _,
                 new_description: _,
             } => {
                 let value = right_tree.path_value(&path)?;
+                let value = match selected_executable {
+                    Some(executable) => with_executable(value, executable),
+                    None => value,
+                };
                 tree_builder.set_or_remove(path, value);
             }
             scm_record::SelectedContents::Present { contents } => {
@@ -522,6 +555,17 @@ pub fn apply_diff_builtin(
     Ok(tree_id)
 }
 
+/// Replaces the executable bit of the file terms in the `value`.
+fn with_executable(value: MergedTreeValue, executable: bool) -> MergedTreeValue {
+    value.map(|term| match term {
+        Some(TreeValue::File { id, executable: _ }) => Some(TreeValue::File {
+            id: id.clone(),
+            executable,
+        }),
+        term => term.clone(),
+    })
+}
+
 pub fn edit_diff_builtin(
     left_tree: &MergedTree,
     right_tree: &MergedTree,
@@ -669,7 +713,6 @@ pub fn edit_merge_builtin(
 #[cfg(test)]
 mod tests {
     use jj_lib::conflicts::extract_as_single_hunk;
-    use jj_lib::merge::MergedTreeValue;
     use jj_lib::repo::Repo;
     use testutils::TestRepo;
 
@@ -905,6 +948,177 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_edit_diff_builtin_executable_bit_only() {
+        let test_repo = TestRepo::init();
+        let store = test_repo.repo.store();
+
+        let text_path = RepoPath::from_internal_string("text");
+        let empty_path = RepoPath::from_internal_string("empty");
+        let binary_path = RepoPath::from_internal_string("binary");
+        let create_tree = |executable: bool| {
+            let mut tree_builder = store.tree_builder(store.empty_tree_id().clone());
+            for (path, contents) in [(text_path, "text\n"), (empty_path, ""), (binary_path, "\0")] {
+                if executable {
+                    testutils::write_executable_file(&mut tree_builder, path, contents);
+                } else {
+                    testutils::write_normal_file(&mut tree_builder, path, contents);
+                }
+            }
+            let id = tree_builder.write_tree().unwrap();
+            MergedTree::resolved(store.get_tree(RepoPathBuf::root(), &id).unwrap())
+        };
+        let left_tree = create_tree(false);
+        let right_tree = create_tree(true);
+
+        let changed_files = vec![
+            text_path.to_owned(),
+            empty_path.to_owned(),
+            binary_path.to_owned(),
+        ];
+        let files = make_diff_files(
+            store,
+            &left_tree,
+            &right_tree,
+            &changed_files,
+            ConflictMarkerStyle::Diff,
+        )
+        .unwrap();
+        insta::assert_debug_snapshot!(files, @r#"
+        [
+            File {
+                old_path: None,
+                path: "text",
+                file_mode: Some(
+                    FileMode(
+                        33188,
+                    ),
+                ),
+                sections: [
+                    FileMode {
+                        is_checked: false,
+                        before: FileMode(
+                            33188,
+                        ),
+                        after: FileMode(
+                            33261,
+                        ),
+                    },
+                    Unchanged {
+                        lines: [
+                            "text\n",
+                        ],
+                    },
+                ],
+            },
+            File {
+                old_path: None,
+                path: "empty",
+                file_mode: Some(
+                    FileMode(
+                        33188,
+                    ),
+                ),
+                sections: [
+                    FileMode {
+                        is_checked: false,
+                        before: FileMode(
+                            33188,
+                        ),
+                        after: FileMode(
+                            33261,
+                        ),
+                    },
+                ],
+            },
+            File {
+                old_path: None,
+                path: "binary",
+                file_mode: Some(
+                    FileMode(
+                        33188,
+                    ),
+                ),
+                sections: [
+                    FileMode {
+                        is_checked: false,
+                        before: FileMode(
+                            33188,
+                        ),
+                        after: FileMode(
+                            33261,
+                        ),
+                    },
+                ],
+            },
+        ]
+        "#);
+        let no_changes_tree_id = apply_diff_builtin(
+            store,
+            &left_tree,
+            &right_tree,
+            changed_files.clone(),
+            &files,
+        )
+        .unwrap();
+        assert_eq!(
+            no_changes_tree_id,
+            left_tree.id(),
+            "no-changes tree was different",
+        );
+
+        let mut files = files;
+        for file in &mut files {
+            file.toggle_all();
+        }
+        let all_changes_tree_id =
+            apply_diff_builtin(store, &left_tree, &right_tree, changed_files, &files).unwrap();
+        assert_eq!(
+            all_changes_tree_id,
+            right_tree.id(),
+            "all-changes tree was different",
+        );
+    }
+
+    #[test]
+    fn test_edit_diff_builtin_binary_without_executable_bit() {
+        let test_repo = TestRepo::init();
+        let store = test_repo.repo.store();
+
+        let binary_path = RepoPath::from_internal_string("binary");
+        let create_tree = |contents: &str, executable: bool| {
+            let mut tree_builder = store.tree_builder(store.empty_tree_id().clone());
+            if executable {
+                testutils::write_executable_file(&mut tree_builder, binary_path, contents);
+            } else {
+                testutils::write_normal_file(&mut tree_builder, binary_path, contents);
+            }
+            let id = tree_builder.write_tree().unwrap();
+            MergedTree::resolved(store.get_tree(RepoPathBuf::root(), &id).unwrap())
+        };
+        let left_tree = create_tree("\0old", false);
+        let right_tree = create_tree("\0new", true);
+
+        let changed_files = vec![binary_path.to_owned()];
+        let mut files = make_diff_files(
+            store,
+            &left_tree,
+            &right_tree,
+            &changed_files,
+            ConflictMarkerStyle::Diff,
+        )
+        .unwrap();
+        // Select the new contents, but not the mode change.
+        for section in &mut files[0].sections {
+            if let scm_record::Section::Binary { is_checked, .. } = section {
+                *is_checked = true;
+            }
+        }
+        let tree_id =
+            apply_diff_builtin(store, &left_tree, &right_tree, changed_files, &files).unwrap();
+        assert_eq!(tree_id, create_tree("\0new", false).id());
+    }
+
     #[test]
     fn test_edit_diff_builtin_delete_empty_file() {
         let test_repo = TestRepo::init();
@@ -21,7 +21,9 @@ use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::store::Store;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::working_copy::FileStateTrust;
 use jj_lib::working_copy::SnapshotOptions;
+use jj_lib::working_copy::SymlinkPolicy;
 use pollster::FutureExt;
 use tempfile::TempDir;
 use thiserror::Error;
@@ -297,8 +299,12 @@ diff editing in mind and be a little inaccurate.
             fsmonitor_settings: FsmonitorSettings::None,
             progress: None,
             start_tracking_matcher: &EverythingMatcher,
+            snapshot_matcher: None,
             max_new_file_size: u64::MAX,
+            file_state_trust: FileStateTrust::default(),
+            rehash_throttle_size: u64::MAX,
             conflict_marker_style,
+            symlink_policy: SymlinkPolicy::default(),
         })?;
         Ok(output_tree_state.current_tree_id().clone())
     }
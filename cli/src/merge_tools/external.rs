@@ -22,6 +22,7 @@ use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::working_copy::SymlinkPolicy;
 use pollster::FutureExt;
 use thiserror::Error;
 
@@ -304,6 +305,7 @@ pub fn edit_diff_external(
         .unwrap_or(default_conflict_marker_style);
     let options = CheckoutOptions {
         conflict_marker_style,
+        symlink_policy: SymlinkPolicy::default(),
     };
 
     let got_output_field = find_all_variables(&editor.edit_args).contains(&"output");
@@ -352,6 +354,7 @@ pub fn generate_diff(
         .unwrap_or(default_conflict_marker_style);
     let options = CheckoutOptions {
         conflict_marker_style,
+        symlink_policy: SymlinkPolicy::default(),
     };
     let store = left_tree.store();
     let diff_wc = check_out_trees(store, left_tree, right_tree, matcher, None, &options)?;
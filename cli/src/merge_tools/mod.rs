@@ -49,7 +49,7 @@ use self::external::ExternalToolError;
 use crate::config::CommandNameAndArgs;
 use crate::ui::Ui;
 
-const BUILTIN_EDITOR_NAME: &str = ":builtin";
+pub(crate) const BUILTIN_EDITOR_NAME: &str = ":builtin";
 
 #[derive(Debug, Error)]
 pub enum DiffEditError {
@@ -22,6 +22,11 @@ use jj_lib::extensions_map::ExtensionsMap;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::OperationId;
 use jj_lib::operation::Operation;
+use jj_lib::refs::diff_named_ref_targets;
+use jj_lib::refs::diff_named_remote_refs;
+use jj_lib::repo::RepoLoader;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::view::View;
 
 use crate::template_builder;
 use crate::template_builder::merge_fn_map;
@@ -39,6 +44,7 @@ use crate::templater::PlainTextFormattedProperty;
 use crate::templater::Template;
 use crate::templater::TemplateFormatter;
 use crate::templater::TemplateProperty;
+use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
 use crate::templater::TimestampRange;
 
@@ -49,6 +55,7 @@ pub trait OperationTemplateLanguageExtension {
 }
 
 pub struct OperationTemplateLanguage {
+    repo_loader: RepoLoader,
     root_op_id: OperationId,
     current_op_id: Option<OperationId>,
     build_fn_table: OperationTemplateBuildFnTable,
@@ -59,7 +66,7 @@ impl OperationTemplateLanguage {
     /// Sets up environment where operation template will be transformed to
     /// evaluation tree.
     pub fn new(
-        root_op_id: &OperationId,
+        repo_loader: &RepoLoader,
         current_op_id: Option<&OperationId>,
         extensions: &[impl AsRef<dyn OperationTemplateLanguageExtension>],
     ) -> Self {
@@ -74,7 +81,8 @@ impl OperationTemplateLanguage {
         }
 
         OperationTemplateLanguage {
-            root_op_id: root_op_id.clone(),
+            repo_loader: repo_loader.clone(),
+            root_op_id: repo_loader.op_store().root_operation_id().clone(),
             current_op_id: current_op_id.cloned(),
             build_fn_table,
             cache_extensions,
@@ -257,6 +265,11 @@ impl OperationTemplateBuildFnTable {
     }
 }
 
+/// Names of the builtin keywords available in operation templates, sorted.
+pub fn builtin_operation_keywords() -> Vec<&'static str> {
+    builtin_operation_methods().into_keys().sorted().collect()
+}
+
 fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
     type L = OperationTemplateLanguage;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -321,6 +334,21 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
             Ok(L::wrap_timestamp_range(out_property))
         },
     );
+    map.insert(
+        "tag",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [name_node] = function.expect_exact_arguments()?;
+            let name_property = template_builder::expect_plain_text_expression(
+                language,
+                diagnostics,
+                build_ctx,
+                name_node,
+            )?;
+            let out_property = (self_property, name_property)
+                .map(|(op, name)| op.metadata().tags.get(&name).cloned().unwrap_or_default());
+            Ok(L::wrap_string(out_property))
+        },
+    );
     map.insert(
         "user",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -332,6 +360,48 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
             Ok(L::wrap_string(out_property))
         },
     );
+    map.insert(
+        "username",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|op| op.metadata().username.clone());
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "hostname",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|op| op.metadata().hostname.clone());
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "refs_changed",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|op| {
+                let Some(parent) = op.parents().next() else {
+                    return Ok(0);
+                };
+                let count = count_changed_refs(&parent?.view()?, &op.view()?);
+                Ok(i64::try_from(count)?)
+            });
+            Ok(L::wrap_integer(out_property))
+        },
+    );
+    map.insert(
+        "commits_created",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo_loader = language.repo_loader.clone();
+            let out_property = self_property.and_then(move |op| {
+                let count = count_commits_created(&repo_loader, &op)?;
+                Ok(i64::try_from(count)?)
+            });
+            Ok(L::wrap_integer(out_property))
+        },
+    );
     map.insert(
         "root",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -344,6 +414,43 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
     map
 }
 
+/// Counts the bookmarks, tags, remote bookmarks, and Git refs which point to
+/// different targets in the two views.
+fn count_changed_refs(old_view: &View, new_view: &View) -> usize {
+    diff_named_ref_targets(old_view.local_bookmarks(), new_view.local_bookmarks()).count()
+        + diff_named_ref_targets(old_view.tags(), new_view.tags()).count()
+        + diff_named_remote_refs(
+            old_view.all_remote_bookmarks(),
+            new_view.all_remote_bookmarks(),
+        )
+        .count()
+        + diff_named_ref_targets(old_view.git_refs(), new_view.git_refs()).count()
+}
+
+/// Counts the commits which became visible in the operation, such as new and
+/// rewritten commits.
+fn count_commits_created(
+    repo_loader: &RepoLoader,
+    op: &Operation,
+) -> Result<usize, TemplatePropertyError> {
+    if op.parent_ids().is_empty() {
+        return Ok(0);
+    }
+    let mut parent_heads = vec![];
+    for parent in op.parents() {
+        parent_heads.extend(parent?.view()?.heads().iter().cloned());
+    }
+    let repo = repo_loader.load_at(op)?;
+    let heads = repo.view().heads().iter().cloned().collect_vec();
+    let count = RevsetExpression::commits(heads)
+        .ancestors()
+        .minus(&RevsetExpression::commits(parent_heads).ancestors())
+        .evaluate(repo.as_ref())?
+        .iter()
+        .process_results(|ids| ids.count())?;
+    Ok(count)
+}
+
 impl Template for OperationId {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         write!(formatter, "{}", self.hex())
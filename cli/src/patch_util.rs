@@ -0,0 +1,684 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing and application of patches in unified diff and mbox formats.
+
+use std::borrow::Cow;
+
+use bstr::ByteSlice as _;
+use jj_lib::backend::Timestamp;
+use thiserror::Error;
+
+/// Error occurred while parsing a patch.
+#[derive(Debug, Error)]
+pub enum PatchParseError {
+    #[error("Malformed patch at line {line}: {message}")]
+    Malformed { line: usize, message: String },
+    #[error("Binary patch for {0} is not supported")]
+    Binary(String),
+    #[error("Invalid Date header: {0}")]
+    InvalidDate(String),
+}
+
+/// A patch email, or a plain diff without any metadata.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PatchMessage {
+    /// Name and email address from the `From:` header.
+    pub author: Option<(String, String)>,
+    /// Timestamp from the `Date:` header.
+    pub date: Option<Timestamp>,
+    /// Commit description built from the subject and the message body.
+    pub description: String,
+    pub files: Vec<FilePatch>,
+}
+
+/// Changes to a single file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FilePatch {
+    /// Path before the change, or `None` if the file is created.
+    pub old_path: Option<String>,
+    /// Path after the change, or `None` if the file is deleted.
+    pub new_path: Option<String>,
+    /// Whether the old file is kept in place when `old_path` and `new_path`
+    /// differ.
+    pub is_copy: bool,
+    pub old_mode: Option<String>,
+    pub new_mode: Option<String>,
+    /// Abbreviated blob hash of the old contents from the `index` line.
+    pub old_blob: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+/// Contiguous region of changed lines.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Hunk {
+    /// 1-based line number of the first old line.
+    pub old_start: usize,
+    /// Context and removed lines, including line terminators.
+    pub old_lines: Vec<Vec<u8>>,
+    /// Context and added lines, including line terminators.
+    pub new_lines: Vec<Vec<u8>>,
+}
+
+/// Parses patch emails in mbox format, or a plain unified diff.
+pub fn parse_patches(input: &[u8]) -> Result<Vec<PatchMessage>, PatchParseError> {
+    let lines: Vec<&[u8]> = input.lines_with_terminator().collect();
+    let starts = (0..lines.len())
+        .filter(|&i| is_mbox_separator(&lines, i))
+        .collect::<Vec<_>>();
+    if starts.is_empty() {
+        let files = parse_diff(&lines, 0)?;
+        return Ok(vec![PatchMessage {
+            files,
+            ..PatchMessage::default()
+        }]);
+    }
+    let ends = starts.iter().skip(1).copied().chain([lines.len()]);
+    starts
+        .iter()
+        .zip(ends)
+        .map(|(&start, end)| parse_message(&lines[..end], start + 1))
+        .collect()
+}
+
+/// Returns true if the line starts a new message in mbox format.
+fn is_mbox_separator(lines: &[&[u8]], i: usize) -> bool {
+    lines[i].starts_with(b"From ")
+        && (i == 0 || lines[i - 1].trim().is_empty())
+        && lines
+            .get(i + 1)
+            .and_then(|line| line.find_byte(b':'))
+            .is_some()
+}
+
+fn parse_message(lines: &[&[u8]], start: usize) -> Result<PatchMessage, PatchParseError> {
+    let mut message = PatchMessage::default();
+    let mut subject = String::new();
+    let mut i = start;
+    // Headers, possibly folded into continuation lines
+    let mut headers: Vec<(String, String)> = vec![];
+    while i < lines.len() && !lines[i].trim().is_empty() {
+        let line = lines[i].to_str_lossy();
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.to_ascii_lowercase(), value.trim().to_owned()));
+        }
+        i += 1;
+    }
+    for (name, value) in headers {
+        match name.as_str() {
+            "from" => message.author = Some(parse_address(&decode_header_value(&value))),
+            "date" => {
+                let datetime = chrono::DateTime::parse_from_rfc2822(&value)
+                    .map_err(|_| PatchParseError::InvalidDate(value.clone()))?;
+                message.date = Some(Timestamp::from_datetime(datetime));
+            }
+            "subject" => subject = decode_header_value(strip_subject_prefix(&value)),
+            _ => {}
+        }
+    }
+    // Message body up to the "---" separator or the diff itself
+    let body_start = i;
+    while i < lines.len() && lines[i].trim_end() != b"---" && !is_diff_start(lines, i) {
+        i += 1;
+    }
+    let body = lines[body_start..i].concat();
+    let body = unescape_mbox_from_lines(&body.to_str_lossy());
+    let body = body.trim_matches('\n');
+    message.description = match (subject.is_empty(), body.is_empty()) {
+        (true, true) => String::new(),
+        (false, true) => format!("{subject}\n"),
+        (true, false) => format!("{body}\n"),
+        (false, false) => format!("{subject}\n\n{body}\n"),
+    };
+    message.files = parse_diff(lines, i)?;
+    Ok(message)
+}
+
+/// Parses `Name <email>` into its parts.
+fn parse_address(value: &str) -> (String, String) {
+    match value.rsplit_once('<') {
+        Some((name, email)) => (
+            name.trim().trim_matches('"').to_owned(),
+            email.trim_end_matches('>').trim().to_owned(),
+        ),
+        None => (String::new(), value.to_owned()),
+    }
+}
+
+/// Strips `[PATCH n/m]`-style prefixes from the subject.
+fn strip_subject_prefix(mut subject: &str) -> &str {
+    while let Some(rest) = subject.strip_prefix('[') {
+        match rest.split_once(']') {
+            Some((_, rest)) => subject = rest.trim_start(),
+            None => break,
+        }
+    }
+    subject
+}
+
+fn is_diff_start(lines: &[&[u8]], i: usize) -> bool {
+    lines[i].starts_with(b"diff --git ")
+        || (lines[i].starts_with(b"--- ")
+            && lines
+                .get(i + 1)
+                .is_some_and(|line| line.starts_with(b"+++ ")))
+}
+
+fn parse_diff(lines: &[&[u8]], mut i: usize) -> Result<Vec<FilePatch>, PatchParseError> {
+    let malformed = |i: usize, message: &str| PatchParseError::Malformed {
+        line: i + 1,
+        message: message.to_owned(),
+    };
+    let mut files: Vec<FilePatch> = vec![];
+    // Whether the current file has a "diff --git" header, which is
+    // authoritative about the paths
+    let mut is_git_header = false;
+    while i < lines.len() {
+        let line = lines[i].to_str_lossy();
+        let line = line.trim_end_matches(['\n', '\r']);
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            let (old_path, new_path) =
+                parse_git_header_paths(rest).ok_or_else(|| malformed(i, "invalid diff header"))?;
+            files.push(FilePatch {
+                old_path: Some(old_path),
+                new_path: Some(new_path),
+                ..FilePatch::default()
+            });
+            is_git_header = true;
+        } else if line.starts_with("--- ")
+            && lines
+                .get(i + 1)
+                .is_some_and(|line| line.starts_with(b"+++ "))
+        {
+            let old_path = parse_unified_header_path(&line[4..]);
+            let new_line = lines[i + 1].to_str_lossy();
+            let new_path = parse_unified_header_path(&new_line.trim_end_matches(['\n', '\r'])[4..]);
+            let starts_file =
+                !is_git_header || files.last().is_some_and(|file| !file.hunks.is_empty());
+            if starts_file {
+                files.push(FilePatch::default());
+                is_git_header = false;
+            }
+            let file = files.last_mut().unwrap();
+            if !is_git_header || old_path.is_none() {
+                file.old_path = old_path;
+            }
+            if !is_git_header || new_path.is_none() {
+                file.new_path = new_path;
+            }
+            i += 1;
+        } else if line.starts_with("@@ ") {
+            let file = files
+                .last_mut()
+                .ok_or_else(|| malformed(i, "hunk without file header"))?;
+            let (hunk, next) = parse_hunk(lines, i).ok_or_else(|| malformed(i, "invalid hunk"))?;
+            file.hunks.push(hunk);
+            i = next;
+            continue;
+        } else if let Some(file) = files.last_mut().filter(|_| is_git_header) {
+            if let Some(mode) = line.strip_prefix("new file mode ") {
+                file.old_path = None;
+                file.new_mode = Some(mode.to_owned());
+            } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+                file.new_path = None;
+                file.old_mode = Some(mode.to_owned());
+            } else if let Some(mode) = line.strip_prefix("old mode ") {
+                file.old_mode = Some(mode.to_owned());
+            } else if let Some(mode) = line.strip_prefix("new mode ") {
+                file.new_mode = Some(mode.to_owned());
+            } else if let Some(rest) = line.strip_prefix("index ") {
+                let (hashes, mode) = rest.split_once(' ').unwrap_or((rest, ""));
+                let (old_blob, _) = hashes
+                    .split_once("..")
+                    .ok_or_else(|| malformed(i, "invalid index line"))?;
+                if !old_blob.bytes().all(|b| b == b'0') {
+                    file.old_blob = Some(old_blob.to_owned());
+                }
+                if !mode.is_empty() {
+                    file.old_mode.get_or_insert_with(|| mode.to_owned());
+                    file.new_mode.get_or_insert_with(|| mode.to_owned());
+                }
+            } else if let Some(path) = line.strip_prefix("rename from ") {
+                file.old_path = Some(path.to_owned());
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                file.new_path = Some(path.to_owned());
+            } else if let Some(path) = line.strip_prefix("copy from ") {
+                file.old_path = Some(path.to_owned());
+                file.is_copy = true;
+            } else if let Some(path) = line.strip_prefix("copy to ") {
+                file.new_path = Some(path.to_owned());
+                file.is_copy = true;
+            } else if line.starts_with("GIT binary patch") || line.starts_with("Binary files ") {
+                let path = file.new_path.as_ref().or(file.old_path.as_ref());
+                return Err(PatchParseError::Binary(path.cloned().unwrap_or_default()));
+            } else if !file.hunks.is_empty() {
+                // Trailing text such as an email signature
+                is_git_header = false;
+            }
+        }
+        i += 1;
+    }
+    Ok(files)
+}
+
+/// Parses `a/<path> b/<path>` from a `diff --git` line.
+fn parse_git_header_paths(rest: &str) -> Option<(String, String)> {
+    let rest = rest.strip_prefix("a/")?;
+    // Paths may contain " b/", so prefer the split where both sides are equal.
+    let candidates = rest
+        .match_indices(" b/")
+        .map(|(pos, _)| pos)
+        .collect::<Vec<_>>();
+    let pos = candidates
+        .iter()
+        .copied()
+        .find(|&pos| rest[..pos] == rest[pos + 3..])
+        .or_else(|| candidates.first().copied())?;
+    Some((rest[..pos].to_owned(), rest[pos + 3..].to_owned()))
+}
+
+/// Parses the path from a `---`/`+++` line, stripping the leading directory
+/// like `patch -p1`.
+fn parse_unified_header_path(value: &str) -> Option<String> {
+    let path = value.split('\t').next().unwrap_or(value).trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path.split_once('/').map_or(path, |(_, rest)| rest);
+    Some(path.to_owned())
+}
+
+/// Parses a hunk starting at the `@@` line. Returns the hunk and the index of
+/// the line following it.
+fn parse_hunk(lines: &[&[u8]], start: usize) -> Option<(Hunk, usize)> {
+    let header = lines[start].to_str().ok()?;
+    let ranges = header.strip_prefix("@@ -")?.split(" @@").next()?;
+    let (old_range, new_range) = ranges.split_once(" +")?;
+    let parse_range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, mut old_remaining) = parse_range(old_range)?;
+    let (_, mut new_remaining) = parse_range(new_range)?;
+    let mut hunk = Hunk {
+        old_start,
+        ..Hunk::default()
+    };
+    let mut i = start + 1;
+    // Which sides the last line belonged to, for "\ No newline at end of file"
+    let mut last_sides = (false, false);
+    while i < lines.len() {
+        let line = lines[i];
+        if line.starts_with(b"\\") {
+            for (is_side, side_lines) in [
+                (last_sides.0, &mut hunk.old_lines),
+                (last_sides.1, &mut hunk.new_lines),
+            ] {
+                if let Some(last) = side_lines.last_mut().filter(|_| is_side) {
+                    if last.ends_with(b"\n") {
+                        last.pop();
+                    }
+                    if last.ends_with(b"\r") {
+                        last.pop();
+                    }
+                }
+            }
+            i += 1;
+            continue;
+        }
+        if old_remaining == 0 && new_remaining == 0 {
+            break;
+        }
+        let (marker, content) = match line.split_first() {
+            Some((b'\n', _)) | None => (b' ', &b"\n"[..]),
+            Some((marker, content)) => (*marker, content),
+        };
+        last_sides = match marker {
+            b' ' => (true, true),
+            b'-' => (true, false),
+            b'+' => (false, true),
+            _ => return None,
+        };
+        if last_sides.0 {
+            old_remaining = old_remaining.checked_sub(1)?;
+            hunk.old_lines.push(content.to_vec());
+        }
+        if last_sides.1 {
+            new_remaining = new_remaining.checked_sub(1)?;
+            hunk.new_lines.push(content.to_vec());
+        }
+        i += 1;
+    }
+    (old_remaining == 0 && new_remaining == 0).then_some((hunk, i))
+}
+
+/// Applies the hunks to the `content`. Hunks may be offset from their
+/// recorded positions, but their context lines must match exactly. Returns
+/// `None` if any hunk doesn't apply.
+pub fn apply_hunks(content: &[u8], hunks: &[Hunk]) -> Option<Vec<u8>> {
+    let lines: Vec<&[u8]> = content.lines_with_terminator().collect();
+    let mut output = vec![];
+    let mut pos = 0;
+    for hunk in hunks {
+        // An empty old side means insertion after the old_start line.
+        let expected = if hunk.old_lines.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let matches_at = |start: usize| {
+            start >= pos
+                && start + hunk.old_lines.len() <= lines.len()
+                && lines[start..start + hunk.old_lines.len()]
+                    .iter()
+                    .zip(&hunk.old_lines)
+                    .all(|(line, old_line)| *line == old_line.as_slice())
+        };
+        let start = (0..=lines.len()).find_map(|offset| {
+            [expected.checked_add(offset), expected.checked_sub(offset)]
+                .into_iter()
+                .flatten()
+                .find(|&start| matches_at(start))
+        })?;
+        output.extend(lines[pos..start].concat());
+        output.extend(hunk.new_lines.concat());
+        pos = start + hunk.old_lines.len();
+    }
+    output.extend(lines[pos..].concat());
+    Some(output)
+}
+
+/// Maximum length of a single RFC 2047 encoded word.
+const ENCODED_WORD_MAX_LEN: usize = 75;
+
+/// Encodes a header value as RFC 2047 "Q" encoded words if it contains
+/// non-ASCII characters. Long values are folded into continuation lines.
+pub fn encode_header_value(value: &str) -> Cow<'_, str> {
+    if value.is_ascii() && !value.contains("=?") {
+        return Cow::Borrowed(value);
+    }
+    const PREFIX: &str = "=?UTF-8?q?";
+    const SUFFIX: &str = "?=";
+    let mut words = vec![];
+    let mut word = String::new();
+    for c in value.chars() {
+        let mut encoded = String::new();
+        let mut buf = [0; 4];
+        for &b in c.encode_utf8(&mut buf).as_bytes() {
+            if b.is_ascii_alphanumeric() || b"!*+-/".contains(&b) {
+                encoded.push(char::from(b));
+            } else {
+                encoded.push_str(&format!("={b:02X}"));
+            }
+        }
+        if PREFIX.len() + word.len() + encoded.len() + SUFFIX.len() > ENCODED_WORD_MAX_LEN {
+            words.push(format!("{PREFIX}{word}{SUFFIX}"));
+            word.clear();
+        }
+        word.push_str(&encoded);
+    }
+    words.push(format!("{PREFIX}{word}{SUFFIX}"));
+    Cow::Owned(words.join("\n "))
+}
+
+/// Decodes RFC 2047 encoded words in a header value. Words in unsupported
+/// encodings are left as is.
+pub fn decode_header_value(value: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = value;
+    let mut last_was_encoded = false;
+    while !rest.is_empty() {
+        let Some(start) = rest.find("=?") else {
+            decoded.push_str(rest);
+            break;
+        };
+        let (text, word) = rest.split_at(start);
+        match decode_encoded_word(word) {
+            Some((word_text, len)) => {
+                // Whitespace between adjacent encoded words is ignored.
+                if !(last_was_encoded && text.trim().is_empty()) {
+                    decoded.push_str(text);
+                }
+                decoded.push_str(&word_text);
+                rest = &word[len..];
+                last_was_encoded = true;
+            }
+            None => {
+                decoded.push_str(text);
+                decoded.push_str("=?");
+                rest = &word[2..];
+                last_was_encoded = false;
+            }
+        }
+    }
+    decoded
+}
+
+/// Decodes a single `=?charset?q?text?=` word. Returns the decoded text and
+/// the length of the encoded word.
+fn decode_encoded_word(word: &str) -> Option<(String, usize)> {
+    let (charset, rest) = word.strip_prefix("=?")?.split_once('?')?;
+    let (encoding, rest) = rest.split_once('?')?;
+    let (text, _) = rest.split_once("?=")?;
+    let len = 2 + charset.len() + 1 + encoding.len() + 1 + text.len() + 2;
+    let is_utf8 = charset.eq_ignore_ascii_case("utf-8") || charset.eq_ignore_ascii_case("us-ascii");
+    if !is_utf8 || !encoding.eq_ignore_ascii_case("q") {
+        return None;
+    }
+    let mut bytes = vec![];
+    let mut iter = text.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'_' => bytes.push(b' '),
+            b'=' => {
+                let hex = [iter.next()?, iter.next()?];
+                let hex = std::str::from_utf8(&hex).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            }
+            _ => bytes.push(b),
+        }
+    }
+    Some((String::from_utf8_lossy(&bytes).into_owned(), len))
+}
+
+/// Escapes message body lines that could be mistaken for an mbox separator,
+/// as in the "mboxrd" format.
+pub fn escape_mbox_from_lines(body: &str) -> String {
+    body.split_inclusive('\n')
+        .map(|line| {
+            if line.trim_start_matches('>').starts_with("From ") {
+                Cow::Owned(format!(">{line}"))
+            } else {
+                Cow::Borrowed(line)
+            }
+        })
+        .collect()
+}
+
+/// Reverses [`escape_mbox_from_lines()`].
+fn unescape_mbox_from_lines(body: &str) -> String {
+    body.split_inclusive('\n')
+        .map(|line| {
+            if line.starts_with('>') && line.trim_start_matches('>').starts_with("From ") {
+                &line[1..]
+            } else {
+                line
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain_diff() {
+        let input = b"\
+--- a/foo\tdate
++++ b/foo
+@@ -1,2 +1,2 @@
+ a
+-b
++c
+";
+        let messages = parse_patches(input).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].description, "");
+        assert_eq!(
+            messages[0].files,
+            vec![FilePatch {
+                old_path: Some("foo".to_owned()),
+                new_path: Some("foo".to_owned()),
+                hunks: vec![Hunk {
+                    old_start: 1,
+                    old_lines: vec![b"a\n".to_vec(), b"b\n".to_vec()],
+                    new_lines: vec![b"a\n".to_vec(), b"c\n".to_vec()],
+                }],
+                ..FilePatch::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_mbox() {
+        let input = b"\
+From 0123 Mon Sep 17 00:00:00 2001
+From: \"Some One\" <some@example.com>
+Date: Sat, 3 Feb 2001 04:05:08 +0700
+Subject: [PATCH v2
+ 1/2] Add foo
+
+Body text.
+---
+ foo | 1 +
+diff --git a/foo b/foo
+new file mode 100755
+index 0000000000..257cc5642c
+--- /dev/null
++++ b/foo
+@@ -0,0 +1 @@
++foo
+\\ No newline at end of file
+--
+2.40.0
+
+From 4567 Mon Sep 17 00:00:00 2001
+From: other@example.com
+Subject: [PATCH 2/2] Delete foo
+
+diff --git a/foo b/foo
+deleted file mode 100755
+index 257cc5642c..0000000000
+--- a/foo
++++ /dev/null
+@@ -1 +0,0 @@
+-foo
+\\ No newline at end of file
+";
+        let messages = parse_patches(input).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0].author,
+            Some(("Some One".to_owned(), "some@example.com".to_owned()))
+        );
+        assert_eq!(
+            messages[0].date.as_ref().map(|date| date.tz_offset),
+            Some(7 * 60)
+        );
+        assert_eq!(messages[0].description, "Add foo\n\nBody text.\n");
+        assert_eq!(
+            messages[0].files,
+            vec![FilePatch {
+                old_path: None,
+                new_path: Some("foo".to_owned()),
+                new_mode: Some("100755".to_owned()),
+                hunks: vec![Hunk {
+                    old_start: 0,
+                    old_lines: vec![],
+                    new_lines: vec![b"foo".to_vec()],
+                }],
+                ..FilePatch::default()
+            }]
+        );
+        assert_eq!(
+            messages[1].author,
+            Some(("".to_owned(), "other@example.com".to_owned()))
+        );
+        assert_eq!(messages[1].description, "Delete foo\n");
+        assert_eq!(messages[1].files[0].old_path, Some("foo".to_owned()));
+        assert_eq!(messages[1].files[0].new_path, None);
+        assert_eq!(messages[1].files[0].old_blob, Some("257cc5642c".to_owned()));
+    }
+
+    #[test]
+    fn test_apply_hunks() {
+        let hunk = |old_start, old: &[&str], new: &[&str]| Hunk {
+            old_start,
+            old_lines: old.iter().map(|line| line.as_bytes().to_vec()).collect(),
+            new_lines: new.iter().map(|line| line.as_bytes().to_vec()).collect(),
+        };
+        let content = b"a\nb\nc\nd\n";
+        // Exact position
+        assert_eq!(
+            apply_hunks(content, &[hunk(2, &["b\n"], &["B\n"])]).unwrap(),
+            b"a\nB\nc\nd\n"
+        );
+        // Offset position
+        assert_eq!(
+            apply_hunks(content, &[hunk(1, &["c\n", "d\n"], &["c\n"])]).unwrap(),
+            b"a\nb\nc\n"
+        );
+        // Insertion into empty file
+        assert_eq!(apply_hunks(b"", &[hunk(0, &[], &["x\n"])]).unwrap(), b"x\n");
+        // Context mismatch
+        assert_eq!(apply_hunks(content, &[hunk(2, &["x\n"], &["y\n"])]), None);
+    }
+
+    #[test]
+    fn test_header_value_encoding() {
+        assert_eq!(encode_header_value("Fix the thing"), "Fix the thing");
+        assert_eq!(
+            encode_header_value("Fix café"),
+            "=?UTF-8?q?Fix=20caf=C3=A9?="
+        );
+        let long = "é".repeat(20);
+        let encoded = encode_header_value(&long);
+        assert!(encoded.lines().all(|line| line.trim().len() <= 75));
+        assert_eq!(decode_header_value(&encoded.replace('\n', "")), long);
+        assert_eq!(
+            decode_header_value("=?UTF-8?q?caf=C3=A9?= au lait"),
+            "café au lait"
+        );
+        assert_eq!(
+            decode_header_value("=?iso-8859-1?b?abc?="),
+            "=?iso-8859-1?b?abc?="
+        );
+    }
+
+    #[test]
+    fn test_mbox_from_line_escaping() {
+        let body = "From here\n>From there\nnot From\n";
+        let escaped = escape_mbox_from_lines(body);
+        assert_eq!(escaped, ">From here\n>>From there\nnot From\n");
+        assert_eq!(unescape_mbox_from_lines(&escaped), body);
+    }
+}
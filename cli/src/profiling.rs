@@ -0,0 +1,264 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-phase timing of a command, collected from `tracing` spans.
+//!
+//! The profiling layer is always installed, but it doesn't record anything
+//! until profiling is enabled by the `--profile` flag.
+
+use std::cell::Cell;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use tracing::span;
+use tracing::Metadata;
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Phase of a command which is reported separately.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Phase {
+    Snapshot,
+    RevsetEvaluation,
+    TreeDiffing,
+    BackendRead,
+    BackendWrite,
+    TransactionCommit,
+}
+
+impl Phase {
+    const ALL: [Phase; 6] = [
+        Phase::Snapshot,
+        Phase::RevsetEvaluation,
+        Phase::TreeDiffing,
+        Phase::BackendRead,
+        Phase::BackendWrite,
+        Phase::TransactionCommit,
+    ];
+
+    fn from_metadata(metadata: &Metadata<'_>) -> Option<Self> {
+        match (metadata.target(), metadata.name()) {
+            ("jj_cli::cli_util", "snapshot_working_copy") => Some(Phase::Snapshot),
+            ("jj_lib::revset", "evaluate" | "evaluate_unoptimized") => {
+                Some(Phase::RevsetEvaluation)
+            }
+            ("jj_cli::diff_util", "show_diff") => Some(Phase::TreeDiffing),
+            ("jj_lib::store", name) if name.starts_with("write_") => Some(Phase::BackendWrite),
+            ("jj_lib::store", _) => Some(Phase::BackendRead),
+            ("jj_lib::transaction", "commit") => Some(Phase::TransactionCommit),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Phase::Snapshot => "snapshot",
+            Phase::RevsetEvaluation => "revset evaluation",
+            Phase::TreeDiffing => "tree diffing",
+            Phase::BackendRead => "backend reads",
+            Phase::BackendWrite => "backend writes",
+            Phase::TransactionCommit => "transaction commit",
+        }
+    }
+}
+
+/// Timing of a closed span.
+#[derive(Clone, Debug)]
+struct SpanRecord {
+    name: &'static str,
+    target: &'static str,
+    thread_id: u64,
+    start: Duration,
+    duration: Duration,
+    /// Phase this span is accounted to. Spans nested in another span of the
+    /// same phase aren't accounted.
+    phase: Option<Phase>,
+}
+
+/// Timing attached to an open span.
+struct SpanTiming {
+    start: Instant,
+    thread_id: u64,
+    phase: Option<Phase>,
+}
+
+#[derive(Debug)]
+struct ProfilerState {
+    enabled: AtomicBool,
+    start: Instant,
+    trace_path: Mutex<Option<PathBuf>>,
+    records: Mutex<Vec<SpanRecord>>,
+}
+
+/// Handle to enable profiling and report the results.
+#[derive(Clone, Debug)]
+pub struct Profiler {
+    state: Arc<ProfilerState>,
+}
+
+impl Profiler {
+    /// Creates disabled profiler and the layer feeding it.
+    pub fn new<S>() -> (Self, impl Layer<S>)
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let state = Arc::new(ProfilerState {
+            enabled: AtomicBool::new(false),
+            start: Instant::now(),
+            trace_path: Mutex::new(None),
+            records: Mutex::new(vec![]),
+        });
+        let filter_state = state.clone();
+        let filter = tracing_subscriber::filter::dynamic_filter_fn(move |metadata, _cx| {
+            metadata.is_span() && filter_state.enabled.load(Ordering::Relaxed)
+        });
+        let layer = ProfilingLayer {
+            state: state.clone(),
+        }
+        .with_filter(filter);
+        (Profiler { state }, layer)
+    }
+
+    /// Starts recording spans. If `trace_path` is specified, the recorded
+    /// spans will also be written to the file in Chrome trace format.
+    pub fn enable(&self, trace_path: Option<PathBuf>) {
+        *self.state.trace_path.lock().unwrap() = trace_path;
+        self.state.enabled.store(true, Ordering::Relaxed);
+    }
+
+    /// Prints time spent per phase, and writes trace file if requested.
+    ///
+    /// Does nothing if profiling isn't enabled.
+    pub fn finish(&self, out: &mut dyn io::Write) -> io::Result<()> {
+        if !self.state.enabled.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        let total = self.state.start.elapsed();
+        let records = self.state.records.lock().unwrap();
+        writeln!(out, "Time spent per phase:")?;
+        for phase in Phase::ALL {
+            let (count, duration) = records
+                .iter()
+                .filter(|record| record.phase == Some(phase))
+                .fold((0, Duration::ZERO), |(count, duration), record| {
+                    (count + 1, duration + record.duration)
+                });
+            writeln!(
+                out,
+                "  {label:<20} {duration:>12} ({count} {spans})",
+                label = phase.label(),
+                duration = format_duration(duration),
+                spans = if count == 1 { "span" } else { "spans" },
+            )?;
+        }
+        writeln!(out, "  {:<20} {:>12}", "total", format_duration(total))?;
+        if let Some(path) = self.state.trace_path.lock().unwrap().as_ref() {
+            fs::write(path, chrome_trace_json(&records))?;
+        }
+        Ok(())
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn chrome_trace_json(records: &[SpanRecord]) -> String {
+    let events: Vec<_> = records
+        .iter()
+        .map(|record| {
+            serde_json::json!({
+                "name": record.name,
+                "cat": record.target,
+                "ph": "X",
+                "ts": record.start.as_secs_f64() * 1e6,
+                "dur": record.duration.as_secs_f64() * 1e6,
+                "pid": 1,
+                "tid": record.thread_id,
+            })
+        })
+        .collect();
+    serde_json::json!({ "traceEvents": events }).to_string()
+}
+
+fn current_thread_id() -> u64 {
+    static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+    thread_local! {
+        static THREAD_ID: Cell<u64> = const { Cell::new(0) };
+    }
+    THREAD_ID.with(|id| {
+        if id.get() == 0 {
+            id.set(NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed));
+        }
+        id.get()
+    })
+}
+
+struct ProfilingLayer {
+    state: Arc<ProfilerState>,
+}
+
+impl<S> Layer<S> for ProfilingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let phase = Phase::from_metadata(attrs.metadata()).filter(|&phase| {
+            // Don't count time spent in nested spans of the same phase twice.
+            !span.scope().skip(1).any(|parent| {
+                parent
+                    .extensions()
+                    .get::<SpanTiming>()
+                    .is_some_and(|timing| timing.phase == Some(phase))
+            })
+        });
+        span.extensions_mut().insert(SpanTiming {
+            start: Instant::now(),
+            thread_id: current_thread_id(),
+            phase,
+        });
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(timing) = extensions.get::<SpanTiming>() else {
+            return;
+        };
+        let record = SpanRecord {
+            name: span.metadata().name(),
+            target: span.metadata().target(),
+            thread_id: timing.thread_id,
+            start: timing.start.duration_since(self.state.start),
+            duration: timing.start.elapsed(),
+            phase: timing.phase,
+        };
+        self.state.records.lock().unwrap().push(record);
+    }
+}
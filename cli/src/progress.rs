@@ -1,14 +1,18 @@
 use std::io;
+use std::io::Write;
 use std::path::Path;
 use std::sync::Mutex;
 use std::time::Duration;
 use std::time::Instant;
 
+use crossterm::cursor::MoveToPreviousLine;
 use crossterm::terminal::Clear;
 use crossterm::terminal::ClearType;
+use itertools::Itertools as _;
 use jj_lib::fmt_util::binary_prefix;
 use jj_lib::git;
 use jj_lib::repo_path::RepoPath;
+use unicode_width::UnicodeWidthStr as _;
 
 use crate::cleanup_guard::CleanupGuard;
 use crate::text_util;
@@ -65,26 +69,8 @@ impl Progress {
 
         self.buffer.clear();
         write!(self.buffer, "\r").unwrap();
-        let control_chars = self.buffer.len();
-        write!(self.buffer, "{: >3.0}% ", 100.0 * progress.overall).unwrap();
-        if let Some(total) = progress.bytes_downloaded {
-            let (scaled, prefix) = binary_prefix(total as f32);
-            write!(self.buffer, "{scaled: >5.1} {prefix}B ").unwrap();
-        }
-        if let Some(estimate) = rate {
-            let (scaled, prefix) = binary_prefix(estimate);
-            write!(self.buffer, "at {scaled: >5.1} {prefix}B/s ").unwrap();
-        }
-
-        let bar_width = output
-            .term_width()
-            .map(usize::from)
-            .unwrap_or(0)
-            .saturating_sub(self.buffer.len() - control_chars + 2);
-        self.buffer.push('[');
-        draw_progress(progress.overall, &mut self.buffer, bar_width);
-        self.buffer.push(']');
-
+        let width = output.term_width().map(usize::from).unwrap_or(0);
+        write_progress(&mut self.buffer, progress, rate, width);
         write!(self.buffer, "{}", Clear(ClearType::UntilNewLine)).unwrap();
         write!(output, "{}", self.buffer)?;
         output.flush()?;
@@ -92,6 +78,28 @@ impl Progress {
     }
 }
 
+/// Appends the percentage, the transferred size and rate, and a progress bar
+/// filling up the rest of the `width` columns.
+fn write_progress(buffer: &mut String, progress: &git::Progress, rate: Option<f32>, width: usize) {
+    use std::fmt::Write as _;
+
+    let start = buffer.len();
+    write!(buffer, "{: >3.0}% ", 100.0 * progress.overall).unwrap();
+    if let Some(total) = progress.bytes_downloaded {
+        let (scaled, prefix) = binary_prefix(total as f32);
+        write!(buffer, "{scaled: >5.1} {prefix}B ").unwrap();
+    }
+    if let Some(estimate) = rate {
+        let (scaled, prefix) = binary_prefix(estimate);
+        write!(buffer, "at {scaled: >5.1} {prefix}B/s ").unwrap();
+    }
+
+    let bar_width = width.saturating_sub(buffer.len() - start + 2);
+    buffer.push('[');
+    draw_progress(progress.overall, buffer, bar_width);
+    buffer.push(']');
+}
+
 fn draw_progress(progress: f32, buffer: &mut String, width: usize) {
     const CHARS: [char; 9] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
     const RESOLUTION: usize = CHARS.len() - 1;
@@ -163,6 +171,242 @@ impl RateEstimateState {
     }
 }
 
+/// Region at the bottom of the terminal displaying the progress of multiple
+/// concurrent operations (such as transfers from several remotes), one line
+/// per operation.
+///
+/// If the output isn't a terminal, nothing is drawn, and the message passed to
+/// [`ProgressBar::finish()`] is printed instead as each operation completes.
+pub struct ProgressRegion<W: Write> {
+    state: Mutex<ProgressRegionState<W>>,
+}
+
+enum ProgressRegionOutput<W> {
+    Terminal(ProgressOutput<W>),
+    Log(W),
+    Hidden,
+}
+
+struct ProgressRegionState<W> {
+    output: ProgressRegionOutput<W>,
+    bars: Vec<Option<ProgressBarState>>,
+    next_print: Instant,
+    drawn_lines: usize,
+    guard: Option<CleanupGuard>,
+}
+
+struct ProgressBarState {
+    label: String,
+    progress: git::Progress,
+    rate: RateEstimate,
+    rate_value: Option<f32>,
+}
+
+impl<W: Write> ProgressRegion<W> {
+    /// Creates region that draws progress bars to the terminal `output`.
+    pub fn terminal(output: ProgressOutput<W>, now: Instant) -> Self {
+        Self::new(ProgressRegionOutput::Terminal(output), now)
+    }
+
+    /// Creates region that only prints a line to `output` when an operation
+    /// finishes.
+    pub fn log(output: W) -> Self {
+        Self::new(ProgressRegionOutput::Log(output), Instant::now())
+    }
+
+    /// Creates region that doesn't print anything.
+    pub fn hidden() -> Self {
+        Self::new(ProgressRegionOutput::Hidden, Instant::now())
+    }
+
+    fn new(output: ProgressRegionOutput<W>, now: Instant) -> Self {
+        let state = ProgressRegionState {
+            output,
+            bars: vec![],
+            next_print: now + INITIAL_DELAY,
+            drawn_lines: 0,
+            guard: None,
+        };
+        ProgressRegion {
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Adds a progress bar for a new operation labeled as `label`.
+    pub fn add_bar(&self, label: impl Into<String>) -> ProgressBar<'_, W> {
+        let mut state = self.state.lock().unwrap();
+        state.bars.push(Some(ProgressBarState {
+            label: label.into(),
+            progress: git::Progress {
+                bytes_downloaded: None,
+                overall: 0.0,
+            },
+            rate: RateEstimate::new(),
+            rate_value: None,
+        }));
+        ProgressBar {
+            region: self,
+            index: state.bars.len() - 1,
+            message: None,
+        }
+    }
+}
+
+impl<W: Write> Drop for ProgressRegion<W> {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap();
+        _ = state.clear();
+        state.guard = None;
+    }
+}
+
+impl<W: Write> ProgressRegionState<W> {
+    fn update_bar(&mut self, index: usize, now: Instant, progress: &git::Progress) {
+        let Some(bar) = &mut self.bars[index] else {
+            return;
+        };
+        bar.rate_value = progress
+            .bytes_downloaded
+            .and_then(|x| bar.rate.update(now, x));
+        bar.progress = git::Progress {
+            bytes_downloaded: progress.bytes_downloaded,
+            overall: progress.overall,
+        };
+        if !matches!(self.output, ProgressRegionOutput::Terminal(_)) || now < self.next_print {
+            return;
+        }
+        self.next_print = now + Duration::from_secs(1) / UPDATE_HZ;
+        _ = self.draw();
+    }
+
+    fn finish_bar(&mut self, index: usize, message: Option<&str>) {
+        if self.bars[index].take().is_none() {
+            return;
+        }
+        if self.bars.iter().all(Option::is_none) {
+            self.bars.clear();
+        }
+        let Some(message) = message else {
+            if self.drawn_lines > 0 {
+                _ = self.draw();
+            }
+            return;
+        };
+        match &mut self.output {
+            ProgressRegionOutput::Terminal(_) => {
+                // Print the message above the region, and redraw the remaining
+                // bars below it.
+                let was_drawn = self.drawn_lines > 0;
+                _ = self.clear();
+                if let ProgressRegionOutput::Terminal(output) = &mut self.output {
+                    _ = writeln!(output, "{message}");
+                    _ = output.flush();
+                }
+                if was_drawn {
+                    _ = self.draw();
+                }
+            }
+            ProgressRegionOutput::Log(output) => {
+                _ = writeln!(output, "{message}");
+                _ = output.flush();
+            }
+            ProgressRegionOutput::Hidden => {}
+        }
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        use std::fmt::Write as _;
+
+        let ProgressRegionOutput::Terminal(output) = &mut self.output else {
+            return Ok(());
+        };
+        if self.guard.is_none() {
+            let guard = output.output_guard(crossterm::cursor::Show.to_string());
+            let guard = CleanupGuard::new(move || {
+                drop(guard);
+            });
+            write!(output, "{}", crossterm::cursor::Hide)?;
+            self.guard = Some(guard);
+        }
+
+        let width = output.term_width().map(usize::from).unwrap_or(0);
+        let bars = self.bars.iter().flatten().collect_vec();
+        let label_width = bars
+            .iter()
+            .map(|bar| bar.label.width())
+            .max()
+            .unwrap_or(0)
+            .min(width / 3);
+        let mut buffer = String::new();
+        write!(buffer, "\r").unwrap();
+        if self.drawn_lines > 0 {
+            write!(buffer, "{}", MoveToPreviousLine(to_u16(self.drawn_lines))).unwrap();
+        }
+        for bar in &bars {
+            let (label, label_actual_width) = text_util::elide_end(&bar.label, "...", label_width);
+            let padding = label_width - label_actual_width;
+            write!(buffer, "{label}{:padding$} ", "").unwrap();
+            let bar_width = width.saturating_sub(label_width + 1);
+            write_progress(&mut buffer, &bar.progress, bar.rate_value, bar_width);
+            writeln!(buffer, "{}", Clear(ClearType::UntilNewLine)).unwrap();
+        }
+        write!(buffer, "{}", Clear(ClearType::FromCursorDown)).unwrap();
+        write!(output, "{buffer}")?;
+        output.flush()?;
+        self.drawn_lines = bars.len();
+        Ok(())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        let ProgressRegionOutput::Terminal(output) = &mut self.output else {
+            return Ok(());
+        };
+        if self.drawn_lines == 0 {
+            return Ok(());
+        }
+        write!(
+            output,
+            "\r{}{}",
+            MoveToPreviousLine(to_u16(self.drawn_lines)),
+            Clear(ClearType::FromCursorDown)
+        )?;
+        output.flush()?;
+        self.drawn_lines = 0;
+        Ok(())
+    }
+}
+
+fn to_u16(lines: usize) -> u16 {
+    lines.try_into().unwrap_or(u16::MAX)
+}
+
+/// Handle to a line in [`ProgressRegion`]. The line is removed from the region
+/// when dropped.
+pub struct ProgressBar<'a, W: Write> {
+    region: &'a ProgressRegion<W>,
+    index: usize,
+    message: Option<String>,
+}
+
+impl<W: Write> ProgressBar<'_, W> {
+    pub fn update(&self, now: Instant, progress: &git::Progress) {
+        let mut state = self.region.state.lock().unwrap();
+        state.update_bar(self.index, now, progress);
+    }
+
+    /// Removes the progress bar, and prints `message` in place of it.
+    pub fn finish(mut self, message: impl Into<String>) {
+        self.message = Some(message.into());
+    }
+}
+
+impl<W: Write> Drop for ProgressBar<'_, W> {
+    fn drop(&mut self) {
+        let mut state = self.region.state.lock().unwrap();
+        state.finish_bar(self.index, self.message.as_deref());
+    }
+}
+
 pub fn snapshot_progress(ui: &Ui) -> Option<impl Fn(&RepoPath) + '_> {
     struct State {
         guard: Option<OutputGuard>,
@@ -215,10 +459,42 @@ pub fn snapshot_progress(ui: &Ui) -> Option<impl Fn(&RepoPath) + '_> {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use insta::assert_snapshot;
 
     use super::*;
 
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl SharedBuffer {
+        /// Takes the output written so far, with control characters escaped.
+        fn take(&self) -> String {
+            String::from_utf8(std::mem::take(&mut *self.0.lock().unwrap()))
+                .unwrap()
+                .replace('\x1b', "\\e")
+                .replace('\r', "\\r")
+        }
+    }
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn new_progress(overall: f32) -> git::Progress {
+        git::Progress {
+            bytes_downloaded: None,
+            overall,
+        }
+    }
+
     #[test]
     fn test_bar() {
         let mut buf = String::new();
@@ -270,4 +546,62 @@ mod tests {
         // previous output, so we don't get an update here
         assert_snapshot!(update(Duration::from_millis(30), 0.40), @"");
     }
+
+    #[test]
+    fn test_region() {
+        let buf = SharedBuffer::default();
+        let start = Instant::now();
+        let region = ProgressRegion::terminal(ProgressOutput::for_test(buf.clone(), 30), start);
+        let origin = region.add_bar("origin");
+        let upstream = region.add_bar("upstream");
+
+        // First output is after the initial delay
+        origin.update(start, &new_progress(0.1));
+        assert_snapshot!(buf.take(), @"");
+        origin.update(start + INITIAL_DELAY, &new_progress(0.5));
+        assert_snapshot!(buf.take(), @r"
+        \e[?25l\rorigin    50% [███████       ]\e[K
+        upstream   0% [              ]\e[K
+        \e[J
+        ");
+        // Updates are rate limited across all bars
+        upstream.update(start + INITIAL_DELAY, &new_progress(0.2));
+        assert_snapshot!(buf.take(), @"");
+        // The whole region is redrawn in place
+        upstream.update(start + INITIAL_DELAY * 2, &new_progress(0.3));
+        assert_snapshot!(buf.take(), @r"
+        \r\e[2Forigin    50% [███████       ]\e[K
+        upstream  30% [████▎         ]\e[K
+        \e[J
+        ");
+
+        // The message is printed above the remaining bars
+        origin.finish("Fetched from origin");
+        assert_snapshot!(buf.take(), @r"
+        \r\e[2F\e[JFetched from origin
+        \rupstream  30% [████▎         ]\e[K
+        \e[J
+        ");
+        // Dropped bar is just removed
+        drop(upstream);
+        assert_snapshot!(buf.take(), @r"\r\e[1F\e[J");
+        drop(region);
+        assert_snapshot!(buf.take(), @"");
+    }
+
+    #[test]
+    fn test_region_log() {
+        let buf = SharedBuffer::default();
+        let region = ProgressRegion::log(buf.clone());
+        let origin = region.add_bar("origin");
+        let upstream = region.add_bar("upstream");
+        origin.update(Instant::now() + INITIAL_DELAY, &new_progress(0.5));
+        upstream.finish("Fetched from upstream");
+        origin.finish("Fetched from origin");
+        drop(region);
+        assert_snapshot!(buf.take(), @r"
+        Fetched from upstream
+        Fetched from origin
+        ");
+    }
 }
@@ -0,0 +1,172 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! On-disk cache of evaluated revsets.
+//!
+//! Evaluating `::immutable_heads()` and the like may walk most of the history
+//! of a large repo. Since the result only depends on the repo at a specific
+//! operation, it can be shared by all commands run at that operation. The
+//! evaluated set is stored as a bitset of commit index positions.
+
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use jj_lib::backend::CommitId;
+use jj_lib::content_hash::blake2b_hash;
+use jj_lib::default_index::AsCompositeIndex as _;
+use jj_lib::default_index::CompositeIndex;
+use jj_lib::default_index::DefaultReadonlyIndex;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::WorkspaceId;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::revset::Revset;
+use jj_lib::revset::RevsetContainingFn;
+use jj_lib::revset::RevsetEvaluationError;
+use jj_lib::revset::UserRevsetExpression;
+use tempfile::NamedTempFile;
+
+/// Cache of revsets evaluated at the operations of a repo.
+#[derive(Clone, Debug)]
+pub struct RevsetCache {
+    dir: PathBuf,
+}
+
+impl RevsetCache {
+    /// Creates cache stored in the given repo directory.
+    pub fn new(repo_path: &Path) -> Self {
+        RevsetCache {
+            dir: repo_path.join("revset_cache"),
+        }
+    }
+
+    /// Removes all cached sets. This must be called when the index is rebuilt
+    /// since the sets refer to index positions.
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Returns function that tests whether a commit is contained in the
+    /// `expression` evaluated at the operation of `repo`.
+    ///
+    /// If the set isn't cached yet, it's evaluated by `evaluate` and stored.
+    pub fn containing_fn<'a, E>(
+        &self,
+        repo: &'a ReadonlyRepo,
+        workspace_id: &WorkspaceId,
+        expression: &UserRevsetExpression,
+        evaluate: impl FnOnce() -> Result<Box<dyn Revset + 'a>, E>,
+    ) -> Result<Box<RevsetContainingFn<'a>>, E> {
+        let Some(index) = repo
+            .readonly_index()
+            .as_any()
+            .downcast_ref::<DefaultReadonlyIndex>()
+        else {
+            return Ok(evaluate()?.containing_fn());
+        };
+        let index = index.as_composite();
+        // Symbols such as bookmark names are resolved against the view, and
+        // `@` against the workspace, so both must be part of the key.
+        let op_dir = self.dir.join(repo.op_id().hex());
+        let path = op_dir.join(hash_key(&[
+            format!("{expression:?}"),
+            workspace_id.as_str().to_owned(),
+        ]));
+        let cached = fs::read(&path)
+            .ok()
+            .and_then(|data| decode_bitset(data, index.num_commits()));
+        let bitset = match cached {
+            Some(bitset) => bitset,
+            None => {
+                let revset = evaluate()?;
+                let Ok(bitset) = build_bitset(revset.as_ref(), index) else {
+                    // Let the caller observe the error when testing commits.
+                    return Ok(revset.containing_fn());
+                };
+                self.insert(&op_dir, &path, &encode_bitset(&bitset, index.num_commits()));
+                bitset
+            }
+        };
+        Ok(Box::new(move |commit_id: &CommitId| {
+            let Some(pos) = index.commit_id_to_pos(commit_id) else {
+                return Ok(false);
+            };
+            let pos = usize::try_from(pos.value()).unwrap();
+            Ok(bitset
+                .get(pos / 8)
+                .is_some_and(|byte| byte & (1 << (pos % 8)) != 0))
+        }))
+    }
+
+    fn insert(&self, op_dir: &Path, path: &Path, data: &[u8]) {
+        if !op_dir.exists() {
+            // Sets evaluated at the other operations are unlikely to be used
+            // again.
+            _ = fs::remove_dir_all(&self.dir);
+            if fs::create_dir_all(op_dir).is_err() {
+                return;
+            }
+        }
+        // Write to a temporary file first so concurrent commands never see a
+        // partially written entry.
+        let Ok(mut temp_file) = NamedTempFile::new_in(op_dir) else {
+            return;
+        };
+        if temp_file.write_all(data).is_ok() {
+            _ = temp_file.persist(path);
+        }
+    }
+}
+
+fn build_bitset(
+    revset: &dyn Revset,
+    index: &CompositeIndex,
+) -> Result<Vec<u8>, RevsetEvaluationError> {
+    let num_commits = usize::try_from(index.num_commits()).unwrap();
+    let mut bitset = vec![0; num_commits.div_ceil(8)];
+    for commit_id in revset.iter() {
+        if let Some(pos) = index.commit_id_to_pos(&commit_id?) {
+            let pos = usize::try_from(pos.value()).unwrap();
+            bitset[pos / 8] |= 1 << (pos % 8);
+        }
+    }
+    Ok(bitset)
+}
+
+fn encode_bitset(bitset: &[u8], num_commits: u32) -> Vec<u8> {
+    let mut data = num_commits.to_le_bytes().to_vec();
+    data.extend_from_slice(bitset);
+    data
+}
+
+fn decode_bitset(mut data: Vec<u8>, num_commits: u32) -> Option<Vec<u8>> {
+    let header = data.get(..4)?;
+    if u32::from_le_bytes(header.try_into().unwrap()) != num_commits {
+        return None;
+    }
+    let bitset = data.split_off(4);
+    (bitset.len() == usize::try_from(num_commits).unwrap().div_ceil(8)).then_some(bitset)
+}
+
+fn hash_key(parts: &[String]) -> String {
+    blake2b_hash(parts)[..16]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
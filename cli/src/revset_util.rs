@@ -22,6 +22,7 @@ use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetError;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
@@ -42,8 +43,10 @@ use jj_lib::revset::RevsetParseError;
 use jj_lib::revset::RevsetResolutionError;
 use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::revset::UserRevsetExpression;
+use jj_lib::str_util::StringPattern;
 use thiserror::Error;
 
+use crate::command_error::config_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::formatter::Formatter;
@@ -169,6 +172,13 @@ pub fn load_revset_aliases(
 ) -> Result<RevsetAliasesMap, CommandError> {
     let table_name = ConfigNamePathBuf::from_iter(["revset-aliases"]);
     let mut aliases_map = RevsetAliasesMap::new();
+    // Defined first so it could still be overridden by the config.
+    aliases_map
+        .insert(
+            "configured_immutable_heads()",
+            configured_immutable_heads(stacked_config)?,
+        )
+        .unwrap();
     // Load from all config layers in order. 'f(x)' in default layer should be
     // overridden by 'f(a)' in user.
     for layer in stacked_config.layers() {
@@ -202,6 +212,40 @@ pub fn load_revset_aliases(
     Ok(aliases_map)
 }
 
+/// Builds the `configured_immutable_heads()` expression from the
+/// `immutable-heads.remotes` and `immutable-heads.bookmarks` patterns.
+///
+/// Each pattern is passed to the `immutable_remote_heads(remote)` or
+/// `immutable_bookmark_heads(name)` alias, so users can change what a pattern
+/// contributes by redefining these aliases.
+fn configured_immutable_heads(stacked_config: &StackedConfig) -> Result<String, CommandError> {
+    let mut heads = vec![];
+    for (key, function) in [
+        ("remotes", "immutable_remote_heads"),
+        ("bookmarks", "immutable_bookmark_heads"),
+    ] {
+        let name = ConfigNamePathBuf::from_iter(["immutable-heads", key]);
+        let patterns = stacked_config
+            .get::<Vec<String>>(&name)
+            .optional()?
+            .unwrap_or_default();
+        for src in patterns {
+            let pattern = StringPattern::parse(&src).map_err(|err| {
+                config_error_with_message(format!("Error parsing '{src}' for {name}"), err)
+            })?;
+            // A valid prefix is the kind of the pattern.
+            let kind = src.split_once(':').map_or("exact", |(kind, _)| kind);
+            let literal = pattern.as_str().replace('\\', r"\\").replace('"', r#"\""#);
+            heads.push(format!(r#"{function}({kind}:"{literal}")"#));
+        }
+    }
+    if heads.is_empty() {
+        Ok("none()".to_owned())
+    } else {
+        Ok(heads.join(" | "))
+    }
+}
+
 /// Wraps the given `IdPrefixContext` in `SymbolResolver` to be passed in to
 /// `evaluate()`.
 pub fn default_symbol_resolver<'a>(
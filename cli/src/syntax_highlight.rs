@@ -0,0 +1,224 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Syntax highlighting of file contents.
+//!
+//! Highlighted ranges are written with `syntax <kind>` labels, so they can be
+//! styled by the `colors` config like any other output.
+
+use std::io;
+use std::ops::Range;
+
+use jj_lib::repo_path::RepoPath;
+
+use crate::formatter::Formatter;
+
+/// Byte range within a line, and the kind of syntax element it contains.
+pub type SyntaxSpan = (Range<usize>, &'static str);
+
+/// Syntax highlighting of a file, line by line.
+#[derive(Clone, Debug, Default)]
+pub struct HighlightedLines {
+    /// Sorted, non-overlapping spans of each line. Ranges are relative to the
+    /// start of the line.
+    lines: Vec<Vec<SyntaxSpan>>,
+}
+
+impl HighlightedLines {
+    /// Highlights the `content` of the file at `path`.
+    ///
+    /// The result is empty if the syntax of the file can't be detected, the
+    /// content isn't UTF-8 text, or the binary is built without the
+    /// `syntax-highlight` feature.
+    pub fn new(path: &RepoPath, content: &[u8]) -> Self {
+        #[cfg(feature = "syntax-highlight")]
+        let lines = highlight_lines(path, content).unwrap_or_default();
+        #[cfg(not(feature = "syntax-highlight"))]
+        let lines = {
+            let _ = (path, content);
+            vec![]
+        };
+        HighlightedLines { lines }
+    }
+
+    /// Returns the spans of the line at 0-based `index`.
+    pub fn line(&self, index: usize) -> &[SyntaxSpan] {
+        self.lines.get(index).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Writes `text` which starts at byte `offset` of a line, labeling the parts
+/// covered by `spans`. Each part is written by `write_text()`, which may add
+/// inner labels.
+pub fn write_highlighted(
+    formatter: &mut dyn Formatter,
+    spans: &[SyntaxSpan],
+    offset: usize,
+    text: &[u8],
+    mut write_text: impl FnMut(&mut dyn Formatter, &[u8]) -> io::Result<()>,
+) -> io::Result<()> {
+    let end = offset + text.len();
+    let mut pos = offset;
+    for (range, kind) in spans {
+        if range.end <= pos {
+            continue;
+        }
+        if range.start >= end {
+            break;
+        }
+        if range.start > pos {
+            write_text(formatter, &text[pos - offset..range.start - offset])?;
+            pos = range.start;
+        }
+        let span_end = range.end.min(end);
+        let part = &text[pos - offset..span_end - offset];
+        formatter.with_label("syntax", |formatter| {
+            formatter.with_label(kind, |formatter| write_text(formatter, part))
+        })?;
+        pos = span_end;
+    }
+    if pos < end {
+        write_text(formatter, &text[pos - offset..])?;
+    }
+    Ok(())
+}
+
+/// Files larger than this aren't highlighted. Highlighting them would be too
+/// slow to be useful.
+pub const MAX_HIGHLIGHT_SIZE: usize = 1024 * 1024;
+
+/// Top-level TextMate scopes which are mapped to labels of the same name.
+#[cfg(feature = "syntax-highlight")]
+const SYNTAX_KINDS: &[&str] = &[
+    "comment", "constant", "entity", "keyword", "storage", "string", "support", "variable",
+];
+
+#[cfg(feature = "syntax-highlight")]
+fn highlight_lines(path: &RepoPath, content: &[u8]) -> Option<Vec<Vec<SyntaxSpan>>> {
+    use once_cell::sync::Lazy;
+    use syntect::parsing::ParseState;
+    use syntect::parsing::Scope;
+    use syntect::parsing::ScopeStack;
+    use syntect::parsing::SyntaxSet;
+    use syntect::util::LinesWithEndings;
+
+    static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+    static KIND_SCOPES: Lazy<Vec<(Scope, &str)>> = Lazy::new(|| {
+        SYNTAX_KINDS
+            .iter()
+            .map(|&kind| (Scope::new(kind).unwrap(), kind))
+            .collect()
+    });
+
+    if content.len() > MAX_HIGHLIGHT_SIZE {
+        return None;
+    }
+    let text = std::str::from_utf8(content).ok()?;
+    let (_, file_name) = path.split()?;
+    let file_name = file_name.as_internal_str();
+    let syntax = file_name
+        .rsplit_once('.')
+        .and_then(|(_, extension)| SYNTAX_SET.find_syntax_by_extension(extension))
+        // Some syntaxes are registered by file name, e.g. "Makefile".
+        .or_else(|| SYNTAX_SET.find_syntax_by_extension(file_name))
+        .or_else(|| SYNTAX_SET.find_syntax_by_first_line(text))?;
+
+    // The innermost scope of a known kind determines the label.
+    let current_kind = |stack: &ScopeStack| {
+        stack.as_slice().iter().rev().find_map(|&scope| {
+            KIND_SCOPES
+                .iter()
+                .find(|(kind_scope, _)| kind_scope.is_prefix_of(scope))
+                .map(|&(_, kind)| kind)
+        })
+    };
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut lines = vec![];
+    for line in LinesWithEndings::from(text) {
+        let ops = state.parse_line(line, &SYNTAX_SET).ok()?;
+        let mut spans = vec![];
+        let mut start = 0;
+        for (pos, op) in &ops {
+            push_span(&mut spans, start..*pos, current_kind(&stack));
+            stack.apply(op).ok()?;
+            start = *pos;
+        }
+        push_span(&mut spans, start..line.len(), current_kind(&stack));
+        lines.push(spans);
+    }
+    Some(lines)
+}
+
+/// Appends `range` labeled as `kind`, merging it into the last span if
+/// possible.
+#[cfg(feature = "syntax-highlight")]
+fn push_span(spans: &mut Vec<SyntaxSpan>, range: Range<usize>, kind: Option<&'static str>) {
+    let Some(kind) = kind.filter(|_| !range.is_empty()) else {
+        return;
+    };
+    match spans.last_mut() {
+        Some((last_range, last_kind)) if last_range.end == range.start && *last_kind == kind => {
+            last_range.end = range.end;
+        }
+        _ => spans.push((range, kind)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::PlainTextFormatter;
+
+    #[test]
+    fn test_write_highlighted() {
+        let spans = [(2..4, "keyword"), (6..10, "string")];
+        let write = |offset: usize, text: &[u8]| {
+            let mut output = vec![];
+            let mut formatter = PlainTextFormatter::new(&mut output);
+            write_highlighted(&mut formatter, &spans, offset, text, |formatter, text| {
+                write!(formatter, "[{}]", String::from_utf8_lossy(text))
+            })
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        assert_eq!(write(0, b"0123456789ab"), "[01][23][45][6789][ab]");
+        assert_eq!(write(3, b"3456"), "[3][45][6]");
+        assert_eq!(write(10, b"ab"), "[ab]");
+        assert_eq!(write(0, b""), "");
+    }
+
+    #[test]
+    #[cfg(feature = "syntax-highlight")]
+    fn test_highlighted_lines() {
+        let lines = HighlightedLines::new(
+            RepoPath::from_internal_string("src/main.rs"),
+            b"// comment\nfn main() {}\n",
+        );
+        assert_eq!(lines.line(0), [(0..11, "comment")]);
+        assert_eq!(lines.line(1), [(0..2, "storage"), (3..7, "entity")]);
+        assert!(lines.line(2).is_empty());
+
+        // Unknown syntax
+        let lines = HighlightedLines::new(
+            RepoPath::from_internal_string("file.unknown"),
+            b"fn main() {}\n",
+        );
+        assert!(lines.line(0).is_empty());
+        // Non-UTF-8 content
+        let lines =
+            HighlightedLines::new(RepoPath::from_internal_string("main.rs"), b"fn \xff() {}\n");
+        assert!(lines.line(0).is_empty());
+    }
+}
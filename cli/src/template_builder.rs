@@ -15,12 +15,13 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::io;
+use std::iter;
 
 use itertools::Itertools as _;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::dsl_util::AliasExpandError as _;
-use jj_lib::time_util::DatePattern;
+use jj_lib::time_util::DatePatternContext;
 
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
@@ -29,6 +30,7 @@ use crate::template_parser::BinaryOp;
 use crate::template_parser::ExpressionKind;
 use crate::template_parser::ExpressionNode;
 use crate::template_parser::FunctionCallNode;
+use crate::template_parser::LambdaNode;
 use crate::template_parser::TemplateAliasesMap;
 use crate::template_parser::TemplateDiagnostics;
 use crate::template_parser::TemplateParseError;
@@ -488,6 +490,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
                     property,
                     function,
                     L::wrap_string,
+                    L::wrap_string_list,
                 )
             }
             CoreTemplatePropertyKind::Boolean(property) => {
@@ -1013,11 +1016,29 @@ fn builtin_timestamp_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
     let mut map = TemplateBuildMethodFnMap::<L, Timestamp>::new();
     map.insert(
         "ago",
-        |_language, _diagnostics, _build_ctx, self_property, function| {
-            function.expect_no_arguments()?;
+        |language, diagnostics, build_ctx, self_property, function| {
+            let ([], [precision_node, locale_node]) = function.expect_arguments()?;
+            let precision = precision_node
+                .map(|node| expect_usize_expression(language, diagnostics, build_ctx, node))
+                .transpose()?;
+            let lang = match locale_node {
+                Some(node) => template_parser::expect_string_literal_with(node, |locale, span| {
+                    time_util::relative_timestamp_language(locale)
+                        .ok_or_else(|| TemplateParseError::expression("Unsupported locale", span))
+                })?,
+                None => time_util::relative_timestamp_language_from_env(),
+            };
             let now = Timestamp::now();
-            let format = timeago::Formatter::new();
+            let format = timeago::Formatter::with_language(lang);
             let out_property = self_property.and_then(move |timestamp| {
+                let mut format = format.clone();
+                if let Some(precision) = &precision {
+                    let precision = precision.extract()?;
+                    if precision == 0 {
+                        return Err(TemplatePropertyError("Precision must be at least 1".into()));
+                    }
+                    format.num_items(precision);
+                }
                 Ok(time_util::format_duration(&timestamp, &now, &format)?)
             });
             Ok(L::wrap_string(out_property))
@@ -1072,14 +1093,16 @@ fn builtin_timestamp_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
         "after",
         |_language, _diagnostics, _build_ctx, self_property, function| {
             let [date_pattern_node] = function.expect_exact_arguments()?;
-            let now = chrono::Local::now();
+            let context = DatePatternContext::from(chrono::Local::now());
             let date_pattern = template_parser::expect_string_literal_with(
                 date_pattern_node,
                 |date_pattern, span| {
-                    DatePattern::from_str_kind(date_pattern, function.name, now).map_err(|err| {
-                        TemplateParseError::expression("Invalid date pattern", span)
-                            .with_source(err)
-                    })
+                    context
+                        .parse_relative(date_pattern, function.name)
+                        .map_err(|err| {
+                            TemplateParseError::expression("Invalid date pattern", span)
+                                .with_source(err)
+                        })
                 },
             )?;
             let out_property = self_property.map(move |timestamp| date_pattern.matches(&timestamp));
@@ -1151,6 +1174,7 @@ pub fn build_formattable_list_method<'a, L, O>(
     // TODO: Generic L: WrapProperty<O> trait might be needed to support more
     // list operations such as first()/slice(). For .map(), a simple callback works.
     wrap_item: impl Fn(PropertyPlaceholder<O>) -> L::Property,
+    wrap_list: impl Fn(Box<dyn TemplateProperty<Output = Vec<O>> + 'a>) -> L::Property,
 ) -> TemplateParseResult<L::Property>
 where
     L: TemplateLanguage<'a> + ?Sized,
@@ -1172,6 +1196,17 @@ where
                 });
             L::wrap_template(Box::new(template))
         }
+        "filter" => {
+            let out_property = build_filter_operation(
+                language,
+                diagnostics,
+                build_ctx,
+                self_property,
+                function,
+                wrap_item,
+            )?;
+            wrap_list(Box::new(out_property))
+        }
         "map" => build_map_operation(
             language,
             diagnostics,
@@ -1192,6 +1227,7 @@ pub fn build_unformattable_list_method<'a, L, O>(
     self_property: impl TemplateProperty<Output = Vec<O>> + 'a,
     function: &FunctionCallNode,
     wrap_item: impl Fn(PropertyPlaceholder<O>) -> L::Property,
+    wrap_list: impl Fn(Box<dyn TemplateProperty<Output = Vec<O>> + 'a>) -> L::Property,
 ) -> TemplateParseResult<L::Property>
 where
     L: TemplateLanguage<'a> + ?Sized,
@@ -1204,6 +1240,17 @@ where
             L::wrap_integer(out_property)
         }
         // No "join"
+        "filter" => {
+            let out_property = build_filter_operation(
+                language,
+                diagnostics,
+                build_ctx,
+                self_property,
+                function,
+                wrap_item,
+            )?;
+            wrap_list(Box::new(out_property))
+        }
         "map" => build_map_operation(
             language,
             diagnostics,
@@ -1241,20 +1288,9 @@ where
     let item_placeholder = PropertyPlaceholder::new();
     let item_template = template_parser::expect_lambda_with(lambda_node, |lambda, _span| {
         let item_fn = || wrap_item(item_placeholder.clone());
-        let mut local_variables = build_ctx.local_variables.clone();
-        if let [name] = lambda.params.as_slice() {
-            local_variables.insert(name, &item_fn);
-        } else {
-            return Err(TemplateParseError::expression(
-                "Expected 1 lambda parameters",
-                lambda.params_span,
-            ));
-        }
-        let inner_build_ctx = BuildContext {
-            local_variables,
-            self_variable: build_ctx.self_variable,
-        };
-        expect_template_expression(language, diagnostics, &inner_build_ctx, &lambda.body)
+        build_lambda_expression(build_ctx, lambda, &[&item_fn], |build_ctx, body| {
+            expect_template_expression(language, diagnostics, build_ctx, body)
+        })
     })?;
     let list_template = ListPropertyTemplate::new(
         self_property,
@@ -1266,6 +1302,73 @@ where
     Ok(L::wrap_list_template(Box::new(list_template)))
 }
 
+/// Builds expression that extracts iterable property and keeps the items
+/// for which the predicate evaluates to true.
+///
+/// `wrap_item()` is the function to wrap a list item of type `O` as a property.
+fn build_filter_operation<'a, L, O, P>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    build_ctx: &BuildContext<L::Property>,
+    self_property: P,
+    function: &FunctionCallNode,
+    wrap_item: impl Fn(PropertyPlaceholder<O>) -> L::Property,
+) -> TemplateParseResult<impl TemplateProperty<Output = Vec<O>> + 'a>
+where
+    L: TemplateLanguage<'a> + ?Sized,
+    P: TemplateProperty + 'a,
+    P::Output: IntoIterator<Item = O>,
+    O: Clone + 'a,
+{
+    // Build a predicate with placeholder property, then evaluate it for each
+    // item.
+    let [lambda_node] = function.expect_exact_arguments()?;
+    let item_placeholder = PropertyPlaceholder::new();
+    let item_predicate = template_parser::expect_lambda_with(lambda_node, |lambda, _span| {
+        let item_fn = || wrap_item(item_placeholder.clone());
+        build_lambda_expression(build_ctx, lambda, &[&item_fn], |build_ctx, body| {
+            expect_boolean_expression(language, diagnostics, build_ctx, body)
+        })
+    })?;
+    let out_property = self_property.and_then(move |items| {
+        items
+            .into_iter()
+            .filter_map(|item| {
+                let matched =
+                    item_placeholder.with_value(item.clone(), || item_predicate.extract());
+                matched.map(|matched| matched.then_some(item)).transpose()
+            })
+            .collect()
+    });
+    Ok(out_property)
+}
+
+/// Builds lambda body expression with the parameters bound to the given
+/// `arg_fns`.
+fn build_lambda_expression<'i, P, T>(
+    build_ctx: &BuildContext<'i, P>,
+    lambda: &LambdaNode<'i>,
+    arg_fns: &[&'i dyn Fn() -> P],
+    build_body: impl FnOnce(&BuildContext<'i, P>, &ExpressionNode<'i>) -> TemplateParseResult<T>,
+) -> TemplateParseResult<T> {
+    if lambda.params.len() != arg_fns.len() {
+        return Err(TemplateParseError::expression(
+            format!("Expected {} lambda parameters", arg_fns.len()),
+            lambda.params_span,
+        ));
+    }
+    let mut local_variables = build_ctx.local_variables.clone();
+    local_variables.extend(iter::zip(
+        lambda.params.iter().copied(),
+        arg_fns.iter().copied(),
+    ));
+    let inner_build_ctx = BuildContext {
+        local_variables,
+        self_variable: build_ctx.self_variable,
+    };
+    build_body(&inner_build_ctx, &lambda.body)
+}
+
 fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFunctionFnMap<'a, L> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
     // code completion inside macro is quite restricted.
@@ -2340,6 +2443,32 @@ mod tests {
           |
           = Expected 1 lambda parameters
         "#);
+
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb\nc".lines().filter(|s| !s.contains("b"))"#),
+            @"a c");
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb\nc".lines().filter(|s| s.contains("b")).len()"#),
+            @"1");
+        insta::assert_snapshot!(
+            env.render_ok(r#""ax\nb\ncx".lines().filter(|s| s.contains("x")).join(",")"#),
+            @"ax,cx");
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb\nc".lines().filter(|s| empty).map(|s| s.upper())"#),
+            @"A B C");
+        // Nested filter operations
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb\nc".lines().filter(|s| "x\nb".lines().filter(|t| s.contains(t)))"#),
+            @"b");
+        // Not a boolean predicate
+        insta::assert_snapshot!(env.parse_err(r#""a".lines().filter(|s| s.len())"#), @r#"
+         --> 1:24
+          |
+        1 | "a".lines().filter(|s| s.len())
+          |                        ^-----^
+          |
+          = Expected expression of type "Boolean", but actual type is "Integer"
+        "#);
     }
 
     #[test]
@@ -2590,6 +2719,24 @@ mod tests {
           |
           = Invalid time format
         "#);
+
+        // Relative timestamps with precision and locale
+        let ago = env.render_ok(r#"t0.ago(2, "en_US.UTF-8")"#);
+        assert!(ago.ends_with(" ago"), "{ago}");
+        assert_eq!(ago.split_whitespace().count(), 5, "{ago}");
+        let ago = env.render_ok(r#"t0.ago(1, "de")"#);
+        assert!(ago.starts_with("vor "), "{ago}");
+        insta::assert_snapshot!(
+            env.render_ok(r#"t0.ago(0)"#),
+            @"<Error: Precision must be at least 1>");
+        insta::assert_snapshot!(env.parse_err(r#"t0.ago(1, "xx")"#), @r#"
+         --> 1:11
+          |
+        1 | t0.ago(1, "xx")
+          |           ^--^
+          |
+          = Unsupported locale
+        "#);
     }
 
     #[test]
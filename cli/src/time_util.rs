@@ -7,6 +7,8 @@ use chrono::Utc;
 use jj_lib::backend::Timestamp;
 use once_cell::sync::Lazy;
 use thiserror::Error;
+use timeago::languages::boxup;
+use timeago::BoxedLanguage;
 
 /// Parsed formatting items which should never contain an error.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -84,10 +86,15 @@ pub fn format_absolute_timestamp_with(
     Ok(datetime.format_with_items(format.items.iter()).to_string())
 }
 
-pub fn format_duration(
+/// Formats the timestamp as an RFC 2822 date, as used in email headers.
+pub fn format_rfc2822_timestamp(timestamp: &Timestamp) -> Result<String, TimestampOutOfRange> {
+    Ok(datetime_from_timestamp(timestamp)?.to_rfc2822())
+}
+
+pub fn format_duration<L: timeago::Language>(
     from: &Timestamp,
     to: &Timestamp,
-    format: &timeago::Formatter,
+    format: &timeago::Formatter<L>,
 ) -> Result<String, TimestampOutOfRange> {
     let duration = datetime_from_timestamp(to)?
         .signed_duration_since(datetime_from_timestamp(from)?)
@@ -95,3 +102,48 @@ pub fn format_duration(
         .map_err(|_: chrono::OutOfRangeError| TimestampOutOfRange)?;
     Ok(format.convert(duration))
 }
+
+/// Looks up the language of relative timestamps for a POSIX-style locale name
+/// such as `de` or `de_DE.UTF-8`.
+///
+/// Returns `None` if the language isn't supported.
+pub fn relative_timestamp_language(locale: &str) -> Option<BoxedLanguage> {
+    use timeago::languages::*;
+    let language = locale
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let language = match language.as_str() {
+        "c" | "posix" | "en" => boxup(english::English),
+        "be" => boxup(belarusian::Belarusian),
+        "da" => boxup(danish::Danish),
+        "de" => boxup(german::German),
+        "es" => boxup(spanish::Spanish),
+        "fr" => boxup(french::French),
+        "it" => boxup(italian::Italian),
+        "ja" => boxup(japanese::Japanese),
+        "pl" => boxup(polish::Polish),
+        "pt" => boxup(portuguese::Portuguese),
+        "ro" => boxup(romanian::Romanian),
+        "ru" => boxup(russian::Russian),
+        "sv" => boxup(swedish::Swedish),
+        "tr" => boxup(turkish::Turkish),
+        "uk" => boxup(ukrainian::Ukrainian),
+        "zh" => boxup(chinese::Chinese),
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Returns the language of relative timestamps for the locale set by the
+/// `LC_ALL`, `LC_TIME`, or `LANG` environment variable, falling back to
+/// English.
+pub fn relative_timestamp_language_from_env() -> BoxedLanguage {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|locale| !locale.is_empty())
+        .and_then(|locale| relative_timestamp_language(&locale))
+        .unwrap_or_else(|| boxup(timeago::English))
+}
@@ -29,6 +29,7 @@ use std::process::ChildStdin;
 use std::process::Stdio;
 use std::str::FromStr;
 use std::thread::JoinHandle;
+use std::time::Instant;
 
 use indoc::indoc;
 use itertools::Itertools as _;
@@ -47,6 +48,7 @@ use crate::formatter::FormatterFactory;
 use crate::formatter::HeadingLabeledWriter;
 use crate::formatter::LabeledWriter;
 use crate::formatter::PlainTextFormatter;
+use crate::progress::ProgressRegion;
 
 const BUILTIN_PAGER_NAME: &str = ":builtin";
 
@@ -451,6 +453,19 @@ impl Ui {
             .then(ProgressOutput::for_stderr)
     }
 
+    /// Creates region to display the progress of multiple concurrent
+    /// operations. If the progress indicator is disabled, a status line is
+    /// printed as each operation finishes instead.
+    pub fn progress_region(&self) -> ProgressRegion<std::io::Stderr> {
+        if self.use_progress_indicator() {
+            ProgressRegion::terminal(ProgressOutput::for_stderr(), Instant::now())
+        } else if self.quiet {
+            ProgressRegion::hidden()
+        } else {
+            ProgressRegion::log(io::stderr())
+        }
+    }
+
     /// Writer to print an update that's not part of the command's main output.
     pub fn status(&self) -> Box<dyn Write + '_> {
         if self.quiet {
@@ -618,6 +633,15 @@ impl Ui {
         rpassword::prompt_password(format!("{prompt}: "))
     }
 
+    /// Whether the output is shown on a terminal, either directly or through
+    /// the pager.
+    pub fn is_terminal(&self) -> bool {
+        match &self.output {
+            UiOutput::Terminal { stdout, .. } => stdout.is_terminal(),
+            UiOutput::Paged { .. } | UiOutput::BuiltinPaged { .. } => true,
+        }
+    }
+
     pub fn term_width(&self) -> usize {
         term_width().unwrap_or(80).into()
     }
@@ -0,0 +1,98 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recorded root directories of the workspaces of a repo.
+//!
+//! The view only knows the names of the workspaces, so the root directory of
+//! each workspace is recorded whenever a command is run in it. This lets
+//! commands operating on all workspaces find them on disk.
+//!
+//! Each root is stored in a file named by the hex-encoded workspace name,
+//! containing the absolute path of the root directory. Paths which aren't valid
+//! UTF-8 aren't recorded.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use jj_lib::op_store::WorkspaceId;
+use tempfile::NamedTempFile;
+
+/// Store of workspace root directories recorded in a repo.
+#[derive(Clone, Debug)]
+pub struct WorkspaceRoots {
+    dir: PathBuf,
+}
+
+impl WorkspaceRoots {
+    /// Creates store in the given repo directory.
+    pub fn new(repo_path: &Path) -> Self {
+        WorkspaceRoots {
+            dir: repo_path.join("workspace_roots"),
+        }
+    }
+
+    /// Records that the workspace is rooted at `root`.
+    ///
+    /// Failures to write are ignored since the root is recorded again the
+    /// next time a command is run in the workspace.
+    pub fn record(&self, workspace_id: &WorkspaceId, root: &Path) {
+        if self.get(workspace_id).as_deref() == Some(root) {
+            return;
+        }
+        let Some(content) = root.to_str() else {
+            return;
+        };
+        if fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+        // Write to a temporary file first so concurrent commands never see a
+        // partially written path.
+        let Ok(mut temp_file) = NamedTempFile::new_in(&self.dir) else {
+            return;
+        };
+        if temp_file.write_all(content.as_bytes()).is_ok() {
+            _ = temp_file.persist(self.file_path(workspace_id));
+        }
+    }
+
+    /// Returns the recorded root of the workspace, if any.
+    pub fn get(&self, workspace_id: &WorkspaceId) -> Option<PathBuf> {
+        let content = fs::read_to_string(self.file_path(workspace_id)).ok()?;
+        Some(PathBuf::from(content))
+    }
+
+    /// Removes the recorded root of the workspace.
+    pub fn forget(&self, workspace_id: &WorkspaceId) {
+        _ = fs::remove_file(self.file_path(workspace_id));
+    }
+
+    /// Moves the recorded root of a renamed workspace to its new name.
+    pub fn rename(&self, old_workspace_id: &WorkspaceId, new_workspace_id: &WorkspaceId) {
+        _ = fs::rename(
+            self.file_path(old_workspace_id),
+            self.file_path(new_workspace_id),
+        );
+    }
+
+    fn file_path(&self, workspace_id: &WorkspaceId) -> PathBuf {
+        let name: String = workspace_id
+            .as_str()
+            .bytes()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        self.dir.join(name)
+    }
+}
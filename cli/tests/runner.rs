@@ -13,9 +13,12 @@ mod test_absorb_command;
 mod test_acls;
 mod test_advance_bookmarks;
 mod test_alias;
+mod test_apply_command;
+mod test_archive_command;
 mod test_backout_command;
 mod test_bookmark_command;
 mod test_builtin_aliases;
+mod test_change_alias_command;
 mod test_commit_command;
 mod test_commit_template;
 mod test_completion;
@@ -31,9 +34,12 @@ mod test_edit_command;
 mod test_evolog_command;
 mod test_file_annotate_command;
 mod test_file_chmod_command;
+mod test_file_lock_command;
 mod test_file_show_command;
 mod test_file_track_untrack_commands;
 mod test_fix_command;
+mod test_forge_command;
+mod test_format_patch_command;
 mod test_generate_md_cli_help;
 mod test_git_clone;
 mod test_git_colocated;
@@ -58,15 +64,19 @@ mod test_parallelize_command;
 mod test_rebase_command;
 mod test_repo_change_report;
 mod test_resolve_command;
+mod test_resolve_divergence_command;
 mod test_restore_command;
+mod test_revset_command;
 mod test_revset_output;
 mod test_root;
+mod test_serve;
 mod test_shell_completion;
 mod test_show_command;
 mod test_simplify_parents_command;
 mod test_sparse_command;
 mod test_split_command;
 mod test_squash_command;
+mod test_stash_command;
 mod test_status_command;
 mod test_tag_command;
 mod test_templater;
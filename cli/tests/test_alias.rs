@@ -322,3 +322,64 @@ fn test_alias_in_repo_config() {
     );
     insta::assert_snapshot!(stdout, @r#"aliases.l = ['log', '-r@', '--no-graph', '-T"user alias\n"']"#);
 }
+
+#[test]
+fn test_alias_placeholders() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.add_config(
+        r#"
+    aliases.d = ["log", "--no-graph", "-T", "description", "-r", "description($1)"]
+    aliases.both = ["log", "--no-graph", "-T", "description", "-r", "$2 | $1"]
+    aliases.all = ["log", "--no-graph", "-T", "description", "-r", "$@"]
+    aliases.dollar = ["log", "--no-graph", "-T", '"$$1\n"', "-r", "$1"]
+    aliases.exec = ["util", "exec", "--", "echo", "$1"]
+    "#,
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "first\n"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "second\n"]);
+
+    // Placeholder embedded in a revset
+    let stdout = test_env.jj_cmd_success(&repo_path, &["d", "first"]);
+    insta::assert_snapshot!(stdout, @"first");
+
+    // Arguments not consumed by placeholders are appended
+    let stdout = test_env.jj_cmd_success(&repo_path, &["d", "first", "--reversed"]);
+    insta::assert_snapshot!(stdout, @"first");
+
+    // Placeholders can be reordered
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["both", "description(first)", "description(second)"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    second
+    first
+    ");
+
+    // $@ expands to all arguments
+    let stdout = test_env.jj_cmd_success(&repo_path, &["all", "description(second)"]);
+    insta::assert_snapshot!(stdout, @"second");
+
+    // $$ is a literal $
+    let stdout = test_env.jj_cmd_success(&repo_path, &["dollar", "@"]);
+    insta::assert_snapshot!(stdout, @"$1");
+
+    // Arguments after -- are passed through verbatim
+    let stdout = test_env.jj_cmd_success(&repo_path, &["exec", "foo"]);
+    insta::assert_snapshot!(stdout, @"$1 foo");
+
+    // Missing arguments
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["d"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Alias "d" expects argument $1, but none were given
+    Hint: The alias is defined as ["log", "--no-graph", "-T", "description", "-r", "description($1)"]
+    "#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["both", "@"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Alias "both" expects argument $2, but only 1 was given
+    Hint: The alias is defined as ["log", "--no-graph", "-T", "description", "-r", "$2 | $1"]
+    "#);
+}
@@ -0,0 +1,339 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::get_stderr_string;
+use crate::common::TestEnvironment;
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    let template = r#"separate(" ", change_id.short(), author, description.first_line()) ++ "\n""#;
+    test_env.jj_cmd_success(repo_path, &["log", "-T", template])
+}
+
+#[test]
+fn test_apply_mbox_round_trip() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\nb\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "add file1\n\nwith body"]);
+    std::fs::write(repo_path.join("file1"), "a\nc\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "x\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "commit",
+            "-m",
+            "modify file1, add file2",
+            "--author",
+            "Other <other@example.com>",
+        ],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["format-patch", "-r", "root()..@-", "-o", "../patches"],
+    );
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "apply",
+            "-d",
+            "root()",
+            "../patches/0001-add-file1.patch",
+            "../patches/0002-modify-file1-add-file2.patch",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Applied 2 patches:
+      mzvwutvl e6b8f61d add file1
+      zxsnswpr 1e6f20b6 modify file1, add file2
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  kkmpptxzrspx Test User <test.user@example.com>
+    ○  rlvkpnrzqnoo Other <other@example.com> modify file1, add file2
+    ○  qpvuntsmwlqt Test User <test.user@example.com> add file1
+    │ ○  zxsnswprywvt Other <other@example.com> modify file1, add file2
+    │ ○  mzvwutvlkqwt Test User <test.user@example.com> add file1
+    ├─╯
+    ◆  zzzzzzzzzzzz
+    ");
+
+    // The applied commits have the same contents as the originals
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--from", "@-", "--to", "description(file2) & ~::@"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "root()+ & ~::@",
+            "-T",
+            "description",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    add file1
+
+    with body
+    ");
+}
+
+#[test]
+fn test_apply_plain_diff_from_stdin() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "1\n2\n3\n4\n5\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+
+    let patch = "\
+--- a/file
++++ b/file
+@@ -3,2 +3,2 @@
+ 3
+-4
++four
+--- /dev/null
++++ b/new
+@@ -0,0 +1 @@
++new
+";
+    let (stdout, stderr) = test_env.jj_cmd_stdin_ok(&repo_path, &["apply", "-d", "@-"], patch);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Applied 1 patches:
+      kkmpptxz dea1026d (no description set)
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "-r", "children(@-) ~ @"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/file b/file
+    index 8a1218a102..0742a5ac1c 100644
+    --- a/file
+    +++ b/file
+    @@ -1,5 +1,5 @@
+     1
+     2
+     3
+    -4
+    +four
+     5
+    diff --git a/new b/new
+    new file mode 100644
+    index 0000000000..3e757656cf
+    --- /dev/null
+    +++ b/new
+    @@ -0,0 +1,1 @@
+    +new
+    ");
+
+    // Context that doesn't match can't be applied without blob hashes
+    let patch = "\
+--- a/file
++++ b/file
+@@ -1,2 +1,2 @@
+ x
+-2
++two
+";
+    let assert = test_env
+        .jj_cmd_stdin(&repo_path, &["apply"], patch)
+        .assert()
+        .code(1);
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    insta::assert_snapshot!(stderr, @"Error: Patch does not apply to file");
+}
+
+#[test]
+fn test_apply_three_way_fallback() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "1\n2\n3\n4\n5\n6\n7\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "patch"]);
+    std::fs::write(repo_path.join("file"), "1\n2\n3\n4\n5\n6\nseven\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["format-patch", "-r", "@", "-o", "../patches"]);
+
+    // Concurrent change to the context of the patch
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m", "other"]);
+    std::fs::write(repo_path.join("file"), "1\n2\n3\nfour\n5\n6\n7\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(base)", "-m", "conflicting"],
+    );
+    std::fs::write(repo_path.join("file"), "1\n2\n3\n4\n5\n6\nSEVEN\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "apply",
+            "-d",
+            "description(other)",
+            "../patches/0001-patch.patch",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Patch for file did not apply cleanly, falling back to 3-way merge
+    Applied 1 patches:
+      vruxwmqv 1f461de4 patch
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["file", "show", "-r", "children(description(other))", "file"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    1
+    2
+    3
+    four
+    5
+    6
+    seven
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "am",
+            "-d",
+            "description(conflicting)",
+            "../patches/0001-patch.patch",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Patch for file did not apply cleanly, falling back to 3-way merge
+    Applied 1 patches:
+      znkkpsqq b653ef93 (conflict) patch
+    New conflicts appeared in these commits:
+      znkkpsqq b653ef93 (conflict) patch
+    To resolve the conflicts, start by updating to it:
+      jj new znkkpsqq
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    ");
+}
+
+#[test]
+fn test_apply_date_without_author() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let patch = "\
+From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+Date: Sat, 3 Feb 2001 04:05:08 +0700
+Subject: [PATCH] add file
+
+---
+--- /dev/null
++++ b/file
+@@ -0,0 +1 @@
++a
+";
+    test_env.jj_cmd_stdin_ok(&repo_path, &["apply", "-d", "root()"], patch);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "description(file)",
+            "-T",
+            r#"author ++ " " ++ author.timestamp()"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"Test User <test.user@example.com> 2001-02-03 04:05:08.000 +07:00");
+}
+
+#[test]
+fn test_apply_executable_bit() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let mode_template = r#"diff.files().map(|entry| entry.new_mode()).join(" ")"#;
+    std::fs::write(repo_path.join("file"), "1\n2\n3\n4\n5\n6\n7\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "base"]);
+
+    // A patch that only changes the contents doesn't reset the mode of an
+    // executable file
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "contents"]);
+    std::fs::write(repo_path.join("file"), "one\n2\n3\n4\n5\n6\n7\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["format-patch", "-r", "@", "-o", "../patches"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m", "chmod"]);
+    test_env.jj_cmd_ok(&repo_path, &["file", "chmod", "x", "file"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "apply",
+            "-d",
+            "description(chmod)",
+            "../patches/0001-contents.patch",
+        ],
+    );
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "children(description(chmod))",
+            "-T",
+            mode_template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"executable");
+
+    // The mode change of a patch applied with a 3-way merge is kept
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m", "patch"]);
+    std::fs::write(repo_path.join("file"), "1\n2\n3\n4\n5\n6\nseven\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["file", "chmod", "x", "file"]);
+    test_env.jj_cmd_ok(&repo_path, &["format-patch", "-r", "@", "-o", "../patches"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m", "other"]);
+    std::fs::write(repo_path.join("file"), "1\n2\n3\nfour\n5\n6\n7\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "apply",
+            "-d",
+            "description(other)",
+            "../patches/0001-patch.patch",
+        ],
+    );
+    assert!(stderr.contains("falling back to 3-way merge"), "{stderr}");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "children(description(other)) & description(patch)",
+            "-T",
+            mode_template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"executable");
+}
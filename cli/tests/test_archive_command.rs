@@ -0,0 +1,135 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Read as _;
+
+use crate::common::TestEnvironment;
+
+/// Lists the entries of a tar archive with their mode, type, modification
+/// time, and contents.
+fn list_tar(archive: &[u8]) -> String {
+    assert_eq!(archive.len() % 512, 0);
+    let field = |header: &[u8], range: std::ops::Range<usize>| {
+        let field = &header[range];
+        let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        String::from_utf8(field[..end].to_vec()).unwrap()
+    };
+    let mut entries = vec![];
+    let mut blocks = archive.chunks(512);
+    while let Some(header) = blocks.next() {
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let size = usize::from_str_radix(&field(header, 124..135), 8).unwrap();
+        let contents: Vec<u8> = blocks
+            .by_ref()
+            .take(size.div_ceil(512))
+            .flatten()
+            .take(size)
+            .copied()
+            .collect();
+        let contents = if size > 20 {
+            format!("<{size} bytes>")
+        } else {
+            format!("{:?}", String::from_utf8(contents).unwrap())
+        };
+        entries.push(format!(
+            "{} {} {} {}{} {contents}",
+            field(header, 100..107),
+            header[156] as char,
+            field(header, 136..147),
+            field(header, 345..500),
+            field(header, 0..100),
+        ));
+    }
+    entries.join("\n")
+}
+
+/// Lists the entries in the central directory of a zip archive with their
+/// mode, compression method, and DOS date and time.
+fn list_zip(archive: &[u8]) -> String {
+    let u16_at = |pos: usize| u16::from_le_bytes(archive[pos..pos + 2].try_into().unwrap());
+    let u32_at = |pos: usize| u32::from_le_bytes(archive[pos..pos + 4].try_into().unwrap());
+    let end = archive.len() - 22;
+    assert_eq!(u32_at(end), 0x06054b50);
+    let mut pos = u32_at(end + 16) as usize;
+    let mut entries = vec![];
+    for _ in 0..u16_at(end + 10) {
+        assert_eq!(u32_at(pos), 0x02014b50);
+        let name_len = u16_at(pos + 28) as usize;
+        let name = std::str::from_utf8(&archive[pos + 46..pos + 46 + name_len]).unwrap();
+        entries.push(format!(
+            "{:o} {} {:04x} {:04x} {name}",
+            u32_at(pos + 38) >> 16,
+            u16_at(pos + 10),
+            u16_at(pos + 14),
+            u16_at(pos + 12),
+        ));
+        pos += 46 + name_len;
+    }
+    entries.join("\n")
+}
+
+#[test]
+fn test_archive() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::create_dir(repo_path.join("docs")).unwrap();
+    std::fs::write(repo_path.join("docs").join("a.md"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file"), "file\n".repeat(100)).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "first"]);
+    std::fs::write(repo_path.join("file"), "modified\n").unwrap();
+
+    // The format is inferred from the file name
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["archive", "-r@-", "-o", "out.tar"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let tar = std::fs::read(repo_path.join("out.tar")).unwrap();
+    insta::assert_snapshot!(list_tar(&tar), @r#"
+    0000644 0 07236620404 docs/a.md "a\n"
+    0000644 0 07236620404 file <500 bytes>
+    "#);
+
+    // A prefix is prepended to the paths and paths can be selected
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["archive", "-o", "out.tgz", "--prefix", "proj-1.0/", "docs"],
+    );
+    let mut tar = vec![];
+    flate2::read::GzDecoder::new(std::fs::File::open(repo_path.join("out.tgz")).unwrap())
+        .read_to_end(&mut tar)
+        .unwrap();
+    insta::assert_snapshot!(list_tar(&tar), @r#"0000644 0 07236620406 proj-1.0/docs/a.md "a\n""#);
+
+    // Archives of the same revision are identical
+    test_env.jj_cmd_ok(&repo_path, &["archive", "-r@-", "-o", "out1.zip"]);
+    test_env.jj_cmd_ok(&repo_path, &["archive", "-r@-", "-o", "out2.zip"]);
+    let zip = std::fs::read(repo_path.join("out1.zip")).unwrap();
+    assert_eq!(zip, std::fs::read(repo_path.join("out2.zip")).unwrap());
+    insta::assert_snapshot!(list_zip(&zip), @r"
+    100644 0 2a42 a8a4 docs/a.md
+    100644 8 2a42 a8a4 file
+    ");
+
+    // The format can be given explicitly, such as when writing to stdout
+    let assert = test_env
+        .jj_cmd(&repo_path, &["archive", "-o-", "--format=tar", "docs"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(list_tar(&assert.get_output().stdout), @r#"0000644 0 07236620411 docs/a.md "a\n""#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["archive", "-o", "out.bin"]);
+    insta::assert_snapshot!(stderr, @"Error: Cannot infer the archive format of out.bin; use --format");
+}
@@ -290,6 +290,8 @@ fn test_bookmark_move_matching() {
     let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bookmark", "move", "--from=::@"]);
     insta::assert_snapshot!(stderr, @r###"
     Moved 2 bookmarks to vruxwmqv a2781dd9 b1 c1 | (empty) head2
+      b1: f652c32197cf -> a2781dd9ee37
+      c1: f4f38657a3dd -> a2781dd9ee37
     Hint: Specify bookmark by name to update just one of the bookmarks.
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
@@ -336,6 +338,109 @@ fn test_bookmark_move_matching() {
     ├─╯
     ◆   000000000000
     "###);
+
+    // Move bookmarks matching a pattern backwards and sideways at once
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "bookmark",
+            "move",
+            "--to=b1",
+            "--allow-backwards",
+            "glob:?1",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Moved 2 bookmarks to zsuskuln f652c321 a1 b1 c1 | (empty) (no description set)
+      a1: 6b5e840ea72b -> f652c32197cf
+      c1: f4f38657a3dd -> f652c32197cf
+    ");
+}
+
+#[test]
+fn test_bookmark_protected() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"bookmarks.protected = ["main", "glob:release/*"]"#);
+
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "create", "main", "release/1", "feature"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    // Moving forward is allowed
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bookmark", "move", "main", "--to=@"]);
+    insta::assert_snapshot!(stderr, @"Moved 1 bookmarks to kkmpptxz 4db490c8 main | (empty) (no description set)");
+
+    // Moving backwards requires --allow-protected in addition to --allow-backwards
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["bookmark", "move", "main", "--to=@-", "--allow-backwards"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Refusing to move protected bookmark backwards or sideways: main
+    Hint: Use --allow-protected to allow it.
+    ");
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["bookmark", "set", "main", "-r@-", "--allow-backwards"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Refusing to move protected bookmark backwards or sideways: main
+    Hint: Use --allow-protected to allow it.
+    ");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "bookmark",
+            "move",
+            "main",
+            "--to=@-",
+            "--allow-backwards",
+            "--allow-protected",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @"Moved 1 bookmarks to qpvuntsm 230dd059 feature main release/1 | (empty) (no description set)");
+
+    // Deleting, forgetting, and renaming protected bookmarks
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bookmark", "delete", "glob:*"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Refusing to delete protected bookmark: main
+    Hint: Use --allow-protected to allow it.
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bookmark", "forget", "release/1"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Refusing to forget protected bookmark: release/1
+    Hint: Use --allow-protected to allow it.
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bookmark", "rename", "main", "trunk"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Refusing to rename protected bookmark: main
+    Hint: Use --allow-protected to allow it.
+    ");
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "bookmark",
+            "delete",
+            "feature",
+            r#"--config=bookmarks.protected=["bad:main"]"#,
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Config error: Error parsing 'bad:main' for bookmarks.protected
+    Caused by: Invalid string pattern kind "bad:"
+    For help, see https://martinvonz.github.io/jj/latest/config/.
+    "#);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bookmark", "delete", "feature"]);
+    insta::assert_snapshot!(stderr, @"Deleted 1 bookmarks.");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "delete", "glob:*", "--allow-protected"],
+    );
+    insta::assert_snapshot!(stderr, @"Deleted 2 bookmarks.");
 }
 
 #[test]
@@ -456,6 +561,100 @@ fn test_bookmark_rename() {
     "###);
 }
 
+#[test]
+fn test_bookmark_rename_push() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Set up remote
+    let git_repo_path = test_env.env_root().join("git-repo");
+    git2::Repository::init_bare(git_repo_path).unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=commit-0"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "bremote"]);
+    test_env.jj_cmd_ok(&repo_path, &["git", "push", "--allow-new", "-b=bremote"]);
+
+    // Preview without changing anything
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "bookmark",
+            "rename",
+            "--push",
+            "--dry-run",
+            "bremote",
+            "bremote2",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Delete bookmark bremote from 79a5671f8af1
+      Add bookmark bremote2 to 79a5671f8af1
+    Dry-run requested, not renaming or pushing.
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    bremote: qpvuntsm 79a5671f (empty) commit-0
+      @origin: qpvuntsm 79a5671f (empty) commit-0
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "rename", "--push", "bremote", "bremote2"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Delete bookmark bremote from 79a5671f8af1
+      Add bookmark bremote2 to 79a5671f8af1
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    bremote2: qpvuntsm 79a5671f (empty) commit-0
+      @origin: qpvuntsm 79a5671f (empty) commit-0
+    ");
+
+    // A bookmark which was never pushed is just added on the remote
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "-r@", "blocal"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "rename", "--push", "blocal", "blocal2"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Add bookmark blocal2 to 79a5671f8af1
+    ");
+
+    // Existing remote bookmarks aren't overwritten
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "-r@", "bother"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "delete", "blocal2"]);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["bookmark", "rename", "--push", "bother", "blocal2"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Remote bookmark blocal2@origin already exists
+    Hint: Rename the bookmark without --push, or choose a different name.
+    ");
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["bookmark", "rename", "--dry-run", "bother", "bother2"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: the following required arguments were not provided:
+      --push
+
+    Usage: jj bookmark rename --push --dry-run <OLD> <NEW>
+
+    For more information, try '--help'.
+    ");
+}
+
 #[test]
 fn test_bookmark_rename_colocated() {
     let test_env = TestEnvironment::default();
@@ -0,0 +1,99 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_change_alias_set_delete() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=second"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["alias", "set", "fix-login", "-r@-"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Alias fix-login now points to qpvuntsm fa15625b (empty) first");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["alias", "set", "cur"]);
+    insta::assert_snapshot!(stderr, @"Alias cur now points to kkmpptxz 9ed53a4a (empty) second");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["alias", "set", "cur"]);
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["alias", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    cur: kkmpptxz 9ed53a4a (empty) second
+    fix-login: qpvuntsm fa15625b (empty) first
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  kkmpptxzrspx second cur
+    ○  qpvuntsmwlqt first fix-login
+    ◆  zzzzzzzzzzzz
+    ");
+
+    // The alias follows the change when it's rewritten
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-r", "alias:fix-login", "-m=first v2"],
+    );
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  kkmpptxzrspx second cur
+    ○  qpvuntsmwlqt first v2 fix-login
+    ◆  zzzzzzzzzzzz
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-r", "alias:fix-logn"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Revision "alias:fix-logn" doesn't exist
+    Hint: Did you mean "alias:fix-login"?
+    "#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["alias", "delete", "unknown"]);
+    insta::assert_snapshot!(stderr, @"Error: No such alias: unknown");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["alias", "delete", "fix-login"]);
+    insta::assert_snapshot!(stderr, @"Deleted 1 aliases.");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["alias", "list"]);
+    insta::assert_snapshot!(stdout, @"cur: kkmpptxz 6da38d08 (empty) second");
+}
+
+#[test]
+fn test_change_alias_abandoned() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=doomed"]);
+    test_env.jj_cmd_ok(&repo_path, &["alias", "set", "doomed"]);
+    test_env.jj_cmd_ok(&repo_path, &["abandon"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["alias", "list"]);
+    insta::assert_snapshot!(stdout, @"doomed: rlvkpnrzqnoo (no visible commits)");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r", "alias:doomed"]);
+    insta::assert_snapshot!(stdout, @"");
+}
+
+#[test]
+fn test_change_alias_undo() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["alias", "set", "foo"]);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["alias", "list"]);
+    insta::assert_snapshot!(stdout, @"");
+}
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &std::path::Path) -> String {
+    let template =
+        r#"change_id.short() ++ " " ++ description.first_line() ++ " " ++ change_aliases"#;
+    test_env.jj_cmd_success(repo_path, &["log", "-T", template])
+}
@@ -299,6 +299,51 @@ fn test_commit_paths() {
     ");
 }
 
+#[test]
+fn test_commit_only_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+
+    std::fs::write(workspace_path.join("file1"), "foo\n").unwrap();
+    std::fs::write(workspace_path.join("file2"), "bar\n").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["commit", "-m=initial"]);
+    std::fs::write(workspace_path.join("file1"), "foo2\n").unwrap();
+    std::fs::remove_file(workspace_path.join("file2")).unwrap();
+    std::fs::write(workspace_path.join("file3"), "baz\n").unwrap();
+
+    // Requires paths
+    let stderr = test_env.jj_cmd_cli_error(&workspace_path, &["commit", "-m=first", "--only"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the following required arguments were not provided:
+      <PATHS>...
+
+    Usage: jj commit --message <MESSAGE> --only <PATHS>...
+
+    For more information, try '--help'.
+    ");
+
+    test_env.jj_cmd_ok(&workspace_path, &["commit", "-m=first", "--only", "file1"]);
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &["diff", "--ignore-working-copy", "--summary", "-r", "@-"],
+    );
+    insta::assert_snapshot!(stdout, @"M file1");
+    // The other changes haven't been snapshotted yet
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &["diff", "--ignore-working-copy", "--summary"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+
+    // They end up in the new working-copy commit on the next snapshot
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r"
+    D file2
+    A file3
+    ");
+}
+
 #[test]
 fn test_commit_paths_warning() {
     let test_env = TestEnvironment::default();
@@ -77,6 +77,38 @@ fn test_log_parents() {
     "###);
 }
 
+#[test]
+fn test_log_list_filter() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "fix: first\n\nbody"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "feat: second"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "fix: third"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(first)", "description(second)", "@"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "create", "feature-a", "fix-b", "feature-c"],
+    );
+
+    let template = r#"
+    parents.filter(|c| c.description().starts_with("fix:")).map(|c| c.description().first_line())
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-T", template]);
+    insta::assert_snapshot!(stdout, @"fix: first fix: third");
+
+    let template = r#"
+    bookmarks.filter(|b| b.name().starts_with("feature")).join(",") ++ " " ++
+    bookmarks.filter(|b| b.name().contains("x")).len()
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-T", template]);
+    insta::assert_snapshot!(stdout, @"feature-a,feature-c 1");
+}
+
 #[test]
 fn test_log_author_timestamp() {
     let test_env = TestEnvironment::default();
@@ -110,6 +142,25 @@ fn test_log_author_timestamp_ago() {
         stdout.lines().all(|x| line_re.is_match(x)),
         "expected every line to match regex"
     );
+
+    let template = r#"author.timestamp().ago(2) ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-T", template]);
+    let line_re = Regex::new(r"^[0-9]+ years [0-9]+ [a-z]+ ago$").unwrap();
+    assert!(
+        stdout.lines().all(|x| line_re.is_match(x)),
+        "expected every line to match regex"
+    );
+
+    // The language is taken from the locale
+    let mut test_env = test_env;
+    test_env.add_env_var("LC_TIME", "de_DE.UTF-8");
+    let template = r#"author.timestamp().ago() ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-T", template]);
+    let line_re = Regex::new(r"^vor [0-9]+ Jahren$").unwrap();
+    assert!(
+        stdout.lines().all(|x| line_re.is_match(x)),
+        "expected every line to match regex"
+    );
 }
 
 #[test]
@@ -516,6 +567,8 @@ fn test_log_evolog_divergence() {
     insta::assert_snapshot!(stdout, @r###"
     @  qpvuntsm?? test.user@example.com 2001-02-03 08:05:08 ff309c29
     │  description 1
+    │ ○  qpvuntsm?? test.user@example.com 2001-02-03 08:05:10 6ba70e00
+    ├─╯  description 2
     ○  qpvuntsm hidden test.user@example.com 2001-02-03 08:05:08 485d52a9
     │  (no description set)
     ○  qpvuntsm hidden test.user@example.com 2001-02-03 08:05:07 230dd059
@@ -527,6 +580,8 @@ fn test_log_evolog_divergence() {
     insta::assert_snapshot!(stdout, @r###"
     [1m[38;5;2m@[0m  [1m[4m[38;5;1mq[24mpvuntsm[38;5;9m??[39m [38;5;3mtest.user@example.com[39m [38;5;14m2001-02-03 08:05:08[39m [38;5;12mf[38;5;8mf309c29[39m[0m
     │  [1mdescription 1[0m
+    │ ○  [1m[4m[38;5;1mq[0m[38;5;1mpvuntsm??[39m [38;5;3mtest.user@example.com[39m [38;5;6m2001-02-03 08:05:10[39m [1m[38;5;4m6[0m[38;5;8mba70e00[39m
+    ├─╯  description 2
     ○  [1m[39mq[0m[38;5;8mpvuntsm[39m hidden [38;5;3mtest.user@example.com[39m [38;5;6m2001-02-03 08:05:08[39m [1m[38;5;4m4[0m[38;5;8m85d52a9[39m
     │  [38;5;3m(no description set)[39m
     ○  [1m[39mq[0m[38;5;8mpvuntsm[39m hidden [38;5;3mtest.user@example.com[39m [38;5;6m2001-02-03 08:05:07[39m [1m[38;5;4m2[0m[38;5;8m30dd059[39m
@@ -1018,6 +1073,87 @@ fn test_short_prefix_in_transaction() {
     "###);
 }
 
+#[test]
+fn test_log_diff_stat() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\nb\n").unwrap();
+    std::fs::write(repo_path.join("rename-source"), "rename").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "a\nc\nd\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "a\n").unwrap();
+    std::fs::rename(
+        repo_path.join("rename-source"),
+        repo_path.join("rename-target"),
+    )
+    .unwrap();
+
+    let template = r#"
+    separate(" ", change_id.short(), self.diff_stat(),
+      "(" ++ diff_stat.files() ++ "/" ++ diff_stat.added() ++ "/" ++ diff_stat.removed() ++ ")",
+    ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    rlvkpnrzqnoo +3 -1 in 3 files (3/3/1)
+    qpvuntsmwlqt +3 -0 in 2 files (2/3/0)
+    zzzzzzzzzzzz +0 -0 in 0 files (0/0/0)
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "--color=debug",
+            "-r@",
+            "-T",
+            "diff_stat",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"[38;5;2m<<log diff_stat added::+3>>[39m<<log diff_stat:: >>[38;5;1m<<log diff_stat removed::-1>>[39m<<log diff_stat:: in 3 files>>");
+
+    // The stats are cached in the index directory
+    let diff_stats_dir = repo_path.join(".jj/repo/index/diff_stats");
+    assert_eq!(diff_stats_dir.read_dir().unwrap().count(), 3);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    rlvkpnrzqnoo +3 -1 in 3 files (3/3/1)
+    qpvuntsmwlqt +3 -0 in 2 files (2/3/0)
+    zzzzzzzzzzzz +0 -0 in 0 files (0/0/0)
+    ");
+
+    // Conflicts are materialized differently per marker style, so the stats
+    // are cached separately
+    test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "--config=ui.conflict-marker-style=git",
+            "-T",
+            template,
+        ],
+    );
+    assert_eq!(diff_stats_dir.read_dir().unwrap().count(), 6);
+
+    // The stats of a diff, optionally limited to some files
+    let template = r#"
+    separate(" ", change_id.short(), diff.stat(),
+      "+" ++ diff.stat().insertions() ++ " -" ++ diff.stat().deletions()
+        ++ " (" ++ diff.stat().files_changed() ++ " files)",
+      self.diff("file1").stat(),
+    ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    rlvkpnrzqnoo +3 -1 in 3 files +3 -1 (3 files) +2 -1 in 1 file
+    qpvuntsmwlqt +3 -0 in 2 files +3 -0 (2 files) +2 -0 in 1 file
+    zzzzzzzzzzzz +0 -0 in 0 files +0 -0 (0 files) +0 -0 in 0 files
+    ");
+}
+
 #[test]
 fn test_log_diff_predefined_formats() {
     let test_env = TestEnvironment::default();
@@ -1209,3 +1345,51 @@ fn test_log_diff_predefined_formats() {
     +c
     "###);
 }
+
+#[test]
+fn test_log_diff_files() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "a\n").unwrap();
+    std::fs::write(repo_path.join("rename-source"), "rename").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    std::fs::remove_file(repo_path.join("file2")).unwrap();
+    std::fs::write(repo_path.join("file3"), "c\n").unwrap();
+    std::fs::rename(
+        repo_path.join("rename-source"),
+        repo_path.join("rename-target"),
+    )
+    .unwrap();
+
+    let template = r#"
+    self.files().map(|entry| separate(" ",
+      entry.status(),
+      entry.source_path(),
+      entry.path(),
+      "[" ++ entry.old_mode() ++ "]",
+      "[" ++ entry.new_mode() ++ "]",
+    ) ++ "\n").join("")
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    modified file1 file1 [file] [file]
+    removed file2 file2 [file] []
+    added file3 file3 [] [file]
+    renamed rename-source rename-target [file] [file]
+    ");
+
+    // Limited to some files, and filtered
+    let template = r#"
+    separate(" ",
+      self.files("file1 | file3").map(|entry| entry.path()),
+      diff.files().filter(|entry| entry.status() == "removed").map(|entry| entry.path()),
+      self.parents().map(|c| if(c.files(), "parent has changes")),
+    ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-T", template]);
+    insta::assert_snapshot!(stdout, @"file1 file3 file2 parent has changes");
+}
@@ -74,17 +74,24 @@ fn test_bookmark_names() {
     aaa-tracked	x
     bbb-local	x
     bbb-tracked	x
+    --push	Also rename the bookmark on the remote
+    --remote	The remote to push to
+    --dry-run	Only display what will change on the remote, without renaming the bookmark
+    --allow-protected	Allow changing bookmarks protected by `bookmarks.protected`
+    --help	Print help (see more with '--help')
     --repository	Path to repository to operate on
     --ignore-working-copy	Don't snapshot the working copy, and don't update it
     --ignore-immutable	Allow rewriting immutable commits
     --at-operation	Operation to load the repo at
+    --op-metadata	Attach metadata to the operation created by the command
     --debug	Enable debug logging
+    --profile	Report the time spent in each phase of the command
+    --profile-trace	Also write the profile in Chrome trace format to the given file
     --color	When to colorize output (always, never, debug, auto)
     --quiet	Silence non-primary command output
     --no-pager	Disable the pager
     --config	Additional configuration options (can be repeated)
     --config-file	Additional configuration files (can be repeated)
-    --help	Print help (see more with '--help')
     ");
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "bookmark", "rename", "a"]);
@@ -135,10 +142,7 @@ fn test_bookmark_names() {
     insta::assert_snapshot!(stdout, @"aaa-tracked@origin	x");
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "git", "push", "-b", "a"]);
-    insta::assert_snapshot!(stdout, @r"
-    aaa-local	x
-    aaa-tracked	x
-    ");
+    insta::assert_snapshot!(stdout, @"aaa-local	new");
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "git", "fetch", "-b", "a"]);
     insta::assert_snapshot!(stdout, @r"
@@ -148,6 +152,119 @@ fn test_bookmark_names() {
     ");
 }
 
+#[test]
+fn test_pushable_bookmark_names() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "origin"]);
+    let origin_git_repo_path = test_env
+        .env_root()
+        .join("origin")
+        .join(".jj")
+        .join("repo")
+        .join("store")
+        .join("git");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "origin",
+            origin_git_repo_path.to_str().unwrap(),
+        ],
+    );
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    for name in ["ahead", "behind", "diverged", "synced"] {
+        test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", name]);
+    }
+    test_env.jj_cmd_ok(&repo_path, &["git", "push", "--all"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "third"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "set", "ahead", "-r", "@"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "bookmark",
+            "set",
+            "behind",
+            "--allow-backwards",
+            "-r",
+            "@--",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "@--", "-m", "fourth"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "bookmark",
+            "set",
+            "diverged",
+            "--allow-backwards",
+            "-r",
+            "@",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "new"]);
+
+    let mut test_env = test_env;
+    test_env.add_env_var("COMPLETE", "fish");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "git", "push", "-b", ""]);
+    insta::assert_snapshot!(stdout, @r"
+    ahead	origin: 1 ahead
+    behind	origin: 1 behind
+    diverged	origin: 1 ahead, 1 behind
+    new	new
+    ");
+}
+
+#[test]
+fn test_completion_cache() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let cache_dir = repo_path.join(".jj").join("repo").join("completion_cache");
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "aaa"]);
+    test_env.add_env_var("COMPLETE", "fish");
+
+    let complete_bookmarks = |test_env: &TestEnvironment| {
+        test_env.jj_cmd_success(&repo_path, &["--", "jj", "bookmark", "delete", "a"])
+    };
+    let cache_entries = || {
+        let state_dirs = std::fs::read_dir(&cache_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect_vec();
+        assert_eq!(state_dirs.len(), 1);
+        std::fs::read_dir(&state_dirs[0])
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect_vec()
+    };
+
+    insta::assert_snapshot!(complete_bookmarks(&test_env), @"aaa	(no description set)");
+    let entries = cache_entries();
+    assert_eq!(entries.len(), 1);
+
+    // The cached output is reused as long as the repo doesn't change
+    std::fs::write(&entries[0], "aaa-cached (no description set)\n").unwrap();
+    insta::assert_snapshot!(complete_bookmarks(&test_env), @"aaa-cached	(no description set)");
+
+    // The cache is invalidated when the operation head changes
+    test_env
+        .jj_cmd(&repo_path, &["bookmark", "create", "abc"])
+        .env_remove("COMPLETE")
+        .assert()
+        .success();
+    insta::assert_snapshot!(complete_bookmarks(&test_env), @r"
+    aaa	(no description set)
+    abc	(no description set)
+    ");
+    assert_eq!(cache_entries().len(), 1);
+}
+
 #[test]
 fn test_global_arg_repository_is_respected() {
     let test_env = TestEnvironment::default();
@@ -200,6 +317,29 @@ fn test_aliases_are_resolved() {
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "b2", "rename", "a"]);
     insta::assert_snapshot!(stdout, @"aaa	(no description set)");
+
+    // alias with placeholders, completed at the placeholder position
+    test_env.add_config(r#"aliases.rn = ["bookmark", "rename", "$1", "$2"]"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "rn", "a"]);
+    insta::assert_snapshot!(stdout, @"aaa	(no description set)");
+
+    // the completion index is adjusted to the expanded args
+    let mut test_env = test_env;
+    test_env.add_env_var("COMPLETE", "zsh");
+    let test_env = test_env;
+    let complete_at = |index: usize, args: &[&str]| {
+        let assert = test_env
+            .jj_cmd(&repo_path, args)
+            .env("_CLAP_COMPLETE_INDEX", index.to_string())
+            .assert()
+            .success();
+        get_stdout_string(&assert)
+    };
+    test_env.add_config(r#"aliases.rnp = ["bookmark", "rename", "--allow-protected"]"#);
+    let stdout = complete_at(2, &["--", "jj", "rnp", "a"]);
+    insta::assert_snapshot!(stdout, @"aaa:(no description set)");
+    let stdout = complete_at(2, &["--", "jj", "rn", "a", "b"]);
+    insta::assert_snapshot!(stdout, @"aaa:(no description set)");
 }
 
 #[test]
@@ -243,8 +383,12 @@ fn test_zsh_completion() {
     absorb:Move changes from a revision into the stack of mutable revisions
     ");
 
+    // The arg being completed isn't expanded even if it names an alias.
     let stdout = complete_at(1, &["--", "jj", "b"]);
-    insta::assert_snapshot!(stdout, @"bookmark:Manage bookmarks [default alias: b]");
+    insta::assert_snapshot!(stdout, @r"
+    backout:Apply the reverse of a revision on top of another revision
+    bookmark:Manage bookmarks [default alias: b]
+    ");
 }
 
 #[test]
@@ -452,9 +596,15 @@ fn test_operations() {
     let test_env = test_env;
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "op", "show", ""]);
-    let add_workspace_id = stdout.lines().nth(5).unwrap().split('\t').next().unwrap();
+    let add_workspace_id = stdout.lines().nth(7).unwrap().split('\t').next().unwrap();
     insta::assert_snapshot!(add_workspace_id, @"eac759b9ab75");
 
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "op", "show", "@"]);
+    insta::assert_snapshot!(stdout, @r"
+    @	The current operation
+    @-	The parent of the current operation
+    ");
+
     let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "op", "show", "5"]);
     insta::assert_snapshot!(stdout, @r"
     5bbb4ca536a8	(2001-02-03 08:05:12) describe commit 968261075dddabf4b0e333c1cc9a49ce26a3f710
@@ -469,6 +619,25 @@ fn test_operations() {
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "--at-op", "5b"]);
     insta::assert_snapshot!(stdout, @"5bbb4ca536a8	(2001-02-03 08:05:12) describe commit 968261075dddabf4b0e333c1cc9a49ce26a3f710");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "--at-operation", "@"]);
+    insta::assert_snapshot!(stdout, @r"
+    @	The current operation
+    @-	The parent of the current operation
+    ");
+    // --at-op=@ doesn't snapshot the working copy while completing
+    let count_ops = || {
+        let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "op", "show", ""]);
+        stdout.lines().count()
+    };
+    let num_ops = count_ops();
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["--", "jj", "--at-op", "@", "op", "show", "5"]);
+    assert_eq!(count_ops(), num_ops);
+    insta::assert_snapshot!(stdout, @r"
+    5bbb4ca536a8	(2001-02-03 08:05:12) describe commit 968261075dddabf4b0e333c1cc9a49ce26a3f710
+    518b588abbc6	(2001-02-03 08:05:09) describe commit 19611c995a342c01f525583e5fcafdd211f6d009
+    ");
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "op", "abandon", "5b"]);
     insta::assert_snapshot!(stdout, @"5bbb4ca536a8	(2001-02-03 08:05:12) describe commit 968261075dddabf4b0e333c1cc9a49ce26a3f710");
@@ -513,6 +682,96 @@ fn test_workspaces() {
     ");
 }
 
+#[test]
+fn test_change_aliases() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "initial"]);
+    test_env.jj_cmd_ok(&repo_path, &["alias", "set", "fix-login"]);
+    test_env.jj_cmd_ok(&repo_path, &["alias", "set", "fix-logout"]);
+    test_env.jj_cmd_ok(&repo_path, &["alias", "set", "other"]);
+
+    test_env.add_env_var("COMPLETE", "fish");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "alias", "delete", "fix"]);
+    insta::assert_snapshot!(stdout, @r"
+    fix-login	initial
+    fix-logout	initial
+    ");
+}
+
+#[test]
+fn test_merge_tools() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"
+        [merge-tools.my-diff-editor]
+        program = "fancy-diff"
+        edit-args = ["--edit", "$left", "$right"]
+
+        [merge-tools.my-merge-tool]
+        merge-args = ["$base", "$left", "$right", "-o", "$output"]
+        edit-args = []
+        "#,
+    );
+
+    test_env.add_env_var("COMPLETE", "fish");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "diffedit", "--tool", "my"]);
+    insta::assert_snapshot!(stdout, @"my-diff-editor	fancy-diff --edit $left $right");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "squash", "--tool", ":"]);
+    insta::assert_snapshot!(stdout, @":builtin	Use the built-in editor");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "resolve", "--tool", "my"]);
+    insta::assert_snapshot!(stdout, @"my-merge-tool	my-merge-tool $base $left $right -o $output");
+}
+
+#[test]
+fn test_templates() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"
+        [template-aliases]
+        my_log = 'commit_id'
+        'my_format(id)' = 'id.short()'
+        "#,
+    );
+
+    test_env.add_env_var("COMPLETE", "fish");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "log", "-T", "my"]);
+    insta::assert_snapshot!(stdout, @"my_log	Template alias");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "log", "-T", "co"]);
+    insta::assert_snapshot!(stdout, @r"
+    commit_summary_separator	Template alias
+    commit_id	Keyword
+    committer	Keyword
+    conflict	Keyword
+    contained_in	Keyword
+    ");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "op", "log", "-T", "c"]);
+    insta::assert_snapshot!(stdout, @r"
+    commit_summary_separator	Template alias
+    commits_created	Keyword
+    current_operation	Keyword
+    ");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "bookmark", "list", "-T", "n"]);
+    insta::assert_snapshot!(stdout, @r"
+    name_placeholder	Template alias
+    name	Keyword
+    normal_target	Keyword
+    ");
+}
+
 #[test]
 fn test_config() {
     let mut test_env = TestEnvironment::default();
@@ -521,18 +780,69 @@ fn test_config() {
 
     let stdout = test_env.jj_cmd_success(dir, &["--", "jj", "config", "get", "c"]);
     insta::assert_snapshot!(stdout, @r"
-    core.fsmonitor	Whether to use an external filesystem monitor, useful for large repos
+    commit.description-template	Template rendered against a commit without description to populate the editor with. Overrides `ui.default-description`.
+    commit.trailers	Template rendered against a commit whose output lines are added as trailers to its description
+    commit.verify.command	Command which receives a non-empty description on stdin, and rejects it by exiting with an error
+    commit.verify.pattern	Regular expression a non-empty description must match
+    core.fsmonitor	Whether to use an external filesystem monitor, useful for large repos. Superseded by `fsmonitor.backend`.
     core.watchman.register_snapshot_trigger	Whether to use triggers to monitor for changes in the background.
     ");
 
     let stdout = test_env.jj_cmd_success(dir, &["--", "jj", "config", "list", "c"]);
     insta::assert_snapshot!(stdout, @r"
     colors	Mapping from jj formatter labels to colors
+    commit	Settings for editing commit descriptions
+    commit.description-template	Template rendered against a commit without description to populate the editor with. Overrides `ui.default-description`.
+    commit.trailers	Template rendered against a commit whose output lines are added as trailers to its description
+    commit.verify	Checks of edited descriptions, which can be skipped by --no-verify
+    commit.verify.command	Command which receives a non-empty description on stdin, and rejects it by exiting with an error
+    commit.verify.pattern	Regular expression a non-empty description must match
     core
-    core.fsmonitor	Whether to use an external filesystem monitor, useful for large repos
+    core.fsmonitor	Whether to use an external filesystem monitor, useful for large repos. Superseded by `fsmonitor.backend`.
     core.watchman
     core.watchman.register_snapshot_trigger	Whether to use triggers to monitor for changes in the background.
     ");
+
+    let stdout = test_env.jj_cmd_success(dir, &["--", "jj", "config", "set", "ui.diff.format", ""]);
+    insta::assert_snapshot!(stdout, @r"
+    color-words
+    git
+    summary
+    --user	Target the user-level config
+    --repo	Target the repo-level config
+    --help	Print help (see more with '--help')
+    --repository	Path to repository to operate on
+    --ignore-working-copy	Don't snapshot the working copy, and don't update it
+    --ignore-immutable	Allow rewriting immutable commits
+    --at-operation	Operation to load the repo at
+    --op-metadata	Attach metadata to the operation created by the command
+    --debug	Enable debug logging
+    --profile	Report the time spent in each phase of the command
+    --profile-trace	Also write the profile in Chrome trace format to the given file
+    --color	When to colorize output (always, never, debug, auto)
+    --quiet	Silence non-primary command output
+    --no-pager	Disable the pager
+    --config	Additional configuration options (can be repeated)
+    --config-file	Additional configuration files (can be repeated)
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        dir,
+        &[
+            "--",
+            "jj",
+            "config",
+            "set",
+            "--user",
+            "ui.log-word-wrap",
+            "f",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"false");
+
+    // No values are known for strings
+    let stdout = test_env.jj_cmd_success(dir, &["--", "jj", "config", "set", "user.name", "f"]);
+    insta::assert_snapshot!(stdout, @"");
 }
 
 fn create_commit(
@@ -724,6 +1034,17 @@ fn test_files() {
     f_renamed	Added
     ");
 
+    // the completer sees the args of the command the alias expands to
+    test_env.add_config(r#"aliases.dr = ["diff", "-r", "$1"]"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "dr", "@-", "f_"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    f_added	Added
+    f_deleted	Deleted
+    f_dir/
+    f_modified	Modified
+    f_renamed	Added
+    ");
+
     let stdout = test_env.jj_cmd_success(
         &repo_path,
         &[
@@ -754,6 +1075,15 @@ fn test_files() {
     f_unchanged	Added
     ");
 
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "diffedit", "-r", "@-", "f_"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    f_added	Added
+    f_deleted	Deleted
+    f_dir/
+    f_modified	Modified
+    f_renamed	Added
+    ");
+
     // interdiff has a different behavior with --from and --to flags
     let stdout = test_env.jj_cmd_success(
         &repo_path,
@@ -782,6 +1112,27 @@ fn test_files() {
     f_unchanged	Added
     ");
 
+    // with the destination, only the files differing from it are completed
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["--", "jj", "squash", "--from=first", "--into=second", "f_"],
+    );
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    f_added	Added
+    f_deleted	Deleted
+    f_dir/
+    f_modified	Modified
+    f_renamed	Added
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["--", "jj", "squash", "--into=interdiff_from", "f_i"],
+    );
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    f_interdiff_only_from	Added
+    f_interdiff_same	Added
+    ");
+
     let stdout =
         test_env.jj_cmd_success(&repo_path, &["--", "jj", "resolve", "-r=conflicted", "f_"]);
     insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
@@ -12,12 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
 use std::path::PathBuf;
 
 use indoc::indoc;
 use itertools::Itertools;
 use regex::Regex;
 
+use crate::common::get_stderr_string;
 use crate::common::TestEnvironment;
 
 #[test]
@@ -252,6 +254,176 @@ fn test_config_list_layer() {
     "###);
 }
 
+#[test]
+fn test_config_list_show_origin() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config("test-table.x = 'user'\ntest-table.y = 1");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["config", "set", "--repo", "test-table.x", "repo"],
+    );
+
+    let args = ["config", "list", "test-table", "--include-overridden"];
+    let stdout = test_env.jj_cmd_success(&repo_path, &[&args[..], &["--show-origin"]].concat());
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r#"
+    # test-table.x = 'user' # user: $TEST_ENV/config/config0002.toml
+    test-table.y = 1 # user: $TEST_ENV/config/config0002.toml
+    test-table.x = "repo" # repo: $TEST_ENV/repo/.jj/repo/config.toml
+    "#);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "list",
+            "test-table",
+            "-T",
+            r#"source ++ " " ++ name ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    user test-table.y
+    repo test-table.x
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "list",
+            "test-table",
+            "--format=toml",
+            "--show-origin",
+        ],
+    );
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r#"
+    [test-table]
+    y = 1 # user: $TEST_ENV/config/config0002.toml
+    x = "repo" # repo: $TEST_ENV/repo/.jj/repo/config.toml
+    "#);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "list", "test-table.y", "--format=toml"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    [test-table]
+    y = 1
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[&args[..], &["--format=json", "--config=test-table.y=2"]].concat(),
+    );
+    insta::assert_snapshot!(stdout.replace("\\\\", "/"), @r#"
+    [
+      {
+        "name": "test-table.x",
+        "overridden": true,
+        "path": "$TEST_ENV/config/config0002.toml",
+        "source": "user",
+        "value": "user"
+      },
+      {
+        "name": "test-table.y",
+        "overridden": true,
+        "path": "$TEST_ENV/config/config0002.toml",
+        "source": "user",
+        "value": 1
+      },
+      {
+        "name": "test-table.x",
+        "overridden": false,
+        "path": "$TEST_ENV/repo/.jj/repo/config.toml",
+        "source": "repo",
+        "value": "repo"
+      },
+      {
+        "name": "test-table.y",
+        "overridden": false,
+        "path": null,
+        "source": "cli",
+        "value": 2
+      }
+    ]
+    "#);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &[&args[..], &["--format=toml"]].concat());
+    insta::assert_snapshot!(stderr, @"Error: --include-overridden cannot be used with --format toml");
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["config", "list", "--format=json", "-T", "name"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: --template can only be used with --format text");
+}
+
+#[test]
+fn test_config_include_if() {
+    let test_env = TestEnvironment::default();
+    std::fs::create_dir(test_env.env_root().join("work")).unwrap();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "personal"]);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "work/repo"]);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "acme"]);
+    let personal_path = test_env.env_root().join("personal");
+    let work_path = test_env.env_root().join("work").join("repo");
+    let acme_path = test_env.env_root().join("acme");
+    test_env.jj_cmd_ok(
+        &acme_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/acme/widget.git",
+        ],
+    );
+    std::fs::write(
+        test_env.env_root().join("acme.toml"),
+        "template-aliases.profile = '\"acme\"'",
+    )
+    .unwrap();
+    test_env.add_config(&format!(
+        r#"
+        template-aliases.profile = '"personal"'
+
+        [[include-if]]
+        repositories = ['{work}']
+        config.template-aliases.profile = '"work"'
+
+        [[include-if]]
+        remotes = ["https://github.com/acme/*"]
+        path = '{acme}'
+        "#,
+        work = test_env.env_root().join("work").display(),
+        acme = test_env.env_root().join("acme.toml").display(),
+    ));
+
+    let get_profile =
+        |path: &Path| test_env.jj_cmd_success(path, &["log", "--no-graph", "-r@", "-Tprofile"]);
+    insta::assert_snapshot!(get_profile(&personal_path), @"personal");
+    insta::assert_snapshot!(get_profile(&work_path), @"work");
+    insta::assert_snapshot!(get_profile(&acme_path), @"acme");
+
+    // The included file is reported as the origin of the value
+    let stdout = test_env.jj_cmd_success(
+        &acme_path,
+        &[
+            "config",
+            "list",
+            "--show-origin",
+            "template-aliases.profile",
+        ],
+    );
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r#"template-aliases.profile = '"acme"' # user: $TEST_ENV/acme.toml"#);
+
+    test_env.add_config("include-if = [{ repository = ['foo'] }]");
+    let stderr = test_env.jj_cmd_failure(&personal_path, &["status"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Config error: Invalid include-if section in $TEST_ENV/config/config0003.toml: unknown key "repository"
+    For help, see https://martinvonz.github.io/jj/latest/config/.
+    "#);
+}
+
 #[test]
 fn test_config_layer_override_default() {
     let test_env = TestEnvironment::default();
@@ -647,6 +819,75 @@ fn test_config_set_type_mismatch() {
     );
 }
 
+#[test]
+fn test_config_set_schema_mismatch() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["config", "set", "--repo", "ui.paginate", ":builtin"],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Invalid type or value for ui.paginate
+    Hint: Expected one of "never", "auto".
+    "#);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["config", "set", "--repo", "snapshot.auto-track", "true"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Invalid type or value for snapshot.auto-track
+    Hint: Expected a string.
+    ");
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "config",
+            "set",
+            "--repo",
+            "colors.error",
+            "{ fg = 'red', bold = 'yes' }",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Invalid type or value for colors.error.bold
+    Hint: Expected a boolean.
+    ");
+    let repo_config_path = repo_path.join(PathBuf::from_iter([".jj", "repo", "config.toml"]));
+    assert!(!repo_config_path.exists());
+
+    // Structured values of the right type can be set
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "config",
+            "set",
+            "--repo",
+            "colors.error",
+            "{ fg = 'red', bold = true }",
+        ],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "config",
+            "set",
+            "--repo",
+            "git.fetch",
+            "['origin', 'upstream']",
+        ],
+    );
+    insta::assert_snapshot!(std::fs::read_to_string(&repo_config_path).unwrap(), @r"
+    [colors]
+    error = { fg = 'red', bold = true }
+
+    [git]
+    fetch = ['origin', 'upstream']
+    ");
+}
+
 #[test]
 fn test_config_set_nontable_parent() {
     let test_env = TestEnvironment::default();
@@ -848,6 +1089,115 @@ fn test_config_edit_repo() {
     assert!(repo_config_path.exists(), "new file should be created");
 }
 
+#[test]
+fn test_config_edit_invalid() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let repo_config_path = repo_path.join(PathBuf::from_iter([".jj", "repo", "config.toml"]));
+    std::fs::write(&repo_config_path, "ui.paginate = 'never'\n").unwrap();
+    let edit_script = test_env.set_up_fake_editor();
+
+    std::fs::write(&edit_script, "write\nui.paginate = 'builtin'\n").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["config", "edit", "--repo"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Invalid type or value for ui.paginate
+    Hint: Expected one of "never", "auto".
+    Hint: The config file was restored to its previous contents.
+    Hint: The edited contents were saved to $TEST_ENV/repo/.jj/repo/config.toml.rejected
+    "#);
+    insta::assert_snapshot!(std::fs::read_to_string(&repo_config_path).unwrap(), @"ui.paginate = 'never'");
+    let rejected_config_path = repo_config_path.with_file_name("config.toml.rejected");
+    insta::assert_snapshot!(std::fs::read_to_string(&rejected_config_path).unwrap(), @"ui.paginate = 'builtin'");
+
+    std::fs::write(&edit_script, "write\nui.paginate = \n").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["config", "edit", "--repo"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Failed to parse the edited config file
+    Caused by:
+    1: Configuration cannot be parsed as TOML document
+    2: TOML parse error at line 1, column 15
+      |
+    1 | ui.paginate = 
+      |               ^
+    invalid string
+    expected `"`, `'`
+
+    Hint: The config file was restored to its previous contents.
+    Hint: The edited contents were saved to $TEST_ENV/repo/.jj/repo/config.toml.rejected
+    "#);
+    insta::assert_snapshot!(std::fs::read_to_string(&repo_config_path).unwrap(), @"ui.paginate = 'never'");
+    // The previously rejected contents are overwritten
+    insta::assert_snapshot!(std::fs::read_to_string(&rejected_config_path).unwrap(), @"ui.paginate =");
+
+    std::fs::write(&edit_script, "write\nui.paginate = 'auto'\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["config", "edit", "--repo"]);
+    insta::assert_snapshot!(std::fs::read_to_string(&repo_config_path).unwrap(), @"ui.paginate = 'auto'");
+}
+
+#[test]
+fn test_config_edit_validate_on_save() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let repo_config_path = repo_path.join(PathBuf::from_iter([".jj", "repo", "config.toml"]));
+    std::fs::write(&repo_config_path, "ui.paginate = 'never'\n").unwrap();
+    let edit_script = test_env.set_up_fake_editor();
+
+    // The invalid file is re-opened, and the fixed contents are kept
+    std::fs::write(
+        &edit_script,
+        [
+            "write\nui.paginate = 'builtin'\n",
+            "next invocation\n",
+            "expect\nui.paginate = 'builtin'\n",
+            "write\nui.paginate = 'auto'\n",
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_stdin_ok(
+        &repo_path,
+        &["config", "edit", "--repo", "--validate-on-save"],
+        "\n",
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Invalid type or value for ui.paginate
+    Hint: Expected one of "never", "auto".
+    Re-open the editor to fix the config file? (Yn):
+    "#);
+    insta::assert_snapshot!(std::fs::read_to_string(&repo_config_path).unwrap(), @"ui.paginate = 'auto'");
+
+    // The previous contents are restored if the user gives up
+    std::fs::write(&edit_script, "write\nui.paginate = \n").unwrap();
+    let assert = test_env
+        .jj_cmd_stdin(
+            &repo_path,
+            &["config", "edit", "--repo", "--validate-on-save"],
+            "n\n",
+        )
+        .assert()
+        .code(1);
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Failed to parse the edited config file
+    Caused by:
+    1: Configuration cannot be parsed as TOML document
+    2: TOML parse error at line 1, column 15
+      |
+    1 | ui.paginate = 
+      |               ^
+    invalid string
+    expected `"`, `'`
+
+    Re-open the editor to fix the config file? (Yn): Error: The edited config file is invalid
+    Hint: The config file was restored to its previous contents.
+    Hint: The edited contents were saved to $TEST_ENV/repo/.jj/repo/config.toml.rejected
+    "#);
+    insta::assert_snapshot!(std::fs::read_to_string(&repo_config_path).unwrap(), @"ui.paginate = 'auto'");
+}
+
 #[test]
 fn test_config_path() {
     let mut test_env = TestEnvironment::default();
@@ -1051,18 +1401,14 @@ fn test_config_path_syntax() {
 fn test_config_show_paths() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
-    let repo_path = test_env.env_root().join("repo");
 
-    test_env.jj_cmd_ok(
-        &repo_path,
-        &["config", "set", "--user", "ui.paginate", ":builtin"],
-    );
+    test_env.add_config("ui.paginate = ':builtin'");
     let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["st"]);
     insta::assert_snapshot!(stderr, @r"
     Config error: Invalid type or value for ui.paginate
     Caused by: unknown variant `:builtin`, expected `never` or `auto`
 
-    Hint: Check the config file: $TEST_ENV/config/config0001.toml
+    Hint: Check the config file: $TEST_ENV/config/config0002.toml
     For help, see https://martinvonz.github.io/jj/latest/config/.
     ");
 }
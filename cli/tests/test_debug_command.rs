@@ -17,6 +17,80 @@ use regex::Regex;
 
 use crate::common::TestEnvironment;
 
+#[test]
+fn test_debug_backend() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+
+    // Working-copy capabilities depend on the platform and enabled features
+    let filter_wc = |stdout: String| {
+        Regex::new(r#"(?m)^(  Capabilities: sparse|      "sparse"),?[^\n]*(\n      "[^"]*",?)*$"#)
+            .unwrap()
+            .replace(&stdout, "$1 [..]")
+            .into_owned()
+    };
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "backend"]);
+    assert_snapshot!(filter_wc(stdout), @r"
+    Commit store: git
+      Capabilities: copy-tracking, git-interop, signing
+      Commit ID length: 20
+      Change ID length: 16
+      Concurrency: 1
+    Operation store: simple_op_store
+    Operation heads store: simple_op_heads_store
+    Index store: default
+      Format version: 6
+    Working copy: local
+      Capabilities: sparse [..]
+    Submodule store: default
+    ");
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "backend", "--json"]);
+    assert_snapshot!(filter_wc(stdout), @r#"
+    {
+      "commit_store": {
+        "name": "git",
+        "format_version": null,
+        "capabilities": [
+          "copy-tracking",
+          "git-interop",
+          "signing"
+        ],
+        "commit_id_length": 20,
+        "change_id_length": 16,
+        "concurrency": 1
+      },
+      "op_store": {
+        "name": "simple_op_store",
+        "format_version": null,
+        "capabilities": []
+      },
+      "op_heads_store": {
+        "name": "simple_op_heads_store",
+        "format_version": null,
+        "capabilities": []
+      },
+      "index_store": {
+        "name": "default",
+        "format_version": 6,
+        "capabilities": []
+      },
+      "working_copy": {
+        "name": "local",
+        "format_version": null,
+        "capabilities": [
+          "sparse" [..]
+        ]
+      },
+      "submodule_store": {
+        "name": "default",
+        "format_version": null,
+        "capabilities": []
+      }
+    }
+    "#);
+}
+
 #[test]
 fn test_debug_fileset() {
     let test_env = TestEnvironment::default();
@@ -83,6 +157,43 @@ fn test_debug_revset() {
     });
 }
 
+#[test]
+fn test_debug_revset_explain() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &[
+            "debug",
+            "revset",
+            "--explain",
+            "::@ & (description(foo) | root()..@) ~ heads(all())",
+        ],
+    );
+    insta::with_settings!({filters => vec![
+        (r"(?s)^-- Parsed:.*-- Plan:", "-- Plan:"),
+    ]}, {
+        assert_snapshot!(stdout, @r"
+        -- Plan:
+        FilterWithin: lazy filter of candidates by predicate
+          Difference: lazy merge of sorted positions
+            Ancestors: lazy ancestor walk from heads
+              Commits (1 ids): sorted list of index positions
+            Heads: eager head computation over candidates
+              Ancestors: lazy ancestor walk from heads
+                Commits (1 ids): sorted list of index positions
+          Union: either predicate
+            Filter Description: reads each commit
+            Set: evaluated as set, then tested by position
+              Range: lazy ancestor walk from heads, stopped at ancestors of roots
+                Commits (1 ids): sorted list of index positions
+                Commits (1 ids): sorted list of index positions
+        ");
+    });
+}
+
 #[test]
 fn test_debug_index() {
     let test_env = TestEnvironment::default();
@@ -146,6 +257,128 @@ fn test_debug_reindex() {
     );
 }
 
+#[test]
+fn test_debug_reindex_changed_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    std::fs::write(workspace_path.join("file1"), "1").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["commit", "-m", "add file1"]);
+    std::fs::write(workspace_path.join("file2"), "2").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["commit", "-m", "add file2"]);
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_path, &["debug", "reindex", "--changed-paths"]);
+    assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Finished indexing 8 commits.
+    Indexed changed paths of 8 commits.
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &["log", "-r", "files(file1)", "-T", "description"],
+    );
+    assert_snapshot!(stdout, @r"
+    ○  add file1
+    │
+    ~
+    ");
+
+    // Once enabled, the changed-path index is rebuilt along with the index
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "reindex"]);
+    insta::assert_snapshot!(stderr, @"Finished indexing 8 commits.");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "index-paths"]);
+    insta::assert_snapshot!(stderr, @"Indexed changed paths of 0 commits.");
+}
+
+#[test]
+fn test_debug_index_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    std::fs::write(workspace_path.join("file1"), "1").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["commit", "-m", "add file1"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "index-paths"]);
+    assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Indexed changed paths of 5 commits.");
+
+    // New commits are indexed as they're added
+    std::fs::write(workspace_path.join("file2"), "2").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["commit", "-m", "add file2"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "index-paths"]);
+    insta::assert_snapshot!(stderr, @"Indexed changed paths of 0 commits.");
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &[
+            "log",
+            "-r",
+            "files(file1) | files(file2)",
+            "-T",
+            "description",
+        ],
+    );
+    assert_snapshot!(stdout, @r"
+    ○  add file2
+    ○  add file1
+    │
+    ~
+    ");
+}
+
+#[test]
+fn test_debug_create_graph_fixture() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    std::fs::write(workspace_path.join("secret"), "private").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&workspace_path, &["new", "root()", "-m", "second"]);
+    test_env.jj_cmd_ok(
+        &workspace_path,
+        &["new", "description(first)", "@", "-m", "merge"],
+    );
+    test_env.jj_cmd_ok(&workspace_path, &["bookmark", "create", "main"]);
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "create-graph-fixture"]);
+    assert_snapshot!(stdout, @r#"
+    #!/bin/sh
+    # Recreates the commit graph of a jj repo. Run in an empty directory.
+    set -e
+    jj git init
+    jj new 'root()' -m 'commit 1'
+    echo 1 > file1
+    jj new 'root()' -m 'commit 2'
+    jj new 'description(exact:"commit 1\n")' 'description(exact:"commit 2\n")' -m 'commit 3'
+    jj new 'description(exact:"commit 3\n")' -m 'commit 4'
+    jj bookmark create main -r 'description(exact:"commit 3\n")'
+    jj edit 'description(exact:"commit 4\n")'
+    "#);
+
+    // Edges to excluded commits are reconnected to the included ancestors
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &[
+            "debug",
+            "create-graph-fixture",
+            "-r",
+            "description(first) | description(merge)",
+        ],
+    );
+    assert_snapshot!(stdout, @r#"
+    #!/bin/sh
+    # Recreates the commit graph of a jj repo. Run in an empty directory.
+    set -e
+    jj git init
+    jj new 'root()' -m 'commit 1'
+    echo 1 > file1
+    jj new 'description(exact:"commit 1\n")' -m 'commit 2'
+    jj bookmark create main -r 'description(exact:"commit 2\n")'
+    "#);
+}
+
 #[test]
 fn test_debug_tree() {
     let test_env = TestEnvironment::default();
@@ -227,6 +460,101 @@ fn test_debug_tree() {
     );
 }
 
+#[test]
+fn test_debug_export_import_repo() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"ui.allow-init-native = true"#);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "git-repo"]);
+    let git_repo_path = test_env.env_root().join("git-repo");
+    std::fs::write(git_repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&git_repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&git_repo_path, &["new", "-m", "second"]);
+    std::fs::write(git_repo_path.join("file"), "b\n").unwrap();
+    test_env.jj_cmd_ok(
+        &git_repo_path,
+        &["new", "description(first)", "-m", "third"],
+    );
+    std::fs::write(git_repo_path.join("file"), "c\n").unwrap();
+    test_env.jj_cmd_ok(
+        &git_repo_path,
+        &["rebase", "-r", "description(second)", "-d", "@"],
+    );
+    test_env.jj_cmd_ok(
+        &git_repo_path,
+        &["bookmark", "create", "main", "-r", "description(second)"],
+    );
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&git_repo_path, &["debug", "export-repo", "../git.archive"]);
+    insta::assert_snapshot!(stderr, @"Exported 9 operations and 8 commits.");
+
+    // Import into a repo with the native backend
+    let template = r#"change_id.short() ++ " " ++ description.first_line() ++ " " ++ bookmarks ++ if(conflict, " conflict") ++ "\n""#;
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "local-repo"]);
+    let local_repo_path = test_env.env_root().join("local-repo");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &local_repo_path,
+        &["debug", "import-repo", "../git.archive"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Imported 9 operations and 8 commits.
+    Working copy now at: zsuskuln 75398558 third
+    Added 1 files, modified 0 files, removed 0 files
+    Updated working copy to fresh commit 75398558007b
+    ");
+    let git_log = test_env.jj_cmd_success(&git_repo_path, &["log", "-T", template]);
+    let local_log = test_env.jj_cmd_success(&local_repo_path, &["log", "-T", template]);
+    assert_eq!(local_log, git_log);
+    insta::assert_snapshot!(local_log, @r"
+    ×  kkmpptxzrspx second main conflict
+    @  zsuskulnrvyr third
+    ○  qpvuntsmwlqt first
+    ◆  zzzzzzzzzzzz
+    ");
+    assert_eq!(
+        std::fs::read_to_string(local_repo_path.join("file")).unwrap(),
+        "c\n"
+    );
+    let op_log_template = r#"description ++ "\n""#;
+    let git_op_log = test_env.jj_cmd_success(
+        &git_repo_path,
+        &["op", "log", "--no-graph", "-T", op_log_template],
+    );
+    let local_op_log = test_env.jj_cmd_success(
+        &local_repo_path,
+        &["op", "log", "--no-graph", "-T", op_log_template],
+    );
+    assert_eq!(local_op_log, git_op_log);
+
+    // Import back into a repo backed by Git, which should recreate the same
+    // commits
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &local_repo_path,
+        &["debug", "export-repo", "../local.archive"],
+    );
+    insta::assert_snapshot!(stderr, @"Exported 9 operations and 8 commits.");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "git-repo2"]);
+    let git_repo2_path = test_env.env_root().join("git-repo2");
+    test_env.jj_cmd_ok(
+        &git_repo2_path,
+        &["debug", "import-repo", "../local.archive"],
+    );
+    let commit_ids_template = r#"commit_id ++ "\n""#;
+    assert_eq!(
+        test_env.jj_cmd_success(&git_repo2_path, &["log", "-T", commit_ids_template]),
+        test_env.jj_cmd_success(&git_repo_path, &["log", "-T", commit_ids_template]),
+    );
+
+    // The destination must be empty
+    let stderr = test_env.jj_cmd_failure(
+        &git_repo_path,
+        &["debug", "import-repo", "../local.archive"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: The repo is not empty
+    Hint: Import the archive into a repo created with `jj git init` or `jj init`.
+    ");
+}
+
 #[test]
 fn test_debug_operation_id() {
     let test_env = TestEnvironment::default();
@@ -240,6 +568,44 @@ fn test_debug_operation_id() {
     );
 }
 
+#[cfg(feature = "native-fsmonitor")]
+#[test]
+fn test_debug_fsmonitor_native() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "fsmonitor", "status"]);
+    assert_snapshot!(stdout, @"The native filesystem monitor daemon is not running.");
+
+    // Snapshotting starts the daemon in the background
+    test_env.add_config(r#"fsmonitor.backend = "native""#);
+    test_env.jj_cmd_ok(&workspace_path, &["status"]);
+    let mut attempts = 0;
+    loop {
+        let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "fsmonitor", "status"]);
+        if stdout.contains("is running") {
+            break;
+        }
+        attempts += 1;
+        assert!(attempts < 100, "daemon didn't start");
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    std::fs::create_dir(workspace_path.join("dir")).unwrap();
+    std::fs::write(workspace_path.join("dir").join("file"), "").unwrap();
+    std::fs::write(workspace_path.join("file"), "").unwrap();
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["diff", "--summary"]);
+    assert_snapshot!(stdout, @r"
+    A dir/file
+    A file
+    ");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "fsmonitor", "stop"]);
+    assert_snapshot!(stderr, @"Stopped the native filesystem monitor daemon.");
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "fsmonitor", "status"]);
+    assert_snapshot!(stdout, @"The native filesystem monitor daemon is not running.");
+}
+
 fn filter_index_stats(text: &str) -> String {
     let regex = Regex::new(r"    Name: [0-9a-z]+").unwrap();
     regex.replace_all(text, "    Name: [hash]").to_string()
@@ -485,6 +485,30 @@ fn test_describe_default_description() {
     "###);
 }
 
+#[test]
+fn test_describe_description_template() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    test_env.add_config(r#"ui.default-description = "ignored\n""#);
+    test_env
+        .add_config(r#"commit.description-template = '"\n\nAuthor: " ++ author.email() ++ "\n"'"#);
+    let workspace_path = test_env.env_root().join("repo");
+
+    std::fs::write(workspace_path.join("file1"), "foo\n").unwrap();
+    let edit_script = test_env.set_up_fake_editor();
+    std::fs::write(edit_script, ["dump editor"].join("\0")).unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["describe"]);
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor")).unwrap(), @r#"
+    Author: test.user@example.com
+
+    JJ: This commit contains the following changes:
+    JJ:     A file1
+
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+}
+
 #[test]
 fn test_describe_author() {
     let mut test_env = TestEnvironment::default();
@@ -668,6 +692,167 @@ fn test_describe_author() {
     "#);
 }
 
+#[test]
+fn test_describe_gerrit_change_id() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config("gerrit.enabled = true");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "subject"]);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @r"
+    subject
+
+    Change-Id: I6a6a69649a45c67d3e96a7e5007c110ede34dec5
+    ");
+
+    // The trailer is kept stable when the description is edited
+    let edit_script = test_env.set_up_fake_editor();
+    std::fs::write(edit_script, "write\nnew subject\n\nbody\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe"]);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @r"
+    new subject
+
+    body
+
+    Change-Id: I6a6a69649a45c67d3e96a7e5007c110ede34dec5
+    ");
+
+    // Empty descriptions don't get a trailer
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", ""]);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    // The trailer is also added by `jj commit`
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "committed"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r@-",
+            "-Tchange_id ++ \"\\n\" ++ description",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu
+    committed
+
+    Change-Id: I6a6a69649a45c67d3e96a7e5007c110ede34dec5
+    ");
+}
+
+#[test]
+fn test_describe_trailers() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"commit.trailers = '"Signed-off-by: " ++ committer.name() ++ " <" ++ committer.email() ++ ">\n"'"#,
+    );
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "subject"]);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @r"
+    subject
+
+    Signed-off-by: Test User <test.user@example.com>
+    ");
+
+    // The trailer isn't duplicated when the description is edited
+    let edit_script = test_env.set_up_fake_editor();
+    std::fs::write(
+        edit_script,
+        ["dump editor", "write\nnew subject\n"].join("\0"),
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe"]);
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor")).unwrap(), @r#"
+    subject
+
+    Signed-off-by: Test User <test.user@example.com>
+
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @r"
+    new subject
+
+    Signed-off-by: Test User <test.user@example.com>
+    ");
+
+    // Empty descriptions don't get trailers
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", ""]);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    // Trailers are also added by `jj commit`
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "committed"]);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@-", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @r"
+    committed
+
+    Signed-off-by: Test User <test.user@example.com>
+    ");
+}
+
+#[test]
+fn test_describe_verify() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"commit.verify.pattern = '^(feat|fix): '"#);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "-m", "subject"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: The description doesn't match the pattern '^(feat|fix): '
+    Hint: Use --no-verify to skip the check.
+    ");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "fix: subject"]);
+    // Empty descriptions are accepted
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", ""]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "--no-verify", "-m", "subject"]);
+    // Unchanged descriptions are checked too
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "--reset-author", "--no-edit"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: The description doesn't match the pattern '^(feat|fix): '
+    Hint: Use --no-verify to skip the check.
+    ");
+
+    // The check also applies to `jj commit`
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["commit", "-m", "committed"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: The description doesn't match the pattern '^(feat|fix): '
+    Hint: Use --no-verify to skip the check.
+    ");
+
+    let formatter_path = assert_cmd::cargo::cargo_bin("fake-formatter");
+    test_env.add_config(&format!(
+        r#"commit.verify.command = ['{}', '--stdout', "Missing ticket number\n", '--fail']"#,
+        formatter_path.to_str().unwrap()
+    ));
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "-m", "feat: subject"]);
+    let stderr = stderr.replace(formatter_path.to_str().unwrap(), "$FORMATTER");
+    insta::assert_snapshot!(stderr, @r"
+    Missing ticket number
+    Error: The description was rejected by '$FORMATTER'
+    Hint: Use --no-verify to skip the check.
+    ");
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @"subject");
+}
+
 #[test]
 fn test_describe_avoids_unc() {
     let mut test_env = TestEnvironment::default();
@@ -736,6 +736,59 @@ fn test_diff_hunks() {
     ");
 }
 
+#[cfg(feature = "syntax-highlight")]
+#[test]
+fn test_diff_syntax_highlight() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(
+        repo_path.join("main.rs"),
+        "fn main() {\n    let x = 1;\n}\n",
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(
+        repo_path.join("main.rs"),
+        "fn main() {\n    let x = 2;\n}\n",
+    )
+    .unwrap();
+
+    // Styles of removed and added tokens take precedence
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--color=debug", "--config=ui.syntax-highlight=true"],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    [38;5;3m<<diff header::Modified regular file main.rs:>>[39m
+    [38;5;1m<<diff removed line_number::   1>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   1>>[39m<<diff::: >>[38;5;5m<<diff syntax storage::fn>>[39m<<diff:: >>[38;5;4m<<diff syntax entity::main>>[39m<<diff::() {>>
+    [38;5;1m<<diff removed line_number::   2>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   2>>[39m<<diff:::     >>[38;5;5m<<diff syntax storage::let>>[39m<<diff:: x >>[38;5;5m<<diff syntax keyword::=>>[39m<<diff:: >>[4m[38;5;1m<<diff syntax constant removed token::1>>[38;5;2m<<diff syntax constant added token::2>>[24m[39m<<diff::;>>
+    [38;5;1m<<diff removed line_number::   3>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   3>>[39m<<diff::: }>>
+    "#);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "diff",
+            "--git",
+            "--color=debug",
+            "--config=ui.syntax-highlight=true",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    [1m<<diff file_header::diff --git a/main.rs b/main.rs>>[0m
+    [1m<<diff file_header::index d5dd9c3865..2ae9a5bb86 100644>>[0m
+    [1m<<diff file_header::--- a/main.rs>>[0m
+    [1m<<diff file_header::+++ b/main.rs>>[0m
+    [38;5;6m<<diff hunk_header::@@ -1,3 +1,3 @@>>[39m
+    <<diff context:: >>[38;5;5m<<diff syntax storage context::fn>>[39m<<diff context:: >>[38;5;4m<<diff syntax entity context::main>>[39m<<diff context::() {>>
+    [38;5;1m<<diff removed::-    >><<diff syntax storage removed::let>><<diff removed:: x >><<diff syntax keyword removed::=>><<diff removed:: >>[4m<<diff syntax constant removed token::1>>[24m<<diff removed::;>>[39m
+    [38;5;2m<<diff added::+    >><<diff syntax storage added::let>><<diff added:: x >><<diff syntax keyword added::=>><<diff added:: >>[4m<<diff syntax constant added token::2>>[24m<<diff added::;>>[39m
+    <<diff context:: }>>
+    "#);
+}
+
 #[test]
 fn test_diff_color_words_inlining_threshold() {
     let test_env = TestEnvironment::default();
@@ -171,6 +171,39 @@ fn test_diffedit() {
     "###);
 }
 
+#[test]
+fn test_diffedit_paths() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "b\n").unwrap();
+
+    let edit_script = test_env.set_up_fake_diff_editor();
+
+    // Only the given paths are shown in the diff editor, and changes to the
+    // other paths are kept
+    std::fs::write(
+        &edit_script,
+        "files-before file2\0files-after JJ-INSTRUCTIONS file2\0reset file2",
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["diffedit", "file2"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Created rlvkpnrz 30a219ac (no description set)
+    Working copy now at: rlvkpnrz 30a219ac (no description set)
+    Parent commit      : qpvuntsm fc687cb8 (no description set)
+    Added 0 files, modified 1 files, removed 0 files
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s"]);
+    insta::assert_snapshot!(stdout, @"M file1");
+}
+
 #[test]
 fn test_diffedit_new_file() {
     let mut test_env = TestEnvironment::default();
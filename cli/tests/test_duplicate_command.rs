@@ -2398,6 +2398,41 @@ fn test_rebase_duplicates() {
     "#);
 }
 
+#[test]
+fn test_duplicate_move_bookmarks() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    create_commit(&test_env, &repo_path, "d", &[]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["duplicate", "-r", "b::c", "-d", "d", "--move-bookmarks"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Duplicated 1394f625cbbd as znkkpsqq 2b9403df b | b
+    Duplicated 7e4fbf4f2759 as uuzqqzqu 3b349b41 c | c
+    Moved 2 bookmarks to the duplicated commits
+    ");
+    let template = r#"description.first_line() ++ " " ++ bookmarks"#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    ○  c c
+    ○  b b
+    @  d d
+    │ ○  c
+    │ ○  b
+    │ ○  a a
+    ├─╯
+    ◆
+    ");
+}
+
 fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     let template = r#"commit_id.short() ++ "   " ++ description.first_line()"#;
     test_env.jj_cmd_success(repo_path, &["log", "-T", template])
@@ -331,6 +331,7 @@ fn test_evolog_with_no_template() {
 
     For more information, try '--help'.
     Hint: The following template aliases are defined:
+    - builtin_config_list_origin
     - builtin_log_comfortable
     - builtin_log_compact
     - builtin_log_compact_full_description
@@ -348,3 +349,47 @@ fn test_evolog_with_no_template() {
     - name_placeholder
     "#);
 }
+
+#[test]
+fn test_evolog_divergent() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "initial"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first version"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m", "second version", "--at-op", "@-"],
+    );
+
+    // Both versions are shown along with their common predecessors
+    let template = r#"commit_id.short() ++ " " ++ description ++ "\n""#;
+    let (stdout, _stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["evolog", "-r", "description(first)", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    @  4e61ddfdc59c first version
+    │
+    │ ○  15198627351c second version
+    ├─╯
+    ○  68e1101283b0 initial
+    │
+    ○  230dd059e1b0
+    ");
+
+    // The visible versions are marked as divergent
+    let (stdout, _stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["evolog", "-r", "description(second)"]);
+    insta::assert_snapshot!(stdout, @r"
+    ○  qpvuntsm?? test.user@example.com 2001-02-03 08:05:10 15198627
+    │  (empty) second version
+    │ @  qpvuntsm?? test.user@example.com 2001-02-03 08:05:09 4e61ddfd
+    ├─╯  (empty) first version
+    ○  qpvuntsm hidden test.user@example.com 2001-02-03 08:05:08 68e11012
+    │  (empty) initial
+    ○  qpvuntsm hidden test.user@example.com 2001-02-03 08:05:07 230dd059
+       (empty) (no description set)
+    ");
+}
@@ -0,0 +1,140 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_file_lock_shared_through_remote() {
+    let test_env = TestEnvironment::default();
+    git2::Repository::init_bare(test_env.env_root().join("origin")).unwrap();
+    for name in ["alice", "bob"] {
+        test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", name]);
+        test_env.jj_cmd_ok(
+            &test_env.env_root().join(name),
+            &["git", "remote", "add", "origin", "../origin"],
+        );
+    }
+    let alice_path = test_env.env_root().join("alice");
+    let bob_path = test_env.env_root().join("bob");
+    let bob_config = "--config=user.email=bob@example.com";
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &alice_path,
+        &["file", "lock", "--remote=origin", "asset.bin"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Locked asset.bin");
+
+    // Alice can re-lock a file she already holds
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &alice_path,
+        &["file", "lock", "--remote=origin", "asset.bin"],
+    );
+    insta::assert_snapshot!(stderr, @"Locked asset.bin");
+
+    // Bob sees the lock after fetching, and gets warned when editing the file
+    test_env.jj_cmd_ok(&bob_path, &["git", "fetch", bob_config]);
+    std::fs::write(bob_path.join("asset.bin"), "data").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&bob_path, &["status", bob_config]);
+    insta::assert_snapshot!(stderr, @"Warning: asset.bin is locked by Test User <test.user@example.com>");
+
+    // Bob can't take or release the lock
+    let stderr = test_env.jj_cmd_failure(&bob_path, &["file", "lock", "asset.bin", bob_config]);
+    insta::assert_snapshot!(stderr, @"Error: File asset.bin is already locked by Test User <test.user@example.com>");
+    let stderr = test_env.jj_cmd_failure(&bob_path, &["file", "unlock", "asset.bin", bob_config]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: File asset.bin is locked by Test User <test.user@example.com>
+    Hint: Use --force to release it anyway.
+    ");
+
+    // Alice doesn't get warned about her own lock
+    std::fs::write(alice_path.join("asset.bin"), "data").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&alice_path, &["status"]);
+    insta::assert_snapshot!(stderr, @"");
+
+    // Once Alice releases the lock, Bob can take it
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&alice_path, &["file", "unlock", "asset.bin"]);
+    insta::assert_snapshot!(stderr, @"Unlocked asset.bin");
+    test_env.jj_cmd_ok(&bob_path, &["git", "fetch", bob_config]);
+    std::fs::write(bob_path.join("asset.bin"), "more data").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &bob_path,
+        &["file", "lock", "--remote=origin", "asset.bin", bob_config],
+    );
+    insta::assert_snapshot!(stderr, @"Locked asset.bin");
+}
+
+#[test]
+fn test_file_lock_remote_name_with_slash() {
+    let test_env = TestEnvironment::default();
+    git2::Repository::init_bare(test_env.env_root().join("origin")).unwrap();
+    for name in ["alice", "bob"] {
+        test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", name]);
+        test_env.jj_cmd_ok(
+            &test_env.env_root().join(name),
+            &["git", "remote", "add", "team/origin", "../origin"],
+        );
+    }
+    let alice_path = test_env.env_root().join("alice");
+    let bob_path = test_env.env_root().join("bob");
+    let bob_config = "--config=user.email=bob@example.com";
+
+    test_env.jj_cmd_ok(
+        &alice_path,
+        &["file", "lock", "--remote=team/origin", "asset.bin"],
+    );
+    test_env.jj_cmd_ok(
+        &bob_path,
+        &["git", "fetch", "--remote=team/origin", bob_config],
+    );
+    std::fs::write(bob_path.join("asset.bin"), "data").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&bob_path, &["status", bob_config]);
+    insta::assert_snapshot!(stderr, @"Warning: asset.bin is locked by Test User <test.user@example.com>");
+}
+
+#[test]
+fn test_file_lock_local() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "lock", "a", "b"]);
+    insta::assert_snapshot!(stderr, @r"
+    Locked a
+    Locked b
+    ");
+
+    // Another user is warned about the local lock
+    std::fs::write(repo_path.join("a"), "a").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["status", "--config=user.email=other@example.com"],
+    );
+    insta::assert_snapshot!(stderr, @"Warning: a is locked by Test User <test.user@example.com>");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "file",
+            "unlock",
+            "--force",
+            "a",
+            "--config=user.email=other@example.com",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @"Unlocked a");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["file", "unlock", "a"]);
+    insta::assert_snapshot!(stderr, @"Error: File a is not locked");
+}
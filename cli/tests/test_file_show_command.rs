@@ -94,6 +94,37 @@ fn test_show() {
     "###);
 }
 
+#[cfg(feature = "syntax-highlight")]
+#[test]
+fn test_show_syntax_highlight() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("main.rs"), "// hi\nfn main() {}\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "file",
+            "show",
+            "main.rs",
+            "--color=debug",
+            "--config=ui.syntax-highlight=true",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    [38;5;8m<<syntax comment::// hi>>[39m
+    [38;5;5m<<syntax storage::fn>>[39m [38;5;4m<<syntax entity::main>>[39m() {}
+    "#);
+
+    // Not highlighted by default
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "main.rs", "--color=debug"]);
+    insta::assert_snapshot!(stdout, @r#"
+    // hi
+    fn main() {}
+    "#);
+}
+
 #[cfg(unix)]
 #[test]
 fn test_show_symlink() {
@@ -116,3 +147,156 @@ fn test_show_symlink() {
     Warning: Path 'symlink1' exists but is not a file
     "###);
 }
+
+#[cfg(unix)]
+#[test]
+fn test_show_output_dir() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::create_dir_all(repo_path.join("docs").join("sub")).unwrap();
+    std::fs::write(repo_path.join("docs").join("a.md"), "a\n").unwrap();
+    std::fs::write(repo_path.join("docs").join("sub").join("b.md"), "b\n").unwrap();
+    std::fs::write(repo_path.join("docs").join("run.sh"), "run\n").unwrap();
+    std::fs::set_permissions(
+        repo_path.join("docs").join("run.sh"),
+        std::fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+    std::os::unix::fs::symlink("a.md", repo_path.join("docs").join("link")).unwrap();
+    std::fs::write(repo_path.join("other"), "other\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::remove_dir_all(repo_path.join("docs")).unwrap();
+
+    // Exports the selected paths at the revision, keeping their paths
+    let export_path = test_env.env_root().join("export");
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "file",
+            "show",
+            "-r@-",
+            "glob:docs/**",
+            "--output-dir",
+            export_path.to_str().unwrap(),
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Exported 4 files to $TEST_ENV/export");
+    let export_docs = export_path.join("docs");
+    assert_eq!(std::fs::read(export_docs.join("a.md")).unwrap(), b"a\n");
+    assert_eq!(
+        std::fs::read(export_docs.join("sub").join("b.md")).unwrap(),
+        b"b\n"
+    );
+    let mode = std::fs::metadata(export_docs.join("run.sh"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o111, 0o111);
+    assert_eq!(
+        std::fs::read_link(export_docs.join("link")).unwrap(),
+        std::path::Path::new("a.md")
+    );
+    assert!(!export_path.join("other").exists());
+
+    // Existing files and directories in the way are replaced
+    std::fs::remove_file(export_docs.join("a.md")).unwrap();
+    std::fs::create_dir_all(export_docs.join("a.md").join("dir")).unwrap();
+    std::fs::write(export_docs.join("sub").join("b.md"), "old\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "file",
+            "show",
+            "-r@-",
+            "glob:docs/**",
+            "--output-dir",
+            export_path.to_str().unwrap(),
+        ],
+    );
+    assert_eq!(std::fs::read(export_docs.join("a.md")).unwrap(), b"a\n");
+    assert_eq!(
+        std::fs::read(export_docs.join("sub").join("b.md")).unwrap(),
+        b"b\n"
+    );
+
+    // Cannot export to a directory and as a tarball at once
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["file", "show", "--tar", "--output-dir=x", "."],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--tar' cannot be used with '--output-dir <OUTPUT_DIR>'
+
+    Usage: jj file show --tar <PATHS>...
+
+    For more information, try '--help'.
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_show_tar() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let long_dir = "d".repeat(120);
+    std::fs::create_dir_all(repo_path.join("docs").join(&long_dir)).unwrap();
+    std::fs::write(repo_path.join("docs").join("a.md"), "a\n").unwrap();
+    std::fs::write(repo_path.join("docs").join(&long_dir).join("b.md"), "b\n").unwrap();
+    std::os::unix::fs::symlink("a.md", repo_path.join("docs").join("link")).unwrap();
+    std::fs::write(repo_path.join("other"), "other\n").unwrap();
+
+    let assert = test_env
+        .jj_cmd(&repo_path, &["file", "show", "--tar", "docs"])
+        .assert()
+        .success();
+    let archive = assert.get_output().stdout.as_slice();
+    assert_eq!(archive.len() % 512, 0);
+
+    // List the entries of the archive
+    let field = |header: &[u8], range: std::ops::Range<usize>| {
+        let field = &header[range];
+        let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        String::from_utf8(field[..end].to_vec()).unwrap()
+    };
+    let mut entries = vec![];
+    let mut blocks = archive.chunks(512);
+    while let Some(header) = blocks.next() {
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let prefix = field(header, 345..500);
+        let name = field(header, 0..100);
+        let path = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}/{name}")
+        };
+        let size = usize::from_str_radix(&field(header, 124..135), 8).unwrap();
+        let contents = blocks
+            .by_ref()
+            .take(size.div_ceil(512))
+            .flatten()
+            .take(size)
+            .copied()
+            .collect::<Vec<_>>();
+        entries.push(format!(
+            "{} {} {path} {:?} {:?}",
+            field(header, 100..107),
+            header[156] as char,
+            String::from_utf8(contents).unwrap(),
+            field(header, 157..257),
+        ));
+    }
+    insta::assert_snapshot!(entries.join("\n").replace(&long_dir, "<long>"), @r#"
+    0000644 0 docs/a.md "a\n" ""
+    0000644 0 docs/<long>/b.md "b\n" ""
+    0000777 2 docs/link "" "a.md"
+    "#);
+}
@@ -220,3 +220,86 @@ fn test_track_ignored() {
     file1
     "###);
 }
+
+#[test]
+fn test_track_large_file() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"
+        snapshot.auto-track = "none()"
+        snapshot.max-new-file-size = 5
+        "#,
+    );
+    std::fs::write(repo_path.join("large1"), "too large").unwrap();
+    std::fs::write(repo_path.join("large2"), "too large").unwrap();
+
+    // Refused without a terminal to prompt on
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "track", "large1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Refused to snapshot some files:
+      large1: 9.0B (9 bytes); the maximum size allowed is 5.0B (5 bytes)
+    Hint: This is to prevent large files from being added by accident. You can fix this by:
+      - Adding the file to `.gitignore`
+      - Run `jj file track --force <path>`
+        This will track the file regardless of its size.
+      - Run `jj config set --repo snapshot.max-new-file-size 9`
+        This will increase the maximum file size allowed for new files, in this repository only.
+      - Run `jj --config snapshot.max-new-file-size=9 st`
+        This will increase the maximum file size allowed for new files, for this command only.
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "list"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    // Declining the prompt leaves the file untracked
+    let (stdout, stderr) =
+        test_env.jj_cmd_stdin_ok(&repo_path, &["file", "track", "large1"], "n\n");
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Refused to snapshot some files:
+      large1: 9.0B (9 bytes); the maximum size allowed is 5.0B (5 bytes)
+    Hint: This is to prevent large files from being added by accident. You can fix this by:
+      - Adding the file to `.gitignore`
+      - Run `jj file track --force <path>`
+        This will track the file regardless of its size.
+      - Run `jj config set --repo snapshot.max-new-file-size 9`
+        This will increase the maximum file size allowed for new files, in this repository only.
+      - Run `jj --config snapshot.max-new-file-size=9 st`
+        This will increase the maximum file size allowed for new files, for this command only.
+    Track them anyway? (yN):
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "list"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    // Accepting the prompt tracks the file
+    let (stdout, stderr) =
+        test_env.jj_cmd_stdin_ok(&repo_path, &["file", "track", "large1"], "y\n");
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Refused to snapshot some files:
+      large1: 9.0B (9 bytes); the maximum size allowed is 5.0B (5 bytes)
+    Hint: This is to prevent large files from being added by accident. You can fix this by:
+      - Adding the file to `.gitignore`
+      - Run `jj file track --force <path>`
+        This will track the file regardless of its size.
+      - Run `jj config set --repo snapshot.max-new-file-size 9`
+        This will increase the maximum file size allowed for new files, in this repository only.
+      - Run `jj --config snapshot.max-new-file-size=9 st`
+        This will increase the maximum file size allowed for new files, for this command only.
+    Track them anyway? (yN):
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "list"]);
+    insta::assert_snapshot!(stdout, @"large1");
+
+    // --force tracks the file without asking
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "track", "--force", "large2"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    large1
+    large2
+    ");
+}
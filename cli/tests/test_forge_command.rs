@@ -0,0 +1,292 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "forge")]
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::common::TestEnvironment;
+
+/// Sets up a clone of the "origin" repo, which is hosted on a fake GitHub.
+fn set_up() -> (TestEnvironment, PathBuf) {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "origin"]);
+    let origin_path = test_env.env_root().join("origin");
+    test_env.jj_cmd_ok(&origin_path, &["describe", "-m=description 1"]);
+    test_env.jj_cmd_ok(&origin_path, &["bookmark", "create", "main"]);
+    test_env.jj_cmd_ok(&origin_path, &["git", "export"]);
+    let origin_git_repo_path = origin_path.join(".jj/repo/store/git");
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &[
+            "git",
+            "clone",
+            origin_git_repo_path.to_str().unwrap(),
+            "local",
+        ],
+    );
+
+    // The fake tool records its arguments, and prints the pull requests from
+    // a file.
+    let fake_gh = test_env.env_root().join("fake-gh");
+    std::fs::write(
+        &fake_gh,
+        r#"#!/bin/sh
+dir=$(dirname "$0")
+echo "$* (token: $GH_TOKEN)" >> "$dir/gh-args"
+case "$1 $2" in
+  "pr list") cat "$dir/pull-requests.json" ;;
+  "pr create") echo "Creating pull request"; echo "https://github.com/owner/repo/pull/7" ;;
+  *) echo "unexpected arguments" >&2; exit 1 ;;
+esac
+"#,
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        std::fs::set_permissions(&fake_gh, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    test_env.add_config(&format!(
+        r#"
+        forge.kind = "github"
+        forge.github.program = "{}"
+        forge.github.token = "secret"
+        "#,
+        fake_gh.to_str().unwrap().replace('\\', "\\\\")
+    ));
+    let workspace_root = test_env.env_root().join("local");
+    (test_env, workspace_root)
+}
+
+fn read_gh_args(test_env: &TestEnvironment) -> String {
+    let path = test_env.env_root().join("gh-args");
+    let args = std::fs::read_to_string(&path).unwrap_or_default();
+    std::fs::remove_file(&path).ok();
+    args
+}
+
+fn write_pull_requests(test_env: &TestEnvironment, json: &str) {
+    std::fs::write(test_env.env_root().join("pull-requests.json"), json).unwrap();
+}
+
+fn get_bookmark_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    test_env.jj_cmd_success(repo_path, &["bookmark", "list", "--all-remotes"])
+}
+
+#[cfg(unix)]
+#[test]
+fn test_forge_list() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "feature", "-r@-"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "other", "-r@-"]);
+    write_pull_requests(
+        &test_env,
+        r#"[
+          {"number": 1, "title": "Add feature", "url": "https://github.com/owner/repo/pull/1",
+           "headRefName": "feature", "baseRefName": "main"},
+          {"number": 2, "title": "Other change", "url": "https://github.com/owner/repo/pull/2",
+           "headRefName": "other", "baseRefName": "feature"},
+          {"number": 3, "title": "Not mine", "url": "https://github.com/owner/repo/pull/3",
+           "headRefName": "unknown", "baseRefName": "main"}
+        ]"#,
+    );
+
+    // Only pull requests for local bookmarks are listed
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["forge", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    feature #1 -> main: Add feature
+      https://github.com/owner/repo/pull/1
+    other #2 -> feature: Other change
+      https://github.com/owner/repo/pull/2
+    ");
+    insta::assert_snapshot!(read_gh_args(&test_env), @"pr list --state=open --limit=1000 --json=number,title,url,headRefName,baseRefName (token: secret)");
+
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["forge", "list", "-b=glob:oth*"]);
+    insta::assert_snapshot!(stdout, @r"
+    other #2 -> feature: Other change
+      https://github.com/owner/repo/pull/2
+    ");
+
+    // Errors from the tool are reported
+    write_pull_requests(&test_env, "not json");
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["forge", "list"]);
+    insta::assert_snapshot!(stderr, @"Error: Unexpected output from $TEST_ENV/fake-gh: expected ident at line 1 column 2");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_forge_create() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["describe", "-m", "Add feature\n\nThis adds a feature."],
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "feature"]);
+
+    // The bookmark must be pushed first
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["forge", "create", "-b=feature"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Bookmark feature has not been pushed to origin
+    Hint: Run `jj git push --bookmark feature --remote origin` first.
+    ");
+
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "-b=feature", "--allow-new"],
+    );
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["forge", "create", "-b=feature"]);
+    insta::assert_snapshot!(stdout, @"https://github.com/owner/repo/pull/7");
+    insta::assert_snapshot!(stderr, @"Created pull request #7 on GitHub for bookmark feature");
+    insta::assert_snapshot!(read_gh_args(&test_env), @"pr create --head feature --title Add feature --body This adds a feature. (token: secret)");
+
+    // Explicit title, body, and base
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "forge",
+            "create",
+            "-b=feature",
+            "--base=main",
+            "--title=Title",
+            "--body=Body",
+            "--draft",
+        ],
+    );
+    insta::assert_snapshot!(read_gh_args(&test_env), @"pr create --head feature --base main --title Title --body Body --draft (token: secret)");
+
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["forge", "create", "-b=unknown"]);
+    insta::assert_snapshot!(stderr, @"Error: No such bookmark: unknown");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_forge_fetch() {
+    let (test_env, workspace_root) = set_up();
+    // Create a pull request ref on the remote, as if it came from a fork
+    let origin_path = test_env.env_root().join("origin");
+    test_env.jj_cmd_ok(&origin_path, &["new", "main", "-m=pull request 3"]);
+    let origin_git_repo = git2::Repository::open(origin_path.join(".jj/repo/store/git")).unwrap();
+    let stdout =
+        test_env.jj_cmd_success(&origin_path, &["log", "--no-graph", "-r@", "-T=commit_id"]);
+    origin_git_repo
+        .reference(
+            "refs/pull/3/head",
+            git2::Oid::from_str(&stdout).unwrap(),
+            false,
+            "",
+        )
+        .unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["forge", "fetch", "3"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Fetched pull request #3 into bookmark pr-3 at b42aa1cb7876");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    main@origin: xtvrqkyv d13ecdbd (empty) description 1
+    pr-3: xmnxtvns b42aa1cb (empty) pull request 3
+    ");
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["log", "-r=pr-3", "-T=description"]);
+    insta::assert_snapshot!(stdout, @r"
+    ○  pull request 3
+    │
+    ~
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["forge", "fetch", "4"]);
+    insta::assert_snapshot!(stderr, @"Error: No pull request #4 on origin");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_forge_list_gitlab_pages() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "feature", "-r@-"]);
+    let fake_glab = test_env.env_root().join("fake-glab");
+    std::fs::write(
+        &fake_glab,
+        r#"#!/bin/sh
+dir=$(dirname "$0")
+echo "$*" >> "$dir/glab-args"
+for arg in "$@"; do
+  case "$arg" in
+    --page=*) cat "$dir/merge-requests-${arg#--page=}.json" ;;
+  esac
+done
+"#,
+    )
+    .unwrap();
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        std::fs::set_permissions(&fake_glab, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    test_env.add_config(&format!(
+        r#"
+        forge.kind = "gitlab"
+        forge.gitlab.program = "{}"
+        "#,
+        fake_glab.to_str().unwrap()
+    ));
+    // The first page is full, so the second page is requested too
+    let merge_request = |iid: usize, branch: &str| {
+        format!(
+            r#"{{"iid": {iid}, "title": "Change {iid}", "web_url": "https://gitlab.com/owner/repo/-/merge_requests/{iid}",
+               "source_branch": "{branch}", "target_branch": "main"}}"#
+        )
+    };
+    let first_page = (1..=100).map(|iid| merge_request(iid, "unknown"));
+    std::fs::write(
+        test_env.env_root().join("merge-requests-1.json"),
+        format!("[{}]", first_page.collect::<Vec<_>>().join(",")),
+    )
+    .unwrap();
+    std::fs::write(
+        test_env.env_root().join("merge-requests-2.json"),
+        format!("[{}]", merge_request(101, "feature")),
+    )
+    .unwrap();
+
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["forge", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    feature #101 -> main: Change 101
+      https://gitlab.com/owner/repo/-/merge_requests/101
+    ");
+    let args = std::fs::read_to_string(test_env.env_root().join("glab-args")).unwrap();
+    insta::assert_snapshot!(args, @r"
+    mr list --output=json --page=1 --per-page=100
+    mr list --output=json --page=2 --per-page=100
+    ");
+}
+
+#[test]
+fn test_forge_unknown() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "origin",
+            "https://example.com/repo.git",
+        ],
+    );
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["forge", "list"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Cannot determine the forge hosting remote 'origin'
+    Hint: Set `forge.kind` to "github" or "gitlab".
+    "#);
+}
@@ -0,0 +1,264 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn create_commits(test_env: &TestEnvironment, repo_path: &Path) {
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(
+        repo_path,
+        &["commit", "-m", "Add file1\n\nWith a longer body."],
+    );
+    std::fs::write(repo_path.join("file1"), "foo\nbar\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "baz\n").unwrap();
+    test_env.jj_cmd_ok(repo_path, &["commit", "-m", "Modify file1, add file2"]);
+}
+
+fn redact_version(output: &str) -> String {
+    output.replace(env!("CARGO_PKG_VERSION"), "[VERSION]")
+}
+
+#[test]
+fn test_format_patch_stdout() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_commits(&test_env, &repo_path);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["format-patch", "-r", "@-"]);
+    insta::assert_snapshot!(redact_version(&stdout), @r"
+    From b3df039d1e54541decd692e9f67d2594e83cc369 Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:09 +0700
+    Subject: [PATCH] Modify file1, add file2
+
+    ---
+    file1 | 1 +
+    file2 | 1 +
+    2 files changed, 2 insertions(+), 0 deletions(-)
+
+    diff --git a/file1 b/file1
+    index 257cc5642c..3bd1f0e297 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,1 +1,2 @@
+     foo
+    +bar
+    diff --git a/file2 b/file2
+    new file mode 100644
+    index 0000000000..76018072e0
+    --- /dev/null
+    +++ b/file2
+    @@ -0,0 +1,1 @@
+    +baz
+    -- 
+    jj [VERSION]
+    ");
+    insta::assert_snapshot!(stderr, @"");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "format-patch",
+            "-r",
+            "root()..@-",
+            "--subject-prefix=RFC PATCH",
+            "-v2",
+            "--in-reply-to=cover@example.com",
+        ],
+    );
+    insta::assert_snapshot!(redact_version(&stdout), @r"
+    From 1ecded722512e89f9dda0a24e277a9d36eb375cf Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:08 +0700
+    Subject: [RFC PATCH v2 1/2] Add file1
+    In-Reply-To: <cover@example.com>
+    References: <cover@example.com>
+
+    With a longer body.
+
+    ---
+    file1 | 1 +
+    1 file changed, 1 insertion(+), 0 deletions(-)
+
+    diff --git a/file1 b/file1
+    new file mode 100644
+    index 0000000000..257cc5642c
+    --- /dev/null
+    +++ b/file1
+    @@ -0,0 +1,1 @@
+    +foo
+    -- 
+    jj [VERSION]
+
+    From b3df039d1e54541decd692e9f67d2594e83cc369 Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:09 +0700
+    Subject: [RFC PATCH v2 2/2] Modify file1, add file2
+    In-Reply-To: <cover@example.com>
+    References: <cover@example.com>
+
+    ---
+    file1 | 1 +
+    file2 | 1 +
+    2 files changed, 2 insertions(+), 0 deletions(-)
+
+    diff --git a/file1 b/file1
+    index 257cc5642c..3bd1f0e297 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,1 +1,2 @@
+     foo
+    +bar
+    diff --git a/file2 b/file2
+    new file mode 100644
+    index 0000000000..76018072e0
+    --- /dev/null
+    +++ b/file2
+    @@ -0,0 +1,1 @@
+    +baz
+    -- 
+    jj [VERSION]
+    ");
+    insta::assert_snapshot!(stderr, @"");
+}
+
+#[test]
+fn test_format_patch_output_directory() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_commits(&test_env, &repo_path);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["format-patch", "-r", "root()..@-", "-o", "patches"],
+    );
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    patches/0001-Add-file1.patch
+    patches/0002-Modify-file1-add-file2.patch
+    ");
+    insta::assert_snapshot!(stderr, @"");
+    let patch = std::fs::read_to_string(repo_path.join("patches/0001-Add-file1.patch")).unwrap();
+    insta::assert_snapshot!(redact_version(&patch), @r"
+    From 1ecded722512e89f9dda0a24e277a9d36eb375cf Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:08 +0700
+    Subject: [PATCH 1/2] Add file1
+
+    With a longer body.
+
+    ---
+    file1 | 1 +
+    1 file changed, 1 insertion(+), 0 deletions(-)
+
+    diff --git a/file1 b/file1
+    new file mode 100644
+    index 0000000000..257cc5642c
+    --- /dev/null
+    +++ b/file1
+    @@ -0,0 +1,1 @@
+    +foo
+    -- 
+    jj [VERSION]
+    ");
+}
+
+#[test]
+fn test_format_patch_non_ascii() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "café\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "commit",
+            "--config=user.name=Zoë",
+            "--reset-author",
+            "-m",
+            "Add café\n\nFrom the menu.",
+        ],
+    );
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["format-patch", "-r", "@-"]);
+    insta::assert_snapshot!(redact_version(&stdout), @r"
+    From a801e1865cc6f5b22e9e1b57054d9f09ed6bc926 Mon Sep 17 00:00:00 2001
+    From: =?UTF-8?q?Zo=C3=AB?= <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:08 +0700
+    Subject: [PATCH] =?UTF-8?q?Add=20caf=C3=A9?=
+    MIME-Version: 1.0
+    Content-Type: text/plain; charset=UTF-8
+    Content-Transfer-Encoding: 8bit
+
+    >From the menu.
+
+    ---
+    file | 1 +
+    1 file changed, 1 insertion(+), 0 deletions(-)
+
+    diff --git a/file b/file
+    new file mode 100644
+    index 0000000000..572eb43fe8
+    --- /dev/null
+    +++ b/file
+    @@ -0,0 +1,1 @@
+    +café
+    -- 
+    jj [VERSION]
+    ");
+
+    // The patch can be applied back
+    let patch_path = test_env.env_root().join("patch.mbox");
+    std::fs::write(&patch_path, &stdout).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()"]);
+    test_env.jj_cmd_ok(&repo_path, &["apply", patch_path.to_str().unwrap()]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-rchildren(@)",
+            "-T",
+            r#"author.name() ++ "\n" ++ description"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    Zoë
+    Add café
+
+    From the menu.
+    ");
+}
+
+#[test]
+fn test_format_patch_merge_and_root() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(a)", "description(b)"]);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["format-patch"]);
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Skipping merge commit zsuskuln c6b17ecd (empty) (no description set)
+    Error: No revisions to format
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["format-patch", "-r", "root()"]);
+    insta::assert_snapshot!(stderr, @"Error: Cannot format the root commit as a patch");
+}
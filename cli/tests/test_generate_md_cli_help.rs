@@ -21,6 +21,8 @@ const PREAMBLE: &str = r#"
 
 "#;
 
+// The reference documents the commands of the default features.
+#[cfg(feature = "forge")]
 #[test]
 fn test_generate_markdown_docs_in_docs_dir() {
     let test_env = TestEnvironment::default();
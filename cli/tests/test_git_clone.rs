@@ -354,6 +354,69 @@ fn test_git_clone_colocate() {
     "###);
 }
 
+#[test]
+fn test_git_clone_bare() {
+    let test_env = TestEnvironment::default();
+    let git_repo_path = test_env.env_root().join("source");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    set_up_non_empty_git_repo(&git_repo);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", "--bare", "source", "clone"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Fetching into new repo in "$TEST_ENV/clone"
+    bookmark: main@origin [new] untracked
+    Setting the revset alias "trunk()" to "main@origin"
+    "#);
+    let clone_path = test_env.env_root().join("clone");
+    assert!(!clone_path.join("file").exists());
+
+    // Commands that don't need a working copy work
+    let stdout = test_env.jj_cmd_success(&clone_path, &["log"]);
+    insta::assert_snapshot!(stdout, @r"
+    ◆  mzyxwzks some.one@example.com 1970-01-01 11:00:00 main 9f01a0e0
+    │  message
+    ~
+    ");
+    let stdout = test_env.jj_cmd_success(&clone_path, &["status"]);
+    insta::assert_snapshot!(stdout, @"No working copy");
+    let stdout = test_env.jj_cmd_success(&clone_path, &["workspace", "list"]);
+    insta::assert_snapshot!(stdout, @"");
+    let stdout = test_env.jj_cmd_success(&clone_path, &["file", "list", "-r", "main"]);
+    insta::assert_snapshot!(stdout, @"file");
+    let stderr = test_env.jj_cmd_failure(&clone_path, &["diff"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Workspace "default" doesn't have a working-copy commit
+    Hint: Specify a revision explicitly. In a bare repo, run `jj workspace add --first` to check out a working copy.
+    "#);
+
+    // The working copy can be checked out later
+    let (stdout, stderr) = test_env.jj_cmd_ok(&clone_path, &["workspace", "add", "--first"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: yqosqzyt a54b825a (empty) (no description set)
+    Parent commit      : mzyxwzks 9f01a0e0 main | message
+    Added 1 files, modified 0 files, removed 0 files
+    ");
+    assert!(clone_path.join("file").exists());
+
+    // Cannot be combined with --colocate
+    let stderr = test_env.jj_cmd_cli_error(
+        test_env.env_root(),
+        &["git", "clone", "--bare", "--colocate", "source", "clone2"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--bare' cannot be used with '--colocate'
+
+    Usage: jj git clone --bare <SOURCE> <DESTINATION>
+
+    For more information, try '--help'.
+    ");
+}
+
 #[test]
 fn test_git_clone_remote_default_bookmark() {
     let test_env = TestEnvironment::default();
@@ -85,6 +85,28 @@ fn test_git_fetch_with_default_config() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_with_bad_ssl_ca_info() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+
+    // The fetch proceeds, but the user is told about the unusable bundle
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "fetch", "--config=git.ssl-ca-info=nonexistent.pem"],
+    );
+    // The error message depends on the TLS library
+    let (warning, stderr) = stderr.split_once('\n').unwrap();
+    assert!(
+        warning.starts_with("Warning: Failed to load CA certificates from nonexistent.pem: "),
+        "{warning}"
+    );
+    insta::assert_snapshot!(stderr, @"bookmark: origin@origin [new] untracked");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @"origin@origin: oputwtnw ffecd2d6 message");
+}
+
 #[test]
 fn test_git_fetch_default_remote() {
     let test_env = TestEnvironment::default();
@@ -238,6 +260,7 @@ fn test_git_fetch_nonexistent_remote() {
         &["git", "fetch", "--remote", "rem1", "--remote", "rem2"],
     );
     insta::assert_snapshot!(stderr, @r###"
+    Fetched from rem1
     bookmark: rem1@rem1 [new] untracked
     Error: No git remote named 'rem2'
     "###);
@@ -255,6 +278,7 @@ fn test_git_fetch_nonexistent_remote_from_config() {
 
     let stderr = &test_env.jj_cmd_failure(&repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(stderr, @r###"
+    Fetched from rem1
     bookmark: rem1@rem1 [new] untracked
     Error: No git remote named 'rem2'
     "###);
@@ -336,6 +360,122 @@ fn test_git_fetch_prune_before_updating_tips() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_prune() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    let git_repo = git2::Repository::open(test_env.env_root().join("origin")).unwrap();
+    let commit = git_repo
+        .find_branch("origin", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    git_repo.branch("feature", &commit, false).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    // Move the local bookmark so it isn't deleted along with the remote one
+    test_env.jj_cmd_ok(&repo_path, &["new", "feature"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "set", "feature"]);
+
+    // Remove feature bookmark in git repo
+    git_repo
+        .find_branch("feature", git2::BranchType::Local)
+        .unwrap()
+        .delete()
+        .unwrap();
+
+    // Deleted remote bookmarks are kept if pruning is disabled
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--no-prune"]);
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+    test_env.add_config("git.fetch-prune = false");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    feature: zsuskuln 6340f705 (empty) (no description set)
+      @origin (behind by 1 commits): oputwtnw ffecd2d6 message
+    origin: oputwtnw ffecd2d6 message
+      @origin: oputwtnw ffecd2d6 message
+    ");
+
+    // Per-remote setting overrides the default
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "fetch", "--config=remotes.origin.fetch-prune=true"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    bookmark: feature@origin [deleted] untracked
+    These local bookmarks no longer track any remote bookmark:
+      feature
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    feature (conflicted):
+      - oputwtnw ffecd2d6 message
+      + zsuskuln 6340f705 (empty) (no description set)
+    origin: oputwtnw ffecd2d6 message
+      @origin: oputwtnw ffecd2d6 message
+    ");
+}
+
+#[test]
+fn test_git_fetch_refspec() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    let git_repo = git2::Repository::open(test_env.env_root().join("origin")).unwrap();
+    let commit = git_repo
+        .find_branch("origin", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    git_repo.branch("team/a", &commit, false).unwrap();
+    git_repo.branch("team/b", &commit, false).unwrap();
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "fetch",
+            "--refspec",
+            "+refs/heads/team/a:refs/remotes/origin/team/a",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @"bookmark: team/a@origin [new] tracked");
+
+    // Configured refspecs are used if no --refspec is specified
+    test_env.add_config(
+        r#"remotes.origin.fetch-refspecs = ["+refs/heads/team/*:refs/remotes/origin/team/*"]"#,
+    );
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @"bookmark: team/b@origin [new] tracked");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    team/a: oputwtnw ffecd2d6 message
+      @origin: oputwtnw ffecd2d6 message
+    team/b: oputwtnw ffecd2d6 message
+      @origin: oputwtnw ffecd2d6 message
+    ");
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["git", "fetch", "--refspec", "refs/heads/*:refs/heads/main"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value 'refs/heads/*:refs/heads/main' for '--refspec <REFSPEC>': Invalid refspec 'refs/heads/*:refs/heads/main': either both or neither side must contain `*`
+
+    For more information, try '--help'.
+    ");
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["git", "fetch", "--refspec", "refs/heads/*:refs/heads/*"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Fetch refspec 'refs/heads/*:refs/heads/*' must map to remote-tracking refs under 'refs/remotes/origin/'");
+}
+
 #[test]
 fn test_git_fetch_conflicting_bookmarks() {
     let test_env = TestEnvironment::default();
@@ -739,6 +739,62 @@ fn test_git_init_colocated_via_flag_git_dir_exists() {
     "#);
 }
 
+#[test]
+fn test_git_init_colocated_import_stashes() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let mut git_repo = init_git_repo(&workspace_root, false);
+    git_repo
+        .checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .unwrap();
+    std::fs::write(workspace_root.join("some-file"), "modified").unwrap();
+    std::fs::write(workspace_root.join("untracked-file"), "untracked").unwrap();
+    let git_signature = git2::Signature::new(
+        "Git User",
+        "git.user@example.com",
+        &git2::Time::new(456, 60),
+    )
+    .unwrap();
+    git_repo
+        .stash_save2(
+            &git_signature,
+            Some("work in progress"),
+            Some(git2::StashFlags::INCLUDE_UNTRACKED),
+        )
+        .unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "init", "--colocate", "--import-stashes", "repo"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Done importing changes from the underlying Git repo.
+    Imported Git stash as lrwmptuv e24ccd4a On my-bookmark: work in progress
+    Initialized repo in "repo"
+    "#);
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_root), @r"
+    @  f6950fc115ae
+    │ ○  e24ccd4a9ffc On my-bookmark: work in progress
+    ├─╯
+    ○  8d698d4a8ee1 my-bookmark git_head() My commit message
+    ◆  000000000000
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &workspace_root,
+        &["diff", "--summary", "-r", "description(work)"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    M some-file
+    A untracked-file
+    ");
+
+    // Stashes which were already imported are skipped
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "import", "--stashes"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+}
+
 #[test]
 fn test_git_init_colocated_via_flag_git_dir_not_exists() {
     let test_env = TestEnvironment::default();
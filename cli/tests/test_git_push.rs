@@ -718,6 +718,102 @@ fn test_git_push_changes() {
     "#);
 }
 
+#[test]
+fn test_git_push_stack() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-m", "foo"]);
+    std::fs::write(workspace_root.join("file"), "foo").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "bar"]);
+    std::fs::write(workspace_root.join("file"), "bar").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "baz"]);
+    std::fs::write(workspace_root.join("file"), "baz").unwrap();
+
+    // The mapping is printed to stdout, parents first
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--stack", "bookmark1..@", "--dry-run"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    push-vruxwmqvtpmx	ae6e51bcdb31809dfada5afb388d177a0f917e9c	bookmark1
+    push-yostqsxwqrlt	78c1b3947dff3df087c95f701f161fb9dea4a854	push-vruxwmqvtpmx
+    push-znkkpsqqskkl	76313b28dc4bec5fa16231f34b38a1f768415dc4	push-yostqsxwqrlt
+    ");
+    insta::assert_snapshot!(stderr, @r"
+    Creating bookmark push-vruxwmqvtpmx for revision vruxwmqvtpmx
+    Creating bookmark push-yostqsxwqrlt for revision yostqsxwqrlt
+    Creating bookmark push-znkkpsqqskkl for revision znkkpsqqskkl
+    Changes to push to origin:
+      Add bookmark push-vruxwmqvtpmx to ae6e51bcdb31
+      Add bookmark push-yostqsxwqrlt to 78c1b3947dff
+      Add bookmark push-znkkpsqqskkl to 76313b28dc4b
+    Dry-run requested, not pushing.
+    ");
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--stack", "bookmark1..@"]);
+    insta::assert_snapshot!(stdout, @r"
+    push-vruxwmqvtpmx	ae6e51bcdb31809dfada5afb388d177a0f917e9c	bookmark1
+    push-yostqsxwqrlt	78c1b3947dff3df087c95f701f161fb9dea4a854	push-vruxwmqvtpmx
+    push-znkkpsqqskkl	76313b28dc4bec5fa16231f34b38a1f768415dc4	push-yostqsxwqrlt
+    ");
+    insta::assert_snapshot!(stderr, @r"
+    Creating bookmark push-vruxwmqvtpmx for revision vruxwmqvtpmx
+    Creating bookmark push-yostqsxwqrlt for revision yostqsxwqrlt
+    Creating bookmark push-znkkpsqqskkl for revision znkkpsqqskkl
+    Changes to push to origin:
+      Add bookmark push-vruxwmqvtpmx to ae6e51bcdb31
+      Add bookmark push-yostqsxwqrlt to 78c1b3947dff
+      Add bookmark push-znkkpsqqskkl to 76313b28dc4b
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    bookmark1: xtvrqkyv d13ecdbd (empty) description 1
+      @origin: xtvrqkyv d13ecdbd (empty) description 1
+    bookmark2: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    push-vruxwmqvtpmx: vruxwmqv ae6e51bc foo
+      @origin: vruxwmqv ae6e51bc foo
+    push-yostqsxwqrlt: yostqsxw 78c1b394 bar
+      @origin: yostqsxw 78c1b394 bar
+    push-znkkpsqqskkl: znkkpsqq 76313b28 baz
+      @origin: znkkpsqq 76313b28 baz
+    ");
+
+    // Rewriting a commit in the middle updates the bookmarks of it and its
+    // descendants
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "@-", "-m", "bar 2"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--stack", "bookmark1..@"]);
+    insta::assert_snapshot!(stdout, @r"
+    push-vruxwmqvtpmx	ae6e51bcdb31809dfada5afb388d177a0f917e9c	bookmark1
+    push-yostqsxwqrlt	cbc7addddb615b5e5858a0845b6c997754f1d9bf	push-vruxwmqvtpmx
+    push-znkkpsqqskkl	16b22e9ba23bfd6274213815cbbc085a765b63ff	push-yostqsxwqrlt
+    ");
+    insta::assert_snapshot!(stderr, @r"
+    Bookmark push-vruxwmqvtpmx@origin already matches push-vruxwmqvtpmx
+    Changes to push to origin:
+      Move sideways bookmark push-yostqsxwqrlt from 78c1b3947dff to cbc7addddb61
+      Move sideways bookmark push-znkkpsqqskkl from 76313b28dc4b to 16b22e9ba23b
+    ");
+
+    // Nothing changed, but the mapping is still printed
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--stack", "bookmark1..@"]);
+    insta::assert_snapshot!(stdout, @r"
+    push-vruxwmqvtpmx	ae6e51bcdb31809dfada5afb388d177a0f917e9c	bookmark1
+    push-yostqsxwqrlt	cbc7addddb615b5e5858a0845b6c997754f1d9bf	push-vruxwmqvtpmx
+    push-znkkpsqqskkl	16b22e9ba23bfd6274213815cbbc085a765b63ff	push-yostqsxwqrlt
+    ");
+    insta::assert_snapshot!(stderr, @r"
+    Bookmark push-vruxwmqvtpmx@origin already matches push-vruxwmqvtpmx
+    Bookmark push-yostqsxwqrlt@origin already matches push-yostqsxwqrlt
+    Bookmark push-znkkpsqqskkl@origin already matches push-znkkpsqqskkl
+    Nothing changed.
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--stack", "none()"]);
+    insta::assert_snapshot!(stderr, @"Error: No revisions in the stack to push");
+}
+
 #[test]
 fn test_git_push_revisions() {
     let (test_env, workspace_root) = set_up();
@@ -1147,6 +1243,305 @@ fn test_git_push_missing_committer_in_immutable() {
     "#);
 }
 
+#[test]
+fn test_git_push_refspec() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config(r#"remotes.origin.push-refspecs = ["refs/heads/*:refs/for/*"]"#);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-m", "review me"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "-bbookmark1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to 598bf792a5ec
+    ");
+
+    // The bookmark got pushed to the mapped ref, and the remote-tracking
+    // bookmark stays where the remote branch is
+    let origin_git_repo = git2::Repository::open(
+        test_env
+            .env_root()
+            .join("origin")
+            .join(".jj")
+            .join("repo")
+            .join("store")
+            .join("git"),
+    )
+    .unwrap();
+    let pushed_commit = origin_git_repo
+        .find_reference("refs/for/bookmark1")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(pushed_commit.message(), Some("review me\n"));
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    bookmark1: vruxwmqv 598bf792 (empty) review me
+      @origin (behind by 1 commits): xtvrqkyv d13ecdbd (empty) description 1
+    bookmark2: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    ");
+
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["bookmark", "delete", "bookmark1"]);
+    insta::assert_snapshot!(stderr, @"Deleted 1 bookmarks.");
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "-bbookmark1"]);
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Delete bookmark bookmark1 from d13ecdbda2a2
+    Error: Cannot delete bookmark 'bookmark1' which is pushed to 'refs/for/bookmark1' by push refspecs
+    ");
+}
+
+#[test]
+fn test_git_push_for_review() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-m", "review me"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--for", "main", "--dry-run"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Push commit 598bf792a5ec to refs/for/main
+    Dry-run requested, not pushing.
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "--for=main",
+            "--reviewer=alice@example.com",
+            "--reviewer=bob@example.com",
+            "--topic=feature",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Push commit 598bf792a5ec to refs/for/main%r=alice@example.com,r=bob@example.com,topic=feature
+    ");
+    let origin_git_repo = git2::Repository::open(
+        test_env
+            .env_root()
+            .join("origin")
+            .join(".jj")
+            .join("repo")
+            .join("store")
+            .join("git"),
+    )
+    .unwrap();
+    let pushed_commit = origin_git_repo
+        .find_reference("refs/for/main%r=alice@example.com,r=bob@example.com,topic=feature")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(pushed_commit.message(), Some("review me\n"));
+    // No bookmarks are created or moved
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    bookmark1: xtvrqkyv d13ecdbd (empty) description 1
+      @origin: xtvrqkyv d13ecdbd (empty) description 1
+    bookmark2: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    ");
+
+    // Commits without description are rejected
+    test_env.jj_cmd_ok(&workspace_root, &["new"]);
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--for", "main"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Won't push commit a12d555d0592 since it has no description
+    Hint: Rejected commit: kmkuslsw a12d555d (empty) (no description set)
+    ");
+
+    // Only a single head can be pushed
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--for", "main", "-rbookmark1|bookmark2"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Revisions to push for review must have a single head
+    Hint: Push each stack of changes separately.
+    ");
+
+    // Reviewers require --for
+    let stderr = test_env.jj_cmd_cli_error(&workspace_root, &["git", "push", "--reviewer=alice"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the following required arguments were not provided:
+      --for <BRANCH>
+
+    Usage: jj git push --for <BRANCH> --reviewer <REVIEWER>
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn test_git_push_tags() {
+    let (test_env, workspace_root) = set_up();
+    let git_repo_path = |name: &str| {
+        test_env
+            .env_root()
+            .join(name)
+            .join(".jj")
+            .join("repo")
+            .join("store")
+            .join("git")
+    };
+    let local_git_repo = git2::Repository::open(git_repo_path("local")).unwrap();
+    let origin_git_repo = git2::Repository::open(git_repo_path("origin")).unwrap();
+    let commit1 = local_git_repo
+        .find_reference("refs/remotes/origin/bookmark1")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    let commit2 = local_git_repo
+        .find_reference("refs/remotes/origin/bookmark2")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    local_git_repo
+        .tag_lightweight("v1.0", commit1.as_object(), false)
+        .unwrap();
+    local_git_repo
+        .tag_lightweight("v1.1", commit2.as_object(), false)
+        .unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["git", "import"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--tag=glob:v1.*", "--dry-run"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Push tag v1.0 at d13ecdbda2a2
+      Push tag v1.1 at 8476341eb395
+    Dry-run requested, not pushing.
+    ");
+
+    // Tags can be pushed along with bookmarks
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-m", "new commit"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "bookmark3"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "--allow-new",
+            "--bookmark=bookmark3",
+            "--tag=v1.0",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Add bookmark bookmark3 to c38f2be32dc4
+      Push tag v1.0 at d13ecdbda2a2
+    ");
+    assert_eq!(
+        origin_git_repo
+            .find_reference("refs/tags/v1.0")
+            .unwrap()
+            .target(),
+        Some(commit1.id())
+    );
+
+    // Pushing a tag which is already on the remote is fine
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--tag=v1.0"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Push tag v1.0 at d13ecdbda2a2
+    ");
+
+    // Existing remote tags are never moved
+    let origin_commit1 = origin_git_repo.find_commit(commit1.id()).unwrap();
+    origin_git_repo
+        .tag_lightweight("v1.1", origin_commit1.as_object(), false)
+        .unwrap();
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--tag=v1.1"]);
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Push tag v1.1 at 8476341eb395
+    Error: Refusing to move tags that already exist on the remote: v1.1
+    Hint: Published tags shouldn't be moved. Create a new tag instead.
+    ");
+
+    // Unknown tags are rejected
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--tag=v2.0"]);
+    insta::assert_snapshot!(stderr, @"Error: No such tag: v2.0");
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--tag=v2.0", "--tag=glob:v3*"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: No matching tags for patterns: v2.0, v3*");
+
+    // Signing requires a signing backend
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--tag=v1.0", "--sign-tags"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Cannot sign tags because no signing backend is configured
+    Hint: Set `signing.backend` to sign tags.
+    ");
+}
+
+#[test]
+fn test_git_push_protected() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config(r#"bookmarks.protected = ["bookmark1"]"#);
+
+    // Moving the bookmark sideways can't be pushed
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "bookmark",
+            "set",
+            "bookmark1",
+            "-rbookmark2",
+            "--allow-backwards",
+            "--allow-protected",
+        ],
+    );
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--all"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Refusing to force-push protected bookmark: bookmark1
+    Hint: Use --allow-protected to allow it.
+    ");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--all", "--allow-protected", "--dry-run"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move sideways bookmark bookmark1 from d13ecdbda2a2 to 8476341eb395
+    Dry-run requested, not pushing.
+    ");
+
+    // Deleting the bookmark can't be pushed
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["bookmark", "delete", "bookmark1", "--allow-protected"],
+    );
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--deleted"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Refusing to delete protected bookmark from the remote: bookmark1
+    Hint: Use --allow-protected to allow it.
+    ");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--deleted", "--allow-protected"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Delete bookmark bookmark1 from d13ecdbda2a2
+    ");
+}
+
 #[test]
 fn test_git_push_deleted() {
     let (test_env, workspace_root) = set_up();
@@ -60,6 +60,55 @@ fn test_version() {
     );
 }
 
+#[test]
+fn test_version_verbose() {
+    let test_env = TestEnvironment::default();
+
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["version", "--verbose"]);
+    let version_line = stdout.lines().next().unwrap();
+    assert!(version_line.starts_with("jj "), "{stdout}");
+    assert!(stdout.contains("\nlibgit2: "), "{stdout}");
+    assert!(stdout.contains("\ngitoxide: "), "{stdout}");
+    let platform = format!(
+        "\nplatform: {}-{}\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    assert!(stdout.contains(&platform), "{stdout}");
+    assert!(
+        stdout.ends_with("\nconfig files:\n  user: $TEST_ENV/config/config0001.toml\n"),
+        "{stdout}"
+    );
+
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["version", "--format=json"]);
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        format!("jj {}", report["version"].as_str().unwrap()),
+        version_line
+    );
+    let features = report["features"].as_array().unwrap();
+    let has_feature = |name: &str| features.contains(&serde_json::json!(name));
+    assert_eq!(
+        has_feature("native-fsmonitor"),
+        cfg!(feature = "native-fsmonitor")
+    );
+    assert_eq!(has_feature("s3"), cfg!(feature = "s3"));
+    assert_eq!(
+        has_feature("syntax-highlight"),
+        cfg!(feature = "syntax-highlight")
+    );
+    assert!(!has_feature("test-fakes"));
+    assert!(report["libgit2"].is_string());
+    assert_eq!(report["os"], std::env::consts::OS);
+    assert_eq!(report["arch"], std::env::consts::ARCH);
+    assert_eq!(
+        report["config_files"],
+        serde_json::json!([
+            {"source": "user", "path": "$TEST_ENV/config/config0001.toml"},
+        ])
+    );
+}
+
 #[test]
 fn test_no_subcommand() {
     let test_env = TestEnvironment::default();
@@ -774,7 +823,10 @@ fn test_help() {
     insta::assert_snapshot!(stdout, @r"
     Touch up the content changes in a revision with a diff editor
 
-    Usage: jj diffedit [OPTIONS]
+    Usage: jj diffedit [OPTIONS] [PATHS]...
+
+    Arguments:
+      [PATHS]...  Edit only the changes in these paths (instead of all paths)
 
     Options:
       -r, --revision <REVISION>  The revision to touch up
@@ -789,7 +841,10 @@ fn test_help() {
           --ignore-working-copy          Don't snapshot the working copy, and don't update it
           --ignore-immutable             Allow rewriting immutable commits
           --at-operation <AT_OPERATION>  Operation to load the repo at [aliases: at-op]
+          --op-metadata <KEY=VALUE>      Attach metadata to the operation created by the command
           --debug                        Enable debug logging
+          --profile                      Report the time spent in each phase of the command
+          --profile-trace <FILE>         Also write the profile in Chrome trace format to the given file
           --color <WHEN>                 When to colorize output (always, never, debug, auto)
           --quiet                        Silence non-primary command output
           --no-pager                     Disable the pager
@@ -816,3 +871,41 @@ fn test_debug_logging_enabled() {
     // Luckily, insta will print this in colour when reviewing.
     insta::assert_snapshot!(log_line, @"[32m INFO[0m [2mjj_cli::cli_util[0m[2m:[0m debug logging enabled");
 }
+
+#[test]
+fn test_profile() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["--profile", "--profile-trace=trace.json", "log"],
+    );
+    insta::with_settings!({filters => vec![
+        (r" +\d+\.\d{3}ms", " <duration>"),
+        (r"\(\d+ spans?\)", "(<count>)"),
+    ]}, {
+        insta::assert_snapshot!(stderr, @r"
+        Time spent per phase:
+          snapshot <duration> (<count>)
+          revset evaluation <duration> (<count>)
+          tree diffing <duration> (<count>)
+          backend reads <duration> (<count>)
+          backend writes <duration> (<count>)
+          transaction commit <duration> (<count>)
+          total <duration>
+        ");
+    });
+    let trace: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(repo_path.join("trace.json")).unwrap()).unwrap();
+    let events = trace["traceEvents"].as_array().unwrap();
+    assert!(events
+        .iter()
+        .any(|event| event["name"] == "snapshot_working_copy" && event["ph"] == "X"));
+
+    // Nothing is reported without --profile
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["log"]);
+    insta::assert_snapshot!(stderr, @"");
+}
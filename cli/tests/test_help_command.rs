@@ -136,7 +136,7 @@ fn test_help_keyword() {
     insta::assert_snapshot!(help_cmd_stderr, @r#"
     error: unrecognized subcommand 'revsets'
 
-      tip: some similar subcommands exist: 'resolve', 'prev', 'restore', 'rebase', 'revert'
+      tip: some similar subcommands exist: 'serve', 'resolve', 'prev', 'restore', 'rebase', 'revert', 'revset'
 
     Usage: jj [OPTIONS] <COMMAND>
 
@@ -169,6 +169,68 @@ fn test_new_wc_commit_when_wc_immutable_multi_workspace() {
     "###);
 }
 
+#[test]
+fn test_immutable_revset_cache() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let cache_dir = repo_path.join(".jj/repo/revset_cache");
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=a"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "main""#);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=b"]);
+    let cache_files = || {
+        let mut paths = vec![];
+        for op_dir in std::fs::read_dir(&cache_dir).unwrap() {
+            for entry in std::fs::read_dir(op_dir.unwrap().path()).unwrap() {
+                paths.push(entry.unwrap().path());
+            }
+        }
+        paths
+    };
+
+    // The immutable set is evaluated once per operation
+    let template = r#"separate(" ", description.first_line(), if(immutable, "immutable"))"#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    @  b
+    ◆  a immutable
+    ~  (elided revisions)
+    ◆  immutable
+    ");
+    let [cache_file] = cache_files().try_into().unwrap();
+
+    // The cached set is used by the following commands. Pretend that all
+    // commits are immutable.
+    let mut data = std::fs::read(&cache_file).unwrap();
+    data[4..].fill(0xff);
+    std::fs::write(&cache_file, data).unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    @  b immutable
+    ◆  a immutable
+    ~  (elided revisions)
+    ◆  immutable
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "-m=c"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Commit 329d8f9d019c is immutable
+    Hint: Could not modify commit: zsuskuln 329d8f9d (empty) b
+    Hint: Pass `--ignore-immutable` or configure the set of immutable commits via `revset-aliases.immutable_heads()`.
+    ");
+
+    // Reindexing invalidates the cache
+    test_env.jj_cmd_ok(&repo_path, &["debug", "reindex"]);
+    assert!(!cache_dir.exists());
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=c"]);
+
+    // Only the sets evaluated at the latest operation are kept
+    assert_eq!(cache_files().len(), 1);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=d"]);
+    let [new_cache_file] = cache_files().try_into().unwrap();
+    assert_ne!(new_cache_file, cache_file);
+}
+
 #[test]
 fn test_rewrite_immutable_commands() {
     let test_env = TestEnvironment::default();
@@ -348,3 +410,83 @@ fn test_rewrite_immutable_commands() {
     Hint: Pass `--ignore-immutable` or configure the set of immutable commits via `revset-aliases.immutable_heads()`.
     "#);
 }
+
+#[test]
+fn test_immutable_heads_configured_remotes_and_bookmarks() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = {
+        let mut git_repo_path = repo_path.clone();
+        git_repo_path.extend([".jj", "repo", "store", "git"]);
+        git2::Repository::open(git_repo_path).unwrap()
+    };
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=a"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "release/1"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=b"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "feature"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=c"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "pushed"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=d"]);
+    // Make a tracked remote bookmark pushed@origin
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    let oid = git_repo.refname_to_id("refs/heads/pushed").unwrap();
+    git_repo
+        .reference("refs/remotes/origin/pushed", oid, false, "")
+        .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "track", "pushed@origin"]);
+    let template = r#"description.first_line() ++ " " ++ bookmarks"#;
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["log", "-T", template]), @r"
+    @  d
+    ○  c pushed
+    │ ○  b feature
+    │ ○  a release/1
+    ├─╯
+    ◆
+    ");
+
+    test_env.add_config(
+        r#"
+        [immutable-heads]
+        remotes = ["origin"]
+        bookmarks = ["glob:release/*"]
+        "#,
+    );
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["log", "-T", template]), @r"
+    @  d
+    ◆  c pushed
+    │ ○  b feature
+    │ ◆  a release/1
+    ├─╯
+    ◆
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "release/1", "-m=a2"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Commit d8d5f980a897 is immutable
+    Hint: Could not modify commit: qpvuntsm d8d5f980 release/1 | (empty) a
+    Hint: Pass `--ignore-immutable` or configure the set of immutable commits via `revset-aliases.immutable_heads()`.
+    ");
+
+    // What a pattern contributes can be changed by redefining the aliases
+    test_env.add_config(r#"revset-aliases."immutable_remote_heads(remote)" = "none()""#);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["log", "-T", template]), @r"
+    @  d
+    ○  c pushed
+    │ ○  b feature
+    │ ◆  a release/1
+    ├─╯
+    ◆
+    ");
+
+    // Invalid patterns are reported
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", r#"--config=immutable-heads.bookmarks=["bad:main"]"#],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Config error: Error parsing 'bad:main' for immutable-heads.bookmarks
+    Caused by: Invalid string pattern kind "bad:"
+    For help, see https://martinvonz.github.io/jj/latest/config/.
+    "#);
+}
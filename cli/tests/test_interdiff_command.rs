@@ -163,3 +163,110 @@ fn test_interdiff_conflicting() {
     +def
     "###);
 }
+
+#[test]
+fn test_interdiff_positional() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "left"]);
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()"]);
+    std::fs::write(repo_path.join("file1"), "foo\nbar\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "foo\nbar\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "right"]);
+
+    // implicit TO
+    let stdout = test_env.jj_cmd_success(&repo_path, &["interdiff", "left", "-s"]);
+    insta::assert_snapshot!(stdout, @r"
+    M file1
+    M file2
+    ");
+
+    // explicit TO and paths
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["interdiff", "left", "right", "file1"]);
+    insta::assert_snapshot!(stdout, @r"
+    Modified regular file file1:
+       1    1: foo
+            2: bar
+    ");
+
+    // paths after --
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["interdiff", "left", "right", "-s", "--", "file2"],
+    );
+    insta::assert_snapshot!(stdout, @"M file2");
+
+    // --to makes all positional arguments paths, comparing from @
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["interdiff", "--to", "right", "-s", "file2"]);
+    insta::assert_snapshot!(stdout, @"A file2");
+
+    // --from makes all positional arguments paths
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "interdiff",
+            "--from",
+            "left",
+            "--to",
+            "right",
+            "-s",
+            "file1",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"M file1");
+
+    // only --to compares from @
+    let stdout = test_env.jj_cmd_success(&repo_path, &["interdiff", "--to", "right", "-s"]);
+    insta::assert_snapshot!(stdout, @r"
+    A file1
+    A file2
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["interdiff"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: No revision to compare from
+    Hint: Use `jj interdiff FROM [TO]` or `jj interdiff --from FROM`
+    ");
+}
+
+#[test]
+fn test_interdiff_multiple_pairs() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "v1 first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "v1 second"]);
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "v1"]);
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "v2 first"]);
+    std::fs::write(repo_path.join("file1"), "foo\nbar\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "v2 second"]);
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "v2"]);
+
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["interdiff", "::v1 ~ root()", "::v2 ~ root()"]);
+    insta::assert_snapshot!(stdout, @r"
+    From: qpvuntsm b520ec52 v1 first
+    To:   mzvwutvl d6c7d512 v2 first
+    Modified regular file file1:
+       1    1: foo
+            2: bar
+
+    From: kkmpptxz 47b4ff3f v1 | v1 second
+    To:   royxmykx af5758c8 v2 | v2 second
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["interdiff", "::v1 ~ root()", "v2"]);
+    insta::assert_snapshot!(stderr, @"Error: Cannot pair up revisions: 2 to compare from, but 1 to compare to");
+}
@@ -41,6 +41,7 @@ fn test_log_with_no_template() {
 
     For more information, try '--help'.
     Hint: The following template aliases are defined:
+    - builtin_config_list_origin
     - builtin_log_comfortable
     - builtin_log_compact
     - builtin_log_compact_full_description
@@ -803,6 +804,14 @@ fn test_log_reversed() {
     @  second
     "###);
 
+    // `--reverse` is accepted like in `git log`
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--reverse"]);
+    insta::assert_snapshot!(stdout, @r"
+    ◆
+    ○  first
+    @  second
+    ");
+
     let stdout = test_env.jj_cmd_success(
         &repo_path,
         &["log", "-T", "description", "--reversed", "--no-graph"],
@@ -1004,6 +1013,306 @@ fn test_log_limit() {
     │
     ~
     "###);
+
+    // Zero means no limit. The default limit only applies to terminal output.
+    test_env.add_config("ui.log-default-limit = 1");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--limit=0", "--no-graph"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    d
+    c
+    b
+    a
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--no-graph"]);
+    insta::assert_snapshot!(stdout, @r"
+    d
+    c
+    b
+    a
+    ");
+}
+
+#[test]
+fn test_log_columns() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m", "first\tline\\\nsecond line"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "foo", "bar"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "child"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r=::@ ~ root()",
+            "--columns=change_id,commit_id,author,description,bookmarks",
+        ],
+    );
+    insta::assert_debug_snapshot!(stdout.lines().collect::<Vec<_>>(), @r#"
+    [
+        "zsuskulnrvyrovkzqrwmxqlsskqntxvp\ta8979ca56a040a2012f63310cec0e961d4588acc\tTest User <test.user@example.com>\tchild\t",
+        "qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu\t769ef1210b4673870bef8741f472686c6c03db66\tTest User <test.user@example.com>\tfirst\\tline\\\\\tbar,foo",
+    ]
+    "#);
+
+    // Columns can be repeated and reordered, and don't render the graph
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--reversed",
+            "--columns=bookmarks",
+            "--columns=description",
+        ],
+    );
+    insta::assert_debug_snapshot!(stdout.lines().collect::<Vec<_>>(), @r#"
+    [
+        "\t",
+        "bar,foo\tfirst\\tline\\\\",
+        "\tchild",
+    ]
+    "#);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["log", "--columns=change_id", "-T=x"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--columns <COLUMNS>' cannot be used with '--template <TEMPLATE>'
+
+    Usage: jj log --columns <COLUMNS> [PATHS]...
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn test_log_delimiter() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first\nsecond"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "child"]);
+
+    // Multi-line descriptions can be split on NUL
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r=::@ ~ root()",
+            "--no-graph",
+            "-Tdescription",
+            "--delimiter=NUL",
+        ],
+    );
+    insta::assert_debug_snapshot!(stdout.split_terminator('\0').collect::<Vec<_>>(), @r#"
+    [
+        "child\n",
+        "first\nsecond\n",
+    ]
+    "#);
+
+    // The delimiter replaces the newline of each row of columns
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r=::@ ~ root()",
+            "--columns=description",
+            "--delimiter=nul",
+        ],
+    );
+    insta::assert_debug_snapshot!(stdout, @r#""child\0first\0""#);
+
+    // The newline delimiter isn't doubled after entries ending with a newline
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r=::@ ~ root()",
+            "--no-graph",
+            "-Tdescription",
+            "--delimiter=newline",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    child
+    first
+    second
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r=::@ ~ root()",
+            "--no-graph",
+            "-Tdescription.first_line()",
+            "--delimiter=newline",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    child
+    first
+    ");
+
+    // The graph can't be delimited
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["log", "--delimiter=newline"]);
+    insta::assert_snapshot!(stderr, @"Error: --delimiter requires --no-graph or --columns");
+}
+
+#[test]
+fn test_log_simplify_by_decoration() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "c"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "c"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "d", "description(a)"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "e"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "f"]);
+
+    let template = r#"separate(" ", description.first_line(), bookmarks) ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r::", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    @  f
+    ○  e
+    ○  d
+    │ ○  c c
+    │ ○  b
+    ├─╯
+    ○  a a
+    ◆
+    ");
+
+    // Only decorated revisions are shown, along with how they are connected
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r::", "-T", template, "--simplify-by-decoration"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    @  f
+    ~  (elided revisions)
+    │ ○  c c
+    │ ~  (elided revisions)
+    ├─╯
+    ○  a a
+    │
+    ~
+    ");
+
+    // Can be combined with other filters
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r=description(a)::",
+            "-T",
+            template,
+            "--simplify-by-decoration",
+            "--no-graph",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    f
+    c c
+    a a
+    ");
+}
+
+#[test]
+fn test_log_collapse_linear() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "a"]);
+    for description in ["b", "c", "d"] {
+        test_env.jj_cmd_ok(&repo_path, &["new", "-m", description]);
+    }
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "e", "description(a)"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "f"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "g"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "g"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "h"]);
+
+    let template = r#"separate(" ", description.first_line(), bookmarks) ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r::", "-T", template, "--collapse-linear"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    @  h
+    ○  g g
+    ~  (2 revisions collapsed)
+    │ ○  d
+    │ ~  (2 revisions collapsed)
+    ├─╯
+    ○  a a
+    ◆
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r::",
+            "-T",
+            template,
+            "--collapse-linear",
+            "--reversed",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    ◆
+    ○    a a
+    ├─╮
+    ~ │  (2 revisions collapsed)
+    │ ~  (2 revisions collapsed)
+    │ ○  d
+    ○  g g
+    @  h
+    ");
+
+    // Runs of a single revision are kept
+    test_env.add_config("ui.log-collapse-linear = true");
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "-r::description(c)", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    ○  c
+    ○  b
+    ○  a a
+    ◆
+    ");
+
+    // Node symbols can depend on revset membership
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r::description(d)",
+            "-T",
+            template,
+            "--config",
+            r#"templates.log_node='coalesce(if(!self, "~"), if(self.contained_in("bookmarks()"), "◇"), "○")'"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    ○  d
+    ~  (2 revisions collapsed)
+    ◇  a a
+    ○
+    ");
 }
 
 #[test]
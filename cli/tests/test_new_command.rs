@@ -674,6 +674,112 @@ fn test_new_error_revision_does_not_exist() {
     "###);
 }
 
+#[test]
+fn test_new_from_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "A"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "B"]);
+
+    // Extract changes to file1 into a new commit before the working copy
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "-m",
+            "C",
+            "-B",
+            "@",
+            "--from",
+            "@",
+            "--path",
+            "file1",
+            "--no-edit",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Created new commit zsuskuln ec3c8a05 C
+    Rebased 1 descendant commits
+    Working copy now at: rlvkpnrz 32e8b3bd B
+    Parent commit      : zsuskuln ec3c8a05 C
+    ");
+    let template = r#"description ++ "\n" ++ diff.summary()"#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    @  B
+    │
+    │  M file2
+    ○  C
+    │
+    │  M file1
+    ○  A
+    │
+    │  A file1
+    │  A file2
+    ◆
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "-r", "C", "file1"]);
+    insta::assert_snapshot!(stdout, @"b");
+
+    // Move changes out of an ancestor into a new commit after it
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "-m",
+            "D",
+            "-A",
+            "description(A)",
+            "--from",
+            "description(A)",
+            "--path",
+            "file2",
+        ],
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    ○  B
+    │
+    │  M file2
+    ○  C
+    │
+    │  M file1
+    @  D
+    │
+    │  A file2
+    ○  A
+    │
+    │  A file1
+    ◆
+    ");
+
+    // Nothing to move
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["new", "--from", "@", "--path", "nonexistent"]);
+    insta::assert_snapshot!(stderr, @r"
+    Warning: No changes to the given paths in the source revision
+    Working copy now at: yostqsxw 263a9de9 (empty) (no description set)
+    Parent commit      : yqosqzyt 4e2b8ec9 D
+    ");
+
+    // --path requires --from
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["new", "--path", "file1"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the following required arguments were not provided:
+      --from <REVSET>
+
+    Usage: jj new --path <FILESETS> --from <REVSET> [REVISIONS]...
+
+    For more information, try '--help'.
+    ");
+}
+
 fn setup_before_insertion(test_env: &TestEnvironment, repo_path: &Path) {
     test_env.jj_cmd_ok(repo_path, &["bookmark", "create", "A"]);
     test_env.jj_cmd_ok(repo_path, &["commit", "-m", "A"]);
@@ -146,6 +146,7 @@ fn test_op_log_with_no_template() {
 
     For more information, try '--help'.
     Hint: The following template aliases are defined:
+    - builtin_config_list_origin
     - builtin_log_comfortable
     - builtin_log_compact
     - builtin_log_compact_full_description
@@ -176,6 +177,121 @@ fn test_op_log_limit() {
     "###);
 }
 
+#[test]
+fn test_op_log_operations() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "-m", "second", "--config=operation.username=alice"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "--op-metadata",
+            "ci-job=1",
+            "--config=operation.username=alice",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
+    let render = |opset: &str, extra_args: &[&str]| {
+        let mut args = vec!["op", "log", "-Tdescription ++ \"\\n\"", "-r", opset];
+        args.extend(extra_args);
+        test_env.jj_cmd_success(&repo_path, &args)
+    };
+
+    insta::assert_snapshot!(render("@", &[]), @r"
+    @  create bookmark main pointing to commit c97da310c66008034013412d321397242e1e43ef
+    │
+    ~
+    ");
+    insta::assert_snapshot!(render("@--::@-", &[]), @r"
+    ○  new empty commit
+    ○  new empty commit
+    │
+    ~
+    ");
+    insta::assert_snapshot!(render("user(alice)", &[]), @r"
+    ○  new empty commit
+    ○  new empty commit
+    │
+    ~
+    ");
+    insta::assert_snapshot!(render("~user(alice) & ~root()", &[]), @r"
+    @  create bookmark main pointing to commit c97da310c66008034013412d321397242e1e43ef
+    ○  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ○  add workspace 'default'
+    │
+    ~
+    ");
+    insta::assert_snapshot!(render("predecessors(@-)", &["--no-graph"]), @r"
+    new empty commit
+    describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    add workspace 'default'
+    ");
+    insta::assert_snapshot!(render("tag(ci-job)", &[]), @r"
+    ○  new empty commit
+    │
+    ~
+    ");
+    insta::assert_snapshot!(render("tag(args, glob:'*describe*')", &[]), @r"
+    ○  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    │
+    ~
+    ");
+    insta::assert_snapshot!(render(r#"description(exact:"add workspace 'default'")"#, &[]), @r"
+    ○  add workspace 'default'
+    │
+    ~
+    ");
+    insta::assert_snapshot!(render("date(before:'2000-01-01')", &[]), @"○");
+    insta::assert_snapshot!(render("root() | heads()", &["--limit=1"]), @"@  create bookmark main pointing to commit c97da310c66008034013412d321397242e1e43ef");
+
+    // Multiple expressions are unioned
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "-Tdescription ++ \"\\n\"",
+            "--no-graph",
+            "-r@",
+            "-r@-",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    create bookmark main pointing to commit c97da310c66008034013412d321397242e1e43ef
+    new empty commit
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "-r", "usr(alice)"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Failed to parse operation set: Function "usr" doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | usr(alice)
+      | ^-^
+      |
+      = Function "usr" doesn't exist
+    Hint: Did you mean "user"?
+    "#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "-r", "foo@"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to parse operation set: Operation set cannot refer to commits
+    Caused by:  --> 1:1
+      |
+    1 | foo@
+      | ^--^
+      |
+      = Operation set cannot refer to commits
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "-r", "zzz"]);
+    insta::assert_snapshot!(stderr, @r#"Error: Operation ID "zzz" is not a valid hexadecimal prefix"#);
+}
+
 #[test]
 fn test_op_log_no_graph() {
     let test_env = TestEnvironment::default();
@@ -265,6 +381,110 @@ fn test_op_log_template() {
     "#);
 }
 
+#[test]
+fn test_op_log_template_counts_and_tags() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    test_env.jj_cmd_ok(&repo_path, &["abandon", "@-"]);
+
+    let template = r#"
+    separate(" ",
+      id.short(5),
+      username,
+      hostname,
+      "refs:" ++ refs_changed,
+      "commits:" ++ commits_created,
+      self.tag("args"),
+    ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    @  fae3d test-username host.example.com refs:1 commits:1 jj abandon @-
+    ○  9a148 test-username host.example.com refs:0 commits:1 jj new -m second
+    ○  ba5b6 test-username host.example.com refs:1 commits:1 jj describe -m first
+    ○  43a55 test-username host.example.com refs:1 commits:0 jj bookmark create main
+    ○  289cb test-username host.example.com refs:0 commits:1 jj new
+    ○  eac75 test-username host.example.com refs:0 commits:1
+    ○  00000 refs:0 commits:0
+    ");
+
+    // Operations can be filtered by the counts
+    let template = r#"if(refs_changed > 0, description ++ "\n")"#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    abandon commit a662e4a8a04bb3bef7a700b357336978658cd7ba
+    describe commit 65b6b74e08973b88d38404430f119c8c79465250
+    create bookmark main pointing to commit 65b6b74e08973b88d38404430f119c8c79465250
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "--no-graph",
+            "-T",
+            r#"self.tag("missing") ++ "|""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"|||||||");
+}
+
+#[test]
+fn test_op_metadata_arg() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "--op-metadata",
+            "ci-job=1234",
+            "--op-metadata=script=release.sh",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+
+    let template = r#"
+    separate(" ",
+      id.short(5),
+      "ci-job:" ++ self.tag("ci-job"),
+      "script:" ++ self.tag("script"),
+    ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    9063b ci-job: script:
+    64cf6 ci-job:1234 script:release.sh
+    eac75 ci-job: script:
+    00000 ci-job: script:
+    ");
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["new", "--op-metadata", "foo"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value 'foo' for '--op-metadata <KEY=VALUE>': expected KEY=VALUE
+
+    For more information, try '--help'.
+    ");
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["new", "--op-metadata", "=foo"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value '=foo' for '--op-metadata <KEY=VALUE>': metadata key must not be empty
+
+    For more information, try '--help'.
+    ");
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["new", "--op-metadata", "args=foo"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value 'args=foo' for '--op-metadata <KEY=VALUE>': metadata key 'args' is reserved
+
+    For more information, try '--help'.
+    ");
+}
+
 #[test]
 fn test_op_log_builtin_templates() {
     let test_env = TestEnvironment::default();
@@ -675,6 +895,59 @@ fn test_op_abandon_multiple_heads() {
     "###);
 }
 
+#[test]
+fn test_op_log_divergent_heads() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "commit 1"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "commit 2"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "--at-op=@-", "-m", "commit 3"]);
+
+    // Divergent operations are shown as separate heads without being merged
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["op", "log", "--at-op=@", "--ignore-working-copy"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    ○  3aef84bb8003 test-username@host.example.com 2001-02-03 04:05:10.000 +07:00 - 2001-02-03 04:05:10.000 +07:00
+    │  commit 81a4ef3dd421f3184289df1c58bd3a16ea1e3d8e
+    │  args: jj commit '--at-op=@-' -m 'commit 3'
+    │ ○  116edde65ded test-username@host.example.com 2001-02-03 04:05:09.000 +07:00 - 2001-02-03 04:05:09.000 +07:00
+    ├─╯  commit 81a4ef3dd421f3184289df1c58bd3a16ea1e3d8e
+    │    args: jj commit -m 'commit 2'
+    ○  bee8c02a64bf test-username@host.example.com 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00
+    │  commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    │  args: jj commit -m 'commit 1'
+    ○  eac759b9ab75 test-username@host.example.com 2001-02-03 04:05:07.000 +07:00 - 2001-02-03 04:05:07.000 +07:00
+    │  add workspace 'default'
+    ○  000000000000 root()
+    ");
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Operation heads are divergent: 116edde65ded, 3aef84bb8003
+    Hint: The next command that modifies the repo will reconcile them.
+    ");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["op", "log", "--ignore-working-copy"]);
+    insta::assert_snapshot!(stdout.lines().next().unwrap(), @"○  3aef84bb8003 test-username@host.example.com 2001-02-03 04:05:10.000 +07:00 - 2001-02-03 04:05:10.000 +07:00");
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Operation heads are divergent: 116edde65ded, 3aef84bb8003
+    Hint: The next command that modifies the repo will reconcile them.
+    ");
+
+    // The next mutating command reconciles them
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["op", "log", "-n2"]);
+    insta::assert_snapshot!(stdout, @r"
+    @    97ae2c6bca12 test-username@host.example.com 2001-02-03 04:05:13.000 +07:00 - 2001-02-03 04:05:13.000 +07:00
+    ├─╮  reconcile divergent operations
+    │ │  args: jj op log -n2
+    ○ │  116edde65ded test-username@host.example.com 2001-02-03 04:05:09.000 +07:00 - 2001-02-03 04:05:09.000 +07:00
+    │ │  commit 81a4ef3dd421f3184289df1c58bd3a16ea1e3d8e
+    │ │  args: jj commit -m 'commit 2'
+    ");
+    insta::assert_snapshot!(stderr, @"Concurrent modification detected, resolving automatically.");
+}
+
 #[test]
 fn test_op_recover_from_bad_gc() {
     let test_env = TestEnvironment::default();
@@ -2251,3 +2524,112 @@ fn get_log_output(test_env: &TestEnvironment, repo_path: &Path, op_id: &str) ->
         &["log", "-T", "commit_id", "--at-op", op_id, "-r", "all()"],
     )
 }
+
+#[test]
+fn test_op_push_fetch() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "a"]);
+    let a_path = test_env.env_root().join("a");
+    std::fs::write(a_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&a_path, &["commit", "-m", "first"]);
+    test_env.jj_cmd_ok(&a_path, &["bookmark", "create", "main", "-r@-"]);
+    // Copy the repo to emulate a clone on another machine
+    let b_path = test_env.env_root().join("b");
+    copy_dir_all(&a_path, &b_path);
+
+    std::fs::write(a_path.join("file"), "a\nb\n").unwrap();
+    test_env.jj_cmd_ok(&a_path, &["commit", "-m", "second"]);
+    test_env.jj_cmd_ok(&a_path, &["bookmark", "set", "main", "-r@-"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&a_path, &["op", "push", "../sync"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Pushed 7 operations and 7 commits.");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&a_path, &["op", "push", "../sync"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&b_path, &["op", "fetch", "../sync"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Fetched 3 operations and 3 commits.
+    Working copy now at: zsuskuln 729398bb (empty) (no description set)
+    Added 0 files, modified 1 files, removed 0 files
+    Updated working copy to fresh commit 729398bb3362
+    ");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&b_path, &["log"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  zsuskuln test.user@example.com 2001-02-03 08:05:10 729398bb
+    │  (empty) (no description set)
+    ○  rlvkpnrz test.user@example.com 2001-02-03 08:05:10 main d0226d74
+    │  second
+    ○  qpvuntsm test.user@example.com 2001-02-03 08:05:08 2d4fa21a
+    │  first
+    ◆  zzzzzzzz root() 00000000
+    ");
+    insta::assert_snapshot!(stderr, @"");
+    let stdout = test_env.jj_cmd_success(&b_path, &["op", "log", "-n3"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  a6efd182f61e test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
+    │  point bookmark main to commit d0226d745d5e3f067b3ec5a98f82688d2cbaec7d
+    │  args: jj bookmark set main -r@-
+    ○  de0087fce1ab test-username@host.example.com 2001-02-03 04:05:10.000 +07:00 - 2001-02-03 04:05:10.000 +07:00
+    │  commit 31711e8ba61abbfe5975a60da146d30ed1b53a86
+    │  args: jj commit -m second
+    ○  358bf29bdc87 test-username@host.example.com 2001-02-03 04:05:10.000 +07:00 - 2001-02-03 04:05:10.000 +07:00
+    │  snapshot working copy
+    │  args: jj commit -m second
+    ");
+
+    // Concurrent operations are merged
+    test_env.jj_cmd_ok(&a_path, &["describe", "-m", "third from a"]);
+    test_env.jj_cmd_ok(&a_path, &["op", "push", "../sync"]);
+    test_env.jj_cmd_ok(&b_path, &["bookmark", "create", "feature", "-r@-"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&b_path, &["op", "fetch", "../sync"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Fetched 1 operations and 1 commits.
+    Concurrent modification detected, resolving automatically.
+    ");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&b_path, &["log"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  zsuskuln test.user@example.com 2001-02-03 08:05:17 04ffc805
+    │  (empty) third from a
+    ○  rlvkpnrz test.user@example.com 2001-02-03 08:05:10 feature main d0226d74
+    │  second
+    ○  qpvuntsm test.user@example.com 2001-02-03 08:05:08 2d4fa21a
+    │  first
+    ◆  zzzzzzzz root() 00000000
+    ");
+    insta::assert_snapshot!(stderr, @"");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&b_path, &["op", "fetch", "../sync"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+
+    // The merged operations can be pushed back
+    test_env.jj_cmd_ok(&b_path, &["op", "push", "../sync"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&a_path, &["op", "fetch", "../sync"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Fetched 2 operations and 0 commits.");
+    let stdout = test_env.jj_cmd_success(&a_path, &["bookmark", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    feature: rlvkpnrz d0226d74 second
+    main: rlvkpnrz d0226d74 second
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&b_path, &["op", "fetch", "../nonexistent"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to access $TEST_ENV/b/../nonexistent
+    Caused by: No such file or directory (os error 2)
+    ");
+}
+
+fn copy_dir_all(src: &Path, dest: &Path) {
+    std::fs::create_dir(dest).unwrap();
+    for entry in std::fs::read_dir(src).unwrap() {
+        let entry = entry.unwrap();
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir_all(&entry.path(), &dest.join(entry.file_name()));
+        } else {
+            std::fs::copy(entry.path(), dest.join(entry.file_name())).unwrap();
+        }
+    }
+}
@@ -2816,3 +2816,127 @@ fn get_long_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
                     ++ surround(':  ', '', parents.map(|c| c.bookmarks()))";
     test_env.jj_cmd_success(repo_path, &["log", "-T", template])
 }
+
+#[test]
+fn test_rebase_reuse_resolutions() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[]);
+    std::fs::write(repo_path.join("file"), "base\n").unwrap();
+    create_commit(&test_env, &repo_path, "a", &["base"]);
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    create_commit(&test_env, &repo_path, "b", &["base"]);
+    std::fs::write(repo_path.join("file"), "b\n").unwrap();
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    let setup_opid = test_env.current_operation_id(&repo_path);
+
+    // Resolve the conflict once
+    test_env.jj_cmd_ok(&repo_path, &["rebase", "-s", "b", "-d", "a"]);
+    let editor_script = test_env.set_up_fake_editor();
+    std::fs::write(&editor_script, "write\nresolution\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["resolve", "-r", "b"]);
+
+    // Without --reuse-resolutions, the conflict is left in the commit
+    test_env.jj_cmd_ok(&repo_path, &["op", "restore", &setup_opid]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-s", "b", "-d", "a"]);
+    insta::assert_snapshot!(stderr, @r"
+    Rebased 2 commits onto destination
+    Working copy now at: vruxwmqv 4b6389f2 c | (conflict) c
+    Parent commit      : royxmykx 7f301e0e b | (conflict) b
+    Added 1 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file    2-sided conflict
+    New conflicts appeared in these commits:
+      vruxwmqv 4b6389f2 c | (conflict) c
+      royxmykx 7f301e0e b | (conflict) b
+    To resolve the conflicts, start by updating to the first one:
+      jj new royxmykx
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    ");
+
+    // The recorded resolution is applied to the rebased commit and propagates
+    // to its descendants
+    test_env.jj_cmd_ok(&repo_path, &["op", "restore", &setup_opid]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-s", "b", "-d", "a", "--reuse-resolutions"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Reused recorded resolutions of 1 conflicts in 1 commits
+    Rebased 2 commits onto destination
+    Working copy now at: vruxwmqv 5a7da4f0 c | c
+    Parent commit      : royxmykx a726f6cc b | b
+    Added 1 files, modified 1 files, removed 0 files
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "-r", "c", "file"]);
+    insta::assert_snapshot!(stdout, @"resolution");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r", "conflicts()"]);
+    insta::assert_snapshot!(stdout, @"");
+}
+
+#[test]
+fn test_rebase_on_conflict() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[]);
+    std::fs::write(repo_path.join("file"), "base\n").unwrap();
+    create_commit(&test_env, &repo_path, "a", &["base"]);
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    create_commit(&test_env, &repo_path, "b", &["base"]);
+    std::fs::write(repo_path.join("file"), "b\n").unwrap();
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  c: b
+    ○  b: base
+    │ ○  a: base
+    ├─╯
+    ○  base
+    ◆
+    ");
+
+    // Nothing is rebased if a commit would have conflicts
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["rebase", "-s", "b", "-d", "a", "--on-conflict=stop"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Rebasing would create conflicts in commit royxmykx 88e75783 b | (conflict) b
+    Hint: Use --on-conflict=keep to rebase anyway, or --on-conflict=skip to leave out the conflicted commits.
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  c: b
+    ○  b: base
+    │ ○  a: base
+    ├─╯
+    ○  base
+    ◆
+    ");
+
+    // Commits that would have conflicts are left out
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-s", "b", "-d", "a", "--on-conflict=skip"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Skipped 1 commits that would have conflicts:
+      royxmykx 99b4d819 b | (conflict) b
+    Rebased 2 commits onto destination
+    Working copy now at: vruxwmqv 8b2436f4 c | c
+    Parent commit      : zsuskuln a4719dc6 a b | a
+    Added 1 files, modified 1 files, removed 1 files
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  c: a b
+    ○  a b: base
+    ○  base
+    ◆
+    ");
+}
@@ -1166,3 +1166,79 @@ fn test_multiple_conflicts() {
     Error: No conflicts found at this revision
     "###);
 }
+
+#[test]
+fn test_rerere() {
+    let mut test_env = TestEnvironment::default();
+    test_env.add_config("rerere.enabled = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+
+    // Resolving a conflict in the working copy records the resolution
+    test_env.jj_cmd_ok(&repo_path, &["new", "a", "b"]);
+    std::fs::write(repo_path.join("file"), "resolution\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["util", "resolutions"]);
+    insta::assert_snapshot!(stdout, @"26276f280e54  file");
+
+    // The resolution is reused when the same conflict shows up again
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["new", "a", "b"]);
+    insta::assert_snapshot!(stderr, @r"
+    Reused recorded resolutions of 1 conflicts in 1 commits
+    Working copy now at: znkkpsqq 633663c0 (no description set)
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(repo_path.join("file")).unwrap(), @"resolution");
+
+    // Forgotten resolutions aren't reused
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["util", "resolutions", "--forget", "file"]);
+    insta::assert_snapshot!(stderr, @"Forgot 1 recorded resolutions");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["new", "a", "b"]);
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: kmkuslsw e69a25d0 (conflict) (empty) (no description set)
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file    2-sided conflict
+    ");
+
+    // Resolutions made with --no-rerere aren't recorded
+    let editor_script = test_env.set_up_fake_editor();
+    std::fs::write(&editor_script, "write\nresolution\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["resolve", "--no-rerere"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["util", "resolutions"]);
+    insta::assert_snapshot!(stdout, @"");
+}
+
+#[test]
+fn test_rerere_undo() {
+    let mut test_env = TestEnvironment::default();
+    test_env.add_config("rerere.enabled = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "a", "b"]);
+    std::fs::write(repo_path.join("file"), "resolution\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["util", "resolutions"]);
+    insta::assert_snapshot!(stdout, @"26276f280e54  file");
+
+    // Undoing the snapshot brings back the conflicted commit, which gets the
+    // recorded resolution again
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    assert!(
+        stderr.contains("Reused recorded resolutions of 1 conflicts in 1 commits"),
+        "{stderr}"
+    );
+    insta::assert_snapshot!(
+        std::fs::read_to_string(repo_path.join("file")).unwrap(), @"resolution");
+}
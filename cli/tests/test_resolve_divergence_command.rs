@@ -0,0 +1,182 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn create_divergent_change(test_env: &TestEnvironment, repo_path: &Path) {
+    test_env.jj_cmd_ok(repo_path, &["commit", "-m", "base"]);
+    std::fs::write(repo_path.join("file1"), "1\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "2\n").unwrap();
+    test_env.jj_cmd_ok(repo_path, &["describe", "-m", "change"]);
+    test_env.jj_cmd_ok(repo_path, &["bookmark", "create", "feature"]);
+    // Modify the change in two concurrent operations
+    test_env.jj_cmd_ok(repo_path, &["file", "chmod", "x", "file1"]);
+    test_env.jj_cmd_ok(repo_path, &["file", "chmod", "x", "file2", "--at-op=@-"]);
+    // Reconcile the operations
+    test_env.jj_cmd_ok(repo_path, &["status"]);
+}
+
+fn get_version_ids(test_env: &TestEnvironment, repo_path: &Path) -> Vec<String> {
+    let stdout = test_env.jj_cmd_success(
+        repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r=description(change)",
+            r#"-T=commit_id ++ "\n""#,
+        ],
+    );
+    stdout.lines().map(|line| line.to_owned()).collect()
+}
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    let template = r#"separate(" ", change_id.short(), commit_id.short(), description.first_line(), bookmarks, if(divergent, "divergent"))"#;
+    test_env.jj_cmd_success(repo_path, &["log", "-T", template])
+}
+
+#[test]
+fn test_resolve_divergence_list() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_divergent_change(&test_env, &repo_path);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  rlvkpnrzqnoo 1753df356b85 change feature?? divergent
+    │ ○  rlvkpnrzqnoo 933620fbdbb7 change feature?? divergent
+    ├─╯
+    ○  qpvuntsmwlqt 494c7b830985 base
+    ◆  zzzzzzzzzzzz 000000000000
+    ");
+
+    // Without --keep or --merge, the versions are only listed
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve-divergence", "--summary"]);
+    insta::assert_snapshot!(stdout, @r"
+    The change has 2 versions:
+    1: rlvkpnrz?? 1753df35 feature?? | change
+    A file1
+    A file2
+    2: rlvkpnrz?? 933620fb feature?? | change
+    A file1
+    A file2
+    ");
+    insta::assert_snapshot!(stderr, @"Hint: Use `--keep` to keep one of the versions or `--merge` to merge them.");
+
+    // Not divergent
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["resolve-divergence", "-r", "@-"]);
+    insta::assert_snapshot!(stderr, @"Error: Change qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu is not divergent");
+}
+
+#[test]
+fn test_resolve_divergence_keep() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_divergent_change(&test_env, &repo_path);
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["resolve-divergence", "--keep", "description(base)"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Commit 494c7b8309856d8f7b04a92eb5c13a19ade0271f is not a version of the divergent change");
+
+    let version_ids = get_version_ids(&test_env, &repo_path);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["resolve-divergence", "--keep", &version_ids[1]],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Resolved divergence with commit rlvkpnrz 1753df35 feature | change
+    Abandoned 1 other versions of the change
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  rlvkpnrzqnoo 1753df356b85 change feature
+    ○  qpvuntsmwlqt 494c7b830985 base
+    ◆  zzzzzzzzzzzz 000000000000
+    ");
+}
+
+#[test]
+fn test_resolve_divergence_merge() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_divergent_change(&test_env, &repo_path);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve-divergence", "--merge"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Resolved divergence with commit rlvkpnrz 1c2cb26a feature | change
+    Abandoned 1 other versions of the change
+    Working copy now at: rlvkpnrz 1c2cb26a feature | change
+    Parent commit      : qpvuntsm 494c7b83 (empty) base
+    Added 0 files, modified 1 files, removed 0 files
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  rlvkpnrzqnoo 1c2cb26abdaf change feature
+    ○  qpvuntsmwlqt 494c7b830985 base
+    ◆  zzzzzzzzzzzz 000000000000
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/file1 b/file1
+    new file mode 100755
+    index 0000000000..d00491fd7e
+    --- /dev/null
+    +++ b/file1
+    @@ -0,0 +1,1 @@
+    +1
+    diff --git a/file2 b/file2
+    new file mode 100755
+    index 0000000000..0cfbf08886
+    --- /dev/null
+    +++ b/file2
+    @@ -0,0 +1,1 @@
+    +2
+    ");
+}
+
+#[test]
+fn test_resolve_divergence_prompt() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_divergent_change(&test_env, &repo_path);
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_stdin_ok(&repo_path, &["resolve-divergence", "--summary"], "2\n");
+    insta::assert_snapshot!(stdout, @r"
+    The change has 2 versions:
+    1: rlvkpnrz?? 1753df35 feature?? | change
+    A file1
+    A file2
+    2: rlvkpnrz?? 933620fb feature?? | change
+    A file1
+    A file2
+    ");
+    insta::assert_snapshot!(stderr, @r"
+    Enter the index of the version to keep, m to merge them, or q to quit: Resolved divergence with commit rlvkpnrz 933620fb feature | change
+    Abandoned 1 other versions of the change
+    Working copy now at: rlvkpnrz 933620fb feature | change
+    Parent commit      : qpvuntsm 494c7b83 (empty) base
+    Added 0 files, modified 2 files, removed 0 files
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  rlvkpnrzqnoo 933620fbdbb7 change feature
+    ○  qpvuntsmwlqt 494c7b830985 base
+    ◆  zzzzzzzzzzzz 000000000000
+    ");
+}
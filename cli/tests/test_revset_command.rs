@@ -0,0 +1,103 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_revset_evaluate() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "second\n\nbody"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["revset", "evaluate", "::@-"]);
+    insta::assert_snapshot!(stdout, @r"
+    1031a9681498cd0660780ef0ee2cb24499aa3b2d
+    fa15625b4a986997697639dfc2844138900c79f2
+    0000000000000000000000000000000000000000
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "revset",
+            "evaluate",
+            "::@-",
+            "--reversed",
+            "-n2",
+            "--id=change_id",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz
+    qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["revset", "evaluate", "@-", "--format=nul"]);
+    insta::assert_snapshot!(stdout.replace('\0', "<NUL>"), @"1031a9681498cd0660780ef0ee2cb24499aa3b2d<NUL>");
+
+    // The configured templates don't affect the output
+    test_env.add_config(r#"templates.log = "'ignored'""#);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "revset",
+            "evaluate",
+            "@-",
+            "--format=json",
+            "--field=description=description",
+            r#"--field=parents=parents.map(|c| c.commit_id().short()).join(" ")"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    [
+      {
+        "change_id": "rlvkpnrzqnoowoytxnquwvuryrwnrmlp",
+        "commit_id": "1031a9681498cd0660780ef0ee2cb24499aa3b2d",
+        "description": "second\n\nbody\n",
+        "parents": "fa15625b4a98"
+      }
+    ]
+    "#);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["revset", "evaluate", "none()", "--format=json"],
+    );
+    insta::assert_snapshot!(stdout, @"[]");
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["revset", "evaluate", "@", "--field=description=description"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: --field can only be used with --format json");
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "revset",
+            "evaluate",
+            "@",
+            "--format=json",
+            "--field=commit_id=description",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r#"Error: Field "commit_id" is specified more than once"#);
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["revset", "evaluate", "@", "--field=description"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value 'description' for '--field <NAME=TEMPLATE>': field must be of the form NAME=TEMPLATE
+
+    For more information, try '--help'.
+    ");
+}
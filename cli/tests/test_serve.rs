@@ -0,0 +1,189 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_serve_stdio() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::create_dir(repo_path.join("dir")).unwrap();
+    std::fs::write(repo_path.join("dir").join("file"), "contents\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main", "-r", "@-"]);
+
+    let requests = [
+        r#"{"id": 1, "method": "revset", "params": {"revset": "main | root()", "limit": 1}}"#,
+        r#"{"id": 2, "method": "files", "params": {"revision": "main"}}"#,
+        r#"{"id": 3, "method": "file", "params": {"revision": "main", "path": "dir/file"}}"#,
+        r#"{"id": 4, "method": "bookmarks"}"#,
+        r#"{"id": 5, "method": "operations", "params": {"limit": 1}}"#,
+        r#"{"id": 6, "method": "file", "params": {"path": "missing"}}"#,
+        r#"{"id": 7, "method": "revset", "params": {"revset": "nonexistent"}}"#,
+        r#"{"id": 8, "method": "unknown"}"#,
+        r#"not json"#,
+        r#"{"id": 9, "method": "shutdown"}"#,
+        r#"{"id": 10, "method": "bookmarks"}"#,
+    ];
+    let (stdout, stderr) =
+        test_env.jj_cmd_stdin_ok(&repo_path, &["serve", "--stdio"], &requests.join("\n"));
+    insta::assert_snapshot!(stdout, @r#"
+    {"id":1,"result":[{"author":{"email":"test.user@example.com","name":"Test User","timestamp":981147908000,"tz_offset":420},"change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","commit_id":"b7348de19baeb4fe418f992331bdc6439dff2e76","committer":{"email":"test.user@example.com","name":"Test User","timestamp":981147908000,"tz_offset":420},"description":"first\n","parent_ids":["0000000000000000000000000000000000000000"]}]}
+    {"id":2,"result":["dir/file"]}
+    {"id":3,"result":{"content":"contents\n"}}
+    {"id":4,"result":[{"name":"main","targets":["b7348de19baeb4fe418f992331bdc6439dff2e76"]}]}
+    {"id":5,"result":[{"description":"create bookmark main pointing to commit b7348de19baeb4fe418f992331bdc6439dff2e76","end_time":981147909000,"id":"bce9a22e76bd54c7bc17d93caea6d963863ec5c66640d020d3aa5416d09eb199ace81d5e7d89dd68a1f0626204a9ba3886d47b26e6f328d34c56838937e5675e","parent_ids":["73e39dd19bacd68b7c54d8561ed5ef7316fe0ec4974766fd5431c9185b8f6dc056ff609ac7f9678ce5be5451743e10978efb1e7da739e6dff95ef8d1688577ff"],"start_time":981147909000,"user":"test-username@host.example.com"}]}
+    {"error":{"message":"No such file: missing"},"id":6}
+    {"error":{"message":"Revision \"nonexistent\" doesn't exist"},"id":7}
+    {"error":{"message":"Unknown method: unknown"},"id":8}
+    {"error":{"message":"Invalid request: expected ident at line 1 column 2"},"id":null}
+    {"id":9,"result":null}
+    "#);
+    insta::assert_snapshot!(stderr, @"");
+}
+
+#[test]
+fn test_serve_requires_transport() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["serve"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the following required arguments were not provided:
+      <--socket <SOCKET>|--stdio>
+
+    Usage: jj serve <--socket <SOCKET>|--stdio>
+
+    For more information, try '--help'.
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_serve_socket() {
+    use std::io::BufRead as _;
+    use std::io::BufReader;
+    use std::io::Write as _;
+    use std::os::unix::net::UnixStream;
+
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "--colocate", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "first"]);
+
+    let socket_path = test_env.env_root().join("jj.sock");
+    let mut child = test_env
+        .jj_std_cmd(
+            &repo_path,
+            &["serve", "--socket", socket_path.to_str().unwrap()],
+        )
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let stream = loop {
+        if let Ok(stream) = UnixStream::connect(&socket_path) {
+            break stream;
+        }
+        assert!(child.try_wait().unwrap().is_none(), "server exited");
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    };
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request = |line: &str| {
+        writeln!(&stream, "{line}").unwrap();
+        let mut response = String::new();
+        reader.read_line(&mut response).unwrap();
+        response
+    };
+
+    // Another server can't listen on the same socket
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["serve", "--socket", socket_path.to_str().unwrap()],
+    );
+    insta::assert_snapshot!(stderr.replace(socket_path.to_str().unwrap(), "<socket>"), @"Error: Another server is already listening on <socket>");
+
+    // Branches created behind jj's back are imported
+    let git_repo = git2::Repository::open(&repo_path).unwrap();
+    let head_commit = git_repo.head().unwrap().peel_to_commit().unwrap();
+    git_repo.branch("from-git", &head_commit, false).unwrap();
+    let response = request(r#"{"id": 1, "method": "bookmarks"}"#);
+    insta::assert_snapshot!(response, @r#"{"id":1,"result":[{"name":"from-git","targets":["062bf9d27aa69073b93414b9ea7be32abd5bb0f2"]}]}"#);
+
+    // Clients can't stop the server without the token
+    let response = request(r#"{"id": 2, "method": "shutdown"}"#);
+    insta::assert_snapshot!(response, @r#"{"error":{"message":"Invalid or missing param: token"},"id":2}"#);
+    let token = std::fs::read_to_string(test_env.env_root().join("jj.sock.token")).unwrap();
+    let response = request(&format!(
+        r#"{{"id": 3, "method": "shutdown", "params": {{"token": "{token}"}}}}"#
+    ));
+    insta::assert_snapshot!(response, @r#"{"id":3,"result":null}"#);
+    assert!(child.wait().unwrap().success());
+    assert!(!socket_path.exists());
+    assert!(!test_env.env_root().join("jj.sock.token").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_serve_socket_existing_file() {
+    use std::os::unix::net::UnixListener;
+    use std::os::unix::net::UnixStream;
+
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Files other than sockets are left alone
+    let file_path = test_env.env_root().join("notes.txt");
+    std::fs::write(&file_path, "notes").unwrap();
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["serve", "--socket", file_path.to_str().unwrap()],
+    );
+    insta::assert_snapshot!(stderr, @"Error: $TEST_ENV/notes.txt already exists and is not a socket");
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "notes");
+
+    // A token file without a stale socket isn't ours to remove
+    let socket_path = test_env.env_root().join("jj.sock");
+    let token_path = test_env.env_root().join("jj.sock.token");
+    std::fs::write(&token_path, "data").unwrap();
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["serve", "--socket", socket_path.to_str().unwrap()],
+    );
+    insta::assert_snapshot!(stderr, @"Error: $TEST_ENV/jj.sock.token already exists");
+    assert_eq!(std::fs::read_to_string(&token_path).unwrap(), "data");
+
+    // The socket and token left behind by a dead server are replaced
+    drop(UnixListener::bind(&socket_path).unwrap());
+    let mut child = test_env
+        .jj_std_cmd(
+            &repo_path,
+            &["serve", "--socket", socket_path.to_str().unwrap()],
+        )
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    loop {
+        if UnixStream::connect(&socket_path).is_ok() {
+            break;
+        }
+        assert!(child.try_wait().unwrap().is_none(), "server exited");
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    assert_ne!(std::fs::read_to_string(&token_path).unwrap(), "data");
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
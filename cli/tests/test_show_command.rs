@@ -243,6 +243,56 @@ fn test_show_with_template() {
     "###);
 }
 
+#[test]
+fn test_show_multiple_revisions() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "fix: first"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "second"]);
+    std::fs::write(repo_path.join("file1"), "c\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "fix: third"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "show",
+            r#"description(glob:"fix*")"#,
+            "-T",
+            "description",
+            "--summary",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    fix: third
+    M file1
+
+    fix: first
+    A file1
+    ");
+
+    // Multiple revset arguments, oldest first, custom separator
+    test_env.add_config(r#"templates.show_separator = '"---\n"'"#);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["show", "@-", "@--", "--reversed", "-T", "description"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    fix: first
+    Added regular file file1:
+            1: a
+    ---
+    second
+    Modified regular file file1:
+       1    1: ab
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["show", "none()"]);
+    insta::assert_snapshot!(stderr, @"Error: Empty revision set");
+}
+
 #[test]
 fn test_show_with_no_template() {
     let test_env = TestEnvironment::default();
@@ -255,6 +305,7 @@ fn test_show_with_no_template() {
 
     For more information, try '--help'.
     Hint: The following template aliases are defined:
+    - builtin_config_list_origin
     - builtin_log_comfortable
     - builtin_log_compact
     - builtin_log_compact_full_description
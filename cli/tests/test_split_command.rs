@@ -152,6 +152,58 @@ fn test_split_by_paths() {
     "###);
 }
 
+#[test]
+fn test_split_with_author() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.set_up_fake_editor();
+    let get_signatures = || {
+        let template = r#"
+            separate(" ", author.name(), author.email(), author.timestamp(), committer.name())
+            ++ "\n"
+        "#;
+        test_env.jj_cmd_success(
+            &repo_path,
+            &["log", "--no-graph", "-T", template, "-r", "::@ ~ root()"],
+        )
+    };
+
+    std::fs::write(repo_path.join("file1"), "foo").unwrap();
+    std::fs::write(repo_path.join("file2"), "foo").unwrap();
+    std::fs::write(repo_path.join("file3"), "foo").unwrap();
+    // Both commits get the new author
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "split",
+            "file1",
+            "--author",
+            "Super Seeder <super.seeder@example.com>",
+        ],
+    );
+    insta::assert_snapshot!(get_signatures(), @r"
+    Super Seeder super.seeder@example.com 2001-02-03 04:05:08.000 +07:00 Test User
+    Super Seeder super.seeder@example.com 2001-02-03 04:05:08.000 +07:00 Test User
+    ");
+
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "split",
+            "file2",
+            "--config=user.name=Ove Ridder",
+            "--config=user.email=ove.ridder@example.com",
+            "--reset-author",
+        ],
+    );
+    insta::assert_snapshot!(get_signatures(), @r"
+    Ove Ridder ove.ridder@example.com 2001-02-03 04:05:10.000 +07:00 Ove Ridder
+    Ove Ridder ove.ridder@example.com 2001-02-03 04:05:10.000 +07:00 Ove Ridder
+    Super Seeder super.seeder@example.com 2001-02-03 04:05:08.000 +07:00 Test User
+    ");
+}
+
 #[test]
 fn test_split_with_non_empty_description() {
     let mut test_env = TestEnvironment::default();
@@ -1221,6 +1221,155 @@ fn test_squash_use_destination_message_and_message_mutual_exclusion() {
     "###);
 }
 
+#[test]
+fn test_squash_filter_description() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "add file1"]);
+    std::fs::write(repo_path.join("file2"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "add file2"]);
+    std::fs::write(repo_path.join("file1"), "a2\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "fixup! add file1"]);
+    std::fs::write(repo_path.join("file3"), "c\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "add file3"]);
+    std::fs::write(repo_path.join("file2"), "b2\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "fixup! add file2"]);
+    std::fs::write(repo_path.join("file1"), "a3\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "fixup! add file1"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  5eed31a8d6ab (empty)
+    ○  4b9560e1ba8e fixup! add file1
+    ○  6debd8d6564c fixup! add file2
+    ○  2c5c162c33ed add file3
+    ○  53dd1f7e2baf fixup! add file1
+    ○  e15ae9ac414b add file2
+    ○  ae85d9d1a195 add file1
+    ◆  000000000000 (empty)
+    ");
+
+    // The pattern requires --from
+    let stderr =
+        test_env.jj_cmd_cli_error(&repo_path, &["squash", "--filter-description", "^fixup! "]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the following required arguments were not provided:
+      --from <FROM>
+
+    Usage: jj squash --from <FROM> --filter-description <REGEX> [PATHS]...
+
+    For more information, try '--help'.
+    ");
+
+    // Nothing to do if no revision matches
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "squash",
+            "--from",
+            "mutable()",
+            "--filter-description",
+            "^squash! ",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"No revisions match the description pattern.");
+
+    // Each fixup is squashed into the nearest ancestor it refers to
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "squash",
+            "--from",
+            "mutable()",
+            "--filter-description",
+            "^fixup! ",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Squashed 2 revisions into qpvuntsm 45966995 add file1
+    Squashed 1 revision into rlvkpnrz f2f943c6 add file2
+    Working copy now at: yqosqzyt c47a130d (empty) (no description set)
+    Parent commit      : zsuskuln 2299c54e add file3
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  c47a130d4da5 (empty)
+    ○  2299c54ea7c8 add file3
+    ○  f2f943c62138 add file2
+    ○  459669958566 add file1
+    ◆  000000000000 (empty)
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "file",
+            "show",
+            "-r",
+            "description(exact:'add file1\n')",
+            "file1",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"a3");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "file",
+            "show",
+            "-r",
+            "description(exact:'add file2\n')",
+            "file2",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"b2");
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    // The referenced description can be captured by a group
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "squash",
+            "--from",
+            "mutable()",
+            "--filter-description",
+            r"(?m)^fixup! (.* file2)$",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Squashed 1 revision into rlvkpnrz d43fec12 add file2
+    Working copy now at: yqosqzyt ca4eca7e (empty) (no description set)
+    Parent commit      : royxmykx 3470d53c fixup! add file1
+    ");
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    // Fails if the referenced revision can't be found
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "fixup! add file4"]);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "squash",
+            "--from",
+            "mutable()",
+            "--filter-description",
+            "^fixup! ",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r#"Error: No ancestor of revision 63712b23772d is described as "add file4""#);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "fixup!"]);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "squash",
+            "--from",
+            "mutable()",
+            "--filter-description",
+            "^fixup!",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Description of revision 1cb0e0a3e423 doesn't refer to another revision");
+}
+
 fn get_description(test_env: &TestEnvironment, repo_path: &Path, rev: &str) -> String {
     test_env.jj_cmd_success(
         repo_path,
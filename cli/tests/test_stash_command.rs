@@ -0,0 +1,145 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_stash_push_pop() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("a"), "a\n").unwrap();
+    std::fs::write(repo_path.join("b"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+    std::fs::write(repo_path.join("a"), "a2\n").unwrap();
+    std::fs::write(repo_path.join("b"), "b2\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "wip"]);
+
+    // Stash only one of the files
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["stash", "a"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Saved stash@{0}: zsuskuln hidden 6af224bb wip
+    Working copy now at: rlvkpnrz 046c439f wip
+    Parent commit      : qpvuntsm 45eaa7cc base
+    Added 0 files, modified 1 files, removed 0 files
+    ");
+    assert_eq!(std::fs::read_to_string(repo_path.join("a")).unwrap(), "a\n");
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("b")).unwrap(),
+        "b2\n"
+    );
+
+    // Stash the rest with a message
+    test_env.jj_cmd_ok(&repo_path, &["stash", "push", "-m", "second"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["stash", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    stash@{0}: mzvwutvl hidden 60ba931e second
+    stash@{1}: zsuskuln hidden 6af224bb wip
+    ");
+    assert_eq!(std::fs::read_to_string(repo_path.join("b")).unwrap(), "b\n");
+
+    // Stashes are hidden
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r", "all()"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  rlvkpnrz test.user@example.com 2001-02-03 08:05:11 d021c50a
+    │  (empty) wip
+    ○  qpvuntsm test.user@example.com 2001-02-03 08:05:08 45eaa7cc
+    │  base
+    ◆  zzzzzzzz root() 00000000
+    ");
+
+    // Pop the older stash
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["stash", "pop", "stash@{1}"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Applied stash@{1}: zsuskuln hidden 6af224bb wip
+    Working copy now at: rlvkpnrz 8c91f554 wip
+    Parent commit      : qpvuntsm 45eaa7cc base
+    Added 0 files, modified 1 files, removed 0 files
+    ");
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("a")).unwrap(),
+        "a2\n"
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["stash", "list"]);
+    insta::assert_snapshot!(stdout, @"stash@{0}: mzvwutvl hidden 60ba931e second");
+
+    // Apply keeps the stash, drop removes it
+    test_env.jj_cmd_ok(&repo_path, &["stash", "apply"]);
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("b")).unwrap(),
+        "b2\n"
+    );
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["stash", "drop", "0"]);
+    insta::assert_snapshot!(stderr, @"Dropped stash@{0}: mzvwutvl hidden 60ba931e second");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["stash", "list"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    // Undo brings the stash back
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["stash", "list"]);
+    insta::assert_snapshot!(stdout, @"stash@{0}: mzvwutvl hidden 60ba931e second");
+}
+
+#[test]
+fn test_stash_pop_conflict() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("a"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+    std::fs::write(repo_path.join("a"), "stashed\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["stash"]);
+    std::fs::write(repo_path.join("a"), "changed\n").unwrap();
+
+    // The stash is kept if popping it results in conflicts
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["stash", "pop"]);
+    insta::assert_snapshot!(stderr, @r"
+    Applied stash@{0}: kkmpptxz hidden f8aadc30 (no description set)
+    The stash was kept because applying it resulted in conflicts
+    Working copy now at: rlvkpnrz e0293dfd (conflict) (no description set)
+    Parent commit      : qpvuntsm 1c79272e base
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    a    2-sided conflict
+    New conflicts appeared in these commits:
+      rlvkpnrz e0293dfd (conflict) (no description set)
+    To resolve the conflicts, start by updating to it:
+      jj new rlvkpnrz
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["stash", "list"]);
+    insta::assert_snapshot!(stdout, @"stash@{0}: kkmpptxz hidden f8aadc30 (no description set)");
+}
+
+#[test]
+fn test_stash_errors() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["stash"]);
+    insta::assert_snapshot!(stderr, @"Error: No changes to stash");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["stash", "pop"]);
+    insta::assert_snapshot!(stderr, @"Error: No stash entry at stash@{0}");
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["stash", "drop", "stash@{x}"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value 'stash@{x}' for '[STASH]': expected `N` or `stash@{N}`, got `stash@{x}`
+
+    For more information, try '--help'.
+    ");
+}
@@ -368,3 +368,233 @@ fn test_status_simplify_conflict_sides() {
     Then run `jj squash` to move the resolution into the conflicted commit.
     "#);
 }
+
+#[test]
+fn test_status_format_json() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        [status.sections]
+        divergence = true
+        untracked = true
+        skipped = true
+        bookmark-drift = true
+        "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "1").unwrap();
+    std::fs::write(repo_path.join("file2"), "2").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "first"]);
+    std::fs::write(repo_path.join("file1"), "changed").unwrap();
+    std::fs::remove_file(repo_path.join("file2")).unwrap();
+    std::fs::write(repo_path.join("file3"), "3").unwrap();
+    std::fs::write(repo_path.join("large"), "too large").unwrap();
+
+    let (stdout, _stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "status",
+            "--format=json",
+            "--config=snapshot.max-new-file-size=5",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    {
+      "bookmark_drift": [],
+      "changes": [
+        {
+          "path": "file1",
+          "status": "modified"
+        },
+        {
+          "path": "file2",
+          "status": "removed"
+        },
+        {
+          "path": "file3",
+          "status": "added"
+        }
+      ],
+      "conflicted_bookmarks": [],
+      "conflicted_remote_bookmarks": [],
+      "conflicts": [],
+      "divergent_commits": [],
+      "parents": [
+        {
+          "change_id": "qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu",
+          "commit_id": "abc63081ccb8a25585d9ff40165ba7a9a6009f27",
+          "conflict": false,
+          "description": "first\n"
+        }
+      ],
+      "skipped": [],
+      "untracked": [
+        {
+          "max_size": 5,
+          "path": "large",
+          "reason": "too-large",
+          "size": 9
+        }
+      ],
+      "working_copy": {
+        "change_id": "rlvkpnrzqnoowoytxnquwvuryrwnrmlp",
+        "commit_id": "d0273835027f97e4b3a0fff99e77a3079ed7eb4b",
+        "conflict": false,
+        "description": ""
+      }
+    }
+    "#);
+
+    // Disabled sections are omitted
+    test_env.add_config(
+        r#"
+        [status.sections]
+        changes = false
+        untracked = false
+        bookmark-conflicts = false
+        bookmark-drift = false
+        "#,
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status", "--format=json"]);
+    insta::assert_snapshot!(stdout, @r#"
+    {
+      "conflicts": [],
+      "divergent_commits": [],
+      "parents": [
+        {
+          "change_id": "qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu",
+          "commit_id": "abc63081ccb8a25585d9ff40165ba7a9a6009f27",
+          "conflict": false,
+          "description": "first\n"
+        }
+      ],
+      "skipped": [],
+      "working_copy": {
+        "change_id": "rlvkpnrzqnoowoytxnquwvuryrwnrmlp",
+        "commit_id": "bd2a180c3b3ccfe5e31558e314fdc4425aed1751",
+        "conflict": false,
+        "description": ""
+      }
+    }
+    "#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status"]);
+    insta::assert_snapshot!(stdout, @r"
+    Working copy : rlvkpnrz bd2a180c (no description set)
+    Parent commit: qpvuntsm abc63081 first
+    ");
+}
+
+#[test]
+fn test_status_divergent() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "original"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m", "concurrent", "--at-op", "@-"],
+    );
+
+    // The section is disabled by default
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status"]);
+    insta::assert_snapshot!(stdout, @r"
+    The working copy is clean
+    Working copy : qpvuntsm?? 9116e1ee (empty) original
+    Parent commit: zzzzzzzz 00000000 (empty) (no description set)
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["status", "--config=status.sections.divergence=true"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    The working copy is clean
+    Working copy : qpvuntsm?? 9116e1ee (empty) original
+    Parent commit: zzzzzzzz 00000000 (empty) (no description set)
+    The working-copy change is divergent. Other commits of the change:
+      qpvuntsm?? adff1ac1 (empty) concurrent
+    ");
+}
+
+#[test]
+fn test_status_bookmark_drift() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "remote"]);
+    let remote_path = test_env.env_root().join("remote");
+    create_commit(&test_env, &remote_path, "main", &[], &[("file", "1")]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+    let mut remote_git_path = remote_path.clone();
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "repo"],
+    );
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "track", "main@origin"]);
+
+    // Make main diverge from main@origin
+    create_commit(&test_env, &remote_path, "other", &["main"], &[]);
+    test_env.jj_cmd_ok(&remote_path, &["bookmark", "set", "main", "-r", "other"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "main@origin-"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "local 1"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "local 2"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "set", "main", "-r", "@-", "--allow-backwards"],
+    );
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["status", "--config=status.sections.bookmark-drift=true"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    The working copy is clean
+    Working copy : lylxulpl 56cce30e (empty) (no description set)
+    Parent commit: wqnwkozp 4eac331a main* | (empty) local 2
+    These bookmarks differ from their tracked remotes:
+      main: 2 ahead, 1 behind main@origin
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "status",
+            "--format=json",
+            "--config=status.sections.changes=false",
+            "--config=status.sections.bookmark-drift=true",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    {
+      "bookmark_drift": [
+        {
+          "ahead": 2,
+          "behind": 1,
+          "name": "main",
+          "remote": "origin"
+        }
+      ],
+      "conflicted_bookmarks": [],
+      "conflicted_remote_bookmarks": [],
+      "conflicts": [],
+      "parents": [
+        {
+          "change_id": "wqnwkozpkustnxypnnntnykwrqrkrpvv",
+          "commit_id": "4eac331a9af5a40a41a5a76257e92060db178a95",
+          "conflict": false,
+          "description": "local 2\n"
+        }
+      ],
+      "working_copy": {
+        "change_id": "lylxulplsnywpkoymoszzvuzpotpukzq",
+        "commit_id": "56cce30e8c5f430de8ca8edfa074f345e5351e50",
+        "conflict": false,
+        "description": ""
+      }
+    }
+    "#);
+}
@@ -117,3 +117,126 @@ fn test_tag_list() {
     added_targets: commit2
     "###);
 }
+
+#[test]
+fn test_tag_create_delete() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = {
+        let mut git_repo_path = repo_path.clone();
+        git_repo_path.extend([".jj", "repo", "store", "git"]);
+        git2::Repository::open(git_repo_path).unwrap()
+    };
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-mcommit1"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mcommit2"]);
+
+    // Lightweight tag
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["tag", "create", "-r@-", "v1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Created 1 tags pointing to qpvuntsm caf975d0 (empty) commit1");
+    let git_ref = git_repo.find_reference("refs/tags/v1").unwrap();
+    assert!(git_ref.peel_to_tag().is_err());
+
+    // Annotated tag
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["tag", "create", "-mRelease 2", "v2", "v2.0"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Created 2 tags pointing to kkmpptxz d0a19ea4 (empty) commit2
+    Warning: The working-copy commit in workspace 'default' became immutable, so a new commit has been created on top of it.
+    Working copy now at: mzvwutvl d91a3a3c (empty) (no description set)
+    Parent commit      : kkmpptxz d0a19ea4 (empty) commit2
+    ");
+    let tag = git_repo
+        .find_reference("refs/tags/v2")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    assert_eq!(tag.message(), Some("Release 2\n"));
+    assert_eq!(tag.tagger().unwrap().name(), Some("Test User"));
+
+    // Importing the refs doesn't change the tags
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @r"
+    v1: qpvuntsm caf975d0 (empty) commit1
+    v2: kkmpptxz d0a19ea4 (empty) commit2
+    v2.0: kkmpptxz d0a19ea4 (empty) commit2
+    ");
+
+    // Existing tags are never moved
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["tag", "create", "v1"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Tag already exists: v1
+    Hint: Use `jj tag delete` to delete it first.
+    ");
+
+    // Tags which weren't imported from Git yet can't be created
+    let commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "-r@-", "--no-graph", "-Tcommit_id"]);
+    let oid = git2::Oid::from_str(&commit_id).unwrap();
+    git_repo.reference("refs/tags/v4", oid, false, "").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["tag", "create", "v4"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Tag already exists in the Git repo: v4
+    Hint: Run `jj git import` to import it.
+    ");
+
+    // Signing requires a signing backend
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["tag", "create", "--sign", "v3"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Cannot sign tags because no signing backend is configured
+    Hint: Set `signing.backend` to sign tags.
+    ");
+
+    // Delete tags
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["tag", "delete", "glob:v2*"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Deleted 2 tags.");
+    assert!(git_repo.find_reference("refs/tags/v2").is_err());
+    assert!(git_repo.find_reference("refs/tags/v2.0").is_err());
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["tag", "delete", "v2"]);
+    insta::assert_snapshot!(stderr, @"Error: No such tag: v2");
+    test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @r"
+    v1: qpvuntsm caf975d0 (empty) commit1
+    v4: kkmpptxz d0a19ea4 (empty) commit2
+    ");
+}
+
+#[test]
+fn test_tag_delete_conflicted() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = {
+        let mut git_repo_path = repo_path.clone();
+        git_repo_path.extend([".jj", "repo", "store", "git"]);
+        git2::Repository::open(git_repo_path).unwrap()
+    };
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-mcommit1"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-mcommit2"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["tag", "create", "-rdescription(commit1)", "v1"],
+    );
+
+    // The tag is moved in Git and imported by a concurrent operation
+    let commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "-r@", "--no-graph", "-Tcommit_id"]);
+    let oid = git2::Oid::from_str(&commit_id).unwrap();
+    git_repo.reference("refs/tags/v1", oid, true, "").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "import", "--at-op=@-"]);
+    test_env.jj_cmd_ok(&repo_path, &["status"]); // resolve concurrent ops
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @r"
+    v1 (conflicted):
+      + qpvuntsm caf975d0 (empty) commit1
+      + kkmpptxz f5d6b504 (empty) commit2
+    ");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["tag", "delete", "v1"]);
+    insta::assert_snapshot!(stderr, @"Deleted 1 tags.");
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @"");
+    assert!(git_repo.find_reference("refs/tags/v1").is_err());
+}
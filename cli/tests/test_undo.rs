@@ -13,6 +13,8 @@
 // limitations under the License.
 use std::path::Path;
 
+use crate::common::get_stderr_string;
+use crate::common::get_stdout_string;
 use crate::common::TestEnvironment;
 
 #[test]
@@ -46,6 +48,97 @@ fn test_undo_rewrite_with_child() {
     "###);
 }
 
+#[test]
+fn test_undo_interactive() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "initial"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "modified"]);
+
+    // Confirmation is required unless --yes is passed
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["undo", "--interactive"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Refusing to undo without confirmation
+    Hint: Use --yes to undo without confirmation.
+    ");
+
+    // Declining leaves the repo unchanged
+    let assert = test_env
+        .jj_cmd_stdin(&repo_path, &["undo", "-i"], "n\n")
+        .assert()
+        .code(1);
+    let stdout = test_env.normalize_output(&get_stdout_string(&assert));
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    insta::assert_snapshot!(stdout, @r"
+    Operation to undo: e9ae2aef4f48 (2001-02-03 08:05:10) describe commit 68e1101283b0a6c4694f92fab85bcd90f0c83652
+
+    Changed commits:
+    ○  Change qpvuntsmwlqt
+       + qpvuntsm 68e11012 main | (empty) initial
+       - qpvuntsm hidden 71e87fbe (empty) modified
+
+    Changed local bookmarks:
+    main:
+    + qpvuntsm 68e11012 main | (empty) initial
+    - qpvuntsm hidden 71e87fbe (empty) modified
+    ");
+    insta::assert_snapshot!(stderr, @"Undo this operation? (yN): Error: Aborted");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  modified
+    ◆
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_stdin_ok(&repo_path, &["undo", "-i"], "y\n");
+    insta::assert_snapshot!(stdout, @r"
+    Operation to undo: e9ae2aef4f48 (2001-02-03 08:05:10) describe commit 68e1101283b0a6c4694f92fab85bcd90f0c83652
+
+    Changed commits:
+    ○  Change qpvuntsmwlqt
+       + qpvuntsm 68e11012 main | (empty) initial
+       - qpvuntsm hidden 71e87fbe (empty) modified
+
+    Changed local bookmarks:
+    main:
+    + qpvuntsm 68e11012 main | (empty) initial
+    - qpvuntsm hidden 71e87fbe (empty) modified
+    ");
+    insta::assert_snapshot!(stderr, @r"
+    Undo this operation? (yN): Undid operation: e9ae2aef4f48 (2001-02-03 08:05:10) describe commit 68e1101283b0a6c4694f92fab85bcd90f0c83652
+    Working copy now at: qpvuntsm 68e11012 main | (empty) initial
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    ");
+
+    // --yes skips the confirmation
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["undo", "-i", "--yes"]);
+    insta::assert_snapshot!(stdout, @r"
+    Operation to undo: b35fc3f8ae70 (2001-02-03 08:05:14) undo operation e9ae2aef4f484863ce8684ea69984c2a3ab811e40b1e5f1d035c79a1210a67d24399178acc99c9a8962a3e679de4660cbca04619c7151eae0573034e6f70bbf7
+
+    Changed commits:
+    ○  Change qpvuntsmwlqt
+       + qpvuntsm 71e87fbe main | (empty) modified
+       - qpvuntsm hidden 68e11012 (empty) initial
+
+    Changed local bookmarks:
+    main:
+    + qpvuntsm 71e87fbe main | (empty) modified
+    - qpvuntsm hidden 68e11012 (empty) initial
+    ");
+    insta::assert_snapshot!(stderr, @r"
+    Undid operation: b35fc3f8ae70 (2001-02-03 08:05:14) undo operation e9ae2aef4f484863ce8684ea69984c2a3ab811e40b1e5f1d035c79a1210a67d24399178acc99c9a8962a3e679de4660cbca04619c7151eae0573034e6f70bbf7
+    Working copy now at: qpvuntsm 71e87fbe main | (empty) modified
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  modified
+    ◆
+    ");
+}
+
 #[test]
 fn test_git_push_undo() {
     let test_env = TestEnvironment::default();
@@ -143,3 +143,75 @@ fn test_util_exec_fail() {
     );
     insta::assert_snapshot!(strip_last_line(&err), @"Error: Failed to execute external command 'missing-program'");
 }
+
+#[cfg(unix)]
+#[test]
+fn test_util_exec_with_patches() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "first"]);
+    std::fs::write(repo_path.join("file"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "second"]);
+
+    // The patch files are passed after the other arguments
+    let script = r#"echo "$1"; shift; for f; do basename "$f"; grep Subject "$f"; done"#;
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "util",
+            "exec-with-patches",
+            "-r",
+            "root()..@-",
+            "--",
+            "sh",
+            "-c",
+            script,
+            "sh",
+            "--to=list@example.org",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    --to=list@example.org
+    0001-first.patch
+    Subject: [PATCH 1/2] first
+    0002-second.patch
+    Subject: [PATCH 2/2] second
+    ");
+    insta::assert_snapshot!(stderr, @"");
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["util", "exec-with-patches", "-r", "@-", "--", "false"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: External command exited with 1");
+}
+
+#[test]
+fn test_util_prompt() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["util", "prompt"]);
+    insta::assert_snapshot!(stdout, @"qpvuntsm eac759b9ab75");
+
+    // Bookmarks, divergence and conflicts are included
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()"]);
+    std::fs::write(repo_path.join("file"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "main", "@"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "feature"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "x"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "y", "--at-op", "@-"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["util", "prompt"]);
+    insta::assert_snapshot!(stdout, @"mzvwutvl?? feature?? (conflict) 386c84b6d3b8");
+    insta::assert_snapshot!(stderr, @"Concurrent modification detected, resolving automatically.");
+
+    // Changes in the working copy aren't snapshotted
+    std::fs::write(repo_path.join("file"), "resolved\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["util", "prompt"]);
+    insta::assert_snapshot!(stdout, @"mzvwutvl?? feature?? (conflict) 386c84b6d3b8");
+}
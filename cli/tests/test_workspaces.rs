@@ -16,6 +16,7 @@ use std::path::Path;
 
 use test_case::test_case;
 
+use crate::common::get_stderr_string;
 use crate::common::TestEnvironment;
 
 /// Test adding a second workspace
@@ -71,6 +72,86 @@ fn test_workspaces_add_second_workspace() {
     "###);
 }
 
+/// Test template and revset access to the workspaces of working-copy commits
+#[test]
+fn test_workspaces_working_copies_template_and_revset() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    test_env.jj_cmd_ok(
+        &main_path,
+        &["workspace", "add", "--name", "second", "../second"],
+    );
+    test_env.jj_cmd_ok(
+        &main_path,
+        &["workspace", "add", "--name", "third", "../third"],
+    );
+    let third_path = test_env.env_root().join("third");
+    test_env.jj_cmd_ok(&third_path, &["edit", "second@"]);
+
+    let template = r#"
+    if(working_copies, working_copies.len() ++ ": " ++ working_copies.join(","), "-") ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&main_path, &["log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    2: second@,third@
+    1: default@
+    -
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &main_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "wc_of(second)",
+            "-T",
+            "working_copies ++ \"\\n\"",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"second@ third@");
+    let stdout = test_env.jj_cmd_success(
+        &main_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "wc_of(glob:'*t*')",
+            "-T",
+            "working_copies ++ \"\\n\"",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    second@ third@
+    default@
+    ");
+    let stdout =
+        test_env.jj_cmd_success(&main_path, &["log", "--no-graph", "-r", "wc_of(missing)"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    let stdout = test_env.jj_cmd_success(
+        &main_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "ws(third) | ws('default')",
+            "-T",
+            "working_copies ++ \"\\n\"",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    second@ third@
+    default@
+    ");
+    let stderr = test_env.jj_cmd_failure(&main_path, &["log", "-r", "ws(missing)"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Workspace "missing" doesn't have a working-copy commit
+    Hint: Specify a revision explicitly. In a bare repo, run `jj workspace add --first` to check out a working copy.
+    "#);
+}
+
 /// Test how sparse patterns are inherited
 #[test]
 fn test_workspaces_sparse_patterns() {
@@ -457,6 +538,46 @@ fn test_workspaces_add_workspace_in_current_workspace() {
     "###);
 }
 
+#[test]
+fn test_workspaces_add_first() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+
+    std::fs::write(main_path.join("file"), "contents").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["commit", "-m", "initial"]);
+
+    // Not allowed while the repo has a working copy
+    let stderr = test_env.jj_cmd_failure(&main_path, &["workspace", "add", "--first"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: The repo already has a working copy
+    Hint: Use `jj workspace add <destination>` to add another workspace
+    ");
+    let stderr =
+        test_env.jj_cmd_cli_error(&main_path, &["workspace", "add", "--first", "../second"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--first' cannot be used with '[DESTINATION]'
+
+    Usage: jj workspace add --first [DESTINATION]
+
+    For more information, try '--help'.
+    ");
+
+    // Check out the given revision once the working copy is gone
+    test_env.jj_cmd_ok(&main_path, &["workspace", "forget"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &main_path,
+        &["workspace", "add", "--first", "-r", "description(initial)"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: royxmykx 0aae36cb (empty) (no description set)
+    Parent commit      : qpvuntsm 751b12b7 initial
+    ");
+    let stdout = test_env.jj_cmd_success(&main_path, &["workspace", "list"]);
+    insta::assert_snapshot!(stdout, @"default: royxmykx 0aae36cb (empty) (no description set)");
+}
+
 /// Test making changes to the working copy in a workspace as it gets rewritten
 /// from another workspace
 #[test]
@@ -903,6 +1024,109 @@ fn test_workspaces_update_stale_snapshot() {
     "###);
 }
 
+/// Test updating a stale working copy to another revision, discarding changes
+#[test]
+fn test_workspaces_update_stale_to_and_discard() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    let secondary_path = test_env.env_root().join("secondary");
+
+    std::fs::write(main_path.join("file"), "contents\n").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["new"]);
+    test_env.jj_cmd_ok(&main_path, &["workspace", "add", "../secondary"]);
+
+    // Make the secondary workspace stale, and change files in it
+    std::fs::write(main_path.join("file"), "changed in main\n").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["squash"]);
+    std::fs::write(secondary_path.join("file"), "changed in second\n").unwrap();
+
+    // Discarding changes needs confirmation
+    let stderr = test_env.jj_cmd_failure(
+        &secondary_path,
+        &["workspace", "update-stale", "--discard-changes"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Refusing to discard changes without confirmation
+    Hint: Use --yes to discard them without confirmation.
+    ");
+    let assert = test_env
+        .jj_cmd_stdin(
+            &secondary_path,
+            &["workspace", "update-stale", "--discard-changes"],
+            "n\n",
+        )
+        .assert()
+        .code(1);
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Changes in the working copy that haven't been snapshotted will be lost.
+    Discard them? (yN): Error: Aborted
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_stdin_ok(
+        &secondary_path,
+        &[
+            "workspace",
+            "update-stale",
+            "--discard-changes",
+            "--to",
+            "root()",
+        ],
+        "y\n",
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Changes in the working copy that haven't been snapshotted will be lost.
+    Discard them? (yN): Working copy now at: yqosqzyt 5b36783c (empty) (no description set)
+    Added 0 files, modified 0 files, removed 1 files
+    Updated working copy to fresh commit 5b36783cd11c
+    ");
+    assert!(!secondary_path.join("file").exists());
+    insta::assert_snapshot!(get_log_output(&test_env, &secondary_path), @r"
+    @  5b36783cd11c secondary@
+    │ ○  9f9a75fd0fb4 default@
+    │ ○  709a45307e19
+    ├─╯
+    ◆  000000000000
+    ");
+    let stdout = test_env.jj_cmd_success(&secondary_path, &["status"]);
+    insta::assert_snapshot!(stdout, @r"
+    The working copy is clean
+    Working copy : yqosqzyt 5b36783c (empty) (no description set)
+    Parent commit: zzzzzzzz 00000000 (empty) (no description set)
+    ");
+}
+
+/// Test that discarding changes in a stale working copy keeps untracked files
+#[test]
+fn test_workspaces_update_stale_discard_keeps_untracked_files() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    let secondary_path = test_env.env_root().join("secondary");
+
+    std::fs::write(main_path.join("file"), "contents\n").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["new"]);
+    test_env.jj_cmd_ok(&main_path, &["workspace", "add", "../secondary"]);
+
+    // Make the secondary workspace stale, and add a file that has never been
+    // snapshotted
+    std::fs::write(main_path.join("file"), "changed in main\n").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["squash"]);
+    std::fs::write(secondary_path.join("file"), "changed in second\n").unwrap();
+    std::fs::write(secondary_path.join("untracked"), "untracked\n").unwrap();
+
+    test_env.jj_cmd_ok(
+        &secondary_path,
+        &["workspace", "update-stale", "--discard-changes", "--yes"],
+    );
+    insta::assert_snapshot!(
+        std::fs::read_to_string(secondary_path.join("file")).unwrap(), @"changed in main");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(secondary_path.join("untracked")).unwrap(), @"untracked");
+}
+
 /// Test forgetting workspaces
 #[test]
 fn test_workspaces_forget() {
@@ -945,6 +1169,7 @@ fn test_workspaces_forget() {
     let stderr = test_env.jj_cmd_failure(&main_path, &["log", "-r", "@"]);
     insta::assert_snapshot!(stderr, @r###"
     Error: Workspace "default" doesn't have a working-copy commit
+    Hint: Specify a revision explicitly. In a bare repo, run `jj workspace add --first` to check out a working copy.
     "###);
 
     // Try to add back the workspace
@@ -1261,6 +1486,181 @@ fn test_workspaces_rename_workspace() {
     "###);
 }
 
+/// Test moving the root directory of a workspace
+#[test]
+fn test_workspaces_move_root() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    std::fs::write(main_path.join("file"), "contents").unwrap();
+    test_env.jj_cmd_ok(
+        &main_path,
+        &["workspace", "add", "--name", "second", "../secondary"],
+    );
+    let secondary_path = test_env.env_root().join("secondary");
+    std::fs::write(secondary_path.join("new-file"), "new contents").unwrap();
+
+    // The destination must not exist or be inside the workspace
+    let stderr = test_env.jj_cmd_failure(&secondary_path, &["workspace", "move-root", "../main"]);
+    insta::assert_snapshot!(stderr, @"Error: The destination ($TEST_ENV/main) already exists");
+    let stderr = test_env.jj_cmd_failure(&secondary_path, &["workspace", "move-root", "sub"]);
+    insta::assert_snapshot!(stderr, @"Error: Cannot move the workspace into itself");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&secondary_path, &["workspace", "move-root", "."]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+
+    // Missing parent directories are created, and the working copy is
+    // snapshotted before it's moved
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &secondary_path,
+        &["workspace", "move-root", "../moved/secondary"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr.replace('\\', "/"), @"Moved workspace 'second' to $TEST_ENV/moved/secondary");
+    assert!(!secondary_path.exists());
+    let moved_path = test_env.env_root().join("moved").join("secondary");
+    let stdout = test_env.jj_cmd_success(&moved_path, &["workspace", "root"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @"$TEST_ENV/moved/secondary");
+    let stdout = test_env.jj_cmd_success(&moved_path, &["status"]);
+    insta::assert_snapshot!(stdout, @r"
+    Working copy changes:
+    A new-file
+    Working copy : uuqppmxq f7f794aa (no description set)
+    Parent commit: zzzzzzzz 00000000 (empty) (no description set)
+    ");
+
+    // Moving the workspace that holds the repo leaves other workspaces behind
+    let (stdout, stderr) = test_env.jj_cmd_ok(&main_path, &["workspace", "move-root", "../main2"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr.replace('\\', "/"), @r"
+    Moved workspace 'default' to $TEST_ENV/main2
+    Warning: The repo was moved along with this workspace. These workspaces still refer to its old location: second
+    Hint: Update the path in their `.jj/repo` file to $TEST_ENV/main2/.jj/repo
+    ");
+    let main_path = test_env.env_root().join("main2");
+    let stdout = test_env.jj_cmd_success(&main_path, &["workspace", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    default: qpvuntsm 4e8f9d2b (no description set)
+    second: uuqppmxq f7f794aa (no description set)
+    ");
+}
+
+#[test]
+fn test_workspaces_run() {
+    let test_env = TestEnvironment::default();
+    let formatter_path = assert_cmd::cargo::cargo_bin("fake-formatter");
+    assert!(formatter_path.is_file());
+    let formatter = formatter_path.to_str().unwrap();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    test_env.jj_cmd_ok(&main_path, &["workspace", "add", "../second"]);
+    test_env.jj_cmd_ok(&main_path, &["workspace", "add", "../third"]);
+
+    // A workspace must be selected
+    let stderr = test_env.jj_cmd_cli_error(&main_path, &["workspace", "run", "true"]);
+    insta::assert_snapshot!(stderr.lines().next().unwrap(), @"error: the following required arguments were not provided:");
+
+    // The command runs in the root of each workspace
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &main_path,
+        &[
+            "workspace",
+            "run",
+            "--all",
+            "--",
+            formatter,
+            "--stdout",
+            "ran\n",
+            "--tee",
+            "out",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    ran
+    ran
+    ran
+    ");
+    insta::assert_snapshot!(stderr.replace('\\', "/"), @r"
+    Running in workspace 'default' at .:
+    Running in workspace 'second' at ../second:
+    Running in workspace 'third' at ../third:
+    Results:
+      default: exited with 0
+      second: exited with 0
+      third: exited with 0
+    ");
+    for name in ["main", "second", "third"] {
+        let out = std::fs::read_to_string(test_env.env_root().join(name).join("out")).unwrap();
+        assert_eq!(out, "ran\n");
+    }
+
+    // Failures are reported per workspace
+    let stderr = test_env.jj_cmd_failure(
+        &main_path,
+        &[
+            "workspace",
+            "run",
+            "--parallel",
+            "-w",
+            "second",
+            "-w",
+            "third",
+            "--",
+            formatter,
+            "--stdout",
+            "",
+            "--stderr",
+            "oops\n",
+            "--fail",
+        ],
+    );
+    insta::assert_snapshot!(stderr.replace('\\', "/"), @r"
+    Running in workspace 'second' at ../second:
+    oops
+    Running in workspace 'third' at ../third:
+    oops
+    Results:
+      second: exited with 1
+      third: exited with 1
+    Error: The command failed in 2 of 2 workspaces
+    ");
+    let stderr = test_env.jj_cmd_failure(
+        &main_path,
+        &["workspace", "run", "-w", "missing", "--", formatter],
+    );
+    insta::assert_snapshot!(stderr, @"Error: No such workspace: missing");
+
+    // Workspaces with an unknown or missing location are skipped
+    std::fs::remove_dir_all(main_path.join(".jj/repo/workspace_roots")).unwrap();
+    test_env.jj_cmd_ok(&test_env.env_root().join("third"), &["status"]);
+    std::fs::rename(
+        test_env.env_root().join("third"),
+        test_env.env_root().join("moved"),
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &main_path,
+        &[
+            "workspace",
+            "run",
+            "--all",
+            "--",
+            formatter,
+            "--stdout",
+            "ran\n",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"ran");
+    insta::assert_snapshot!(stderr.replace('\\', "/"), @r"
+    Warning: Skipping workspace 'second' since its location is unknown
+    Hint: Run any jj command in the workspace to record its location.
+    Warning: Skipping workspace 'third' since $TEST_ENV/third doesn't exist
+    Running in workspace 'default' at .:
+    Results:
+      default: exited with 0
+    ");
+}
+
 fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
     let template = r#"
     separate(" ",
@@ -269,6 +269,19 @@ pub enum ConfigSource {
     CommandArg,
 }
 
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ConfigSource::Default => "default",
+            ConfigSource::EnvBase | ConfigSource::EnvOverrides => "env",
+            ConfigSource::User => "user",
+            ConfigSource::Repo => "repo",
+            ConfigSource::CommandArg => "cli",
+        };
+        write!(f, "{name}")
+    }
+}
+
 /// Set of configuration variables with source information.
 #[derive(Clone, Debug)]
 pub struct ConfigLayer {
@@ -0,0 +1,462 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Index of the commits that changed each path.
+//!
+//! The index is stored per index segment. Since segment files are immutable
+//! and content-addressed, the local positions of the commits in a segment
+//! never change. When segments are squashed, the entries of the old segments
+//! are carried over, so only newly added commits have to be diffed.
+//!
+//! Paths are keyed by hash. A commit changing a file is also indexed under the
+//! parent directories of the file, so directory lookups work the same way.
+
+#![allow(missing_docs)]
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use blake2::Blake2b512;
+use digest::Digest as _;
+use futures::StreamExt as _;
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use tempfile::NamedTempFile;
+
+use super::composite::CompositeIndex;
+use super::composite::IndexSegment as _;
+use super::entry::IndexPosition;
+use super::entry::LocalPosition;
+use super::readonly::ReadonlyIndexSegment;
+use crate::backend::BackendResult;
+use crate::backend::CommitId;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::fileset::FilePattern;
+use crate::fileset::FilesetExpression;
+use crate::matchers::EverythingMatcher;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::rewrite;
+use crate::store::Store;
+
+const FORMAT_VERSION: u8 = 1;
+
+/// Commits changing more paths than this aren't indexed. Such commits are rare
+/// and would match most queries anyway.
+const MAX_NUM_KEYS: usize = 16 * 1024;
+
+/// Hashed path to look up in the changed-path index.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(super) struct ChangedPathKey(u64, u64);
+
+impl ChangedPathKey {
+    pub fn new(path: &RepoPath) -> Self {
+        let hash = Blake2b512::digest(path.as_internal_file_string());
+        let h1 = u64::from_le_bytes(hash[0..8].try_into().unwrap());
+        let h2 = u64::from_le_bytes(hash[8..16].try_into().unwrap());
+        ChangedPathKey(h1, h2)
+    }
+
+    /// Returns the keys of the literal paths the `expression` is limited to,
+    /// or `None` if the expression may match arbitrary paths.
+    ///
+    /// A directory key matches commits changing any file in the directory.
+    pub fn from_fileset(expression: &FilesetExpression) -> Option<Vec<Self>> {
+        match expression {
+            FilesetExpression::None => Some(vec![]),
+            FilesetExpression::All => None,
+            FilesetExpression::Pattern(pattern) => {
+                let path = match pattern {
+                    FilePattern::FilePath(path) | FilePattern::PrefixPath(path) => path,
+                    FilePattern::FileGlob { dir, .. } => dir,
+                };
+                // Every commit changing something changes the root.
+                (!path.is_root()).then(|| vec![ChangedPathKey::new(path)])
+            }
+            FilesetExpression::UnionAll(expressions) => {
+                let mut keys = vec![];
+                for expression in expressions {
+                    keys.extend(Self::from_fileset(expression)?);
+                }
+                Some(keys)
+            }
+            FilesetExpression::Intersection(expression1, expression2) => {
+                Self::from_fileset(expression1).or_else(|| Self::from_fileset(expression2))
+            }
+            FilesetExpression::Difference(expression1, _) => Self::from_fileset(expression1),
+        }
+    }
+}
+
+/// Returns the keys of the changed paths and their parent directories.
+fn changed_path_keys<'a>(paths: impl IntoIterator<Item = &'a RepoPath>) -> HashSet<ChangedPathKey> {
+    let mut dirs = HashSet::new();
+    for path in paths {
+        let mut path = Some(path);
+        while let Some(dir) = path.filter(|path| !path.is_root()) {
+            if !dirs.insert(dir) {
+                break;
+            }
+            path = dir.parent();
+        }
+    }
+    dirs.into_iter().map(ChangedPathKey::new).collect()
+}
+
+/// Returns the paths changed by the commit, or `None` if there are too many.
+fn changed_paths_from_parents(
+    store: &Arc<Store>,
+    index: &CompositeIndex,
+    commit_id: &CommitId,
+) -> BackendResult<Option<Vec<RepoPathBuf>>> {
+    let commit = store.get_commit(commit_id)?;
+    let parents: Vec<_> = commit.parents().try_collect()?;
+    let from_tree = rewrite::merge_commit_trees_no_resolve_without_repo(store, &index, &parents)?;
+    let to_tree = commit.tree()?;
+    let mut tree_diff = from_tree.diff_stream(&to_tree, &EverythingMatcher);
+    async {
+        let mut paths = vec![];
+        while let Some(entry) = tree_diff.next().await {
+            entry.values?;
+            if paths.len() >= MAX_NUM_KEYS {
+                return Ok(None);
+            }
+            paths.push(entry.path);
+        }
+        Ok(Some(paths))
+    }
+    .block_on()
+}
+
+/// Directory of the changed-path index files keyed by segment name.
+///
+/// The index is maintained only if the directory exists.
+#[derive(Clone, Debug)]
+pub(super) struct ChangedPathIndexStore {
+    dir: PathBuf,
+}
+
+impl ChangedPathIndexStore {
+    pub fn new(dir: PathBuf) -> Self {
+        ChangedPathIndexStore { dir }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.dir.is_dir()
+    }
+
+    pub fn enable(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)
+    }
+
+    pub fn has_segment(&self, name: &str) -> bool {
+        self.dir.join(name).is_file()
+    }
+
+    /// Loads the index of the given segment. Unreadable files are treated as
+    /// missing.
+    pub fn read_segment(&self, name: &str) -> Option<SegmentPathIndex> {
+        let buf = fs::read(self.dir.join(name)).ok()?;
+        SegmentPathIndex::from_bytes(&buf)
+    }
+
+    pub fn write_segment(&self, name: &str, index: &SegmentPathIndex) -> io::Result<()> {
+        let mut temp_file = NamedTempFile::new_in(&self.dir)?;
+        temp_file.as_file_mut().write_all(&index.to_bytes())?;
+        persist_content_addressed_temp_file(temp_file, self.dir.join(name))?;
+        Ok(())
+    }
+
+    /// Builds and saves the index of the `segment` unless it exists. Entries of
+    /// the `reusable` segments are carried over. Returns the number of the
+    /// commits diffed.
+    pub fn update_segment(
+        &self,
+        store: &Arc<Store>,
+        segment: &ReadonlyIndexSegment,
+        reusable: &[Arc<ReadonlyIndexSegment>],
+    ) -> BackendResult<usize> {
+        if self.has_segment(segment.name()) {
+            return Ok(0);
+        }
+        let reusable = reusable
+            .iter()
+            .filter_map(|old| Some((old, self.read_segment(old.name())?)))
+            .collect::<Vec<_>>();
+        let (index, num_diffed) = SegmentPathIndex::build(store, segment, &reusable)?;
+        // The index is a cache. If it can't be saved, the segment is diffed
+        // as before.
+        if let Err(err) = self.write_segment(segment.name(), &index) {
+            tracing::warn!(?err, "failed to save changed-path index");
+        }
+        Ok(num_diffed)
+    }
+}
+
+/// Commits that changed each path, by local position in an index segment.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(super) struct SegmentPathIndex {
+    /// Commits that changed too many paths to be indexed.
+    uncovered: Vec<u32>,
+    /// Sorted by key.
+    entries: Vec<(ChangedPathKey, Vec<u32>)>,
+}
+
+impl SegmentPathIndex {
+    fn build(
+        store: &Arc<Store>,
+        segment: &ReadonlyIndexSegment,
+        reusable: &[(&Arc<ReadonlyIndexSegment>, SegmentPathIndex)],
+    ) -> BackendResult<(Self, usize)> {
+        let num_local_commits = segment.num_local_commits();
+        let mut covered = vec![false; usize::try_from(num_local_commits).unwrap()];
+        let mut uncovered = vec![];
+        let mut entries: BTreeMap<ChangedPathKey, Vec<u32>> = BTreeMap::new();
+        for (old_segment, old_index) in reusable {
+            let to_new_pos = |LocalPosition(old_pos)| {
+                let commit_id = old_segment.commit_id(LocalPosition(old_pos));
+                segment.commit_id_to_pos(&commit_id)
+            };
+            let old_uncovered: HashSet<_> = old_index.uncovered.iter().copied().collect();
+            for old_pos in 0..old_segment.num_local_commits() {
+                let Some(LocalPosition(new_pos)) = to_new_pos(LocalPosition(old_pos)) else {
+                    continue;
+                };
+                covered[usize::try_from(new_pos).unwrap()] = true;
+                if old_uncovered.contains(&old_pos) {
+                    uncovered.push(new_pos);
+                }
+            }
+            for (key, old_positions) in &old_index.entries {
+                let new_positions = old_positions
+                    .iter()
+                    .filter_map(|&old_pos| to_new_pos(LocalPosition(old_pos)))
+                    .map(|LocalPosition(new_pos)| new_pos);
+                entries.entry(*key).or_default().extend(new_positions);
+            }
+        }
+
+        let index = segment.as_composite();
+        let mut num_diffed = 0;
+        for pos in 0..num_local_commits {
+            if covered[usize::try_from(pos).unwrap()] {
+                continue;
+            }
+            let commit_id = segment.commit_id(LocalPosition(pos));
+            num_diffed += 1;
+            match changed_paths_from_parents(store, index, &commit_id)? {
+                Some(paths) => {
+                    for key in changed_path_keys(paths.iter().map(AsRef::as_ref)) {
+                        entries.entry(key).or_default().push(pos);
+                    }
+                }
+                None => uncovered.push(pos),
+            }
+        }
+
+        uncovered.sort_unstable();
+        let entries = entries
+            .into_iter()
+            .map(|(key, mut positions)| {
+                positions.sort_unstable();
+                positions.dedup();
+                (key, positions)
+            })
+            .collect();
+        Ok((SegmentPathIndex { uncovered, entries }, num_diffed))
+    }
+
+    fn lookup(&self, key: ChangedPathKey) -> &[u32] {
+        match self.entries.binary_search_by_key(&key, |(key, _)| *key) {
+            Ok(i) => &self.entries[i].1,
+            Err(_) => &[],
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![FORMAT_VERSION];
+        write_positions(&mut buf, &self.uncovered);
+        buf.extend(u32::try_from(self.entries.len()).unwrap().to_le_bytes());
+        for (ChangedPathKey(h1, h2), positions) in &self.entries {
+            buf.extend(h1.to_le_bytes());
+            buf.extend(h2.to_le_bytes());
+            write_positions(&mut buf, positions);
+        }
+        buf
+    }
+
+    fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let (&version, mut buf) = buf.split_first()?;
+        if version != FORMAT_VERSION {
+            return None;
+        }
+        let uncovered = read_positions(&mut buf)?;
+        let num_entries = read_u32(&mut buf)?;
+        let mut entries = Vec::new();
+        for _ in 0..num_entries {
+            let h1 = u64::from_le_bytes(read_bytes(&mut buf, 8)?.try_into().unwrap());
+            let h2 = u64::from_le_bytes(read_bytes(&mut buf, 8)?.try_into().unwrap());
+            entries.push((ChangedPathKey(h1, h2), read_positions(&mut buf)?));
+        }
+        buf.is_empty()
+            .then_some(SegmentPathIndex { uncovered, entries })
+    }
+}
+
+fn write_positions(buf: &mut Vec<u8>, positions: &[u32]) {
+    buf.extend(u32::try_from(positions.len()).unwrap().to_le_bytes());
+    for pos in positions {
+        buf.extend(pos.to_le_bytes());
+    }
+}
+
+fn read_bytes<'a>(buf: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if buf.len() < len {
+        return None;
+    }
+    let (bytes, rest) = buf.split_at(len);
+    *buf = rest;
+    Some(bytes)
+}
+
+fn read_u32(buf: &mut &[u8]) -> Option<u32> {
+    Some(u32::from_le_bytes(read_bytes(buf, 4)?.try_into().unwrap()))
+}
+
+fn read_positions(buf: &mut &[u8]) -> Option<Vec<u32>> {
+    let len = read_u32(buf)?;
+    (0..len).map(|_| read_u32(buf)).collect()
+}
+
+/// Commits matching path keys, looked up in the indexed segments of a
+/// composite index.
+pub(super) struct ChangedPathLookup {
+    /// Position ranges of the indexed segments, and the commits in them that
+    /// aren't indexed.
+    covered: Vec<(IndexPosition, IndexPosition, HashSet<IndexPosition>)>,
+    matched: HashSet<IndexPosition>,
+}
+
+impl ChangedPathLookup {
+    pub fn new(
+        store: &ChangedPathIndexStore,
+        index: &CompositeIndex,
+        keys: &[ChangedPathKey],
+    ) -> Option<Self> {
+        let mut covered = vec![];
+        let mut matched = HashSet::new();
+        for segment in index.ancestor_index_segments() {
+            let Some(name) = segment.name() else {
+                continue;
+            };
+            let Some(segment_index) = store.read_segment(&name) else {
+                continue;
+            };
+            let base = segment.num_parent_commits();
+            let to_global = |pos: &u32| IndexPosition(base + pos);
+            for key in keys {
+                matched.extend(segment_index.lookup(*key).iter().map(to_global));
+            }
+            covered.push((
+                IndexPosition(base),
+                IndexPosition(base + segment.num_local_commits()),
+                segment_index.uncovered.iter().map(to_global).collect(),
+            ));
+        }
+        (!covered.is_empty()).then_some(ChangedPathLookup { covered, matched })
+    }
+
+    /// Returns whether the commit at `pos` might change the paths, or `None` if
+    /// the commit isn't indexed. Since directory keys match any change in the
+    /// directory, a positive answer has to be confirmed by diffing.
+    pub fn might_match(&self, pos: IndexPosition) -> Option<bool> {
+        self.covered
+            .iter()
+            .any(|(start, end, uncovered)| {
+                (*start..*end).contains(&pos) && !uncovered.contains(&pos)
+            })
+            .then(|| self.matched.contains(&pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_path(value: &str) -> &RepoPath {
+        RepoPath::from_internal_string(value)
+    }
+
+    fn key(value: &str) -> ChangedPathKey {
+        ChangedPathKey::new(repo_path(value))
+    }
+
+    #[test]
+    fn test_changed_path_keys_include_parents() {
+        let keys = changed_path_keys([repo_path("dir/sub/file"), repo_path("dir/other")]);
+        let expected = ["dir/sub/file", "dir/sub", "dir", "dir/other"].map(key);
+        assert_eq!(keys, HashSet::from(expected));
+    }
+
+    #[test]
+    fn test_keys_from_fileset() {
+        let file = || FilesetExpression::file_path(repo_path("file").to_owned());
+        let dir = || FilesetExpression::prefix_path(repo_path("dir").to_owned());
+        assert_eq!(
+            ChangedPathKey::from_fileset(&FilesetExpression::union_all(vec![file(), dir()])),
+            Some(vec![key("file"), key("dir")])
+        );
+        assert_eq!(
+            ChangedPathKey::from_fileset(&dir().difference(file())),
+            Some(vec![key("dir")])
+        );
+        assert_eq!(
+            ChangedPathKey::from_fileset(&FilesetExpression::all().intersection(file())),
+            Some(vec![key("file")])
+        );
+        assert_eq!(
+            ChangedPathKey::from_fileset(&FilesetExpression::all().difference(file())),
+            None
+        );
+        assert_eq!(
+            ChangedPathKey::from_fileset(&FilesetExpression::prefix_path(RepoPathBuf::root())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_segment_path_index_serialization() {
+        let mut entries = vec![(key("a"), vec![0, 2]), (key("b"), vec![1])];
+        entries.sort();
+        let index = SegmentPathIndex {
+            uncovered: vec![3],
+            entries,
+        };
+        let buf = index.to_bytes();
+        assert_eq!(SegmentPathIndex::from_bytes(&buf), Some(index.clone()));
+        assert_eq!(index.lookup(key("a")), &[0, 2]);
+        assert_eq!(index.lookup(key("c")), &[] as &[u32]);
+        assert_eq!(SegmentPathIndex::from_bytes(&buf[..buf.len() - 1]), None);
+    }
+}
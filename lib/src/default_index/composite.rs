@@ -28,6 +28,7 @@ use itertools::Itertools;
 use ref_cast::ref_cast_custom;
 use ref_cast::RefCastCustom;
 
+use super::changed_path_index::ChangedPathIndexStore;
 use super::entry::IndexEntry;
 use super::entry::IndexPosition;
 use super::entry::IndexPositionByGeneration;
@@ -417,7 +418,21 @@ impl CompositeIndex {
         expression: &ResolvedExpression,
         store: &Arc<Store>,
     ) -> Result<Box<dyn Revset + '_>, RevsetEvaluationError> {
-        let revset_impl = revset_engine::evaluate(expression, store, self)?;
+        self.evaluate_revset_with_changed_paths(expression, store, None)
+    }
+
+    pub(super) fn evaluate_revset_with_changed_paths(
+        &self,
+        expression: &ResolvedExpression,
+        store: &Arc<Store>,
+        changed_path_index: Option<&ChangedPathIndexStore>,
+    ) -> Result<Box<dyn Revset + '_>, RevsetEvaluationError> {
+        let revset_impl = revset_engine::evaluate_with_changed_paths(
+            expression,
+            store,
+            self,
+            changed_path_index,
+        )?;
         Ok(Box::new(revset_impl))
     }
 }
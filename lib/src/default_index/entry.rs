@@ -34,6 +34,12 @@ pub struct IndexPosition(pub(super) u32);
 impl IndexPosition {
     pub const MIN: Self = IndexPosition(u32::MIN);
     pub const MAX: Self = IndexPosition(u32::MAX);
+
+    /// Returns the position as a number, which is only meaningful within the
+    /// index the position was obtained from.
+    pub fn value(self) -> u32 {
+        self.0
+    }
 }
 
 /// Local position within an index segment.
@@ -21,6 +21,7 @@
 
 #![allow(missing_docs)]
 
+mod changed_path_index;
 mod composite;
 mod entry;
 mod mutable;
@@ -38,10 +39,13 @@ pub use self::entry::IndexEntry;
 pub use self::entry::IndexPosition;
 pub use self::mutable::DefaultMutableIndex;
 pub use self::readonly::DefaultReadonlyIndex;
+pub use self::readonly::IndexLoadMode;
 pub use self::readonly::ReadonlyIndexLoadError;
+pub use self::readonly::INDEX_SEGMENT_FILE_FORMAT_VERSION;
 pub use self::store::DefaultIndexStore;
 pub use self::store::DefaultIndexStoreError;
 pub use self::store::DefaultIndexStoreInitError;
+pub use self::store::DiffStatCounts;
 
 #[cfg(test)]
 mod tests {
@@ -55,6 +59,7 @@ mod tests {
     use super::composite::IndexSegment;
     use super::entry::SmallIndexPositionsVec;
     use super::mutable::MutableIndexSegment;
+    use super::readonly::ReadonlyIndexSegment;
     use super::*;
     use crate::backend::ChangeId;
     use crate::backend::CommitId;
@@ -342,6 +347,72 @@ mod tests {
         assert_eq!(entry_6.generation_number(), 2);
     }
 
+    #[test]
+    fn index_streaming_load() {
+        let temp_dir = testutils::new_temp_dir();
+        let mut new_commit_id = commit_id_generator();
+        let mut new_change_id = change_id_generator();
+        // Make the segment span multiple blocks, with some merges and repeated
+        // change ids stored in the overflow tables.
+        let mut mutable_segment = MutableIndexSegment::full(16, 16);
+        let mut commit_ids: Vec<CommitId> = vec![];
+        let mut change_ids: Vec<ChangeId> = vec![];
+        for i in 0..5000 {
+            let commit_id = new_commit_id();
+            let change_id = if i % 7 == 3 {
+                change_ids[i - 1].clone()
+            } else {
+                new_change_id()
+            };
+            let parents = match i {
+                0 => vec![],
+                _ if i % 11 == 0 => commit_ids[i - 5..i].to_vec(),
+                _ => vec![commit_ids[i - 1].clone()],
+            };
+            mutable_segment.add_commit_data(commit_id.clone(), change_id.clone(), &parents);
+            commit_ids.push(commit_id);
+            change_ids.push(change_id);
+        }
+        let saved_index = mutable_segment.save_in(temp_dir.path()).unwrap();
+        let load = |load_mode| {
+            ReadonlyIndexSegment::load(
+                temp_dir.path(),
+                saved_index.name().to_owned(),
+                16,
+                16,
+                load_mode,
+            )
+            .unwrap()
+        };
+        let memory_segment = load(IndexLoadMode::Memory);
+        let streaming_segment = load(IndexLoadMode::Streaming);
+        let memory_index = memory_segment.as_composite();
+        let streaming_index = streaming_segment.as_composite();
+
+        assert_eq!(streaming_index.num_commits(), memory_index.num_commits());
+        assert_eq!(
+            streaming_index.stats().num_changes,
+            memory_index.stats().num_changes
+        );
+        for (commit_id, change_id) in commit_ids.iter().zip(&change_ids) {
+            let expected = memory_index.entry_by_id(commit_id).unwrap();
+            let actual = streaming_index.entry_by_id(commit_id).unwrap();
+            assert_eq!(actual.position(), expected.position());
+            assert_eq!(actual.change_id(), *change_id);
+            assert_eq!(actual.generation_number(), expected.generation_number());
+            assert_eq!(actual.parent_positions(), expected.parent_positions());
+            assert_eq!(
+                streaming_index.resolve_neighbor_commit_ids(commit_id),
+                memory_index.resolve_neighbor_commit_ids(commit_id)
+            );
+            let prefix = HexPrefix::new(&change_id.hex()[..6]).unwrap();
+            assert_eq!(
+                streaming_index.resolve_change_id_prefix(&prefix),
+                memory_index.resolve_change_id_prefix(&prefix)
+            );
+        }
+    }
+
     #[test]
     fn resolve_commit_id_prefix() {
         let temp_dir = testutils::new_temp_dir();
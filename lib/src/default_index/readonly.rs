@@ -15,18 +15,25 @@
 #![allow(missing_docs)]
 
 use std::any::Any;
+use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fs::File;
 use std::io;
 use std::io::Read;
+use std::io::Seek as _;
+use std::io::SeekFrom;
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::Mutex;
 
+use clru::CLruCache;
+use serde::Deserialize;
 use smallvec::smallvec;
 use thiserror::Error;
 
+use super::changed_path_index::ChangedPathIndexStore;
 use super::composite::AsCompositeIndex;
 use super::composite::ChangeIdIndexImpl;
 use super::composite::CompositeIndex;
@@ -103,7 +110,47 @@ impl ReadonlyIndexLoadError {
 }
 
 /// Current format version of the index segment file.
-pub(crate) const INDEX_SEGMENT_FILE_FORMAT_VERSION: u32 = 6;
+pub const INDEX_SEGMENT_FILE_FORMAT_VERSION: u32 = 6;
+
+/// In `Auto` mode, segment files larger than this are read on demand instead
+/// of being loaded into memory.
+const AUTO_STREAMING_THRESHOLD: u64 = if cfg!(target_pointer_width = "64") {
+    4 << 30
+} else {
+    256 << 20
+};
+
+/// Size of the blocks read from segment files in streaming mode.
+const STREAMING_CHUNK_SIZE: usize = 64 << 10;
+
+/// Maximum number of blocks cached per segment file in streaming mode.
+const STREAMING_CACHE_CAPACITY: usize = 64;
+
+/// How index segment files are read.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum IndexLoadMode {
+    /// Loads segment files into memory unless they are too large for the
+    /// platform.
+    #[default]
+    Auto,
+    /// Always loads whole segment files into memory.
+    Memory,
+    /// Reads segment files on demand, keeping a bounded number of blocks in
+    /// memory. Lookups are slower, but large indexes can be used on
+    /// constrained systems.
+    Streaming,
+}
+
+impl IndexLoadMode {
+    fn should_stream(self, data_len: u64) -> bool {
+        match self {
+            IndexLoadMode::Auto => data_len > AUTO_STREAMING_THRESHOLD,
+            IndexLoadMode::Memory => false,
+            IndexLoadMode::Streaming => true,
+        }
+    }
+}
 
 /// If set, the value is stored in the overflow table.
 pub(crate) const OVERFLOW_FLAG: u32 = 0x8000_0000;
@@ -137,7 +184,7 @@ impl ChangeLocalPosition {
 }
 
 struct CommitGraphEntry<'a> {
-    data: &'a [u8],
+    data: Cow<'a, [u8]>,
 }
 
 // TODO: Add pointers to ancestors further back, like a skip list. Clear the
@@ -173,6 +220,120 @@ impl CommitGraphEntry<'_> {
     }
 }
 
+/// Number of entries in the tables of an index segment.
+struct SegmentCounts {
+    num_local_commits: u32,
+    num_local_change_ids: u32,
+    num_parent_overflow_entries: u32,
+    num_change_overflow_entries: u32,
+}
+
+impl SegmentCounts {
+    fn read_from(file: &mut dyn Read) -> io::Result<Self> {
+        let mut read_u32 = || {
+            let mut buf = [0; 4];
+            file.read_exact(&mut buf)?;
+            Ok::<_, io::Error>(u32::from_le_bytes(buf))
+        };
+        Ok(SegmentCounts {
+            num_local_commits: read_u32()?,
+            num_local_change_ids: read_u32()?,
+            num_parent_overflow_entries: read_u32()?,
+            num_change_overflow_entries: read_u32()?,
+        })
+    }
+}
+
+/// Local entries of an index segment.
+enum SegmentData {
+    InMemory(Vec<u8>),
+    Streaming(StreamingSegmentData),
+}
+
+impl SegmentData {
+    fn len(&self) -> usize {
+        match self {
+            SegmentData::InMemory(data) => data.len(),
+            SegmentData::Streaming(data) => data.len,
+        }
+    }
+
+    fn bytes(&self, offset: usize, len: usize) -> Cow<'_, [u8]> {
+        match self {
+            SegmentData::InMemory(data) => Cow::Borrowed(&data[offset..][..len]),
+            SegmentData::Streaming(data) => Cow::Owned(data.read(offset, len)),
+        }
+    }
+
+    fn read_u32(&self, offset: usize) -> u32 {
+        u32::from_le_bytes(self.bytes(offset, 4).as_ref().try_into().unwrap())
+    }
+}
+
+/// Local entries of an index segment which are read from the file on demand.
+///
+/// Recently used blocks are cached. Since segment files are immutable, a read
+/// error after the file has been opened is unexpected, and causes a panic.
+struct StreamingSegmentData {
+    name: String,
+    len: usize,
+    state: Mutex<StreamingSegmentState>,
+}
+
+struct StreamingSegmentState {
+    file: File,
+    base_offset: u64,
+    cache: CLruCache<usize, Arc<[u8]>>,
+}
+
+impl StreamingSegmentData {
+    fn new(name: String, file: File, base_offset: u64, len: usize) -> Self {
+        let state = StreamingSegmentState {
+            file,
+            base_offset,
+            cache: CLruCache::new(STREAMING_CACHE_CAPACITY.try_into().unwrap()),
+        };
+        StreamingSegmentData {
+            name,
+            len,
+            state: Mutex::new(state),
+        }
+    }
+
+    fn read(&self, offset: usize, len: usize) -> Vec<u8> {
+        assert!(offset + len <= self.len, "read out of index segment bounds");
+        let mut state = self.state.lock().unwrap();
+        let mut buf = Vec::with_capacity(len);
+        let mut pos = offset;
+        while buf.len() < len {
+            let chunk = self.chunk(&mut state, pos / STREAMING_CHUNK_SIZE);
+            let start = pos % STREAMING_CHUNK_SIZE;
+            let end = chunk.len().min(start + len - buf.len());
+            buf.extend_from_slice(&chunk[start..end]);
+            pos += end - start;
+        }
+        buf
+    }
+
+    fn chunk(&self, state: &mut StreamingSegmentState, index: usize) -> Arc<[u8]> {
+        if let Some(chunk) = state.cache.get(&index) {
+            return chunk.clone();
+        }
+        let start = index * STREAMING_CHUNK_SIZE;
+        let mut chunk = vec![0; STREAMING_CHUNK_SIZE.min(self.len - start)];
+        state
+            .file
+            .seek(SeekFrom::Start(state.base_offset + start as u64))
+            .and_then(|_| state.file.read_exact(&mut chunk))
+            .unwrap_or_else(|err| {
+                panic!("failed to read commit index file '{}': {err}", self.name)
+            });
+        let chunk: Arc<[u8]> = chunk.into();
+        state.cache.put(index, chunk.clone());
+        chunk
+    }
+}
+
 /// Commit index segment backed by immutable file.
 ///
 /// File format:
@@ -233,7 +394,7 @@ pub(super) struct ReadonlyIndexSegment {
     change_pos_table_base: usize,
     parent_overflow_base: usize,
     change_overflow_base: usize,
-    data: Vec<u8>,
+    data: SegmentData,
 }
 
 impl Debug for ReadonlyIndexSegment {
@@ -252,19 +413,28 @@ impl ReadonlyIndexSegment {
         name: String,
         commit_id_length: usize,
         change_id_length: usize,
+        load_mode: IndexLoadMode,
     ) -> Result<Arc<ReadonlyIndexSegment>, ReadonlyIndexLoadError> {
-        let mut file = File::open(dir.join(&name))
+        let file = File::open(dir.join(&name))
             .map_err(|err| ReadonlyIndexLoadError::from_io_err(&name, err))?;
-        Self::load_from(&mut file, dir, name, commit_id_length, change_id_length)
+        Self::load_from(
+            file,
+            dir,
+            name,
+            commit_id_length,
+            change_id_length,
+            load_mode,
+        )
     }
 
     /// Loads both parent segments and local entries from the given `file`.
-    pub(super) fn load_from(
-        file: &mut dyn Read,
+    fn load_from(
+        mut file: File,
         dir: &Path,
         name: String,
         commit_id_length: usize,
         change_id_length: usize,
+        load_mode: IndexLoadMode,
     ) -> Result<Arc<ReadonlyIndexSegment>, ReadonlyIndexLoadError> {
         let from_io_err = |err| ReadonlyIndexLoadError::from_io_err(&name, err);
         let read_u32 = |file: &mut dyn Read| {
@@ -272,14 +442,14 @@ impl ReadonlyIndexSegment {
             file.read_exact(&mut buf).map_err(from_io_err)?;
             Ok(u32::from_le_bytes(buf))
         };
-        let format_version = read_u32(file)?;
+        let format_version = read_u32(&mut file)?;
         if format_version != INDEX_SEGMENT_FILE_FORMAT_VERSION {
             return Err(ReadonlyIndexLoadError::UnexpectedVersion {
                 found_version: format_version,
                 expected_version: INDEX_SEGMENT_FILE_FORMAT_VERSION,
             });
         }
-        let parent_filename_len = read_u32(file)?;
+        let parent_filename_len = read_u32(&mut file)?;
         let maybe_parent_file = if parent_filename_len > 0 {
             let mut parent_filename_bytes = vec![0; parent_filename_len as usize];
             file.read_exact(&mut parent_filename_bytes)
@@ -292,18 +462,31 @@ impl ReadonlyIndexSegment {
                 parent_filename,
                 commit_id_length,
                 change_id_length,
+                load_mode,
             )?;
             Some(parent_file)
         } else {
             None
         };
-        Self::load_with_parent_file(
-            file,
-            name,
-            maybe_parent_file,
-            commit_id_length,
-            change_id_length,
-        )
+        let file_len = file.metadata().map_err(from_io_err)?.len();
+        let entries_offset = file.stream_position().map_err(from_io_err)?;
+        if load_mode.should_stream(file_len.saturating_sub(entries_offset)) {
+            Self::load_streaming_with_parent_file(
+                file,
+                name,
+                maybe_parent_file,
+                commit_id_length,
+                change_id_length,
+            )
+        } else {
+            Self::load_with_parent_file(
+                &mut file,
+                name,
+                maybe_parent_file,
+                commit_id_length,
+                change_id_length,
+            )
+        }
     }
 
     /// Loads local entries from the given `file`, returns new segment linked to
@@ -316,20 +499,62 @@ impl ReadonlyIndexSegment {
         change_id_length: usize,
     ) -> Result<Arc<ReadonlyIndexSegment>, ReadonlyIndexLoadError> {
         let from_io_err = |err| ReadonlyIndexLoadError::from_io_err(&name, err);
-        let read_u32 = |file: &mut dyn Read| {
-            let mut buf = [0; 4];
-            file.read_exact(&mut buf).map_err(from_io_err)?;
-            Ok(u32::from_le_bytes(buf))
-        };
+        let counts = SegmentCounts::read_from(file).map_err(from_io_err)?;
+        let mut data = vec![];
+        file.read_to_end(&mut data).map_err(from_io_err)?;
+        Self::from_data(
+            name,
+            parent_file,
+            commit_id_length,
+            change_id_length,
+            counts,
+            SegmentData::InMemory(data),
+        )
+    }
+
+    /// Opens local entries of the given `file` to be read on demand, returns
+    /// new segment linked to the given `parent_file`.
+    fn load_streaming_with_parent_file(
+        mut file: File,
+        name: String,
+        parent_file: Option<Arc<ReadonlyIndexSegment>>,
+        commit_id_length: usize,
+        change_id_length: usize,
+    ) -> Result<Arc<ReadonlyIndexSegment>, ReadonlyIndexLoadError> {
+        let from_io_err = |err| ReadonlyIndexLoadError::from_io_err(&name, err);
+        let counts = SegmentCounts::read_from(&mut file).map_err(from_io_err)?;
+        let base_offset = file.stream_position().map_err(from_io_err)?;
+        let file_len = file.metadata().map_err(from_io_err)?.len();
+        let len = usize::try_from(file_len.saturating_sub(base_offset))
+            .map_err(|_| ReadonlyIndexLoadError::invalid_data(&name, "index file is too large"))?;
+        let data = StreamingSegmentData::new(name.clone(), file, base_offset, len);
+        Self::from_data(
+            name,
+            parent_file,
+            commit_id_length,
+            change_id_length,
+            counts,
+            SegmentData::Streaming(data),
+        )
+    }
+
+    fn from_data(
+        name: String,
+        parent_file: Option<Arc<ReadonlyIndexSegment>>,
+        commit_id_length: usize,
+        change_id_length: usize,
+        counts: SegmentCounts,
+        data: SegmentData,
+    ) -> Result<Arc<ReadonlyIndexSegment>, ReadonlyIndexLoadError> {
+        let SegmentCounts {
+            num_local_commits,
+            num_local_change_ids,
+            num_parent_overflow_entries,
+            num_change_overflow_entries,
+        } = counts;
         let num_parent_commits = parent_file
             .as_ref()
             .map_or(0, |segment| segment.as_composite().num_commits());
-        let num_local_commits = read_u32(file)?;
-        let num_local_change_ids = read_u32(file)?;
-        let num_parent_overflow_entries = read_u32(file)?;
-        let num_change_overflow_entries = read_u32(file)?;
-        let mut data = vec![];
-        file.read_to_end(&mut data).map_err(from_io_err)?;
 
         let commit_graph_entry_size = CommitGraphEntry::size(commit_id_length);
         let graph_size = (num_local_commits as usize) * commit_graph_entry_size;
@@ -389,42 +614,47 @@ impl ReadonlyIndexSegment {
     }
 
     fn graph_entry(&self, local_pos: LocalPosition) -> CommitGraphEntry {
-        let table = &self.data[..self.commit_lookup_base];
         let entry_size = CommitGraphEntry::size(self.commit_id_length);
         let offset = (local_pos.0 as usize) * entry_size;
+        assert!(offset + entry_size <= self.commit_lookup_base);
         CommitGraphEntry {
-            data: &table[offset..][..entry_size],
+            data: self.data.bytes(offset, entry_size),
         }
     }
 
     fn commit_lookup_pos(&self, lookup_pos: u32) -> LocalPosition {
-        let table = &self.data[self.commit_lookup_base..self.change_id_table_base];
         let offset = (lookup_pos as usize) * 4;
-        LocalPosition(u32::from_le_bytes(table[offset..][..4].try_into().unwrap()))
+        assert!(offset + 4 <= self.change_id_table_base - self.commit_lookup_base);
+        LocalPosition(self.data.read_u32(self.commit_lookup_base + offset))
     }
 
     fn change_lookup_id(&self, lookup_pos: u32) -> ChangeId {
-        ChangeId::from_bytes(self.change_lookup_id_bytes(lookup_pos))
+        ChangeId::from_bytes(&self.change_lookup_id_bytes(lookup_pos))
     }
 
     // might be better to add borrowed version of ChangeId
-    fn change_lookup_id_bytes(&self, lookup_pos: u32) -> &[u8] {
-        let table = &self.data[self.change_id_table_base..self.change_pos_table_base];
+    fn change_lookup_id_bytes(&self, lookup_pos: u32) -> Cow<'_, [u8]> {
         let offset = (lookup_pos as usize) * self.change_id_length;
-        &table[offset..][..self.change_id_length]
+        assert!(
+            offset + self.change_id_length
+                <= self.change_pos_table_base - self.change_id_table_base
+        );
+        self.data
+            .bytes(self.change_id_table_base + offset, self.change_id_length)
     }
 
     fn change_lookup_pos(&self, lookup_pos: u32) -> ChangeLocalPosition {
-        let table = &self.data[self.change_pos_table_base..self.parent_overflow_base];
         let offset = (lookup_pos as usize) * 4;
-        ChangeLocalPosition(u32::from_le_bytes(table[offset..][..4].try_into().unwrap()))
+        assert!(offset + 4 <= self.parent_overflow_base - self.change_pos_table_base);
+        ChangeLocalPosition(self.data.read_u32(self.change_pos_table_base + offset))
     }
 
     fn overflow_parents(&self, overflow_pos: u32, num_parents: u32) -> SmallIndexPositionsVec {
-        let table = &self.data[self.parent_overflow_base..self.change_overflow_base];
         let offset = (overflow_pos as usize) * 4;
         let size = (num_parents as usize) * 4;
-        table[offset..][..size]
+        assert!(offset + size <= self.change_overflow_base - self.parent_overflow_base);
+        self.data
+            .bytes(self.parent_overflow_base + offset, size)
             .chunks_exact(4)
             .map(|chunk| IndexPosition(u32::from_le_bytes(chunk.try_into().unwrap())))
             .collect()
@@ -432,11 +662,10 @@ impl ReadonlyIndexSegment {
 
     /// Scans graph entry positions stored in the overflow change ids table.
     fn overflow_changes_from(&self, overflow_pos: u32) -> impl Iterator<Item = LocalPosition> + '_ {
-        let table = &self.data[self.change_overflow_base..];
-        let offset = (overflow_pos as usize) * 4;
-        table[offset..]
-            .chunks_exact(4)
-            .map(|chunk| LocalPosition(u32::from_le_bytes(chunk.try_into().unwrap())))
+        (overflow_pos..self.num_change_overflow_entries).map(|pos| {
+            let offset = self.change_overflow_base + (pos as usize) * 4;
+            LocalPosition(self.data.read_u32(offset))
+        })
     }
 
     /// Binary searches commit id by `prefix`. Returns the lookup position.
@@ -452,7 +681,7 @@ impl ReadonlyIndexSegment {
     fn change_id_byte_prefix_to_lookup_pos(&self, prefix: &[u8]) -> PositionLookupResult {
         binary_search_pos_by(self.num_local_change_ids, |pos| {
             let change_id_bytes = self.change_lookup_id_bytes(pos);
-            change_id_bytes.cmp(prefix)
+            change_id_bytes.as_ref().cmp(prefix)
         })
     }
 }
@@ -587,21 +816,38 @@ impl IndexSegment for ReadonlyIndexSegment {
 
 /// Commit index backend which stores data on local disk.
 #[derive(Clone, Debug)]
-pub struct DefaultReadonlyIndex(Arc<ReadonlyIndexSegment>);
+pub struct DefaultReadonlyIndex {
+    segment: Arc<ReadonlyIndexSegment>,
+    changed_path_index: Option<ChangedPathIndexStore>,
+}
 
 impl DefaultReadonlyIndex {
     pub(super) fn from_segment(segment: Arc<ReadonlyIndexSegment>) -> Self {
-        DefaultReadonlyIndex(segment)
+        DefaultReadonlyIndex {
+            segment,
+            changed_path_index: None,
+        }
+    }
+
+    /// Sets the changed-path index used when evaluating revsets.
+    pub(super) fn with_changed_path_index(
+        self,
+        changed_path_index: Option<ChangedPathIndexStore>,
+    ) -> Self {
+        DefaultReadonlyIndex {
+            changed_path_index,
+            ..self
+        }
     }
 
     pub(super) fn as_segment(&self) -> &Arc<ReadonlyIndexSegment> {
-        &self.0
+        &self.segment
     }
 }
 
 impl AsCompositeIndex for DefaultReadonlyIndex {
     fn as_composite(&self) -> &CompositeIndex {
-        self.0.as_composite()
+        self.segment.as_composite()
     }
 }
 
@@ -642,7 +888,11 @@ impl Index for DefaultReadonlyIndex {
         expression: &ResolvedExpression,
         store: &Arc<Store>,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
-        self.as_composite().evaluate_revset(expression, store)
+        self.as_composite().evaluate_revset_with_changed_paths(
+            expression,
+            store,
+            self.changed_path_index.as_ref(),
+        )
     }
 }
 
@@ -663,7 +913,7 @@ impl ReadonlyIndex for DefaultReadonlyIndex {
     }
 
     fn start_modification(&self) -> Box<dyn MutableIndex> {
-        Box::new(DefaultMutableIndex::incremental(self.0.clone()))
+        Box::new(DefaultMutableIndex::incremental(self.segment.clone()))
     }
 }
 
@@ -31,6 +31,9 @@ use futures::StreamExt as _;
 use itertools::Itertools;
 use pollster::FutureExt as _;
 
+use super::changed_path_index::ChangedPathIndexStore;
+use super::changed_path_index::ChangedPathKey;
+use super::changed_path_index::ChangedPathLookup;
 use super::rev_walk::EagerRevWalk;
 use super::rev_walk::PeekableRevWalk;
 use super::rev_walk::RevWalk;
@@ -765,18 +768,213 @@ pub fn evaluate<I: AsCompositeIndex + Clone>(
     expression: &ResolvedExpression,
     store: &Arc<Store>,
     index: I,
+) -> Result<RevsetImpl<I>, RevsetEvaluationError> {
+    evaluate_with_changed_paths(expression, store, index, None)
+}
+
+/// Like [`evaluate()`], but skips commits which the changed-path index tells
+/// don't match path predicates.
+pub(super) fn evaluate_with_changed_paths<I: AsCompositeIndex + Clone>(
+    expression: &ResolvedExpression,
+    store: &Arc<Store>,
+    index: I,
+    changed_path_index: Option<&ChangedPathIndexStore>,
 ) -> Result<RevsetImpl<I>, RevsetEvaluationError> {
     let context = EvaluationContext {
         store: store.clone(),
         index: index.as_composite(),
+        changed_path_index,
     };
     let internal_revset = context.evaluate(expression)?;
     Ok(RevsetImpl::new(internal_revset, index))
 }
 
+/// Describes how [`evaluate()`] will evaluate the `expression`.
+///
+/// Each line of the returned text is a node of the expression tree, indented
+/// by its depth, followed by the strategy used to evaluate the node.
+pub fn explain(expression: &ResolvedExpression) -> String {
+    let mut out = String::new();
+    explain_expression(&mut out, expression, 0);
+    out
+}
+
+fn explain_expression(out: &mut String, expression: &ResolvedExpression, depth: usize) {
+    let mut node = |label: &str, strategy: &str| {
+        out.push_str(&"  ".repeat(depth));
+        out.push_str(label);
+        out.push_str(": ");
+        out.push_str(strategy);
+        out.push('\n');
+    };
+    let with_generation = |label: &str, generation: &Range<u64>| {
+        if generation == &GENERATION_RANGE_FULL {
+            label.to_owned()
+        } else {
+            format!("{label} (generation {generation:?})")
+        }
+    };
+    match expression {
+        ResolvedExpression::Commits(commit_ids) => {
+            let label = format!("Commits ({} ids)", commit_ids.len());
+            node(&label, "sorted list of index positions");
+        }
+        ResolvedExpression::Ancestors { heads, generation } => {
+            let label = with_generation("Ancestors", generation);
+            if generation == &GENERATION_RANGE_FULL {
+                node(&label, "lazy ancestor walk from heads");
+            } else {
+                node(
+                    &label,
+                    "lazy ancestor walk from heads, filtered by generation",
+                );
+            }
+            explain_expression(out, heads, depth + 1);
+        }
+        ResolvedExpression::Range {
+            roots,
+            heads,
+            generation,
+        } => {
+            let label = with_generation("Range", generation);
+            node(
+                &label,
+                "lazy ancestor walk from heads, stopped at ancestors of roots",
+            );
+            explain_expression(out, roots, depth + 1);
+            explain_expression(out, heads, depth + 1);
+        }
+        ResolvedExpression::DagRange {
+            roots,
+            heads,
+            generation_from_roots,
+        } => {
+            let label = with_generation("DagRange", generation_from_roots);
+            if generation_from_roots == &(1..2) {
+                node(
+                    &label,
+                    "lazy ancestor walk from heads until roots, filtered by parents in roots",
+                );
+            } else if generation_from_roots == &GENERATION_RANGE_FULL {
+                node(
+                    &label,
+                    "eager descendant walk from roots within ancestors of heads",
+                );
+            } else {
+                node(
+                    &label,
+                    "eager descendant walk from roots within ancestors of heads, filtered by \
+                     generation",
+                );
+            }
+            explain_expression(out, roots, depth + 1);
+            explain_expression(out, heads, depth + 1);
+        }
+        ResolvedExpression::Reachable { sources, domain } => {
+            node("Reachable", "union-find of connected commits within domain");
+            explain_expression(out, sources, depth + 1);
+            explain_expression(out, domain, depth + 1);
+        }
+        ResolvedExpression::Heads(candidates) => {
+            node("Heads", "eager head computation over candidates");
+            explain_expression(out, candidates, depth + 1);
+        }
+        ResolvedExpression::Roots(candidates) => {
+            node(
+                "Roots",
+                "eager descendant walk from candidates, keeping those without parents in it",
+            );
+            explain_expression(out, candidates, depth + 1);
+        }
+        ResolvedExpression::ForkPoint(expression) => {
+            node("ForkPoint", "eager common ancestor computation");
+            explain_expression(out, expression, depth + 1);
+        }
+        ResolvedExpression::Latest { candidates, count } => {
+            let label = format!("Latest (count {count})");
+            node(&label, "eager scan of candidates by committer timestamp");
+            explain_expression(out, candidates, depth + 1);
+        }
+        ResolvedExpression::Coalesce(expression1, expression2) => {
+            node("Coalesce", "first non-empty set");
+            explain_expression(out, expression1, depth + 1);
+            explain_expression(out, expression2, depth + 1);
+        }
+        ResolvedExpression::Union(expression1, expression2) => {
+            node("Union", "lazy merge of sorted positions");
+            explain_expression(out, expression1, depth + 1);
+            explain_expression(out, expression2, depth + 1);
+        }
+        ResolvedExpression::FilterWithin {
+            candidates,
+            predicate,
+        } => {
+            node("FilterWithin", "lazy filter of candidates by predicate");
+            explain_expression(out, candidates, depth + 1);
+            explain_predicate(out, predicate, depth + 1);
+        }
+        ResolvedExpression::Intersection(expression1, expression2) => {
+            node("Intersection", "lazy merge of sorted positions");
+            explain_expression(out, expression1, depth + 1);
+            explain_expression(out, expression2, depth + 1);
+        }
+        ResolvedExpression::Difference(expression1, expression2) => {
+            node("Difference", "lazy merge of sorted positions");
+            explain_expression(out, expression1, depth + 1);
+            explain_expression(out, expression2, depth + 1);
+        }
+    }
+}
+
+fn explain_predicate(out: &mut String, expression: &ResolvedPredicateExpression, depth: usize) {
+    let mut node = |label: &str, strategy: &str| {
+        out.push_str(&"  ".repeat(depth));
+        out.push_str(label);
+        out.push_str(": ");
+        out.push_str(strategy);
+        out.push('\n');
+    };
+    match expression {
+        ResolvedPredicateExpression::Filter(predicate) => {
+            let (label, strategy) = match predicate {
+                RevsetFilterPredicate::ParentCount(_) => ("ParentCount", "index lookup"),
+                RevsetFilterPredicate::Description(_) => ("Description", "reads each commit"),
+                RevsetFilterPredicate::Author(_) => ("Author", "reads each commit"),
+                RevsetFilterPredicate::Committer(_) => ("Committer", "reads each commit"),
+                RevsetFilterPredicate::AuthorDate(_) => ("AuthorDate", "reads each commit"),
+                RevsetFilterPredicate::CommitterDate(_) => ("CommitterDate", "reads each commit"),
+                RevsetFilterPredicate::File(_) => (
+                    "File",
+                    "changed-path index if available, then diffs each commit",
+                ),
+                RevsetFilterPredicate::DiffContains { .. } => {
+                    ("DiffContains", "diffs file contents of each commit")
+                }
+                RevsetFilterPredicate::HasConflict => ("HasConflict", "reads each commit"),
+                RevsetFilterPredicate::Extension(_) => ("Extension", "reads each commit"),
+            };
+            node(&format!("Filter {label}"), strategy);
+        }
+        ResolvedPredicateExpression::Set(expression) => {
+            node("Set", "evaluated as set, then tested by position");
+            explain_expression(out, expression, depth + 1);
+        }
+        ResolvedPredicateExpression::NotIn(complement) => {
+            node("NotIn", "negated predicate");
+            explain_predicate(out, complement, depth + 1);
+        }
+        ResolvedPredicateExpression::Union(expression1, expression2) => {
+            node("Union", "either predicate");
+            explain_predicate(out, expression1, depth + 1);
+            explain_predicate(out, expression2, depth + 1);
+        }
+    }
+}
+
 struct EvaluationContext<'index> {
     store: Arc<Store>,
     index: &'index CompositeIndex,
+    changed_path_index: Option<&'index ChangedPathIndexStore>,
 }
 
 fn to_u32_generation_range(range: &Range<u64>) -> Result<Range<u32>, RevsetEvaluationError> {
@@ -1012,9 +1210,12 @@ impl EvaluationContext<'_> {
         expression: &ResolvedPredicateExpression,
     ) -> Result<Box<dyn ToPredicateFn>, RevsetEvaluationError> {
         match expression {
-            ResolvedPredicateExpression::Filter(predicate) => {
-                Ok(build_predicate_fn(self.store.clone(), predicate))
-            }
+            ResolvedPredicateExpression::Filter(predicate) => Ok(build_predicate_fn(
+                self.store.clone(),
+                self.index,
+                self.changed_path_index,
+                predicate,
+            )),
             ResolvedPredicateExpression::Set(expression) => {
                 Ok(self.evaluate(expression)?.into_predicate())
             }
@@ -1143,6 +1344,8 @@ fn box_pure_predicate_fn<'a>(
 
 fn build_predicate_fn(
     store: Arc<Store>,
+    index: &CompositeIndex,
+    changed_path_index: Option<&ChangedPathIndexStore>,
     predicate: &RevsetFilterPredicate,
 ) -> Box<dyn ToPredicateFn> {
     match predicate {
@@ -1201,7 +1404,14 @@ fn build_predicate_fn(
         }
         RevsetFilterPredicate::File(expr) => {
             let matcher: Rc<dyn Matcher> = expr.to_matcher().into();
+            let lookup = changed_path_index
+                .zip(ChangedPathKey::from_fileset(expr))
+                .and_then(|(path_index, keys)| ChangedPathLookup::new(path_index, index, &keys))
+                .map(Rc::new);
             box_pure_predicate_fn(move |index, pos| {
+                if lookup.as_ref().and_then(|lookup| lookup.might_match(pos)) == Some(false) {
+                    return Ok(false);
+                }
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 Ok(has_diff_from_parent(&store, index, &commit, &*matcher)?)
@@ -1680,4 +1890,27 @@ mod tests {
 
         assert!(positions_accum.contains(&id_1).unwrap());
     }
+
+    #[test]
+    fn test_explain() {
+        let commits = || Box::new(ResolvedExpression::Commits(vec![CommitId::from_hex("01")]));
+        let expression = ResolvedExpression::FilterWithin {
+            candidates: Box::new(ResolvedExpression::DagRange {
+                roots: commits(),
+                heads: commits(),
+                generation_from_roots: 1..2,
+            }),
+            predicate: ResolvedPredicateExpression::NotIn(Box::new(
+                ResolvedPredicateExpression::Filter(RevsetFilterPredicate::HasConflict),
+            )),
+        };
+        insta::assert_snapshot!(explain(&expression), @r"
+        FilterWithin: lazy filter of candidates by predicate
+          DagRange (generation 1..2): lazy ancestor walk from heads until roots, filtered by parents in roots
+            Commits (1 ids): sorted list of index positions
+            Commits (1 ids): sorted list of index positions
+          NotIn: negated predicate
+            Filter HasConflict: reads each commit
+        ");
+    }
 }
@@ -19,6 +19,7 @@ use std::collections::HashSet;
 use std::fs;
 use std::io;
 use std::io::Write;
+use std::iter;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -27,14 +28,19 @@ use itertools::Itertools;
 use tempfile::NamedTempFile;
 use thiserror::Error;
 
+use super::changed_path_index::ChangedPathIndexStore;
+use super::composite::AsCompositeIndex as _;
+use super::composite::IndexSegment as _;
 use super::mutable::DefaultMutableIndex;
 use super::readonly::DefaultReadonlyIndex;
+use super::readonly::IndexLoadMode;
 use super::readonly::ReadonlyIndexLoadError;
 use super::readonly::ReadonlyIndexSegment;
 use crate::backend::BackendError;
 use crate::backend::BackendInitError;
 use crate::backend::CommitId;
 use crate::commit::CommitByCommitterTimestamp;
+use crate::conflicts::ConflictMarkerStyle;
 use crate::dag_walk;
 use crate::file_util;
 use crate::file_util::persist_content_addressed_temp_file;
@@ -84,13 +90,60 @@ pub enum DefaultIndexStoreError {
         op_id: OperationId,
         source: BackendError,
     },
+    #[error("Failed to index changed paths")]
+    IndexChangedPaths(#[source] BackendError),
+    #[error("Failed to enable changed-path index")]
+    EnableChangedPathIndex(#[source] io::Error),
     #[error(transparent)]
     OpStore(#[from] OpStoreError),
 }
 
+/// Number of changed files and lines in a commit compared to its parents.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DiffStatCounts {
+    pub files: u64,
+    pub added: u64,
+    pub removed: u64,
+}
+
+impl DiffStatCounts {
+    /// Bump this if the way the stats are counted changes. Entries of other
+    /// versions are ignored.
+    const FORMAT_VERSION: u8 = 1;
+    const SERIALIZED_LEN: usize = 1 + 3 * 8;
+
+    fn to_bytes(self) -> [u8; Self::SERIALIZED_LEN] {
+        let mut buf = [0; Self::SERIALIZED_LEN];
+        buf[0] = Self::FORMAT_VERSION;
+        for (chunk, value) in
+            buf[1..]
+                .chunks_exact_mut(8)
+                .zip([self.files, self.added, self.removed])
+        {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        buf
+    }
+
+    fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let (&version, buf) = buf.split_first()?;
+        if version != Self::FORMAT_VERSION || buf.len() != Self::SERIALIZED_LEN - 1 {
+            return None;
+        }
+        let [files, added, removed] =
+            [0, 1, 2].map(|i| u64::from_le_bytes(buf[i * 8..(i + 1) * 8].try_into().unwrap()));
+        Some(DiffStatCounts {
+            files,
+            added,
+            removed,
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct DefaultIndexStore {
     dir: PathBuf,
+    load_mode: IndexLoadMode,
 }
 
 impl DefaultIndexStore {
@@ -101,6 +154,7 @@ impl DefaultIndexStore {
     pub fn init(dir: &Path) -> Result<Self, DefaultIndexStoreInitError> {
         let store = DefaultIndexStore {
             dir: dir.to_owned(),
+            load_mode: IndexLoadMode::default(),
         };
         store.ensure_base_dirs()?;
         Ok(store)
@@ -109,9 +163,15 @@ impl DefaultIndexStore {
     pub fn load(dir: &Path) -> DefaultIndexStore {
         DefaultIndexStore {
             dir: dir.to_owned(),
+            load_mode: IndexLoadMode::default(),
         }
     }
 
+    /// Sets how index segment files are read.
+    pub fn with_load_mode(self, load_mode: IndexLoadMode) -> Self {
+        DefaultIndexStore { load_mode, ..self }
+    }
+
     pub fn reinit(&self) -> Result<(), DefaultIndexStoreInitError> {
         // Create base directories in case the store was initialized by old jj.
         self.ensure_base_dirs()?;
@@ -120,6 +180,12 @@ impl DefaultIndexStore {
         // Remove index segments to save disk space. If raced, new segment file
         // will be created by the other process.
         file_util::remove_dir_contents(&self.segments_dir())?;
+        // The changed-path index refers to the removed segments. It'll be
+        // rebuilt along with the index if enabled.
+        let path_index_dir = self.changed_path_index().dir().to_owned();
+        if path_index_dir.is_dir() {
+            file_util::remove_dir_contents(&path_index_dir)?;
+        }
         // jj <= 0.14 created segment files in the top directory
         for entry in self.dir.read_dir().context(&self.dir)? {
             let entry = entry.context(&self.dir)?;
@@ -134,7 +200,11 @@ impl DefaultIndexStore {
     }
 
     fn ensure_base_dirs(&self) -> Result<(), PathError> {
-        for dir in [self.operations_dir(), self.segments_dir()] {
+        for dir in [
+            self.operations_dir(),
+            self.segments_dir(),
+            self.diff_stats_dir(),
+        ] {
             file_util::create_or_reuse_dir(&dir).context(&dir)?;
         }
         Ok(())
@@ -148,6 +218,87 @@ impl DefaultIndexStore {
         self.dir.join("segments")
     }
 
+    fn diff_stats_dir(&self) -> PathBuf {
+        self.dir.join("diff_stats")
+    }
+
+    fn changed_path_index(&self) -> ChangedPathIndexStore {
+        ChangedPathIndexStore::new(self.dir.join("changed_path_index"))
+    }
+
+    fn enabled_changed_path_index(&self) -> Option<ChangedPathIndexStore> {
+        let index = self.changed_path_index();
+        index.is_enabled().then_some(index)
+    }
+
+    /// Builds the changed-path index of the commits in `index`, and keeps it
+    /// up to date as new commits are indexed. Returns the number of the
+    /// commits diffed.
+    pub fn build_changed_path_index(
+        &self,
+        index: &DefaultReadonlyIndex,
+        store: &Arc<Store>,
+    ) -> Result<usize, DefaultIndexStoreError> {
+        let path_index = self.changed_path_index();
+        path_index
+            .enable()
+            .map_err(DefaultIndexStoreError::EnableChangedPathIndex)?;
+        let mut num_diffed = 0;
+        for segment in iter::successors(Some(index.as_segment()), |segment| segment.parent_file()) {
+            num_diffed += path_index
+                .update_segment(store, segment, &[])
+                .map_err(DefaultIndexStoreError::IndexChangedPaths)?;
+        }
+        Ok(num_diffed)
+    }
+
+    /// Path to the cached diff stats of the commit. Since conflicts are
+    /// counted in their materialized form, the stats are cached per conflict
+    /// marker style.
+    fn diff_stats_path(&self, commit_id: &CommitId, style: ConflictMarkerStyle) -> PathBuf {
+        let style_name = match style {
+            ConflictMarkerStyle::Diff => "diff",
+            ConflictMarkerStyle::Snapshot => "snapshot",
+            ConflictMarkerStyle::Git => "git",
+        };
+        self.diff_stats_dir()
+            .join(format!("{}-{style_name}", commit_id.hex()))
+    }
+
+    /// Looks up the cached diff stats of the given commit.
+    ///
+    /// Since commits are immutable, the cached entries never need to be
+    /// invalidated. Unreadable entries and entries written by other versions
+    /// are treated as missing.
+    pub fn read_diff_stats(
+        &self,
+        commit_id: &CommitId,
+        style: ConflictMarkerStyle,
+    ) -> Option<DiffStatCounts> {
+        let buf = fs::read(self.diff_stats_path(commit_id, style)).ok()?;
+        DiffStatCounts::from_bytes(&buf)
+    }
+
+    /// Caches the diff stats of the given commit.
+    pub fn write_diff_stats(
+        &self,
+        commit_id: &CommitId,
+        style: ConflictMarkerStyle,
+        counts: &DiffStatCounts,
+    ) -> Result<(), PathError> {
+        let dir = self.diff_stats_dir();
+        // The directory may be missing if the store was initialized by old jj.
+        file_util::create_or_reuse_dir(&dir).context(&dir)?;
+        let path = self.diff_stats_path(commit_id, style);
+        let mut temp_file = NamedTempFile::new_in(&dir).context(&dir)?;
+        temp_file
+            .as_file_mut()
+            .write_all(&counts.to_bytes())
+            .context(temp_file.path())?;
+        persist_content_addressed_temp_file(temp_file, &path).context(&path)?;
+        Ok(())
+    }
+
     fn load_index_segments_at_operation(
         &self,
         op_id: &OperationId,
@@ -162,6 +313,7 @@ impl DefaultIndexStore {
             index_file_id_hex,
             commit_id_length,
             change_id_length,
+            self.load_mode,
         )
         .map_err(DefaultIndexStoreError::LoadIndex)
     }
@@ -176,7 +328,8 @@ impl DefaultIndexStore {
         store: &Arc<Store>,
     ) -> Result<DefaultReadonlyIndex, DefaultIndexStoreError> {
         let index_segment = self.build_index_segments_at_operation(operation, store)?;
-        Ok(DefaultReadonlyIndex::from_segment(index_segment))
+        Ok(DefaultReadonlyIndex::from_segment(index_segment)
+            .with_changed_path_index(self.enabled_changed_path_index()))
     }
 
     #[tracing::instrument(skip(self, store))]
@@ -273,7 +426,7 @@ impl DefaultIndexStore {
             mutable_index.add_commit(commit);
         }
 
-        let index_file = self.save_mutable_index(mutable_index, operation.id())?;
+        let index_file = self.save_mutable_index(mutable_index, operation.id(), store)?;
         tracing::info!(
             ?index_file,
             commits_count = commits.len(),
@@ -287,10 +440,33 @@ impl DefaultIndexStore {
         &self,
         mutable_index: DefaultMutableIndex,
         op_id: &OperationId,
+        store: &Arc<Store>,
     ) -> Result<Arc<ReadonlyIndexSegment>, DefaultIndexStoreError> {
+        // Segments may be squashed into the new one.
+        let old_segments = mutable_index
+            .as_composite()
+            .ancestor_files_without_local()
+            .cloned()
+            .collect_vec();
         let index_segment = mutable_index
             .squash_and_save_in(&self.segments_dir())
             .map_err(DefaultIndexStoreError::SaveIndex)?;
+        let path_index = self.changed_path_index();
+        if path_index.is_enabled() {
+            let kept_segments: HashSet<&str> = index_segment
+                .as_composite()
+                .ancestor_files_without_local()
+                .map(|segment| segment.name())
+                .collect();
+            let squashed_segments = old_segments
+                .into_iter()
+                .filter(|segment| !kept_segments.contains(segment.name()))
+                .collect_vec();
+            if let Err(err) = path_index.update_segment(store, &index_segment, &squashed_segments) {
+                // The segment will be diffed when evaluating revsets.
+                tracing::warn!(?err, "failed to index changed paths");
+            }
+        }
         self.associate_file_with_operation(&index_segment, op_id)
             .map_err(|source| DefaultIndexStoreError::AssociateIndex {
                 op_id: op_id.to_owned(),
@@ -360,21 +536,28 @@ impl IndexStore for DefaultIndexStore {
             result => result,
         }
         .map_err(|err| IndexReadError(err.into()))?;
-        Ok(Box::new(DefaultReadonlyIndex::from_segment(index_segment)))
+        Ok(Box::new(
+            DefaultReadonlyIndex::from_segment(index_segment)
+                .with_changed_path_index(self.enabled_changed_path_index()),
+        ))
     }
 
     fn write_index(
         &self,
         index: Box<dyn MutableIndex>,
         op: &Operation,
+        store: &Arc<Store>,
     ) -> Result<Box<dyn ReadonlyIndex>, IndexWriteError> {
         let index = index
             .into_any()
             .downcast::<DefaultMutableIndex>()
             .expect("index to merge in must be a DefaultMutableIndex");
         let index_segment = self
-            .save_mutable_index(*index, op.id())
+            .save_mutable_index(*index, op.id(), store)
             .map_err(|err| IndexWriteError(err.into()))?;
-        Ok(Box::new(DefaultReadonlyIndex::from_segment(index_segment)))
+        Ok(Box::new(
+            DefaultReadonlyIndex::from_segment(index_segment)
+                .with_changed_path_index(self.enabled_changed_path_index()),
+        ))
     }
 }
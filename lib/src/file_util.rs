@@ -132,6 +132,87 @@ pub fn normalize_path(path: &Path) -> PathBuf {
     }
 }
 
+/// Moves the directory `from` to `to`.
+///
+/// `to` must not exist unless it refers to `from` itself, which happens when
+/// only the case of the name changes on a case-insensitive file system. Such
+/// a rename goes through a temporary name since some file systems would
+/// otherwise ignore it. If `to` is on another file system, the directory is
+/// copied next to `to` under a temporary name, renamed into place, and then
+/// removed from the original location.
+pub fn move_dir(from: &Path, to: &Path) -> Result<(), PathError> {
+    let to_parent = match to.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    if to.symlink_metadata().is_ok() {
+        if !same_file::is_same_file(from, to).context(to)? {
+            return Err(io::Error::from(io::ErrorKind::AlreadyExists)).context(to);
+        }
+        let temp_path = to_parent.join(format!(
+            ".{}.jj-move",
+            to.file_name().unwrap_or_default().to_string_lossy()
+        ));
+        fs::rename(from, &temp_path).context(from)?;
+        return fs::rename(&temp_path, to).context(to);
+    }
+    match fs::rename(from, to) {
+        Ok(()) => return Ok(()),
+        Err(_) if !is_same_file_system(from, to_parent).context(from)? => {}
+        Err(err) => return Err(err).context(from),
+    }
+    let temp_dir = tempfile::Builder::new()
+        .prefix(".jj-move-")
+        .tempdir_in(to_parent)
+        .context(to_parent)?;
+    copy_dir_contents(from, temp_dir.path())?;
+    let permissions = from.metadata().context(from)?.permissions();
+    fs::set_permissions(temp_dir.path(), permissions).context(temp_dir.path())?;
+    fs::rename(temp_dir.path(), to).context(to)?;
+    fs::remove_dir_all(from).context(from)
+}
+
+/// Copies the contents of the directory `from` into the existing directory
+/// `to`, keeping symlinks, permissions, and file modification times.
+fn copy_dir_contents(from: &Path, to: &Path) -> Result<(), PathError> {
+    for entry in from.read_dir().context(from)? {
+        let entry = entry.context(from)?;
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        let metadata = path.symlink_metadata().context(&path)?;
+        if metadata.is_symlink() {
+            let original = fs::read_link(&path).context(&path)?;
+            try_symlink(original, &target).context(&target)?;
+        } else if metadata.is_dir() {
+            fs::create_dir(&target).context(&target)?;
+            copy_dir_contents(&path, &target)?;
+            fs::set_permissions(&target, metadata.permissions()).context(&target)?;
+        } else {
+            fs::copy(&path, &target).context(&path)?;
+            let modified = metadata.modified().context(&path)?;
+            File::options()
+                .write(true)
+                .open(&target)
+                .and_then(|file| file.set_modified(modified))
+                .context(&target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if the file system at `dir` treats names differing only in case
+/// as the same name. Creates a temporary file in `dir` to test that.
+pub fn is_case_insensitive_dir(dir: &Path) -> io::Result<bool> {
+    let temp_file = tempfile::Builder::new()
+        .prefix("case-test-")
+        .tempfile_in(dir)?;
+    let name = temp_file.path().file_name().unwrap().to_string_lossy();
+    Ok(dir
+        .join(name.to_ascii_uppercase())
+        .symlink_metadata()
+        .is_ok())
+}
+
 /// Like `NamedTempFile::persist()`, but doesn't try to overwrite the existing
 /// target on Windows.
 pub fn persist_content_addressed_temp_file<P: AsRef<Path>>(
@@ -167,6 +248,7 @@ pub fn persist_content_addressed_temp_file<P: AsRef<Path>>(
 mod platform {
     use std::io;
     use std::os::unix::fs::symlink;
+    use std::os::unix::fs::MetadataExt as _;
     use std::path::Path;
 
     /// Symlinks are always available on UNIX
@@ -174,6 +256,11 @@ mod platform {
         Ok(true)
     }
 
+    /// Returns true if both paths reside on the same device.
+    pub fn is_same_file_system(a: &Path, b: &Path) -> io::Result<bool> {
+        Ok(a.metadata()?.dev() == b.metadata()?.dev())
+    }
+
     pub fn try_symlink<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> io::Result<()> {
         symlink(original, link)
     }
@@ -198,6 +285,11 @@ mod platform {
         Ok(developer_mode == 1)
     }
 
+    /// Returns true if both paths reside on the same volume.
+    pub fn is_same_file_system(a: &Path, b: &Path) -> io::Result<bool> {
+        Ok(a.canonicalize()?.components().next() == b.canonicalize()?.components().next())
+    }
+
     pub fn try_symlink<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> io::Result<()> {
         // this will create a nonfunctional link for directories, but at the moment
         // we don't have enough information in the tree to determine whether the
@@ -231,6 +323,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_move_dir() {
+        let temp_dir = testutils::new_temp_dir();
+        let from = temp_dir.path().join("from");
+        fs::create_dir_all(from.join("sub")).unwrap();
+        fs::write(from.join("sub").join("file"), "contents").unwrap();
+        let to = temp_dir.path().join("dir").join("to");
+        fs::create_dir(temp_dir.path().join("dir")).unwrap();
+        move_dir(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs::read(to.join("sub").join("file")).unwrap(), b"contents");
+
+        // Renaming to an existing path only works if it's the same directory
+        move_dir(&to, &to).unwrap();
+        assert!(to.is_dir());
+        assert!(move_dir(&to, temp_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_copy_dir_contents() {
+        let temp_dir = testutils::new_temp_dir();
+        let from = temp_dir.path().join("from");
+        let to = temp_dir.path().join("to");
+        fs::create_dir_all(from.join("sub")).unwrap();
+        fs::create_dir(&to).unwrap();
+        fs::write(from.join("sub").join("file"), "contents").unwrap();
+        let modified = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1 << 30);
+        File::options()
+            .write(true)
+            .open(from.join("sub").join("file"))
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        if check_symlink_support().unwrap() {
+            try_symlink("sub/file", from.join("link")).unwrap();
+        }
+        copy_dir_contents(&from, &to).unwrap();
+        let file_path = to.join("sub").join("file");
+        assert_eq!(fs::read(&file_path).unwrap(), b"contents");
+        assert_eq!(file_path.metadata().unwrap().modified().unwrap(), modified);
+        if check_symlink_support().unwrap() {
+            assert_eq!(
+                fs::read_link(to.join("link")).unwrap(),
+                Path::new("sub/file")
+            );
+        }
+    }
+
     #[test]
     fn test_persist_no_existing_file() {
         let temp_dir = testutils::new_temp_dir();
@@ -41,6 +41,9 @@ pub enum FsmonitorSettings {
     /// The Watchman filesystem monitor (<https://facebook.github.io/watchman/>).
     Watchman(WatchmanConfig),
 
+    /// The built-in filesystem monitor daemon. See [`native`].
+    Native,
+
     /// Only used in tests.
     Test {
         /// The set of changed files to pretend that the filesystem monitor is
@@ -58,7 +61,16 @@ pub enum FsmonitorSettings {
 impl FsmonitorSettings {
     /// Creates an `FsmonitorSettings` from a `config`.
     pub fn from_settings(settings: &UserSettings) -> Result<FsmonitorSettings, ConfigGetError> {
-        let name = "core.fsmonitor";
+        // `fsmonitor.backend` takes precedence over the older `core.fsmonitor`.
+        let name = if settings
+            .get_value("fsmonitor.backend")
+            .optional()?
+            .is_some()
+        {
+            "fsmonitor.backend"
+        } else {
+            "core.fsmonitor"
+        };
         match settings.get_string(name) {
             Ok(s) => match s.as_str() {
                 "watchman" => Ok(Self::Watchman(WatchmanConfig {
@@ -67,6 +79,7 @@ impl FsmonitorSettings {
                         .optional()?
                         .unwrap_or_default(),
                 })),
+                "native" => Ok(Self::Native),
                 "test" => Err(ConfigGetError::Type {
                     name: name.to_owned(),
                     error: "Cannot use test fsmonitor in real repository".into(),
@@ -341,3 +354,470 @@ pub mod watchman {
         }
     }
 }
+
+/// Filesystem monitor integration using a daemon process built on the
+/// [`notify`] crate, for systems without Watchman.
+///
+/// The daemon is a separate background process, which the caller is
+/// responsible for spawning (`jj` runs `jj debug fsmonitor daemon`). It
+/// watches the working copy and appends the changed paths to a journal file in
+/// the working copy state directory. Clients remember how far they've read
+/// into the journal. To make sure that the daemon has caught up with the
+/// changes made before a query, clients create a "cookie" file and wait for it
+/// to show up in the journal.
+///
+/// The daemon exits when the journal is removed, for example by
+/// [`Fsmonitor::stop_daemon()`], or when it hasn't been queried for
+/// a while.
+#[cfg(feature = "native-fsmonitor")]
+pub mod native {
+    use std::fs;
+    use std::fs::File;
+    use std::io;
+    use std::io::BufRead as _;
+    use std::io::BufReader;
+    use std::io::BufWriter;
+    use std::io::Read as _;
+    use std::io::Seek as _;
+    use std::io::SeekFrom;
+    use std::io::Write as _;
+    use std::path::Component;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::thread;
+    use std::time::Duration;
+    use std::time::Instant;
+    use std::time::SystemTime;
+    use std::time::UNIX_EPOCH;
+
+    use itertools::Itertools as _;
+    use notify::event::ModifyKind;
+    use notify::EventKind;
+    use notify::RecursiveMode;
+    use notify::Watcher as _;
+    use tempfile::NamedTempFile;
+    use thiserror::Error;
+    use tracing::info;
+    use tracing::instrument;
+
+    use crate::lock::FileLock;
+    use crate::lock::FileLockError;
+
+    const JOURNAL_FILE_NAME: &str = "journal";
+    const LOCK_FILE_NAME: &str = "daemon.lock";
+    const COOKIES_DIR_NAME: &str = "cookies";
+    // Journal entries are NUL-terminated. Paths are relative, so entries
+    // starting with "/" can't be confused with them.
+    const RESCAN_ENTRY: &[u8] = b"/rescan";
+    const COOKIE_ENTRY_PREFIX: &str = "/cookie/";
+    /// How long to wait for the daemon to catch up with the filesystem.
+    const SYNC_TIMEOUT: Duration = Duration::from_secs(1);
+    /// The daemon exits once the journal grows beyond this size, and the next
+    /// command will start a new one.
+    const MAX_JOURNAL_SIZE: u64 = 64 << 20;
+    /// How often an idle daemon checks whether it should exit.
+    const DAEMON_POLL_INTERVAL: Duration = Duration::from_secs(10);
+    /// The daemon exits if it hasn't been queried for this long.
+    const DAEMON_IDLE_TIMEOUT: Duration = Duration::from_secs(4 * 60 * 60);
+    /// How long a starting daemon keeps trying to take the lock, which clients
+    /// may be holding briefly while checking for a stale journal.
+    const DAEMON_LOCK_TIMEOUT: Duration = Duration::from_millis(100);
+
+    /// Position in the journal of a daemon instance.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Clock {
+        daemon_id: String,
+        offset: u64,
+    }
+
+    impl From<crate::protos::working_copy::NativeFsmonitorClock> for Clock {
+        fn from(clock: crate::protos::working_copy::NativeFsmonitorClock) -> Self {
+            Self {
+                daemon_id: clock.daemon_id,
+                offset: clock.offset,
+            }
+        }
+    }
+
+    impl From<Clock> for crate::protos::working_copy::NativeFsmonitorClock {
+        fn from(clock: Clock) -> Self {
+            Self {
+                daemon_id: clock.daemon_id,
+                offset: clock.offset,
+            }
+        }
+    }
+
+    #[allow(missing_docs)]
+    #[derive(Debug, Error)]
+    pub enum Error {
+        #[error("Could not canonicalize working copy root path")]
+        CanonicalizeRootError(#[source] io::Error),
+
+        #[error("Failed to lock the filesystem monitor state")]
+        LockError(#[from] FileLockError),
+
+        #[error("Failed to watch the working copy")]
+        WatchError(#[source] notify::Error),
+
+        #[error("Failed to access filesystem monitor state {path}")]
+        StateError {
+            path: PathBuf,
+            #[source]
+            source: io::Error,
+        },
+    }
+
+    /// Handle to the filesystem monitor state of a working copy.
+    #[derive(Clone, Debug)]
+    pub struct Fsmonitor {
+        state_dir: PathBuf,
+    }
+
+    impl Fsmonitor {
+        /// Creates a handle for the daemon keeping its state in `state_dir`.
+        pub fn new(state_dir: PathBuf) -> Self {
+            Fsmonitor { state_dir }
+        }
+
+        fn journal_path(&self) -> PathBuf {
+            self.state_dir.join(JOURNAL_FILE_NAME)
+        }
+
+        fn lock_path(&self) -> PathBuf {
+            self.state_dir.join(LOCK_FILE_NAME)
+        }
+
+        fn cookies_dir(&self) -> PathBuf {
+            self.state_dir.join(COOKIES_DIR_NAME)
+        }
+
+        /// Returns whether a daemon is watching the working copy.
+        ///
+        /// This only checks that the daemon's journal exists, so a daemon
+        /// which was killed may be reported as running until the next query
+        /// notices that it doesn't respond.
+        pub fn is_daemon_running(&self) -> bool {
+            self.journal_path().exists()
+        }
+
+        /// Asks the daemon to exit by removing its journal. Returns whether a
+        /// daemon was running.
+        pub fn stop_daemon(&self) -> Result<bool, Error> {
+            let journal_path = self.journal_path();
+            match fs::remove_file(&journal_path) {
+                Ok(()) => Ok(true),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+                Err(source) => Err(Error::StateError {
+                    path: journal_path,
+                    source,
+                }),
+            }
+        }
+
+        /// Query for changed files since the previous point in time.
+        ///
+        /// The returned list of paths is relative to the working copy root,
+        /// and may include directories whose contents should be considered
+        /// changed as a whole. If it is `None`, then the caller must crawl the
+        /// entire working copy themselves. The returned clock is `None` if
+        /// no daemon is running.
+        #[instrument(skip(self))]
+        pub fn query_changed_files(
+            &self,
+            previous_clock: Option<&Clock>,
+        ) -> Result<(Option<Clock>, Option<Vec<PathBuf>>), Error> {
+            let journal_path = self.journal_path();
+            let state_error = |source| Error::StateError {
+                path: journal_path.clone(),
+                source,
+            };
+            let mut journal = match File::open(&journal_path) {
+                Ok(file) => file,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    info!("Filesystem monitor daemon isn't running");
+                    return Ok((None, None));
+                }
+                Err(source) => return Err(state_error(source)),
+            };
+            let daemon_id = read_daemon_id(&mut journal).map_err(state_error)?;
+            let journal_len = journal.metadata().map_err(state_error)?.len();
+            // The entries written before the cookie only need to be read if
+            // they can be compared with the previous state.
+            let previous_offset = previous_clock
+                .filter(|previous| {
+                    previous.daemon_id == daemon_id && previous.offset <= journal_len
+                })
+                .map(|previous| previous.offset);
+            let start = previous_offset.unwrap_or(journal_len);
+
+            let cookie_name = format!("{}-{}", std::process::id(), unique_suffix());
+            let cookie_path = self.cookies_dir().join(&cookie_name);
+            fs::write(&cookie_path, b"").map_err(|source| Error::StateError {
+                path: cookie_path.clone(),
+                source,
+            })?;
+            let cookie_entry = format!("{COOKIE_ENTRY_PREFIX}{cookie_name}");
+            let data = wait_for_entry(&mut journal, start, cookie_entry.as_bytes());
+            fs::remove_file(&cookie_path).ok();
+            let Some(data) = data.map_err(state_error)? else {
+                info!("Timed out waiting for the filesystem monitor daemon");
+                self.remove_stale_journal()?;
+                return Ok((None, None));
+            };
+
+            let clock = Clock {
+                daemon_id,
+                offset: start + data.len() as u64,
+            };
+            if previous_offset.is_none() {
+                return Ok((Some(clock), None));
+            }
+            let mut paths = vec![];
+            for entry in data.split(|&b| b == 0) {
+                if entry == RESCAN_ENTRY {
+                    return Ok((Some(clock), None));
+                }
+                if entry.is_empty() || entry.starts_with(COOKIE_ENTRY_PREFIX.as_bytes()) {
+                    continue;
+                }
+                // The daemon only writes UTF-8 paths
+                paths.push(PathBuf::from(String::from_utf8_lossy(entry).as_ref()));
+            }
+            let paths = paths.into_iter().sorted().dedup().collect();
+            Ok((Some(clock), Some(paths)))
+        }
+
+        /// Removes the journal if the daemon which wrote it is gone, so that
+        /// the daemon will be started again.
+        fn remove_stale_journal(&self) -> Result<(), Error> {
+            // The daemon holds the lock while it's running.
+            if let Some(_lock) = FileLock::try_lock(self.lock_path())? {
+                info!("Removing journal of a filesystem monitor daemon which isn't running");
+                fs::remove_file(self.journal_path()).ok();
+            }
+            Ok(())
+        }
+
+        /// Watches the working copy at `working_copy_path` until the working
+        /// copy state is removed. Returns immediately if another daemon is
+        /// already running.
+        #[instrument(skip(self))]
+        pub fn run_daemon(&self, working_copy_path: &Path) -> Result<(), Error> {
+            let cookies_dir = self.cookies_dir();
+            fs::create_dir_all(&cookies_dir).map_err(|source| Error::StateError {
+                path: cookies_dir.clone(),
+                source,
+            })?;
+            let Some(_lock) = self.try_lock_for_daemon()? else {
+                info!("Filesystem monitor daemon is already running");
+                return Ok(());
+            };
+            let root = working_copy_path
+                .canonicalize()
+                .map_err(Error::CanonicalizeRootError)?;
+            let cookies_dir = cookies_dir
+                .canonicalize()
+                .map_err(Error::CanonicalizeRootError)?;
+
+            let (tx, rx) = mpsc::channel();
+            let mut watcher = notify::recommended_watcher(tx).map_err(Error::WatchError)?;
+            watcher
+                .watch(&root, RecursiveMode::Recursive)
+                .map_err(Error::WatchError)?;
+            // Clients don't trust the journal before it exists, so only create
+            // it once the watch is in place.
+            let mut journal = Journal::create(&self.state_dir, self.journal_path())?;
+            info!(?root, "Filesystem monitor daemon started");
+
+            loop {
+                let first = match rx.recv_timeout(DAEMON_POLL_INTERVAL) {
+                    Ok(result) => Some(result),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                for result in first.into_iter().chain(rx.try_iter()) {
+                    let written = match result {
+                        Ok(event) => journal.record_event(&event, &root, &cookies_dir),
+                        Err(err) => {
+                            tracing::warn!(?err, "Filesystem monitor error");
+                            journal.write_entry(RESCAN_ENTRY)
+                        }
+                    };
+                    written.map_err(|err| journal.error(err))?;
+                }
+                journal.file.flush().map_err(|err| journal.error(err))?;
+                if journal.size > MAX_JOURNAL_SIZE || !journal.path.exists() {
+                    break;
+                }
+                if journal.last_query.elapsed() > DAEMON_IDLE_TIMEOUT {
+                    info!("Filesystem monitor daemon hasn't been queried for a while");
+                    break;
+                }
+            }
+            info!("Filesystem monitor daemon exiting");
+            Ok(())
+        }
+
+        fn try_lock_for_daemon(&self) -> Result<Option<FileLock>, Error> {
+            let deadline = Instant::now() + DAEMON_LOCK_TIMEOUT;
+            loop {
+                if let Some(lock) = FileLock::try_lock(self.lock_path())? {
+                    return Ok(Some(lock));
+                }
+                if Instant::now() >= deadline {
+                    return Ok(None);
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
+    }
+
+    fn unique_suffix() -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos())
+    }
+
+    /// Reads the ID of the daemon from the journal header.
+    fn read_daemon_id(journal: &mut File) -> io::Result<String> {
+        let mut header = vec![];
+        BufReader::new(journal).read_until(0, &mut header)?;
+        if header.pop() != Some(0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Journal header is incomplete",
+            ));
+        }
+        Ok(String::from_utf8_lossy(&header).into_owned())
+    }
+
+    /// Reads the journal from the `start` offset until `entry` appears in it,
+    /// and returns its contents up to and including that entry. Returns `None`
+    /// on timeout.
+    fn wait_for_entry(journal: &mut File, start: u64, entry: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        let deadline = Instant::now() + SYNC_TIMEOUT;
+        journal.seek(SeekFrom::Start(start))?;
+        let mut data = vec![];
+        let mut scanned = 0;
+        loop {
+            journal.read_to_end(&mut data)?;
+            // Only look at complete entries
+            while let Some(len) = data[scanned..].iter().position(|&b| b == 0) {
+                let current = &data[scanned..scanned + len];
+                scanned += len + 1;
+                if current == entry {
+                    data.truncate(scanned);
+                    return Ok(Some(data));
+                }
+            }
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// The journal being written by the daemon. It's deleted when the daemon
+    /// exits.
+    struct Journal {
+        path: PathBuf,
+        file: BufWriter<File>,
+        size: u64,
+        last_query: Instant,
+    }
+
+    impl Journal {
+        fn create(state_dir: &Path, path: PathBuf) -> Result<Self, Error> {
+            let state_error = |source| Error::StateError {
+                path: path.clone(),
+                source,
+            };
+            let daemon_id = format!("{}-{}", std::process::id(), unique_suffix());
+            let mut temp_file = NamedTempFile::new_in(state_dir).map_err(state_error)?;
+            temp_file
+                .write_all(daemon_id.as_bytes())
+                .and_then(|()| temp_file.write_all(b"\0"))
+                .map_err(state_error)?;
+            let file = temp_file
+                .persist(&path)
+                .map_err(|err| state_error(err.error))?;
+            Ok(Journal {
+                path,
+                file: BufWriter::new(file),
+                size: daemon_id.len() as u64 + 1,
+                last_query: Instant::now(),
+            })
+        }
+
+        fn error(&self, source: io::Error) -> Error {
+            Error::StateError {
+                path: self.path.clone(),
+                source,
+            }
+        }
+
+        fn write_entry(&mut self, entry: &[u8]) -> io::Result<()> {
+            self.file.write_all(entry)?;
+            self.file.write_all(b"\0")?;
+            self.size += entry.len() as u64 + 1;
+            Ok(())
+        }
+
+        fn record_event(
+            &mut self,
+            event: &notify::Event,
+            root: &Path,
+            cookies_dir: &Path,
+        ) -> io::Result<()> {
+            if event.need_rescan() {
+                return self.write_entry(RESCAN_ENTRY);
+            }
+            if matches!(event.kind, EventKind::Access(_)) {
+                return Ok(());
+            }
+            for path in &event.paths {
+                if let Ok(cookie) = path.strip_prefix(cookies_dir) {
+                    if !matches!(event.kind, EventKind::Remove(_)) {
+                        if let Some(name) = cookie.to_str() {
+                            self.write_entry(format!("{COOKIE_ENTRY_PREFIX}{name}").as_bytes())?;
+                            self.last_query = Instant::now();
+                        }
+                    }
+                    continue;
+                }
+                let Ok(relative) = path.strip_prefix(root) else {
+                    continue;
+                };
+                match relative.components().next() {
+                    None => continue,
+                    Some(Component::Normal(name)) if name == ".jj" || name == ".git" => continue,
+                    Some(_) => {}
+                }
+                // Only the contents of directories matter, which are reported
+                // separately unless the directory is renamed.
+                let is_renamed = matches!(event.kind, EventKind::Modify(ModifyKind::Name(_)));
+                if matches!(event.kind, EventKind::Modify(_)) && !is_renamed && path.is_dir() {
+                    continue;
+                }
+                let components: Option<Vec<_>> = relative
+                    .components()
+                    .map(|component| component.as_os_str().to_str())
+                    .collect();
+                match components {
+                    Some(components) => self.write_entry(components.join("/").as_bytes())?,
+                    None => self.write_entry(RESCAN_ENTRY)?,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for Journal {
+        fn drop(&mut self) {
+            fs::remove_file(&self.path).ok();
+        }
+    }
+}
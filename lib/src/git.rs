@@ -31,9 +31,16 @@ use tempfile::NamedTempFile;
 use thiserror::Error;
 
 use crate::backend::BackendError;
+use crate::backend::ChangeId;
 use crate::backend::CommitId;
+use crate::backend::MergedTreeId;
+use crate::backend::MillisSinceEpoch;
+use crate::backend::Signature;
+use crate::backend::Timestamp;
+use crate::backend::TreeId;
 use crate::commit::Commit;
 use crate::git_backend::GitBackend;
+use crate::git_backend::EMPTY_STRING_PLACEHOLDER;
 use crate::index::Index;
 use crate::object_id::ObjectId;
 use crate::op_store::RefTarget;
@@ -44,8 +51,13 @@ use crate::refs;
 use crate::refs::BookmarkPushUpdate;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
 use crate::revset::RevsetExpression;
 use crate::settings::GitSettings;
+use crate::settings::UserSettings;
+use crate::signing::SignError;
+use crate::signing::Signer;
 use crate::store::Store;
 use crate::str_util::StringPattern;
 use crate::view::View;
@@ -586,6 +598,93 @@ pub fn import_head(mut_repo: &mut MutableRepo) -> Result<(), GitImportError> {
     Ok(())
 }
 
+/// Imports the stashes in the underlying Git repo as new commits on top of the
+/// commits they were created from, oldest first.
+///
+/// Each stash becomes a single commit with the stashed working-copy changes,
+/// including the untracked files if they were stashed too. The change id is
+/// derived from the stash commit, so stashes which were already imported are
+/// skipped.
+pub fn import_stashes(
+    mut_repo: &mut MutableRepo,
+    settings: &UserSettings,
+) -> Result<Vec<Commit>, GitImportError> {
+    let store = mut_repo.store().clone();
+    let git_backend = get_git_backend(&store).ok_or(GitImportError::UnexpectedBackend)?;
+    let git_repo = git_backend
+        .open_git_repo()
+        .map_err(GitImportError::from_git)?;
+    let reflog = git_repo
+        .reflog("refs/stash")
+        .map_err(GitImportError::from_git)?;
+    let mut imported = vec![];
+    // The most recent stash comes first in the reflog.
+    for entry in reflog.iter().collect_vec().into_iter().rev() {
+        let stash = git_repo
+            .find_commit(entry.id_new())
+            .map_err(GitImportError::from_git)?;
+        // Derived the same way as the change ids of imported Git commits.
+        let change_id = ChangeId::new(
+            stash.id().as_bytes()[4..]
+                .iter()
+                .rev()
+                .map(|b| b.reverse_bits())
+                .collect(),
+        );
+        if mut_repo.resolve_change_id(&change_id).is_some() {
+            continue;
+        }
+        let base_id = CommitId::from_bytes(
+            stash
+                .parent_id(0)
+                .map_err(GitImportError::from_git)?
+                .as_bytes(),
+        );
+        git_backend.import_head_commits([&base_id]).map_err(|err| {
+            GitImportError::MissingHeadTarget {
+                id: base_id.clone(),
+                err,
+            }
+        })?;
+        // The third parent, if any, contains the stashed untracked files.
+        let stash_tree = stash.tree().map_err(GitImportError::from_git)?;
+        let tree_oid = if let Ok(untracked) = stash.parent(2) {
+            let empty_tree = git_repo
+                .find_tree(Oid::from_bytes(store.empty_tree_id().as_bytes()).unwrap())
+                .map_err(GitImportError::from_git)?;
+            let untracked_tree = untracked.tree().map_err(GitImportError::from_git)?;
+            git_repo
+                .merge_trees(&empty_tree, &stash_tree, &untracked_tree, None)
+                .and_then(|mut index| index.write_tree_to(&git_repo))
+                .map_err(GitImportError::from_git)?
+        } else {
+            stash_tree.id()
+        };
+        let when = stash.author().when();
+        let author = Signature {
+            name: stash.author().name().unwrap_or_default().to_owned(),
+            email: stash.author().email().unwrap_or_default().to_owned(),
+            timestamp: Timestamp {
+                timestamp: MillisSinceEpoch(when.seconds() * 1000),
+                tz_offset: when.offset_minutes(),
+            },
+        };
+        let tree_id = MergedTreeId::resolved(TreeId::from_bytes(tree_oid.as_bytes()));
+        let commit = mut_repo
+            .new_commit(settings, vec![base_id], tree_id)
+            .set_change_id(change_id)
+            .set_author(author)
+            .set_description(format!(
+                "{}\n",
+                stash.message().unwrap_or_default().trim_end()
+            ))
+            .write()
+            .map_err(GitImportError::InternalBackend)?;
+        imported.push(commit);
+    }
+    Ok(imported)
+}
+
 #[derive(Error, Debug)]
 pub enum GitExportError {
     #[error("Git error")]
@@ -1213,6 +1312,124 @@ fn rename_remote_refs(mut_repo: &mut MutableRepo, old_remote_name: &str, new_rem
 
 const INVALID_REFSPEC_CHARS: [char; 5] = [':', '^', '?', '[', ']'];
 
+/// Error parsing a [`RefSpec`].
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[error("Invalid refspec '{refspec}': {message}")]
+pub struct RefSpecParseError {
+    pub refspec: String,
+    pub message: String,
+}
+
+/// Git refspec mapping source refs to destination refs, such as
+/// `+refs/heads/*:refs/remotes/origin/*`.
+///
+/// Only the subset of refspecs that can be mapped in both directions is
+/// supported: both sides must be present, and either both or neither contain a
+/// single `*`, which matches any sequence of characters.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RefSpec {
+    forced: bool,
+    source: String,
+    destination: String,
+}
+
+impl RefSpec {
+    pub fn parse(text: &str) -> Result<Self, RefSpecParseError> {
+        let make_error = |message: &str| RefSpecParseError {
+            refspec: text.to_owned(),
+            message: message.to_owned(),
+        };
+        let (forced, rest) = match text.strip_prefix('+') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        let (source, destination) = rest
+            .split_once(':')
+            .ok_or_else(|| make_error("expected <source>:<destination>"))?;
+        if source.is_empty() || destination.is_empty() {
+            return Err(make_error("source and destination must not be empty"));
+        }
+        if [source, destination]
+            .iter()
+            .any(|side| side.contains(&INVALID_REFSPEC_CHARS[..]))
+        {
+            return Err(make_error(&format!(
+                "refs may not contain the characters `{}`",
+                INVALID_REFSPEC_CHARS.iter().join("`, `")
+            )));
+        }
+        let source_globs = source.matches('*').count();
+        let destination_globs = destination.matches('*').count();
+        if source_globs > 1 || destination_globs > 1 {
+            return Err(make_error("only one `*` is allowed on each side"));
+        }
+        if source_globs != destination_globs {
+            return Err(make_error("either both or neither side must contain `*`"));
+        }
+        Ok(RefSpec {
+            forced,
+            source: source.to_owned(),
+            destination: destination.to_owned(),
+        })
+    }
+
+    /// Whether the destination ref may be updated even if it's not a
+    /// fast-forward.
+    pub fn forced(&self) -> bool {
+        self.forced
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// Maps the source ref `name` to the destination ref, or returns `None` if
+    /// `name` doesn't match the source.
+    pub fn map_to_destination(&self, name: &str) -> Option<String> {
+        map_ref_pattern(name, &self.source, &self.destination)
+    }
+
+    /// Maps the destination ref `name` back to the source ref, or returns
+    /// `None` if `name` doesn't match the destination.
+    pub fn map_to_source(&self, name: &str) -> Option<String> {
+        map_ref_pattern(name, &self.destination, &self.source)
+    }
+}
+
+impl fmt::Display for RefSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let RefSpec {
+            forced,
+            source,
+            destination,
+        } = self;
+        let prefix = if *forced { "+" } else { "" };
+        write!(f, "{prefix}{source}:{destination}")
+    }
+}
+
+/// Matches `name` against `from` and substitutes the part matched by `*` into
+/// `to`.
+fn map_ref_pattern(name: &str, from: &str, to: &str) -> Option<String> {
+    let Some((from_prefix, from_suffix)) = from.split_once('*') else {
+        return (name == from).then(|| to.to_owned());
+    };
+    let matched = name
+        .strip_prefix(from_prefix)?
+        .strip_suffix(from_suffix)
+        .filter(|matched| !matched.is_empty())?;
+    let (to_prefix, to_suffix) = to.split_once('*')?;
+    Some(format!("{to_prefix}{matched}{to_suffix}"))
+}
+
+fn parse_refspecs(refspecs: &[String]) -> Result<Vec<RefSpec>, RefSpecParseError> {
+    refspecs.iter().map(|text| RefSpec::parse(text)).collect()
+}
+
 #[derive(Error, Debug)]
 pub enum GitFetchError {
     #[error("No git remote named '{0}'")]
@@ -1222,6 +1439,14 @@ pub enum GitFetchError {
         chars = INVALID_REFSPEC_CHARS.iter().join("`, `")
     )]
     InvalidBranchPattern,
+    #[error("No ref named '{0}' on the remote")]
+    NoSuchRemoteRef(String),
+    #[error(transparent)]
+    InvalidRefSpec(#[from] RefSpecParseError),
+    #[error(
+        "Fetch refspec '{refspec}' must map to remote-tracking refs under 'refs/remotes/{remote}/'"
+    )]
+    UnsupportedFetchRefSpec { refspec: String, remote: String },
     #[error("Failed to import Git refs")]
     GitImportError(#[from] GitImportError),
     // TODO: I'm sure there are other errors possible, such as transport-level errors.
@@ -1229,12 +1454,12 @@ pub enum GitFetchError {
     InternalGitError(#[from] git2::Error),
 }
 
-fn fetch_options(
-    callbacks: RemoteCallbacks<'_>,
+fn fetch_options<'a>(
+    callbacks: RemoteCallbacks<'a>,
     depth: Option<NonZeroU32>,
-) -> git2::FetchOptions<'_> {
-    let mut proxy_options = git2::ProxyOptions::new();
-    proxy_options.auto();
+    remote_url: Option<&str>,
+) -> git2::FetchOptions<'a> {
+    let proxy_options = callbacks.proxy_options(remote_url);
 
     let mut fetch_options = git2::FetchOptions::new();
     fetch_options.proxy_options(proxy_options);
@@ -1246,143 +1471,166 @@ fn fetch_options(
     fetch_options
 }
 
+#[derive(Clone, Debug)]
 struct FetchedBranches {
     branches: Vec<StringPattern>,
+    /// Refspecs used instead of the default ones, if configured.
+    refspecs: Vec<RefSpec>,
     remote: String,
 }
 
-struct GitFetch<'a> {
-    mut_repo: &'a mut MutableRepo,
-    git_repo: &'a git2::Repository,
-    git_settings: &'a GitSettings,
-    fetch_options: git2::FetchOptions<'a>,
-    fetched: Vec<FetchedBranches>,
+/// Remote-tracking branches updated by [`download()`], which are yet to be
+/// imported into the jj repo by [`import_download()`].
+#[derive(Clone, Debug, Default)]
+pub struct GitDownload {
+    fetched: Option<FetchedBranches>,
+    /// Remote's default branch.
+    pub default_branch: Option<String>,
 }
 
-impl<'a> GitFetch<'a> {
-    fn new(
-        mut_repo: &'a mut MutableRepo,
-        git_repo: &'a git2::Repository,
-        git_settings: &'a GitSettings,
-        fetch_options: git2::FetchOptions<'a>,
-    ) -> Self {
-        GitFetch {
-            mut_repo,
-            git_repo,
-            git_settings,
-            fetch_options,
-            fetched: vec![],
+/// Performs a `git fetch` on the local git repo, updating the remote-tracking
+/// branches in the git repo, but not the jj repo.
+///
+/// Unlike [`fetch()`], this doesn't need the jj repo, so downloads from
+/// several remotes can run concurrently, each with its own `git_repo`.
+#[tracing::instrument(skip(git_repo, callbacks))]
+pub fn download(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    branch_names: &[StringPattern],
+    callbacks: RemoteCallbacks<'_>,
+    git_settings: &GitSettings,
+    depth: Option<NonZeroU32>,
+) -> Result<GitDownload, GitFetchError> {
+    let remote_url = git_repo
+        .find_remote(remote_name)
+        .ok()
+        .and_then(|remote| remote.url().map(str::to_owned));
+    let mut fetch_options = fetch_options(callbacks, depth, remote_url.as_deref());
+    let mut remote = git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitFetchError::NoSuchRemote(remote_name.to_string())
+        } else {
+            GitFetchError::InternalGitError(err)
         }
+    })?;
+    let configured_refspecs = parse_refspecs(git_settings.fetch_refspecs_for(remote_name))?;
+    let remote_ref_prefix = format!("refs/remotes/{remote_name}/");
+    if let Some(refspec) = configured_refspecs
+        .iter()
+        .find(|refspec| !refspec.destination().starts_with(&remote_ref_prefix))
+    {
+        return Err(GitFetchError::UnsupportedFetchRefSpec {
+            refspec: refspec.to_string(),
+            remote: remote_name.to_owned(),
+        });
     }
-
-    /// Perform a `git fetch` on the local git repo, updating the
-    /// remote-tracking branches in the git repo.
-    ///
-    /// Keeps track of the {branch_names, remote_name} pair the refs can be
-    /// subsequently imported into the `jj` repo by calling `import_refs()`.
-    fn fetch(
-        &mut self,
-        branch_names: &[StringPattern],
-        remote_name: &str,
-    ) -> Result<Option<String>, GitFetchError> {
-        let mut remote = self.git_repo.find_remote(remote_name).map_err(|err| {
-            if is_remote_not_found_err(&err) {
-                GitFetchError::NoSuchRemote(remote_name.to_string())
-            } else {
-                GitFetchError::InternalGitError(err)
-            }
-        })?;
-        // At this point, we are only updating Git's remote tracking branches, not the
-        // local branches.
-        let refspecs: Vec<_> = branch_names
+    // At this point, we are only updating Git's remote tracking branches, not the
+    // local branches.
+    let mut refspecs: Vec<_> = if !configured_refspecs.is_empty() {
+        // Branch patterns still restrict which of the fetched branches are
+        // imported.
+        configured_refspecs.iter().map(RefSpec::to_string).collect()
+    } else {
+        branch_names
             .iter()
             .map(|pattern| {
                 pattern
                     .to_glob()
                     .filter(
-                        /* This triggered by non-glob `*`s in addition to INVALID_REFSPEC_CHARS
+                        /* This triggered by non-glob `*`s in addition to
+                         * INVALID_REFSPEC_CHARS
                          * because `to_glob()` escapes such `*`s as `[*]`. */
                         |glob| !glob.contains(INVALID_REFSPEC_CHARS),
                     )
                     .map(|glob| format!("+refs/heads/{glob}:refs/remotes/{remote_name}/{glob}"))
             })
             .collect::<Option<_>>()
-            .ok_or(GitFetchError::InvalidBranchPattern)?;
-        if refspecs.is_empty() {
-            // Don't fall back to the base refspecs.
-            return Ok(None);
-        }
-
-        tracing::debug!("remote.download");
-        remote.download(&refspecs, Some(&mut self.fetch_options))?;
+            .ok_or(GitFetchError::InvalidBranchPattern)?
+    };
+    if branch_names.is_empty() || refspecs.is_empty() {
+        // Don't fall back to the base refspecs.
+        return Ok(GitDownload::default());
+    }
+    // Mirror the remote's file locks so they can be checked offline. Locks
+    // deleted on the remote are pruned along with the branches if pruning
+    // is enabled.
+    refspecs.push(format!(
+        "+{FILE_LOCK_REF_PREFIX}*:{REMOTE_FILE_LOCK_REF_PREFIX}{remote_name}/*"
+    ));
+
+    tracing::debug!("remote.download");
+    remote.download(&refspecs, Some(&mut fetch_options))?;
+    if git_settings.fetch_prune_for(remote_name) {
         tracing::debug!("remote.prune");
         remote.prune(None)?;
-        tracing::debug!("remote.update_tips");
-        remote.update_tips(
-            None,
-            git2::RemoteUpdateFlags::empty(),
-            git2::AutotagOption::Unspecified,
-            None,
-        )?;
-
-        self.fetched.push(FetchedBranches {
-            branches: branch_names.to_vec(),
-            remote: remote_name.to_string(),
-        });
+    }
+    tracing::debug!("remote.update_tips");
+    remote.update_tips(
+        None,
+        git2::RemoteUpdateFlags::empty(),
+        git2::AutotagOption::Unspecified,
+        None,
+    )?;
+
+    let fetched = FetchedBranches {
+        branches: branch_names.to_vec(),
+        refspecs: configured_refspecs,
+        remote: remote_name.to_string(),
+    };
 
-        // TODO: We could make it optional to get the default branch since we only care
-        // about it on clone.
-        let mut default_branch = None;
-        if let Ok(default_ref_buf) = remote.default_branch() {
-            if let Some(default_ref) = default_ref_buf.as_str() {
-                // LocalBranch here is the local branch on the remote, so it's really the remote
-                // branch
-                if let Some(RefName::LocalBranch(branch_name)) = parse_git_ref(default_ref) {
-                    tracing::debug!(default_branch = branch_name);
-                    default_branch = Some(branch_name);
-                }
+    // TODO: We could make it optional to get the default branch since we only care
+    // about it on clone.
+    let mut default_branch = None;
+    if let Ok(default_ref_buf) = remote.default_branch() {
+        if let Some(default_ref) = default_ref_buf.as_str() {
+            // LocalBranch here is the local branch on the remote, so it's really the remote
+            // branch
+            if let Some(RefName::LocalBranch(branch_name)) = parse_git_ref(default_ref) {
+                tracing::debug!(default_branch = branch_name);
+                default_branch = Some(branch_name);
             }
         }
-        tracing::debug!("remote.disconnect");
-        remote.disconnect()?;
-        Ok(default_branch)
     }
+    tracing::debug!("remote.disconnect");
+    remote.disconnect()?;
+    Ok(GitDownload {
+        fetched: Some(fetched),
+        default_branch,
+    })
+}
 
-    /// Import the previously fetched remote-tracking branches into the jj repo
-    /// and update jj's local branches. We also import local tags since remote
-    /// tags should have been merged by Git.
-    ///
-    /// Clears all yet-to-be-imported {branch_names, remote_name} pairs after
-    /// the import. If `fetch()` has not been called since the last time
-    /// `import_refs()` was called then this will be a no-op.
-    pub fn import_refs(&mut self) -> Result<GitImportStats, GitImportError> {
-        tracing::debug!("import_refs");
-        let import_stats =
-            import_some_refs(
-                self.mut_repo,
-                self.git_settings,
-                |ref_name| match ref_name {
-                    RefName::LocalBranch(_) => false,
-                    RefName::Tag(_) => true,
-                    RefName::RemoteBranch { branch, remote } => {
-                        self.fetched.iter().any(|fetched| {
-                            if fetched.remote != *remote {
-                                return false;
-                            }
-
-                            fetched
-                                .branches
-                                .iter()
-                                .any(|pattern| pattern.matches(branch))
-                        })
-                    }
-                },
-            )?;
+/// Imports the remote-tracking branches updated by [`download()`] into the jj
+/// repo and updates jj's local branches. We also import local tags since
+/// remote tags should have been merged by Git.
+pub fn import_download(
+    mut_repo: &mut MutableRepo,
+    git_settings: &GitSettings,
+    download: &GitDownload,
+) -> Result<GitImportStats, GitImportError> {
+    tracing::debug!("import_refs");
+    let import_stats = import_some_refs(mut_repo, git_settings, |ref_name| match ref_name {
+        RefName::LocalBranch(_) => false,
+        RefName::Tag(_) => true,
+        RefName::RemoteBranch { branch, remote } => download.fetched.iter().any(|fetched| {
+            if fetched.remote != *remote {
+                return false;
+            }
 
-        self.fetched.clear();
+            let git_ref_name = format!("refs/remotes/{remote}/{branch}");
+            (fetched.refspecs.is_empty()
+                || fetched
+                    .refspecs
+                    .iter()
+                    .any(|refspec| refspec.map_to_source(&git_ref_name).is_some()))
+                && fetched
+                    .branches
+                    .iter()
+                    .any(|pattern| pattern.matches(branch))
+        }),
+    })?;
 
-        Ok(import_stats)
-    }
+    Ok(import_stats)
 }
 
 /// Describes successful `fetch()` result.
@@ -1404,21 +1652,74 @@ pub fn fetch(
     git_settings: &GitSettings,
     depth: Option<NonZeroU32>,
 ) -> Result<GitFetchStats, GitFetchError> {
-    let mut git_fetch = GitFetch::new(
-        mut_repo,
+    let download = download(
         git_repo,
+        remote_name,
+        branch_names,
+        callbacks,
         git_settings,
-        fetch_options(callbacks, depth),
-    );
-    let default_branch = git_fetch.fetch(branch_names, remote_name)?;
-    let import_stats = git_fetch.import_refs()?;
+        depth,
+    )?;
+    let import_stats = import_download(mut_repo, git_settings, &download)?;
     let stats = GitFetchStats {
-        default_branch,
+        default_branch: download.default_branch,
         import_stats,
     };
     Ok(stats)
 }
 
+/// Fetches a single ref which isn't necessarily a branch, such as
+/// `refs/pull/123/head`, and returns the commit it points to.
+///
+/// The commit and its ancestors are imported and added to the visible heads,
+/// but no refs are created for them. It's up to the caller to point a bookmark
+/// at the commit.
+#[tracing::instrument(skip(mut_repo, git_repo, callbacks))]
+pub fn fetch_ref(
+    mut_repo: &mut MutableRepo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    ref_name: &str,
+    callbacks: RemoteCallbacks<'_>,
+) -> Result<CommitId, GitFetchError> {
+    if ref_name.contains(INVALID_REFSPEC_CHARS) || ref_name.contains('*') {
+        return Err(GitFetchError::NoSuchRemoteRef(ref_name.to_owned()));
+    }
+    let mut remote = git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitFetchError::NoSuchRemote(remote_name.to_string())
+        } else {
+            GitFetchError::InternalGitError(err)
+        }
+    })?;
+    let mut fetch_options = fetch_options(callbacks, None, remote.url());
+    tracing::debug!("remote.download");
+    remote.download(&[format!("+{ref_name}")], Some(&mut fetch_options))?;
+    let oid = remote
+        .list()?
+        .iter()
+        .find(|head| head.name() == ref_name)
+        .map(|head| head.oid())
+        .ok_or_else(|| GitFetchError::NoSuchRemoteRef(ref_name.to_owned()))?;
+    tracing::debug!("remote.disconnect");
+    remote.disconnect()?;
+
+    let commit_id = CommitId::from_bytes(oid.as_bytes());
+    let store = mut_repo.store().clone();
+    let git_backend = get_git_backend(&store).ok_or(GitImportError::UnexpectedBackend)?;
+    git_backend
+        .import_head_commits([&commit_id])
+        .map_err(|err| GitImportError::MissingHeadTarget {
+            id: commit_id.clone(),
+            err,
+        })?;
+    store
+        .get_commit(&commit_id)
+        .and_then(|commit| mut_repo.add_head(&commit))
+        .map_err(GitImportError::InternalBackend)?;
+    Ok(commit_id)
+}
+
 #[derive(Error, Debug, PartialEq)]
 pub enum GitPushError {
     #[error("No git remote named '{0}'")]
@@ -1432,6 +1733,16 @@ pub enum GitPushError {
     RefInUnexpectedLocation(Vec<String>),
     #[error("Remote rejected the update of some refs (do you have permission to push to {0:?}?)")]
     RefUpdateRejected(Vec<String>),
+    #[error(transparent)]
+    InvalidRefSpec(#[from] RefSpecParseError),
+    #[error(
+        "Cannot delete bookmark '{branch}' which is pushed to '{remote_ref}' by push refspecs"
+    )]
+    MappedRefDeletion { branch: String, remote_ref: String },
+    #[error("No tag named '{0}' in the Git repository")]
+    NoSuchTag(String),
+    #[error("Tags already exist on the remote at a different location: {0:?}")]
+    TagAlreadyExists(Vec<String>),
     // TODO: I'm sure there are other errors possible, such as transport-level errors,
     // and errors caused by the remote rejecting the push.
     #[error("Unexpected git error when pushing")]
@@ -1441,6 +1752,9 @@ pub enum GitPushError {
 #[derive(Clone, Debug)]
 pub struct GitBranchPushTargets {
     pub branch_updates: Vec<(String, BookmarkPushUpdate)>,
+    /// Tags to push as they are in the Git repository. Tags which already
+    /// exist on the remote are never moved.
+    pub tags: Vec<String>,
 }
 
 pub struct GitRefUpdate {
@@ -1454,28 +1768,74 @@ pub struct GitRefUpdate {
 }
 
 /// Pushes the specified branches and updates the repo view accordingly.
+///
+/// Branches are pushed to the remote ref their `refs/heads/` ref is mapped to
+/// by the first matching push refspec configured for the remote, or to the
+/// same ref if none matches. The remote-tracking branches of branches pushed to
+/// a different ref, such as Gerrit's `refs/for/*`, aren't updated.
 pub fn push_branches(
     mut_repo: &mut MutableRepo,
     git_repo: &git2::Repository,
     remote_name: &str,
     targets: &GitBranchPushTargets,
     callbacks: RemoteCallbacks<'_>,
+    git_settings: &GitSettings,
 ) -> Result<(), GitPushError> {
-    let ref_updates = targets
-        .branch_updates
-        .iter()
-        .map(|(branch_name, update)| GitRefUpdate {
-            qualified_name: format!("refs/heads/{branch_name}"),
-            expected_current_target: update.old_target.clone(),
-            new_target: update.new_target.clone(),
-        })
-        .collect_vec();
-    push_updates(mut_repo, git_repo, remote_name, &ref_updates, callbacks)?;
+    let refspecs = parse_refspecs(git_settings.push_refspecs_for(remote_name))?;
+    let mut ref_updates = vec![];
+    let mut tracked_branch_updates = vec![];
+    for (branch_name, update) in &targets.branch_updates {
+        let local_ref_name = format!("refs/heads/{branch_name}");
+        let remote_ref_name = refspecs
+            .iter()
+            .find_map(|refspec| refspec.map_to_destination(&local_ref_name))
+            .unwrap_or_else(|| local_ref_name.clone());
+        if remote_ref_name == local_ref_name {
+            ref_updates.push(GitRefUpdate {
+                qualified_name: remote_ref_name,
+                expected_current_target: update.old_target.clone(),
+                new_target: update.new_target.clone(),
+            });
+            tracked_branch_updates.push((branch_name, update));
+        } else if update.new_target.is_some() {
+            // We don't know where the mapped ref is on the remote. It usually
+            // doesn't exist, e.g. Gerrit's refs/for/*.
+            ref_updates.push(GitRefUpdate {
+                qualified_name: remote_ref_name,
+                expected_current_target: None,
+                new_target: update.new_target.clone(),
+            });
+        } else {
+            return Err(GitPushError::MappedRefDeletion {
+                branch: branch_name.clone(),
+                remote_ref: remote_ref_name,
+            });
+        }
+    }
+    let mut tag_ref_names = vec![];
+    for tag_name in &targets.tags {
+        let ref_name = format!("refs/tags/{tag_name}");
+        match git_repo.find_reference(&ref_name) {
+            Ok(_) => tag_ref_names.push(ref_name),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => {
+                return Err(GitPushError::NoSuchTag(tag_name.clone()));
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    push_updates_and_tags(
+        mut_repo,
+        git_repo,
+        remote_name,
+        &ref_updates,
+        &tag_ref_names,
+        callbacks,
+    )?;
 
     // TODO: add support for partially pushed refs? we could update the view
     // excluding rejected refs, but the transaction would be aborted anyway
     // if we returned an Err.
-    for (branch_name, update) in &targets.branch_updates {
+    for (branch_name, update) in tracked_branch_updates {
         let git_ref_name = format!("refs/remotes/{remote_name}/{branch_name}");
         let new_remote_ref = RemoteRef {
             target: RefTarget::resolved(update.new_target.clone()),
@@ -1495,6 +1855,17 @@ pub fn push_updates(
     remote_name: &str,
     updates: &[GitRefUpdate],
     callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitPushError> {
+    push_updates_and_tags(repo, git_repo, remote_name, updates, &[], callbacks)
+}
+
+fn push_updates_and_tags(
+    repo: &dyn Repo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    updates: &[GitRefUpdate],
+    tag_ref_names: &[String],
+    callbacks: RemoteCallbacks<'_>,
 ) -> Result<(), GitPushError> {
     let mut qualified_remote_refs_expected_locations = HashMap::new();
     let mut refspecs = vec![];
@@ -1515,6 +1886,11 @@ pub fn push_updates(
             refspecs.push(format!(":{}", update.qualified_name));
         }
     }
+    for ref_name in tag_ref_names {
+        // Tags are pushed as they are in the Git repo, which may be annotated
+        // tag objects rather than commits.
+        refspecs.push(format!("{ref_name}:{ref_name}"));
+    }
     // TODO(ilyagr): `push_refs`, or parts of it, should probably be inlined. This
     // requires adjusting some tests.
     push_refs(
@@ -1522,6 +1898,7 @@ pub fn push_updates(
         git_repo,
         remote_name,
         &qualified_remote_refs_expected_locations,
+        tag_ref_names,
         &refspecs,
         callbacks,
     )
@@ -1532,6 +1909,7 @@ fn push_refs(
     git_repo: &git2::Repository,
     remote_name: &str,
     qualified_remote_refs_expected_locations: &HashMap<&str, Option<&CommitId>>,
+    tag_ref_names: &[String],
     refspecs: &[String],
     callbacks: RemoteCallbacks<'_>,
 ) -> Result<(), GitPushError> {
@@ -1548,19 +1926,30 @@ fn push_refs(
     let mut remaining_remote_refs: HashSet<_> = qualified_remote_refs_expected_locations
         .keys()
         .copied()
+        .chain(tag_ref_names.iter().map(String::as_str))
         .collect();
     let mut failed_push_negotiations = vec![];
+    let mut existing_remote_tags = vec![];
     let push_result = {
         let mut push_options = git2::PushOptions::new();
-        let mut proxy_options = git2::ProxyOptions::new();
-        proxy_options.auto();
-        push_options.proxy_options(proxy_options);
+        push_options.proxy_options(callbacks.proxy_options(remote.url()));
         let mut callbacks = callbacks.into_git();
         callbacks.push_negotiation(|updates| {
             for update in updates {
                 let dst_refname = update
                     .dst_refname()
                     .expect("Expect reference name to be valid UTF-8");
+                if tag_ref_names.iter().any(|name| name == dst_refname) {
+                    // Unlike bookmarks, tags are never moved on the remote.
+                    if !update.src().is_zero() && update.src() != update.dst() {
+                        tracing::info!(
+                            "Cannot push {dst_refname}; it already exists on the remote at {}",
+                            update.src()
+                        );
+                        existing_remote_tags.push(dst_refname.to_string());
+                    }
+                    continue;
+                }
                 let expected_remote_location = *qualified_remote_refs_expected_locations
                     .get(dst_refname)
                     .expect("Push is trying to move a ref it wasn't asked to move");
@@ -1610,7 +1999,7 @@ fn push_refs(
                     }
                 }
             }
-            if failed_push_negotiations.is_empty() {
+            if failed_push_negotiations.is_empty() && existing_remote_tags.is_empty() {
                 Ok(())
             } else {
                 Err(git2::Error::from_str("failed push negotiation"))
@@ -1638,6 +2027,14 @@ fn push_refs(
         Err(GitPushError::RefInUnexpectedLocation(
             failed_push_negotiations,
         ))
+    } else if !existing_remote_tags.is_empty() {
+        assert!(push_result.is_err());
+        let mut tag_names = existing_remote_tags
+            .iter()
+            .map(|name| name.strip_prefix("refs/tags/").unwrap_or(name).to_owned())
+            .collect_vec();
+        tag_names.sort();
+        Err(GitPushError::TagAlreadyExists(tag_names))
     } else {
         push_result?;
         if remaining_remote_refs.is_empty() {
@@ -1712,6 +2109,360 @@ fn allow_push(
     }
 }
 
+/// Ref namespace of the advisory file locks created in this repo.
+const FILE_LOCK_REF_PREFIX: &str = "refs/jj/locks/";
+/// Ref namespace of the advisory file locks fetched from remotes.
+const REMOTE_FILE_LOCK_REF_PREFIX: &str = "refs/jj/remote-locks/";
+
+/// Advisory lock on a file, stored as a Git ref.
+///
+/// The ref points to an empty commit authored by the lock owner. Locks are
+/// merely advisory; nothing prevents other users from editing locked files.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitFileLock {
+    pub path: RepoPathBuf,
+    pub owner_name: String,
+    pub owner_email: String,
+    /// Remote the lock was fetched from, or `None` if the lock was created in
+    /// this repo.
+    pub remote: Option<String>,
+    /// Git commit the lock ref points to.
+    pub id: CommitId,
+}
+
+#[derive(Error, Debug)]
+pub enum GitFileLockError {
+    #[error("Failed to push lock to remote")]
+    Push(#[from] GitPushError),
+    #[error("Unexpected git error when updating file lock")]
+    InternalGitError(#[from] git2::Error),
+}
+
+/// Returns the qualified Git ref name of the lock on the given `path`.
+///
+/// The path is hex-encoded so that any file name can be mapped to a valid
+/// ref name.
+pub fn file_lock_ref_name(path: &RepoPath) -> String {
+    let encoded = hex::encode(path.as_internal_file_string());
+    format!("{FILE_LOCK_REF_PREFIX}{encoded}")
+}
+
+/// Returns true if there are file locks created in this repo or fetched from
+/// remotes. This is cheaper than listing the locks.
+pub fn has_file_locks(
+    git_repo: &gix::Repository,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let git_references = git_repo.references()?;
+    for prefix in [FILE_LOCK_REF_PREFIX, REMOTE_FILE_LOCK_REF_PREFIX] {
+        if git_references.prefixed(prefix)?.next().is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Lists the file locks created in this repo and fetched from remotes.
+pub fn get_file_locks(git_repo: &git2::Repository) -> Result<Vec<GitFileLock>, git2::Error> {
+    let mut locks = vec![];
+    let local_refs = git_repo.references_glob(&format!("{FILE_LOCK_REF_PREFIX}*"))?;
+    let remote_refs = git_repo.references_glob(&format!("{REMOTE_FILE_LOCK_REF_PREFIX}*"))?;
+    for git_ref in local_refs.chain(remote_refs) {
+        let git_ref = git_ref?;
+        let Some(ref_name) = git_ref.name() else {
+            continue;
+        };
+        let (remote, encoded_path) =
+            if let Some(encoded_path) = ref_name.strip_prefix(FILE_LOCK_REF_PREFIX) {
+                (None, encoded_path)
+            } else if let Some(rest) = ref_name.strip_prefix(REMOTE_FILE_LOCK_REF_PREFIX) {
+                // Remote names may contain '/', but the hex-encoded path can't.
+                let Some((remote, encoded_path)) = rest.rsplit_once('/') else {
+                    continue;
+                };
+                (Some(remote.to_owned()), encoded_path)
+            } else {
+                continue;
+            };
+        let Some(path) = hex::decode(encoded_path)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .and_then(|path| RepoPathBuf::from_relative_path(path).ok())
+        else {
+            continue;
+        };
+        let commit = git_ref.peel_to_commit()?;
+        let author = commit.author();
+        let from_placeholder = |bytes: &[u8]| {
+            if bytes == EMPTY_STRING_PLACEHOLDER.as_bytes() {
+                String::new()
+            } else {
+                String::from_utf8_lossy(bytes).into_owned()
+            }
+        };
+        locks.push(GitFileLock {
+            path,
+            owner_name: from_placeholder(author.name_bytes()),
+            owner_email: from_placeholder(author.email_bytes()),
+            remote,
+            id: CommitId::from_bytes(commit.id().as_bytes()),
+        });
+    }
+    Ok(locks)
+}
+
+/// Records a lock on the given `path` owned by `signature`, and publishes it
+/// to `remote_name` if specified.
+///
+/// Pushing fails if the lock has been taken on the remote in the meantime.
+pub fn lock_file(
+    repo: &dyn Repo,
+    git_repo: &git2::Repository,
+    path: &RepoPath,
+    signature: &Signature,
+    remote_name: Option<&str>,
+    callbacks: RemoteCallbacks<'_>,
+) -> Result<GitFileLock, GitFileLockError> {
+    // git does not support empty names or emails
+    let git_signature = git2::Signature::new(
+        non_empty_or_placeholder(&signature.name),
+        non_empty_or_placeholder(&signature.email),
+        &git2::Time::new(
+            signature.timestamp.timestamp.0.div_euclid(1000),
+            signature.timestamp.tz_offset,
+        ),
+    )?;
+    let empty_tree_id = git_repo.treebuilder(None)?.write()?;
+    let empty_tree = git_repo.find_tree(empty_tree_id)?;
+    let message = format!("Lock {}\n", path.as_internal_file_string());
+    let oid = git_repo.commit(
+        None,
+        &git_signature,
+        &git_signature,
+        &message,
+        &empty_tree,
+        &[],
+    )?;
+    let id = CommitId::from_bytes(oid.as_bytes());
+    if let Some(remote_name) = remote_name {
+        // If we already hold the lock, the remote ref is expected to point to
+        // the lock we pushed previously.
+        let remote_ref_name = remote_file_lock_ref_name(remote_name, path);
+        let expected_current_target = match git_repo.find_reference(&remote_ref_name) {
+            Ok(git_ref) => Some(CommitId::from_bytes(
+                git_ref.peel_to_commit()?.id().as_bytes(),
+            )),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+        let update = GitRefUpdate {
+            qualified_name: file_lock_ref_name(path),
+            expected_current_target,
+            new_target: Some(id.clone()),
+        };
+        push_updates(repo, git_repo, remote_name, &[update], callbacks)?;
+        git_repo.reference(&remote_ref_name, oid, true, "jj file lock")?;
+    }
+    git_repo.reference(&file_lock_ref_name(path), oid, true, "jj file lock")?;
+    Ok(GitFileLock {
+        path: path.to_owned(),
+        owner_name: signature.name.clone(),
+        owner_email: signature.email.clone(),
+        remote: None,
+        id,
+    })
+}
+
+/// Removes the given `lock`, deleting it from the remote it was fetched from
+/// or from `remote_name` if specified.
+pub fn unlock_file(
+    repo: &dyn Repo,
+    git_repo: &git2::Repository,
+    lock: &GitFileLock,
+    remote_name: Option<&str>,
+    callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitFileLockError> {
+    if let Some(remote_name) = lock.remote.as_deref().or(remote_name) {
+        let update = GitRefUpdate {
+            qualified_name: file_lock_ref_name(&lock.path),
+            expected_current_target: Some(lock.id.clone()),
+            new_target: None,
+        };
+        push_updates(repo, git_repo, remote_name, &[update], callbacks)?;
+        delete_git_ref_if_exists(
+            git_repo,
+            &remote_file_lock_ref_name(remote_name, &lock.path),
+        )?;
+    }
+    delete_git_ref_if_exists(git_repo, &file_lock_ref_name(&lock.path))?;
+    Ok(())
+}
+
+fn non_empty_or_placeholder(value: &str) -> &str {
+    if value.is_empty() {
+        EMPTY_STRING_PLACEHOLDER
+    } else {
+        value
+    }
+}
+
+fn remote_file_lock_ref_name(remote_name: &str, path: &RepoPath) -> String {
+    let encoded = hex::encode(path.as_internal_file_string());
+    format!("{REMOTE_FILE_LOCK_REF_PREFIX}{remote_name}/{encoded}")
+}
+
+fn delete_git_ref_if_exists(git_repo: &git2::Repository, name: &str) -> Result<(), git2::Error> {
+    match git_repo.find_reference(name) {
+        Ok(mut git_ref) => git_ref.delete(),
+        Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GitTagSignError {
+    #[error("No tag named '{0}' in the Git repository")]
+    NoSuchTag(String),
+    #[error("Failed to sign tag '{0}'")]
+    Sign(String, #[source] SignError),
+    #[error("Unexpected git error when signing tag")]
+    InternalGitError(#[from] git2::Error),
+}
+
+/// Lightweight tag replaced with a signed tag by [`sign_tag()`].
+#[derive(Clone, Debug)]
+pub struct SignedTag {
+    pub name: String,
+    /// Commit the lightweight tag pointed to.
+    pub commit_id: CommitId,
+    tag_oid: Oid,
+}
+
+/// Replaces the lightweight tag `name` in the Git repository with an annotated
+/// tag object signed by the `signer`, with `tagger` as the tagger.
+///
+/// Returns `None` without changing anything if the tag is already annotated.
+/// The lightweight tag can be restored by [`unsign_tag()`].
+pub fn sign_tag(
+    git_repo: &git2::Repository,
+    name: &str,
+    tagger: &Signature,
+    signer: &Signer,
+    key: Option<&str>,
+) -> Result<Option<SignedTag>, GitTagSignError> {
+    let ref_name = format!("refs/tags/{name}");
+    let reference = match git_repo.find_reference(&ref_name) {
+        Ok(reference) => reference,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => {
+            return Err(GitTagSignError::NoSuchTag(name.to_owned()));
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let target = reference
+        .target()
+        .ok_or_else(|| GitTagSignError::NoSuchTag(name.to_owned()))?;
+    if git_repo.find_object(target, None)?.kind() != Some(git2::ObjectType::Commit) {
+        return Ok(None);
+    }
+    let tag_oid = write_tag_object(git_repo, name, target, name, tagger, Some((signer, key)))?;
+    git_repo.reference_matching(&ref_name, tag_oid, true, target, "jj sign tag")?;
+    Ok(Some(SignedTag {
+        name: name.to_owned(),
+        commit_id: CommitId::from_bytes(target.as_bytes()),
+        tag_oid,
+    }))
+}
+
+/// Points the tag signed by [`sign_tag()`] back to the commit, e.g. if
+/// pushing the signed tag failed.
+pub fn unsign_tag(git_repo: &git2::Repository, signed_tag: &SignedTag) -> Result<(), git2::Error> {
+    let ref_name = format!("refs/tags/{}", signed_tag.name);
+    let target = Oid::from_bytes(signed_tag.commit_id.as_bytes())?;
+    git_repo.reference_matching(
+        &ref_name,
+        target,
+        true,
+        signed_tag.tag_oid,
+        "jj restore unsigned tag",
+    )?;
+    Ok(())
+}
+
+/// Annotation of a tag created by [`create_tag()`].
+pub struct TagAnnotation<'a> {
+    pub message: &'a str,
+    pub tagger: &'a Signature,
+    /// Signer and key to sign the tag object with, if any.
+    pub signer: Option<(&'a Signer, Option<&'a str>)>,
+}
+
+/// Creates the tag `name` pointing to `target` in the Git repository.
+///
+/// Without an `annotation`, a lightweight tag is created. Fails if the tag
+/// already exists.
+pub fn create_tag(
+    git_repo: &git2::Repository,
+    name: &str,
+    target: &CommitId,
+    annotation: Option<&TagAnnotation>,
+) -> Result<(), GitTagSignError> {
+    let ref_name = format!("refs/tags/{name}");
+    let target = Oid::from_bytes(target.as_bytes())?;
+    let ref_target = match annotation {
+        Some(annotation) => write_tag_object(
+            git_repo,
+            name,
+            target,
+            annotation.message,
+            annotation.tagger,
+            annotation.signer,
+        )?,
+        None => target,
+    };
+    git_repo.reference(&ref_name, ref_target, false, "jj tag create")?;
+    Ok(())
+}
+
+/// Deletes the tag `name` from the Git repository if it exists.
+pub fn delete_tag(git_repo: &git2::Repository, name: &str) -> Result<(), git2::Error> {
+    delete_git_ref_if_exists(git_repo, &format!("refs/tags/{name}"))
+}
+
+fn write_tag_object(
+    git_repo: &git2::Repository,
+    name: &str,
+    target: Oid,
+    message: &str,
+    tagger: &Signature,
+    signer: Option<(&Signer, Option<&str>)>,
+) -> Result<Oid, GitTagSignError> {
+    let offset = tagger.timestamp.tz_offset;
+    let mut data = format!(
+        "object {target}\ntype commit\ntag {name}\ntagger {tagger_name} <{tagger_email}> \
+         {seconds} {sign}{hours:02}{minutes:02}\n\n{message}",
+        tagger_name = non_empty_or_placeholder(&tagger.name),
+        tagger_email = non_empty_or_placeholder(&tagger.email),
+        seconds = tagger.timestamp.timestamp.0.div_euclid(1000),
+        sign = if offset < 0 { '-' } else { '+' },
+        hours = offset.abs() / 60,
+        minutes = offset.abs() % 60,
+    )
+    .into_bytes();
+    if !data.ends_with(b"\n") {
+        data.push(b'\n');
+    }
+    if let Some((signer, key)) = signer {
+        let signature = signer
+            .sign(&data, key)
+            .map_err(|err| GitTagSignError::Sign(name.to_owned(), err))?;
+        // Git expects the signature to follow the message.
+        data.extend_from_slice(&signature);
+        if !data.ends_with(b"\n") {
+            data.push(b'\n');
+        }
+    }
+    Ok(git_repo.odb()?.write(git2::ObjectType::Tag, &data)?)
+}
+
 #[non_exhaustive]
 #[derive(Default)]
 #[allow(clippy::type_complexity)]
@@ -1721,9 +2472,32 @@ pub struct RemoteCallbacks<'a> {
     pub get_ssh_keys: Option<&'a mut dyn FnMut(&str) -> Vec<PathBuf>>,
     pub get_password: Option<&'a mut dyn FnMut(&str, &str) -> Option<String>>,
     pub get_username_password: Option<&'a mut dyn FnMut(&str) -> Option<(String, String)>>,
+    /// Don't ask the Git credential helpers for credentials.
+    pub skip_credential_helper: bool,
+    /// Don't ask the SSH agent for keys.
+    pub skip_ssh_agent: bool,
+    /// Proxy to connect to HTTP(S) remotes through, instead of the one
+    /// configured by Git or the environment.
+    pub proxy: Option<&'a str>,
+    /// Hosts to connect to without going through a proxy.
+    pub no_proxy: &'a [String],
 }
 
 impl<'a> RemoteCallbacks<'a> {
+    /// Returns the proxy options to connect to the remote at `url` with.
+    fn proxy_options(&self, url: Option<&str>) -> git2::ProxyOptions<'static> {
+        let mut proxy_options = git2::ProxyOptions::new();
+        let host = url.and_then(url_host);
+        if host.is_some_and(|host| is_no_proxy_host(self.no_proxy, host)) {
+            // Leave the proxy unset to connect directly.
+        } else if let Some(proxy) = self.proxy {
+            proxy_options.url(proxy);
+        } else {
+            proxy_options.auto();
+        }
+        proxy_options
+    }
+
     fn into_git(mut self) -> git2::RemoteCallbacks<'a> {
         let mut callbacks = git2::RemoteCallbacks::new();
         if let Some(progress_cb) = self.progress {
@@ -1745,15 +2519,19 @@ impl<'a> RemoteCallbacks<'a> {
         }
         // TODO: We should expose the callbacks to the caller instead -- the library
         // crate shouldn't read environment variables.
-        let mut tried_ssh_agent = false;
+        let mut tried_ssh_agent = self.skip_ssh_agent;
+        let skip_credential_helper = self.skip_credential_helper;
         let mut ssh_key_paths_to_try: Option<Vec<PathBuf>> = None;
         callbacks.credentials(move |url, username_from_url, allowed_types| {
             let span = tracing::debug_span!("RemoteCallbacks.credentials");
             let _ = span.enter();
 
-            let git_config = git2::Config::open_default();
-            let credential_helper = git_config
-                .and_then(|conf| git2::Cred::credential_helper(&conf, url, username_from_url));
+            let credential_helper = if skip_credential_helper {
+                Err(git2::Error::from_str("credential helper disabled"))
+            } else {
+                git2::Config::open_default()
+                    .and_then(|conf| git2::Cred::credential_helper(&conf, url, username_from_url))
+            };
             if let Ok(creds) = credential_helper {
                 tracing::info!("using credential_helper");
                 return Ok(creds);
@@ -1820,6 +2598,37 @@ impl<'a> RemoteCallbacks<'a> {
     }
 }
 
+/// Returns the host name of a remote URL, either `scheme://[user@]host[:port]/`
+/// or scp-like `[user@]host:path`.
+fn url_host(url: &str) -> Option<&str> {
+    let (authority, is_scp_like) = match url.split_once("://") {
+        Some((_, rest)) => (rest.split('/').next()?, false),
+        None => (url.split(':').next()?, true),
+    };
+    let host_port = authority.rsplit('@').next()?;
+    let host = if let Some(bracketed) = host_port.strip_prefix('[') {
+        bracketed.split(']').next()?
+    } else if is_scp_like {
+        host_port
+    } else {
+        host_port.split(':').next()?
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// Whether the `host` matches any of the `no_proxy` patterns. Like `$NO_PROXY`,
+/// `*` matches all hosts, and a domain matches itself and its subdomains.
+fn is_no_proxy_host(no_proxy: &[String], host: &str) -> bool {
+    no_proxy.iter().any(|pattern| {
+        let domain = pattern.trim_start_matches('.');
+        pattern == "*"
+            || host.eq_ignore_ascii_case(domain)
+            || host
+                .to_ascii_lowercase()
+                .ends_with(&format!(".{}", domain.to_ascii_lowercase()))
+    })
+}
+
 pub struct Progress {
     /// `Some` iff data transfer is currently in progress
     pub bytes_downloaded: Option<u64>,
@@ -1905,3 +2714,35 @@ pub fn parse_gitmodules(
         .collect();
     Ok(ret)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("https://example.com/repo.git"),
+            Some("example.com")
+        );
+        assert_eq!(
+            url_host("https://user@example.com:8443/repo.git"),
+            Some("example.com")
+        );
+        assert_eq!(url_host("ssh://git@[::1]:22/repo.git"), Some("::1"));
+        assert_eq!(url_host("git@example.com:repo.git"), Some("example.com"));
+        assert_eq!(url_host("file:///path/to/repo"), None);
+    }
+
+    #[test]
+    fn test_is_no_proxy_host() {
+        let no_proxy = ["localhost".to_owned(), ".internal.example.com".to_owned()];
+        assert!(is_no_proxy_host(&no_proxy, "localhost"));
+        assert!(is_no_proxy_host(&no_proxy, "internal.example.com"));
+        assert!(is_no_proxy_host(&no_proxy, "git.INTERNAL.example.com"));
+        assert!(!is_no_proxy_host(&no_proxy, "example.com"));
+        assert!(!is_no_proxy_host(&no_proxy, "notinternal.example.com"));
+        assert!(is_no_proxy_host(&["*".to_owned()], "example.com"));
+        assert!(!is_no_proxy_host(&[], "example.com"));
+    }
+}
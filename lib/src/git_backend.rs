@@ -601,7 +601,7 @@ fn commit_from_git_without_root_parent(
     })
 }
 
-const EMPTY_STRING_PLACEHOLDER: &str = "JJ_EMPTY_STRING";
+pub(crate) const EMPTY_STRING_PLACEHOLDER: &str = "JJ_EMPTY_STRING";
 
 fn signature_from_git(signature: gix::actor::SignatureRef) -> Signature {
     let name = signature.name;
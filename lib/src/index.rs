@@ -71,6 +71,7 @@ pub trait IndexStore: Send + Sync + Debug {
         &self,
         index: Box<dyn MutableIndex>,
         op: &Operation,
+        store: &Arc<Store>,
     ) -> Result<Box<dyn ReadonlyIndex>, IndexWriteError>;
 }
 
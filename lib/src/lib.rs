@@ -66,16 +66,22 @@ pub mod merged_tree;
 pub mod object_id;
 pub mod op_heads_store;
 pub mod op_store;
+#[cfg(feature = "git")]
+pub mod op_sync;
 pub mod op_walk;
 pub mod operation;
+pub mod opset;
 #[allow(missing_docs)]
 pub mod protos;
 pub mod refs;
 pub mod repo;
+pub mod repo_archive;
 pub mod repo_path;
 pub mod revset;
 mod revset_parser;
 pub mod rewrite;
+#[cfg(feature = "s3")]
+pub mod s3_backend;
 #[cfg(feature = "testing")]
 pub mod secret_backend;
 pub mod settings;
@@ -31,6 +31,8 @@ use std::iter;
 use std::mem;
 use std::ops::Range;
 #[cfg(unix)]
+use std::os::unix::fs::MetadataExt as _;
+#[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 use std::path::PathBuf;
@@ -71,7 +73,10 @@ use crate::conflicts::materialize_tree_value;
 use crate::conflicts::ConflictMarkerStyle;
 use crate::conflicts::MaterializedTreeValue;
 use crate::file_util::check_symlink_support;
+use crate::file_util::is_case_insensitive_dir;
 use crate::file_util::try_symlink;
+#[cfg(feature = "native-fsmonitor")]
+use crate::fsmonitor::native;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::watchman;
 use crate::fsmonitor::FsmonitorSettings;
@@ -102,12 +107,14 @@ use crate::tree::Tree;
 use crate::working_copy::CheckoutError;
 use crate::working_copy::CheckoutOptions;
 use crate::working_copy::CheckoutStats;
+use crate::working_copy::FileStateTrust;
 use crate::working_copy::LockedWorkingCopy;
 use crate::working_copy::ResetError;
 use crate::working_copy::SnapshotError;
 use crate::working_copy::SnapshotOptions;
 use crate::working_copy::SnapshotProgress;
 use crate::working_copy::SnapshotStats;
+use crate::working_copy::SymlinkPolicy;
 use crate::working_copy::UntrackedReason;
 use crate::working_copy::WorkingCopy;
 use crate::working_copy::WorkingCopyFactory;
@@ -130,6 +137,8 @@ pub struct FileState {
     pub file_type: FileType,
     pub mtime: MillisSinceEpoch,
     pub size: u64,
+    /// Inode number, or 0 if unknown.
+    pub inode: u64,
     /* TODO: What else do we need here? Git stores a lot of fields.
      * TODO: Could possibly handle case-insensitive file systems keeping an
      *       Option<PathBuf> with the actual path here. */
@@ -147,6 +156,38 @@ impl FileState {
             file_type: FileType::Normal { executable },
             mtime: MillisSinceEpoch(0),
             size: 0,
+            inode: 0,
+        }
+    }
+
+    /// Whether the file can be assumed to be unchanged given its new state,
+    /// without looking at the contents.
+    ///
+    /// `own_mtime` is the mtime of the tree state file. Files at least
+    /// `rehash_throttle_size` large are considered unchanged if only their
+    /// mtime changed.
+    fn is_clean(
+        &self,
+        new: &Self,
+        trust: FileStateTrust,
+        own_mtime: MillisSinceEpoch,
+        rehash_throttle_size: u64,
+    ) -> bool {
+        if trust == FileStateTrust::Paranoid
+            || self.file_type != new.file_type
+            || self.size != new.size
+            || (trust == FileStateTrust::MtimeInode && self.inode != new.inode)
+        {
+            return false;
+        }
+        if self.mtime == new.mtime {
+            // If the file's mtime was set at the same time as this state file's own mtime,
+            // then we don't know if the file was modified before or after this state file.
+            self.mtime < own_mtime
+        } else {
+            // Builds tend to touch large files without changing them, so don't re-hash
+            // them if only the timestamps changed.
+            new.size >= rehash_throttle_size
         }
     }
 
@@ -160,6 +201,7 @@ impl FileState {
             file_type: FileType::Normal { executable },
             mtime: mtime_from_metadata(metadata),
             size,
+            inode: inode_from_metadata(metadata),
         }
     }
 
@@ -171,6 +213,7 @@ impl FileState {
             file_type: FileType::Symlink,
             mtime: mtime_from_metadata(metadata),
             size: metadata.len(),
+            inode: inode_from_metadata(metadata),
         }
     }
 
@@ -179,6 +222,7 @@ impl FileState {
             file_type: FileType::GitSubmodule,
             mtime: MillisSinceEpoch(0),
             size: 0,
+            inode: 0,
         }
     }
 }
@@ -389,6 +433,9 @@ pub struct TreeState {
     file_states: FileStatesMap,
     // Currently only path prefixes
     sparse_patterns: Vec<RepoPathBuf>,
+    /// Sorted paths in the tree that weren't checked out because the file
+    /// system can't represent them.
+    unrepresentable_paths: Vec<RepoPathBuf>,
     own_mtime: MillisSinceEpoch,
     symlink_support: bool,
 
@@ -396,6 +443,8 @@ pub struct TreeState {
     /// the repo is configured to use the Watchman filesystem monitor and
     /// Watchman has been queried at least once.
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
+    /// The most recent clock value returned by the native filesystem monitor.
+    native_fsmonitor_clock: Option<crate::protos::working_copy::NativeFsmonitorClock>,
 }
 
 fn file_state_from_proto(proto: &crate::protos::working_copy::FileState) -> FileState {
@@ -418,6 +467,7 @@ fn file_state_from_proto(proto: &crate::protos::working_copy::FileState) -> File
         file_type,
         mtime: MillisSinceEpoch(proto.mtime_millis_since_epoch),
         size: proto.size,
+        inode: proto.inode,
     }
 }
 
@@ -436,6 +486,7 @@ fn file_state_to_proto(file_state: &FileState) -> crate::protos::working_copy::F
     proto.file_type = file_type as i32;
     proto.mtime_millis_since_epoch = file_state.mtime.0;
     proto.size = file_state.size;
+    proto.inode = file_state.inode;
     proto
 }
 
@@ -609,6 +660,123 @@ fn can_create_new_file(disk_path: &Path) -> Result<bool, CheckoutError> {
 
 const RESERVED_DIR_NAMES: &[&str] = &[".git", ".jj"];
 
+/// Device names that can't be used as file names on Windows, even with an
+/// extension.
+const RESERVED_WINDOWS_FILE_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Returns true if any component of the path is a reserved device name on
+/// Windows, e.g. "aux" or "nul.txt".
+fn has_reserved_windows_file_name(path: &RepoPath) -> bool {
+    path.components().any(|component| {
+        let name = component.as_internal_str();
+        let stem = name.split('.').next().unwrap().trim_end_matches(' ');
+        RESERVED_WINDOWS_FILE_NAMES
+            .iter()
+            .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+    })
+}
+
+/// Detects paths whose existing entries on disk are named differently in case,
+/// which can happen only on case-insensitive file systems. For example, if the
+/// tree contains both "Foo" and "foo", only one of them can be checked out.
+struct CaseMismatchChecker {
+    state_path: PathBuf,
+    case_insensitive: Option<bool>,
+    // Directories known to exist with the expected name
+    exact_dirs: HashSet<PathBuf>,
+}
+
+impl CaseMismatchChecker {
+    fn new(state_path: PathBuf) -> Self {
+        CaseMismatchChecker {
+            state_path,
+            case_insensitive: None,
+            exact_dirs: HashSet::new(),
+        }
+    }
+
+    fn is_case_insensitive(&mut self) -> bool {
+        *self
+            .case_insensitive
+            .get_or_insert_with(|| is_case_insensitive_dir(&self.state_path).unwrap_or(false))
+    }
+
+    /// Returns true if any of the existing parent directories of `disk_path`
+    /// below `working_copy_path` is named differently in case.
+    fn parent_dirs_differ_in_case(
+        &mut self,
+        working_copy_path: &Path,
+        disk_path: &Path,
+    ) -> Result<bool, CheckoutError> {
+        if !self.is_case_insensitive() {
+            return Ok(false);
+        }
+        let mut checked_dirs = vec![];
+        for dir in disk_path.ancestors().skip(1) {
+            if dir == working_copy_path || self.exact_dirs.contains(dir) {
+                break;
+            }
+            if entry_differs_in_case(dir)? {
+                return Ok(true);
+            }
+            checked_dirs.push(dir.to_owned());
+        }
+        self.exact_dirs.extend(checked_dirs);
+        Ok(false)
+    }
+
+    /// Returns true if the existing entry at `disk_path` is named differently
+    /// in case.
+    fn entry_differs_in_case(&mut self, disk_path: &Path) -> Result<bool, CheckoutError> {
+        if !self.is_case_insensitive() {
+            return Ok(false);
+        }
+        entry_differs_in_case(disk_path)
+    }
+}
+
+/// Removes `path` from the sorted `paths`. Returns true if it was there.
+fn remove_sorted_path(paths: &mut Vec<RepoPathBuf>, path: &RepoPath) -> bool {
+    match paths.binary_search_by(|p| p.as_ref().cmp(path)) {
+        Ok(index) => {
+            paths.remove(index);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Inserts `path` into the sorted `paths` unless it's already there.
+fn insert_sorted_path(paths: &mut Vec<RepoPathBuf>, path: RepoPathBuf) {
+    if let Err(index) = paths.binary_search(&path) {
+        paths.insert(index, path);
+    }
+}
+
+fn entry_differs_in_case(disk_path: &Path) -> Result<bool, CheckoutError> {
+    let parent_dir_path = disk_path.parent().expect("content path shouldn't be root");
+    let name = disk_path.file_name().unwrap();
+    let read_dir_error = |err: io::Error| CheckoutError::Other {
+        message: format!("Failed to read directory {}", parent_dir_path.display()),
+        err: err.into(),
+    };
+    let mut found_other_case = false;
+    for entry in fs::read_dir(parent_dir_path).map_err(read_dir_error)? {
+        let entry_name = entry.map_err(read_dir_error)?.file_name();
+        if entry_name == name {
+            return Ok(false);
+        }
+        // The name may also differ in Unicode normalization, which shouldn't
+        // be considered a mismatch.
+        found_other_case |=
+            entry_name.to_string_lossy().to_lowercase() == name.to_string_lossy().to_lowercase();
+    }
+    Ok(found_other_case)
+}
+
 /// Suppose the `disk_path` exists, checks if the last component points to
 /// ".git" or ".jj" in the same parent directory.
 fn reject_reserved_existing_path(disk_path: &Path) -> Result<(), CheckoutError> {
@@ -651,6 +819,16 @@ fn mtime_from_metadata(metadata: &Metadata) -> MillisSinceEpoch {
     )
 }
 
+#[cfg(unix)]
+fn inode_from_metadata(metadata: &Metadata) -> u64 {
+    metadata.ino()
+}
+
+#[cfg(windows)]
+fn inode_from_metadata(_metadata: &Metadata) -> u64 {
+    0
+}
+
 fn file_state(metadata: &Metadata) -> Option<FileState> {
     let metadata_file_type = metadata.file_type();
     let file_type = if metadata_file_type.is_dir() {
@@ -672,10 +850,12 @@ fn file_state(metadata: &Metadata) -> Option<FileState> {
     file_type.map(|file_type| {
         let mtime = mtime_from_metadata(metadata);
         let size = metadata.len();
+        let inode = inode_from_metadata(metadata);
         FileState {
             file_type,
             mtime,
             size,
+            inode,
         }
     })
 }
@@ -683,6 +863,7 @@ fn file_state(metadata: &Metadata) -> Option<FileState> {
 struct FsmonitorMatcher {
     matcher: Option<Box<dyn Matcher>>,
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
+    native_fsmonitor_clock: Option<crate::protos::working_copy::NativeFsmonitorClock>,
 }
 
 #[derive(Debug, Error)]
@@ -719,6 +900,20 @@ impl TreeState {
         &self.sparse_patterns
     }
 
+    pub fn unrepresentable_paths(&self) -> &[RepoPathBuf] {
+        &self.unrepresentable_paths
+    }
+
+    /// Returns true if symlinks are materialized as real symlinks rather than
+    /// placeholder files.
+    fn materializes_native_symlinks(&self, policy: SymlinkPolicy) -> bool {
+        match policy {
+            SymlinkPolicy::Auto => self.symlink_support,
+            SymlinkPolicy::Native => true,
+            SymlinkPolicy::Placeholder => false,
+        }
+    }
+
     fn sparse_matcher(&self) -> Box<dyn Matcher> {
         Box::new(PrefixMatcher::new(&self.sparse_patterns))
     }
@@ -742,9 +937,11 @@ impl TreeState {
             tree_id,
             file_states: FileStatesMap::new(),
             sparse_patterns: vec![RepoPathBuf::root()],
+            unrepresentable_paths: vec![],
             own_mtime: MillisSinceEpoch(0),
             symlink_support: check_symlink_support().unwrap_or(false),
             watchman_clock: None,
+            native_fsmonitor_clock: None,
         }
     }
 
@@ -807,7 +1004,14 @@ impl TreeState {
         self.file_states =
             FileStatesMap::from_proto(proto.file_states, proto.is_file_states_sorted);
         self.sparse_patterns = sparse_patterns_from_proto(proto.sparse_patterns.as_ref());
+        self.unrepresentable_paths = proto
+            .unrepresentable_paths
+            .iter()
+            .map(RepoPathBuf::from_internal_string)
+            .sorted()
+            .collect();
         self.watchman_clock = proto.watchman_clock;
+        self.native_fsmonitor_clock = proto.native_fsmonitor_clock;
         Ok(())
     }
 
@@ -834,7 +1038,13 @@ impl TreeState {
                 .push(path.as_internal_file_string().to_owned());
         }
         proto.sparse_patterns = Some(sparse_patterns);
+        proto.unrepresentable_paths = self
+            .unrepresentable_paths
+            .iter()
+            .map(|path| path.as_internal_file_string().to_owned())
+            .collect();
         proto.watchman_clock = self.watchman_clock.clone();
+        proto.native_fsmonitor_clock = self.native_fsmonitor_clock.clone();
 
         let mut temp_file = NamedTempFile::new_in(&self.state_path).unwrap();
         temp_file
@@ -887,6 +1097,22 @@ impl TreeState {
         Ok(changed_files)
     }
 
+    #[cfg(feature = "native-fsmonitor")]
+    fn native_fsmonitor(&self) -> native::Fsmonitor {
+        native::Fsmonitor::new(self.state_path.join("fsmonitor"))
+    }
+
+    #[cfg(feature = "native-fsmonitor")]
+    #[instrument(skip(self))]
+    pub fn query_native_fsmonitor(
+        &self,
+    ) -> Result<(Option<native::Clock>, Option<Vec<PathBuf>>), TreeStateError> {
+        let previous_clock = self.native_fsmonitor_clock.clone().map(native::Clock::from);
+        self.native_fsmonitor()
+            .query_changed_files(previous_clock.as_ref())
+            .map_err(|err| TreeStateError::Fsmonitor(Box::new(err)))
+    }
+
     #[cfg(feature = "watchman")]
     #[tokio::main(flavor = "current_thread")]
     #[instrument(skip(self))]
@@ -918,27 +1144,42 @@ impl TreeState {
             ref fsmonitor_settings,
             progress,
             start_tracking_matcher,
+            snapshot_matcher,
             max_new_file_size,
+            file_state_trust,
+            rehash_throttle_size,
             conflict_marker_style,
+            symlink_policy,
         } = options;
 
         let sparse_matcher = self.sparse_matcher();
 
-        let fsmonitor_clock_needs_save = *fsmonitor_settings != FsmonitorSettings::None;
+        // The fsmonitor clock can't be advanced by a partial snapshot since
+        // that would lose the changes to the paths that weren't looked at.
+        let fsmonitor_clock_needs_save =
+            *fsmonitor_settings != FsmonitorSettings::None && snapshot_matcher.is_none();
         let mut is_dirty = fsmonitor_clock_needs_save;
         let FsmonitorMatcher {
             matcher: fsmonitor_matcher,
             watchman_clock,
+            native_fsmonitor_clock,
         } = self.make_fsmonitor_matcher(fsmonitor_settings)?;
+        if fsmonitor_clock_needs_save {
+            self.watchman_clock = watchman_clock;
+            self.native_fsmonitor_clock = native_fsmonitor_clock;
+        }
         let fsmonitor_matcher = match fsmonitor_matcher.as_ref() {
             None => &EverythingMatcher,
             Some(fsmonitor_matcher) => fsmonitor_matcher.as_ref(),
         };
+        let fsmonitor_matcher = IntersectionMatcher::new(
+            fsmonitor_matcher,
+            snapshot_matcher.unwrap_or(&EverythingMatcher),
+        );
 
         let matcher = IntersectionMatcher::new(sparse_matcher.as_ref(), fsmonitor_matcher);
         if matcher.visit(RepoPath::root()).is_nothing() {
             // No need to load the current tree, set up channels, etc.
-            self.watchman_clock = watchman_clock;
             return Ok((is_dirty, SnapshotStats::default()));
         }
 
@@ -961,7 +1202,10 @@ impl TreeState {
                 error: OnceLock::new(),
                 progress,
                 max_new_file_size,
+                file_state_trust,
+                rehash_throttle_size,
                 conflict_marker_style,
+                symlink_policy,
             };
             let directory_to_visit = DirectoryToVisit {
                 dir: RepoPathBuf::root(),
@@ -1019,7 +1263,6 @@ impl TreeState {
             let state_paths: HashSet<_> = file_states.paths().map(|path| path.to_owned()).collect();
             assert_eq!(state_paths, tree_paths);
         }
-        self.watchman_clock = watchman_clock;
         Ok((is_dirty, stats))
     }
 
@@ -1028,15 +1271,25 @@ impl TreeState {
         &self,
         fsmonitor_settings: &FsmonitorSettings,
     ) -> Result<FsmonitorMatcher, SnapshotError> {
-        let (watchman_clock, changed_files) = match fsmonitor_settings {
-            FsmonitorSettings::None => (None, None),
-            FsmonitorSettings::Test { changed_files } => (None, Some(changed_files.clone())),
+        let (watchman_clock, native_fsmonitor_clock, changed_files) = match fsmonitor_settings {
+            FsmonitorSettings::None => (None, None, None),
+            FsmonitorSettings::Test { changed_files } => (None, None, Some(changed_files.clone())),
             #[cfg(feature = "watchman")]
             FsmonitorSettings::Watchman(config) => match self.query_watchman(config) {
-                Ok((watchman_clock, changed_files)) => (Some(watchman_clock.into()), changed_files),
+                Ok((watchman_clock, changed_files)) => {
+                    (Some(watchman_clock.into()), None, changed_files)
+                }
+                Err(err) => {
+                    tracing::warn!(?err, "Failed to query filesystem monitor");
+                    (None, None, None)
+                }
+            },
+            #[cfg(feature = "native-fsmonitor")]
+            FsmonitorSettings::Native => match self.query_native_fsmonitor() {
+                Ok((clock, changed_files)) => (None, clock.map(Into::into), changed_files),
                 Err(err) => {
                     tracing::warn!(?err, "Failed to query filesystem monitor");
-                    (None, None)
+                    (None, None, None)
                 }
             },
             #[cfg(not(feature = "watchman"))]
@@ -1048,6 +1301,16 @@ impl TreeState {
                         .into(),
                 });
             }
+            #[cfg(not(feature = "native-fsmonitor"))]
+            FsmonitorSettings::Native => {
+                return Err(SnapshotError::Other {
+                    message: "Failed to query the filesystem monitor".to_string(),
+                    err: "Cannot use the native filesystem monitor because jj was not compiled \
+                          with the `native-fsmonitor` feature (consider disabling \
+                          `fsmonitor.backend`)"
+                        .into(),
+                });
+            }
         };
         let matcher: Option<Box<dyn Matcher>> = match changed_files {
             None => None,
@@ -1059,12 +1322,19 @@ impl TreeState {
                         .collect_vec()
                 });
 
-                Some(Box::new(FilesMatcher::new(repo_paths)))
+                if *fsmonitor_settings == FsmonitorSettings::Native {
+                    // The native monitor reports created or renamed directories
+                    // without listing their contents.
+                    Some(Box::new(PrefixMatcher::new(repo_paths)))
+                } else {
+                    Some(Box::new(FilesMatcher::new(repo_paths)))
+                }
             }
         };
         Ok(FsmonitorMatcher {
             matcher,
             watchman_clock,
+            native_fsmonitor_clock,
         })
     }
 }
@@ -1101,7 +1371,10 @@ struct FileSnapshotter<'a> {
     error: OnceLock<SnapshotError>,
     progress: Option<&'a SnapshotProgress<'a>>,
     max_new_file_size: u64,
+    file_state_trust: FileStateTrust,
+    rehash_throttle_size: u64,
     conflict_marker_style: ConflictMarkerStyle,
+    symlink_policy: SymlinkPolicy,
 }
 
 impl FileSnapshotter<'_> {
@@ -1367,25 +1640,26 @@ impl FileSnapshotter<'_> {
                 // untracked
                 false
             }
-            Some(current_file_state) => {
-                // If the file's mtime was set at the same time as this state file's own mtime,
-                // then we don't know if the file was modified before or after this state file.
-                current_file_state == new_file_state
-                    && current_file_state.mtime < self.tree_state.own_mtime
-            }
+            Some(current_file_state) => current_file_state.is_clean(
+                new_file_state,
+                self.file_state_trust,
+                self.tree_state.own_mtime,
+                self.rehash_throttle_size,
+            ),
         };
         if clean {
             Ok(None)
         } else {
             let current_tree_values = self.current_tree.path_value(repo_path)?;
-            let new_file_type = if !self.tree_state.symlink_support {
-                let mut new_file_type = new_file_state.file_type.clone();
-                if matches!(new_file_type, FileType::Normal { .. })
-                    && matches!(current_tree_values.as_normal(), Some(TreeValue::Symlink(_)))
-                {
-                    new_file_type = FileType::Symlink;
-                }
-                new_file_type
+            // A regular file in place of a symlink is a placeholder if symlinks
+            // are materialized as such.
+            let is_symlink_placeholder = !self
+                .tree_state
+                .materializes_native_symlinks(self.symlink_policy)
+                && matches!(new_file_state.file_type, FileType::Normal { .. })
+                && matches!(current_tree_values.as_normal(), Some(TreeValue::Symlink(_)));
+            let new_file_type = if is_symlink_placeholder {
+                FileType::Symlink
             } else {
                 new_file_state.file_type.clone()
             };
@@ -1395,7 +1669,7 @@ impl FileSnapshotter<'_> {
                     .block_on()?,
                 FileType::Symlink => {
                     let id = self
-                        .write_symlink_to_store(repo_path, disk_path)
+                        .write_symlink_to_store(repo_path, disk_path, is_symlink_placeholder)
                         .block_on()?;
                     Merge::normal(TreeValue::Symlink(id))
                 }
@@ -1497,8 +1771,9 @@ impl FileSnapshotter<'_> {
         &self,
         path: &RepoPath,
         disk_path: &Path,
+        is_placeholder: bool,
     ) -> Result<SymlinkId, SnapshotError> {
-        if self.tree_state.symlink_support {
+        if !is_placeholder {
             let target = disk_path.read_link().map_err(|err| SnapshotError::Other {
                 message: format!("Failed to read symlink {}", disk_path.display()),
                 err: err.into(),
@@ -1621,12 +1896,7 @@ impl TreeState {
             other => CheckoutError::InternalBackendError(other),
         })?;
         let stats = self
-            .update(
-                &old_tree,
-                new_tree,
-                self.sparse_matcher().as_ref(),
-                options.conflict_marker_style,
-            )
+            .update(&old_tree, new_tree, self.sparse_matcher().as_ref(), options)
             .block_on()?;
         self.tree_id = new_tree.id();
         Ok(stats)
@@ -1649,20 +1919,10 @@ impl TreeState {
         let removed_matcher = DifferenceMatcher::new(&old_matcher, &new_matcher);
         let empty_tree = MergedTree::resolved(Tree::empty(self.store.clone(), RepoPathBuf::root()));
         let added_stats = self
-            .update(
-                &empty_tree,
-                &tree,
-                &added_matcher,
-                options.conflict_marker_style,
-            )
+            .update(&empty_tree, &tree, &added_matcher, options)
             .block_on()?;
         let removed_stats = self
-            .update(
-                &tree,
-                &empty_tree,
-                &removed_matcher,
-                options.conflict_marker_style,
-            )
+            .update(&tree, &empty_tree, &removed_matcher, options)
             .block_on()?;
         self.sparse_patterns = sparse_patterns;
         assert_eq!(added_stats.updated_files, 0);
@@ -1670,11 +1930,13 @@ impl TreeState {
         assert_eq!(removed_stats.updated_files, 0);
         assert_eq!(removed_stats.added_files, 0);
         assert_eq!(removed_stats.skipped_files, 0);
+        assert_eq!(removed_stats.unrepresentable_files, 0);
         Ok(CheckoutStats {
             updated_files: 0,
             added_files: added_stats.added_files,
             removed_files: removed_stats.removed_files,
             skipped_files: added_stats.skipped_files,
+            unrepresentable_files: added_stats.unrepresentable_files,
         })
     }
 
@@ -1683,8 +1945,9 @@ impl TreeState {
         old_tree: &MergedTree,
         new_tree: &MergedTree,
         matcher: &dyn Matcher,
-        conflict_marker_style: ConflictMarkerStyle,
+        options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
+        let native_symlinks = self.materializes_native_symlinks(options.symlink_policy);
         // TODO: maybe it's better not include the skipped counts in the "intended"
         // counts
         let mut stats = CheckoutStats {
@@ -1692,7 +1955,10 @@ impl TreeState {
             added_files: 0,
             removed_files: 0,
             skipped_files: 0,
+            unrepresentable_files: 0,
         };
+        let mut case_checker = CaseMismatchChecker::new(self.state_path.clone());
+        let mut unrepresentable_paths = mem::take(&mut self.unrepresentable_paths);
         let mut changed_file_states = Vec::new();
         let mut deleted_files = HashSet::new();
         let mut diff_stream = old_tree
@@ -1716,6 +1982,11 @@ impl TreeState {
             } else {
                 stats.updated_files += 1;
             }
+            // A path that couldn't be checked out before has nothing on disk.
+            let was_unrepresentable = remove_sorted_path(&mut unrepresentable_paths, &path);
+            if was_unrepresentable && after.is_absent() {
+                continue;
+            }
 
             // Existing Git submodule can be a non-empty directory on disk. We
             // shouldn't attempt to manage it as a tracked path.
@@ -1733,6 +2004,12 @@ impl TreeState {
                 continue;
             }
 
+            if cfg!(windows) && after.is_present() && has_reserved_windows_file_name(&path) {
+                insert_sorted_path(&mut unrepresentable_paths, path);
+                stats.unrepresentable_files += 1;
+                continue;
+            }
+
             // Create parent directories no matter if after.is_present(). This
             // ensures that the path never traverses symlinks.
             let Some(disk_path) = create_parent_dirs(&self.working_copy_path, &path)? else {
@@ -1740,12 +2017,28 @@ impl TreeState {
                 stats.skipped_files += 1;
                 continue;
             };
+            let is_tracked = before.is_present() && !was_unrepresentable;
+            // On a case-insensitive file system, a new path may resolve to
+            // existing directories named differently in case. Writing the file
+            // there would clobber another path in the tree.
+            if !is_tracked
+                && case_checker.parent_dirs_differ_in_case(&self.working_copy_path, &disk_path)?
+            {
+                insert_sorted_path(&mut unrepresentable_paths, path);
+                stats.unrepresentable_files += 1;
+                continue;
+            }
             // If the path was present, check reserved path first and delete it.
-            let present_file_deleted = before.is_present() && remove_old_file(&disk_path)?;
+            let present_file_deleted = is_tracked && remove_old_file(&disk_path)?;
             // If not, create temporary file to test the path validity.
             if !present_file_deleted && !can_create_new_file(&disk_path)? {
-                changed_file_states.push((path, FileState::placeholder()));
-                stats.skipped_files += 1;
+                if after.is_present() && case_checker.entry_differs_in_case(&disk_path)? {
+                    insert_sorted_path(&mut unrepresentable_paths, path);
+                    stats.unrepresentable_files += 1;
+                } else {
+                    changed_file_states.push((path, FileState::placeholder()));
+                    stats.skipped_files += 1;
+                }
                 continue;
             }
 
@@ -1768,7 +2061,7 @@ impl TreeState {
                     ..
                 } => self.write_file(&disk_path, &mut reader, executable)?,
                 MaterializedTreeValue::Symlink { id: _, target } => {
-                    if self.symlink_support {
+                    if native_symlinks {
                         self.write_symlink(&disk_path, target)?
                     } else {
                         self.write_file(&disk_path, &mut target.as_bytes(), false)?
@@ -1787,7 +2080,8 @@ impl TreeState {
                     executable,
                 } => {
                     let data =
-                        materialize_merge_result_to_bytes(&contents, conflict_marker_style).into();
+                        materialize_merge_result_to_bytes(&contents, options.conflict_marker_style)
+                            .into();
                     self.write_conflict(&disk_path, data, executable)?
                 }
                 MaterializedTreeValue::OtherConflict { id } => {
@@ -1802,6 +2096,7 @@ impl TreeState {
         }
         self.file_states
             .merge_in(changed_file_states, &deleted_files);
+        self.unrepresentable_paths = unrepresentable_paths;
         Ok(stats)
     }
 
@@ -1819,6 +2114,7 @@ impl TreeState {
         let mut diff_stream = old_tree.diff_stream(new_tree, matcher.as_ref());
         while let Some(TreeDiffEntry { path, values }) = diff_stream.next().await {
             let (_before, after) = values?;
+            remove_sorted_path(&mut self.unrepresentable_paths, &path);
             if after.is_absent() {
                 deleted_files.insert(path);
             } else {
@@ -1851,6 +2147,7 @@ impl TreeState {
                     file_type,
                     mtime: MillisSinceEpoch(0),
                     size: 0,
+                    inode: 0,
                 };
                 changed_file_states.push((path, file_state));
             }
@@ -1863,6 +2160,7 @@ impl TreeState {
 
     pub async fn recover(&mut self, new_tree: &MergedTree) -> Result<(), ResetError> {
         self.file_states.clear();
+        self.unrepresentable_paths.clear();
         self.tree_id = self.store.empty_merged_tree_id();
         self.reset(new_tree).await
     }
@@ -1915,6 +2213,10 @@ impl WorkingCopy for LocalWorkingCopy {
         Ok(self.tree_state()?.sparse_patterns())
     }
 
+    fn unrepresentable_paths(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError> {
+        Ok(self.tree_state()?.unrepresentable_paths())
+    }
+
     fn start_mutation(&self) -> Result<Box<dyn LockedWorkingCopy>, WorkingCopyStateError> {
         let lock_path = self.state_path.join("working_copy.lock");
         let lock = FileLock::lock(lock_path).map_err(|err| WorkingCopyStateError {
@@ -2083,6 +2385,13 @@ impl LocalWorkingCopy {
             })
     }
 
+    /// Returns a handle to the native filesystem monitor daemon of this working
+    /// copy.
+    #[cfg(feature = "native-fsmonitor")]
+    pub fn native_fsmonitor(&self) -> native::Fsmonitor {
+        native::Fsmonitor::new(self.state_path.join("fsmonitor"))
+    }
+
     #[cfg(feature = "watchman")]
     pub fn is_watchman_trigger_registered(
         &self,
@@ -2302,6 +2611,18 @@ mod tests {
         RepoPath::from_internal_string(value)
     }
 
+    #[test]
+    fn test_has_reserved_windows_file_name() {
+        assert!(has_reserved_windows_file_name(repo_path("aux")));
+        assert!(has_reserved_windows_file_name(repo_path("NUL.txt")));
+        assert!(has_reserved_windows_file_name(repo_path("dir/Com1.tar.gz")));
+        assert!(has_reserved_windows_file_name(repo_path("lpt9/file")));
+        assert!(has_reserved_windows_file_name(repo_path("con .txt")));
+        assert!(!has_reserved_windows_file_name(repo_path("auxiliary")));
+        assert!(!has_reserved_windows_file_name(repo_path("dir/com10")));
+        assert!(!has_reserved_windows_file_name(repo_path("x.con")));
+    }
+
     #[test]
     fn test_file_states_merge() {
         let new_state = |size| FileState {
@@ -2310,6 +2631,7 @@ mod tests {
             },
             mtime: MillisSinceEpoch(0),
             size,
+            inode: 0,
         };
         let new_static_entry = |path: &'static str, size| (repo_path(path), new_state(size));
         let new_owned_entry = |path: &str, size| (repo_path(path).to_owned(), new_state(size));
@@ -2358,6 +2680,7 @@ mod tests {
             },
             mtime: MillisSinceEpoch(0),
             size,
+            inode: 0,
         };
         let new_proto_entry = |path: &str, size| {
             file_state_entry_to_proto(repo_path(path).to_owned(), &new_state(size))
@@ -2422,6 +2745,7 @@ mod tests {
             },
             mtime: MillisSinceEpoch(0),
             size,
+            inode: 0,
         };
         let new_proto_entry = |path: &str, size| {
             file_state_entry_to_proto(repo_path(path).to_owned(), &new_state(size))
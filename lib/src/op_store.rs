@@ -28,10 +28,12 @@ use itertools::Itertools as _;
 use once_cell::sync::Lazy;
 use thiserror::Error;
 
+use crate::backend::ChangeId;
 use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
 use crate::backend::Timestamp;
 use crate::content_hash::ContentHash;
+use crate::content_hash::DigestUpdate;
 use crate::merge::Merge;
 use crate::object_id::id_type;
 use crate::object_id::HexPrefix;
@@ -264,7 +266,7 @@ pub struct BookmarkTarget<'a> {
 
 /// Represents the way the repo looks at a given time, just like how a Tree
 /// object represents how the file system looks at a given time.
-#[derive(ContentHash, PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct View {
     /// All head commits
     pub head_ids: HashSet<CommitId>,
@@ -280,6 +282,42 @@ pub struct View {
     // (.jj/working_copy/) has the source of truth about which commit *is* checked out (to be
     // precise: the commit to which we most recently completed an update to).
     pub wc_commit_ids: HashMap<WorkspaceId, CommitId>,
+    /// User-assigned names of changes.
+    pub change_aliases: BTreeMap<String, ChangeId>,
+    /// Stashed commits, newest first. They aren't heads, so they're hidden,
+    /// but they're kept alive by the view.
+    pub stash_ids: Vec<CommitId>,
+}
+
+impl ContentHash for View {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        let View {
+            head_ids,
+            local_bookmarks,
+            tags,
+            remote_views,
+            git_refs,
+            git_head,
+            wc_commit_ids,
+            change_aliases,
+            stash_ids,
+        } = self;
+        head_ids.hash(state);
+        local_bookmarks.hash(state);
+        tags.hash(state);
+        remote_views.hash(state);
+        git_refs.hash(state);
+        git_head.hash(state);
+        wc_commit_ids.hash(state);
+        // Added later. Not hashed if empty so the IDs of existing views are
+        // preserved.
+        if !change_aliases.is_empty() {
+            change_aliases.hash(state);
+        }
+        if !stash_ids.is_empty() {
+            stash_ids.hash(state);
+        }
+    }
 }
 
 impl View {
@@ -296,6 +334,8 @@ impl View {
             git_refs: BTreeMap::new(),
             git_head: RefTarget::absent(),
             wc_commit_ids: HashMap::new(),
+            change_aliases: BTreeMap::new(),
+            stash_ids: vec![],
         }
     }
 
@@ -309,6 +349,8 @@ impl View {
             git_refs: BTreeMap::new(),
             git_head: RefTarget::absent(),
             wc_commit_ids: HashMap::new(),
+            change_aliases: BTreeMap::new(),
+            stash_ids: vec![],
         }
     }
 }
@@ -0,0 +1,383 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Experimental replication of the operation log between repos.
+//!
+//! Operations are copied to and from a directory which can be shared between
+//! machines, for example with a network file system or `rsync`. Along with the
+//! operations, the commits they refer to are copied, so the directory
+//! contains:
+//!
+//! * `op_store` and `op_heads`: the operation log.
+//! * `git`: a bare Git repo with the commit objects.
+//! * `extra`: the jj-specific commit metadata, such as the change ids.
+//!
+//! Only repos backed by Git are supported.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use itertools::Itertools as _;
+use thiserror::Error;
+
+use crate::backend::CommitId;
+use crate::dag_walk;
+use crate::git_backend::GitBackend;
+use crate::object_id::ObjectId as _;
+use crate::op_heads_store::OpHeadsStore;
+use crate::op_heads_store::OpHeadsStoreError;
+use crate::op_store::OpStore;
+use crate::op_store::OpStoreError;
+use crate::op_store::OperationId;
+use crate::op_store::RootOperationData;
+use crate::repo::RepoLoader;
+use crate::simple_op_heads_store::SimpleOpHeadsStore;
+use crate::simple_op_store::SimpleOpStore;
+use crate::view::View;
+
+const KEEP_REF_PREFIX: &str = "refs/jj/keep/";
+
+/// Error that may occur while copying operations.
+#[derive(Debug, Error)]
+pub enum OpSyncError {
+    /// The repo isn't backed by Git.
+    #[error("The repo is not backed by a Git repo")]
+    UnexpectedBackend,
+    /// Failed to read or write a file.
+    #[error("Failed to access {}", path.display())]
+    Io {
+        /// The file or directory.
+        path: PathBuf,
+        /// The underlying error.
+        #[source]
+        source: io::Error,
+    },
+    /// The operation or its view would be stored with a different id in the
+    /// destination.
+    #[error("Operation {0} would get a different id when copied")]
+    OperationIdMismatch(String),
+    /// Failed to copy the commit objects.
+    #[error("Failed to copy commits")]
+    Git(#[source] git2::Error),
+    /// Failed to access the operations or views.
+    #[error(transparent)]
+    OpStore(#[from] OpStoreError),
+    /// Failed to access the operation heads.
+    #[error(transparent)]
+    OpHeadsStore(#[from] OpHeadsStoreError),
+}
+
+/// Number of objects copied by a push or fetch.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OpSyncStats {
+    /// Operations which didn't exist in the destination.
+    pub operation_count: usize,
+    /// Commits which were packed for the destination, including ones it might
+    /// have had already.
+    pub commit_count: usize,
+}
+
+/// One side of the replication.
+struct Replica<'a> {
+    op_store: &'a dyn OpStore,
+    op_heads_store: &'a dyn OpHeadsStore,
+    git_repo: git2::Repository,
+    extra_dir: PathBuf,
+}
+
+/// Operation log stored in a directory outside of the repo.
+struct SyncDir {
+    path: PathBuf,
+    op_store: SimpleOpStore,
+    op_heads_store: SimpleOpHeadsStore,
+}
+
+impl SyncDir {
+    /// Opens the directory at `path`, initializing it if needed.
+    fn open(path: &Path, root_data: RootOperationData) -> Result<Self, OpSyncError> {
+        for dir in [
+            path.join("op_store").join("operations"),
+            path.join("op_store").join("views"),
+            path.join("op_heads").join("heads"),
+            path.join("extra").join("heads"),
+        ] {
+            fs::create_dir_all(&dir).map_err(|source| OpSyncError::Io { path: dir, source })?;
+        }
+        Ok(SyncDir {
+            path: path.to_owned(),
+            op_store: SimpleOpStore::load(&path.join("op_store"), root_data),
+            op_heads_store: SimpleOpHeadsStore::load(&path.join("op_heads")),
+        })
+    }
+
+    fn replica(&self) -> Result<Replica<'_>, OpSyncError> {
+        let git_path = self.path.join("git");
+        let git_repo = if git_path.exists() {
+            git2::Repository::open_bare(&git_path)
+        } else {
+            git2::Repository::init_bare(&git_path)
+        }
+        .map_err(OpSyncError::Git)?;
+        Ok(Replica {
+            op_store: &self.op_store,
+            op_heads_store: &self.op_heads_store,
+            git_repo,
+            extra_dir: self.path.join("extra"),
+        })
+    }
+}
+
+fn open_repo_replica<'a>(
+    repo_loader: &'a RepoLoader,
+    repo_path: &Path,
+) -> Result<Replica<'a>, OpSyncError> {
+    let git_backend: &GitBackend = repo_loader
+        .store()
+        .backend_impl()
+        .downcast_ref()
+        .ok_or(OpSyncError::UnexpectedBackend)?;
+    Ok(Replica {
+        op_store: repo_loader.op_store().as_ref(),
+        op_heads_store: repo_loader.op_heads_store().as_ref(),
+        git_repo: git_backend.open_git_repo().map_err(OpSyncError::Git)?,
+        extra_dir: repo_path.join("store").join("extra"),
+    })
+}
+
+fn root_data(repo_loader: &RepoLoader) -> RootOperationData {
+    RootOperationData {
+        root_commit_id: repo_loader.store().root_commit_id().clone(),
+    }
+}
+
+/// Copies the `head_ids` operations and their ancestors from the repo at
+/// `repo_path` to the directory at `dest`, and adds them to its operation
+/// heads.
+pub fn push_operations(
+    repo_loader: &RepoLoader,
+    repo_path: &Path,
+    head_ids: &[OperationId],
+    dest: &Path,
+) -> Result<OpSyncStats, OpSyncError> {
+    let src = open_repo_replica(repo_loader, repo_path)?;
+    let dir = SyncDir::open(dest, root_data(repo_loader))?;
+    copy_operations(&src, &dir.replica()?, head_ids)
+}
+
+/// Copies the operations from the directory at `source` to the repo at
+/// `repo_path`, and adds them to its operation heads.
+///
+/// The fetched operations are merged with the local ones the next time the
+/// repo is loaded.
+pub fn fetch_operations(
+    repo_loader: &RepoLoader,
+    repo_path: &Path,
+    source: &Path,
+) -> Result<OpSyncStats, OpSyncError> {
+    let dest = open_repo_replica(repo_loader, repo_path)?;
+    // Don't create the directory if it doesn't exist, which is most likely a
+    // typo.
+    fs::metadata(source).map_err(|source_err| OpSyncError::Io {
+        path: source.to_owned(),
+        source: source_err,
+    })?;
+    let dir = SyncDir::open(source, root_data(repo_loader))?;
+    let head_ids = dir.op_heads_store.get_op_heads()?;
+    copy_operations(&dir.replica()?, &dest, &head_ids)
+}
+
+fn copy_operations(
+    src: &Replica,
+    dest: &Replica,
+    head_ids: &[OperationId],
+) -> Result<OpSyncStats, OpSyncError> {
+    // Find the operations which don't exist in the destination yet.
+    let root_operation_id = src.op_store.root_operation_id();
+    let mut visited = HashSet::new();
+    let mut operations = vec![];
+    let mut pending = head_ids.to_vec();
+    while let Some(id) = pending.pop() {
+        if id == *root_operation_id || !visited.insert(id.clone()) {
+            continue;
+        }
+        match dest.op_store.read_operation(&id) {
+            Ok(_) => continue,
+            Err(OpStoreError::ObjectNotFound { .. }) => {}
+            Err(err) => return Err(err.into()),
+        }
+        let operation = src.op_store.read_operation(&id)?;
+        pending.extend(operation.parents.iter().cloned());
+        operations.push((id, operation));
+    }
+
+    let mut views = vec![];
+    let mut commit_ids = HashSet::new();
+    for (_, operation) in &operations {
+        let view = src.op_store.read_view(&operation.view_id)?;
+        commit_ids.extend(View::new(view.clone()).all_referenced_commit_ids().cloned());
+        views.push(view);
+    }
+
+    // Copy the commits first so that the operations never refer to missing
+    // commits.
+    let commit_count = copy_commits(src, dest, &commit_ids)?;
+    copy_extra_metadata(&src.extra_dir, &dest.extra_dir)?;
+    for ((id, operation), view) in operations.iter().zip(&views) {
+        let view_id = dest.op_store.write_view(view)?;
+        let new_id = dest.op_store.write_operation(operation)?;
+        if view_id != operation.view_id || new_id != *id {
+            return Err(OpSyncError::OperationIdMismatch(id.hex()));
+        }
+    }
+    add_op_heads(dest, head_ids)?;
+    Ok(OpSyncStats {
+        operation_count: operations.len(),
+        commit_count,
+    })
+}
+
+/// Packs the `commit_ids` and all commits protected from GC in the source
+/// repo, excluding the ones already protected in the destination.
+fn copy_commits(
+    src: &Replica,
+    dest: &Replica,
+    commit_ids: &HashSet<CommitId>,
+) -> Result<usize, OpSyncError> {
+    let keep_ref_ids = |repo: &git2::Repository| -> Result<Vec<git2::Oid>, git2::Error> {
+        repo.references_glob(&format!("{KEEP_REF_PREFIX}*"))?
+            .map_ok(|reference| reference.target())
+            .filter_map_ok(|id| id)
+            .try_collect()
+    };
+    let copy = || -> Result<usize, git2::Error> {
+        let mut walk = src.git_repo.revwalk()?;
+        let mut tip_ids = keep_ref_ids(&src.git_repo)?;
+        for id in commit_ids {
+            tip_ids.push(git2::Oid::from_bytes(id.as_bytes())?);
+        }
+        for id in &tip_ids {
+            walk.push(*id)?;
+        }
+        for id in keep_ref_ids(&dest.git_repo)? {
+            // Commits unknown to the source can't be hidden.
+            if src.git_repo.find_commit(id).is_ok() {
+                walk.hide(id)?;
+            }
+        }
+        let walked_ids: Vec<_> = walk.try_collect()?;
+        if walked_ids.is_empty() {
+            return Ok(0);
+        }
+        let mut pack_builder = src.git_repo.packbuilder()?;
+        for id in &walked_ids {
+            pack_builder.insert_commit(*id)?;
+        }
+        let mut pack = git2::Buf::new();
+        pack_builder.write_buf(&mut pack)?;
+        let odb = dest.git_repo.odb()?;
+        let mut pack_writer = odb.packwriter()?;
+        pack_writer
+            .write_all(&pack)
+            .map_err(|err| git2::Error::from_str(&err.to_string()))?;
+        pack_writer.commit()?;
+        odb.refresh()?;
+        for id in tip_ids {
+            dest.git_repo
+                .reference(&format!("{KEEP_REF_PREFIX}{id}"), id, true, "")?;
+        }
+        Ok(walked_ids.len())
+    };
+    copy().map_err(OpSyncError::Git)
+}
+
+/// Copies the table files of the commit metadata store. Since the files are
+/// content-addressed, existing files are left alone. Only the heads of newly
+/// copied tables are added, since the existing tables have already been merged
+/// into the destination's heads. The tables are merged when the store is
+/// loaded with multiple heads.
+fn copy_extra_metadata(src_dir: &Path, dest_dir: &Path) -> Result<(), OpSyncError> {
+    let mut copied_names = HashSet::new();
+    for sub_dir in ["", "heads"] {
+        let src_dir = src_dir.join(sub_dir);
+        let dest_dir = dest_dir.join(sub_dir);
+        let entries = fs::read_dir(&src_dir).map_err(|source| OpSyncError::Io {
+            path: src_dir.clone(),
+            source,
+        })?;
+        for entry in entries {
+            let entry = entry.map_err(|source| OpSyncError::Io {
+                path: src_dir.clone(),
+                source,
+            })?;
+            let src_path = entry.path();
+            let dest_path = dest_dir.join(entry.file_name());
+            // Skip the lock file and subdirectories.
+            if !src_path.is_file() || entry.file_name() == "lock" || dest_path.exists() {
+                continue;
+            }
+            if sub_dir.is_empty() {
+                copied_names.insert(entry.file_name());
+            } else if !copied_names.contains(&entry.file_name()) {
+                continue;
+            }
+            fs::copy(&src_path, &dest_path).map_err(|source| OpSyncError::Io {
+                path: dest_path.clone(),
+                source,
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds the `new_ids` to the operation heads of the destination, removing the
+/// heads which become ancestors of others.
+fn add_op_heads(dest: &Replica, new_ids: &[OperationId]) -> Result<(), OpSyncError> {
+    let old_ids = dest.op_heads_store.get_op_heads()?;
+    let all_ids = old_ids
+        .iter()
+        .chain(new_ids)
+        .unique()
+        .cloned()
+        .collect_vec();
+    let head_ids = dag_walk::heads_ok(
+        all_ids.into_iter().map(Ok),
+        |id: &OperationId| id.clone(),
+        |id: &OperationId| match dest.op_store.read_operation(id) {
+            Ok(operation) => operation.parents.into_iter().map(Ok).collect_vec(),
+            Err(err) => vec![Err(err)],
+        },
+    )?;
+    let added_ids = new_ids
+        .iter()
+        .filter(|id| head_ids.contains(id) && !old_ids.contains(id))
+        .unique()
+        .collect_vec();
+    let removed_ids = old_ids
+        .iter()
+        .filter(|id| !head_ids.contains(id))
+        .cloned()
+        .collect_vec();
+    if let Some((first_id, other_ids)) = added_ids.split_first() {
+        dest.op_heads_store
+            .update_op_heads(&removed_ids, first_id)?;
+        for id in other_ids {
+            dest.op_heads_store.update_op_heads(&[], id)?;
+        }
+    }
+    Ok(())
+}
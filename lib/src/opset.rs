@@ -0,0 +1,590 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Operation set language used to filter the operation log.
+//!
+//! The syntax is shared with revsets, but symbols are resolved to operations
+//! and functions filter operations by their metadata.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+use once_cell::sync::Lazy;
+
+use crate::graph::GraphEdge;
+use crate::op_store::OpStore;
+use crate::op_store::OperationId;
+use crate::op_walk;
+use crate::op_walk::OpsetEvaluationError;
+use crate::op_walk::OpsetResolutionError;
+use crate::operation::Operation;
+use crate::revset::expect_date_pattern;
+use crate::revset::expect_string_pattern;
+use crate::revset_parser;
+use crate::revset_parser::BinaryOp;
+use crate::revset_parser::ExpressionKind;
+use crate::revset_parser::ExpressionNode;
+use crate::revset_parser::FunctionCallNode;
+use crate::revset_parser::RevsetDiagnostics;
+use crate::revset_parser::RevsetParseError;
+use crate::revset_parser::RevsetParseErrorKind;
+use crate::revset_parser::UnaryOp;
+use crate::str_util::StringPattern;
+use crate::time_util::DatePattern;
+use crate::time_util::DatePatternContext;
+
+/// Operation set expression.
+#[derive(Clone, Debug)]
+pub enum OpsetExpression {
+    /// All operations.
+    All,
+    /// No operations.
+    None,
+    /// The root operation.
+    Root,
+    /// The current operation (`@`).
+    Current,
+    /// The head operations.
+    Heads,
+    /// Operation ID prefix.
+    Id(String),
+    /// Parents of the operations.
+    Parents(Rc<OpsetExpression>),
+    /// Children of the operations.
+    Children(Rc<OpsetExpression>),
+    /// Ancestors of the operations, including themselves.
+    Ancestors(Rc<OpsetExpression>),
+    /// Descendants of the operations, including themselves.
+    Descendants(Rc<OpsetExpression>),
+    /// Descendants of `roots` which are ancestors of `heads`.
+    DagRange {
+        /// Roots of the range.
+        roots: Rc<OpsetExpression>,
+        /// Heads of the range.
+        heads: Rc<OpsetExpression>,
+    },
+    /// Ancestors of `heads` which aren't ancestors of `roots`.
+    Range {
+        /// Roots of the range (excluded.)
+        roots: Rc<OpsetExpression>,
+        /// Heads of the range.
+        heads: Rc<OpsetExpression>,
+    },
+    /// Operations matching the predicate.
+    Filter(OpsetPredicate),
+    /// Operations in either set.
+    Union(Rc<OpsetExpression>, Rc<OpsetExpression>),
+    /// Operations in both sets.
+    Intersection(Rc<OpsetExpression>, Rc<OpsetExpression>),
+    /// Operations in the first set but not in the second.
+    Difference(Rc<OpsetExpression>, Rc<OpsetExpression>),
+}
+
+/// Predicate to filter operations by their metadata.
+#[derive(Clone, Debug)]
+pub enum OpsetPredicate {
+    /// Operation description matches the pattern.
+    Description(StringPattern),
+    /// User name matches the pattern.
+    User(StringPattern),
+    /// Host name matches the pattern.
+    Hostname(StringPattern),
+    /// End time of the operation matches the pattern.
+    Date(DatePattern),
+    /// Operation has the tag, and its value matches the pattern.
+    Tag {
+        /// Tag name.
+        name: String,
+        /// Pattern to match the tag value against.
+        value: StringPattern,
+    },
+    /// Operation is a working-copy snapshot.
+    Snapshot,
+}
+
+impl OpsetPredicate {
+    fn matches(&self, op: &Operation) -> bool {
+        let metadata = op.metadata();
+        match self {
+            OpsetPredicate::Description(pattern) => pattern.matches(&metadata.description),
+            OpsetPredicate::User(pattern) => pattern.matches(&metadata.username),
+            OpsetPredicate::Hostname(pattern) => pattern.matches(&metadata.hostname),
+            OpsetPredicate::Date(pattern) => pattern.matches(&metadata.end_time),
+            OpsetPredicate::Tag { name, value } => metadata
+                .tags
+                .get(name)
+                .is_some_and(|tag_value| value.matches(tag_value)),
+            OpsetPredicate::Snapshot => metadata.is_snapshot,
+        }
+    }
+}
+
+impl OpsetExpression {
+    /// Unions all the given expressions.
+    pub fn union_all(expressions: &[Rc<OpsetExpression>]) -> Rc<OpsetExpression> {
+        expressions
+            .iter()
+            .cloned()
+            .reduce(|acc, expression| Rc::new(OpsetExpression::Union(acc, expression)))
+            .unwrap_or_else(|| Rc::new(OpsetExpression::None))
+    }
+}
+
+type OpsetFunction = fn(
+    &mut RevsetDiagnostics,
+    &FunctionCallNode,
+    &DatePatternContext,
+) -> Result<Rc<OpsetExpression>, RevsetParseError>;
+
+fn expect_one_opset(
+    diagnostics: &mut RevsetDiagnostics,
+    function: &FunctionCallNode,
+    context: &DatePatternContext,
+) -> Result<Rc<OpsetExpression>, RevsetParseError> {
+    let [arg] = function.expect_exact_arguments()?;
+    lower_expression(diagnostics, arg, context)
+}
+
+fn expect_string_pattern_filter(
+    diagnostics: &mut RevsetDiagnostics,
+    function: &FunctionCallNode,
+    to_predicate: fn(StringPattern) -> OpsetPredicate,
+) -> Result<Rc<OpsetExpression>, RevsetParseError> {
+    let [arg] = function.expect_exact_arguments()?;
+    let pattern = expect_string_pattern(diagnostics, arg)?;
+    Ok(Rc::new(OpsetExpression::Filter(to_predicate(pattern))))
+}
+
+static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, OpsetFunction>> = Lazy::new(|| {
+    let mut map: HashMap<&'static str, OpsetFunction> = HashMap::new();
+    map.insert("all", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::All))
+    });
+    map.insert("none", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::None))
+    });
+    map.insert("root", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::Root))
+    });
+    map.insert("heads", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::Heads))
+    });
+    map.insert("parents", |diagnostics, function, context| {
+        let expression = expect_one_opset(diagnostics, function, context)?;
+        Ok(Rc::new(OpsetExpression::Parents(expression)))
+    });
+    map.insert("children", |diagnostics, function, context| {
+        let expression = expect_one_opset(diagnostics, function, context)?;
+        Ok(Rc::new(OpsetExpression::Children(expression)))
+    });
+    map.insert("ancestors", |diagnostics, function, context| {
+        let expression = expect_one_opset(diagnostics, function, context)?;
+        Ok(Rc::new(OpsetExpression::Ancestors(expression)))
+    });
+    map.insert("descendants", |diagnostics, function, context| {
+        let expression = expect_one_opset(diagnostics, function, context)?;
+        Ok(Rc::new(OpsetExpression::Descendants(expression)))
+    });
+    map.insert("predecessors", |diagnostics, function, context| {
+        let expression = expect_one_opset(diagnostics, function, context)?;
+        let parents = Rc::new(OpsetExpression::Parents(expression));
+        Ok(Rc::new(OpsetExpression::Ancestors(parents)))
+    });
+    map.insert("description", |diagnostics, function, _context| {
+        expect_string_pattern_filter(diagnostics, function, OpsetPredicate::Description)
+    });
+    map.insert("user", |diagnostics, function, _context| {
+        expect_string_pattern_filter(diagnostics, function, OpsetPredicate::User)
+    });
+    map.insert("hostname", |diagnostics, function, _context| {
+        expect_string_pattern_filter(diagnostics, function, OpsetPredicate::Hostname)
+    });
+    map.insert("date", |diagnostics, function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_date_pattern(diagnostics, arg, context)?;
+        Ok(Rc::new(OpsetExpression::Filter(OpsetPredicate::Date(
+            pattern,
+        ))))
+    });
+    map.insert("tag", |diagnostics, function, _context| {
+        let ([name_arg], [value_opt_arg]) = function.expect_arguments()?;
+        let name = revset_parser::expect_literal(diagnostics, "string", name_arg)?;
+        let value = if let Some(value_arg) = value_opt_arg {
+            expect_string_pattern(diagnostics, value_arg)?
+        } else {
+            StringPattern::everything()
+        };
+        Ok(Rc::new(OpsetExpression::Filter(OpsetPredicate::Tag {
+            name,
+            value,
+        })))
+    });
+    map.insert("snapshot", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::Filter(OpsetPredicate::Snapshot)))
+    });
+    map
+});
+
+fn lower_function_call(
+    diagnostics: &mut RevsetDiagnostics,
+    function: &FunctionCallNode,
+    context: &DatePatternContext,
+) -> Result<Rc<OpsetExpression>, RevsetParseError> {
+    if let Some(func) = BUILTIN_FUNCTION_MAP.get(function.name) {
+        func(diagnostics, function, context)
+    } else {
+        Err(RevsetParseError::with_span(
+            RevsetParseErrorKind::NoSuchFunction {
+                name: function.name.to_owned(),
+                candidates: crate::dsl_util::collect_similar(
+                    function.name,
+                    BUILTIN_FUNCTION_MAP.keys(),
+                ),
+            },
+            function.name_span,
+        ))
+    }
+}
+
+fn lower_expression(
+    diagnostics: &mut RevsetDiagnostics,
+    node: &ExpressionNode,
+    context: &DatePatternContext,
+) -> Result<Rc<OpsetExpression>, RevsetParseError> {
+    match &node.kind {
+        ExpressionKind::Identifier(name) => Ok(Rc::new(OpsetExpression::Id((*name).to_owned()))),
+        ExpressionKind::String(name) => Ok(Rc::new(OpsetExpression::Id(name.to_owned()))),
+        ExpressionKind::StringPattern { .. } => Err(RevsetParseError::with_span(
+            RevsetParseErrorKind::NotInfixOperator {
+                op: ":".to_owned(),
+                similar_op: "::".to_owned(),
+                description: "DAG range".to_owned(),
+            },
+            node.span,
+        )),
+        ExpressionKind::RemoteSymbol { .. } | ExpressionKind::AtWorkspace(_) => Err(
+            RevsetParseError::expression("Operation set cannot refer to commits", node.span),
+        ),
+        ExpressionKind::AtCurrentWorkspace => Ok(Rc::new(OpsetExpression::Current)),
+        ExpressionKind::DagRangeAll => Ok(Rc::new(OpsetExpression::All)),
+        ExpressionKind::RangeAll => Ok(Rc::new(OpsetExpression::Range {
+            roots: Rc::new(OpsetExpression::Root),
+            heads: Rc::new(OpsetExpression::Heads),
+        })),
+        ExpressionKind::Unary(op, arg_node) => {
+            let arg = lower_expression(diagnostics, arg_node, context)?;
+            let expression = match op {
+                UnaryOp::Negate => OpsetExpression::Difference(Rc::new(OpsetExpression::All), arg),
+                UnaryOp::DagRangePre => OpsetExpression::Ancestors(arg),
+                UnaryOp::DagRangePost => OpsetExpression::Descendants(arg),
+                UnaryOp::RangePre => OpsetExpression::Range {
+                    roots: Rc::new(OpsetExpression::Root),
+                    heads: arg,
+                },
+                UnaryOp::RangePost => OpsetExpression::Range {
+                    roots: arg,
+                    heads: Rc::new(OpsetExpression::Heads),
+                },
+                UnaryOp::Parents => OpsetExpression::Parents(arg),
+                UnaryOp::Children => OpsetExpression::Children(arg),
+            };
+            Ok(Rc::new(expression))
+        }
+        ExpressionKind::Binary(op, lhs_node, rhs_node) => {
+            let lhs = lower_expression(diagnostics, lhs_node, context)?;
+            let rhs = lower_expression(diagnostics, rhs_node, context)?;
+            let expression = match op {
+                BinaryOp::Intersection => OpsetExpression::Intersection(lhs, rhs),
+                BinaryOp::Difference => OpsetExpression::Difference(lhs, rhs),
+                BinaryOp::DagRange => OpsetExpression::DagRange {
+                    roots: lhs,
+                    heads: rhs,
+                },
+                BinaryOp::Range => OpsetExpression::Range {
+                    roots: lhs,
+                    heads: rhs,
+                },
+            };
+            Ok(Rc::new(expression))
+        }
+        ExpressionKind::UnionAll(nodes) => {
+            let expressions: Vec<_> = nodes
+                .iter()
+                .map(|node| lower_expression(diagnostics, node, context))
+                .try_collect()?;
+            Ok(OpsetExpression::union_all(&expressions))
+        }
+        ExpressionKind::FunctionCall(function) => {
+            lower_function_call(diagnostics, function, context)
+        }
+        ExpressionKind::Modifier(modifier) => {
+            let name = modifier.name;
+            Err(RevsetParseError::expression(
+                format!(r#"Modifier "{name}:" is not allowed in operation set"#),
+                modifier.name_span,
+            ))
+        }
+        ExpressionKind::AliasExpanded(_, subst) => lower_expression(diagnostics, subst, context),
+    }
+}
+
+/// Parses the operation set expression.
+///
+/// Relative dates in date patterns are resolved against the given `context`.
+pub fn parse(
+    diagnostics: &mut RevsetDiagnostics,
+    opset_str: &str,
+    context: &DatePatternContext,
+) -> Result<Rc<OpsetExpression>, RevsetParseError> {
+    let node = revset_parser::parse_program(opset_str)?;
+    lower_expression(diagnostics, &node, context)
+}
+
+/// Operation graph reachable from the head operations, in reverse topological
+/// order.
+struct OpGraph {
+    ops: Vec<Operation>,
+    positions: HashMap<OperationId, usize>,
+    parents: Vec<Vec<usize>>,
+    heads: Vec<usize>,
+}
+
+impl OpGraph {
+    fn load(head_ops: &[Operation]) -> Result<Self, OpsetEvaluationError> {
+        let ops: Vec<Operation> = op_walk::walk_ancestors(head_ops).try_collect()?;
+        let positions: HashMap<OperationId, usize> = ops
+            .iter()
+            .enumerate()
+            .map(|(pos, op)| (op.id().clone(), pos))
+            .collect();
+        let parents = ops
+            .iter()
+            .map(|op| {
+                op.parent_ids()
+                    .iter()
+                    .filter_map(|id| positions.get(id).copied())
+                    .collect()
+            })
+            .collect();
+        let heads = head_ops
+            .iter()
+            .map(|op| positions[op.id()])
+            .unique()
+            .collect();
+        Ok(OpGraph {
+            ops,
+            positions,
+            parents,
+            heads,
+        })
+    }
+
+    fn ancestors(&self, mut set: Vec<bool>) -> Vec<bool> {
+        // Children always precede their parents.
+        for pos in 0..set.len() {
+            if set[pos] {
+                for &parent_pos in &self.parents[pos] {
+                    set[parent_pos] = true;
+                }
+            }
+        }
+        set
+    }
+
+    fn descendants(&self, mut set: Vec<bool>) -> Vec<bool> {
+        for pos in (0..set.len()).rev() {
+            if self.parents[pos].iter().any(|&parent_pos| set[parent_pos]) {
+                set[pos] = true;
+            }
+        }
+        set
+    }
+
+    fn evaluate(
+        &self,
+        op_store: &Arc<dyn OpStore>,
+        expression: &OpsetExpression,
+    ) -> Result<Vec<bool>, OpsetEvaluationError> {
+        let empty = || vec![false; self.ops.len()];
+        let from_positions = |positions: &[usize]| {
+            let mut set = empty();
+            for &pos in positions {
+                set[pos] = true;
+            }
+            set
+        };
+        let set = match expression {
+            OpsetExpression::All => vec![true; self.ops.len()],
+            OpsetExpression::None => empty(),
+            OpsetExpression::Root => self
+                .parents
+                .iter()
+                .map(|parents| parents.is_empty())
+                .collect(),
+            OpsetExpression::Current => match self.heads.as_slice() {
+                [pos] => from_positions(&[*pos]),
+                [] => Err(OpsetResolutionError::EmptyOperations("@".to_owned()))?,
+                positions => Err(OpsetResolutionError::MultipleOperations {
+                    expr: "@".to_owned(),
+                    candidates: positions
+                        .iter()
+                        .map(|&pos| self.ops[pos].id().clone())
+                        .collect(),
+                })?,
+            },
+            OpsetExpression::Heads => from_positions(&self.heads),
+            OpsetExpression::Id(prefix) => {
+                let op = op_walk::resolve_single_op_from_store(op_store, prefix)?;
+                let positions = self
+                    .positions
+                    .get(op.id())
+                    .copied()
+                    .into_iter()
+                    .collect_vec();
+                from_positions(&positions)
+            }
+            OpsetExpression::Parents(expression) => {
+                let set = self.evaluate(op_store, expression)?;
+                let positions = (0..set.len())
+                    .filter(|&pos| set[pos])
+                    .flat_map(|pos| self.parents[pos].iter().copied())
+                    .collect_vec();
+                from_positions(&positions)
+            }
+            OpsetExpression::Children(expression) => {
+                let set = self.evaluate(op_store, expression)?;
+                self.parents
+                    .iter()
+                    .map(|parents| parents.iter().any(|&parent_pos| set[parent_pos]))
+                    .collect()
+            }
+            OpsetExpression::Ancestors(expression) => {
+                self.ancestors(self.evaluate(op_store, expression)?)
+            }
+            OpsetExpression::Descendants(expression) => {
+                self.descendants(self.evaluate(op_store, expression)?)
+            }
+            OpsetExpression::DagRange { roots, heads } => {
+                let descendants = self.descendants(self.evaluate(op_store, roots)?);
+                let ancestors = self.ancestors(self.evaluate(op_store, heads)?);
+                intersect(descendants, &ancestors)
+            }
+            OpsetExpression::Range { roots, heads } => {
+                let excluded = self.ancestors(self.evaluate(op_store, roots)?);
+                let ancestors = self.ancestors(self.evaluate(op_store, heads)?);
+                subtract(ancestors, &excluded)
+            }
+            OpsetExpression::Filter(predicate) => {
+                self.ops.iter().map(|op| predicate.matches(op)).collect()
+            }
+            OpsetExpression::Union(lhs, rhs) => {
+                let mut set = self.evaluate(op_store, lhs)?;
+                for (pos, included) in self.evaluate(op_store, rhs)?.into_iter().enumerate() {
+                    set[pos] |= included;
+                }
+                set
+            }
+            OpsetExpression::Intersection(lhs, rhs) => {
+                let lhs = self.evaluate(op_store, lhs)?;
+                intersect(lhs, &self.evaluate(op_store, rhs)?)
+            }
+            OpsetExpression::Difference(lhs, rhs) => {
+                let lhs = self.evaluate(op_store, lhs)?;
+                subtract(lhs, &self.evaluate(op_store, rhs)?)
+            }
+        };
+        Ok(set)
+    }
+
+    /// Finds edges from the operation at `pos` to the nearest operations in
+    /// the `set`.
+    fn edges_within(&self, set: &[bool], pos: usize) -> Vec<GraphEdge<OperationId>> {
+        let mut edges = vec![];
+        let mut has_missing = false;
+        let mut visited = HashSet::new();
+        let mut to_visit = vec![];
+        for &parent_pos in &self.parents[pos] {
+            if set[parent_pos] {
+                edges.push(GraphEdge::direct(self.ops[parent_pos].id().clone()));
+                visited.insert(parent_pos);
+            } else {
+                to_visit.push(parent_pos);
+            }
+        }
+        while let Some(pos) = to_visit.pop() {
+            if !visited.insert(pos) {
+                continue;
+            }
+            if set[pos] {
+                edges.push(GraphEdge::indirect(self.ops[pos].id().clone()));
+            } else if self.parents[pos].is_empty() {
+                has_missing = true;
+            } else {
+                to_visit.extend(self.parents[pos].iter().copied());
+            }
+        }
+        if edges.is_empty() && has_missing {
+            let root_op = self.ops.last().unwrap();
+            edges.push(GraphEdge::missing(root_op.id().clone()));
+        }
+        edges
+    }
+}
+
+fn intersect(mut set: Vec<bool>, other: &[bool]) -> Vec<bool> {
+    for (included, &other_included) in set.iter_mut().zip(other) {
+        *included &= other_included;
+    }
+    set
+}
+
+fn subtract(mut set: Vec<bool>, other: &[bool]) -> Vec<bool> {
+    for (included, &other_included) in set.iter_mut().zip(other) {
+        *included &= !other_included;
+    }
+    set
+}
+
+/// Operation and its edges to the nearest ancestors in the operation set.
+pub type OpsetGraphNode = (Operation, Vec<GraphEdge<OperationId>>);
+
+/// Evaluates the operation set expression within the ancestors of the
+/// `head_ops`.
+///
+/// The matching operations are returned in reverse topological order, along
+/// with the edges to the nearest matching ancestors.
+pub fn evaluate(
+    expression: &OpsetExpression,
+    op_store: &Arc<dyn OpStore>,
+    head_ops: &[Operation],
+) -> Result<Vec<OpsetGraphNode>, OpsetEvaluationError> {
+    let graph = OpGraph::load(head_ops)?;
+    let set = graph.evaluate(op_store, expression)?;
+    let nodes = graph
+        .ops
+        .iter()
+        .enumerate()
+        .filter(|&(pos, _)| set[pos])
+        .map(|(pos, op)| (op.clone(), graph.edges_within(&set, pos)))
+        .collect();
+    Ok(nodes)
+}
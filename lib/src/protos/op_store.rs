@@ -125,6 +125,15 @@ pub struct View {
     /// Whether "@git" bookmark have been migrated to remote_targets.
     #[prost(bool, tag = "10")]
     pub has_git_refs_migrated_to_remote: bool,
+    /// User-assigned names of changes
+    #[prost(map = "string, bytes", tag = "11")]
+    pub change_aliases: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::vec::Vec<u8>,
+    >,
+    /// Stashed commits, newest first
+    #[prost(bytes = "vec", repeated, tag = "12")]
+    pub stash_ids: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
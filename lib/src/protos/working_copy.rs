@@ -12,6 +12,9 @@ pub struct FileState {
     #[deprecated]
     #[prost(bytes = "vec", tag = "4")]
     pub conflict_id: ::prost::alloc::vec::Vec<u8>,
+    /// Inode number, or 0 if unknown
+    #[prost(uint64, tag = "5")]
+    pub inode: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -44,6 +47,12 @@ pub struct TreeState {
     pub sparse_patterns: ::core::option::Option<SparsePatterns>,
     #[prost(message, optional, tag = "4")]
     pub watchman_clock: ::core::option::Option<WatchmanClock>,
+    /// Paths in the tree that couldn't be checked out because the file system
+    /// can't represent them, e.g. paths differing only in case.
+    #[prost(string, repeated, tag = "7")]
+    pub unrepresentable_paths: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(message, optional, tag = "8")]
+    pub native_fsmonitor_clock: ::core::option::Option<NativeFsmonitorClock>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -64,6 +73,16 @@ pub mod watchman_clock {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NativeFsmonitorClock {
+    /// Identifies the daemon instance that wrote the journal.
+    #[prost(string, tag = "1")]
+    pub daemon_id: ::prost::alloc::string::String,
+    /// Position in the journal up to which changes have been seen.
+    #[prost(uint64, tag = "2")]
+    pub offset: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Checkout {
     /// The operation at which the working copy was updated.
     #[prost(bytes = "vec", tag = "2")]
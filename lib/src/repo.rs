@@ -24,6 +24,7 @@ use std::path::Path;
 use std::slice;
 use std::sync::Arc;
 
+use itertools::EitherOrBoth;
 use itertools::Itertools;
 use once_cell::sync::OnceCell;
 use thiserror::Error;
@@ -409,6 +410,15 @@ impl Default for StoreFactories {
                 )?))
             }),
         );
+        #[cfg(feature = "s3")]
+        factories.add_backend(
+            crate::s3_backend::S3Backend::name(),
+            Box::new(|settings, store_path| {
+                Ok(Box::new(crate::s3_backend::S3Backend::load(
+                    settings, store_path,
+                )?))
+            }),
+        );
         #[cfg(feature = "testing")]
         factories.add_backend(
             crate::secret_backend::SecretBackend::name(),
@@ -436,7 +446,14 @@ impl Default for StoreFactories {
         // Index
         factories.add_index_store(
             DefaultIndexStore::name(),
-            Box::new(|_settings, store_path| Ok(Box::new(DefaultIndexStore::load(store_path)))),
+            Box::new(|settings, store_path| {
+                let load_mode = settings
+                    .index_load_mode()
+                    .map_err(|err| BackendLoadError(err.into()))?;
+                Ok(Box::new(
+                    DefaultIndexStore::load(store_path).with_load_mode(load_mode),
+                ))
+            }),
         );
 
         // SubmoduleStores
@@ -1629,6 +1646,14 @@ impl MutableRepo {
         view.set_tag_target(name, new_target);
     }
 
+    pub fn set_change_alias(&mut self, name: &str, change_id: Option<ChangeId>) {
+        self.view_mut().set_change_alias(name, change_id);
+    }
+
+    pub fn set_stash_ids(&mut self, stash_ids: Vec<CommitId>) {
+        self.view_mut().set_stash_ids(stash_ids);
+    }
+
     pub fn get_git_ref(&self, name: &str) -> RefTarget {
         self.view.with_ref(|v| v.get_git_ref(name).clone())
     }
@@ -1756,6 +1781,44 @@ impl MutableRepo {
             other.git_head(),
         );
         self.set_git_head_target(new_git_head_target);
+
+        // Merge change aliases. If there's a conflict, we keep the self side.
+        let changed_aliases = base
+            .change_aliases()
+            .merge_join_by(other.change_aliases(), |(name1, _), (name2, _)| {
+                name1.cmp(name2)
+            })
+            .map(|entry| match entry {
+                EitherOrBoth::Both((name, base_id), (_, other_id)) => {
+                    (name, Some(base_id), Some(other_id))
+                }
+                EitherOrBoth::Left((name, base_id)) => (name, Some(base_id), None),
+                EitherOrBoth::Right((name, other_id)) => (name, None, Some(other_id)),
+            })
+            .filter(|(_, base_id, other_id)| base_id != other_id)
+            .map(|(name, base_id, other_id)| (name.to_owned(), base_id.cloned(), other_id.cloned()))
+            .collect_vec();
+        for (name, base_id, other_id) in changed_aliases {
+            if self.view().get_change_alias(&name) == base_id.as_ref() {
+                self.set_change_alias(&name, other_id);
+            }
+        }
+
+        // Merge stashes. Stashes pushed on the other side go on top, and
+        // stashes dropped on the other side are dropped here too.
+        if base.stash_ids() != other.stash_ids() {
+            let pushed = other
+                .stash_ids()
+                .iter()
+                .filter(|id| !base.stash_ids().contains(id));
+            let kept = self
+                .view()
+                .stash_ids()
+                .iter()
+                .filter(|id| !base.stash_ids().contains(id) || other.stash_ids().contains(id));
+            let stash_ids = pushed.chain(kept).unique().cloned().collect();
+            self.set_stash_ids(stash_ids);
+        }
     }
 
     /// Finds and records commits that were rewritten or abandoned between
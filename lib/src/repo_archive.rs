@@ -0,0 +1,585 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backend-independent archive of a whole repo.
+//!
+//! The archive contains the operation log and all commits, trees, files,
+//! symlinks, and conflicts reachable from it. It can be imported into a repo
+//! with a different backend, so the objects are written with new ids, and the
+//! operations and views are rewritten to refer to them.
+//!
+//! The archive is a sequence of records, each of which is a kind byte, the id
+//! of the object in the source repo, and the object data. Commits, trees,
+//! conflicts, operations, and views are encoded in the protobuf formats of the
+//! local backend and the simple op store. An object is always preceded by the
+//! objects it refers to, and the last operation is the head of the operation
+//! log.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::slice;
+use std::sync::Arc;
+
+use pollster::FutureExt as _;
+use prost::Message as _;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::CommitId;
+use crate::backend::ConflictId;
+use crate::backend::FileId;
+use crate::backend::MergedTreeId;
+use crate::backend::SymlinkId;
+use crate::backend::Tree;
+use crate::backend::TreeId;
+use crate::backend::TreeValue;
+use crate::local_backend;
+use crate::merge::Merge;
+use crate::merge::MergedTreeValue;
+use crate::object_id::ObjectId;
+use crate::op_store::OpStoreError;
+use crate::op_store::OperationId;
+use crate::op_store::RefTarget;
+use crate::op_store::ViewId;
+use crate::op_walk;
+use crate::operation::Operation;
+use crate::repo::ReadonlyRepo;
+use crate::repo::Repo as _;
+use crate::repo::RepoLoader;
+use crate::repo_path::RepoPath;
+use crate::simple_op_store;
+use crate::store::Store;
+use crate::view::View;
+
+const MAGIC: &[u8] = b"jj-repo-archive-v1\n";
+
+/// Error that may occur while exporting or importing a repo archive.
+#[derive(Debug, Error)]
+pub enum RepoArchiveError {
+    /// Failed to read or write the archive.
+    #[error("Failed to access the archive")]
+    Io(#[from] io::Error),
+    /// The archive is corrupt or was written by an incompatible version.
+    #[error("Invalid archive: {0}")]
+    InvalidArchive(String),
+    /// Failed to read or write the repo objects.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// Failed to read or write the operations or views.
+    #[error(transparent)]
+    OpStore(#[from] OpStoreError),
+}
+
+/// Number of objects exported or imported.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RepoArchiveStats {
+    /// Operations, excluding the root operation.
+    pub operation_count: usize,
+    /// Commits, excluding the root commit.
+    pub commit_count: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum RecordKind {
+    RootCommit = 1,
+    RootOperation = 2,
+    File = 3,
+    Symlink = 4,
+    Conflict = 5,
+    Tree = 6,
+    Commit = 7,
+    View = 8,
+    Operation = 9,
+}
+
+impl RecordKind {
+    fn from_byte(byte: u8) -> Option<Self> {
+        let kind = match byte {
+            1 => RecordKind::RootCommit,
+            2 => RecordKind::RootOperation,
+            3 => RecordKind::File,
+            4 => RecordKind::Symlink,
+            5 => RecordKind::Conflict,
+            6 => RecordKind::Tree,
+            7 => RecordKind::Commit,
+            8 => RecordKind::View,
+            9 => RecordKind::Operation,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// Id of an object which is stored in the archive as a record of `KIND`.
+trait ArchivedId: ObjectId + Sized {
+    const KIND: RecordKind;
+    fn from_vec(bytes: Vec<u8>) -> Self;
+}
+
+macro_rules! impl_archived_id {
+    ($($name:ident => $kind:ident),* $(,)?) => {
+        $(
+            impl ArchivedId for $name {
+                const KIND: RecordKind = RecordKind::$kind;
+                fn from_vec(bytes: Vec<u8>) -> Self {
+                    $name::new(bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_archived_id!(
+    FileId => File,
+    SymlinkId => Symlink,
+    ConflictId => Conflict,
+    TreeId => Tree,
+    CommitId => Commit,
+    ViewId => View,
+    OperationId => Operation,
+);
+
+/// Writes the operation log of the `repo` up to its current operation, and
+/// all objects reachable from it, to the `output`.
+pub fn export_repo(
+    repo: &ReadonlyRepo,
+    output: &mut dyn Write,
+) -> Result<RepoArchiveStats, RepoArchiveError> {
+    let store = repo.store();
+    let op_store = repo.op_store();
+    let mut exporter = Exporter {
+        store,
+        output,
+        exported: HashSet::new(),
+        stats: RepoArchiveStats::default(),
+    };
+    exporter.output.write_all(MAGIC)?;
+    exporter.write_record(
+        RecordKind::RootCommit,
+        store.root_commit_id().as_bytes(),
+        &[],
+    )?;
+    exporter.write_record(
+        RecordKind::RootOperation,
+        op_store.root_operation_id().as_bytes(),
+        &[],
+    )?;
+
+    // Ancestors are emitted after descendants, so reverse the order to
+    // write the parent operations first.
+    let operations: Vec<Operation> =
+        op_walk::walk_ancestors(slice::from_ref(repo.operation())).collect::<Result<_, _>>()?;
+    for operation in operations.iter().rev() {
+        if operation.id() == op_store.root_operation_id() {
+            continue;
+        }
+        let view = op_store.read_view(operation.view_id())?;
+        let commit_ids = View::new(view.clone())
+            .all_referenced_commit_ids()
+            .cloned()
+            .collect();
+        exporter.export_commits(commit_ids)?;
+        exporter.write_record(
+            RecordKind::View,
+            operation.view_id().as_bytes(),
+            &simple_op_store::view_to_proto(&view).encode_to_vec(),
+        )?;
+        exporter.write_record(
+            RecordKind::Operation,
+            operation.id().as_bytes(),
+            &simple_op_store::operation_to_proto(operation.store_operation()).encode_to_vec(),
+        )?;
+        exporter.stats.operation_count += 1;
+    }
+    Ok(exporter.stats)
+}
+
+struct Exporter<'a> {
+    store: &'a Arc<Store>,
+    output: &'a mut dyn Write,
+    exported: HashSet<(RecordKind, Vec<u8>)>,
+    stats: RepoArchiveStats,
+}
+
+impl Exporter<'_> {
+    fn write_record(
+        &mut self,
+        kind: RecordKind,
+        id: &[u8],
+        data: &[u8],
+    ) -> Result<(), RepoArchiveError> {
+        self.output.write_all(&[kind as u8])?;
+        self.output.write_all(&(id.len() as u64).to_be_bytes())?;
+        self.output.write_all(id)?;
+        self.output.write_all(&(data.len() as u64).to_be_bytes())?;
+        self.output.write_all(data)?;
+        self.exported.insert((kind, id.to_vec()));
+        Ok(())
+    }
+
+    fn is_exported<T: ArchivedId>(&self, id: &T) -> bool {
+        self.exported.contains(&(T::KIND, id.to_bytes()))
+    }
+
+    /// Exports the commits and their ancestors and predecessors, parents
+    /// first.
+    fn export_commits(&mut self, commit_ids: Vec<CommitId>) -> Result<(), RepoArchiveError> {
+        let root_commit_id = self.store.root_commit_id();
+        let mut pending = commit_ids
+            .into_iter()
+            .map(|id| (id, false))
+            .collect::<Vec<_>>();
+        while let Some((id, visited)) = pending.pop() {
+            if id == *root_commit_id || self.is_exported(&id) {
+                continue;
+            }
+            let commit = self.store.get_commit(&id)?;
+            if !visited {
+                pending.push((id, true));
+                pending.extend(commit.parent_ids().iter().map(|id| (id.clone(), false)));
+                for predecessor_id in commit.predecessor_ids() {
+                    // Predecessors may have been garbage collected.
+                    match self.store.get_commit(predecessor_id) {
+                        Ok(_) => pending.push((predecessor_id.clone(), false)),
+                        Err(BackendError::ObjectNotFound { .. }) => {}
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+                continue;
+            }
+            let mut data = commit.store_commit().clone();
+            data.predecessors.retain(|id| self.is_exported(id));
+            for tree_id in data.root_tree.to_merge().iter() {
+                self.export_tree(RepoPath::root(), tree_id)?;
+            }
+            self.write_record(
+                RecordKind::Commit,
+                id.as_bytes(),
+                &local_backend::commit_to_proto(&data).encode_to_vec(),
+            )?;
+            self.stats.commit_count += 1;
+        }
+        Ok(())
+    }
+
+    fn export_tree(&mut self, dir: &RepoPath, id: &TreeId) -> Result<(), RepoArchiveError> {
+        if self.is_exported(id) {
+            return Ok(());
+        }
+        let tree = self.store.get_tree(dir.to_owned(), id)?;
+        for entry in tree.entries_non_recursive() {
+            self.export_value(&dir.join(entry.name()), entry.value())?;
+        }
+        self.write_record(
+            RecordKind::Tree,
+            id.as_bytes(),
+            &local_backend::tree_to_proto(tree.data()).encode_to_vec(),
+        )
+    }
+
+    fn export_value(&mut self, path: &RepoPath, value: &TreeValue) -> Result<(), RepoArchiveError> {
+        match value {
+            TreeValue::File { id, executable: _ } => {
+                if !self.is_exported(id) {
+                    let mut contents = vec![];
+                    self.store.read_file(path, id)?.read_to_end(&mut contents)?;
+                    self.write_record(RecordKind::File, id.as_bytes(), &contents)?;
+                }
+            }
+            TreeValue::Symlink(id) => {
+                if !self.is_exported(id) {
+                    let target = self.store.read_symlink(path, id)?;
+                    self.write_record(RecordKind::Symlink, id.as_bytes(), target.as_bytes())?;
+                }
+            }
+            TreeValue::Tree(id) => self.export_tree(path, id)?,
+            TreeValue::GitSubmodule(_) => {}
+            TreeValue::Conflict(id) => {
+                if !self.is_exported(id) {
+                    let conflict = self.store.read_conflict(path, id)?;
+                    for value in conflict.iter().flatten() {
+                        self.export_value(path, value)?;
+                    }
+                    let data = local_backend::conflict_to_proto(&conflict.into_backend_conflict());
+                    self.write_record(RecordKind::Conflict, id.as_bytes(), &data.encode_to_vec())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the objects and operations from the archive `input` to the repo.
+///
+/// Returns the imported head operation. The operation heads of the repo are
+/// left alone, so it's up to the caller to make the operation visible. The
+/// Git refs recorded in the views aren't imported since they describe the
+/// source repo's Git repository.
+pub fn import_repo(
+    repo_loader: &RepoLoader,
+    input: &mut dyn Read,
+) -> Result<(Operation, RepoArchiveStats), RepoArchiveError> {
+    let mut magic = vec![0; MAGIC.len()];
+    input
+        .read_exact(&mut magic)
+        .map_err(|_| invalid_archive("missing header"))?;
+    if magic != MAGIC {
+        return Err(invalid_archive("unknown format"));
+    }
+    let mut importer = Importer {
+        store: repo_loader.store(),
+        id_map: HashMap::new(),
+    };
+    let op_store = repo_loader.op_store();
+    let mut stats = RepoArchiveStats::default();
+    let mut head_id = None;
+    while let Some(Record { kind, id, data }) = read_record(input)? {
+        let new_id = match kind {
+            RecordKind::RootCommit => importer.store.root_commit_id().to_bytes(),
+            RecordKind::RootOperation => op_store.root_operation_id().to_bytes(),
+            RecordKind::File => {
+                let path = RepoPath::root();
+                let id = importer
+                    .store
+                    .write_file(path, &mut data.as_slice())
+                    .block_on()?;
+                id.to_bytes()
+            }
+            RecordKind::Symlink => {
+                let target = String::from_utf8(data)
+                    .map_err(|_| invalid_archive("symlink target is not valid UTF-8"))?;
+                let path = RepoPath::root();
+                let id = importer.store.write_symlink(path, &target).block_on()?;
+                id.to_bytes()
+            }
+            RecordKind::Conflict => {
+                let proto = crate::protos::local_store::Conflict::decode(data.as_slice())
+                    .map_err(decode_error)?;
+                let conflict =
+                    Merge::from_backend_conflict(local_backend::conflict_from_proto(proto));
+                let conflict = importer.map_conflict(&conflict)?;
+                let id = importer.store.write_conflict(RepoPath::root(), &conflict)?;
+                id.to_bytes()
+            }
+            RecordKind::Tree => {
+                let proto = crate::protos::local_store::Tree::decode(data.as_slice())
+                    .map_err(decode_error)?;
+                let tree = importer.map_tree(&local_backend::tree_from_proto(proto))?;
+                let tree = importer
+                    .store
+                    .write_tree(RepoPath::root(), tree)
+                    .block_on()?;
+                tree.id().to_bytes()
+            }
+            RecordKind::Commit => {
+                let proto = crate::protos::local_store::Commit::decode(data.as_slice())
+                    .map_err(decode_error)?;
+                let mut commit = local_backend::commit_from_proto(proto);
+                commit.parents = importer.map_ids(&commit.parents)?;
+                commit.predecessors = importer.map_ids(&commit.predecessors)?;
+                commit.root_tree = match &commit.root_tree {
+                    MergedTreeId::Legacy(tree_id) => {
+                        MergedTreeId::Legacy(importer.map_id(tree_id)?)
+                    }
+                    MergedTreeId::Merge(tree_ids) => {
+                        MergedTreeId::Merge(tree_ids.try_map(|id| importer.map_id(id))?)
+                    }
+                };
+                let commit = importer.store.write_commit(commit, None).block_on()?;
+                stats.commit_count += 1;
+                commit.id().to_bytes()
+            }
+            RecordKind::View => {
+                let proto =
+                    crate::protos::op_store::View::decode(data.as_slice()).map_err(decode_error)?;
+                let view = importer.map_view(simple_op_store::view_from_proto(proto))?;
+                op_store.write_view(&view)?.to_bytes()
+            }
+            RecordKind::Operation => {
+                let proto = crate::protos::op_store::Operation::decode(data.as_slice())
+                    .map_err(decode_error)?;
+                let mut operation = simple_op_store::operation_from_proto(proto);
+                operation.view_id = importer.map_id(&operation.view_id)?;
+                operation.parents = importer.map_ids(&operation.parents)?;
+                let new_id = op_store.write_operation(&operation)?;
+                stats.operation_count += 1;
+                head_id = Some(new_id.clone());
+                new_id.to_bytes()
+            }
+        };
+        let kind = match kind {
+            RecordKind::RootCommit => RecordKind::Commit,
+            RecordKind::RootOperation => RecordKind::Operation,
+            kind => kind,
+        };
+        importer.id_map.insert((kind, id), new_id);
+    }
+    let head_id = head_id.ok_or_else(|| invalid_archive("no operations"))?;
+    let head_op = repo_loader.load_operation(&head_id)?;
+    Ok((head_op, stats))
+}
+
+struct Record {
+    kind: RecordKind,
+    id: Vec<u8>,
+    data: Vec<u8>,
+}
+
+fn read_record(input: &mut dyn Read) -> Result<Option<Record>, RepoArchiveError> {
+    let mut kind = [0];
+    if input.read(&mut kind)? == 0 {
+        return Ok(None);
+    }
+    let kind = RecordKind::from_byte(kind[0]).ok_or_else(|| invalid_archive("unknown record"))?;
+    let id = read_bytes(input)?;
+    let data = read_bytes(input)?;
+    Ok(Some(Record { kind, id, data }))
+}
+
+fn read_bytes(input: &mut dyn Read) -> Result<Vec<u8>, RepoArchiveError> {
+    let mut len = [0; 8];
+    input
+        .read_exact(&mut len)
+        .map_err(|_| invalid_archive("truncated record"))?;
+    let len = u64::from_be_bytes(len);
+    let mut bytes = vec![];
+    input.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(invalid_archive("truncated record"));
+    }
+    Ok(bytes)
+}
+
+fn invalid_archive(message: &str) -> RepoArchiveError {
+    RepoArchiveError::InvalidArchive(message.to_owned())
+}
+
+fn decode_error(err: prost::DecodeError) -> RepoArchiveError {
+    RepoArchiveError::InvalidArchive(err.to_string())
+}
+
+struct Importer<'a> {
+    store: &'a Arc<Store>,
+    /// Maps the ids in the archive to the ids in the destination repo.
+    id_map: HashMap<(RecordKind, Vec<u8>), Vec<u8>>,
+}
+
+impl Importer<'_> {
+    fn map_id<T: ArchivedId>(&self, id: &T) -> Result<T, RepoArchiveError> {
+        let new_id = self.id_map.get(&(T::KIND, id.to_bytes())).ok_or_else(|| {
+            RepoArchiveError::InvalidArchive(format!(
+                "{} {} is referenced before it's defined",
+                id.object_type(),
+                id.hex()
+            ))
+        })?;
+        Ok(T::from_vec(new_id.clone()))
+    }
+
+    fn map_ids<T: ArchivedId>(&self, ids: &[T]) -> Result<Vec<T>, RepoArchiveError> {
+        ids.iter().map(|id| self.map_id(id)).collect()
+    }
+
+    fn map_value(&self, value: &TreeValue) -> Result<TreeValue, RepoArchiveError> {
+        let value = match value {
+            TreeValue::File { id, executable } => TreeValue::File {
+                id: self.map_id(id)?,
+                executable: *executable,
+            },
+            TreeValue::Symlink(id) => TreeValue::Symlink(self.map_id(id)?),
+            TreeValue::Tree(id) => TreeValue::Tree(self.map_id(id)?),
+            TreeValue::GitSubmodule(id) => TreeValue::GitSubmodule(id.clone()),
+            TreeValue::Conflict(id) => TreeValue::Conflict(self.map_id(id)?),
+        };
+        Ok(value)
+    }
+
+    fn map_tree(&self, tree: &Tree) -> Result<Tree, RepoArchiveError> {
+        let mut new_tree = Tree::default();
+        for entry in tree.entries() {
+            new_tree.set(entry.name().to_owned(), self.map_value(entry.value())?);
+        }
+        Ok(new_tree)
+    }
+
+    fn map_conflict(
+        &self,
+        conflict: &MergedTreeValue,
+    ) -> Result<MergedTreeValue, RepoArchiveError> {
+        conflict.try_map(|value| {
+            value
+                .as_ref()
+                .map(|value| self.map_value(value))
+                .transpose()
+        })
+    }
+
+    fn map_ref_target(&self, target: &RefTarget) -> Result<RefTarget, RepoArchiveError> {
+        let merge = target
+            .as_merge()
+            .try_map(|id| id.as_ref().map(|id| self.map_id(id)).transpose())?;
+        Ok(RefTarget::from_merge(merge))
+    }
+
+    fn map_view(
+        &self,
+        view: crate::op_store::View,
+    ) -> Result<crate::op_store::View, RepoArchiveError> {
+        let crate::op_store::View {
+            head_ids,
+            local_bookmarks,
+            tags,
+            mut remote_views,
+            git_refs: _,
+            git_head: _,
+            wc_commit_ids,
+            change_aliases,
+            stash_ids,
+        } = view;
+        for remote_view in remote_views.values_mut() {
+            for remote_ref in remote_view.bookmarks.values_mut() {
+                remote_ref.target = self.map_ref_target(&remote_ref.target)?;
+            }
+        }
+        Ok(crate::op_store::View {
+            head_ids: head_ids
+                .iter()
+                .map(|id| self.map_id(id))
+                .collect::<Result<_, _>>()?,
+            local_bookmarks: local_bookmarks
+                .iter()
+                .map(|(name, target)| Ok((name.clone(), self.map_ref_target(target)?)))
+                .collect::<Result<_, RepoArchiveError>>()?,
+            tags: tags
+                .iter()
+                .map(|(name, target)| Ok((name.clone(), self.map_ref_target(target)?)))
+                .collect::<Result<_, RepoArchiveError>>()?,
+            remote_views,
+            git_refs: Default::default(),
+            git_head: RefTarget::absent(),
+            wc_commit_ids: wc_commit_ids
+                .iter()
+                .map(|(workspace_id, id)| Ok((workspace_id.clone(), self.map_id(id)?)))
+                .collect::<Result<_, RepoArchiveError>>()?,
+            change_aliases,
+            stash_ids: stash_ids
+                .iter()
+                .map(|id| self.map_id(id))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
@@ -26,6 +26,7 @@ use std::sync::Arc;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use thiserror::Error;
+use tracing::instrument;
 
 use crate::backend::BackendError;
 use crate::backend::ChangeId;
@@ -126,6 +127,8 @@ pub enum RevsetModifier {
 pub enum RevsetCommitRef {
     WorkingCopy(WorkspaceId),
     WorkingCopies,
+    /// Working-copy commits of the workspaces matching the pattern.
+    WorkingCopiesOf(StringPattern),
     Symbol(String),
     RemoteSymbol {
         name: String,
@@ -138,6 +141,8 @@ pub enum RevsetCommitRef {
         remote_ref_state: Option<RemoteRefState>,
     },
     Tags(StringPattern),
+    /// Visible commits of the change named by the user-assigned alias.
+    ChangeAlias(String),
     GitRefs,
     GitHead,
 }
@@ -323,6 +328,10 @@ impl<St: ExpressionState<CommitRef = RevsetCommitRef>> RevsetExpression<St> {
         Rc::new(Self::CommitRef(RevsetCommitRef::WorkingCopies))
     }
 
+    pub fn working_copies_of(pattern: StringPattern) -> Rc<Self> {
+        Rc::new(Self::CommitRef(RevsetCommitRef::WorkingCopiesOf(pattern)))
+    }
+
     pub fn symbol(value: String) -> Rc<Self> {
         Rc::new(Self::CommitRef(RevsetCommitRef::Symbol(value)))
     }
@@ -352,6 +361,10 @@ impl<St: ExpressionState<CommitRef = RevsetCommitRef>> RevsetExpression<St> {
         Rc::new(Self::CommitRef(RevsetCommitRef::Tags(pattern)))
     }
 
+    pub fn change_alias(name: String) -> Rc<Self> {
+        Rc::new(Self::CommitRef(RevsetCommitRef::ChangeAlias(name)))
+    }
+
     pub fn git_refs() -> Rc<Self> {
         Rc::new(Self::CommitRef(RevsetCommitRef::GitRefs))
     }
@@ -548,6 +561,7 @@ impl UserRevsetExpression {
 
 impl ResolvedRevsetExpression {
     /// Optimizes and evaluates this expression.
+    #[instrument(skip_all)]
     pub fn evaluate<'index>(
         self: Rc<Self>,
         repo: &'index dyn Repo,
@@ -559,6 +573,7 @@ impl ResolvedRevsetExpression {
     ///
     /// Use this function if `self` is already optimized, or to debug
     /// optimization pass.
+    #[instrument(skip_all)]
     pub fn evaluate_unoptimized<'index>(
         &self,
         repo: &'index dyn Repo,
@@ -703,6 +718,16 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         function.expect_no_arguments()?;
         Ok(RevsetExpression::working_copies())
     });
+    map.insert("ws", |diagnostics, function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let name: String = expect_literal(diagnostics, "string", arg)?;
+        Ok(RevsetExpression::working_copy(WorkspaceId::new(name)))
+    });
+    map.insert("wc_of", |diagnostics, function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(diagnostics, arg)?;
+        Ok(RevsetExpression::working_copies_of(pattern))
+    });
     map.insert("heads", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
         let candidates = lower_expression(diagnostics, arg, context)?;
@@ -1011,7 +1036,7 @@ pub fn expect_date_pattern(
         node,
         |_diagnostics, value, kind| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
             match kind {
-                None => Err("Date pattern must specify 'after' or 'before'".into()),
+                None => Ok(context.parse_range(value)?),
                 Some(kind) => Ok(context.parse_relative(value, kind)?),
             }
         },
@@ -1072,6 +1097,10 @@ pub fn lower_expression(
     match &node.kind {
         ExpressionKind::Identifier(name) => Ok(RevsetExpression::symbol((*name).to_owned())),
         ExpressionKind::String(name) => Ok(RevsetExpression::symbol(name.to_owned())),
+        ExpressionKind::StringPattern {
+            kind: "alias",
+            value,
+        } => Ok(RevsetExpression::change_alias(value.to_owned())),
         ExpressionKind::StringPattern { .. } => Err(RevsetParseError::with_span(
             RevsetParseErrorKind::NotInfixOperator {
                 op: ":".to_owned(),
@@ -2140,6 +2169,16 @@ fn resolve_commit_ref(
             let wc_commits = repo.view().wc_commit_ids().values().cloned().collect_vec();
             Ok(wc_commits)
         }
+        RevsetCommitRef::WorkingCopiesOf(pattern) => {
+            let wc_commits = repo
+                .view()
+                .wc_commit_ids()
+                .iter()
+                .filter(|(workspace_id, _)| pattern.matches(workspace_id.as_str()))
+                .map(|(_, commit_id)| commit_id.clone())
+                .collect_vec();
+            Ok(wc_commits)
+        }
         RevsetCommitRef::Bookmarks(pattern) => {
             let commit_ids = repo
                 .view()
@@ -2186,6 +2225,21 @@ fn resolve_commit_ref(
                 .collect();
             Ok(commit_ids)
         }
+        RevsetCommitRef::ChangeAlias(name) => {
+            let Some(change_id) = repo.view().get_change_alias(name) else {
+                let candidates =
+                    collect_similar(name, repo.view().change_aliases().map(|(name, _)| name));
+                return Err(RevsetResolutionError::NoSuchRevision {
+                    name: format!("alias:{name}"),
+                    candidates: candidates
+                        .into_iter()
+                        .map(|name| format!("alias:{name}"))
+                        .collect(),
+                });
+            };
+            // The change may have been abandoned since the alias was set.
+            Ok(repo.resolve_change_id(change_id).unwrap_or_default())
+        }
         RevsetCommitRef::GitRefs => {
             let mut commit_ids = vec![];
             for ref_target in repo.view().git_refs().values() {
@@ -3137,6 +3191,25 @@ mod tests {
         insta::assert_debug_snapshot!(
             parse_with_modifier(r#"exact:"foo""#).unwrap_err().kind(),
             @r###"NoSuchModifier("exact")"###);
+
+        // "alias:" is a change alias, not a modifier
+        insta::assert_debug_snapshot!(
+            parse_with_modifier("alias:foo | bar").unwrap(), @r#"
+        (
+            Union(
+                CommitRef(ChangeAlias("foo")),
+                CommitRef(Symbol("bar")),
+            ),
+            None,
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse_with_modifier("all:alias:foo").unwrap(), @r#"
+        (
+            CommitRef(ChangeAlias("foo")),
+            Some(All),
+        )
+        "#);
     }
 
     #[test]
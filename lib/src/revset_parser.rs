@@ -126,6 +126,7 @@ impl Rule {
             Rule::neighbors_expression => None,
             Rule::range_expression => None,
             Rule::expression => None,
+            Rule::change_alias_kind => None,
             Rule::program_modifier => None,
             Rule::program => None,
             Rule::function_alias_declaration => None,
@@ -14,7 +14,9 @@
 
 #![allow(missing_docs)]
 
+use std::collections::HashMap;
 use std::path::Path;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -35,9 +37,13 @@ use crate::config::ConfigValue;
 use crate::config::StackedConfig;
 use crate::config::ToConfigNamePath;
 use crate::conflicts::ConflictMarkerStyle;
+use crate::default_index::IndexLoadMode;
+use crate::file_util::expand_home_path;
 use crate::fmt_util::binary_prefix;
 use crate::fsmonitor::FsmonitorSettings;
 use crate::signing::SignBehavior;
+use crate::working_copy::FileStateTrust;
+use crate::working_copy::SymlinkPolicy;
 
 #[derive(Debug, Clone)]
 pub struct UserSettings {
@@ -55,6 +61,32 @@ pub struct RepoSettings {
 pub struct GitSettings {
     pub auto_local_bookmark: bool,
     pub abandon_unreachable_commits: bool,
+    /// Whether to delete remote-tracking refs that no longer exist on the
+    /// remote when fetching.
+    pub fetch_prune: bool,
+    /// Per-remote overrides of `fetch_prune`.
+    pub remote_fetch_prune: HashMap<String, bool>,
+    /// Per-remote refspecs to fetch instead of all branches.
+    pub remote_fetch_refspecs: HashMap<String, Vec<String>>,
+    /// Per-remote refspecs mapping pushed bookmark refs to the remote refs.
+    pub remote_push_refspecs: HashMap<String, Vec<String>>,
+    /// Whether to ask the Git credential helpers for credentials.
+    pub credential_helper: bool,
+    /// Whether to ask the SSH agent for keys.
+    pub ssh_agent: bool,
+    /// SSH private keys to try instead of the default ones.
+    pub ssh_keys: Vec<PathBuf>,
+    /// Per-remote overrides of `ssh_keys`.
+    pub remote_ssh_keys: HashMap<String, Vec<PathBuf>>,
+    /// Command to ask the user for passwords, like Git's `core.askpass`.
+    pub askpass: Option<String>,
+    /// Proxy to connect to HTTP(S) remotes through, instead of the one
+    /// configured by Git or the environment.
+    pub proxy: Option<String>,
+    /// Hosts to connect to without going through a proxy.
+    pub no_proxy: Vec<String>,
+    /// CA certificates bundle to verify HTTPS remotes with.
+    pub ssl_ca_info: Option<PathBuf>,
 }
 
 impl GitSettings {
@@ -66,11 +98,101 @@ impl GitSettings {
         let abandon_unreachable_commits = settings
             .get_bool("git.abandon-unreachable-commits")
             .unwrap_or(true);
+        let fetch_prune = settings.get_bool("git.fetch-prune").unwrap_or(true);
+        let remote_fetch_prune = settings
+            .table_keys("remotes")
+            .filter_map(|remote| {
+                let prune = settings.get_bool(["remotes", remote, "fetch-prune"]).ok()?;
+                Some((remote.to_owned(), prune))
+            })
+            .collect();
+        let remote_refspecs = |key: &'static str| {
+            settings
+                .table_keys("remotes")
+                .filter_map(|remote| {
+                    let refspecs = settings.get(["remotes", remote, key]).ok()?;
+                    Some((remote.to_owned(), refspecs))
+                })
+                .collect()
+        };
+        // A single path or a list of paths
+        let get_ssh_keys = |key: &[&str]| -> Option<Vec<PathBuf>> {
+            let paths = settings
+                .get::<Vec<String>>(key)
+                .or_else(|_| settings.get_string(key).map(|path| vec![path]))
+                .ok()?;
+            Some(paths.iter().map(|path| expand_home_path(path)).collect())
+        };
+        let remote_ssh_keys = settings
+            .table_keys("remotes")
+            .filter_map(|remote| {
+                let paths = get_ssh_keys(&["remotes", remote, "ssh-key"])?;
+                Some((remote.to_owned(), paths))
+            })
+            .collect();
         GitSettings {
             auto_local_bookmark,
             abandon_unreachable_commits,
+            fetch_prune,
+            remote_fetch_prune,
+            remote_fetch_refspecs: remote_refspecs("fetch-refspecs"),
+            remote_push_refspecs: remote_refspecs("push-refspecs"),
+            credential_helper: settings.get_bool("git.credential-helper").unwrap_or(true),
+            ssh_agent: settings.get_bool("git.ssh-agent").unwrap_or(true),
+            ssh_keys: get_ssh_keys(&["git", "ssh-key"]).unwrap_or_default(),
+            remote_ssh_keys,
+            askpass: settings.get_string("git.askpass").ok(),
+            proxy: settings.get_string("git.proxy").ok(),
+            // A list of hosts, or a comma-separated string like $NO_PROXY
+            no_proxy: settings
+                .get::<Vec<String>>("git.no-proxy")
+                .or_else(|_| {
+                    settings.get_string("git.no-proxy").map(|hosts| {
+                        hosts
+                            .split(',')
+                            .map(|host| host.trim().to_owned())
+                            .filter(|host| !host.is_empty())
+                            .collect()
+                    })
+                })
+                .unwrap_or_default(),
+            ssl_ca_info: settings
+                .get_string("git.ssl-ca-info")
+                .ok()
+                .map(|path| expand_home_path(&path)),
         }
     }
+
+    /// Whether to prune deleted refs when fetching from the given remote.
+    pub fn fetch_prune_for(&self, remote_name: &str) -> bool {
+        self.remote_fetch_prune
+            .get(remote_name)
+            .copied()
+            .unwrap_or(self.fetch_prune)
+    }
+
+    /// Refspecs to fetch from the given remote. Empty if not configured.
+    pub fn fetch_refspecs_for(&self, remote_name: &str) -> &[String] {
+        self.remote_fetch_refspecs
+            .get(remote_name)
+            .map_or(&[], |refspecs| refspecs)
+    }
+
+    /// Refspecs mapping bookmark refs pushed to the given remote. Empty if not
+    /// configured.
+    pub fn push_refspecs_for(&self, remote_name: &str) -> &[String] {
+        self.remote_push_refspecs
+            .get(remote_name)
+            .map_or(&[], |refspecs| refspecs)
+    }
+
+    /// SSH private keys to try for the given remote, or for any remote if
+    /// `None`. Empty if the default keys should be used.
+    pub fn ssh_keys_for(&self, remote_name: Option<&str>) -> &[PathBuf] {
+        remote_name
+            .and_then(|name| self.remote_ssh_keys.get(name))
+            .unwrap_or(&self.ssh_keys)
+    }
 }
 
 impl Default for GitSettings {
@@ -78,6 +200,18 @@ impl Default for GitSettings {
         GitSettings {
             auto_local_bookmark: false,
             abandon_unreachable_commits: true,
+            fetch_prune: true,
+            remote_fetch_prune: HashMap::new(),
+            remote_fetch_refspecs: HashMap::new(),
+            remote_push_refspecs: HashMap::new(),
+            credential_helper: true,
+            ssh_agent: true,
+            ssh_keys: vec![],
+            remote_ssh_keys: HashMap::new(),
+            askpass: None,
+            proxy: None,
+            no_proxy: vec![],
+            ssl_ca_info: None,
         }
     }
 }
@@ -247,6 +381,35 @@ impl UserSettings {
         }
     }
 
+    pub fn file_state_trust(&self) -> Result<FileStateTrust, ConfigGetError> {
+        self.get("snapshot.file-state-trust")
+            .optional()
+            .map(Option::unwrap_or_default)
+    }
+
+    pub fn symlink_policy(&self) -> Result<SymlinkPolicy, ConfigGetError> {
+        self.get("working-copy.symlinks")
+            .optional()
+            .map(Option::unwrap_or_default)
+    }
+
+    pub fn index_load_mode(&self) -> Result<IndexLoadMode, ConfigGetError> {
+        self.get("index.load-mode")
+            .optional()
+            .map(Option::unwrap_or_default)
+    }
+
+    pub fn rehash_throttle_size(&self) -> Result<u64, ConfigGetError> {
+        let cfg = self
+            .get_value_with("snapshot.rehash-throttle-size", TryInto::try_into)
+            .map(|HumanByteSize(x)| x);
+        match cfg {
+            Ok(0) | Err(ConfigGetError::NotFound { .. }) => Ok(u64::MAX),
+            x @ Ok(_) => x,
+            e @ Err(_) => e,
+        }
+    }
+
     // separate from sign_settings as those two are needed in pretty different
     // places
     pub fn signing_backend(&self) -> Option<String> {
@@ -415,6 +578,8 @@ mod tests {
     use assert_matches::assert_matches;
 
     use super::*;
+    use crate::config::ConfigLayer;
+    use crate::config::ConfigSource;
 
     #[test]
     fn byte_size_parse() {
@@ -454,4 +619,76 @@ mod tests {
             Err("Integer out of range")
         );
     }
+
+    #[test]
+    fn git_settings_credentials() {
+        let git_settings = |text: &str| {
+            let mut config = StackedConfig::empty();
+            config.add_layer(ConfigLayer::parse(ConfigSource::User, text).unwrap());
+            UserSettings::from_config(config).git_settings()
+        };
+
+        let settings = git_settings("");
+        assert!(settings.credential_helper);
+        assert!(settings.ssh_agent);
+        assert!(settings.ssh_keys_for(None).is_empty());
+        assert!(settings.ssh_keys_for(Some("origin")).is_empty());
+        assert_eq!(settings.askpass, None);
+
+        let settings = git_settings(
+            r#"
+            git.credential-helper = false
+            git.ssh-agent = false
+            git.ssh-key = "/keys/default"
+            git.askpass = "my-askpass"
+            remotes.work.ssh-key = ["/keys/work1", "/keys/work2"]
+            "#,
+        );
+        assert!(!settings.credential_helper);
+        assert!(!settings.ssh_agent);
+        assert_eq!(
+            settings.ssh_keys_for(None),
+            [PathBuf::from("/keys/default")]
+        );
+        assert_eq!(
+            settings.ssh_keys_for(Some("origin")),
+            [PathBuf::from("/keys/default")]
+        );
+        assert_eq!(
+            settings.ssh_keys_for(Some("work")),
+            [PathBuf::from("/keys/work1"), PathBuf::from("/keys/work2")]
+        );
+        assert_eq!(settings.askpass.as_deref(), Some("my-askpass"));
+    }
+
+    #[test]
+    fn git_settings_proxy() {
+        let git_settings = |text: &str| {
+            let mut config = StackedConfig::empty();
+            config.add_layer(ConfigLayer::parse(ConfigSource::User, text).unwrap());
+            UserSettings::from_config(config).git_settings()
+        };
+
+        let settings = git_settings("");
+        assert_eq!(settings.proxy, None);
+        assert!(settings.no_proxy.is_empty());
+        assert_eq!(settings.ssl_ca_info, None);
+
+        let settings = git_settings(
+            r#"
+            git.proxy = "http://proxy.example.com:3128"
+            git.no-proxy = "localhost, .internal.example.com,"
+            git.ssl-ca-info = "/certs/ca.pem"
+            "#,
+        );
+        assert_eq!(
+            settings.proxy.as_deref(),
+            Some("http://proxy.example.com:3128")
+        );
+        assert_eq!(settings.no_proxy, ["localhost", ".internal.example.com"]);
+        assert_eq!(settings.ssl_ca_info, Some(PathBuf::from("/certs/ca.pem")));
+
+        let settings = git_settings(r#"git.no-proxy = ["localhost", "example.com"]"#);
+        assert_eq!(settings.no_proxy, ["localhost", "example.com"]);
+    }
 }
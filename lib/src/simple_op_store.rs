@@ -32,6 +32,7 @@ use prost::Message;
 use tempfile::NamedTempFile;
 use thiserror::Error;
 
+use crate::backend::ChangeId;
 use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
 use crate::backend::Timestamp;
@@ -401,7 +402,7 @@ fn operation_metadata_from_proto(
     }
 }
 
-fn operation_to_proto(operation: &Operation) -> crate::protos::op_store::Operation {
+pub(crate) fn operation_to_proto(operation: &Operation) -> crate::protos::op_store::Operation {
     let mut proto = crate::protos::op_store::Operation {
         view_id: operation.view_id.as_bytes().to_vec(),
         metadata: Some(operation_metadata_to_proto(&operation.metadata)),
@@ -413,7 +414,7 @@ fn operation_to_proto(operation: &Operation) -> crate::protos::op_store::Operati
     proto
 }
 
-fn operation_from_proto(proto: crate::protos::op_store::Operation) -> Operation {
+pub(crate) fn operation_from_proto(proto: crate::protos::op_store::Operation) -> Operation {
     let parents = proto.parents.into_iter().map(OperationId::new).collect();
     let view_id = ViewId::new(proto.view_id);
     let metadata = operation_metadata_from_proto(proto.metadata.unwrap_or_default());
@@ -424,7 +425,7 @@ fn operation_from_proto(proto: crate::protos::op_store::Operation) -> Operation
     }
 }
 
-fn view_to_proto(view: &View) -> crate::protos::op_store::View {
+pub(crate) fn view_to_proto(view: &View) -> crate::protos::op_store::View {
     let mut proto = crate::protos::op_store::View {
         // New/loaded view should have been migrated to the latest format
         has_git_refs_migrated_to_remote: true,
@@ -458,10 +459,18 @@ fn view_to_proto(view: &View) -> crate::protos::op_store::View {
 
     proto.git_head = ref_target_to_proto(&view.git_head);
 
+    for (name, change_id) in &view.change_aliases {
+        proto
+            .change_aliases
+            .insert(name.clone(), change_id.to_bytes());
+    }
+
+    proto.stash_ids = view.stash_ids.iter().map(|id| id.to_bytes()).collect();
+
     proto
 }
 
-fn view_from_proto(proto: crate::protos::op_store::View) -> View {
+pub(crate) fn view_from_proto(proto: crate::protos::op_store::View) -> View {
     let mut view = View::empty();
     // For compatibility with old repos before we had support for multiple working
     // copies
@@ -504,6 +513,12 @@ fn view_from_proto(proto: crate::protos::op_store::View) -> View {
         view.git_head = RefTarget::normal(CommitId::new(proto.git_head_legacy));
     }
 
+    for (name, change_id) in proto.change_aliases {
+        view.change_aliases.insert(name, ChangeId::new(change_id));
+    }
+
+    view.stash_ids = proto.stash_ids.into_iter().map(CommitId::new).collect();
+
     if !proto.has_git_refs_migrated_to_remote {
         migrate_git_refs_to_remote(&mut view);
     }
@@ -771,6 +786,8 @@ mod tests {
                 WorkspaceId::default() => default_wc_commit_id,
                 WorkspaceId::new("test".to_string()) => test_wc_commit_id,
             },
+            change_aliases: btreemap! {},
+            stash_ids: vec![],
         }
     }
 
@@ -833,6 +850,42 @@ mod tests {
         assert_eq!(read_view, view);
     }
 
+    #[test]
+    fn test_read_write_view_with_change_aliases() {
+        let temp_dir = testutils::new_temp_dir();
+        let root_data = RootOperationData {
+            root_commit_id: CommitId::from_hex("000000"),
+        };
+        let store = SimpleOpStore::init(temp_dir.path(), root_data);
+        let view = View {
+            change_aliases: btreemap! {
+                "fix-login".to_string() => ChangeId::from_hex("eee111"),
+            },
+            ..create_view()
+        };
+        let view_id = store.write_view(&view).unwrap();
+        assert_ne!(view_id, store.write_view(&create_view()).unwrap());
+        let read_view = store.read_view(&view_id).unwrap();
+        assert_eq!(read_view, view);
+    }
+
+    #[test]
+    fn test_read_write_view_with_stashes() {
+        let temp_dir = testutils::new_temp_dir();
+        let root_data = RootOperationData {
+            root_commit_id: CommitId::from_hex("000000"),
+        };
+        let store = SimpleOpStore::init(temp_dir.path(), root_data);
+        let view = View {
+            stash_ids: vec![CommitId::from_hex("ddd222"), CommitId::from_hex("ddd111")],
+            ..create_view()
+        };
+        let view_id = store.write_view(&view).unwrap();
+        assert_ne!(view_id, store.write_view(&create_view()).unwrap());
+        let read_view = store.read_view(&view_id).unwrap();
+        assert_eq!(read_view, view);
+    }
+
     #[test]
     fn test_read_write_operation() {
         let temp_dir = testutils::new_temp_dir();
@@ -542,7 +542,12 @@ impl TableStore {
         }
         let merged_table = self.save_table(merged_table)?;
         for table in &tables[1..] {
-            self.remove_head(table);
+            // If one of the heads already contained all the others (e.g. because
+            // it was copied from another repo along with its ancestors), the
+            // merged table is identical to it.
+            if table.name != merged_table.name {
+                self.remove_head(table);
+            }
         }
         Ok((merged_table, lock))
     }
@@ -722,6 +727,33 @@ mod tests {
         assert_eq!(merged_table.get_value(b"\xff\xff\xff"), None);
     }
 
+    #[test]
+    fn stacked_table_automatic_merge_with_ancestor() {
+        // The result depends on the order the heads are listed in, which depends
+        // on the table names, so try with a few different tables.
+        for i in 0..10 {
+            let temp_dir = testutils::new_temp_dir();
+            let store = TableStore::init(temp_dir.path().to_path_buf(), 3);
+            let mut mut_base_table = store.get_head().unwrap().start_mutation();
+            mut_base_table.add_entry(b"abc".to_vec(), format!("value{i}").into_bytes());
+            let base_table = store.save_table(mut_base_table).unwrap();
+            let mut mut_table1 = MutableTable::incremental(base_table.clone());
+            mut_table1.add_entry(b"abd".to_vec(), b"value 2".to_vec());
+            store.save_table(mut_table1).unwrap();
+            // Make the ancestor a head again
+            store.add_head(&base_table).unwrap();
+
+            let merged_table = store.get_head().unwrap();
+            assert_eq!(merged_table.get_value(b"abd"), Some(b"value 2".as_slice()));
+
+            // The merged table is still recorded as a head
+            let store = TableStore::load(temp_dir.path().to_path_buf(), 3);
+            let head_table = store.get_head().unwrap();
+            assert_eq!(head_table.name, merged_table.name);
+            assert_eq!(head_table.get_value(b"abd"), Some(b"value 2".as_slice()));
+        }
+    }
+
     #[test]
     fn stacked_table_store_save_empty() {
         let temp_dir = testutils::new_temp_dir();
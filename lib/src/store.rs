@@ -25,6 +25,9 @@ use std::time::SystemTime;
 use clru::CLruCache;
 use futures::stream::BoxStream;
 use pollster::FutureExt;
+use tracing::info_span;
+use tracing::instrument;
+use tracing::Instrument as _;
 
 use crate::backend;
 use crate::backend::Backend;
@@ -85,6 +88,11 @@ impl Store {
         self.backend.as_any()
     }
 
+    /// The name of the backend, as written to `.jj/repo/store/type`.
+    pub fn backend_name(&self) -> &str {
+        self.backend.name()
+    }
+
     pub fn signer(&self) -> &Signer {
         &self.signer
     }
@@ -146,13 +154,18 @@ impl Store {
                 return Ok(data);
             }
         }
-        let commit = self.backend.read_commit(id).await?;
+        let commit = self
+            .backend
+            .read_commit(id)
+            .instrument(info_span!("read_commit"))
+            .await?;
         let data = Arc::new(commit);
         let mut locked_cache = self.commit_cache.lock().unwrap();
         locked_cache.put(id.clone(), data.clone());
         Ok(data)
     }
 
+    #[instrument(skip_all)]
     pub async fn write_commit(
         self: &Arc<Self>,
         commit: backend::Commit,
@@ -195,7 +208,11 @@ impl Store {
                 return Ok(data);
             }
         }
-        let data = self.backend.read_tree(dir, id).await?;
+        let data = self
+            .backend
+            .read_tree(dir, id)
+            .instrument(info_span!("read_tree"))
+            .await?;
         let data = Arc::new(data);
         let mut locked_cache = self.tree_cache.lock().unwrap();
         locked_cache.put(key, data.clone());
@@ -215,6 +232,7 @@ impl Store {
         }
     }
 
+    #[instrument(skip_all)]
     pub async fn write_tree(
         self: &Arc<Self>,
         path: &RepoPath,
@@ -234,6 +252,7 @@ impl Store {
         self.read_file_async(path, id).block_on()
     }
 
+    #[instrument(skip_all)]
     pub async fn read_file_async(
         &self,
         path: &RepoPath,
@@ -242,6 +261,7 @@ impl Store {
         self.backend.read_file(path, id).await
     }
 
+    #[instrument(skip_all)]
     pub async fn write_file(
         &self,
         path: &RepoPath,
@@ -254,6 +274,7 @@ impl Store {
         self.read_symlink_async(path, id).block_on()
     }
 
+    #[instrument(skip_all)]
     pub async fn read_symlink_async(
         &self,
         path: &RepoPath,
@@ -262,10 +283,12 @@ impl Store {
         self.backend.read_symlink(path, id).await
     }
 
+    #[instrument(skip_all)]
     pub async fn write_symlink(&self, path: &RepoPath, contents: &str) -> BackendResult<SymlinkId> {
         self.backend.write_symlink(path, contents).await
     }
 
+    #[instrument(skip_all)]
     pub fn read_conflict(
         &self,
         path: &RepoPath,
@@ -275,6 +298,7 @@ impl Store {
         Ok(Merge::from_backend_conflict(backend_conflict))
     }
 
+    #[instrument(skip_all)]
     pub fn write_conflict(
         &self,
         path: &RepoPath,
@@ -15,8 +15,11 @@
 //! Provides support for parsing and matching date ranges.
 
 use chrono::DateTime;
+use chrono::Days;
 use chrono::FixedOffset;
 use chrono::Local;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
 use chrono::TimeZone;
 use chrono_english::parse_date_string;
 use chrono_english::DateError;
@@ -37,16 +40,45 @@ pub enum DatePatternContext {
 
 impl DatePatternContext {
     /// Parses a DatePattern from the given string and kind.
+    ///
+    /// The string may end with a time zone such as `UTC` or `+09:00`, in which
+    /// case relative dates are interpreted in that time zone.
     pub fn parse_relative(
         &self,
         s: &str,
         kind: &str,
     ) -> Result<DatePattern, DatePatternParseError> {
+        if let Some((s, offset)) = split_time_zone(s) {
+            return self.with_offset(offset).parse_relative(s, kind);
+        }
         match *self {
             DatePatternContext::Local(dt) => DatePattern::from_str_kind(s, kind, dt),
             DatePatternContext::Fixed(dt) => DatePattern::from_str_kind(s, kind, dt),
         }
     }
+
+    /// Parses a DatePattern from the given string, which is either a range
+    /// `start..end` with optional ends, or a single date matching the whole
+    /// day.
+    ///
+    /// The string may end with a time zone such as `UTC` or `+09:00`, in which
+    /// case dates are interpreted in that time zone.
+    pub fn parse_range(&self, s: &str) -> Result<DatePattern, DatePatternParseError> {
+        if let Some((s, offset)) = split_time_zone(s) {
+            return self.with_offset(offset).parse_range(s);
+        }
+        match *self {
+            DatePatternContext::Local(dt) => DatePattern::from_str_range(s, dt),
+            DatePatternContext::Fixed(dt) => DatePattern::from_str_range(s, dt),
+        }
+    }
+
+    fn with_offset(&self, offset: FixedOffset) -> Self {
+        match *self {
+            DatePatternContext::Local(dt) => DatePatternContext::Fixed(dt.with_timezone(&offset)),
+            DatePatternContext::Fixed(dt) => DatePatternContext::Fixed(dt.with_timezone(&offset)),
+        }
+    }
 }
 
 impl From<DateTime<Local>> for DatePatternContext {
@@ -70,6 +102,9 @@ pub enum DatePatternParseError {
     /// Failed to parse timestamp.
     #[error(transparent)]
     ParseError(#[from] DateError),
+    /// Date range has neither start nor end.
+    #[error("Date range must have a start or an end")]
+    EmptyRange,
 }
 
 /// Represents an range of dates that may be matched against.
@@ -79,6 +114,9 @@ pub enum DatePattern {
     AtOrAfter(MillisSinceEpoch),
     /// Represents all dates before, but not including, the given instant.
     Before(MillisSinceEpoch),
+    /// Represents all dates at or after the first instant and before, but not
+    /// including, the second instant.
+    Between(MillisSinceEpoch, MillisSinceEpoch),
 }
 
 impl DatePattern {
@@ -103,9 +141,7 @@ impl DatePattern {
     where
         Tz::Offset: Copy,
     {
-        let d =
-            parse_date_string(s, now, Dialect::Us).map_err(DatePatternParseError::ParseError)?;
-        let millis_since_epoch = MillisSinceEpoch(d.timestamp_millis());
+        let millis_since_epoch = MillisSinceEpoch(parse_date(s, now)?.timestamp_millis());
         match kind {
             "after" => Ok(DatePattern::AtOrAfter(millis_since_epoch)),
             "before" => Ok(DatePattern::Before(millis_since_epoch)),
@@ -113,17 +149,125 @@ impl DatePattern {
         }
     }
 
+    /// Parses a range of dates into a DatePattern.
+    ///
+    /// * `s` is either `start..end`, where either end may be omitted, or a
+    ///   single date. A single date matches the whole day it falls on, so
+    ///   "yesterday" matches from yesterday's midnight to today's midnight.
+    ///
+    /// * `now` is the user's current time. See [`DatePattern::from_str_kind`].
+    pub fn from_str_range<Tz: TimeZone>(
+        s: &str,
+        now: DateTime<Tz>,
+    ) -> Result<DatePattern, DatePatternParseError>
+    where
+        Tz::Offset: Copy,
+    {
+        let parse_millis = |s: &str| -> Result<_, DatePatternParseError> {
+            Ok(MillisSinceEpoch(parse_date(s, now)?.timestamp_millis()))
+        };
+        if let Some((start, end)) = s.split_once("..") {
+            let start = Some(start.trim()).filter(|s| !s.is_empty());
+            let end = Some(end.trim()).filter(|s| !s.is_empty());
+            return match (start, end) {
+                (Some(start), Some(end)) => Ok(DatePattern::Between(
+                    parse_millis(start)?,
+                    parse_millis(end)?,
+                )),
+                (Some(start), None) => Ok(DatePattern::AtOrAfter(parse_millis(start)?)),
+                (None, Some(end)) => Ok(DatePattern::Before(parse_millis(end)?)),
+                (None, None) => Err(DatePatternParseError::EmptyRange),
+            };
+        }
+        let date = parse_date(s, now)?.date_naive();
+        let start_of_day = |date: NaiveDate| {
+            let midnight = date.and_time(NaiveTime::MIN);
+            // Midnight may be skipped by a DST transition, in which case the
+            // day starts at the next valid time.
+            let dt = now
+                .timezone()
+                .from_local_datetime(&midnight)
+                .earliest()
+                .unwrap_or_else(|| now.timezone().from_utc_datetime(&midnight));
+            MillisSinceEpoch(dt.timestamp_millis())
+        };
+        let next_date = date.checked_add_days(Days::new(1)).unwrap_or(date);
+        Ok(DatePattern::Between(
+            start_of_day(date),
+            start_of_day(next_date),
+        ))
+    }
+
     /// Determines whether a given timestamp is matched by the pattern.
     pub fn matches(&self, timestamp: &Timestamp) -> bool {
         match self {
             DatePattern::AtOrAfter(earliest) => *earliest <= timestamp.timestamp,
             DatePattern::Before(latest) => timestamp.timestamp < *latest,
+            DatePattern::Between(earliest, latest) => {
+                *earliest <= timestamp.timestamp && timestamp.timestamp < *latest
+            }
         }
     }
 }
 
+/// Parses a single date relative to `now`. In addition to the formats
+/// supported by `chrono_english`, "now" means the current instant.
+fn parse_date<Tz: TimeZone>(
+    s: &str,
+    now: DateTime<Tz>,
+) -> Result<DateTime<Tz>, DatePatternParseError>
+where
+    Tz::Offset: Copy,
+{
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("now") {
+        return Ok(now);
+    }
+    Ok(parse_date_string(s, now, Dialect::Us)?)
+}
+
+/// Splits a trailing time zone such as `UTC`, `Z`, `+09:00`, or `-0500` off
+/// the date string.
+fn split_time_zone(s: &str) -> Option<(&str, FixedOffset)> {
+    let (rest, zone) = s.trim_end().rsplit_once(char::is_whitespace)?;
+    let offset = parse_time_zone(zone)?;
+    let rest = rest.trim_end();
+    (!rest.is_empty()).then_some((rest, offset))
+}
+
+fn parse_time_zone(zone: &str) -> Option<FixedOffset> {
+    if ["UTC", "GMT", "Z"]
+        .iter()
+        .any(|name| zone.eq_ignore_ascii_case(name))
+    {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, digits) = if let Some(digits) = zone.strip_prefix('+') {
+        (1, digits)
+    } else {
+        (-1, zone.strip_prefix('-')?)
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if digits.len() == 4 => digits.split_at(2),
+        None if digits.len() <= 2 => (digits, "00"),
+        None => return None,
+    };
+    if !(hours.len() <= 2 && minutes.len() == 2) {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
+
     use super::*;
 
     fn test_equal<Tz: TimeZone>(now: DateTime<Tz>, expression: &str, should_equal_time: &str)
@@ -189,4 +333,113 @@ mod tests {
         test_equal(now, "yesterday 10am", "2023-12-31T18:00:00Z");
         test_equal(now, "yesterday 10:30", "2023-12-31T18:30:00Z");
     }
+
+    fn millis(rfc3339: &str) -> MillisSinceEpoch {
+        MillisSinceEpoch(
+            DateTime::parse_from_rfc3339(rfc3339)
+                .unwrap()
+                .timestamp_millis(),
+        )
+    }
+
+    #[test]
+    fn test_date_pattern_parses_now_as_the_current_instant() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T08:30:00-08:00").unwrap();
+        test_equal(now, "now", "2024-01-01T16:30:00Z");
+        test_equal(now, " NOW ", "2024-01-01T16:30:00Z");
+    }
+
+    #[test]
+    fn test_date_pattern_parses_single_date_as_whole_day() {
+        let now = DateTime::parse_from_rfc3339("2024-01-03T08:00:00-08:00").unwrap();
+        assert_eq!(
+            DatePattern::from_str_range("yesterday", now).unwrap(),
+            DatePattern::Between(
+                millis("2024-01-02T08:00:00Z"),
+                millis("2024-01-03T08:00:00Z")
+            )
+        );
+        assert_eq!(
+            DatePattern::from_str_range("2023-03-25 15:00", now).unwrap(),
+            DatePattern::Between(
+                millis("2023-03-25T08:00:00Z"),
+                millis("2023-03-26T08:00:00Z")
+            )
+        );
+    }
+
+    #[test]
+    fn test_date_pattern_parses_ranges() {
+        // 2024-01-03 is a Wednesday
+        let now = DateTime::parse_from_rfc3339("2024-01-03T08:00:00-08:00").unwrap();
+        assert_eq!(
+            DatePattern::from_str_range("last monday..now", now).unwrap(),
+            DatePattern::Between(
+                millis("2024-01-01T08:00:00Z"),
+                millis("2024-01-03T16:00:00Z")
+            )
+        );
+        assert_eq!(
+            DatePattern::from_str_range("2023-03-25..", now).unwrap(),
+            DatePattern::AtOrAfter(millis("2023-03-25T08:00:00Z"))
+        );
+        assert_eq!(
+            DatePattern::from_str_range(" .. 2 hours ago", now).unwrap(),
+            DatePattern::Before(millis("2024-01-03T14:00:00Z"))
+        );
+        assert_matches!(
+            DatePattern::from_str_range("..", now),
+            Err(DatePatternParseError::EmptyRange)
+        );
+        assert_matches!(
+            DatePattern::from_str_range("yesterday..bogus", now),
+            Err(DatePatternParseError::ParseError(_))
+        );
+    }
+
+    #[test]
+    fn test_date_pattern_parses_time_zone_suffix() {
+        let now = DateTime::parse_from_rfc3339("2024-01-03T08:00:00-08:00").unwrap();
+        let context = DatePatternContext::from(now);
+        assert_eq!(
+            context.parse_relative("2023-03-25 UTC", "after").unwrap(),
+            DatePattern::AtOrAfter(millis("2023-03-25T00:00:00Z"))
+        );
+        assert_eq!(
+            context
+                .parse_relative("2023-03-25 10:00 +09:00", "before")
+                .unwrap(),
+            DatePattern::Before(millis("2023-03-25T01:00:00Z"))
+        );
+        assert_eq!(
+            context.parse_range("yesterday -0500").unwrap(),
+            DatePattern::Between(
+                millis("2024-01-02T05:00:00Z"),
+                millis("2024-01-03T05:00:00Z")
+            )
+        );
+        assert_eq!(
+            context.parse_range("yesterday..now z").unwrap(),
+            DatePattern::Between(
+                millis("2024-01-02T16:00:00Z"),
+                millis("2024-01-03T16:00:00Z")
+            )
+        );
+        // Not a time zone
+        assert_eq!(
+            context.parse_relative("yesterday 5pm", "after").unwrap(),
+            DatePattern::AtOrAfter(millis("2024-01-03T01:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_parse_time_zone() {
+        assert_eq!(parse_time_zone("UTC"), FixedOffset::east_opt(0));
+        assert_eq!(parse_time_zone("+09:00"), FixedOffset::east_opt(9 * 3600));
+        assert_eq!(parse_time_zone("+0930"), FixedOffset::east_opt(34200));
+        assert_eq!(parse_time_zone("-05"), FixedOffset::west_opt(5 * 3600));
+        assert_eq!(parse_time_zone("5pm"), None);
+        assert_eq!(parse_time_zone("+25:00"), None);
+        assert_eq!(parse_time_zone("+123"), None);
+    }
 }
@@ -17,6 +17,7 @@
 use std::sync::Arc;
 
 use itertools::Itertools as _;
+use tracing::instrument;
 
 use crate::backend::Timestamp;
 use crate::dag_walk;
@@ -103,6 +104,7 @@ impl Transaction {
     }
 
     /// Writes the transaction to the operation store and publishes it.
+    #[instrument(skip_all)]
     pub fn commit(
         self,
         description: impl Into<String>,
@@ -140,7 +142,7 @@ impl Transaction {
 
         let index = base_repo
             .index_store()
-            .write_index(mut_index, &operation)
+            .write_index(mut_index, &operation, base_repo.store())
             .unwrap();
         UnpublishedOperation::new(base_repo.loader(), operation, view, index)
     }
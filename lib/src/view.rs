@@ -21,6 +21,7 @@ use std::collections::HashSet;
 use itertools::Itertools;
 use thiserror::Error;
 
+use crate::backend::ChangeId;
 use crate::backend::CommitId;
 use crate::op_store;
 use crate::op_store::BookmarkTarget;
@@ -350,6 +351,38 @@ impl View {
         self.data.git_head = target;
     }
 
+    /// Iterates change aliases `(name, change_id)`s. Entries are sorted by
+    /// `name`.
+    pub fn change_aliases(&self) -> impl Iterator<Item = (&str, &ChangeId)> {
+        self.data
+            .change_aliases
+            .iter()
+            .map(|(name, change_id)| (name.as_ref(), change_id))
+    }
+
+    pub fn get_change_alias(&self, name: &str) -> Option<&ChangeId> {
+        self.data.change_aliases.get(name)
+    }
+
+    /// Sets change alias to point to the given change. If the change is `None`,
+    /// the alias will be removed.
+    pub fn set_change_alias(&mut self, name: &str, change_id: Option<ChangeId>) {
+        if let Some(change_id) = change_id {
+            self.data.change_aliases.insert(name.to_owned(), change_id);
+        } else {
+            self.data.change_aliases.remove(name);
+        }
+    }
+
+    /// Returns the stashed commits, newest first.
+    pub fn stash_ids(&self) -> &[CommitId] {
+        &self.data.stash_ids
+    }
+
+    pub fn set_stash_ids(&mut self, stash_ids: Vec<CommitId>) {
+        self.data.stash_ids = stash_ids;
+    }
+
     /// Iterates all commit ids referenced by this view.
     ///
     /// This can include hidden commits referenced by remote bookmarks, previous
@@ -375,6 +408,8 @@ impl View {
             git_refs,
             git_head,
             wc_commit_ids,
+            change_aliases: _,
+            stash_ids,
         } = &self.data;
         itertools::chain!(
             head_ids,
@@ -388,7 +423,8 @@ impl View {
             }),
             git_refs.values().flat_map(ref_target_ids),
             ref_target_ids(git_head),
-            wc_commit_ids.values()
+            wc_commit_ids.values(),
+            stash_ids
         )
     }
 
@@ -22,6 +22,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use itertools::Itertools;
+use serde::Deserialize;
 use thiserror::Error;
 use tracing::instrument;
 
@@ -73,6 +74,11 @@ pub trait WorkingCopy: Send {
     /// that all files should be checked out.
     fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError>;
 
+    /// Paths in the current tree that couldn't be checked out because the file
+    /// system can't represent them, e.g. paths differing only in case on a
+    /// case-insensitive file system, or reserved names on Windows.
+    fn unrepresentable_paths(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError>;
+
     /// Locks the working copy and returns an instance with methods for updating
     /// the working copy files and state.
     fn start_mutation(&self) -> Result<Box<dyn LockedWorkingCopy>, WorkingCopyStateError>;
@@ -214,14 +220,27 @@ pub struct SnapshotOptions<'a> {
     /// For new files that are not already tracked, start tracking them if they
     /// match this.
     pub start_tracking_matcher: &'a dyn Matcher,
+    /// If set, only look for changes to paths matching this. Changes to other
+    /// paths are left in the working copy to be picked up by a later
+    /// snapshot.
+    pub snapshot_matcher: Option<&'a dyn Matcher>,
     /// The size of the largest file that should be allowed to become tracked
     /// (already tracked files are always snapshotted). If there are larger
     /// files in the working copy, then `LockedWorkingCopy::snapshot()` may
     /// (depending on implementation)
     /// return `SnapshotError::NewFileTooLarge`.
     pub max_new_file_size: u64,
+    /// Which recorded file metadata to trust for deciding that a tracked file
+    /// is unchanged without reading its contents.
+    pub file_state_trust: FileStateTrust,
+    /// Tracked files at least this large are not re-hashed if only their
+    /// timestamps changed (their type and size must still match). Not
+    /// applied if `file_state_trust` is `Paranoid`.
+    pub rehash_throttle_size: u64,
     /// Expected conflict marker style for checking for changed files.
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// How symlinks were materialized, for reading them back.
+    pub symlink_policy: SymlinkPolicy,
 }
 
 impl SnapshotOptions<'_> {
@@ -232,12 +251,50 @@ impl SnapshotOptions<'_> {
             fsmonitor_settings: FsmonitorSettings::None,
             progress: None,
             start_tracking_matcher: &EverythingMatcher,
+            snapshot_matcher: None,
             max_new_file_size: u64::MAX,
+            file_state_trust: FileStateTrust::default(),
+            rehash_throttle_size: u64::MAX,
             conflict_marker_style: ConflictMarkerStyle::default(),
+            symlink_policy: SymlinkPolicy::default(),
         }
     }
 }
 
+/// Which recorded file metadata can be trusted when deciding whether a tracked
+/// file has changed since the last snapshot.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum FileStateTrust {
+    /// Never trust the metadata. Every tracked file is re-hashed.
+    #[serde(rename = "paranoid")]
+    Paranoid,
+    /// The file is unchanged if its type, size, and mtime are unchanged.
+    #[default]
+    #[serde(rename = "mtime")]
+    Mtime,
+    /// Like `Mtime`, but the inode number must also be unchanged. This catches
+    /// files that were replaced by tools that preserve the mtime.
+    #[serde(rename = "mtime+inode")]
+    MtimeInode,
+}
+
+/// How symlinks in the tree are materialized in the working copy.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum SymlinkPolicy {
+    /// Create real symlinks if the platform supports them (on Windows, if
+    /// Developer Mode is enabled), and placeholder files otherwise.
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    /// Always create real symlinks. Checkout fails if they can't be created.
+    #[serde(rename = "native")]
+    Native,
+    /// Always create regular files containing the symlink target. Such files
+    /// are snapshotted back as symlinks.
+    #[serde(rename = "placeholder")]
+    Placeholder,
+}
+
 /// A callback for getting progress updates.
 pub type SnapshotProgress<'a> = dyn Fn(&RepoPath) + 'a + Sync;
 
@@ -265,6 +322,8 @@ pub enum UntrackedReason {
 pub struct CheckoutOptions {
     /// Conflict marker style to use when materializing files
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// How to materialize symlinks
+    pub symlink_policy: SymlinkPolicy,
 }
 
 impl CheckoutOptions {
@@ -272,6 +331,7 @@ impl CheckoutOptions {
     pub fn empty_for_test() -> Self {
         CheckoutOptions {
             conflict_marker_style: ConflictMarkerStyle::default(),
+            symlink_policy: SymlinkPolicy::default(),
         }
     }
 }
@@ -291,6 +351,10 @@ pub struct CheckoutStats {
     /// working copy but were skipped because there was an untracked (probably
     /// ignored) file in its place.
     pub skipped_files: u32,
+    /// The number of files that were supposed to be updated or added in the
+    /// working copy but were skipped because the file system can't represent
+    /// their paths, e.g. because another path differs only in case.
+    pub unrepresentable_files: u32,
 }
 
 /// The working-copy checkout failed.
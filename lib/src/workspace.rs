@@ -23,11 +23,13 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use tempfile::NamedTempFile;
 use thiserror::Error;
 
 use crate::backend::BackendInitError;
 use crate::backend::MergedTreeId;
 use crate::commit::Commit;
+use crate::file_util;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::local_backend::LocalBackend;
@@ -97,6 +99,18 @@ pub enum WorkspaceLoadError {
     Path(#[from] PathError),
 }
 
+#[derive(Error, Debug)]
+pub enum WorkspaceMoveError {
+    #[error("The destination ({0}) already exists")]
+    DestinationExists(PathBuf),
+    #[error("Cannot move the workspace into itself")]
+    DestinationInsideWorkspace,
+    #[error("Repo path could not be interpreted as Unicode text")]
+    NonUnicodePath,
+    #[error(transparent)]
+    Path(#[from] PathError),
+}
+
 /// The combination of a repo and a working copy.
 ///
 /// Represents the combination of a repo and working copy, i.e. what's typically
@@ -194,6 +208,23 @@ impl Workspace {
         Self::init_with_backend(user_settings, workspace_root, backend_initializer, signer)
     }
 
+    /// Initializes a workspace with a new S3 backend storing the objects at
+    /// the bucket `url`.
+    #[cfg(feature = "s3")]
+    pub fn init_s3(
+        user_settings: &UserSettings,
+        workspace_root: &Path,
+        url: &str,
+    ) -> Result<(Self, Arc<ReadonlyRepo>), WorkspaceInitError> {
+        let backend_initializer: &BackendInitializer = &|settings, store_path| {
+            Ok(Box::new(crate::s3_backend::S3Backend::init(
+                settings, store_path, url,
+            )?))
+        };
+        let signer = Signer::from_settings(user_settings)?;
+        Self::init_with_backend(user_settings, workspace_root, backend_initializer, signer)
+    }
+
     /// Initializes a workspace with a new Git backend and bare Git repo in
     /// `.jj/repo/store/git`.
     #[cfg(feature = "git")]
@@ -540,6 +571,73 @@ impl WorkspaceLoaderFactory for DefaultWorkspaceLoaderFactory {
     }
 }
 
+/// Moves the workspace rooted at `workspace_root` to `new_root`, and returns
+/// the canonicalized new root.
+///
+/// If the workspace refers to a repo stored elsewhere, the `.jj/repo` file is
+/// rewritten to hold the absolute path to that repo before the directory is
+/// moved, so the workspace can be loaded from its new location. Other
+/// workspaces referring to a repo stored in the moved workspace are not
+/// updated.
+pub fn move_workspace_root(
+    workspace_root: &Path,
+    new_root: &Path,
+) -> Result<PathBuf, WorkspaceMoveError> {
+    let old_root = workspace_root.canonicalize().context(workspace_root)?;
+    let Some(file_name) = new_root.file_name() else {
+        return Err(WorkspaceMoveError::DestinationExists(new_root.to_owned()));
+    };
+    let new_parent = match new_root.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(new_parent).context(new_parent)?;
+    let new_root = new_parent
+        .canonicalize()
+        .context(new_parent)?
+        .join(file_name);
+    if new_root == old_root {
+        return Ok(new_root);
+    }
+    if new_root.starts_with(&old_root) {
+        return Err(WorkspaceMoveError::DestinationInsideWorkspace);
+    }
+    // A case-only rename on a case-insensitive file system refers to the same
+    // directory.
+    if new_root.symlink_metadata().is_ok()
+        && !same_file::is_same_file(&old_root, &new_root).context(&new_root)?
+    {
+        return Err(WorkspaceMoveError::DestinationExists(new_root));
+    }
+
+    let jj_dir = old_root.join(".jj");
+    let repo_file_path = jj_dir.join("repo");
+    if repo_file_path.is_file() {
+        let buf = fs::read(&repo_file_path).context(&repo_file_path)?;
+        let repo_path_str =
+            String::from_utf8(buf).map_err(|_| WorkspaceMoveError::NonUnicodePath)?;
+        let repo_dir = jj_dir
+            .join(&repo_path_str)
+            .canonicalize()
+            .context(&repo_path_str)?;
+        let mut temp_file = NamedTempFile::new_in(&jj_dir).context(&jj_dir)?;
+        temp_file
+            .write_all(
+                repo_dir
+                    .to_str()
+                    .ok_or(WorkspaceMoveError::NonUnicodePath)?
+                    .as_bytes(),
+            )
+            .context(temp_file.path())?;
+        temp_file
+            .persist(&repo_file_path)
+            .map_err(|err| err.error)
+            .context(&repo_file_path)?;
+    }
+    file_util::move_dir(&old_root, &new_root)?;
+    Ok(new_root)
+}
+
 /// Helps create a `Workspace` instance by reading `.jj/repo/` and
 /// `.jj/working_copy/` from the file system.
 #[derive(Clone, Debug)]
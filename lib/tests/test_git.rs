@@ -30,9 +30,11 @@ use itertools::Itertools;
 use jj_lib::backend::BackendError;
 use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
+use jj_lib::backend::MergedTreeId;
 use jj_lib::backend::MillisSinceEpoch;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
+use jj_lib::backend::TreeId;
 use jj_lib::commit::Commit;
 use jj_lib::commit_builder::CommitBuilder;
 use jj_lib::git;
@@ -42,7 +44,9 @@ use jj_lib::git::GitFetchError;
 use jj_lib::git::GitImportError;
 use jj_lib::git::GitPushError;
 use jj_lib::git::GitRefUpdate;
+use jj_lib::git::GitTagSignError;
 use jj_lib::git::RefName;
+use jj_lib::git::RefSpec;
 use jj_lib::git::SubmoduleConfig;
 use jj_lib::git_backend::GitBackend;
 use jj_lib::object_id::ObjectId;
@@ -61,11 +65,13 @@ use jj_lib::signing::Signer;
 use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 use maplit::btreemap;
+use maplit::hashmap;
 use maplit::hashset;
 use tempfile::TempDir;
 use test_case::test_case;
 use testutils::commit_transactions;
 use testutils::create_random_commit;
+use testutils::test_signing_backend::TestSigningBackend;
 use testutils::write_random_commit;
 use testutils::TestRepo;
 use testutils::TestRepoBackend;
@@ -1474,6 +1480,85 @@ fn test_import_refs_detached_head() {
     assert_eq!(repo.view().git_head(), &RefTarget::normal(jj_id(&commit1)));
 }
 
+#[test]
+fn test_import_stashes() {
+    let test_data = GitRepoData::create();
+    let git_repo = &test_data.git_repo;
+    let signature = git2::Signature::now("Someone", "someone@example.com").unwrap();
+    let write_tree = |files: &[(&str, &str)]| {
+        let mut tree_builder = git_repo.treebuilder(None).unwrap();
+        for (name, content) in files {
+            let blob_oid = git_repo.blob(content.as_bytes()).unwrap();
+            tree_builder
+                .insert(name, blob_oid, git2::FileMode::Blob.into())
+                .unwrap();
+        }
+        tree_builder.write().unwrap()
+    };
+    let write_commit = |message: &str, tree_oid, parents: &[&git2::Commit]| {
+        let tree = git_repo.find_tree(tree_oid).unwrap();
+        let oid = git_repo
+            .commit(None, &signature, &signature, message, &tree, parents)
+            .unwrap();
+        git_repo.find_commit(oid).unwrap()
+    };
+    git_repo.reference_ensure_log("refs/stash").unwrap();
+
+    let base = empty_git_commit(git_repo, "refs/heads/main", &[]);
+    // Stash with a staged and an unstaged file
+    let index1 = write_commit("index on main", write_tree(&[("a", "a")]), &[&base]);
+    let stash1 = write_commit(
+        "WIP on main: base",
+        write_tree(&[("a", "a"), ("b", "b")]),
+        &[&base, &index1],
+    );
+    git_repo
+        .reference("refs/stash", stash1.id(), true, "stash1")
+        .unwrap();
+    // Stash with an untracked file
+    let index2 = write_commit("index on main", write_tree(&[]), &[&base]);
+    let untracked2 = write_commit("untracked files on main", write_tree(&[("u", "u")]), &[]);
+    let stash2 = write_commit(
+        "On main: with untracked",
+        write_tree(&[("c", "c")]),
+        &[&base, &index2, &untracked2],
+    );
+    git_repo
+        .reference("refs/stash", stash2.id(), true, "stash2")
+        .unwrap();
+
+    let mut tx = test_data.repo.start_transaction(&test_data.settings);
+    let commits = git::import_stashes(tx.repo_mut(), &test_data.settings).unwrap();
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].description(), "WIP on main: base\n");
+    assert_eq!(commits[0].parent_ids(), [jj_id(&base)]);
+    assert_eq!(
+        *commits[0].tree_id(),
+        MergedTreeId::resolved(TreeId::from_bytes(stash1.tree_id().as_bytes()))
+    );
+    assert_eq!(commits[1].description(), "On main: with untracked\n");
+    assert_eq!(commits[1].parent_ids(), [jj_id(&base)]);
+    let tree = commits[1].tree().unwrap();
+    let paths = tree.entries().map(|(path, _)| path).collect_vec();
+    assert_eq!(
+        paths,
+        [
+            RepoPath::from_internal_string("c").to_owned(),
+            RepoPath::from_internal_string("u").to_owned(),
+        ]
+    );
+    let expected_heads = hashset! {
+        commits[0].id().clone(),
+        commits[1].id().clone(),
+    };
+    assert_eq!(*tx.repo().view().heads(), expected_heads);
+
+    // Stashes are only imported once
+    assert!(git::import_stashes(tx.repo_mut(), &test_data.settings)
+        .unwrap()
+        .is_empty());
+}
+
 #[test]
 fn test_export_refs_no_detach() {
     // When exporting the bookmark that's current checked out, don't detach HEAD if
@@ -2594,6 +2679,148 @@ fn test_fetch_no_such_remote() {
     assert!(matches!(result, Err(GitFetchError::NoSuchRemote(_))));
 }
 
+#[test]
+fn test_refspec_parse() {
+    let refspec = RefSpec::parse("+refs/heads/team/*:refs/remotes/origin/team/*").unwrap();
+    assert!(refspec.forced());
+    assert_eq!(refspec.source(), "refs/heads/team/*");
+    assert_eq!(refspec.destination(), "refs/remotes/origin/team/*");
+    assert_eq!(
+        refspec.to_string(),
+        "+refs/heads/team/*:refs/remotes/origin/team/*"
+    );
+    assert_eq!(
+        refspec.map_to_destination("refs/heads/team/a/b"),
+        Some("refs/remotes/origin/team/a/b".to_owned())
+    );
+    assert_eq!(refspec.map_to_destination("refs/heads/main"), None);
+    assert_eq!(refspec.map_to_destination("refs/heads/team/"), None);
+    assert_eq!(
+        refspec.map_to_source("refs/remotes/origin/team/a"),
+        Some("refs/heads/team/a".to_owned())
+    );
+
+    let refspec = RefSpec::parse("refs/heads/main:refs/for/main").unwrap();
+    assert!(!refspec.forced());
+    assert_eq!(
+        refspec.map_to_destination("refs/heads/main"),
+        Some("refs/for/main".to_owned())
+    );
+    assert_eq!(refspec.map_to_destination("refs/heads/mainline"), None);
+
+    assert!(RefSpec::parse("refs/heads/main").is_err());
+    assert!(RefSpec::parse(":refs/heads/main").is_err());
+    assert!(RefSpec::parse("refs/heads/*:refs/for/main").is_err());
+    assert!(RefSpec::parse("refs/*/*:refs/remotes/origin/*/*").is_err());
+    assert!(RefSpec::parse("refs/heads/[ab]:refs/heads/a").is_err());
+}
+
+#[test]
+fn test_fetch_with_refspecs() {
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings {
+        remote_fetch_refspecs: hashmap! {
+            "origin".to_owned() => vec!["+refs/heads/team/*:refs/remotes/origin/team/*".to_owned()],
+        },
+        ..Default::default()
+    };
+    let main_commit = empty_git_commit(&test_data.origin_repo, "refs/heads/main", &[]);
+    let team_commit =
+        empty_git_commit(&test_data.origin_repo, "refs/heads/team/a", &[&main_commit]);
+    empty_git_commit(&test_data.origin_repo, "refs/heads/team/b", &[&main_commit]);
+
+    let mut tx = test_data.repo.start_transaction(&test_data.settings);
+    git::fetch(
+        tx.repo_mut(),
+        &test_data.git_repo,
+        "origin",
+        &[StringPattern::exact("team/a")],
+        git::RemoteCallbacks::default(),
+        &git_settings,
+        None,
+    )
+    .unwrap();
+    let repo = tx.commit("test").unwrap();
+    // main isn't fetched, and team/b is fetched but not imported
+    assert_eq!(
+        repo.view()
+            .all_remote_bookmarks()
+            .map(|((name, remote), _)| format!("{name}@{remote}"))
+            .collect_vec(),
+        vec!["team/a@origin"]
+    );
+    assert_eq!(
+        *repo.view().get_remote_bookmark("team/a", "origin"),
+        RemoteRef {
+            target: RefTarget::normal(jj_id(&team_commit)),
+            state: RemoteRefState::New,
+        }
+    );
+    assert!(test_data
+        .git_repo
+        .find_reference("refs/remotes/origin/main")
+        .is_err());
+}
+
+#[test]
+fn test_fetch_unsupported_refspec() {
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings {
+        remote_fetch_refspecs: hashmap! {
+            "origin".to_owned() => vec!["refs/heads/*:refs/heads/*".to_owned()],
+        },
+        ..Default::default()
+    };
+    let mut tx = test_data.repo.start_transaction(&test_data.settings);
+    let result = git::fetch(
+        tx.repo_mut(),
+        &test_data.git_repo,
+        "origin",
+        &[StringPattern::everything()],
+        git::RemoteCallbacks::default(),
+        &git_settings,
+        None,
+    );
+    assert_matches!(
+        result,
+        Err(GitFetchError::UnsupportedFetchRefSpec { refspec, remote })
+            if refspec == "refs/heads/*:refs/heads/*" && remote == "origin"
+    );
+}
+
+#[test]
+fn test_fetch_ref() {
+    let test_data = GitRepoData::create();
+    let commit1 = empty_git_commit(&test_data.origin_repo, "refs/heads/main", &[]);
+    let commit2 = empty_git_commit(&test_data.origin_repo, "refs/pull/1/head", &[&commit1]);
+
+    let mut tx = test_data.repo.start_transaction(&test_data.settings);
+    let commit_id = git::fetch_ref(
+        tx.repo_mut(),
+        &test_data.git_repo,
+        "origin",
+        "refs/pull/1/head",
+        git::RemoteCallbacks::default(),
+    )
+    .unwrap();
+    assert_eq!(commit_id, jj_id(&commit2));
+    // The commit is visible, but no refs are created for it
+    let repo = tx.commit("test").unwrap();
+    assert_eq!(*repo.view().heads(), hashset! {jj_id(&commit2)});
+    assert_eq!(*repo.view().git_refs(), btreemap! {});
+    assert_eq!(repo.view().bookmarks().count(), 0);
+
+    let mut tx = repo.start_transaction(&test_data.settings);
+    let result = git::fetch_ref(
+        tx.repo_mut(),
+        &test_data.git_repo,
+        "origin",
+        "refs/pull/2/head",
+        git::RemoteCallbacks::default(),
+    );
+    assert!(matches!(result, Err(GitFetchError::NoSuchRemoteRef(_))));
+}
+
 struct PushTestSetup {
     source_repo_dir: PathBuf,
     jj_repo: Arc<ReadonlyRepo>,
@@ -2703,6 +2930,7 @@ fn test_push_bookmarks_success() {
                 new_target: Some(setup.child_of_main_commit.id().clone()),
             },
         )],
+        tags: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
@@ -2710,6 +2938,7 @@ fn test_push_bookmarks_success() {
         "origin",
         &targets,
         git::RemoteCallbacks::default(),
+        &GitSettings::default(),
     );
     assert_eq!(result, Ok(()));
 
@@ -2752,6 +2981,267 @@ fn test_push_bookmarks_success() {
     assert!(!tx.repo_mut().has_changes());
 }
 
+#[test]
+fn test_push_bookmarks_with_refspec() {
+    let settings = testutils::user_settings();
+    let git_settings = GitSettings {
+        remote_push_refspecs: hashmap! {
+            "origin".to_owned() => vec!["refs/heads/*:refs/for/*".to_owned()],
+        },
+        ..Default::default()
+    };
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let clone_repo = get_git_repo(&setup.jj_repo);
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![(
+            "main".to_owned(),
+            BookmarkPushUpdate {
+                old_target: Some(setup.main_commit.id().clone()),
+                new_target: Some(setup.child_of_main_commit.id().clone()),
+            },
+        )],
+        tags: vec![],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        git::RemoteCallbacks::default(),
+        &git_settings,
+    );
+    assert_eq!(result, Ok(()));
+
+    // The mapped ref got created in the source repo, and the branch is unchanged
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    assert_eq!(
+        source_repo
+            .find_reference("refs/for/main")
+            .unwrap()
+            .target(),
+        Some(git_id(&setup.child_of_main_commit))
+    );
+    assert_eq!(
+        source_repo
+            .find_reference("refs/heads/main")
+            .unwrap()
+            .target(),
+        Some(git_id(&setup.main_commit))
+    );
+
+    // The remote-tracking bookmark isn't updated
+    assert_eq!(
+        tx.repo_mut().get_remote_bookmark("main", "origin").target,
+        RefTarget::normal(setup.main_commit.id().clone())
+    );
+
+    // Deleting a mapped bookmark is an error
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![(
+            "main".to_owned(),
+            BookmarkPushUpdate {
+                old_target: Some(setup.main_commit.id().clone()),
+                new_target: None,
+            },
+        )],
+        tags: vec![],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        git::RemoteCallbacks::default(),
+        &git_settings,
+    );
+    assert_eq!(
+        result,
+        Err(GitPushError::MappedRefDeletion {
+            branch: "main".to_owned(),
+            remote_ref: "refs/for/main".to_owned(),
+        })
+    );
+}
+
+#[test]
+fn test_push_tags() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let clone_repo = get_git_repo(&setup.jj_repo);
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    let main_git_commit = clone_repo.find_commit(git_id(&setup.main_commit)).unwrap();
+    clone_repo
+        .reference(
+            "refs/tags/lightweight",
+            git_id(&setup.main_commit),
+            false,
+            "",
+        )
+        .unwrap();
+    let signature = git2::Signature::now("Someone", "someone@example.com").unwrap();
+    let annotated_id = clone_repo
+        .tag(
+            "annotated",
+            main_git_commit.as_object(),
+            &signature,
+            "message",
+            false,
+        )
+        .unwrap();
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+
+    // Tags are pushed along with bookmarks
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![(
+            "main".to_owned(),
+            BookmarkPushUpdate {
+                old_target: Some(setup.main_commit.id().clone()),
+                new_target: Some(setup.child_of_main_commit.id().clone()),
+            },
+        )],
+        tags: vec!["lightweight".to_owned(), "annotated".to_owned()],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        git::RemoteCallbacks::default(),
+        &GitSettings::default(),
+    );
+    assert_eq!(result, Ok(()));
+    let source_ref_target =
+        |name: &str| source_repo.find_reference(name).unwrap().target().unwrap();
+    assert_eq!(
+        source_ref_target("refs/heads/main"),
+        git_id(&setup.child_of_main_commit)
+    );
+    assert_eq!(
+        source_ref_target("refs/tags/lightweight"),
+        git_id(&setup.main_commit)
+    );
+    assert_eq!(source_ref_target("refs/tags/annotated"), annotated_id);
+
+    // Pushing unchanged tags is a no-op
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![],
+        tags: vec!["annotated".to_owned()],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        git::RemoteCallbacks::default(),
+        &GitSettings::default(),
+    );
+    assert_eq!(result, Ok(()));
+
+    // Tags which don't exist in the Git repo can't be pushed
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![],
+        tags: vec!["nonexistent".to_owned()],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        git::RemoteCallbacks::default(),
+        &GitSettings::default(),
+    );
+    assert_eq!(
+        result,
+        Err(GitPushError::NoSuchTag("nonexistent".to_owned()))
+    );
+
+    // Tags which exist on the remote at a different location aren't moved
+    clone_repo
+        .reference(
+            "refs/tags/lightweight",
+            git_id(&setup.child_of_main_commit),
+            true,
+            "",
+        )
+        .unwrap();
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![],
+        tags: vec!["lightweight".to_owned()],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &clone_repo,
+        "origin",
+        &targets,
+        git::RemoteCallbacks::default(),
+        &GitSettings::default(),
+    );
+    assert_eq!(
+        result,
+        Err(GitPushError::TagAlreadyExists(vec![
+            "lightweight".to_owned()
+        ]))
+    );
+    assert_eq!(
+        source_ref_target("refs/tags/lightweight"),
+        git_id(&setup.main_commit)
+    );
+}
+
+#[test]
+fn test_sign_tag() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let git_repo = get_git_repo(&test_repo.repo);
+    let commit_id = empty_git_commit(&git_repo, "refs/heads/main", &[]).id();
+    git_repo
+        .reference("refs/tags/v1", commit_id, false, "")
+        .unwrap();
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let tagger = settings.signature();
+
+    assert_matches!(
+        git::sign_tag(&git_repo, "nonexistent", &tagger, &signer, None),
+        Err(GitTagSignError::NoSuchTag(name)) if name == "nonexistent"
+    );
+
+    // The lightweight tag is replaced with a signed annotated tag
+    let signed_tag = git::sign_tag(&git_repo, "v1", &tagger, &signer, Some("key"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(signed_tag.commit_id.hex(), commit_id.to_string());
+    let tag = git_repo
+        .find_reference("refs/tags/v1")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    assert_eq!(tag.target_id(), commit_id);
+    assert_eq!(tag.name(), Some("v1"));
+    assert_eq!(tag.tagger().unwrap().email(), Some(tagger.email.as_str()));
+    let message = tag.message().unwrap();
+    assert!(message.starts_with("v1\n--- JJ-TEST-SIGNATURE ---\nKEY: key\n"));
+
+    // Annotated tags are left alone
+    assert!(git::sign_tag(&git_repo, "v1", &tagger, &signer, None)
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        git_repo.find_reference("refs/tags/v1").unwrap().target(),
+        Some(tag.id())
+    );
+
+    // The lightweight tag can be restored
+    git::unsign_tag(&git_repo, &signed_tag).unwrap();
+    assert_eq!(
+        git_repo.find_reference("refs/tags/v1").unwrap().target(),
+        Some(commit_id)
+    );
+}
+
 #[test]
 fn test_push_bookmarks_deletion() {
     let settings = testutils::user_settings();
@@ -2772,6 +3262,7 @@ fn test_push_bookmarks_deletion() {
                 new_target: None,
             },
         )],
+        tags: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
@@ -2779,6 +3270,7 @@ fn test_push_bookmarks_deletion() {
         "origin",
         &targets,
         git::RemoteCallbacks::default(),
+        &GitSettings::default(),
     );
     assert_eq!(result, Ok(()));
 
@@ -2829,6 +3321,7 @@ fn test_push_bookmarks_mixed_deletion_and_addition() {
                 },
             ),
         ],
+        tags: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
@@ -2836,6 +3329,7 @@ fn test_push_bookmarks_mixed_deletion_and_addition() {
         "origin",
         &targets,
         git::RemoteCallbacks::default(),
+        &GitSettings::default(),
     );
     assert_eq!(result, Ok(()));
 
@@ -2888,6 +3382,7 @@ fn test_push_bookmarks_not_fast_forward() {
                 new_target: Some(setup.sideways_commit.id().clone()),
             },
         )],
+        tags: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
@@ -2895,6 +3390,7 @@ fn test_push_bookmarks_not_fast_forward() {
         "origin",
         &targets,
         git::RemoteCallbacks::default(),
+        &GitSettings::default(),
     );
     assert_eq!(result, Ok(()));
 
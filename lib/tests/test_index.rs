@@ -21,12 +21,15 @@ use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::commit_builder::CommitBuilder;
+use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::default_index::AsCompositeIndex as _;
 use jj_lib::default_index::CompositeIndex;
 use jj_lib::default_index::DefaultIndexStore;
 use jj_lib::default_index::DefaultIndexStoreError;
 use jj_lib::default_index::DefaultMutableIndex;
 use jj_lib::default_index::DefaultReadonlyIndex;
+use jj_lib::default_index::DiffStatCounts;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::index::Index as _;
 use jj_lib::object_id::HexPrefix;
 use jj_lib::object_id::ObjectId as _;
@@ -36,12 +39,16 @@ use jj_lib::op_store::RemoteRef;
 use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::revset::ResolvedExpression;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::revset::GENERATION_RANGE_FULL;
 use jj_lib::settings::UserSettings;
 use maplit::hashset;
 use testutils::commit_transactions;
 use testutils::create_random_commit;
+use testutils::create_tree;
 use testutils::test_backend::TestBackend;
 use testutils::write_random_commit;
 use testutils::CommitGraphBuilder;
@@ -744,6 +751,160 @@ fn test_reindex_missing_commit() {
     assert_matches!(err, DefaultIndexStoreError::IndexCommits { op_id, .. } if op_id == *bad_op_id);
 }
 
+#[test]
+fn test_diff_stats_cache() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let commit_a = write_random_commit(tx.repo_mut(), &settings);
+    let commit_b = write_random_commit(tx.repo_mut(), &settings);
+    let repo = tx.commit("test").unwrap();
+
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    let style = ConflictMarkerStyle::Diff;
+    assert_eq!(
+        default_index_store.read_diff_stats(commit_a.id(), style),
+        None
+    );
+    let counts = DiffStatCounts {
+        files: 7,
+        added: 120,
+        removed: 45,
+    };
+    default_index_store
+        .write_diff_stats(commit_a.id(), style, &counts)
+        .unwrap();
+    assert_eq!(
+        default_index_store.read_diff_stats(commit_a.id(), style),
+        Some(counts)
+    );
+    assert_eq!(
+        default_index_store.read_diff_stats(commit_b.id(), style),
+        None
+    );
+    // Conflicts are counted differently depending on the marker style
+    assert_eq!(
+        default_index_store.read_diff_stats(commit_a.id(), ConflictMarkerStyle::Git),
+        None
+    );
+
+    // The cache survives reindexing
+    default_index_store.reinit().unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    assert_eq!(
+        default_index_store.read_diff_stats(commit_a.id(), style),
+        Some(counts)
+    );
+
+    // Corrupt entries and entries of other format versions are ignored
+    let diff_stats_dir = test_repo.repo_path().join("index").join("diff_stats");
+    let entry_path = diff_stats_dir.join(format!("{}-diff", commit_a.id().hex()));
+    let mut buf = fs::read(&entry_path).unwrap();
+    buf[0] += 1;
+    fs::write(&entry_path, &buf).unwrap();
+    assert_eq!(
+        default_index_store.read_diff_stats(commit_a.id(), style),
+        None
+    );
+    fs::write(&entry_path, b"\0").unwrap();
+    assert_eq!(
+        default_index_store.read_diff_stats(commit_a.id(), style),
+        None
+    );
+}
+
+#[test]
+fn test_changed_path_index() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let file1 = RepoPath::from_internal_string("dir/file1");
+    let file2 = RepoPath::from_internal_string("file2");
+    let dir = RepoPath::from_internal_string("dir");
+    let resolve = |repo: &ReadonlyRepo, path: &RepoPath| -> Vec<CommitId> {
+        let expression = RevsetExpression::filter(RevsetFilterPredicate::File(
+            FilesetExpression::prefix_path(path.to_owned()),
+        ));
+        let revset = expression.evaluate(repo).unwrap();
+        revset.iter().map(Result::unwrap).collect()
+    };
+    let write_commit = |repo: &Arc<ReadonlyRepo>, parent: &CommitId, tree: &[(&RepoPath, &str)]| {
+        let tree = create_tree(repo, tree);
+        let mut tx = repo.start_transaction(&settings);
+        let commit = tx
+            .repo_mut()
+            .new_commit(&settings, vec![parent.clone()], tree.id())
+            .write()
+            .unwrap();
+        (tx.commit("test").unwrap(), commit)
+    };
+    let path_index_files = |repo: &ReadonlyRepo| {
+        let default_index: &DefaultReadonlyIndex =
+            repo.readonly_index().as_any().downcast_ref().unwrap();
+        let dir = test_repo
+            .repo_path()
+            .join("index")
+            .join("changed_path_index");
+        default_index
+            .as_composite()
+            .stats()
+            .levels
+            .into_iter()
+            .map(|level| dir.join(level.name.unwrap()).is_file())
+            .collect::<Vec<_>>()
+    };
+
+    let (repo, commit1) = write_commit(repo, repo.store().root_commit_id(), &[(file1, "1")]);
+    assert!(path_index_files(&repo).iter().all(|exists| !exists));
+
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    let default_index: &DefaultReadonlyIndex =
+        repo.readonly_index().as_any().downcast_ref().unwrap();
+    assert_eq!(
+        default_index_store
+            .build_changed_path_index(default_index, repo.store())
+            .unwrap(),
+        2
+    );
+    assert!(path_index_files(&repo).iter().all(|exists| *exists));
+    assert_eq!(resolve(&repo, file1), vec![commit1.id().clone()]);
+    assert_eq!(resolve(&repo, dir), vec![commit1.id().clone()]);
+    assert_eq!(resolve(&repo, file2), vec![]);
+
+    // New commits are indexed as they're added, and indexed segments are
+    // carried over when squashed.
+    let (repo, commit2) = write_commit(&repo, commit1.id(), &[(file1, "1"), (file2, "2")]);
+    let (repo, commit3) = write_commit(&repo, commit2.id(), &[(file1, "3"), (file2, "2")]);
+    assert!(path_index_files(&repo).iter().all(|exists| *exists));
+    assert_eq!(
+        resolve(&repo, file1),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(resolve(&repo, file2), vec![commit2.id().clone()]);
+    let repo = test_repo
+        .env
+        .load_repo_at_head(&settings, test_repo.repo_path());
+    assert_eq!(resolve(&repo, file2), vec![commit2.id().clone()]);
+
+    // The index isn't used if the fileset isn't limited to literal paths
+    let expression = RevsetExpression::filter(RevsetFilterPredicate::File(
+        FilesetExpression::all().difference(FilesetExpression::file_path(file2.to_owned())),
+    ));
+    let revset = expression.evaluate(repo.as_ref()).unwrap();
+    assert_eq!(
+        revset.iter().map(Result::unwrap).collect::<Vec<_>>(),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+}
+
 /// Test that .jj/repo/index/type is created when the repo is created.
 #[test]
 fn test_index_store_type() {
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs::File;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 #[cfg(unix)]
@@ -19,6 +20,8 @@ use std::os::unix::net::UnixListener;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::UNIX_EPOCH;
 
 use assert_matches::assert_matches;
 use indoc::indoc;
@@ -27,9 +30,11 @@ use jj_lib::backend::MergedTreeId;
 use jj_lib::backend::TreeId;
 use jj_lib::backend::TreeValue;
 use jj_lib::file_util::check_symlink_support;
+use jj_lib::file_util::is_case_insensitive_dir;
 use jj_lib::file_util::try_symlink;
 use jj_lib::fsmonitor::FsmonitorSettings;
 use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::matchers::PrefixMatcher;
 use jj_lib::merge::Merge;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
@@ -46,7 +51,9 @@ use jj_lib::settings::UserSettings;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
+use jj_lib::working_copy::FileStateTrust;
 use jj_lib::working_copy::SnapshotOptions;
+use jj_lib::working_copy::SymlinkPolicy;
 use jj_lib::working_copy::UntrackedReason;
 use jj_lib::workspace::default_working_copy_factories;
 use jj_lib::workspace::LockedWorkspace;
@@ -607,7 +614,8 @@ fn test_conflicting_changes_on_disk() {
             updated_files: 0,
             added_files: 3,
             removed_files: 0,
-            skipped_files: 3
+            skipped_files: 3,
+            unrepresentable_files: 0
         }
     );
 
@@ -839,7 +847,8 @@ fn test_materialize_snapshot_conflicted_files() {
             updated_files: 0,
             added_files: 2,
             removed_files: 0,
-            skipped_files: 0
+            skipped_files: 0,
+            unrepresentable_files: 0
         }
     );
 
@@ -948,6 +957,161 @@ fn test_snapshot_racy_timestamps() {
     }
 }
 
+#[test]
+fn test_snapshot_file_state_trust() {
+    // Tests that a same-size modification that preserves the mtime is only
+    // detected if the file states aren't trusted.
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let file_path = workspace_root.join("file");
+    let mtime = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    let write_file = |contents: &str| {
+        std::fs::write(&file_path, contents).unwrap();
+        let file = File::options().write(true).open(&file_path).unwrap();
+        file.set_modified(mtime).unwrap();
+    };
+    write_file("contents 1");
+    let (old_tree, _stats) = test_workspace
+        .snapshot_with_options(&SnapshotOptions::empty_for_test())
+        .unwrap();
+
+    write_file("contents 2");
+    let (tree, _stats) = test_workspace
+        .snapshot_with_options(&SnapshotOptions::empty_for_test())
+        .unwrap();
+    assert_eq!(tree.id(), old_tree.id());
+
+    let (tree, _stats) = test_workspace
+        .snapshot_with_options(&SnapshotOptions {
+            file_state_trust: FileStateTrust::Paranoid,
+            ..SnapshotOptions::empty_for_test()
+        })
+        .unwrap();
+    assert_ne!(tree.id(), old_tree.id());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_snapshot_file_state_trust_inode() {
+    // Tests that a file replaced by another one with the same size and mtime is
+    // detected if the inode number is checked.
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let file_path = workspace_root.join("file");
+    let mtime = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    let replace_file = |contents: &str| {
+        let temp_path = workspace_root.join(".jj").join("temp");
+        std::fs::write(&temp_path, contents).unwrap();
+        let file = File::options().write(true).open(&temp_path).unwrap();
+        file.set_modified(mtime).unwrap();
+        std::fs::rename(&temp_path, &file_path).unwrap();
+    };
+    replace_file("contents 1");
+    let options = SnapshotOptions {
+        file_state_trust: FileStateTrust::MtimeInode,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (old_tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+
+    replace_file("contents 2");
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_ne!(tree.id(), old_tree.id());
+}
+
+#[test]
+fn test_snapshot_rehash_throttle_size() {
+    // Tests that large files aren't re-hashed if only their mtime changed.
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let small_path = workspace_root.join("small");
+    let large_path = workspace_root.join("large");
+    let write_file = |path: &Path, contents: &str, secs: u64| {
+        std::fs::write(path, contents).unwrap();
+        let file = File::options().write(true).open(path).unwrap();
+        file.set_modified(UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap();
+    };
+    write_file(&small_path, "small 1", 1_000_000_000);
+    write_file(&large_path, "large contents 1", 1_000_000_000);
+    let options = SnapshotOptions {
+        rehash_throttle_size: 10,
+        ..SnapshotOptions::empty_for_test()
+    };
+    test_workspace.snapshot_with_options(&options).unwrap();
+
+    write_file(&small_path, "small 2", 1_000_000_001);
+    write_file(&large_path, "large contents 2", 1_000_000_001);
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    insta::assert_snapshot!(testutils::dump_tree(test_workspace.repo.store(), &tree.id()), @r#"
+    tree bf9536890554bc342cf1
+      file "large" (92549cb1400d6aaad5ac): "large contents 1"
+      file "small" (4806ac8f65b87e7aeb26): "small 2"
+    "#);
+
+    // A size change is still detected
+    write_file(&large_path, "large contents 3!", 1_000_000_002);
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    insta::assert_snapshot!(testutils::dump_tree(test_workspace.repo.store(), &tree.id()), @r#"
+    tree 88dc079b91bab83423de
+      file "large" (7bdeaa5bc14c88da53a7): "large contents 3!"
+      file "small" (4806ac8f65b87e7aeb26): "small 2"
+    "#);
+
+    // The throttle doesn't apply if the metadata isn't trusted at all
+    write_file(&large_path, "large contents 4!", 1_000_000_003);
+    let options = SnapshotOptions {
+        file_state_trust: FileStateTrust::Paranoid,
+        ..options
+    };
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    insta::assert_snapshot!(testutils::dump_tree(test_workspace.repo.store(), &tree.id()), @r#"
+    tree ddea604b2dc19e0eaa0d
+      file "large" (ca1e3d5eeb7de79f0a3a): "large contents 4!"
+      file "small" (4806ac8f65b87e7aeb26): "small 2"
+    "#);
+}
+
+#[test]
+fn test_snapshot_matcher() {
+    // Tests that a partial snapshot only looks at the matching paths, and that
+    // the remaining changes are picked up by a later snapshot.
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    std::fs::write(workspace_root.join("file1"), "contents 1").unwrap();
+    std::fs::write(workspace_root.join("file2"), "contents 2").unwrap();
+    test_workspace.snapshot().unwrap();
+
+    std::fs::write(workspace_root.join("file1"), "changed 1").unwrap();
+    std::fs::remove_file(workspace_root.join("file2")).unwrap();
+    std::fs::write(workspace_root.join("file3"), "contents 3").unwrap();
+    let matcher = PrefixMatcher::new([RepoPath::from_internal_string("file1")]);
+    let options = SnapshotOptions {
+        snapshot_matcher: Some(&matcher),
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    insta::assert_snapshot!(testutils::dump_tree(test_workspace.repo.store(), &tree.id()), @r#"
+    tree cb56130e77dc0e4aef28
+      file "file1" (6f4c389800ac7b3cae45): "changed 1"
+      file "file2" (b343cdd3e199323bc69f): "contents 2"
+    "#);
+
+    let tree = test_workspace.snapshot().unwrap();
+    insta::assert_snapshot!(testutils::dump_tree(test_workspace.repo.store(), &tree.id()), @r#"
+    tree 94925381c3e556c0cd49
+      file "file1" (6f4c389800ac7b3cae45): "changed 1"
+      file "file3" (53f0c3371e861490ae97): "contents 3"
+    "#);
+}
+
 #[cfg(unix)]
 #[test]
 fn test_snapshot_special_file() {
@@ -1488,6 +1652,170 @@ fn test_check_out_existing_file_replaced_with_directory() {
     assert!(file_path.to_fs_path_unchecked(&workspace_root).is_dir());
 }
 
+#[test]
+fn test_check_out_paths_differing_in_case() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    if !is_case_insensitive_dir(&workspace_root).unwrap() {
+        eprintln!("Skipping test because the file system is case-sensitive");
+        return;
+    }
+
+    let upper_file_path = RepoPath::from_internal_string("FILE");
+    let lower_file_path = RepoPath::from_internal_string("file");
+    let upper_dir_file_path = RepoPath::from_internal_string("DIR/a");
+    let lower_dir_file_path = RepoPath::from_internal_string("dir/b");
+    let tree1 = create_tree(
+        repo,
+        &[
+            (upper_file_path, "upper"),
+            (lower_file_path, "lower"),
+            (upper_dir_file_path, "a"),
+            (lower_dir_file_path, "b"),
+        ],
+    );
+    let tree2 = create_tree(
+        repo,
+        &[
+            (lower_file_path, "lower 2"),
+            (upper_dir_file_path, "a"),
+            (lower_dir_file_path, "b"),
+        ],
+    );
+    let commit1 = commit_with_tree(repo.store(), tree1.id());
+    let commit2 = commit_with_tree(repo.store(), tree2.id());
+
+    // Only the first of the paths differing in case is checked out
+    let ws = &mut test_workspace.workspace;
+    let stats = ws
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit1,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+    assert_eq!(stats.unrepresentable_files, 2);
+    assert_eq!(
+        ws.working_copy().unrepresentable_paths().unwrap(),
+        [lower_dir_file_path.to_owned(), lower_file_path.to_owned()]
+    );
+    assert_eq!(
+        std::fs::read_to_string(upper_file_path.to_fs_path_unchecked(&workspace_root)).unwrap(),
+        "upper"
+    );
+
+    // The skipped paths aren't snapshotted as removed
+    let new_tree = test_workspace.snapshot().unwrap();
+    assert_eq!(new_tree.id(), tree1.id());
+
+    // The path can be checked out once the other path is removed
+    let ws = &mut test_workspace.workspace;
+    let stats = ws
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit2,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+    assert_eq!(stats.unrepresentable_files, 0);
+    assert_eq!(
+        ws.working_copy().unrepresentable_paths().unwrap(),
+        [lower_dir_file_path.to_owned()]
+    );
+    let new_tree = test_workspace.snapshot().unwrap();
+    assert_eq!(new_tree.id(), tree2.id());
+}
+
+#[test]
+fn test_check_out_reserved_windows_file_name() {
+    if !cfg!(windows) {
+        eprintln!("Skipping test because reserved file names are specific to Windows");
+        return;
+    }
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let reserved_path = RepoPath::from_internal_string("dir/aux.txt");
+    let normal_path = RepoPath::from_internal_string("dir/auxiliary.txt");
+    let tree = create_tree(repo, &[(reserved_path, "aux"), (normal_path, "normal")]);
+    let commit = commit_with_tree(repo.store(), tree.id());
+
+    let ws = &mut test_workspace.workspace;
+    let stats = ws
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+    assert_eq!(stats.unrepresentable_files, 1);
+    assert_eq!(
+        ws.working_copy().unrepresentable_paths().unwrap(),
+        [reserved_path.to_owned()]
+    );
+    assert!(normal_path.to_fs_path_unchecked(&workspace_root).is_file());
+
+    let new_tree = test_workspace.snapshot().unwrap();
+    assert_eq!(new_tree.id(), tree.id());
+}
+
+#[test]
+fn test_check_out_symlink_as_placeholder() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let link_path = RepoPath::from_internal_string("link");
+    let store = repo.store();
+    let mut tree_builder = store.tree_builder(store.empty_tree_id().clone());
+    testutils::write_symlink(&mut tree_builder, link_path, "target");
+    let tree_id = tree_builder.write_tree().unwrap();
+    let tree = MergedTree::resolved(store.get_tree(RepoPathBuf::root(), &tree_id).unwrap());
+    let commit = commit_with_tree(store, tree.id());
+
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit,
+        &CheckoutOptions {
+            symlink_policy: SymlinkPolicy::Placeholder,
+            ..CheckoutOptions::empty_for_test()
+        },
+    )
+    .unwrap();
+
+    // The symlink is written as a regular file containing the target
+    let disk_path = link_path.to_fs_path_unchecked(&workspace_root);
+    let metadata = disk_path.symlink_metadata().unwrap();
+    assert!(metadata.file_type().is_file());
+    assert_eq!(std::fs::read_to_string(&disk_path).unwrap(), "target");
+
+    // The placeholder is read back as a symlink
+    let options = SnapshotOptions {
+        symlink_policy: SymlinkPolicy::Placeholder,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (new_tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_eq!(new_tree.id(), tree.id());
+
+    // Editing the placeholder changes the target
+    std::fs::write(&disk_path, "other").unwrap();
+    let (new_tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    let mut tree_builder = store.tree_builder(store.empty_tree_id().clone());
+    testutils::write_symlink(&mut tree_builder, link_path, "other");
+    let expected_tree_id = tree_builder.write_tree().unwrap();
+    assert_eq!(new_tree.id(), MergedTreeId::resolved(expected_tree_id));
+}
+
 #[test]
 fn test_check_out_existing_directory_symlink() {
     if !check_symlink_support().unwrap() {
@@ -2120,6 +2448,80 @@ fn test_fsmonitor() {
     }
 }
 
+#[cfg(feature = "native-fsmonitor")]
+#[test]
+fn test_fsmonitor_native() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let wc: &LocalWorkingCopy = test_workspace
+        .workspace
+        .working_copy()
+        .as_any()
+        .downcast_ref()
+        .unwrap();
+    let fsmonitor = wc.native_fsmonitor();
+    {
+        let fsmonitor = fsmonitor.clone();
+        let workspace_root = workspace_root.clone();
+        std::thread::spawn(move || fsmonitor.run_daemon(&workspace_root).unwrap());
+    }
+
+    // Nothing is known until the daemon has set up the watch
+    let mut attempts = 0;
+    let clock = loop {
+        match fsmonitor.query_changed_files(None).unwrap() {
+            (Some(clock), changed_files) => {
+                assert_eq!(changed_files, None);
+                break clock;
+            }
+            (None, _) => {
+                attempts += 1;
+                assert!(attempts < 1000, "daemon didn't start");
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+    };
+
+    let foo_path = RepoPath::from_internal_string("foo");
+    let nested_path = RepoPath::from_internal_string("dir/sub/nested");
+    testutils::write_working_copy_file(&workspace_root, foo_path, "foo\n");
+    testutils::write_working_copy_file(&workspace_root, nested_path, "nested\n");
+    let (clock, changed_files) = fsmonitor.query_changed_files(Some(&clock)).unwrap();
+    let changed_files = changed_files.unwrap();
+    // Files in new directories may be reported only through the directory
+    assert!(changed_files.contains(&PathBuf::from("dir")));
+    assert!(changed_files.contains(&PathBuf::from("foo")));
+    assert!(changed_files
+        .iter()
+        .all(|path| path == Path::new("foo") || path.starts_with("dir")));
+
+    // The state directory and the query itself aren't reported
+    let clock = clock.unwrap();
+    let (_clock, changed_files) = fsmonitor.query_changed_files(Some(&clock)).unwrap();
+    assert_eq!(changed_files, Some(vec![]));
+
+    // The first snapshot scans everything, the next one only the reported paths
+    let options = SnapshotOptions {
+        fsmonitor_settings: FsmonitorSettings::Native,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    let repo = &test_workspace.repo;
+    insta::assert_snapshot!(testutils::dump_tree(repo.store(), &tree.id()), @r#"
+    tree 1dddc9163ea6352af5ab
+      file "dir/sub/nested" (6209060941cd770c8d46): "nested\n"
+      file "foo" (e99c2057c15160add351): "foo\n"
+    "#);
+    std::fs::remove_file(foo_path.to_fs_path_unchecked(&workspace_root)).unwrap();
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    let repo = &test_workspace.repo;
+    insta::assert_snapshot!(testutils::dump_tree(repo.store(), &tree.id()), @r#"
+    tree bf6183e20ae5011668f1
+      file "dir/sub/nested" (6209060941cd770c8d46): "nested\n"
+    "#);
+}
+
 #[test]
 fn test_snapshot_max_new_file_size() {
     let settings = testutils::user_settings();
@@ -52,6 +52,7 @@ use jj_lib::revset::RevsetWorkspaceContext;
 use jj_lib::revset::SymbolResolver;
 use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::settings::GitSettings;
+use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 use test_case::test_case;
 use testutils::create_random_commit;
@@ -530,6 +531,47 @@ fn test_resolve_working_copies() {
     assert_eq!(resolve(), vec![commit2.id().clone(), commit1.id().clone()]);
 }
 
+#[test]
+fn test_resolve_working_copies_of() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.repo_mut();
+
+    let commit1 = write_random_commit(mut_repo, &settings);
+    let commit2 = write_random_commit(mut_repo, &settings);
+    let commit3 = write_random_commit(mut_repo, &settings);
+
+    let ws1 = WorkspaceId::new("ws1".to_string());
+    let ws2 = WorkspaceId::new("ws2".to_string());
+    let other = WorkspaceId::new("other".to_string());
+    mut_repo.set_wc_commit(ws1, commit1.id().clone()).unwrap();
+    mut_repo.set_wc_commit(ws2, commit2.id().clone()).unwrap();
+    mut_repo.set_wc_commit(other, commit3.id().clone()).unwrap();
+    let resolve = |pattern: StringPattern| -> Vec<CommitId> {
+        RevsetExpression::working_copies_of(pattern)
+            .resolve_user_expression(mut_repo, &FailingSymbolResolver)
+            .unwrap()
+            .evaluate(mut_repo)
+            .unwrap()
+            .iter()
+            .map(Result::unwrap)
+            .collect()
+    };
+
+    assert_eq!(
+        resolve(StringPattern::exact("ws1")),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve(StringPattern::glob("ws*").unwrap()),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(resolve(StringPattern::exact("missing")), vec![]);
+}
+
 #[test]
 fn test_resolve_symbol_bookmarks() {
     let settings = testutils::user_settings();
@@ -2032,6 +2074,49 @@ fn test_evaluate_expression_git_head() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_change_alias() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.repo_mut();
+
+    let commit1 = write_random_commit(mut_repo, &settings);
+    let commit2 = write_random_commit(mut_repo, &settings);
+
+    // Unknown alias is an error, which present() suppresses
+    assert_eq!(resolve_commit_ids(mut_repo, "present(alias:foo)"), vec![]);
+    mut_repo.set_change_alias("foo", Some(commit1.change_id().clone()));
+    mut_repo.set_change_alias("bar", Some(commit2.change_id().clone()));
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "alias:foo"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, r#"alias:"bar" | alias:foo"#),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+
+    // The alias follows the change when it's rewritten
+    let commit1_new = mut_repo
+        .rewrite_commit(&settings, &commit1)
+        .set_description("rewritten")
+        .write()
+        .unwrap();
+    mut_repo.rebase_descendants(&settings).unwrap();
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "alias:foo"),
+        vec![commit1_new.id().clone()]
+    );
+
+    // An alias of an abandoned change resolves to nothing
+    mut_repo.record_abandoned_commit(commit2.id().clone());
+    mut_repo.rebase_descendants(&settings).unwrap();
+    assert_eq!(resolve_commit_ids(mut_repo, "alias:bar"), vec![]);
+}
+
 #[test]
 fn test_evaluate_expression_bookmarks() {
     let settings = testutils::user_settings();
@@ -2877,6 +2962,33 @@ fn test_evaluate_expression_author_date() {
         resolve_commit_ids(mut_repo, "author_date(before:'2023-03-25 12:00')"),
         vec![commit1.id().clone(), root_commit.id().clone()]
     );
+
+    // Can match a range with an explicit time zone
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            "author_date('2023-03-25 12:00..2023-03-25 13:00 UTC')"
+        ),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_date('2023-03-25 21:00.. +09:00')"),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+
+    // A single date matches the whole day
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_date('2023-03-25 UTC')"),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone()
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_date('2023-03-26 UTC')"),
+        vec![]
+    );
 }
 
 #[test]
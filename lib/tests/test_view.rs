@@ -14,6 +14,7 @@
 
 use std::collections::BTreeMap;
 
+use itertools::Itertools as _;
 use jj_lib::op_store::BookmarkTarget;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
@@ -340,6 +341,74 @@ fn test_merge_views_tags() {
     );
 }
 
+#[test]
+fn test_merge_views_change_aliases() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.repo_mut();
+    let commit_a = write_random_commit(mut_repo, &settings);
+    let commit_b = write_random_commit(mut_repo, &settings);
+    mut_repo.set_change_alias("a", Some(commit_a.change_id().clone()));
+    mut_repo.set_change_alias("b", Some(commit_b.change_id().clone()));
+    let repo = tx.commit("test").unwrap();
+
+    let mut tx1 = repo.start_transaction(&settings);
+    tx1.repo_mut()
+        .set_change_alias("a", Some(commit_b.change_id().clone()));
+
+    let mut tx2 = repo.start_transaction(&settings);
+    tx2.repo_mut().set_change_alias("b", None);
+    tx2.repo_mut()
+        .set_change_alias("c", Some(commit_a.change_id().clone()));
+
+    let repo = commit_transactions(&settings, vec![tx1, tx2]);
+    assert_eq!(
+        repo.view().change_aliases().collect_vec(),
+        vec![("a", commit_b.change_id()), ("c", commit_a.change_id())]
+    );
+}
+
+#[test]
+fn test_merge_views_stashes() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.repo_mut();
+    let commit_a = write_random_commit(mut_repo, &settings);
+    let commit_b = write_random_commit(mut_repo, &settings);
+    let commit_c = write_random_commit(mut_repo, &settings);
+    let commit_d = write_random_commit(mut_repo, &settings);
+    mut_repo.set_stash_ids(vec![commit_b.id().clone(), commit_a.id().clone()]);
+    let repo = tx.commit("test").unwrap();
+
+    // Push a stash on one side and drop one on the other
+    let mut tx1 = repo.start_transaction(&settings);
+    tx1.repo_mut().set_stash_ids(vec![
+        commit_c.id().clone(),
+        commit_b.id().clone(),
+        commit_a.id().clone(),
+    ]);
+
+    let mut tx2 = repo.start_transaction(&settings);
+    tx2.repo_mut()
+        .set_stash_ids(vec![commit_d.id().clone(), commit_a.id().clone()]);
+
+    let repo = commit_transactions(&settings, vec![tx1, tx2]);
+    let mut stash_ids = repo.view().stash_ids().to_vec();
+    // The order of the stashes pushed on the two sides depends on the order
+    // the operations are merged in.
+    stash_ids[..2].sort();
+    let mut expected = vec![commit_c.id().clone(), commit_d.id().clone()];
+    expected.sort();
+    expected.push(commit_a.id().clone());
+    assert_eq!(stash_ids, expected);
+}
+
 #[test]
 fn test_merge_views_git_refs() {
     // Tests merging of git refs (by performing divergent operations). See